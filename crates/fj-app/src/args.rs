@@ -1,9 +1,14 @@
 use std::{path::PathBuf, str::FromStr as _};
 
 use anyhow::anyhow;
+use fj_export::{
+    GltfColors, Handedness, PartExport, StlColors, StlFormat, UpAxis, View,
+};
 use fj_host::Parameters;
+use fj_interop::units::Units;
 use fj_kernel::algorithms::approx::Tolerance;
 use fj_math::Scalar;
+use fj_viewer::{CameraView, ScreenSize};
 
 /// Fornjot - Experimental CAD System
 #[derive(clap::Parser)]
@@ -12,17 +17,243 @@ pub struct Args {
     /// The model to open
     pub model: Option<PathBuf>,
 
+    /// Open the most recently used model, instead of the default model from
+    /// the configuration
+    ///
+    /// If neither a model nor a default model are specified, the most recently
+    /// used model is opened anyway.
+    #[arg(long, conflicts_with = "model")]
+    pub open_last: bool,
+
     /// Export model to this path
+    ///
+    /// The file type is selected by the extension. STEP files (`.step`,
+    /// `.stp`) contain the exact geometry of the model, DXF (`.dxf`) and SVG
+    /// (`.svg`) files a section or view of the model, all other file types a
+    /// triangle mesh. Fornjot shape files (`.fjshape`) store the evaluated
+    /// model, so other models can import it without computing it again.
     #[arg(short, long, value_name = "PATH")]
     pub export: Option<PathBuf>,
 
+    /// How to export a model made up of multiple parts
+    ///
+    /// `combined` merges all parts into one file. `separate` writes one file
+    /// per part, replacing `{name}` and `{index}` in the export path.
+    /// `multi-object` writes one 3MF/AMF/glTF/OBJ/STEP file with an object
+    /// per part.
+    #[arg(
+        long,
+        value_name = "MODE",
+        default_value = "combined",
+        value_parser = parse_part_export
+    )]
+    pub export_parts: PartExport,
+
+    /// The axis that points up in the exported file (`z` or `y`)
+    #[arg(
+        long,
+        value_name = "AXIS",
+        default_value = "z",
+        value_parser = parse_up_axis
+    )]
+    pub export_up_axis: UpAxis,
+
+    /// The handedness of the exported file's coordinate system (`right` or
+    /// `left`)
+    #[arg(
+        long,
+        value_name = "HANDEDNESS",
+        default_value = "right",
+        value_parser = parse_handedness
+    )]
+    pub export_handedness: Handedness,
+
+    /// The format of exported STL files (`binary` or `ascii`)
+    #[arg(
+        long,
+        value_name = "FORMAT",
+        default_value = "binary",
+        value_parser = parse_stl_format
+    )]
+    pub export_stl_format: StlFormat,
+
+    /// How to store triangle colors in binary STL files (`none`, `viscam` or
+    /// `magics`)
+    #[arg(
+        long,
+        value_name = "FORMAT",
+        default_value = "none",
+        value_parser = parse_stl_colors
+    )]
+    pub export_stl_colors: StlColors,
+
+    /// Whether to store triangle colors in glTF and GLB files (`none` or
+    /// `vertex`)
+    #[arg(
+        long,
+        value_name = "COLORS",
+        default_value = "none",
+        value_parser = parse_gltf_colors
+    )]
+    pub export_gltf_colors: GltfColors,
+
+    /// The units of exported triangle meshes and STEP files (`mm`, `in` or
+    /// `m`)
+    ///
+    /// Parts that are defined in other units are scaled accordingly. By
+    /// default, the units of the model are kept. 3MF, AMF, and STEP files
+    /// record the units, while other formats, like STL, only store the scaled
+    /// coordinates.
+    #[arg(long, value_name = "UNITS", value_parser = parse_units)]
+    pub export_units: Option<Units>,
+
+    /// The height of the plane that the model is cut with for DXF and SVG
+    /// files
+    ///
+    /// The plane is parallel to the xy-plane. Faces that lie within it are
+    /// exported with their exact geometry, which makes the default suitable
+    /// for 2D models.
+    #[arg(long, value_name = "Z", default_value = "0")]
+    pub export_section_height: f64,
+
+    /// Offset the profiles in DXF and SVG files by this distance, to
+    /// compensate for the kerf of a cutting process
+    ///
+    /// Positive values grow the outlines of parts and shrink their holes, so
+    /// the cut parts end up with the intended size. Negative values leave
+    /// clearance instead. Only faces that lie within the section plane can be
+    /// offset, like those of 2D models and the bottom of flat parts.
+    #[arg(long, value_name = "DISTANCE", default_value = "0")]
+    pub export_kerf: f64,
+
+    /// Nest the parts of the model onto sheets of this size, when exporting to
+    /// DXF and SVG files (in the form `WIDTHxHEIGHT`)
+    ///
+    /// The sections of the parts are arranged next to each other, for cutting
+    /// them from sheet material. Each sheet is written to its own file,
+    /// replacing `{index}` in the export path with the sheet's 1-based index.
+    #[arg(long, value_name = "SIZE", value_parser = parse_sheet_size)]
+    pub export_nest_sheet: Option<[Scalar; 2]>,
+
+    /// The minimum distance between nested parts, and between parts and the
+    /// edges of a sheet
+    #[arg(long, value_name = "DISTANCE", default_value = "0")]
+    pub export_nest_spacing: f64,
+
+    /// Don't rotate nested parts, for materials with a grain direction
+    #[arg(long)]
+    pub export_nest_no_rotation: bool,
+
+    /// Export a view of the model with hidden lines removed to DXF and SVG
+    /// files, instead of a section (`front`, `top` or `side`)
+    #[arg(long, value_name = "VIEW", value_parser = parse_view)]
+    pub export_view: Option<View>,
+
+    /// Slice the model into layers of this height, and export their contours
+    /// instead (to `.json` or `.svg` files)
+    ///
+    /// JSON files contain all layers. SVG files are written per layer,
+    /// replacing `{index}` in the export path with the layer's 1-based index.
+    #[arg(long, value_name = "HEIGHT")]
+    pub export_layer_height: Option<f64>,
+
+    /// Render an image of the model to this PNG file
+    ///
+    /// The model is rendered once, without opening a window, as it would be
+    /// shown in the viewer. Can be combined with `--export`.
+    #[arg(long, value_name = "PATH")]
+    pub export_image: Option<PathBuf>,
+
+    /// The direction the image of the model is rendered from (`iso`,
+    /// `front`, `top` or `side`)
+    #[arg(
+        long,
+        value_name = "VIEW",
+        default_value = "iso",
+        value_parser = parse_camera_view
+    )]
+    pub camera: CameraView,
+
+    /// The size of the rendered image, in pixels (in the form
+    /// `WIDTHxHEIGHT`)
+    #[arg(
+        long,
+        value_name = "SIZE",
+        default_value = "1920x1080",
+        value_parser = parse_resolution
+    )]
+    pub resolution: ScreenSize,
+
+    /// Write a bill of materials of the model to this path
+    ///
+    /// Lists the model's parts with their counts, bounding box sizes, and
+    /// volumes, as well as the parameters of the model. The file type is
+    /// selected by the extension (`.csv` or `.json`). Can be combined with
+    /// `--export`.
+    #[arg(long, value_name = "PATH")]
+    pub bom: Option<PathBuf>,
+
+    /// Display a triangle mesh alongside the model, as reference geometry
+    ///
+    /// STL and OBJ files are supported. The viewer checks whether the model
+    /// interferes with the reference. Can be passed multiple times.
+    #[arg(long, value_name = "PATH")]
+    pub reference: Vec<PathBuf>,
+
     /// Parameters for the model, each in the form `key=value`
+    ///
+    /// Values that start with `=` are arithmetic expressions, which can refer
+    /// to other parameters, like `length==25.4*3,width==length/2`.
     #[arg(short, long, value_parser = parse_parameters)]
     pub parameters: Option<Parameters>,
 
+    /// The seed for random numbers that the model generates
+    ///
+    /// Models that are generated randomly produce the same shape for the same
+    /// seed, on every evaluation and every machine. Pass a different seed to
+    /// get a different variation of the model.
+    #[arg(long, default_value = "0")]
+    pub seed: u64,
+
     /// Model deviation tolerance
+    ///
+    /// The maximum distance between the triangle mesh and the exact geometry
+    /// of the model. Defaults to a thousandth of the diagonal of the model's
+    /// bounding box.
     #[arg(short, long, value_parser = parse_tolerance)]
     pub tolerance: Option<Tolerance>,
+
+    /// Maximum angle between adjacent segments of curves, in degrees
+    ///
+    /// Strongly curved geometry, like small circles or fillets, is refined
+    /// until it stays within this angle. Flat and gently curved geometry is
+    /// approximated according to the deviation tolerance alone.
+    #[arg(long, value_name = "DEGREES", value_parser = parse_angular_tolerance)]
+    pub angular_tolerance: Option<fj::Angle>,
+
+    /// Don't reuse triangle meshes from previous runs
+    ///
+    /// By default, the triangle mesh of a model is stored in the user's cache
+    /// directory. If the model is opened again without having changed, the
+    /// stored mesh is displayed, instead of processing the model again.
+    #[arg(long)]
+    pub no_mesh_cache: bool,
+
+    /// Write a thumbnail of this size, in pixels, next to the model
+    ///
+    /// The thumbnail is a PNG image called `thumbnail.png`, in the model's
+    /// directory. It is written again after each evaluation of the model. Can
+    /// be combined with `--export`.
+    #[arg(long, value_name = "SIZE")]
+    pub thumbnail: Option<u32>,
+
+    /// Print the operations of the model, without evaluating them, and exit
+    ///
+    /// Lists each operation with its parameters, and an estimate of the size
+    /// of its result and the cost of computing it. Useful to find out why a
+    /// model is slow, without waiting for it.
+    #[arg(long)]
+    pub dry_run: bool,
 }
 
 impl Args {
@@ -58,6 +289,67 @@ fn parse_parameters(input: &str) -> anyhow::Result<Parameters> {
     Ok(parameters)
 }
 
+fn parse_part_export(input: &str) -> anyhow::Result<PartExport> {
+    match input {
+        "combined" => Ok(PartExport::Combined),
+        "separate" => Ok(PartExport::Separate),
+        "multi-object" => Ok(PartExport::MultiObject),
+        _ => Err(anyhow!(
+            "Expected one of `combined`, `separate`, `multi-object`"
+        )),
+    }
+}
+
+fn parse_up_axis(input: &str) -> anyhow::Result<UpAxis> {
+    match input {
+        "z" => Ok(UpAxis::Z),
+        "y" => Ok(UpAxis::Y),
+        _ => Err(anyhow!("Expected one of `z`, `y`")),
+    }
+}
+
+fn parse_handedness(input: &str) -> anyhow::Result<Handedness> {
+    match input {
+        "right" => Ok(Handedness::Right),
+        "left" => Ok(Handedness::Left),
+        _ => Err(anyhow!("Expected one of `right`, `left`")),
+    }
+}
+
+fn parse_stl_format(input: &str) -> anyhow::Result<StlFormat> {
+    match input {
+        "binary" => Ok(StlFormat::Binary),
+        "ascii" => Ok(StlFormat::Ascii),
+        _ => Err(anyhow!("Expected one of `binary`, `ascii`")),
+    }
+}
+
+fn parse_stl_colors(input: &str) -> anyhow::Result<StlColors> {
+    match input {
+        "none" => Ok(StlColors::None),
+        "viscam" => Ok(StlColors::VisCam),
+        "magics" => Ok(StlColors::Magics),
+        _ => Err(anyhow!("Expected one of `none`, `viscam`, `magics`")),
+    }
+}
+
+fn parse_gltf_colors(input: &str) -> anyhow::Result<GltfColors> {
+    match input {
+        "none" => Ok(GltfColors::None),
+        "vertex" => Ok(GltfColors::Vertex),
+        _ => Err(anyhow!("Expected one of `none`, `vertex`")),
+    }
+}
+
+fn parse_units(input: &str) -> anyhow::Result<Units> {
+    match input {
+        "mm" => Ok(Units::Millimeters),
+        "in" => Ok(Units::Inches),
+        "m" => Ok(Units::Meters),
+        _ => Err(anyhow!("Expected one of `mm`, `in`, `m`")),
+    }
+}
+
 fn parse_tolerance(input: &str) -> anyhow::Result<Tolerance> {
     let tolerance = f64::from_str(input)?;
     let tolerance = Scalar::from_f64(tolerance);
@@ -65,3 +357,59 @@ fn parse_tolerance(input: &str) -> anyhow::Result<Tolerance> {
 
     Ok(tolerance)
 }
+
+fn parse_angular_tolerance(input: &str) -> anyhow::Result<fj::Angle> {
+    let degrees = f64::from_str(input)?;
+    if !(degrees > 0. && degrees < 180.) {
+        return Err(anyhow!("Expected an angle between 0 and 180 degrees"));
+    }
+
+    Ok(fj::Angle::from_deg(degrees))
+}
+
+fn parse_sheet_size(input: &str) -> anyhow::Result<[Scalar; 2]> {
+    let (width, height) = input
+        .split_once('x')
+        .ok_or_else(|| anyhow!("Expected sheet size as `WIDTHxHEIGHT`"))?;
+
+    let width = f64::from_str(width.trim())?;
+    let height = f64::from_str(height.trim())?;
+    if !(width > 0. && height > 0.) {
+        return Err(anyhow!("Sheet size must be positive"));
+    }
+
+    Ok([width, height].map(Scalar::from_f64))
+}
+
+fn parse_camera_view(input: &str) -> anyhow::Result<CameraView> {
+    match input {
+        "iso" => Ok(CameraView::Iso),
+        "front" => Ok(CameraView::Front),
+        "top" => Ok(CameraView::Top),
+        "side" => Ok(CameraView::Side),
+        _ => Err(anyhow!("Expected one of `iso`, `front`, `top`, `side`")),
+    }
+}
+
+fn parse_resolution(input: &str) -> anyhow::Result<ScreenSize> {
+    let (width, height) = input
+        .split_once('x')
+        .ok_or_else(|| anyhow!("Expected resolution as `WIDTHxHEIGHT`"))?;
+
+    let width = u32::from_str(width.trim())?;
+    let height = u32::from_str(height.trim())?;
+    if width == 0 || height == 0 {
+        return Err(anyhow!("Resolution must not be zero"));
+    }
+
+    Ok(ScreenSize { width, height })
+}
+
+fn parse_view(input: &str) -> anyhow::Result<View> {
+    match input {
+        "front" => Ok(View::Front),
+        "top" => Ok(View::Top),
+        "side" => Ok(View::Side),
+        _ => Err(anyhow!("Expected one of `front`, `top`, `side`")),
+    }
+}
@@ -12,6 +12,7 @@ pub struct Config {
     pub default_path: Option<PathBuf>,
     pub default_model: Option<PathBuf>,
     pub invert_zoom: Option<bool>,
+    pub samples: Option<u32>,
 }
 
 impl Config {
@@ -15,13 +15,30 @@
 mod args;
 mod config;
 mod path;
+mod samples;
+
+use std::path::Path;
 
 use anyhow::{anyhow, Context};
-use fj_export::export;
+use fj_export::{
+    export_bom, export_brep_parts, export_nested, export_parts, export_section,
+    export_slices, export_thumbnail, export_view, is_2d_format, is_brep_format,
+    BillOfMaterials, BrepPart, CoordinateSystem, ExportOptions, NestOptions,
+    Part,
+};
 use fj_host::Parameters;
-use fj_operations::shape_processor::ShapeProcessor;
-use fj_window::run::run;
+use fj_kernel::algorithms::slice::slice_mesh;
+use fj_math::{Plane, Point, Scalar, Vector};
+use fj_operations::{
+    dry_run::dry_run, mesh_cache::MeshCache, shape_processor::ShapeProcessor,
+};
+use fj_viewer::{
+    Camera, DrawConfig, OffscreenRenderer, Reference, DEFAULT_SAMPLE_COUNT,
+};
+use fj_window::{recent_models::RecentModels, run::run};
+use futures::executor::block_on;
 use path::ModelPath;
+use tracing::warn;
 use tracing_subscriber::fmt::format;
 use tracing_subscriber::EnvFilter;
 
@@ -43,31 +60,282 @@ fn main() -> anyhow::Result<()> {
 
     let args = Args::parse();
     let config = Config::load()?;
-    let model_path = ModelPath::from_args_and_config(&args, &config);
+    let mut recent_models = load_recent_models();
+    let model_path =
+        ModelPath::from_args_and_config(&args, &config, &recent_models);
     let parameters = args.parameters.unwrap_or_else(Parameters::empty);
     let shape_processor = ShapeProcessor {
         tolerance: args.tolerance,
+        angular_tolerance: args.angular_tolerance,
     };
 
-    let model = model_path.map(|m| m.load_model(parameters)).transpose()?;
+    let model = model_path
+        .map(|m| m.load_model(parameters))
+        .transpose()?
+        .map(|model| model.with_seed(args.seed));
+    if let Some(model) = &model {
+        if let Err(err) = recent_models.add(model.path()) {
+            warn!("Failed to update list of recent models: {err}");
+        }
+    }
+
+    if args.dry_run {
+        let model = model.as_ref().with_context(no_model_error)?;
+        let evaluation = model.evaluate()?;
+
+        let operation = dry_run(&evaluation.shape);
+        print!("{operation}");
+        println!(
+            "{} operations, estimated total cost {}",
+            operation.num_operations(),
+            operation.total_cost()
+        );
+        return Ok(());
+    }
+
+    if let Some(image_path) = &args.export_image {
+        let model = model.as_ref().with_context(no_model_error)?;
+        let evaluation = model.evaluate()?;
+
+        let shape = shape_processor.process(&evaluation.shape)?;
+        let size = args.resolution;
+        let [width, height] = size.as_f64();
+
+        let mut camera = Camera::new();
+        camera.look_at(args.camera, &shape.aabb, width / height);
+
+        let sample_count = config.samples.unwrap_or(DEFAULT_SAMPLE_COUNT);
+        let mut renderer =
+            block_on(OffscreenRenderer::new(size, sample_count))?;
+        renderer
+            .render(&shape, &camera, &DrawConfig::default())?
+            .save(image_path)?;
+
+        if args.export.is_none() && args.bom.is_none() {
+            return Ok(());
+        }
+    }
+
+    if let Some(bom_path) = &args.bom {
+        let model = model.as_ref().with_context(no_model_error)?;
+        let evaluation = model.evaluate()?;
+
+        let shape = shape_processor.process(&evaluation.shape)?;
+        let parts = shape
+            .parts
+            .iter()
+            .map(|part| Part {
+                name: &part.name,
+                mesh: &part.mesh,
+                units: part.units,
+            })
+            .collect::<Vec<_>>();
+        let bom = BillOfMaterials::new(&parts, model.parameters().0.clone());
+
+        export_bom(&bom, bom_path)?;
+        if args.export.is_none() {
+            return Ok(());
+        }
+    }
+
+    if let (Some(size), Some(_)) = (args.thumbnail, &args.export) {
+        let model = model.as_ref().with_context(no_model_error)?;
+        let evaluation = model.evaluate()?;
+
+        let shape = shape_processor.process(&evaluation.shape)?;
+        export_thumbnail(&shape.mesh, size, &model.thumbnail_path())?;
+    }
 
     if let Some(export_path) = args.export {
         // export only mode. just load model, process, export and exit
 
         let evaluation = model.with_context(no_model_error)?.evaluate()?;
-        let shape = shape_processor.process(&evaluation.shape)?;
+        let options = ExportOptions {
+            parts: args.export_parts,
+            coordinate_system: CoordinateSystem {
+                up: args.export_up_axis,
+                handedness: args.export_handedness,
+            },
+            stl_format: args.export_stl_format,
+            stl_colors: args.export_stl_colors,
+            gltf_colors: args.export_gltf_colors,
+            units: args.export_units,
+        };
+
+        if let Some(layer_height) = args.export_layer_height {
+            if layer_height <= 0. {
+                return Err(anyhow!("Layer height must be positive"));
+            }
 
-        export(&shape.mesh, &export_path)?;
+            let shape = shape_processor.process(&evaluation.shape)?;
+            let layers = slice_mesh(&shape.mesh, layer_height);
+
+            export_slices(&layers, &export_path)?;
+            return Ok(());
+        }
+
+        if let (true, Some(view)) =
+            (is_2d_format(&export_path), args.export_view)
+        {
+            let shape = shape_processor.process(&evaluation.shape)?;
+            let parts = shape
+                .parts
+                .iter()
+                .map(|part| Part {
+                    name: &part.name,
+                    mesh: &part.mesh,
+                    units: part.units,
+                })
+                .collect::<Vec<_>>();
+
+            export_view(&parts, view, &export_path)?;
+            return Ok(());
+        }
+
+        if is_2d_format(&export_path) {
+            let evaluated = shape_processor.evaluate(&evaluation.shape)?;
+            let parts = evaluated
+                .parts()
+                .map(|(name, faces, units)| BrepPart { name, faces, units })
+                .collect::<Vec<_>>();
+            let plane = Plane::from_parametric(
+                Point::from([0., 0., args.export_section_height]),
+                Vector::unit_x(),
+                Vector::unit_y(),
+            );
+
+            if let Some(sheet) = args.export_nest_sheet {
+                if args.export_nest_spacing < 0. {
+                    return Err(anyhow!(
+                        "Nesting spacing must not be negative"
+                    ));
+                }
+
+                let options = NestOptions {
+                    sheet,
+                    spacing: Scalar::from_f64(args.export_nest_spacing),
+                    rotate: !args.export_nest_no_rotation,
+                };
+                export_nested(
+                    &parts,
+                    &plane,
+                    Scalar::from_f64(args.export_kerf),
+                    evaluated.tolerance(),
+                    &options,
+                    &export_path,
+                )?;
+                return Ok(());
+            }
+
+            export_section(
+                &parts,
+                &plane,
+                Scalar::from_f64(args.export_kerf),
+                evaluated.tolerance(),
+                &export_path,
+            )?;
+            return Ok(());
+        }
+
+        if is_shape_file(&export_path) {
+            let evaluated = shape_processor.evaluate(&evaluation.shape)?;
+            evaluated.save(&export_path)?;
+            return Ok(());
+        }
+
+        if is_brep_format(&export_path) {
+            let evaluated = shape_processor.evaluate(&evaluation.shape)?;
+            let parts = evaluated
+                .parts()
+                .map(|(name, faces, units)| BrepPart { name, faces, units })
+                .collect::<Vec<_>>();
+
+            export_brep_parts(&parts, &export_path, &options)?;
+            return Ok(());
+        }
+
+        let shape = shape_processor.process(&evaluation.shape)?;
+        let parts = shape
+            .parts
+            .iter()
+            .map(|part| Part {
+                name: &part.name,
+                mesh: &part.mesh,
+                units: part.units,
+            })
+            .collect::<Vec<_>>();
+        export_parts(&parts, &export_path, &options)?;
 
         return Ok(());
     }
 
+    let references = args
+        .reference
+        .iter()
+        .map(|path| {
+            let mesh = fj_import::import_mesh(path).with_context(|| {
+                format!("Failed to import reference `{}`", path.display())
+            })?;
+            let name = path
+                .file_stem()
+                .unwrap_or(path.as_os_str())
+                .to_string_lossy();
+
+            Ok(Reference::new(name, mesh))
+        })
+        .collect::<anyhow::Result<Vec<_>>>()?;
+
+    let mesh_cache = if args.no_mesh_cache {
+        None
+    } else {
+        dirs::cache_dir()
+            .map(|dir| MeshCache::new(dir.join("fornjot").join("meshes")))
+    };
+
     let invert_zoom = config.invert_zoom.unwrap_or(false);
-    run(model, shape_processor, invert_zoom)?;
+    let sample_count = config.samples.unwrap_or(DEFAULT_SAMPLE_COUNT);
+    run(
+        model,
+        shape_processor,
+        mesh_cache,
+        invert_zoom,
+        sample_count,
+        recent_models,
+        samples::sample_models(),
+        references,
+        args.thumbnail,
+    )?;
 
     Ok(())
 }
 
+/// Load the list of recently used models
+///
+/// The list is stored in the user's local data directory. If that is not
+/// available, the list is kept in memory only.
+fn load_recent_models() -> RecentModels {
+    let Some(dir) = dirs::data_local_dir() else {
+        return RecentModels::new();
+    };
+    let file = dir.join("fornjot").join("recent-models");
+
+    RecentModels::load(&file).unwrap_or_else(|err| {
+        warn!(
+            "Failed to load list of recent models from `{}`: {err}",
+            file.display()
+        );
+        RecentModels::new()
+    })
+}
+
+/// Check whether a shape is to be saved in Fornjot's own format
+fn is_shape_file(path: &Path) -> bool {
+    matches!(
+        path.extension(),
+        Some(extension) if extension.eq_ignore_ascii_case("FJSHAPE")
+    )
+}
+
 fn no_model_error() -> anyhow::Error {
     anyhow!(
         "You must specify a model to start Fornjot in export only mode.\n\
@@ -5,6 +5,7 @@ use std::{
 
 use anyhow::Context;
 use fj_host::{Model, Parameters};
+use fj_window::recent_models::RecentModels;
 
 use crate::{args::Args, config::Config};
 
@@ -14,7 +15,11 @@ pub struct ModelPath {
 }
 
 impl ModelPath {
-    pub fn from_args_and_config(args: &Args, config: &Config) -> Option<Self> {
+    pub fn from_args_and_config(
+        args: &Args,
+        config: &Config,
+        recent_models: &RecentModels,
+    ) -> Option<Self> {
         let default_path = config.default_path.clone();
 
         let model_path_from_args = args
@@ -25,7 +30,21 @@ impl ModelPath {
             .default_model
             .as_ref()
             .map(|model| ModelPathSource::Config(model.clone()));
-        let model_path = model_path_from_args.or(model_path_from_config)?;
+        let model_path_from_recent = recent_models
+            .latest()
+            .map(|model| ModelPathSource::Recent(model.to_path_buf()));
+
+        // Exporting the last model the user happened to look at would be
+        // surprising, unless they explicitly asked for it.
+        let model_path = if args.open_last {
+            model_path_from_args.or(model_path_from_recent)
+        } else if args.export.is_some() {
+            model_path_from_args.or(model_path_from_config)
+        } else {
+            model_path_from_args
+                .or(model_path_from_config)
+                .or(model_path_from_recent)
+        }?;
 
         Some(Self {
             default_path,
@@ -66,6 +85,7 @@ impl ModelPath {
 enum ModelPathSource {
     Args(PathBuf),
     Config(PathBuf),
+    Recent(PathBuf),
 }
 
 impl ModelPathSource {
@@ -73,6 +93,7 @@ impl ModelPathSource {
         match self {
             ModelPathSource::Args(path) => path,
             ModelPathSource::Config(path) => path,
+            ModelPathSource::Recent(path) => path,
         }
     }
 }
@@ -104,6 +125,9 @@ fn load_error_context_inner(
         ModelPathSource::Config(_) => {
             write!(error, "\n- Specified as default model in configuration")?
         }
+        ModelPathSource::Recent(_) => {
+            write!(error, "\n- Most recently used model")?
+        }
     }
     write!(error, "\n- Path of model: {}", path.display())?;
 
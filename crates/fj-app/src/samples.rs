@@ -0,0 +1,69 @@
+use std::{env, path::Path};
+
+use fj_host::{EmbeddedModel, FjDependency};
+use fj_window::sample_models::SampleModels;
+
+/// The example models that new users are offered to open
+const MODELS: &[EmbeddedModel] = &[
+    EmbeddedModel {
+        name: "cuboid",
+        files: &[
+            (
+                "README.md",
+                include_str!("../../../models/cuboid/README.md"),
+            ),
+            (
+                "src/lib.rs",
+                include_str!("../../../models/cuboid/src/lib.rs"),
+            ),
+        ],
+    },
+    EmbeddedModel {
+        name: "spacer",
+        files: &[
+            (
+                "README.md",
+                include_str!("../../../models/spacer/README.md"),
+            ),
+            (
+                "src/lib.rs",
+                include_str!("../../../models/spacer/src/lib.rs"),
+            ),
+        ],
+    },
+    EmbeddedModel {
+        name: "star",
+        files: &[
+            ("README.md", include_str!("../../../models/star/README.md")),
+            (
+                "src/lib.rs",
+                include_str!("../../../models/star/src/lib.rs"),
+            ),
+        ],
+    },
+];
+
+/// Load the sample models
+///
+/// They are unpacked into the user's local data directory, or the temporary
+/// directory, if that is not available.
+pub fn sample_models() -> SampleModels {
+    let dir = dirs::data_local_dir()
+        .map(|dir| dir.join("fornjot"))
+        .unwrap_or_else(|| env::temp_dir().join("fornjot"))
+        .join("samples");
+
+    // If this application was built from the Fornjot repository, and that is
+    // still around, the samples should use the same version of `fj`. Otherwise
+    // they wouldn't be compatible with the application, unless it is a
+    // released version.
+    let fj_path = Path::new(env!("CARGO_MANIFEST_DIR")).join("../fj");
+    let fj = match fj_path.canonicalize() {
+        Ok(path) if path.join("Cargo.toml").exists() => {
+            FjDependency::Path(path)
+        }
+        _ => FjDependency::Version(fj::version::VERSION_PKG.to_string()),
+    };
+
+    SampleModels::new(dir, fj, MODELS.iter().copied())
+}
@@ -0,0 +1,179 @@
+//! Export to AMF
+//!
+//! Each part becomes a named object. Within an object, the triangles of each
+//! color form a separate volume, so printers that support multiple materials
+//! can tell the regions of the model apart.
+
+use std::{
+    collections::BTreeMap,
+    fs::File,
+    io::{BufWriter, Write},
+    path::Path,
+};
+
+use crate::{escape, unit_name, Error, Part};
+
+/// Write the parts to an AMF file, one object per part
+pub fn write(parts: &[Part], path: &Path) -> Result<(), Error> {
+    let mut file = BufWriter::new(File::create(path)?);
+
+    writeln!(file, r#"<?xml version="1.0" encoding="UTF-8"?>"#)?;
+    let unit = unit_name(parts.first().map(|part| part.units));
+    writeln!(file, r#"<amf unit="{unit}" version="1.1">"#)?;
+    writeln!(file, r#"  <metadata type="producer">Fornjot</metadata>"#)?;
+
+    // AMF requires every volume to have at least one triangle, so empty parts
+    // are left out.
+    let parts = parts
+        .iter()
+        .filter(|part| part.mesh.indices().next().is_some());
+
+    for (id, part) in parts.enumerate() {
+        writeln!(file, r#"  <object id="{id}">"#)?;
+        writeln!(
+            file,
+            r#"    <metadata type="name">{}</metadata>"#,
+            escape(part.name)
+        )?;
+        writeln!(file, "    <mesh>")?;
+
+        writeln!(file, "      <vertices>")?;
+        for vertex in part.mesh.vertices() {
+            let [x, y, z] = vertex.coords.components.map(|s| s.into_f64());
+            writeln!(
+                file,
+                "        <vertex><coordinates>\
+                <x>{x}</x><y>{y}</y><z>{z}</z>\
+                </coordinates></vertex>"
+            )?;
+        }
+        writeln!(file, "      </vertices>")?;
+
+        let indices = part.mesh.indices().collect::<Vec<_>>();
+        let mut volumes = BTreeMap::new();
+        for (triangle, indices) in part.mesh.triangles().zip(indices.chunks(3))
+        {
+            volumes
+                .entry(triangle.color)
+                .or_insert_with(Vec::new)
+                .push(indices);
+        }
+
+        for (color, triangles) in volumes {
+            writeln!(file, "      <volume>")?;
+
+            let [r, g, b, a] = color.0.map(|channel| f64::from(channel) / 255.);
+            writeln!(
+                file,
+                "        <color><r>{r}</r><g>{g}</g><b>{b}</b><a>{a}</a>\
+                </color>"
+            )?;
+
+            for triangle in triangles {
+                writeln!(
+                    file,
+                    "        <triangle><v1>{}</v1><v2>{}</v2><v3>{}</v3>\
+                    </triangle>",
+                    triangle[0], triangle[1], triangle[2],
+                )?;
+            }
+
+            writeln!(file, "      </volume>")?;
+        }
+
+        writeln!(file, "    </mesh>")?;
+        writeln!(file, "  </object>")?;
+    }
+
+    writeln!(file, "</amf>")?;
+    file.flush()?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use fj_interop::{
+        mesh::{Color, Mesh},
+        units::Units,
+    };
+
+    use crate::Part;
+
+    #[test]
+    fn write_volume_per_color() {
+        let red = Color([255, 0, 0, 255]);
+        let blue = Color([0, 0, 255, 255]);
+
+        let mut mesh = Mesh::new();
+        mesh.push_triangle([[0., 0., 0.], [1., 0., 0.], [1., 1., 0.]], red);
+        mesh.push_triangle([[0., 0., 0.], [1., 1., 0.], [0., 1., 0.]], blue);
+        let empty = Mesh::new();
+
+        let path = std::env::temp_dir()
+            .join(format!("fj-export-amf-test-{}.amf", std::process::id()));
+        super::write(
+            &[
+                Part {
+                    name: "empty",
+                    mesh: &empty,
+                    units: Units::Inches,
+                },
+                Part {
+                    name: "bolts & nuts",
+                    mesh: &mesh,
+                    units: Units::Inches,
+                },
+            ],
+            &path,
+        )
+        .unwrap();
+        let source = fs::read_to_string(&path).unwrap();
+        fs::remove_file(&path).unwrap();
+
+        let count = |pattern: &str| source.matches(pattern).count();
+
+        assert!(source.contains(r#"<amf unit="inch" version="1.1">"#));
+
+        // Empty parts are left out.
+        assert_eq!(count("<object "), 1);
+        assert!(source
+            .contains(r#"<metadata type="name">bolts &amp; nuts</metadata>"#));
+
+        assert_eq!(count("<vertex>"), 4);
+        assert!(source.contains(
+            "<vertex><coordinates><x>1</x><y>1</y><z>0</z></coordinates>\
+            </vertex>"
+        ));
+
+        // Each volume has its own color, and its triangles refer to the
+        // vertices of the object. Volumes are ordered by their color.
+        let volumes = source
+            .split("<volume>")
+            .skip(1)
+            .map(|volume| {
+                volume
+                    .lines()
+                    .map(str::trim)
+                    .filter(|line| !line.is_empty())
+                    .take_while(|&line| line != "</volume>")
+                    .collect::<Vec<_>>()
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(
+            volumes,
+            [
+                [
+                    "<color><r>0</r><g>0</g><b>1</b><a>1</a></color>",
+                    "<triangle><v1>0</v1><v2>2</v2><v3>3</v3></triangle>",
+                ],
+                [
+                    "<color><r>1</r><g>0</g><b>0</b><a>1</a></color>",
+                    "<triangle><v1>0</v1><v2>1</v2><v3>2</v3></triangle>",
+                ],
+            ]
+        );
+    }
+}
@@ -0,0 +1,163 @@
+//! Export of bills of materials
+//!
+//! See [`BillOfMaterials`].
+
+use std::{
+    collections::BTreeMap,
+    fs::File,
+    io::{BufWriter, Write as _},
+    path::Path,
+};
+
+use fj_kernel::algorithms::properties::mesh_properties;
+use fj_math::{Aabb, Scalar, Vector};
+use serde_json::json;
+
+use crate::{Error, Part};
+
+/// A bill of materials
+///
+/// Lists the distinct parts of a model, and the parameters it was evaluated
+/// with. Parts are identified by their name, so multiple instances of the
+/// same part, for example within an assembly, are counted.
+#[derive(Clone, Debug, PartialEq)]
+pub struct BillOfMaterials {
+    /// The distinct parts, in the order they first appear in the model
+    pub entries: Vec<BomEntry>,
+
+    /// The parameters that the model was evaluated with, sorted by key
+    pub parameters: BTreeMap<String, String>,
+}
+
+impl BillOfMaterials {
+    /// Compute the bill of materials of the given parts
+    pub fn new(
+        parts: &[Part],
+        parameters: impl IntoIterator<Item = (String, String)>,
+    ) -> Self {
+        let mut entries: Vec<BomEntry> = Vec::new();
+
+        for part in parts {
+            if let Some(entry) =
+                entries.iter_mut().find(|entry| entry.name == part.name)
+            {
+                entry.count += 1;
+                continue;
+            }
+
+            let size = if part.mesh.vertices().next().is_some() {
+                Aabb::<3>::from_points(part.mesh.vertices()).size()
+            } else {
+                Vector::from([0., 0., 0.])
+            };
+
+            entries.push(BomEntry {
+                name: part.name.to_string(),
+                count: 1,
+                size,
+                volume: mesh_properties(part.mesh).volume,
+            });
+        }
+
+        Self {
+            entries,
+            parameters: parameters.into_iter().collect(),
+        }
+    }
+}
+
+/// A distinct part in a [`BillOfMaterials`]
+#[derive(Clone, Debug, PartialEq)]
+pub struct BomEntry {
+    /// The name of the part
+    pub name: String,
+
+    /// How many instances of the part the model contains
+    pub count: usize,
+
+    /// The size of the bounding box of the part's first instance
+    ///
+    /// The bounding box is aligned to the model's axes, so the size depends
+    /// on how the instance is oriented.
+    pub size: Vector<3>,
+
+    /// The volume of a single instance of the part
+    pub volume: Scalar,
+}
+
+/// Write the bill of materials to a CSV file
+///
+/// The file has a header row, followed by one row per part. Every parameter
+/// adds a column, with the same value in every row.
+pub fn write_csv(bom: &BillOfMaterials, path: &Path) -> Result<(), Error> {
+    let mut file = BufWriter::new(File::create(path)?);
+
+    let mut header = ["name", "count", "size_x", "size_y", "size_z", "volume"]
+        .map(String::from)
+        .to_vec();
+    header.extend(bom.parameters.keys().cloned());
+    write_row(&mut file, &header)?;
+
+    for entry in &bom.entries {
+        let mut row = vec![entry.name.clone(), entry.count.to_string()];
+        row.extend(entry.size.components.map(|size| size.to_string()));
+        row.push(entry.volume.to_string());
+        row.extend(bom.parameters.values().cloned());
+
+        write_row(&mut file, &row)?;
+    }
+
+    file.flush()?;
+
+    Ok(())
+}
+
+/// Write the bill of materials to a JSON file
+///
+/// The file contains an object with a `parts` array and a `parameters` object.
+/// Each part has a `name`, a `count`, a `size` (`[x, y, z]`), and a `volume`.
+pub fn write_json(bom: &BillOfMaterials, path: &Path) -> Result<(), Error> {
+    let parts = bom
+        .entries
+        .iter()
+        .map(|entry| {
+            json!({
+                "name": entry.name,
+                "count": entry.count,
+                "size": entry.size.components.map(Scalar::into_f64),
+                "volume": entry.volume.into_f64(),
+            })
+        })
+        .collect::<Vec<_>>();
+
+    let mut file = BufWriter::new(File::create(path)?);
+    serde_json::to_writer_pretty(
+        &mut file,
+        &json!({
+            "parts": parts,
+            "parameters": bom.parameters,
+        }),
+    )?;
+    file.flush()?;
+
+    Ok(())
+}
+
+fn write_row(
+    file: &mut impl std::io::Write,
+    fields: &[String],
+) -> Result<(), Error> {
+    let fields = fields.iter().map(|field| escape(field)).collect::<Vec<_>>();
+    writeln!(file, "{}", fields.join(","))?;
+
+    Ok(())
+}
+
+/// Quote a CSV field, if it contains characters that would break the format
+fn escape(field: &str) -> String {
+    if field.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
@@ -0,0 +1,210 @@
+use fj_interop::mesh::{Mesh, Triangle};
+use fj_math::{Point, Vector};
+
+/// The coordinate system that exported files use
+///
+/// Fornjot models use a right-handed coordinate system, with the z-axis
+/// pointing up. Many consumers of exported files, like game engines or tools
+/// built around glTF, expect the y-axis to point up instead, and some use a
+/// left-handed coordinate system.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct CoordinateSystem {
+    /// The axis that points up
+    pub up: UpAxis,
+
+    /// The handedness of the coordinate system
+    pub handedness: Handedness,
+}
+
+impl CoordinateSystem {
+    /// Convert a point from the coordinate system of the model into this one
+    ///
+    /// Switching to a y-up coordinate system maps the model's z-axis to the
+    /// y-axis. For right-handed coordinate systems, the model's y-axis then
+    /// points along the negative z-axis, which amounts to a rotation. For
+    /// left-handed ones, the handedness changes by mirroring the axis that
+    /// points forward.
+    pub fn convert_point(&self, point: Point<3>) -> Point<3> {
+        Point {
+            coords: self.convert_vector(point.coords),
+        }
+    }
+
+    /// Convert a vector from the coordinate system of the model into this one
+    ///
+    /// See [`CoordinateSystem::convert_point`].
+    pub fn convert_vector(&self, vector: Vector<3>) -> Vector<3> {
+        let [x, y, z] = vector.components;
+
+        let components = match (self.up, self.handedness) {
+            (UpAxis::Z, Handedness::Right) => [x, y, z],
+            (UpAxis::Z, Handedness::Left) => [x, -y, z],
+            (UpAxis::Y, Handedness::Right) => [x, z, -y],
+            (UpAxis::Y, Handedness::Left) => [x, z, y],
+        };
+
+        Vector::from(components)
+    }
+
+    /// Convert a mesh from the coordinate system of the model into this one
+    ///
+    /// If the conversion changes the handedness, the triangles are reversed,
+    /// so they keep facing outward.
+    pub fn convert_mesh(&self, mesh: &Mesh<Point<3>>) -> Mesh<Point<3>> {
+        let mut converted = Mesh::new();
+
+        for triangle in mesh.triangles() {
+            let [a, b, c] = triangle
+                .inner
+                .points()
+                .map(|point| self.convert_point(point));
+
+            let points = match self.handedness {
+                Handedness::Right => [a, b, c],
+                Handedness::Left => [a, c, b],
+            };
+
+            let normals = triangle.normals.map(|normals| {
+                let [na, nb, nc] =
+                    normals.map(|normal| self.convert_vector(normal));
+                match self.handedness {
+                    Handedness::Right => [na, nb, nc],
+                    Handedness::Left => [na, nc, nb],
+                }
+            });
+
+            let origin = match self.handedness {
+                Handedness::Right => triangle.origin,
+                Handedness::Left => triangle.origin.reverse(),
+            };
+
+            converted.extend([Triangle {
+                inner: points.into(),
+                normals,
+                origin,
+                ..triangle
+            }]);
+        }
+
+        converted
+    }
+}
+
+/// The axis that points up in a [`CoordinateSystem`]
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum UpAxis {
+    /// The z-axis points up, like in Fornjot and most CAD applications
+    #[default]
+    Z,
+
+    /// The y-axis points up, like in glTF and most game engines
+    Y,
+}
+
+/// The handedness of a [`CoordinateSystem`]
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum Handedness {
+    /// A right-handed coordinate system, like in Fornjot
+    #[default]
+    Right,
+
+    /// A left-handed coordinate system
+    Left,
+}
+
+#[cfg(test)]
+mod tests {
+    use fj_interop::mesh::{Color, Mesh, ObjectId, Origin};
+    use fj_math::{Point, Vector};
+
+    use super::{CoordinateSystem, Handedness, UpAxis};
+
+    const SYSTEMS: [CoordinateSystem; 4] = [
+        CoordinateSystem {
+            up: UpAxis::Z,
+            handedness: Handedness::Right,
+        },
+        CoordinateSystem {
+            up: UpAxis::Z,
+            handedness: Handedness::Left,
+        },
+        CoordinateSystem {
+            up: UpAxis::Y,
+            handedness: Handedness::Right,
+        },
+        CoordinateSystem {
+            up: UpAxis::Y,
+            handedness: Handedness::Left,
+        },
+    ];
+
+    #[test]
+    fn convert_point() {
+        let expected =
+            [[1., 2., 3.], [1., -2., 3.], [1., 3., -2.], [1., 3., 2.]];
+
+        for (system, expected) in SYSTEMS.into_iter().zip(expected) {
+            assert_eq!(
+                system.convert_point(Point::from([1., 2., 3.])),
+                Point::from(expected),
+                "{system:?}",
+            );
+        }
+    }
+
+    #[test]
+    fn convert_vector_to_handedness() {
+        for system in SYSTEMS {
+            let [x, y, z] =
+                [Vector::unit_x(), Vector::unit_y(), Vector::unit_z()]
+                    .map(|axis| system.convert_vector(axis));
+
+            let expected = match system.handedness {
+                Handedness::Right => z,
+                Handedness::Left => -z,
+            };
+            assert_eq!(x.cross(&y), expected, "{system:?}");
+        }
+    }
+
+    #[test]
+    fn convert_mesh_keeps_triangles_facing_outward() {
+        let normals = [[0., 0., 1.], [0., 0.1, 1.], [0.1, 0., 1.]]
+            .map(|normal| Vector::from(normal).normalize());
+
+        let mut mesh = Mesh::new();
+        mesh.push_triangle_with_normals(
+            [[0., 0., 0.], [1., 0., 0.], [0., 1., 0.]],
+            normals,
+            Color::default(),
+        );
+        let mut triangle = mesh.triangles().next().unwrap();
+        triangle.origin = Origin {
+            face: Some(ObjectId(1)),
+            edges: [Some(ObjectId(2)), Some(ObjectId(3)), None],
+            vertices: [Some(ObjectId(4)), None, Some(ObjectId(5))],
+        };
+        let mut mesh = Mesh::new();
+        mesh.extend([triangle.clone()]);
+
+        for system in SYSTEMS {
+            let converted =
+                system.convert_mesh(&mesh).triangles().next().unwrap();
+
+            // The triangle keeps facing in the direction of its normal.
+            assert_eq!(
+                converted.inner.normal(),
+                system.convert_vector(triangle.inner.normal()),
+                "{system:?}",
+            );
+
+            let [a, b, c] = normals.map(|normal| system.convert_vector(normal));
+            let (normals, origin) = match system.handedness {
+                Handedness::Right => ([a, b, c], triangle.origin),
+                Handedness::Left => ([a, c, b], triangle.origin.reverse()),
+            };
+            assert_eq!(converted.normals, Some(normals), "{system:?}");
+            assert_eq!(converted.origin, origin, "{system:?}");
+        }
+    }
+}
@@ -0,0 +1,190 @@
+//! Export to DXF
+//!
+//! Writes two-dimensional profiles, as expected by laser cutters and CNC
+//! routers. Lines, circles and circular arcs are written as such, all other
+//! curves as polylines.
+//!
+//! Files are written in the R12 variant of the format, which is understood by
+//! pretty much every tool that reads DXF at all.
+
+use std::{
+    fmt::Display,
+    fs::File,
+    io::{BufWriter, Write},
+    path::Path,
+};
+
+use fj_math::Point;
+
+use crate::{
+    profile::{Entity, Layer},
+    Error,
+};
+
+/// Write the layers to a DXF file
+pub fn write(layers: &[Layer], path: &Path) -> Result<(), Error> {
+    let mut file = BufWriter::new(File::create(path)?);
+
+    group(&mut file, 0, "SECTION")?;
+    group(&mut file, 2, "HEADER")?;
+    group(&mut file, 9, "$ACADVER")?;
+    group(&mut file, 1, "AC1009")?;
+    // Millimeters
+    group(&mut file, 9, "$INSUNITS")?;
+    group(&mut file, 70, 4)?;
+    group(&mut file, 0, "ENDSEC")?;
+
+    group(&mut file, 0, "SECTION")?;
+    group(&mut file, 2, "ENTITIES")?;
+    for (layer, entity) in layers.iter().flat_map(|layer| {
+        layer.entities.iter().map(|entity| (&layer.name, entity))
+    }) {
+        match entity {
+            Entity::Line([start, end]) => {
+                group(&mut file, 0, "LINE")?;
+                group(&mut file, 8, layer)?;
+                point(&mut file, 10, *start)?;
+                point(&mut file, 11, *end)?;
+            }
+            Entity::Circle { center, radius } => {
+                group(&mut file, 0, "CIRCLE")?;
+                group(&mut file, 8, layer)?;
+                point(&mut file, 10, *center)?;
+                group(&mut file, 40, radius)?;
+            }
+            Entity::Arc {
+                center,
+                radius,
+                angles: [start, end],
+            } => {
+                group(&mut file, 0, "ARC")?;
+                group(&mut file, 8, layer)?;
+                point(&mut file, 10, *center)?;
+                group(&mut file, 40, radius)?;
+                group(&mut file, 50, start)?;
+                group(&mut file, 51, end)?;
+            }
+            Entity::Polyline { points, closed } => {
+                group(&mut file, 0, "POLYLINE")?;
+                group(&mut file, 8, layer)?;
+                group(&mut file, 66, 1)?;
+                point(&mut file, 10, Point::origin())?;
+                group(&mut file, 70, u32::from(*closed))?;
+
+                for &vertex in points {
+                    group(&mut file, 0, "VERTEX")?;
+                    group(&mut file, 8, layer)?;
+                    point(&mut file, 10, vertex)?;
+                }
+
+                group(&mut file, 0, "SEQEND")?;
+                group(&mut file, 8, layer)?;
+            }
+        }
+    }
+    group(&mut file, 0, "ENDSEC")?;
+    group(&mut file, 0, "EOF")?;
+
+    file.flush()?;
+
+    Ok(())
+}
+
+/// Write a group, which is made up of a group code and a value
+fn group(
+    file: &mut impl Write,
+    code: u32,
+    value: impl Display,
+) -> Result<(), Error> {
+    writeln!(file, "{code}")?;
+    writeln!(file, "{value}")?;
+
+    Ok(())
+}
+
+/// Write the coordinates of a point, starting with the given group code
+fn point(
+    file: &mut impl Write,
+    code: u32,
+    point: Point<2>,
+) -> Result<(), Error> {
+    group(file, code, point.u)?;
+    group(file, code + 10, point.v)?;
+    group(file, code + 20, 0.)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use fj_kernel::{
+        algorithms::bounding_volume::BoundingVolume, services::Services,
+    };
+    use fj_math::{Aabb, Point, Scalar};
+
+    use crate::profile::{Entity, Layer};
+
+    #[test]
+    fn round_trip() {
+        let square = [[0., 0.], [10., 0.], [10., 10.], [0., 10.]];
+        let layers = [
+            Layer {
+                name: "plate".into(),
+                entities: vec![
+                    Entity::Polyline {
+                        points: square.map(Point::from).to_vec(),
+                        closed: true,
+                    },
+                    Entity::Circle {
+                        center: Point::from([5., 5.]),
+                        radius: Scalar::from(2.),
+                    },
+                ],
+            },
+            Layer {
+                name: "slot".into(),
+                entities: vec![
+                    Entity::Line([[20., 0.], [30., 0.]].map(Point::from)),
+                    Entity::Arc {
+                        center: Point::from([30., 5.]),
+                        radius: Scalar::from(5.),
+                        angles: [Scalar::from(-90.), Scalar::from(90.)],
+                    },
+                    Entity::Line([[30., 10.], [20., 10.]].map(Point::from)),
+                    Entity::Line([[20., 10.], [20., 0.]].map(Point::from)),
+                ],
+            },
+        ];
+
+        let path = std::env::temp_dir()
+            .join(format!("fj-export-dxf-test-{}.dxf", std::process::id()));
+        super::write(&layers, &path).unwrap();
+        let source = fs::read_to_string(&path).unwrap();
+        fs::remove_file(&path).unwrap();
+
+        assert!(source.contains("$INSUNITS\n70\n4\n"));
+        assert!(source.contains("ARC\n8\nslot\n10\n30\n20\n5\n30\n0\n"));
+
+        let mut services = Services::new();
+        let surface = services.objects.surfaces.xy_plane();
+        let sketch =
+            fj_import::import_dxf(&source, surface, &mut services.objects)
+                .unwrap();
+
+        // The circle is a hole in the plate, while the slot is a face of its
+        // own.
+        let mut interiors = sketch
+            .faces()
+            .into_iter()
+            .map(|face| face.interiors().count())
+            .collect::<Vec<_>>();
+        interiors.sort();
+        assert_eq!(interiors, [0, 1]);
+
+        let aabb = sketch.faces().aabb().unwrap();
+        let expected = Aabb::<3>::from_points([[0., 0., 0.], [35., 10., 0.]]);
+        for (a, b) in [(aabb.min, expected.min), (aabb.max, expected.max)] {
+            assert!((a - b).magnitude() < Scalar::from(1e-9), "{a:?} != {b:?}");
+        }
+    }
+}
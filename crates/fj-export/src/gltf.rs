@@ -0,0 +1,273 @@
+//! Export to glTF
+//!
+//! Supports both the binary (GLB) and the JSON-based variant of the format.
+//! Each part becomes a named node with its own mesh.
+//!
+//! The meshes come with vertex normals, so they are shaded correctly by viewers
+//! that don't compute normals themselves.
+//!
+//! Triangles whose faces have a material attribute are split off into separate
+//! primitives, which refer to a material of that name.
+
+use std::{collections::BTreeMap, fs::File, io::Write, path::Path, sync::Arc};
+
+use serde_json::json;
+
+use crate::{normals::vertices, Error, GltfColors, Part};
+
+const COMPONENT_TYPE_UNSIGNED_BYTE: u32 = 5121;
+const COMPONENT_TYPE_FLOAT: u32 = 5126;
+const COMPONENT_TYPE_UNSIGNED_INT: u32 = 5125;
+const TARGET_ARRAY_BUFFER: u32 = 34962;
+const TARGET_ELEMENT_ARRAY_BUFFER: u32 = 34963;
+const MODE_TRIANGLES: u32 = 4;
+
+/// Write the parts to a binary glTF file
+pub fn write_glb(
+    parts: &[Part],
+    colors: GltfColors,
+    path: &Path,
+) -> Result<(), Error> {
+    let (document, buffer) = document(parts, colors, None);
+
+    let mut json = serde_json::to_vec(&document)?;
+    pad(&mut json, b' ');
+    let mut buffer = buffer;
+    pad(&mut buffer, 0);
+
+    let length = 12 + 8 + json.len() + 8 + buffer.len();
+    let length = u32::try_from(length).map_err(|_| Error::FileTooLarge)?;
+
+    let mut file = File::create(path)?;
+
+    file.write_all(b"glTF")?;
+    file.write_all(&2u32.to_le_bytes())?;
+    file.write_all(&length.to_le_bytes())?;
+
+    for (chunk_type, chunk) in [(b"JSON", json), (b"BIN\0", buffer)] {
+        let chunk_length =
+            u32::try_from(chunk.len()).map_err(|_| Error::FileTooLarge)?;
+
+        file.write_all(&chunk_length.to_le_bytes())?;
+        file.write_all(chunk_type)?;
+        file.write_all(&chunk)?;
+    }
+
+    Ok(())
+}
+
+/// Write the parts to a glTF file, with the binary data embedded
+pub fn write_gltf(
+    parts: &[Part],
+    colors: GltfColors,
+    path: &Path,
+) -> Result<(), Error> {
+    let (_, buffer) = document(parts, colors, None);
+    let uri = format!(
+        "data:application/octet-stream;base64,{}",
+        base64::encode(&buffer)
+    );
+    let (document, _) = document(parts, colors, Some(uri));
+
+    let file = File::create(path)?;
+    serde_json::to_writer(file, &document)?;
+
+    Ok(())
+}
+
+/// Create the glTF document for the parts, and the buffer it refers to
+///
+/// If `uri` is `None`, the buffer is expected to be stored in the binary chunk
+/// of a GLB file.
+fn document(
+    parts: &[Part],
+    colors: GltfColors,
+    uri: Option<String>,
+) -> (serde_json::Value, Vec<u8>) {
+    let mut buffer = Vec::new();
+
+    let mut nodes = Vec::new();
+    let mut meshes = Vec::new();
+    let mut accessors = Vec::new();
+    let mut buffer_views = Vec::new();
+    let mut materials = Vec::<Arc<str>>::new();
+
+    for part in parts {
+        let (vertices, indices) = vertices(part);
+
+        // Accessors must not be empty. A part without any triangles still
+        // gets a node, so it doesn't disappear completely.
+        if indices.is_empty() {
+            nodes.push(json!({ "name": part.name }));
+            continue;
+        }
+
+        let mut min = [f32::INFINITY; 3];
+        let mut max = [f32::NEG_INFINITY; 3];
+
+        let mut attributes = serde_json::Map::new();
+
+        let offset = buffer.len();
+        for vertex in &vertices {
+            let components =
+                vertex.position.coords.components.map(|s| s.into_f32());
+
+            for (i, component) in components.into_iter().enumerate() {
+                min[i] = min[i].min(component);
+                max[i] = max[i].max(component);
+                buffer.extend(component.to_le_bytes());
+            }
+        }
+        attributes.insert("POSITION".into(), accessors.len().into());
+        buffer_views.push(json!({
+            "buffer": 0,
+            "byteOffset": offset,
+            "byteLength": buffer.len() - offset,
+            "target": TARGET_ARRAY_BUFFER,
+        }));
+        accessors.push(json!({
+            "bufferView": buffer_views.len() - 1,
+            "componentType": COMPONENT_TYPE_FLOAT,
+            "count": vertices.len(),
+            "type": "VEC3",
+            "min": min,
+            "max": max,
+        }));
+
+        let offset = buffer.len();
+        for vertex in &vertices {
+            for component in vertex.normal.components {
+                buffer.extend(component.into_f32().to_le_bytes());
+            }
+        }
+        attributes.insert("NORMAL".into(), accessors.len().into());
+        buffer_views.push(json!({
+            "buffer": 0,
+            "byteOffset": offset,
+            "byteLength": buffer.len() - offset,
+            "target": TARGET_ARRAY_BUFFER,
+        }));
+        accessors.push(json!({
+            "bufferView": buffer_views.len() - 1,
+            "componentType": COMPONENT_TYPE_FLOAT,
+            "count": vertices.len(),
+            "type": "VEC3",
+        }));
+
+        if colors == GltfColors::Vertex {
+            let offset = buffer.len();
+            for vertex in &vertices {
+                buffer.extend(vertex.color.0);
+            }
+            attributes.insert("COLOR_0".into(), accessors.len().into());
+            buffer_views.push(json!({
+                "buffer": 0,
+                "byteOffset": offset,
+                "byteLength": buffer.len() - offset,
+                "target": TARGET_ARRAY_BUFFER,
+            }));
+            accessors.push(json!({
+                "bufferView": buffer_views.len() - 1,
+                "componentType": COMPONENT_TYPE_UNSIGNED_BYTE,
+                "normalized": true,
+                "count": vertices.len(),
+                "type": "VEC4",
+            }));
+        }
+
+        // The indices come in the same order as the triangles.
+        let mut indices_by_material = BTreeMap::<_, Vec<u32>>::new();
+        for (triangle, indices) in part.mesh.triangles().zip(indices.chunks(3))
+        {
+            indices_by_material
+                .entry(triangle.attributes.material)
+                .or_default()
+                .extend(indices);
+        }
+
+        let mut primitives = Vec::new();
+        for (material, indices) in indices_by_material {
+            let offset = buffer.len();
+            for index in &indices {
+                buffer.extend(index.to_le_bytes());
+            }
+            buffer_views.push(json!({
+                "buffer": 0,
+                "byteOffset": offset,
+                "byteLength": buffer.len() - offset,
+                "target": TARGET_ELEMENT_ARRAY_BUFFER,
+            }));
+            accessors.push(json!({
+                "bufferView": buffer_views.len() - 1,
+                "componentType": COMPONENT_TYPE_UNSIGNED_INT,
+                "count": indices.len(),
+                "type": "SCALAR",
+            }));
+
+            let mut primitive = json!({
+                "attributes": attributes,
+                "indices": accessors.len() - 1,
+                "mode": MODE_TRIANGLES,
+            });
+            if let Some(material) = material {
+                let index = materials
+                    .iter()
+                    .position(|m| *m == material)
+                    .unwrap_or_else(|| {
+                        materials.push(material);
+                        materials.len() - 1
+                    });
+                primitive["material"] = index.into();
+            }
+            primitives.push(primitive);
+        }
+
+        meshes.push(json!({
+            "name": part.name,
+            "primitives": primitives,
+        }));
+        nodes.push(json!({
+            "name": part.name,
+            "mesh": meshes.len() - 1,
+        }));
+    }
+
+    let mut buffer_json = json!({ "byteLength": buffer.len() });
+    if let Some(uri) = uri {
+        buffer_json["uri"] = uri.into();
+    }
+
+    let mut document = json!({
+        "asset": {
+            "version": "2.0",
+            "generator": "Fornjot",
+        },
+        "scene": 0,
+        "scenes": [{ "nodes": (0..nodes.len()).collect::<Vec<_>>() }],
+        "nodes": nodes,
+    });
+
+    // glTF doesn't allow empty arrays, so only add those, if there's any
+    // geometry at all.
+    if !meshes.is_empty() {
+        document["meshes"] = meshes.into();
+        document["accessors"] = accessors.into();
+        document["bufferViews"] = buffer_views.into();
+        document["buffers"] = json!([buffer_json]);
+    }
+    if !materials.is_empty() {
+        document["materials"] = materials
+            .iter()
+            .map(|material| json!({ "name": &**material }))
+            .collect::<Vec<_>>()
+            .into();
+    }
+
+    (document, buffer)
+}
+
+/// Pad the data to a multiple of 4 bytes, as GLB chunks require
+fn pad(data: &mut Vec<u8>, padding: u8) {
+    let num_padding_bytes = (4 - data.len() % 4) % 4;
+    data.resize(data.len() + num_padding_bytes, padding);
+}
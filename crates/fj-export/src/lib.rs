@@ -14,26 +14,100 @@
 
 #![warn(missing_docs)]
 
-use std::{fs::File, path::Path};
+mod amf;
+mod bom;
+mod coordinate_system;
+mod dxf;
+mod gltf;
+mod nest;
+mod normals;
+mod obj;
+mod ply;
+mod profile;
+mod slices;
+mod step;
+mod svg;
+mod three_mf;
+mod thumbnail;
+mod view;
+
+use std::{
+    fs::File,
+    io::{BufWriter, Write as _},
+    path::{Path, PathBuf},
+};
 
 use thiserror::Error;
 
-use fj_interop::mesh::Mesh;
-use fj_math::{Point, Triangle};
+use fj_interop::{
+    mesh::{Color, Mesh, Triangle as MeshTriangle},
+    units::Units,
+};
+use fj_kernel::{
+    algorithms::{approx::Tolerance, offset, slice},
+    objects::Sketch,
+};
+use fj_math::{Plane, Point, Scalar, Triangle};
+
+pub use self::{
+    bom::{BillOfMaterials, BomEntry},
+    coordinate_system::{CoordinateSystem, Handedness, UpAxis},
+    nest::NestOptions,
+    step::BrepPart,
+    view::View,
+};
 
 /// Export the provided mesh to the file at the given path.
 ///
 /// This function will create a file if it does not exist, and will truncate it if it does.
 ///
-/// Currently 3MF & STL file types are supported. The case insensitive file extension of
+/// Currently 3MF, AMF, STL, glTF, GLB, OBJ & PLY file types are supported. The case insensitive file extension of
 /// the provided path is used to switch between supported types.
 pub fn export(mesh: &Mesh<Point<3>>, path: &Path) -> Result<(), Error> {
+    export_mesh(
+        Part::unnamed(mesh, Units::default()),
+        path,
+        &ExportOptions::default(),
+    )
+}
+
+fn export_mesh(
+    part: Part,
+    path: &Path,
+    options: &ExportOptions,
+) -> Result<(), Error> {
     match path.extension() {
         Some(extension) if extension.to_ascii_uppercase() == "3MF" => {
-            export_3mf(mesh, path)
+            three_mf::write(&[part], path)
         }
         Some(extension) if extension.to_ascii_uppercase() == "STL" => {
-            export_stl(mesh, path)
+            match options.stl_format {
+                StlFormat::Binary => {
+                    export_stl(part.mesh, path, options.stl_colors)
+                }
+                StlFormat::Ascii => {
+                    if options.stl_colors != StlColors::None {
+                        return Err(Error::AsciiStlColors);
+                    }
+
+                    export_ascii_stl(part, path)
+                }
+            }
+        }
+        Some(extension) if extension.to_ascii_uppercase() == "GLB" => {
+            gltf::write_glb(&[part], options.gltf_colors, path)
+        }
+        Some(extension) if extension.to_ascii_uppercase() == "GLTF" => {
+            gltf::write_gltf(&[part], options.gltf_colors, path)
+        }
+        Some(extension) if extension.to_ascii_uppercase() == "OBJ" => {
+            obj::write(&[part], path)
+        }
+        Some(extension) if extension.to_ascii_uppercase() == "AMF" => {
+            amf::write(&[part], path)
+        }
+        Some(extension) if extension.to_ascii_uppercase() == "PLY" => {
+            ply::write(part, path)
         }
         Some(extension) => Err(Error::InvalidExtension(
             extension.to_string_lossy().into_owned(),
@@ -42,36 +116,569 @@ pub fn export(mesh: &Mesh<Point<3>>, path: &Path) -> Result<(), Error> {
     }
 }
 
-fn export_3mf(mesh: &Mesh<Point<3>>, path: &Path) -> Result<(), Error> {
-    let vertices = mesh.vertices().map(|vertex| vertex.into()).collect();
+/// Export the provided parts to one or more files, based on the given path
+///
+/// Returns the paths of all files that have been written.
+///
+/// See [`ExportOptions`] for how the parts are exported.
+pub fn export_parts(
+    parts: &[Part],
+    path: &Path,
+    options: &ExportOptions,
+) -> Result<Vec<PathBuf>, Error> {
+    let units = options.units(parts.iter().map(|part| part.units));
 
-    let indices: Vec<_> = mesh.indices().collect();
-    let triangles = indices
-        .chunks(3)
-        .map(|triangle| {
-            [
-                triangle[0] as usize,
-                triangle[1] as usize,
-                triangle[2] as usize,
-            ]
+    let meshes = parts
+        .iter()
+        .map(|part| {
+            let mesh = scale_mesh(part.mesh, part.units.factor(units));
+            options.coordinate_system.convert_mesh(&mesh)
+        })
+        .collect::<Vec<_>>();
+    let parts = parts
+        .iter()
+        .zip(&meshes)
+        .map(|(part, mesh)| Part {
+            name: part.name,
+            mesh,
+            units,
         })
-        .collect();
+        .collect::<Vec<_>>();
 
-    let mesh = threemf::TriangleMesh {
-        vertices,
-        triangles,
-    };
+    match options.parts {
+        PartExport::Combined => {
+            let mut mesh = Mesh::new();
+            for part in &parts {
+                mesh.extend(part.mesh.triangles());
+            }
 
-    threemf::write(path, &mesh)?;
+            export_mesh(Part::unnamed(&mesh, units), path, options)?;
+            Ok(vec![path.to_path_buf()])
+        }
+        PartExport::Separate => {
+            let mut paths = Vec::new();
 
-    Ok(())
+            for (i, part) in parts.iter().enumerate() {
+                let path = part_path(path, part.name, i + 1);
+                export_mesh(*part, &path, options)?;
+                paths.push(path);
+            }
+
+            Ok(paths)
+        }
+        PartExport::MultiObject => {
+            match path.extension() {
+                Some(extension) if extension.to_ascii_uppercase() == "3MF" => {
+                    three_mf::write(&parts, path)?
+                }
+                Some(extension) if extension.to_ascii_uppercase() == "GLB" => {
+                    gltf::write_glb(&parts, options.gltf_colors, path)?
+                }
+                Some(extension) if extension.to_ascii_uppercase() == "GLTF" => {
+                    gltf::write_gltf(&parts, options.gltf_colors, path)?
+                }
+                Some(extension) if extension.to_ascii_uppercase() == "OBJ" => {
+                    obj::write(&parts, path)?
+                }
+                Some(extension) if extension.to_ascii_uppercase() == "AMF" => {
+                    amf::write(&parts, path)?
+                }
+                Some(extension) => {
+                    return Err(Error::MultiObjectUnsupported(
+                        extension.to_string_lossy().into_owned(),
+                    ))
+                }
+                None => return Err(Error::NoExtension),
+            }
+
+            Ok(vec![path.to_path_buf()])
+        }
+    }
+}
+
+/// Export the boundary representation of the provided parts
+///
+/// Returns the paths of all files that have been written. Unlike
+/// [`export_parts`], this preserves the exact geometry of the model, instead of
+/// a triangle mesh. See [`is_brep_format`] for the file types this supports.
+///
+/// All parts are exported to a single file, unless [`PartExport::Separate`] is
+/// selected in the options.
+pub fn export_brep_parts(
+    parts: &[BrepPart],
+    path: &Path,
+    options: &ExportOptions,
+) -> Result<Vec<PathBuf>, Error> {
+    if !is_brep_format(path) {
+        return match path.extension() {
+            Some(extension) => Err(Error::InvalidExtension(
+                extension.to_string_lossy().into_owned(),
+            )),
+            None => Err(Error::NoExtension),
+        };
+    }
+
+    let units = options.units(parts.iter().map(|part| part.units));
+
+    match options.parts {
+        PartExport::Combined | PartExport::MultiObject => {
+            step::write(parts, path, options.coordinate_system, units)?;
+            Ok(vec![path.to_path_buf()])
+        }
+        PartExport::Separate => {
+            let mut paths = Vec::new();
+
+            for (i, part) in parts.iter().enumerate() {
+                let path = part_path(path, part.name, i + 1);
+                step::write(&[*part], &path, options.coordinate_system, units)?;
+                paths.push(path);
+            }
+
+            Ok(paths)
+        }
+    }
+}
+
+/// Determine whether the file at the given path stores boundary
+/// representations
+///
+/// Such files need to be exported using [`export_brep_parts`]. Currently, STEP
+/// is the only supported format of that kind.
+pub fn is_brep_format(path: &Path) -> bool {
+    matches!(
+        path.extension(),
+        Some(extension)
+            if extension.to_ascii_uppercase() == "STEP"
+                || extension.to_ascii_uppercase() == "STP"
+    )
+}
+
+/// Export the provided sketch to the file at the given path
+///
+/// Lines, circles and circular arcs are exported exactly. All other curves are
+/// approximated with the given tolerance. See [`is_2d_format`] for the file
+/// types this supports.
+pub fn export_sketch(
+    sketch: &Sketch,
+    tolerance: Tolerance,
+    path: &Path,
+) -> Result<(), Error> {
+    check_2d_format(path)?;
+    write_2d(&profile::sketch(sketch, tolerance)?, path)
+}
+
+/// Export the section of the provided parts with a plane
+///
+/// Coordinates in the exported file are relative to the plane. The section is
+/// approximated with the given tolerance, except where faces of the parts lie
+/// within the plane. See [`is_2d_format`] for the file types this supports.
+///
+/// The profiles of faces within the plane are offset by `kerf`, to compensate
+/// for the material that a cutting process removes. Positive values grow the
+/// faces and shrink their holes, negative values leave clearance instead. Pass
+/// zero to export the profiles as they are. Sections of faces that cross the
+/// plane can't be offset, and result in an error, unless `kerf` is zero.
+pub fn export_section(
+    parts: &[BrepPart],
+    plane: &Plane,
+    kerf: Scalar,
+    tolerance: Tolerance,
+    path: &Path,
+) -> Result<(), Error> {
+    check_2d_format(path)?;
+    write_2d(&profile::section(parts, plane, kerf, tolerance)?, path)
+}
+
+/// Export the section of the provided parts, nested onto sheets
+///
+/// Computes the same profiles as [`export_section`], then arranges them next
+/// to each other on sheets of the size given in the [`NestOptions`], ready for
+/// cutting. Each sheet is written to its own file, with the outline of the
+/// sheet in a separate layer. `{index}` in the file name is replaced with the
+/// 1-based index of the sheet. If the file name doesn't contain it, `-{index}`
+/// is inserted before the extension.
+///
+/// Returns the paths of all files that have been written.
+pub fn export_nested(
+    parts: &[BrepPart],
+    plane: &Plane,
+    kerf: Scalar,
+    tolerance: Tolerance,
+    options: &NestOptions,
+    path: &Path,
+) -> Result<Vec<PathBuf>, Error> {
+    check_2d_format(path)?;
+
+    let layers = profile::section(parts, plane, kerf, tolerance)?;
+
+    let mut paths = Vec::new();
+    for (i, layers) in nest::nest(layers, options)?.iter().enumerate() {
+        let path = slices::layer_path(path, i + 1);
+        write_2d(layers, &path)?;
+        paths.push(path);
+    }
+
+    Ok(paths)
+}
+
+/// Export a view of the provided parts, with hidden lines removed
+///
+/// The view is computed from the triangle meshes of the parts. Sharp edges,
+/// the boundaries of the meshes, and the silhouettes of curved surfaces are
+/// exported where they are not hidden behind other triangles. See
+/// [`is_2d_format`] for the file types this supports.
+pub fn export_view(
+    parts: &[Part],
+    view: View,
+    path: &Path,
+) -> Result<(), Error> {
+    check_2d_format(path)?;
+    write_2d(&view::layers(parts, view), path)
+}
+
+/// Export the layers of a sliced model
+///
+/// Returns the paths of all files that have been written. JSON files store all
+/// layers at once. SVG files store one layer each, and `{index}` in the file
+/// name is replaced with the 1-based index of the layer. If the file name
+/// doesn't contain it, `-{index}` is inserted before the extension.
+///
+/// See [`slice::slice`] for how to compute the layers.
+pub fn export_slices(
+    layers: &[slice::Layer],
+    path: &Path,
+) -> Result<Vec<PathBuf>, Error> {
+    match path.extension() {
+        Some(extension) if extension.to_ascii_uppercase() == "JSON" => {
+            slices::write_json(layers, path)?;
+            Ok(vec![path.to_path_buf()])
+        }
+        Some(extension) if extension.to_ascii_uppercase() == "SVG" => {
+            slices::write_svg(layers, path)
+        }
+        Some(extension) => Err(Error::InvalidExtension(
+            extension.to_string_lossy().into_owned(),
+        )),
+        None => Err(Error::NoExtension),
+    }
+}
+
+/// Export a bill of materials
+///
+/// CSV and JSON files are supported. See [`BillOfMaterials::new`] for how to
+/// compute the bill of materials.
+pub fn export_bom(bom: &BillOfMaterials, path: &Path) -> Result<(), Error> {
+    match path.extension() {
+        Some(extension) if extension.to_ascii_uppercase() == "CSV" => {
+            bom::write_csv(bom, path)
+        }
+        Some(extension) if extension.to_ascii_uppercase() == "JSON" => {
+            bom::write_json(bom, path)
+        }
+        Some(extension) => Err(Error::InvalidExtension(
+            extension.to_string_lossy().into_owned(),
+        )),
+        None => Err(Error::NoExtension),
+    }
+}
+
+/// Write a thumbnail of the provided mesh to a PNG file
+///
+/// The thumbnail is square, `size` pixels wide and high. The model is shown
+/// from the front, right, and above, and fills most of the thumbnail. It is
+/// rendered in software, so this works on machines without a graphics device.
+pub fn export_thumbnail(
+    mesh: &Mesh<Point<3>>,
+    size: u32,
+    path: &Path,
+) -> Result<(), Error> {
+    thumbnail::write(mesh, size, path)
+}
+
+/// Determine whether the file at the given path stores two-dimensional
+/// profiles
+///
+/// Such files need to be exported using [`export_sketch`], [`export_section`]
+/// or [`export_view`]. Currently, DXF and SVG are the supported formats of that
+/// kind.
+pub fn is_2d_format(path: &Path) -> bool {
+    matches!(
+        path.extension(),
+        Some(extension)
+            if extension.to_ascii_uppercase() == "DXF"
+                || extension.to_ascii_uppercase() == "SVG"
+    )
+}
+
+fn write_2d(layers: &[profile::Layer], path: &Path) -> Result<(), Error> {
+    match path.extension() {
+        Some(extension) if extension.to_ascii_uppercase() == "DXF" => {
+            dxf::write(layers, path)
+        }
+        Some(extension) if extension.to_ascii_uppercase() == "SVG" => {
+            svg::write(layers, path)
+        }
+        Some(extension) => Err(Error::InvalidExtension(
+            extension.to_string_lossy().into_owned(),
+        )),
+        None => Err(Error::NoExtension),
+    }
+}
+
+fn check_2d_format(path: &Path) -> Result<(), Error> {
+    if is_2d_format(path) {
+        return Ok(());
+    }
+
+    match path.extension() {
+        Some(extension) => Err(Error::InvalidExtension(
+            extension.to_string_lossy().into_owned(),
+        )),
+        None => Err(Error::NoExtension),
+    }
+}
+
+/// Options for exporting a model
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct ExportOptions {
+    /// How to export a model that consists of multiple parts
+    pub parts: PartExport,
+
+    /// The coordinate system that the exported files use
+    pub coordinate_system: CoordinateSystem,
+
+    /// Whether STL files are written in binary or ASCII format
+    pub stl_format: StlFormat,
+
+    /// How to store the colors of triangles in binary STL files
+    pub stl_colors: StlColors,
+
+    /// Whether glTF and GLB files include the colors of triangles
+    pub gltf_colors: GltfColors,
+
+    /// The units that exported files use
+    ///
+    /// Parts that are given in other units are scaled accordingly. If this is
+    /// `None`, the units of the first part are used. 3MF, AMF, and STEP files
+    /// record the units. Other formats, like STL, have no notion of units, and
+    /// only store the scaled coordinates.
+    pub units: Option<Units>,
+}
+
+impl ExportOptions {
+    /// Select the units of the exported files, for parts in the given units
+    fn units(&self, mut parts: impl Iterator<Item = Units>) -> Units {
+        self.units.or_else(|| parts.next()).unwrap_or_default()
+    }
+}
+
+/// The format of STL files
+///
+/// Binary files are much smaller and faster to read. ASCII files are easier to
+/// inspect and are still expected by some older tools, but can't store colors.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum StlFormat {
+    /// Write binary STL files
+    #[default]
+    Binary,
+
+    /// Write ASCII STL files
+    ///
+    /// The name of the part is used as the name of the solid.
+    Ascii,
+}
+
+/// How to store the colors of triangles in binary STL files
+///
+/// The STL format has no support for colors. The two-byte "attribute" of each
+/// triangle is unused by the standard, and there are two competing, mutually
+/// incompatible, conventions to store a 15-bit color in there.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum StlColors {
+    /// Don't store any colors
+    #[default]
+    None,
+
+    /// Store colors as used by VisCAM and SolidView
+    ///
+    /// The highest bit marks the color as valid. Blue is stored in the lowest
+    /// bits, red in the highest.
+    VisCam,
+
+    /// Store colors as used by Materialise Magics
+    ///
+    /// A default color is stored in the header. The highest bit of each
+    /// triangle's attribute is cleared, to mark its color as valid. Red is
+    /// stored in the lowest bits, blue in the highest.
+    Magics,
+}
+
+impl StlColors {
+    fn attribute(&self, color: Color) -> u16 {
+        let [r, g, b, _] = color.0.map(|channel| u16::from(channel >> 3));
+
+        match self {
+            Self::None => 0,
+            Self::VisCam => 0x8000 | (r << 10) | (g << 5) | b,
+            Self::Magics => (b << 10) | (g << 5) | r,
+        }
+    }
+}
+
+/// Whether glTF and GLB files include the colors of triangles
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum GltfColors {
+    /// Don't store any colors
+    #[default]
+    None,
+
+    /// Store the color of each triangle as a vertex color
+    ///
+    /// Vertices aren't shared between triangles of different colors, so each
+    /// face keeps its own color. Colors that don't come from the model, like
+    /// the results of an analysis, can be exported by changing the colors of
+    /// the mesh's triangles before exporting it.
+    Vertex,
+}
+
+/// A named part of a model, to be exported
+#[derive(Clone, Copy, Debug)]
+pub struct Part<'r> {
+    /// The name of the part
+    pub name: &'r str,
+
+    /// The triangle mesh of the part
+    pub mesh: &'r Mesh<Point<3>>,
+
+    /// The units that the lengths of the part are given in
+    pub units: Units,
+}
+
+impl<'r> Part<'r> {
+    fn unnamed(mesh: &'r Mesh<Point<3>>, units: Units) -> Self {
+        Self {
+            name: "model",
+            mesh,
+            units,
+        }
+    }
+}
+
+/// How to export a model that consists of multiple parts
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum PartExport {
+    /// Merge all parts into a single mesh, and export that to one file
+    #[default]
+    Combined,
+
+    /// Export each part to its own file
+    ///
+    /// The file name of the provided path is used as a template. `{name}` is
+    /// replaced with the name of the part, `{index}` with its 1-based index.
+    /// If the file name contains neither, `-{name}` is inserted before the
+    /// extension.
+    Separate,
+
+    /// Export all parts to a single file, as separate, named objects
+    ///
+    /// Only supported for 3MF, AMF, glTF, GLB, OBJ & STEP files.
+    MultiObject,
+}
+
+fn part_path(path: &Path, name: &str, index: usize) -> PathBuf {
+    let file_name = path
+        .file_name()
+        .map(|file_name| file_name.to_string_lossy().into_owned())
+        .unwrap_or_default();
+
+    let template =
+        if file_name.contains("{name}") || file_name.contains("{index}") {
+            file_name
+        } else {
+            let stem = path
+                .file_stem()
+                .map(|stem| stem.to_string_lossy().into_owned())
+                .unwrap_or_default();
+
+            match path.extension() {
+                Some(extension) => {
+                    format!("{stem}-{{name}}.{}", extension.to_string_lossy())
+                }
+                None => format!("{stem}-{{name}}"),
+            }
+        };
+
+    let file_name = template
+        .replace("{name}", &sanitize(name))
+        .replace("{index}", &index.to_string());
+
+    path.with_file_name(file_name)
+}
+
+/// Make a part name safe for use in a file name
+fn sanitize(name: &str) -> String {
+    name.chars()
+        .map(|c| match c {
+            '/' | '\\' | ':' | '*' | '?' | '"' | '<' | '>' | '|' => '_',
+            c if c.is_control() => '_',
+            c => c,
+        })
+        .collect()
+}
+
+/// Scale a mesh uniformly, around the origin
+fn scale_mesh(mesh: &Mesh<Point<3>>, factor: f64) -> Mesh<Point<3>> {
+    let mut scaled = Mesh::new();
+
+    scaled.extend(mesh.triangles().map(|triangle| MeshTriangle {
+        inner: triangle.inner.points().map(|point| point * factor).into(),
+        ..triangle
+    }));
+
+    scaled
+}
+
+/// The name of the units, as used by the `unit` attribute of 3MF and AMF files
+///
+/// Files without any parts default to millimeters.
+fn unit_name(units: Option<Units>) -> &'static str {
+    match units.unwrap_or_default() {
+        Units::Millimeters => "millimeter",
+        Units::Inches => "inch",
+        Units::Meters => "meter",
+    }
+}
+
+/// Escape a string for use in XML
+fn escape(s: &str) -> String {
+    let mut escaped = String::with_capacity(s.len());
+
+    for c in s.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&apos;"),
+            c => escaped.push(c),
+        }
+    }
+
+    escaped
 }
 
-fn export_stl(mesh: &Mesh<Point<3>>, path: &Path) -> Result<(), Error> {
+fn export_stl(
+    mesh: &Mesh<Point<3>>,
+    path: &Path,
+    colors: StlColors,
+) -> Result<(), Error> {
     let points = mesh
         .triangles()
         .map(|triangle| triangle.inner.points())
         .collect::<Vec<_>>();
+    let attributes = mesh
+        .triangles()
+        .map(|triangle| colors.attribute(triangle.color));
 
     let vertices = points.iter().map(|points| {
         points.map(|point| point.coords.components.map(|s| s.into_f32()))
@@ -85,20 +692,35 @@ fn export_stl(mesh: &Mesh<Point<3>>, path: &Path) -> Result<(), Error> {
 
     let triangles = vertices
         .zip(normals)
-        .map(|([v1, v2, v3], normal)| stl::Triangle {
+        .zip(attributes)
+        .map(|(([v1, v2, v3], normal), attr_byte_count)| stl::Triangle {
             normal,
             v1,
             v2,
             v3,
-            attr_byte_count: 0,
+            attr_byte_count,
         })
         .collect::<Vec<_>>();
 
+    // Magics expects a default color in the header, which is used for
+    // triangles that don't have a valid color of their own.
+    let mut header = [0u8; 80];
+    if colors == StlColors::Magics {
+        let color = mesh
+            .triangles()
+            .next()
+            .map(|triangle| triangle.color)
+            .unwrap_or_default();
+
+        header[..6].copy_from_slice(b"COLOR=");
+        header[6..10].copy_from_slice(&color.0);
+    }
+
     let mut file = File::create(path)?;
 
     let binary_stl_file = stl::BinaryStlFile {
         header: stl::BinaryStlHeader {
-            header: [0u8; 80],
+            header,
             num_triangles: triangles
                 .len()
                 .try_into()
@@ -112,6 +734,37 @@ fn export_stl(mesh: &Mesh<Point<3>>, path: &Path) -> Result<(), Error> {
     Ok(())
 }
 
+fn export_ascii_stl(part: Part, path: &Path) -> Result<(), Error> {
+    let mut file = BufWriter::new(File::create(path)?);
+
+    // The name ends at the first whitespace, as far as most readers are
+    // concerned.
+    let name = part.name.split_whitespace().collect::<Vec<_>>().join("_");
+    writeln!(file, "solid {name}")?;
+
+    for triangle in part.mesh.triangles() {
+        let points = triangle.inner.points();
+        let [nx, ny, nz] = Triangle::<3>::from(points)
+            .normal()
+            .components
+            .map(|s| s.into_f64());
+
+        writeln!(file, "  facet normal {nx:e} {ny:e} {nz:e}")?;
+        writeln!(file, "    outer loop")?;
+        for point in points {
+            let [x, y, z] = point.coords.components.map(|s| s.into_f64());
+            writeln!(file, "      vertex {x:e} {y:e} {z:e}")?;
+        }
+        writeln!(file, "    endloop")?;
+        writeln!(file, "  endfacet")?;
+    }
+
+    writeln!(file, "endsolid {name}")?;
+    file.flush()?;
+
+    Ok(())
+}
+
 /// An error that can occur while exporting
 #[derive(Debug, Error)]
 pub enum Error {
@@ -127,11 +780,43 @@ pub enum Error {
     #[error("I/O error whilst exporting to file")]
     Io(#[from] std::io::Error),
 
+    /// Colors were requested for an ASCII STL file, which can't store them
+    #[error("colors can only be stored in binary STL files")]
+    AsciiStlColors,
+
     /// Maximum triangle count exceeded
     #[error("maximum triangle count exceeded")]
     InvalidTriangleCount,
 
-    /// Threemf error whilst exporting to 3MF file
-    #[error("threemf error whilst exporting to 3MF file")]
-    ThreeMF(#[from] threemf::Error),
+    /// Error writing ZIP archive whilst exporting to 3MF file
+    #[error("error writing ZIP archive whilst exporting to 3MF file")]
+    Zip(#[from] zip::result::ZipError),
+
+    /// Error serializing JSON whilst exporting to glTF file
+    #[error("error serializing JSON whilst exporting to glTF file")]
+    Json(#[from] serde_json::Error),
+
+    /// The exported data is too large for the file format
+    #[error("exported data is too large for the file format")]
+    FileTooLarge,
+
+    /// The model contains geometry that the file format can't represent
+    #[error("geometry not supported by the file format: {0}")]
+    UnsupportedGeometry(String),
+
+    /// The profiles couldn't be offset to compensate for the kerf
+    #[error("failed to compensate for kerf: {0}")]
+    Kerf(#[from] offset::ProfileOffsetError),
+
+    /// File type doesn't support multiple objects
+    #[error("file type `{0:?}` doesn't support exporting multiple objects")]
+    MultiObjectUnsupported(String),
+
+    /// A part is too large for the sheets it is nested onto
+    #[error("part `{0}` doesn't fit onto a sheet")]
+    PartTooLarge(String),
+
+    /// Error encoding PNG image whilst exporting thumbnail
+    #[error("error encoding PNG image whilst exporting thumbnail")]
+    Png(#[from] png::EncodingError),
 }
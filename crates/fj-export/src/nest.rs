@@ -0,0 +1,409 @@
+//! Nesting of flat parts onto sheets
+//!
+//! See [`nest`].
+
+use fj_math::{Aabb, Point, Scalar, Vector};
+
+use crate::{
+    profile::{Entity, Layer},
+    svg::extreme_points,
+    Error,
+};
+
+/// Options for nesting flat parts onto sheets
+///
+/// See [`crate::export_nested`].
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct NestOptions {
+    /// The width and height of a sheet
+    pub sheet: [Scalar; 2],
+
+    /// The minimum distance between parts, and between parts and the edges of
+    /// a sheet
+    pub spacing: Scalar,
+
+    /// Whether parts may be rotated by 90 degrees, to make better use of the
+    /// sheets
+    ///
+    /// Disable this for materials with a grain direction.
+    pub rotate: bool,
+}
+
+/// Arrange the layers of parts on sheets
+///
+/// Each layer is treated as a part and placed by its bounding box, using a
+/// shelf packing: Parts are sorted by height, then placed next to each other
+/// in rows (shelves), which are stacked on top of each other. A new sheet is
+/// started, once a part doesn't fit onto any of the existing ones.
+///
+/// This wastes the space within and around parts that aren't rectangular, but
+/// is predictable and fast. Returns the layers of each sheet, starting with a
+/// layer that contains the outline of the sheet.
+pub fn nest(
+    layers: Vec<Layer>,
+    options: &NestOptions,
+) -> Result<Vec<Vec<Layer>>, Error> {
+    let [sheet_width, sheet_height] = options.sheet;
+    let spacing = options.spacing;
+
+    let fits = |[width, height]: [Scalar; 2]| {
+        width + spacing * 2. <= sheet_width
+            && height + spacing * 2. <= sheet_height
+    };
+
+    let mut parts = Vec::new();
+    for layer in layers {
+        let points = layer
+            .entities
+            .iter()
+            .flat_map(extreme_points)
+            .collect::<Vec<_>>();
+        if points.is_empty() {
+            continue;
+        }
+
+        let aabb = Aabb::<2>::from_points(points);
+        let size = aabb.max - aabb.min;
+        let upright = [size.u, size.v];
+        let rotated = [size.v, size.u];
+
+        // Parts that lie flat make for lower shelves, which leaves less space
+        // unused above the parts that are lower than their shelf.
+        let rotate = if options.rotate
+            && fits(rotated)
+            && (!fits(upright) || size.v > size.u)
+        {
+            true
+        } else if fits(upright) {
+            false
+        } else {
+            return Err(Error::PartTooLarge(layer.name));
+        };
+
+        parts.push(NestedPart {
+            layer,
+            min: aabb.min,
+            size: if rotate { rotated } else { upright },
+            rotate,
+        });
+    }
+
+    parts.sort_by(|a, b| b.size[1].cmp(&a.size[1]));
+
+    let mut sheets: Vec<Sheet> = Vec::new();
+    for part in parts {
+        let [width, height] = part.size;
+
+        let placed = sheets.iter_mut().enumerate().find_map(|(i, sheet)| {
+            sheet
+                .place(width, height, options)
+                .map(|position| (i, position))
+        });
+        let (i, position) = match placed {
+            Some(placed) => placed,
+            None => {
+                let mut sheet = Sheet::new(spacing);
+                let position = sheet
+                    .place(width, height, options)
+                    .expect("Part fits onto an empty sheet");
+                sheets.push(sheet);
+                (sheets.len() - 1, position)
+            }
+        };
+
+        sheets[i].layers.push(part.place_at(position));
+    }
+
+    let sheets = sheets
+        .into_iter()
+        .map(|sheet| {
+            let outline = [[0., 0.], [1., 0.], [1., 1.], [0., 1.]]
+                .map(|[u, v]| Point::from([sheet_width * u, sheet_height * v]));
+
+            let mut layers = vec![Layer {
+                name: "sheet".into(),
+                entities: vec![Entity::Polyline {
+                    points: outline.to_vec(),
+                    closed: true,
+                }],
+            }];
+            layers.extend(sheet.layers);
+            layers
+        })
+        .collect();
+
+    Ok(sheets)
+}
+
+/// A part that is about to be placed on a sheet
+struct NestedPart {
+    layer: Layer,
+
+    /// The minimum of the part's bounding box, before rotating it
+    min: Point<2>,
+
+    /// The size of the part's bounding box, after rotating it
+    size: [Scalar; 2],
+
+    rotate: bool,
+}
+
+impl NestedPart {
+    /// Move the part, so the minimum of its bounding box ends up at `position`
+    fn place_at(self, position: Point<2>) -> Layer {
+        // Rotating turns the minimum of the bounding box into its lower-right
+        // corner. The width of the rotated bounding box is to the left of it.
+        let (rotation, min) = if self.rotate {
+            let min =
+                rotate(self.min) - Vector::from([self.size[0], Scalar::ZERO]);
+            (Scalar::from(90.), min)
+        } else {
+            (Scalar::ZERO, self.min)
+        };
+        let offset = position - min;
+
+        let transform = |point: Point<2>| {
+            let point = if self.rotate { rotate(point) } else { point };
+            point + offset
+        };
+
+        let entities = self
+            .layer
+            .entities
+            .into_iter()
+            .map(|entity| match entity {
+                Entity::Line(points) => Entity::Line(points.map(transform)),
+                Entity::Circle { center, radius } => Entity::Circle {
+                    center: transform(center),
+                    radius,
+                },
+                Entity::Arc {
+                    center,
+                    radius,
+                    angles,
+                } => Entity::Arc {
+                    center: transform(center),
+                    radius,
+                    angles: angles.map(|angle| angle + rotation),
+                },
+                Entity::Polyline { points, closed } => Entity::Polyline {
+                    points: points.into_iter().map(transform).collect(),
+                    closed,
+                },
+            })
+            .collect();
+
+        Layer {
+            name: self.layer.name,
+            entities,
+        }
+    }
+}
+
+/// Rotate a point by 90 degrees counter-clockwise, around the origin
+fn rotate(point: Point<2>) -> Point<2> {
+    Point::from([-point.v, point.u])
+}
+
+/// A sheet that parts are placed on
+struct Sheet {
+    shelves: Vec<Shelf>,
+
+    /// The lower edge of the next shelf
+    top: Scalar,
+
+    layers: Vec<Layer>,
+}
+
+impl Sheet {
+    fn new(spacing: Scalar) -> Self {
+        Self {
+            shelves: Vec::new(),
+            top: spacing,
+            layers: Vec::new(),
+        }
+    }
+
+    /// Find a place for a part, if there's room left on the sheet
+    ///
+    /// Returns the position of the lower-left corner of the part.
+    fn place(
+        &mut self,
+        width: Scalar,
+        height: Scalar,
+        options: &NestOptions,
+    ) -> Option<Point<2>> {
+        let [sheet_width, sheet_height] = options.sheet;
+        let spacing = options.spacing;
+
+        for shelf in &mut self.shelves {
+            if height <= shelf.height
+                && shelf.x + width + spacing <= sheet_width
+            {
+                let point = Point::from([shelf.x, shelf.y]);
+                shelf.x += width + spacing;
+                return Some(point);
+            }
+        }
+
+        if self.top + height + spacing > sheet_height
+            || spacing + width + spacing > sheet_width
+        {
+            return None;
+        }
+
+        let point = Point::from([spacing, self.top]);
+        self.shelves.push(Shelf {
+            x: spacing + width + spacing,
+            y: self.top,
+            height,
+        });
+        self.top += height + spacing;
+
+        Some(point)
+    }
+}
+
+/// A row of parts on a sheet
+struct Shelf {
+    /// The left edge of the next part
+    x: Scalar,
+
+    /// The lower edge of the shelf
+    y: Scalar,
+
+    height: Scalar,
+}
+
+#[cfg(test)]
+mod tests {
+    use fj_math::{Aabb, Point, Scalar};
+
+    use crate::{
+        profile::{Entity, Layer},
+        svg::extreme_points,
+        Error,
+    };
+
+    use super::{nest, NestOptions};
+
+    #[test]
+    fn parts_are_packed_onto_shelves() {
+        let options = NestOptions {
+            sheet: [Scalar::from(100.), Scalar::from(100.)],
+            spacing: Scalar::from(5.),
+            rotate: false,
+        };
+        let layers = vec![
+            rectangle("small", [50., 50.], [20., 10.]),
+            rectangle("medium", [-10., 0.], [40., 30.]),
+            rectangle("large", [3., 7.], [80., 60.]),
+        ];
+
+        let sheets = nest(layers, &options).unwrap();
+
+        // The large part leaves no room for the medium part on the first
+        // sheet, but the small part still fits below it.
+        assert_eq!(sheets.len(), 2);
+        assert_eq!(
+            placements(&sheets[0]),
+            [
+                ("sheet", [[0., 0.], [100., 100.]]),
+                ("large", [[5., 5.], [85., 65.]]),
+                ("small", [[5., 70.], [25., 80.]]),
+            ]
+        );
+        assert_eq!(
+            placements(&sheets[1]),
+            [
+                ("sheet", [[0., 0.], [100., 100.]]),
+                ("medium", [[5., 5.], [45., 35.]]),
+            ]
+        );
+    }
+
+    #[test]
+    fn tall_parts_are_laid_flat() {
+        let options = NestOptions {
+            sheet: [Scalar::from(100.), Scalar::from(100.)],
+            spacing: Scalar::from(2.),
+            rotate: true,
+        };
+        let mut layer = rectangle("tall", [10., 20.], [10., 40.]);
+        layer.entities.push(Entity::Arc {
+            center: Point::from([15., 40.]),
+            radius: Scalar::from(5.),
+            angles: [Scalar::ZERO, Scalar::from(90.)],
+        });
+
+        let sheets = nest(vec![layer], &options).unwrap();
+
+        assert_eq!(
+            placements(&sheets[0]),
+            [
+                ("sheet", [[0., 0.], [100., 100.]]),
+                ("tall", [[2., 2.], [42., 12.]]),
+            ]
+        );
+
+        // The arc is rotated along with the rest of the part.
+        let Entity::Arc { center, angles, .. } = &sheets[0][1].entities[1]
+        else {
+            panic!("Expected arc");
+        };
+        assert_eq!(*center, Point::from([22., 7.]));
+        assert_eq!(*angles, [Scalar::from(90.), Scalar::from(180.)]);
+    }
+
+    #[test]
+    fn parts_that_dont_fit_are_rejected() {
+        let options = NestOptions {
+            sheet: [Scalar::from(100.), Scalar::from(100.)],
+            spacing: Scalar::from(5.),
+            rotate: true,
+        };
+        let layers = vec![rectangle("too-large", [0., 0.], [120., 10.])];
+
+        let result = nest(layers, &options);
+
+        assert!(matches!(
+            result,
+            Err(Error::PartTooLarge(name)) if name == "too-large"
+        ));
+    }
+
+    fn rectangle(name: &str, min: [f64; 2], size: [f64; 2]) -> Layer {
+        let [x, y] = min;
+        let [width, height] = size;
+
+        Layer {
+            name: name.into(),
+            entities: vec![Entity::Polyline {
+                points: [
+                    [x, y],
+                    [x + width, y],
+                    [x + width, y + height],
+                    [x, y + height],
+                ]
+                .map(Point::from)
+                .to_vec(),
+                closed: true,
+            }],
+        }
+    }
+
+    fn placements(layers: &[Layer]) -> Vec<(&str, [[f64; 2]; 2])> {
+        layers
+            .iter()
+            .map(|layer| {
+                let aabb = Aabb::<2>::from_points(
+                    layer.entities.iter().flat_map(extreme_points),
+                );
+                let [min, max] = [aabb.min, aabb.max]
+                    .map(|point| [point.u.into_f64(), point.v.into_f64()]);
+
+                (layer.name.as_str(), [min, max])
+            })
+            .collect()
+    }
+}
@@ -0,0 +1,109 @@
+//! Vertex normals for exported meshes
+//!
+//! Triangles that were triangulated from a shape carry the normals of the
+//! surfaces they approximate. For other triangles, those that meet at a shallow
+//! angle share their normals, to make curved surfaces appear smooth. Sharp
+//! edges stay sharp in both cases.
+
+use std::collections::HashMap;
+
+use fj_interop::mesh::{Color, Triangle};
+use fj_math::{Point, Scalar, Vector};
+
+use crate::Part;
+
+/// Triangles whose normals deviate by more than this angle (in degrees) from
+/// each other, form a sharp edge
+pub const CREASE_ANGLE: f64 = 40.;
+
+/// A vertex of an exported mesh
+///
+/// Unlike the vertices of the source mesh, which are identified by their
+/// position alone, triangles can only share a vertex, if they also share its
+/// normal and color.
+#[derive(Clone, Copy, Eq, PartialEq, Hash)]
+pub struct Vertex {
+    pub position: Point<3>,
+    pub normal: Vector<3>,
+    pub color: Color,
+}
+
+/// Compute the vertices and indices of a part's mesh
+pub fn vertices(part: &Part) -> (Vec<Vertex>, Vec<u32>) {
+    let triangles = part.mesh.triangles().collect::<Vec<_>>();
+    let normals = triangles
+        .iter()
+        .map(|triangle| triangle.inner.normal())
+        .collect::<Vec<_>>();
+
+    let mut triangles_at_point: HashMap<Point<3>, Vec<usize>> = HashMap::new();
+    for (i, triangle) in triangles.iter().enumerate() {
+        for point in triangle.inner.points() {
+            triangles_at_point.entry(point).or_default().push(i);
+        }
+    }
+
+    let min_cos = Scalar::from_f64(CREASE_ANGLE.to_radians().cos());
+
+    let mut vertices = Vec::new();
+    let mut indices = Vec::new();
+    let mut index_by_vertex = HashMap::new();
+
+    for (i, triangle) in triangles.iter().enumerate() {
+        for (corner, point) in triangle.inner.points().into_iter().enumerate() {
+            let normal = match triangle.normals {
+                Some(surface_normals) => surface_normals[corner],
+                None => {
+                    // Average the normals of all triangles at this point, that
+                    // don't form a sharp edge with this one. Weighing them by
+                    // the angle of their corners keeps the result independent
+                    // of how the surrounding surface is split into triangles.
+                    let normal = triangles_at_point[&point]
+                        .iter()
+                        .filter(|&&j| normals[i].dot(&normals[j]) >= min_cos)
+                        .map(|&j| {
+                            normals[j] * corner_angle(&triangles[j], point)
+                        })
+                        .fold(Vector::from([0., 0., 0.]), |sum, normal| {
+                            sum + normal
+                        });
+
+                    if normal.magnitude() > Scalar::ZERO {
+                        normal.normalize()
+                    } else {
+                        normals[i]
+                    }
+                }
+            };
+
+            let vertex = Vertex {
+                position: point,
+                normal,
+                color: triangle.color,
+            };
+            let index = *index_by_vertex.entry(vertex).or_insert_with(|| {
+                vertices.push(vertex);
+                vertices.len() as u32 - 1
+            });
+            indices.push(index);
+        }
+    }
+
+    (vertices, indices)
+}
+
+/// Compute the angle of a triangle's corner at the given point
+fn corner_angle(triangle: &Triangle, point: Point<3>) -> Scalar {
+    let [a, b, c] = triangle.inner.points();
+    let [others_a, others_b] = if point == a {
+        [b, c]
+    } else if point == b {
+        [c, a]
+    } else {
+        [a, b]
+    };
+
+    let u = (others_a - point).normalize();
+    let v = (others_b - point).normalize();
+    Scalar::from_f64(u.dot(&v).into_f64().clamp(-1., 1.).acos())
+}
@@ -0,0 +1,163 @@
+//! Export to Wavefront OBJ
+//!
+//! Each part becomes a named object. Vertex positions are shared between all
+//! triangles that meet there, normals only between triangles that form a smooth
+//! surface. OBJ has no standard way to store colors, so those are left out.
+
+use std::{
+    collections::HashMap,
+    fs::File,
+    io::{self, BufWriter, Write},
+    path::Path,
+};
+
+use fj_math::Vector;
+
+use crate::{normals::vertices, Error, Part};
+
+/// Write the parts to an OBJ file
+pub fn write(parts: &[Part], path: &Path) -> Result<(), Error> {
+    let mut file = BufWriter::new(File::create(path)?);
+
+    writeln!(file, "# Exported by Fornjot")?;
+
+    // Indices continue across objects, so positions and normals can be shared
+    // between them.
+    let mut positions = Indices::new("v");
+    let mut normals = Indices::new("vn");
+
+    for part in parts {
+        // Object names end at the first whitespace, as far as most readers
+        // are concerned.
+        let name = part.name.split_whitespace().collect::<Vec<_>>().join("_");
+        writeln!(file, "o {name}")?;
+
+        let (vertices, indices) = vertices(part);
+
+        for triangle in indices.chunks(3) {
+            let mut face = Vec::new();
+
+            for &index in triangle {
+                let vertex = vertices[index as usize];
+
+                let position =
+                    positions.get(vertex.position.coords, &mut file)?;
+                let normal = normals.get(vertex.normal, &mut file)?;
+
+                face.push(format!("{position}//{normal}"));
+            }
+
+            writeln!(file, "f {}", face.join(" "))?;
+        }
+    }
+
+    file.flush()?;
+
+    Ok(())
+}
+
+/// Assigns indices to vectors, writing each vector when it first comes up
+struct Indices {
+    keyword: &'static str,
+    indices: HashMap<Vector<3>, usize>,
+}
+
+impl Indices {
+    fn new(keyword: &'static str) -> Self {
+        Self {
+            keyword,
+            indices: HashMap::new(),
+        }
+    }
+
+    fn get(
+        &mut self,
+        vector: Vector<3>,
+        file: &mut impl Write,
+    ) -> io::Result<usize> {
+        if let Some(index) = self.indices.get(&vector) {
+            return Ok(*index);
+        }
+
+        let [x, y, z] = vector.components.map(|s| s.into_f64());
+        writeln!(file, "{} {x} {y} {z}", self.keyword)?;
+
+        // Indices in OBJ files start at 1.
+        let index = self.indices.len() + 1;
+        self.indices.insert(vector, index);
+
+        Ok(index)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use fj_interop::{
+        mesh::{Color, Mesh},
+        units::Units,
+    };
+
+    use crate::Part;
+
+    #[test]
+    fn share_positions_and_normals() {
+        let mut quad = Mesh::new();
+        quad.push_triangle(
+            [[0., 0., 0.], [1., 0., 0.], [1., 1., 0.]],
+            Color::default(),
+        );
+        quad.push_triangle(
+            [[0., 0., 0.], [1., 1., 0.], [0., 1., 0.]],
+            Color::default(),
+        );
+
+        let mut triangle = Mesh::new();
+        triangle.push_triangle(
+            [[1., 0., 0.], [1., 1., 0.], [1., 0., 1.]],
+            Color::default(),
+        );
+
+        let path = std::env::temp_dir()
+            .join(format!("fj-export-obj-test-{}.obj", std::process::id()));
+        super::write(
+            &[
+                Part {
+                    name: "the quad",
+                    mesh: &quad,
+                    units: Units::Millimeters,
+                },
+                Part {
+                    name: "triangle",
+                    mesh: &triangle,
+                    units: Units::Millimeters,
+                },
+            ],
+            &path,
+        )
+        .unwrap();
+        let source = fs::read_to_string(&path).unwrap();
+        fs::remove_file(&path).unwrap();
+
+        let lines = |keyword: &str| {
+            source
+                .lines()
+                .filter(|line| line.split(' ').next() == Some(keyword))
+                .collect::<Vec<_>>()
+        };
+
+        // The parts share the positions on the edge where they meet, but not
+        // the normals there, as they form a sharp edge.
+        assert_eq!(lines("v").len(), 5);
+        assert_eq!(lines("vn"), ["vn 0 0 1", "vn 1 0 0"]);
+        assert_eq!(lines("o"), ["o the_quad", "o triangle"]);
+        assert_eq!(
+            lines("f"),
+            ["f 1//1 2//1 3//1", "f 1//1 3//1 4//1", "f 2//2 3//2 5//2"]
+        );
+
+        let imported = fj_import::import_obj(&source).unwrap();
+        assert_eq!(imported.triangles().count(), 3);
+    }
+}
@@ -0,0 +1,139 @@
+//! Export to binary PLY
+//!
+//! Vertices come with normals and colors. Triangles only share a vertex, if
+//! they agree on both, so each face keeps its own color.
+
+use std::{
+    fs::File,
+    io::{BufWriter, Write},
+    path::Path,
+};
+
+use crate::{normals::vertices, Error, Part};
+
+/// Write the part to a binary PLY file
+pub fn write(part: Part, path: &Path) -> Result<(), Error> {
+    let (vertices, indices) = vertices(&part);
+
+    let mut file = BufWriter::new(File::create(path)?);
+
+    writeln!(file, "ply")?;
+    writeln!(file, "format binary_little_endian 1.0")?;
+    writeln!(file, "comment Exported by Fornjot")?;
+    writeln!(file, "element vertex {}", vertices.len())?;
+    for property in ["x", "y", "z", "nx", "ny", "nz"] {
+        writeln!(file, "property float {property}")?;
+    }
+    for property in ["red", "green", "blue", "alpha"] {
+        writeln!(file, "property uchar {property}")?;
+    }
+    writeln!(file, "element face {}", indices.len() / 3)?;
+    writeln!(file, "property list uchar uint vertex_indices")?;
+    writeln!(file, "end_header")?;
+
+    for vertex in &vertices {
+        let components = vertex
+            .position
+            .coords
+            .components
+            .into_iter()
+            .chain(vertex.normal.components);
+        for component in components {
+            file.write_all(&component.into_f32().to_le_bytes())?;
+        }
+
+        file.write_all(&vertex.color.0)?;
+    }
+
+    for triangle in indices.chunks(3) {
+        file.write_all(&[3])?;
+        for index in triangle {
+            file.write_all(&index.to_le_bytes())?;
+        }
+    }
+
+    file.flush()?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use fj_interop::{
+        mesh::{Color, Mesh},
+        units::Units,
+    };
+
+    use crate::Part;
+
+    #[test]
+    fn write_vertices_and_faces() {
+        let red = Color([255, 0, 0, 255]);
+        let blue = Color([0, 0, 255, 255]);
+
+        let mut mesh = Mesh::new();
+        mesh.push_triangle([[0., 0., 0.], [1., 0., 0.], [1., 1., 0.]], red);
+        mesh.push_triangle([[0., 0., 0.], [1., 1., 0.], [0., 1., 0.]], red);
+        mesh.push_triangle([[1., 0., 0.], [2., 0., 0.], [1., 1., 0.]], blue);
+
+        let path = std::env::temp_dir()
+            .join(format!("fj-export-ply-test-{}.ply", std::process::id()));
+        super::write(
+            Part {
+                name: "model",
+                mesh: &mesh,
+                units: Units::Millimeters,
+            },
+            &path,
+        )
+        .unwrap();
+        let bytes = fs::read(&path).unwrap();
+        fs::remove_file(&path).unwrap();
+
+        let end_of_header = b"end_header\n";
+        let header_len = bytes
+            .windows(end_of_header.len())
+            .position(|window| window == end_of_header)
+            .unwrap()
+            + end_of_header.len();
+        let header = String::from_utf8(bytes[..header_len].to_vec()).unwrap();
+        let body = &bytes[header_len..];
+
+        // Triangles of the same color share their vertices. Those of different
+        // colors don't, even where their positions are the same.
+        assert!(header.contains("element vertex 7\n"));
+        assert!(header.contains("element face 3\n"));
+
+        let vertex_len = 6 * 4 + 4;
+        let face_len = 1 + 3 * 4;
+        assert_eq!(body.len(), 7 * vertex_len + 3 * face_len);
+
+        let float = |offset: usize| {
+            f32::from_le_bytes(body[offset..offset + 4].try_into().unwrap())
+        };
+        let vertex = |i: usize| {
+            let offset = i * vertex_len;
+            let position = [0, 1, 2].map(|j| float(offset + j * 4));
+            let normal = [3, 4, 5].map(|j| float(offset + j * 4));
+            let color = &body[offset + 24..offset + 28];
+            (position, normal, color.to_vec())
+        };
+        assert_eq!(vertex(0), ([0., 0., 0.], [0., 0., 1.], red.0.to_vec()));
+        assert_eq!(vertex(4), ([1., 0., 0.], [0., 0., 1.], blue.0.to_vec()));
+
+        let faces = body[7 * vertex_len..]
+            .chunks(face_len)
+            .map(|face| {
+                assert_eq!(face[0], 3);
+                [1, 5, 9].map(|offset| {
+                    u32::from_le_bytes(
+                        face[offset..offset + 4].try_into().unwrap(),
+                    )
+                })
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(faces, [[0, 1, 2], [0, 2, 3], [4, 5, 6]]);
+    }
+}
@@ -0,0 +1,446 @@
+//! Two-dimensional profiles, as written by the 2D exporters
+//!
+//! Profiles are made up of lines, circles, circular arcs, and polylines, which
+//! approximate all other curves. They are derived from sketches, from sections
+//! of parts, or from views of parts (see [`crate::view`]).
+
+use std::collections::BTreeMap;
+
+use fj_interop::mesh::Mesh;
+use fj_kernel::{
+    algorithms::{
+        approx::{Approx, Tolerance},
+        offset::offset_profiles,
+        triangulate::Triangulate,
+    },
+    geometry::{
+        path::{GlobalPath, SurfacePath},
+        surface::SurfaceGeometry,
+    },
+    objects::{FaceSet, HalfEdge, Sketch},
+    services::Services,
+};
+use fj_math::{Plane, Point, Scalar, Vector};
+
+use crate::{BrepPart, Error};
+
+/// The relative tolerance used when classifying geometry
+///
+/// See the constant of the same name in the STEP exporter.
+const EPSILON: f64 = 1e-9;
+
+/// A named group of entities
+pub struct Layer {
+    pub name: String,
+    pub entities: Vec<Entity>,
+}
+
+/// Compute the profile of a sketch
+///
+/// Coordinates are relative to the surface of the sketch's first face, which
+/// must be a plane.
+pub fn sketch(
+    sketch: &Sketch,
+    tolerance: Tolerance,
+) -> Result<Vec<Layer>, Error> {
+    let Some(frame) = sketch
+        .faces()
+        .into_iter()
+        .next()
+        .map(|face| Frame::from_surface(&face.surface().geometry()))
+    else {
+        return Ok(Vec::new());
+    };
+    let frame = frame.ok_or_else(|| {
+        Error::UnsupportedGeometry(
+            "2D export requires a sketch that is defined on a plane".into(),
+        )
+    })?;
+
+    let entities = sketch
+        .faces()
+        .into_iter()
+        .flat_map(|face| face.all_cycles())
+        .flat_map(|cycle| cycle.half_edges())
+        .map(|half_edge| edge(half_edge, &frame, tolerance))
+        .collect();
+
+    Ok(vec![Layer {
+        name: "0".into(),
+        entities,
+    }])
+}
+
+/// Compute the section of the parts with a plane
+///
+/// Faces that lie within the plane keep their exact geometry, after offsetting
+/// their profiles by the kerf. Where faces cross the plane, the section is
+/// computed from their triangle mesh and approximated by polylines. Each part
+/// ends up in its own layer.
+pub fn section(
+    parts: &[BrepPart],
+    plane: &Plane,
+    kerf: Scalar,
+    tolerance: Tolerance,
+) -> Result<Vec<Layer>, Error> {
+    let frame =
+        Frame::new(plane.origin(), plane.u(), plane.v()).ok_or_else(|| {
+            Error::UnsupportedGeometry("degenerate section plane".into())
+        })?;
+
+    let mut layers = Vec::new();
+    for part in parts {
+        let mut profiles = part
+            .faces
+            .into_iter()
+            .filter(|face| {
+                frame.contains_surface(&face.surface().geometry(), tolerance)
+            })
+            .cloned()
+            .collect::<FaceSet>();
+        if kerf != Scalar::ZERO {
+            let mut services = Services::new();
+            profiles = offset_profiles(
+                &profiles,
+                kerf,
+                tolerance,
+                &mut services.objects,
+            )?;
+        }
+
+        let mut entities = profiles
+            .into_iter()
+            .flat_map(|face| {
+                face.all_cycles()
+                    .flat_map(|cycle| cycle.half_edges())
+                    .map(|half_edge| edge(half_edge, &frame, tolerance))
+                    .collect::<Vec<_>>()
+            })
+            .collect::<Vec<_>>();
+
+        let mesh = (part.faces, tolerance).triangulate();
+        let sections = slice(&mesh, &frame, tolerance);
+        if kerf != Scalar::ZERO && !sections.is_empty() {
+            return Err(Error::UnsupportedGeometry(
+                "kerf compensation requires faces that lie within the \
+                section plane"
+                    .into(),
+            ));
+        }
+        entities.extend(sections);
+
+        layers.push(Layer {
+            name: layer_name(part.name),
+            entities,
+        });
+    }
+
+    Ok(layers)
+}
+
+/// An orthonormal coordinate frame in a plane
+pub struct Frame {
+    origin: Point<3>,
+    x: Vector<3>,
+    y: Vector<3>,
+    normal: Vector<3>,
+}
+
+impl Frame {
+    /// Create a frame in the plane that is spanned by the two vectors
+    ///
+    /// Returns `None`, if the vectors don't span a plane.
+    pub fn new(origin: Point<3>, u: Vector<3>, v: Vector<3>) -> Option<Self> {
+        let normal = u.cross(&v);
+        if u.magnitude() == Scalar::ZERO
+            || normal.magnitude() <= u.magnitude() * v.magnitude() * EPSILON
+        {
+            return None;
+        }
+
+        let x = u.normalize();
+        let normal = normal.normalize();
+        let y = normal.cross(&x);
+
+        Some(Self {
+            origin,
+            x,
+            y,
+            normal,
+        })
+    }
+
+    /// Create a frame in a surface
+    ///
+    /// Returns `None`, if the surface is not a plane.
+    fn from_surface(surface: &SurfaceGeometry) -> Option<Self> {
+        let SurfaceGeometry::Swept {
+            u: GlobalPath::Line(line),
+            v,
+        } = surface
+        else {
+            return None;
+        };
+
+        Self::new(line.origin(), line.direction(), *v)
+    }
+
+    /// Determine whether the surface lies within the plane of the frame
+    fn contains_surface(
+        &self,
+        surface: &SurfaceGeometry,
+        tolerance: Tolerance,
+    ) -> bool {
+        let Some(other) = Self::from_surface(surface) else {
+            return false;
+        };
+
+        self.distance(other.origin).abs() <= tolerance.inner()
+            && other.normal.cross(&self.normal).magnitude()
+                <= Scalar::from(EPSILON)
+    }
+
+    /// Convert a point into the coordinates of the frame
+    pub fn point(&self, point: Point<3>) -> Point<2> {
+        let vector = point - self.origin;
+        Point::from([vector.dot(&self.x), vector.dot(&self.y)])
+    }
+
+    fn vector(&self, vector: Vector<3>) -> Vector<2> {
+        Vector::from([vector.dot(&self.x), vector.dot(&self.y)])
+    }
+
+    /// Compute the signed distance of a point from the plane of the frame
+    pub fn distance(&self, point: Point<3>) -> Scalar {
+        (point - self.origin).dot(&self.normal)
+    }
+}
+
+/// A two-dimensional geometric entity
+pub enum Entity {
+    Line([Point<2>; 2]),
+    Circle {
+        center: Point<2>,
+        radius: Scalar,
+    },
+    /// An arc that runs counter-clockwise between the angles, in degrees
+    Arc {
+        center: Point<2>,
+        radius: Scalar,
+        angles: [Scalar; 2],
+    },
+    Polyline {
+        points: Vec<Point<2>>,
+        closed: bool,
+    },
+}
+
+/// Convert a half-edge into an entity in the frame
+fn edge(half_edge: &HalfEdge, frame: &Frame, tolerance: Tolerance) -> Entity {
+    let surface = half_edge.surface().geometry();
+    let [back, front] = half_edge
+        .vertices()
+        .each_ref()
+        .map(|vertex| frame.point(vertex.global_form().position()));
+
+    if let SurfaceGeometry::Swept {
+        u: GlobalPath::Line(_),
+        ..
+    } = surface
+    {
+        match half_edge.curve().path() {
+            SurfacePath::Line(_) => return Entity::Line([back, front]),
+            SurfacePath::Circle(circle) => {
+                let center = frame
+                    .point(surface.point_from_surface_coords(circle.center()));
+                let [a, b] = [circle.a(), circle.b()].map(|axis| {
+                    frame.vector(surface.vector_from_surface_coords(axis))
+                });
+
+                // A circle that isn't parallel to the frame appears as an
+                // ellipse, which is approximated below.
+                let radius = a.magnitude();
+                let is_circle = (radius - b.magnitude()).abs()
+                    <= radius * EPSILON
+                    && a.dot(&b).abs() <= radius * radius * EPSILON;
+
+                if is_circle {
+                    let [t_back, t_front] = half_edge
+                        .vertices()
+                        .each_ref()
+                        .map(|vertex| vertex.position().t);
+
+                    if (t_front - t_back).abs() >= Scalar::TAU * (1. - EPSILON)
+                    {
+                        return Entity::Circle { center, radius };
+                    }
+
+                    let is_counter_clockwise =
+                        (a.cross2d(&b) > Scalar::ZERO) == (t_front > t_back);
+                    let [start, end] = if is_counter_clockwise {
+                        [back, front]
+                    } else {
+                        [front, back]
+                    };
+
+                    return Entity::Arc {
+                        center,
+                        radius,
+                        angles: [start, end].map(|point| angle(point - center)),
+                    };
+                }
+            }
+            _ => {}
+        }
+    }
+
+    let mut points = half_edge
+        .approx(tolerance)
+        .points()
+        .into_iter()
+        .map(|point| frame.point(point.global_form))
+        .collect::<Vec<_>>();
+    points.push(front);
+
+    Entity::Polyline {
+        points,
+        closed: false,
+    }
+}
+
+/// Compute the angle of a vector, in degrees between 0 and 360
+fn angle(vector: Vector<2>) -> Scalar {
+    let angle = vector.v.atan2(vector.u).into_f64().to_degrees();
+    Scalar::from(angle.rem_euclid(360.))
+}
+
+/// Compute the section of a triangle mesh with the plane of the frame
+///
+/// Triangles that only touch the plane with one of their edges don't
+/// contribute to the section. Such edges are either bounded by faces that lie
+/// within the plane, which are written with their exact geometry, or they only
+/// touch the plane without crossing it.
+fn slice(
+    mesh: &Mesh<Point<3>>,
+    frame: &Frame,
+    tolerance: Tolerance,
+) -> Vec<Entity> {
+    // Points that are closer to the plane, or to each other, than this are
+    // considered to be on the plane, or the same point.
+    let resolution = tolerance.inner() / 1000.;
+
+    let mut segments = Vec::new();
+    for triangle in mesh.triangles() {
+        let points = triangle.inner.points();
+        let distances = points.map(|point| frame.distance(point));
+        let sides = distances.map(|distance| {
+            if distance > resolution {
+                1
+            } else if distance < -resolution {
+                -1
+            } else {
+                0
+            }
+        });
+
+        if sides.iter().filter(|&&side| side == 0).count() > 1 {
+            continue;
+        }
+
+        let mut section = Vec::new();
+        for i in 0..3 {
+            let j = (i + 1) % 3;
+
+            if sides[i] == 0 {
+                section.push(frame.point(points[i]));
+            }
+            if sides[i] * sides[j] < 0 {
+                // Always interpolate in the same direction, so the triangles
+                // on both sides of an edge end up with the same point.
+                let [above, below] = if sides[i] > 0 { [i, j] } else { [j, i] };
+                let t =
+                    distances[above] / (distances[above] - distances[below]);
+                let point = points[above] + (points[below] - points[above]) * t;
+
+                section.push(frame.point(point));
+            }
+        }
+
+        if let [a, b] = section[..] {
+            segments.push([a, b]);
+        }
+    }
+
+    chain(segments, resolution)
+}
+
+/// Connect line segments to polylines
+///
+/// Segments are connected where their end points are closer to each other
+/// than the resolution.
+pub fn chain(segments: Vec<[Point<2>; 2]>, resolution: Scalar) -> Vec<Entity> {
+    let key = |point: Point<2>| {
+        point
+            .coords
+            .components
+            .map(|coord| (coord / resolution).round().into_f64() as i64)
+    };
+
+    let mut segments_by_point: BTreeMap<_, Vec<_>> = BTreeMap::new();
+    for (i, segment) in segments.iter().enumerate() {
+        for &point in segment {
+            segments_by_point.entry(key(point)).or_default().push(i);
+        }
+    }
+
+    let mut used = vec![false; segments.len()];
+    let mut polylines = Vec::new();
+    for i in 0..segments.len() {
+        if used[i] {
+            continue;
+        }
+        used[i] = true;
+
+        let mut points = segments[i].to_vec();
+        for _ in 0..2 {
+            while let Some(&last) = points.last() {
+                let next = segments_by_point[&key(last)]
+                    .iter()
+                    .copied()
+                    .find(|&j| !used[j]);
+                let Some(next) = next else {
+                    break;
+                };
+                used[next] = true;
+
+                let [a, b] = segments[next];
+                points.push(if key(a) == key(last) { b } else { a });
+            }
+
+            points.reverse();
+        }
+
+        let closed =
+            points.len() > 3 && key(points[0]) == key(points[points.len() - 1]);
+        if closed {
+            points.pop();
+        }
+
+        polylines.push(Entity::Polyline { points, closed });
+    }
+
+    polylines
+}
+
+/// Make a part name safe for use as a layer name
+pub fn layer_name(name: &str) -> String {
+    name.chars()
+        .map(|c| {
+            if c.is_alphanumeric() || c == '-' || c == '_' {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect()
+}
@@ -0,0 +1,111 @@
+//! Export of the layers of a sliced model
+//!
+//! All layers are written to a single JSON file, or each layer to its own SVG
+//! file. Contours are closed polygons in both cases.
+
+use std::{
+    fs::File,
+    io::{BufWriter, Write as _},
+    path::{Path, PathBuf},
+};
+
+use fj_kernel::algorithms::slice::Layer;
+use serde_json::json;
+
+use crate::{
+    profile::{self, Entity},
+    svg, Error,
+};
+
+/// Write all layers to a JSON file
+///
+/// The file contains an object with a `layers` array. Each layer is an object
+/// with its height `z`, and its `contours`, each of which is an array of
+/// `[x, y]` points.
+pub fn write_json(layers: &[Layer], path: &Path) -> Result<(), Error> {
+    let layers = layers
+        .iter()
+        .map(|layer| {
+            let contours = layer
+                .contours
+                .iter()
+                .map(|contour| {
+                    contour
+                        .iter()
+                        .map(|point| [point.u.into_f64(), point.v.into_f64()])
+                        .collect::<Vec<_>>()
+                })
+                .collect::<Vec<_>>();
+
+            json!({
+                "z": layer.z.into_f64(),
+                "contours": contours,
+            })
+        })
+        .collect::<Vec<_>>();
+
+    let mut file = BufWriter::new(File::create(path)?);
+    serde_json::to_writer(&mut file, &json!({ "layers": layers }))?;
+    file.flush()?;
+
+    Ok(())
+}
+
+/// Write each layer to its own SVG file
+///
+/// Returns the paths of all files that have been written.
+pub fn write_svg(layers: &[Layer], path: &Path) -> Result<Vec<PathBuf>, Error> {
+    let mut paths = Vec::new();
+
+    for (i, layer) in layers.iter().enumerate() {
+        let path = layer_path(path, i + 1);
+
+        let entities = layer
+            .contours
+            .iter()
+            .map(|contour| Entity::Polyline {
+                points: contour.clone(),
+                closed: true,
+            })
+            .collect();
+        let layer = profile::Layer {
+            name: format!("layer-{}", i + 1),
+            entities,
+        };
+
+        svg::write(&[layer], &path)?;
+        paths.push(path);
+    }
+
+    Ok(paths)
+}
+
+/// Compute the path of the file that a layer (or sheet) is written to
+///
+/// `{index}` in the file name is replaced with the 1-based index of the layer.
+/// If the file name doesn't contain it, `-{index}` is inserted before the
+/// extension.
+pub fn layer_path(path: &Path, index: usize) -> PathBuf {
+    let file_name = path
+        .file_name()
+        .map(|file_name| file_name.to_string_lossy().into_owned())
+        .unwrap_or_default();
+
+    let file_name = if file_name.contains("{index}") {
+        file_name.replace("{index}", &index.to_string())
+    } else {
+        let stem = path
+            .file_stem()
+            .map(|stem| stem.to_string_lossy().into_owned())
+            .unwrap_or_default();
+
+        match path.extension() {
+            Some(extension) => {
+                format!("{stem}-{index}.{}", extension.to_string_lossy())
+            }
+            None => format!("{stem}-{index}"),
+        }
+    };
+
+    path.with_file_name(file_name)
+}
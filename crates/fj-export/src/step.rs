@@ -0,0 +1,1177 @@
+//! Export to STEP
+//!
+//! Writes the boundary representation of the model, using the AP214 schema.
+//! Unlike the other formats, this preserves the exact geometry of the model,
+//! which makes the files suitable for use in other CAD packages.
+//!
+//! Each connected set of faces becomes a `MANIFOLD_SOLID_BREP`, named after
+//! the part it belongs to. All of them are placed in a single product.
+
+use std::{
+    collections::HashMap,
+    f64::consts::PI,
+    fmt::{self, Write as _},
+    fs,
+    path::Path,
+    time::SystemTime,
+};
+
+use fj_interop::units::Units;
+use fj_kernel::{
+    algorithms::transform::TransformObject,
+    geometry::{
+        path::{GlobalPath, SurfacePath},
+        surface::SurfaceGeometry,
+    },
+    objects::{
+        Face, FaceSet, GlobalVertex, HalfEdge, Handedness as FaceHandedness,
+        Surface, VerticesInNormalizedOrder,
+    },
+    services::Services,
+    storage::Handle,
+};
+use fj_math::{Nurbs, NurbsSurface, Point, Scalar, Vector};
+
+use crate::{CoordinateSystem, Error, Handedness};
+
+/// The relative tolerance used when classifying geometry
+///
+/// Mapping geometry from surface coordinates into 3D introduces small
+/// numerical errors. Those must not prevent a circle from being recognized as
+/// such.
+const EPSILON: f64 = 1e-9;
+
+/// A part of a model, given by its boundary representation
+#[derive(Clone, Copy, Debug)]
+pub struct BrepPart<'r> {
+    /// The name of the part
+    pub name: &'r str,
+
+    /// The faces that bound the part
+    pub faces: &'r FaceSet,
+
+    /// The units that the lengths of the part are given in
+    pub units: Units,
+}
+
+/// Write the parts to a STEP file
+///
+/// Parts that aren't given in `units` are scaled accordingly.
+pub fn write(
+    parts: &[BrepPart],
+    path: &Path,
+    coordinate_system: CoordinateSystem,
+    units: Units,
+) -> Result<(), Error> {
+    let name = path
+        .file_stem()
+        .map(|stem| stem.to_string_lossy().into_owned())
+        .unwrap_or_default();
+
+    let mut writer = Writer::new(coordinate_system, units);
+
+    let mut services = Services::new();
+    let mut items = Vec::new();
+    for part in parts {
+        let scaled;
+        let faces = if part.units == units {
+            part.faces
+        } else {
+            scaled = part
+                .faces
+                .clone()
+                .scale(part.units.factor(units), &mut services.objects);
+            &scaled
+        };
+        let faces = faces.into_iter().collect::<Vec<_>>();
+
+        for shell in connected_shells(&faces, &mut writer.edge_ids) {
+            items.push(writer.solid(part.name, &shell)?);
+        }
+    }
+
+    let context = writer.context();
+    let origin =
+        writer.placement(Point::origin(), Vector::unit_z(), Vector::unit_x());
+    items.push(origin);
+
+    let representation = writer.add(format!(
+        "ADVANCED_BREP_SHAPE_REPRESENTATION({},{},{context})",
+        string(&name),
+        list(items),
+    ));
+    let product_shape = writer.product(&name);
+    writer.add(format!(
+        "SHAPE_DEFINITION_REPRESENTATION({product_shape},{representation})"
+    ));
+
+    fs::write(path, writer.finish(&name))?;
+
+    Ok(())
+}
+
+/// Group faces into shells, which are connected through shared edges
+///
+/// Each of those shells needs to be closed. The faces of the kernel don't know
+/// which solid they belong to, so this is the only way to recover that.
+fn connected_shells<'r>(
+    faces: &[&'r Handle<Face>],
+    edges: &mut Edges,
+) -> Vec<Vec<&'r Face>> {
+    let mut faces_by_edge = HashMap::<usize, Vec<usize>>::new();
+    for (i, face) in faces.iter().enumerate() {
+        for half_edge in half_edges(face) {
+            faces_by_edge
+                .entry(edges.identify(half_edge))
+                .or_default()
+                .push(i);
+        }
+    }
+
+    let mut visited = vec![false; faces.len()];
+    let mut shells = Vec::new();
+
+    for start in 0..faces.len() {
+        if visited[start] {
+            continue;
+        }
+        visited[start] = true;
+
+        let mut shell = Vec::new();
+        let mut queue = vec![start];
+
+        while let Some(i) = queue.pop() {
+            shell.push(&**faces[i]);
+
+            for half_edge in half_edges(faces[i]) {
+                for &j in &faces_by_edge[&edges.identify(half_edge)] {
+                    if !visited[j] {
+                        visited[j] = true;
+                        queue.push(j);
+                    }
+                }
+            }
+        }
+
+        shells.push(shell);
+    }
+
+    shells
+}
+
+/// Identifies the edges that half-edges belong to
+///
+/// The kernel doesn't reliably share [`GlobalEdge`]s between the faces that
+/// meet at an edge. Edges are identified by their vertices and their midpoint
+/// instead. The midpoint distinguishes edges that share both vertices, like
+/// the two halves of a circle.
+///
+/// [`GlobalEdge`]: fj_kernel::objects::GlobalEdge
+#[derive(Default)]
+struct Edges {
+    by_vertices: HashMap<[Handle<GlobalVertex>; 2], Vec<(Point<3>, usize)>>,
+    next_id: usize,
+}
+
+impl Edges {
+    fn identify(&mut self, half_edge: &HalfEdge) -> usize {
+        let vertices = half_edge
+            .vertices()
+            .clone()
+            .map(|vertex| vertex.global_form().clone());
+        let (vertices, _) = VerticesInNormalizedOrder::new(vertices);
+
+        let [midpoint] = points_on_half_edge(half_edge, [0.5]);
+
+        let edges = self
+            .by_vertices
+            .entry(vertices.access_in_normalized_order())
+            .or_default();
+        let existing = edges.iter().find(|(other, _)| {
+            (midpoint - *other).magnitude() <= Scalar::from(EPSILON)
+        });
+
+        match existing {
+            Some(&(_, id)) => id,
+            None => {
+                let id = self.next_id;
+                self.next_id += 1;
+
+                edges.push((midpoint, id));
+                id
+            }
+        }
+    }
+}
+
+/// Compute points along a half-edge, in model coordinates
+///
+/// Each point is given as a fraction of the way from the back to the front
+/// vertex of the half-edge.
+fn points_on_half_edge<const N: usize>(
+    half_edge: &HalfEdge,
+    fractions: [f64; N],
+) -> [Point<3>; N] {
+    let surface = half_edge.surface().geometry();
+    let path = half_edge.curve().path();
+    let [back, front] = half_edge
+        .vertices()
+        .clone()
+        .map(|vertex| vertex.position().t);
+
+    fractions.map(|fraction| {
+        let t = back + (front - back) * fraction;
+        let point = path.point_from_path_coords([t]);
+        surface.point_from_surface_coords(point)
+    })
+}
+
+fn half_edges(face: &Face) -> impl Iterator<Item = &Handle<HalfEdge>> {
+    face.all_cycles().flat_map(|cycle| cycle.half_edges())
+}
+
+/// The reference to an entity in the file
+#[derive(Clone, Copy, Debug)]
+struct Id(usize);
+
+impl fmt::Display for Id {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "#{}", self.0)
+    }
+}
+
+/// Writes the entities of a STEP file
+struct Writer {
+    data: String,
+    next_id: usize,
+    coordinate_system: CoordinateSystem,
+    units: Units,
+    context: Option<Id>,
+    vertices: HashMap<Handle<GlobalVertex>, Id>,
+    edge_ids: Edges,
+    edges: HashMap<usize, EdgeCurve>,
+    surfaces: HashMap<Handle<Surface>, (Id, bool)>,
+}
+
+/// An edge that has been written to the file
+#[derive(Clone, Copy)]
+struct EdgeCurve {
+    id: Id,
+
+    /// The half-edge that defines the direction of the edge
+    ///
+    /// Other half-edges of the same edge need to be compared to this one, to
+    /// find out whether they run in the same direction.
+    half_edge: [Point<3>; 2],
+}
+
+impl Writer {
+    fn new(coordinate_system: CoordinateSystem, units: Units) -> Self {
+        Self {
+            data: String::new(),
+            next_id: 1,
+            coordinate_system,
+            units,
+            context: None,
+            vertices: HashMap::new(),
+            edge_ids: Edges::default(),
+            edges: HashMap::new(),
+            surfaces: HashMap::new(),
+        }
+    }
+
+    fn add(&mut self, entity: impl fmt::Display) -> Id {
+        let id = Id(self.next_id);
+        self.next_id += 1;
+
+        writeln!(self.data, "{id}={entity};")
+            .expect("Writing to `String` can't fail");
+
+        id
+    }
+
+    fn finish(self, name: &str) -> String {
+        format!(
+            "ISO-10303-21;\n\
+            HEADER;\n\
+            FILE_DESCRIPTION(('Fornjot model'),'2;1');\n\
+            FILE_NAME({},'{}',(''),(''),'Fornjot','Fornjot','');\n\
+            FILE_SCHEMA(('AUTOMOTIVE_DESIGN {{ 1 0 10303 214 1 1 1 1 }}'));\n\
+            ENDSEC;\n\
+            DATA;\n\
+            {}\
+            ENDSEC;\n\
+            END-ISO-10303-21;\n",
+            string(name),
+            timestamp(),
+            self.data,
+        )
+    }
+
+    /// Whether the coordinate system mirrors the model
+    ///
+    /// A mirrored model has the orientation of all its faces reversed, which
+    /// needs to be compensated for.
+    fn mirrors(&self) -> bool {
+        self.coordinate_system.handedness == Handedness::Left
+    }
+
+    fn convert_point(&self, point: Point<3>) -> Point<3> {
+        self.coordinate_system.convert_point(point)
+    }
+
+    fn convert_vector(&self, vector: Vector<3>) -> Vector<3> {
+        self.coordinate_system
+            .convert_point(Point { coords: vector })
+            .coords
+    }
+
+    fn product(&mut self, name: &str) -> Id {
+        let name = string(name);
+
+        let application = self.add("APPLICATION_CONTEXT('automotive design')");
+        self.add(format!(
+            "APPLICATION_PROTOCOL_DEFINITION('international standard',\
+            'automotive_design',2000,{application})"
+        ));
+        let product_context =
+            self.add(format!("PRODUCT_CONTEXT('',{application},'mechanical')"));
+        let product =
+            self.add(format!("PRODUCT({name},{name},'',({product_context}))"));
+        self.add(format!(
+            "PRODUCT_RELATED_PRODUCT_CATEGORY('part',$,({product}))"
+        ));
+        let formation =
+            self.add(format!("PRODUCT_DEFINITION_FORMATION('','',{product})"));
+        let definition_context = self.add(format!(
+            "PRODUCT_DEFINITION_CONTEXT('part definition',{application},\
+            'design')"
+        ));
+        let definition = self.add(format!(
+            "PRODUCT_DEFINITION('design','',{formation},{definition_context})"
+        ));
+
+        self.add(format!("PRODUCT_DEFINITION_SHAPE('','',{definition})"))
+    }
+
+    /// The geometric context that all geometry is defined in
+    fn context(&mut self) -> Id {
+        if let Some(context) = self.context {
+            return context;
+        }
+
+        let length = match self.units {
+            Units::Millimeters => self
+                .add("(LENGTH_UNIT() NAMED_UNIT(*) SI_UNIT(.MILLI.,.METRE.))"),
+            Units::Meters => {
+                self.add("(LENGTH_UNIT() NAMED_UNIT(*) SI_UNIT($,.METRE.))")
+            }
+            Units::Inches => {
+                // Inches aren't an SI unit, so they are defined by their
+                // conversion into one.
+                let millimeter = self.add(
+                    "(LENGTH_UNIT() NAMED_UNIT(*) SI_UNIT(.MILLI.,.METRE.))",
+                );
+                let measure = self.add(format!(
+                    "LENGTH_MEASURE_WITH_UNIT(LENGTH_MEASURE(25.4),\
+                    {millimeter})"
+                ));
+                let exponents =
+                    self.add("DIMENSIONAL_EXPONENTS(1.,0.,0.,0.,0.,0.,0.)");
+                self.add(format!(
+                    "(CONVERSION_BASED_UNIT('INCH',{measure}) LENGTH_UNIT() \
+                    NAMED_UNIT({exponents}))"
+                ))
+            }
+        };
+        let angle =
+            self.add("(NAMED_UNIT(*) PLANE_ANGLE_UNIT() SI_UNIT($,.RADIAN.))");
+        let solid_angle = self
+            .add("(NAMED_UNIT(*) SI_UNIT($,.STERADIAN.) SOLID_ANGLE_UNIT())");
+        let uncertainty = self.add(format!(
+            "UNCERTAINTY_MEASURE_WITH_UNIT(LENGTH_MEASURE(1.E-07),{length},\
+            'distance_accuracy_value','confusion accuracy')"
+        ));
+        let context = self.add(format!(
+            "(GEOMETRIC_REPRESENTATION_CONTEXT(3) \
+            GLOBAL_UNCERTAINTY_ASSIGNED_CONTEXT(({uncertainty})) \
+            GLOBAL_UNIT_ASSIGNED_CONTEXT(({length},{angle},{solid_angle})) \
+            REPRESENTATION_CONTEXT('',''))"
+        ));
+
+        self.context = Some(context);
+        context
+    }
+
+    fn solid(&mut self, name: &str, faces: &[&Face]) -> Result<Id, Error> {
+        let mut uses = HashMap::<usize, usize>::new();
+        for face in faces {
+            for half_edge in half_edges(face) {
+                *uses.entry(self.edge_ids.identify(half_edge)).or_default() +=
+                    1;
+            }
+        }
+        if uses.values().any(|&uses| uses != 2) {
+            return Err(Error::UnsupportedGeometry(format!(
+                "part `{name}` is not a closed solid"
+            )));
+        }
+
+        let faces = faces
+            .iter()
+            .map(|face| self.face(face))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let shell = self.add(format!("CLOSED_SHELL('',{})", list(faces)));
+        Ok(self.add(format!("MANIFOLD_SOLID_BREP({},{shell})", string(name))))
+    }
+
+    fn face(&mut self, face: &Face) -> Result<Id, Error> {
+        let (surface, surface_agrees) = self.surface(face)?;
+
+        // The front side of a face is where its exterior cycle is wound
+        // counter-clockwise, looking at the surface's coordinate system.
+        // Mirroring the model flips the orientation of the surface relative
+        // to the face.
+        let face_agrees =
+            face.coord_handedness() == FaceHandedness::RightHanded;
+        let same_sense = (face_agrees != self.mirrors()) == surface_agrees;
+
+        // STEP expects the bounds of a face to be wound counter-clockwise
+        // around its normal (clockwise for holes), which they already are.
+        // Unless the coordinate system mirrors the model, which reverses the
+        // winding.
+        let orientation = boolean(!self.mirrors());
+
+        let mut bounds = Vec::new();
+        for (i, cycle) in face.all_cycles().enumerate() {
+            let edges = cycle
+                .half_edges()
+                .map(|half_edge| self.oriented_edge(half_edge))
+                .collect::<Result<Vec<_>, _>>()?;
+            let edge_loop = self.add(format!("EDGE_LOOP('',{})", list(edges)));
+
+            let bound = if i == 0 {
+                "FACE_OUTER_BOUND"
+            } else {
+                "FACE_BOUND"
+            };
+            bounds.push(
+                self.add(format!("{bound}('',{edge_loop},{orientation})")),
+            );
+        }
+
+        Ok(self.add(format!(
+            "ADVANCED_FACE('',{},{surface},{})",
+            list(bounds),
+            boolean(same_sense),
+        )))
+    }
+
+    /// Write the surface of a face
+    ///
+    /// Also returns whether the normal of the surface, as defined by STEP,
+    /// points in the same direction as the one defined by the kernel.
+    fn surface(&mut self, face: &Face) -> Result<(Id, bool), Error> {
+        let surface = face.surface();
+        if let Some(&surface) = self.surfaces.get(surface) {
+            return Ok(surface);
+        }
+
+        let written = match surface.geometry() {
+            SurfaceGeometry::Swept { u, v } => {
+                let v = self.convert_vector(v);
+
+                match u {
+                    GlobalPath::Line(line) => {
+                        let u = self.convert_vector(line.direction());
+                        let placement = self.placement(
+                            self.convert_point(line.origin()),
+                            u.cross(&v),
+                            u,
+                        );
+                        (self.add(format!("PLANE('',{placement})")), true)
+                    }
+                    GlobalPath::Circle(circle) => {
+                        let center = self.convert_point(circle.center());
+                        let a = self.convert_vector(circle.a());
+                        let b = self.convert_vector(circle.b());
+                        let axis = a.cross(&b);
+
+                        if is_parallel(axis, v) {
+                            // A cylinder's normal always points outward, but
+                            // the one of the kernel's surface depends on the
+                            // direction of the circle.
+                            let placement = self.placement(center, axis, a);
+                            let cylinder = self.add(format!(
+                                "CYLINDRICAL_SURFACE('',{placement},{})",
+                                real(circle.radius()),
+                            ));
+                            (cylinder, v.dot(&axis) > Scalar::ZERO)
+                        } else {
+                            let curve = self.conic(center, a, b)?;
+                            (self.extrusion(curve, v), true)
+                        }
+                    }
+                    GlobalPath::Ellipse(ellipse) => {
+                        let center = self.convert_point(ellipse.center());
+                        let a = self.convert_vector(ellipse.a());
+                        let b = self.convert_vector(ellipse.b());
+
+                        let curve = self.conic(center, a, b)?;
+                        (self.extrusion(curve, v), true)
+                    }
+                    GlobalPath::Nurbs(nurbs) => {
+                        let curve = self.nurbs_curve(&nurbs, |point| point);
+                        (self.extrusion(curve, v), true)
+                    }
+                    GlobalPath::Helix(_) => {
+                        return Err(Error::UnsupportedGeometry(
+                            "STEP has no representation for surfaces that \
+                            are swept along a helix"
+                                .into(),
+                        ))
+                    }
+                }
+            }
+            SurfaceGeometry::Nurbs(nurbs) => (self.nurbs_surface(&nurbs), true),
+            SurfaceGeometry::Cone(cone) => {
+                let apex = self.convert_point(cone.apex());
+                let a = self.convert_vector(cone.a());
+                let b = self.convert_vector(cone.b());
+                let axis = self.convert_vector(cone.axis());
+
+                // STEP places a cone at the circle with the given radius, its
+                // axis pointing away from the apex. Like the one of a
+                // cylinder, its normal always points outward.
+                let placement = self.placement(apex + axis, axis, a);
+                let cone = self.add(format!(
+                    "CONICAL_SURFACE('',{placement},{},{})",
+                    real(a.magnitude()),
+                    real(cone.half_angle()),
+                ));
+                (cone, a.cross(&b).dot(&axis) > Scalar::ZERO)
+            }
+            SurfaceGeometry::Ruled { .. } => {
+                return Err(Error::UnsupportedGeometry(
+                    "Exporting ruled surfaces to STEP is not supported yet"
+                        .into(),
+                ))
+            }
+        };
+
+        self.surfaces.insert(surface.clone(), written);
+        Ok(written)
+    }
+
+    fn extrusion(&mut self, curve: Id, v: Vector<3>) -> Id {
+        let direction = self.direction(v);
+        let vector =
+            self.add(format!("VECTOR('',{direction},{})", real(v.magnitude())));
+
+        self.add(format!("SURFACE_OF_LINEAR_EXTRUSION('',{curve},{vector})"))
+    }
+
+    fn oriented_edge(&mut self, half_edge: &HalfEdge) -> Result<Id, Error> {
+        let key = self.edge_ids.identify(half_edge);
+
+        let edge = match self.edges.get(&key) {
+            Some(&edge) => edge,
+            None => {
+                let edge = self.edge_curve(half_edge)?;
+                self.edges.insert(key, edge);
+                edge
+            }
+        };
+
+        // Both half-edges start at the same point, if they run in the same
+        // direction. Comparing their directions close to that point works for
+        // closed edges too, whose half-edges start and end at the same vertex.
+        let [start, next] = self.half_edge_points(half_edge, [0., 0.1]);
+        let [edge_start, edge_next] = edge.half_edge;
+        let same_direction = (start - edge_start).magnitude()
+            < (start - edge_next).magnitude()
+            && (next - start).dot(&(edge_next - edge_start)) > Scalar::ZERO;
+
+        Ok(self.add(format!(
+            "ORIENTED_EDGE('',*,*,{},{})",
+            edge.id,
+            boolean(same_direction),
+        )))
+    }
+
+    fn edge_curve(&mut self, half_edge: &HalfEdge) -> Result<EdgeCurve, Error> {
+        let [start, end] = half_edge
+            .vertices()
+            .clone()
+            .map(|vertex| self.vertex(vertex.global_form()));
+
+        let (curve, same_sense) = self.curve(half_edge)?;
+
+        let id = self.add(format!(
+            "EDGE_CURVE('',{start},{end},{curve},{})",
+            boolean(same_sense),
+        ));
+
+        Ok(EdgeCurve {
+            id,
+            half_edge: self.half_edge_points(half_edge, [0., 0.1]),
+        })
+    }
+
+    /// Write the curve of a half-edge
+    ///
+    /// Also returns whether the half-edge runs in the direction of the curve.
+    fn curve(&mut self, half_edge: &HalfEdge) -> Result<(Id, bool), Error> {
+        let surface = half_edge.surface().geometry();
+        let path = half_edge.curve().path();
+
+        let [back, front] = half_edge
+            .vertices()
+            .clone()
+            .map(|vertex| vertex.position().t);
+        let increasing = back < front;
+
+        match (&surface, &path) {
+            // A line is a line in 3D, if the surface is a plane, or if the line
+            // runs along the direction the surface was swept in.
+            (
+                SurfaceGeometry::Swept {
+                    u: GlobalPath::Line(_),
+                    ..
+                },
+                SurfacePath::Line(_),
+            ) => Ok((self.line(half_edge)?, true)),
+            (SurfaceGeometry::Swept { .. }, SurfacePath::Line(line))
+                if line.direction().u == Scalar::ZERO =>
+            {
+                Ok((self.line(half_edge)?, true))
+            }
+
+            // Planes map circles and ellipses to circles and ellipses.
+            (
+                SurfaceGeometry::Swept {
+                    u: GlobalPath::Line(_),
+                    ..
+                },
+                SurfacePath::Circle(_) | SurfacePath::Ellipse(_),
+            ) => {
+                let (center, a, b) = match &path {
+                    SurfacePath::Circle(circle) => {
+                        (circle.center(), circle.a(), circle.b())
+                    }
+                    SurfacePath::Ellipse(ellipse) => {
+                        (ellipse.center(), ellipse.a(), ellipse.b())
+                    }
+                    _ => unreachable!("Matched circle or ellipse above"),
+                };
+
+                let center = self
+                    .convert_point(surface.point_from_surface_coords(center));
+                let [a, b] = [a, b].map(|axis| {
+                    self.convert_vector(
+                        surface.vector_from_surface_coords(axis),
+                    )
+                });
+
+                self.half_edge_conic(half_edge, center, a, b)
+            }
+            (
+                SurfaceGeometry::Swept {
+                    u: GlobalPath::Line(_),
+                    ..
+                },
+                SurfacePath::Nurbs(nurbs),
+            ) => {
+                let curve = self.nurbs_curve(nurbs, |point| {
+                    surface.point_from_surface_coords(point)
+                });
+                Ok((curve, increasing))
+            }
+
+            // A line that runs along the u-axis of a swept surface is a copy
+            // of the path that the surface was swept from.
+            (SurfaceGeometry::Swept { u, v }, SurfacePath::Line(line))
+                if line.direction().v == Scalar::ZERO =>
+            {
+                let offset = *v * line.origin().v;
+
+                match u {
+                    GlobalPath::Line(_) => Ok((self.line(half_edge)?, true)),
+                    GlobalPath::Circle(circle) => {
+                        let center =
+                            self.convert_point(circle.center() + offset);
+                        let a = self.convert_vector(circle.a());
+                        let b = self.convert_vector(circle.b());
+
+                        self.half_edge_conic(half_edge, center, a, b)
+                    }
+                    GlobalPath::Ellipse(ellipse) => {
+                        let center =
+                            self.convert_point(ellipse.center() + offset);
+                        let a = self.convert_vector(ellipse.a());
+                        let b = self.convert_vector(ellipse.b());
+
+                        self.half_edge_conic(half_edge, center, a, b)
+                    }
+                    GlobalPath::Nurbs(nurbs) => {
+                        let curve =
+                            self.nurbs_curve(nurbs, |point| point + offset);
+                        let reversed = line.direction().u < Scalar::ZERO;
+
+                        Ok((curve, increasing != reversed))
+                    }
+                    GlobalPath::Helix(_) => Err(Error::UnsupportedGeometry(
+                        "STEP has no representation for helices".into(),
+                    )),
+                }
+            }
+
+            _ => Err(Error::UnsupportedGeometry(format!(
+                "can't derive the 3D geometry of a curve ({path:?}) on its \
+                surface ({surface:?})"
+            ))),
+        }
+    }
+
+    /// Write the straight line between the vertices of a half-edge
+    fn line(&mut self, half_edge: &HalfEdge) -> Result<Id, Error> {
+        let [start, end] = half_edge
+            .vertices()
+            .clone()
+            .map(|vertex| self.convert_point(vertex.global_form().position()));
+
+        let direction = end - start;
+        if direction.magnitude() == Scalar::ZERO {
+            return Err(Error::UnsupportedGeometry(
+                "straight edge starts and ends at the same vertex".into(),
+            ));
+        }
+
+        let origin = self.point(start);
+        let vector = {
+            let direction_id = self.direction(direction);
+            self.add(format!(
+                "VECTOR('',{direction_id},{})",
+                real(direction.magnitude())
+            ))
+        };
+
+        Ok(self.add(format!("LINE('',{origin},{vector})")))
+    }
+
+    /// Write a circle or ellipse
+    ///
+    /// The conic is given by its center and two perpendicular vectors, which
+    /// point to the points at a quarter turn from each other. This also
+    /// defines the direction of the conic.
+    fn conic(
+        &mut self,
+        center: Point<3>,
+        a: Vector<3>,
+        b: Vector<3>,
+    ) -> Result<Id, Error> {
+        let [radius_a, radius_b] = [a, b].map(|axis| axis.magnitude());
+
+        if !is_perpendicular(a, b) {
+            return Err(Error::UnsupportedGeometry(
+                "conic whose axes aren't perpendicular".into(),
+            ));
+        }
+
+        let placement = self.placement(center, a.cross(&b), a);
+
+        let is_circle =
+            (radius_a - radius_b).abs() <= radius_a * Scalar::from(EPSILON);
+        let conic = if is_circle {
+            self.add(format!("CIRCLE('',{placement},{})", real(radius_a)))
+        } else {
+            self.add(format!(
+                "ELLIPSE('',{placement},{},{})",
+                real(radius_a),
+                real(radius_b)
+            ))
+        };
+
+        Ok(conic)
+    }
+
+    /// Write the conic of a half-edge
+    ///
+    /// Also returns whether the half-edge runs in the direction of the conic.
+    fn half_edge_conic(
+        &mut self,
+        half_edge: &HalfEdge,
+        center: Point<3>,
+        a: Vector<3>,
+        b: Vector<3>,
+    ) -> Result<(Id, bool), Error> {
+        let angle = |point: Point<3>| {
+            let offset = point - center;
+            let x = offset.dot(&a) / a.dot(&a);
+            let y = offset.dot(&b) / b.dot(&b);
+            y.into_f64().atan2(x.into_f64())
+        };
+
+        // A quarter of the half-edge spans at most a quarter turn, which
+        // makes the direction unambiguous.
+        let [start, quarter] = self.half_edge_points(half_edge, [0., 0.25]);
+
+        let mut difference = angle(quarter) - angle(start);
+        if difference > PI {
+            difference -= 2. * PI;
+        }
+        if difference <= -PI {
+            difference += 2. * PI;
+        }
+
+        Ok((self.conic(center, a, b)?, difference > 0.))
+    }
+
+    fn nurbs_curve<const D: usize>(
+        &mut self,
+        nurbs: &Nurbs<D>,
+        to_global: impl Fn(Point<D>) -> Point<3>,
+    ) -> Id {
+        let points = nurbs
+            .control_points()
+            .iter()
+            .map(|&point| self.point(self.convert_point(to_global(point))))
+            .collect::<Vec<_>>();
+        let (multiplicities, knots) = knots(nurbs.knots());
+
+        let degree = nurbs.degree();
+        let points = list(points);
+        let rest = format!(
+            "{},{},.UNSPECIFIED.",
+            list(multiplicities),
+            list(knots.iter().map(|&knot| real(knot)))
+        );
+
+        if is_rational(nurbs.weights()) {
+            let weights = list(nurbs.weights().iter().map(|&w| real(w)));
+            self.add(format!(
+                "(BOUNDED_CURVE() \
+                B_SPLINE_CURVE({degree},{points},.UNSPECIFIED.,.F.,.F.) \
+                B_SPLINE_CURVE_WITH_KNOTS({rest}) CURVE() \
+                GEOMETRIC_REPRESENTATION_ITEM() \
+                RATIONAL_B_SPLINE_CURVE({weights}) REPRESENTATION_ITEM(''))"
+            ))
+        } else {
+            self.add(format!(
+                "B_SPLINE_CURVE_WITH_KNOTS('',{degree},{points},\
+                .UNSPECIFIED.,.F.,.F.,{rest})"
+            ))
+        }
+    }
+
+    fn nurbs_surface(&mut self, nurbs: &NurbsSurface<3>) -> Id {
+        let points = nurbs
+            .control_points()
+            .iter()
+            .map(|row| {
+                let row = row
+                    .iter()
+                    .map(|&point| self.point(self.convert_point(point)))
+                    .collect::<Vec<_>>();
+                list(row)
+            })
+            .collect::<Vec<_>>();
+        let [(u_multiplicities, u_knots), (v_multiplicities, v_knots)] =
+            nurbs.knots().map(knots);
+
+        let [u_degree, v_degree] = nurbs.degrees();
+        let points = list(points);
+        let rest = format!(
+            "{},{},{},{},.UNSPECIFIED.",
+            list(u_multiplicities),
+            list(v_multiplicities),
+            list(u_knots.iter().map(|&knot| real(knot))),
+            list(v_knots.iter().map(|&knot| real(knot))),
+        );
+
+        let weights = nurbs.weights();
+        if weights.iter().any(|row| is_rational(row)) {
+            let weights = list(
+                weights.iter().map(|row| list(row.iter().map(|&w| real(w)))),
+            );
+            self.add(format!(
+                "(BOUNDED_SURFACE() \
+                B_SPLINE_SURFACE({u_degree},{v_degree},{points},\
+                .UNSPECIFIED.,.F.,.F.,.F.) \
+                B_SPLINE_SURFACE_WITH_KNOTS({rest}) \
+                GEOMETRIC_REPRESENTATION_ITEM() \
+                RATIONAL_B_SPLINE_SURFACE({weights}) REPRESENTATION_ITEM('') \
+                SURFACE())"
+            ))
+        } else {
+            self.add(format!(
+                "B_SPLINE_SURFACE_WITH_KNOTS('',{u_degree},{v_degree},\
+                {points},.UNSPECIFIED.,.F.,.F.,.F.,{rest})"
+            ))
+        }
+    }
+
+    fn vertex(&mut self, vertex: &Handle<GlobalVertex>) -> Id {
+        if let Some(&id) = self.vertices.get(vertex) {
+            return id;
+        }
+
+        let point = self.point(self.convert_point(vertex.position()));
+        let id = self.add(format!("VERTEX_POINT('',{point})"));
+
+        self.vertices.insert(vertex.clone(), id);
+        id
+    }
+
+    /// Compute points along a half-edge, in the exported coordinate system
+    ///
+    /// The points are given as fractions of the way from the back to the
+    /// front vertex of the half-edge.
+    fn half_edge_points<const N: usize>(
+        &self,
+        half_edge: &HalfEdge,
+        fractions: [f64; N],
+    ) -> [Point<3>; N] {
+        points_on_half_edge(half_edge, fractions)
+            .map(|point| self.convert_point(point))
+    }
+
+    fn point(&mut self, point: Point<3>) -> Id {
+        let [x, y, z] = point.coords.components.map(real);
+        self.add(format!("CARTESIAN_POINT('',({x},{y},{z}))"))
+    }
+
+    fn direction(&mut self, direction: Vector<3>) -> Id {
+        let [x, y, z] = direction.normalize().components.map(real);
+        self.add(format!("DIRECTION('',({x},{y},{z}))"))
+    }
+
+    fn placement(
+        &mut self,
+        origin: Point<3>,
+        axis: Vector<3>,
+        reference: Vector<3>,
+    ) -> Id {
+        let origin = self.point(origin);
+        let axis = self.direction(axis);
+        let reference = self.direction(reference);
+
+        self.add(format!(
+            "AXIS2_PLACEMENT_3D('',{origin},{axis},{reference})"
+        ))
+    }
+}
+
+fn is_parallel(a: Vector<3>, b: Vector<3>) -> bool {
+    let cross = a.normalize().cross(&b.normalize());
+    cross.magnitude() <= Scalar::from(EPSILON)
+}
+
+fn is_perpendicular(a: Vector<3>, b: Vector<3>) -> bool {
+    a.normalize().dot(&b.normalize()).abs() <= Scalar::from(EPSILON)
+}
+
+fn is_rational(weights: &[Scalar]) -> bool {
+    weights.iter().any(|&weight| weight != weights[0])
+}
+
+/// Convert a knot vector into distinct knots and their multiplicities
+fn knots(knots: &[Scalar]) -> (Vec<usize>, Vec<Scalar>) {
+    let mut multiplicities = Vec::<usize>::new();
+    let mut distinct = Vec::<Scalar>::new();
+
+    for &knot in knots {
+        match (distinct.last(), multiplicities.last_mut()) {
+            (Some(&last), Some(multiplicity)) if last == knot => {
+                *multiplicity += 1;
+            }
+            _ => {
+                distinct.push(knot);
+                multiplicities.push(1);
+            }
+        }
+    }
+
+    (multiplicities, distinct)
+}
+
+fn list<T: fmt::Display>(items: impl IntoIterator<Item = T>) -> String {
+    let items = items
+        .into_iter()
+        .map(|item| item.to_string())
+        .collect::<Vec<_>>();
+    format!("({})", items.join(","))
+}
+
+fn boolean(value: bool) -> &'static str {
+    if value {
+        ".T."
+    } else {
+        ".F."
+    }
+}
+
+/// Format a real number
+///
+/// STEP requires a decimal point in every real number, and an upper-case
+/// exponent marker.
+fn real(value: Scalar) -> String {
+    let value = format!("{:?}", value.into_f64());
+
+    match value.split_once('e') {
+        Some((mantissa, exponent)) if mantissa.contains('.') => {
+            format!("{mantissa}E{exponent}")
+        }
+        Some((mantissa, exponent)) => format!("{mantissa}.E{exponent}"),
+        None if value.contains('.') => value,
+        None => format!("{value}."),
+    }
+}
+
+/// Format a string
+///
+/// Characters outside of printable ASCII need to be encoded. Only the basic
+/// multilingual plane is supported, anything else is replaced.
+fn string(value: &str) -> String {
+    let mut string = String::from("'");
+
+    for c in value.chars() {
+        match c {
+            '\'' => string.push_str("''"),
+            '\\' => string.push_str("\\\\"),
+            ' '..='~' => string.push(c),
+            _ => match u16::try_from(u32::from(c)) {
+                Ok(code) => {
+                    write!(string, "\\X2\\{code:04X}\\X0\\")
+                        .expect("Writing to `String` can't fail");
+                }
+                Err(_) => string.push('?'),
+            },
+        }
+    }
+
+    string.push('\'');
+    string
+}
+
+/// The current time, as required by the header of the file
+fn timestamp() -> String {
+    let seconds = SystemTime::now()
+        .duration_since(SystemTime::UNIX_EPOCH)
+        .map(|duration| duration.as_secs())
+        .unwrap_or_default();
+
+    let days = (seconds / 86400) as i64;
+    let time = seconds % 86400;
+
+    // Convert days since the epoch into a civil date. See
+    // http://howardhinnant.github.io/date_algorithms.html#civil_from_days
+    let z = days + 719468;
+    let era = z.div_euclid(146097);
+    let day_of_era = z.rem_euclid(146097);
+    let year_of_era = (day_of_era - day_of_era / 1460 + day_of_era / 36524
+        - day_of_era / 146096)
+        / 365;
+    let day_of_year =
+        day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let mp = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = year_of_era + era * 400 + i64::from(month <= 2);
+
+    format!(
+        "{year:04}-{month:02}-{day:02}T{:02}:{:02}:{:02}",
+        time / 3600,
+        time / 60 % 60,
+        time % 60,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+
+    use fj_interop::units::Units;
+    use fj_kernel::{
+        algorithms::bounding_volume::BoundingVolume,
+        iter::ObjectIters,
+        objects::{FaceSet, Shell},
+        services::Services,
+    };
+    use fj_math::{Aabb, Scalar};
+
+    use crate::CoordinateSystem;
+
+    use super::BrepPart;
+
+    #[test]
+    fn cube_round_trip() {
+        let (imported, aabb) = round_trip(Units::Millimeters);
+
+        assert_eq!(imported.face_iter().count(), 6);
+        assert_eq!(imported.global_vertex_iter().count(), 8);
+        assert_cube(aabb, 0.5);
+    }
+
+    #[test]
+    fn lengths_are_imported_as_millimeters() {
+        let (imported, aabb) = round_trip(Units::Inches);
+
+        assert_eq!(imported.face_iter().count(), 6);
+        assert_cube(aabb, 12.7);
+    }
+
+    /// Export a cube with an edge length of 1, and import it again
+    fn round_trip(units: Units) -> (FaceSet, Aabb<3>) {
+        let mut services = Services::new();
+        let shell = Shell::builder()
+            .with_cube_from_edge_length(1., &mut services.objects)
+            .build(&mut services.objects);
+
+        let path = temp_path(units);
+        super::write(
+            &[BrepPart {
+                name: "cube",
+                faces: shell.faces(),
+                units,
+            }],
+            &path,
+            CoordinateSystem::default(),
+            units,
+        )
+        .unwrap();
+
+        let mut services = Services::new();
+        let solids = fj_import::import(&path, &mut services.objects).unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        assert_eq!(solids.len(), 1);
+        let faces = solids
+            .iter()
+            .flat_map(|solid| solid.shells())
+            .flat_map(|shell| shell.faces().clone())
+            .collect::<FaceSet>();
+
+        let aabb = faces.aabb().unwrap();
+        (faces, aabb)
+    }
+
+    fn assert_cube(aabb: Aabb<3>, half_edge_length: f64) {
+        let expected = Aabb::<3>::from_points([
+            [-half_edge_length; 3],
+            [half_edge_length; 3],
+        ]);
+
+        for (a, b) in [(aabb.min, expected.min), (aabb.max, expected.max)] {
+            assert!((a - b).magnitude() < Scalar::from(1e-9), "{a:?} != {b:?}");
+        }
+    }
+
+    fn temp_path(units: Units) -> PathBuf {
+        std::env::temp_dir().join(format!(
+            "fj-export-step-test-{}-{units:?}.step",
+            std::process::id()
+        ))
+    }
+}
@@ -0,0 +1,259 @@
+//! Export to SVG
+//!
+//! Writes two-dimensional profiles, for use in documentation, or by laser
+//! cutters. One unit in the model is one millimeter in the drawing. Each layer
+//! becomes a group of paths.
+
+use std::{
+    fs::File,
+    io::{BufWriter, Write as _},
+    path::Path,
+};
+
+use fj_math::{Aabb, Point, Scalar, Vector};
+
+use crate::{
+    escape,
+    profile::{Entity, Layer},
+    Error,
+};
+
+/// The width of the lines, in millimeters
+const STROKE_WIDTH: f64 = 0.1;
+
+/// Write the layers to an SVG file
+pub fn write(layers: &[Layer], path: &Path) -> Result<(), Error> {
+    let points = layers
+        .iter()
+        .flat_map(|layer| &layer.entities)
+        .flat_map(extreme_points)
+        .collect::<Vec<_>>();
+    let [min, max] = if points.is_empty() {
+        [Point::origin(); 2]
+    } else {
+        let aabb = Aabb::<2>::from_points(points);
+        [aabb.min, aabb.max]
+    };
+
+    // SVG's y-axis points down, so the drawing is flipped.
+    let margin = Scalar::from(STROKE_WIDTH);
+    let x = min.u - margin;
+    let y = -max.v - margin;
+    let width = max.u - min.u + margin * 2.;
+    let height = max.v - min.v + margin * 2.;
+
+    let mut file = BufWriter::new(File::create(path)?);
+
+    writeln!(file, r#"<?xml version="1.0" encoding="UTF-8"?>"#)?;
+    writeln!(
+        file,
+        r#"<svg xmlns="http://www.w3.org/2000/svg" version="1.1" width="{width}mm" height="{height}mm" viewBox="{x} {y} {width} {height}">"#
+    )?;
+
+    for layer in layers {
+        writeln!(
+            file,
+            r#"  <g id="{}" fill="none" stroke="black" stroke-width="{STROKE_WIDTH}" stroke-linecap="round" stroke-linejoin="round">"#,
+            escape(&layer.name)
+        )?;
+
+        for entity in &layer.entities {
+            match entity {
+                Entity::Line([start, end]) => {
+                    writeln!(
+                        file,
+                        r#"    <line x1="{}" y1="{}" x2="{}" y2="{}"/>"#,
+                        start.u,
+                        flip(start.v),
+                        end.u,
+                        flip(end.v)
+                    )?;
+                }
+                Entity::Circle { center, radius } => {
+                    writeln!(
+                        file,
+                        r#"    <circle cx="{}" cy="{}" r="{radius}"/>"#,
+                        center.u,
+                        flip(center.v)
+                    )?;
+                }
+                Entity::Arc {
+                    center,
+                    radius,
+                    angles,
+                } => {
+                    let [start, end] = angles
+                        .map(|angle| point_on_circle(*center, *radius, angle));
+                    let span =
+                        (angles[1] - angles[0]).into_f64().rem_euclid(360.);
+                    let large_arc = u8::from(span > 180.);
+
+                    // Flipping the drawing keeps the arcs counter-clockwise.
+                    // With SVG's y-axis pointing down, that is the negative
+                    // angle direction, which a sweep flag of 0 selects.
+                    writeln!(
+                        file,
+                        r#"    <path d="M {} {} A {radius} {radius} 0 {large_arc} 0 {} {}"/>"#,
+                        start.u,
+                        flip(start.v),
+                        end.u,
+                        flip(end.v)
+                    )?;
+                }
+                Entity::Polyline { points, closed } => {
+                    let element = if *closed { "polygon" } else { "polyline" };
+                    let points = points
+                        .iter()
+                        .map(|point| format!("{},{}", point.u, flip(point.v)))
+                        .collect::<Vec<_>>()
+                        .join(" ");
+
+                    writeln!(file, r#"    <{element} points="{points}"/>"#)?;
+                }
+            }
+        }
+
+        writeln!(file, "  </g>")?;
+    }
+
+    writeln!(file, "</svg>")?;
+    file.flush()?;
+
+    Ok(())
+}
+
+/// Flip a y-coordinate into SVG's coordinate system
+fn flip(y: Scalar) -> f64 {
+    // Adding zero turns negative zero into zero, which looks less odd.
+    -y.into_f64() + 0.
+}
+
+/// Compute the point on a circle at the given angle, in degrees
+fn point_on_circle(
+    center: Point<2>,
+    radius: Scalar,
+    angle: Scalar,
+) -> Point<2> {
+    let (sin, cos) = angle.into_f64().to_radians().sin_cos();
+    center + Vector::from([cos, sin]) * radius
+}
+
+/// Compute the points that bound an entity
+pub fn extreme_points(entity: &Entity) -> Vec<Point<2>> {
+    match entity {
+        Entity::Line(points) => points.to_vec(),
+        Entity::Circle { center, radius } => [[-1., -1.], [1., 1.]]
+            .map(|direction| *center + Vector::from(direction) * *radius)
+            .to_vec(),
+        Entity::Arc {
+            center,
+            radius,
+            angles: [start, end],
+        } => {
+            let span = (*end - *start).into_f64().rem_euclid(360.);
+
+            // Besides its end points, an arc is bounded by the points where it
+            // crosses the axes.
+            [0., 90., 180., 270.]
+                .into_iter()
+                .filter(|angle| {
+                    (angle - start.into_f64()).rem_euclid(360.) < span
+                })
+                .map(Scalar::from)
+                .chain([*start, *end])
+                .map(|angle| point_on_circle(*center, *radius, angle))
+                .collect()
+        }
+        Entity::Polyline { points, .. } => points.clone(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use fj_math::{Aabb, Point, Scalar};
+
+    use crate::profile::{Entity, Layer};
+
+    use super::extreme_points;
+
+    #[test]
+    fn write_entities() {
+        let layers = [Layer {
+            name: "a & b".into(),
+            entities: vec![
+                Entity::Line([[0., 0.], [10., 0.]].map(Point::from)),
+                Entity::Circle {
+                    center: Point::from([5., 5.]),
+                    radius: Scalar::from(2.),
+                },
+                Entity::Polyline {
+                    points: [[0., 0.], [10., 0.], [10., 10.]]
+                        .map(Point::from)
+                        .to_vec(),
+                    closed: true,
+                },
+                Entity::Arc {
+                    center: Point::origin(),
+                    radius: Scalar::from(10.),
+                    angles: [Scalar::ZERO, Scalar::from(270.)],
+                },
+            ],
+        }];
+
+        let path = std::env::temp_dir()
+            .join(format!("fj-export-svg-test-{}.svg", std::process::id()));
+        super::write(&layers, &path).unwrap();
+        let source = fs::read_to_string(&path).unwrap();
+        fs::remove_file(&path).unwrap();
+
+        // The drawing is flipped, so the y-axis points up, and the view box
+        // includes the width of the lines.
+        assert!(source.contains(
+            r#"width="20.2mm" height="20.2mm" viewBox="-10.1 -10.1 20.2 20.2""#
+        ));
+        assert!(source.contains(r#"<g id="a &amp; b" "#));
+        assert!(source.contains(r#"<line x1="0" y1="0" x2="10" y2="0"/>"#));
+        assert!(source.contains(r#"<circle cx="5" cy="-5" r="2"/>"#));
+        assert!(source.contains(r#"<polygon points="0,0 10,0 10,-10"/>"#));
+
+        // The arc runs counter-clockwise from the positive x-axis to the
+        // negative y-axis. That's three quarters of the circle, which requires
+        // the large arc flag.
+        let arc = source
+            .lines()
+            .find_map(|line| line.trim().strip_prefix(r#"<path d=""#))
+            .unwrap();
+        let values = arc
+            .trim_end_matches(r#""/>"#)
+            .split(' ')
+            .filter_map(|value| value.parse::<f64>().ok())
+            .collect::<Vec<_>>();
+        let expected = [10., 0., 10., 10., 0., 1., 0., 0., 10.];
+        assert_eq!(values.len(), expected.len());
+        for (value, expected) in values.into_iter().zip(expected) {
+            assert!((value - expected).abs() < 1e-9, "{arc}");
+        }
+    }
+
+    #[test]
+    fn extreme_points_of_arc() {
+        let arc = Entity::Arc {
+            center: Point::from([1., 1.]),
+            radius: Scalar::from(2.),
+            angles: [Scalar::from(45.), Scalar::from(135.)],
+        };
+
+        // The arc crosses the y-axis of its center, which bounds it at the top.
+        let aabb = Aabb::<2>::from_points(extreme_points(&arc));
+        let half_diagonal = 2_f64.sqrt();
+        let expected = Aabb::<2>::from_points([
+            [1. - half_diagonal, 1. + half_diagonal],
+            [1. + half_diagonal, 3.],
+        ]);
+        for (a, b) in [(aabb.min, expected.min), (aabb.max, expected.max)] {
+            assert!((a - b).magnitude() < Scalar::from(1e-9), "{a:?} != {b:?}");
+        }
+    }
+}
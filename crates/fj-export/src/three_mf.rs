@@ -0,0 +1,169 @@
+//! Export to 3MF
+//!
+//! Each part is written as a separate, named object.
+//!
+//! Triangles are assigned base materials, one for each combination of
+//! material and color that occurs in the model. Materials are named after the
+//! material attribute of the faces, or after their color, if they don't have
+//! one.
+
+use std::{
+    collections::BTreeMap,
+    fs::File,
+    io::{self, Write},
+    path::Path,
+    sync::Arc,
+};
+
+use fj_interop::mesh::{Color, Triangle};
+use zip::{write::FileOptions, ZipWriter};
+
+use crate::{escape, unit_name, Error, Part};
+
+const CONTENT_TYPES: &str = r#"<?xml version="1.0" encoding="utf-8"?>
+<Types xmlns="http://schemas.openxmlformats.org/package/2006/content-types">
+	<Default Extension="model" ContentType="application/vnd.ms-package.3dmanufacturing-3dmodel+xml" />
+	<Default Extension="rels" ContentType="application/vnd.openxmlformats-package.relationships+xml" />
+</Types>
+"#;
+
+const RELS: &str = r#"<?xml version="1.0" encoding="utf-8"?>
+<Relationships xmlns="http://schemas.openxmlformats.org/package/2006/relationships">
+	<Relationship Type="http://schemas.microsoft.com/3dmanufacturing/2013/01/3dmodel" Target="/3D/model.model" Id="rel0" />
+</Relationships>
+"#;
+
+/// Write the parts to a 3MF file, one object per part
+pub fn write(parts: &[Part], path: &Path) -> Result<(), Error> {
+    let file = File::create(path)?;
+    let mut archive = ZipWriter::new(file);
+
+    archive.start_file("[Content_Types].xml", FileOptions::default())?;
+    archive.write_all(CONTENT_TYPES.as_bytes())?;
+
+    archive.start_file("_rels/.rels", FileOptions::default())?;
+    archive.write_all(RELS.as_bytes())?;
+
+    archive.start_file("3D/model.model", FileOptions::default())?;
+    write_model(&mut archive, parts)?;
+
+    archive.finish()?;
+
+    Ok(())
+}
+
+fn write_model(mut sink: impl Write, parts: &[Part]) -> io::Result<()> {
+    writeln!(sink, r#"<?xml version="1.0" encoding="utf-8"?>"#)?;
+    let unit = unit_name(parts.first().map(|part| part.units));
+    writeln!(
+        sink,
+        r#"<model xmlns="http://schemas.microsoft.com/3dmanufacturing/core/2015/02" unit="{unit}" xml:lang="en-US">"#
+    )?;
+
+    writeln!(sink, "\t<resources>")?;
+
+    let materials = materials(parts);
+    if !materials.is_empty() {
+        writeln!(sink, "\t\t<basematerials id=\"{MATERIALS_ID}\">")?;
+        for (material, color) in materials.keys() {
+            let [r, g, b, a] = color.0;
+            let color = format!("#{r:02X}{g:02X}{b:02X}{a:02X}");
+            let name = material.as_deref().unwrap_or(&color);
+
+            writeln!(
+                sink,
+                "\t\t\t<base name=\"{}\" displaycolor=\"{color}\" />",
+                escape(name),
+            )?;
+        }
+        writeln!(sink, "\t\t</basematerials>")?;
+    }
+
+    for (id, part) in object_ids(parts) {
+        writeln!(
+            sink,
+            "\t\t<object id=\"{id}\" name=\"{}\" type=\"model\" \
+            pid=\"{MATERIALS_ID}\" pindex=\"0\">",
+            escape(part.name)
+        )?;
+        writeln!(sink, "\t\t\t<mesh>")?;
+
+        writeln!(sink, "\t\t\t\t<vertices>")?;
+        for vertex in part.mesh.vertices() {
+            let [x, y, z] = vertex.coords.components.map(|s| s.into_f64());
+            writeln!(
+                sink,
+                "\t\t\t\t\t<vertex x=\"{x}\" y=\"{y}\" z=\"{z}\" />",
+            )?;
+        }
+        writeln!(sink, "\t\t\t\t</vertices>")?;
+
+        writeln!(sink, "\t\t\t\t<triangles>")?;
+        let indices = part.mesh.indices().collect::<Vec<_>>();
+        for (triangle, indices) in part.mesh.triangles().zip(indices.chunks(3))
+        {
+            let material = materials[&material_key(&triangle)];
+            writeln!(
+                sink,
+                "\t\t\t\t\t<triangle v1=\"{}\" v2=\"{}\" v3=\"{}\" \
+                p1=\"{material}\" />",
+                indices[0], indices[1], indices[2],
+            )?;
+        }
+        writeln!(sink, "\t\t\t\t</triangles>")?;
+
+        writeln!(sink, "\t\t\t</mesh>")?;
+        writeln!(sink, "\t\t</object>")?;
+    }
+    writeln!(sink, "\t</resources>")?;
+
+    writeln!(sink, "\t<build>")?;
+    for (id, _) in object_ids(parts) {
+        writeln!(sink, "\t\t<item objectid=\"{id}\" />")?;
+    }
+    writeln!(sink, "\t</build>")?;
+
+    writeln!(sink, "</model>")?;
+
+    Ok(())
+}
+
+/// The ID of the base materials, which precede all objects
+const MATERIALS_ID: usize = 1;
+
+/// Identifies the base material of a triangle
+type MaterialKey = (Option<Arc<str>>, Color);
+
+fn material_key(triangle: &Triangle) -> MaterialKey {
+    (triangle.attributes.material.clone(), triangle.color)
+}
+
+/// Assign an index to each base material that the parts use
+fn materials(parts: &[Part]) -> BTreeMap<MaterialKey, usize> {
+    let mut materials = BTreeMap::new();
+
+    for part in parts {
+        for triangle in part.mesh.triangles() {
+            materials.entry(material_key(&triangle)).or_insert(0);
+        }
+    }
+    for (index, value) in materials.values_mut().enumerate() {
+        *value = index;
+    }
+
+    materials
+}
+
+/// Assign object IDs to the parts that have any triangles
+///
+/// 3MF requires a mesh to have at least one triangle, so empty parts are left
+/// out.
+fn object_ids<'a, 'r>(
+    parts: &'a [Part<'r>],
+) -> impl Iterator<Item = (usize, &'a Part<'r>)> {
+    parts
+        .iter()
+        .filter(|part| part.mesh.indices().next().is_some())
+        .enumerate()
+        .map(|(i, part)| (MATERIALS_ID + i + 1, part))
+}
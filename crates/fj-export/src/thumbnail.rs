@@ -0,0 +1,251 @@
+//! Rendering of thumbnails
+//!
+//! See [`render`].
+
+use std::{f64::consts::PI, fs::File, io::BufWriter, path::Path};
+
+use fj_interop::mesh::Mesh;
+use fj_math::{Aabb, Point, Scalar, Vector};
+
+use crate::Error;
+
+/// Each pixel is rendered from this many samples in each direction, to smooth
+/// out the edges of the model
+const SAMPLES: usize = 2;
+
+/// The fraction of the thumbnail that the model leaves empty around it
+const MARGIN: f64 = 0.05;
+
+/// Render a thumbnail of a mesh, and write it to a PNG file
+pub fn write(
+    mesh: &Mesh<Point<3>>,
+    size: u32,
+    path: &Path,
+) -> Result<(), Error> {
+    let pixels = render(mesh, size);
+
+    let file = BufWriter::new(File::create(path)?);
+    let mut encoder = png::Encoder::new(file, size, size);
+    encoder.set_color(png::ColorType::Rgba);
+    encoder.set_depth(png::BitDepth::Eight);
+    encoder.write_header()?.write_image_data(&pixels)?;
+
+    Ok(())
+}
+
+/// Render a square thumbnail of a mesh
+///
+/// The model is rendered in software, so this works without a graphics
+/// device. It is shown from the front, right, and above, with an orthographic
+/// projection, and shaded like in the viewer. The background is transparent.
+///
+/// Returns the pixels, row by row from the top, as 8-bit RGBA.
+pub fn render(mesh: &Mesh<Point<3>>, size: u32) -> Vec<u8> {
+    let size = size as usize;
+    let samples = size * SAMPLES;
+
+    let vertices = mesh.vertices().collect::<Vec<_>>();
+    let mut colors = vec![[0.; 4]; samples * samples];
+
+    if !vertices.is_empty() && size > 0 {
+        let camera = ThumbnailCamera::frame(&Aabb::<3>::from_points(vertices));
+        let mut depths = vec![f64::INFINITY; samples * samples];
+
+        for triangle in mesh.triangles() {
+            let points = triangle.inner.points().map(|point| {
+                let [x, y, depth] = camera.project(&point);
+
+                // Sample positions are in the centers of the samples. Screen y
+                // points down.
+                let x = (x + 1.) / 2. * samples as f64 - 0.5;
+                let y = (1. - y) / 2. * samples as f64 - 0.5;
+                [x, y, depth]
+            });
+
+            let brightness = camera.brightness(triangle.inner.normal());
+            let color = triangle.color.0.map(|c| f64::from(c) / 255.);
+            let color = [
+                color[0] * brightness,
+                color[1] * brightness,
+                color[2] * brightness,
+                color[3],
+            ];
+
+            rasterize(points, samples, |i, depth| {
+                if depth < depths[i] {
+                    depths[i] = depth;
+                    colors[i] = color;
+                }
+            });
+        }
+    }
+
+    // Average the samples of each pixel. Colors are weighted by their alpha,
+    // so the transparent background doesn't darken the edges of the model.
+    let mut pixels = Vec::with_capacity(size * size * 4);
+    for y in 0..size {
+        for x in 0..size {
+            let mut sum = [0.; 4];
+            for sy in 0..SAMPLES {
+                for sx in 0..SAMPLES {
+                    let i = (y * SAMPLES + sy) * samples + x * SAMPLES + sx;
+                    let [r, g, b, a] = colors[i];
+                    for (sum, value) in
+                        sum.iter_mut().zip([r * a, g * a, b * a, a])
+                    {
+                        *sum += value;
+                    }
+                }
+            }
+
+            let [r, g, b, a] = sum;
+            let alpha = a / (SAMPLES * SAMPLES) as f64;
+            let color = if a > 0. {
+                [r / a, g / a, b / a, alpha]
+            } else {
+                [0.; 4]
+            };
+            pixels.extend(color.map(|c| (c * 255.).round() as u8));
+        }
+    }
+
+    pixels
+}
+
+/// The camera that thumbnails are rendered with
+struct ThumbnailCamera {
+    center: Point<3>,
+    right: Vector<3>,
+    up: Vector<3>,
+
+    /// The direction the camera looks in
+    forward: Vector<3>,
+
+    /// The distance from the center to the edge of the thumbnail
+    half_width: Scalar,
+}
+
+impl ThumbnailCamera {
+    /// Find a view that fits a bounding box into the thumbnail
+    ///
+    /// The model is looked at diagonally, from the front, right, and above,
+    /// which shows three of its sides. The z-axis points up, as in the viewer.
+    /// Looking at each side from a different angle shades them differently,
+    /// which keeps the thumbnail from looking flat.
+    fn frame(aabb: &Aabb<3>) -> Self {
+        let forward = Vector::from([-0.6, 1., -0.8]).normalize();
+        let right = forward.cross(&Vector::unit_z()).normalize();
+        let up = right.cross(&forward);
+
+        let mut camera = Self {
+            center: aabb.center(),
+            right,
+            up,
+            forward,
+            half_width: Scalar::ONE,
+        };
+
+        // Center the thumbnail on the projected bounding box, not the center
+        // of the bounding box, which doesn't project to the center of the
+        // corners.
+        let corners = aabb.vertices().map(|vertex| {
+            let offset = vertex - camera.center;
+            [offset.dot(&right), offset.dot(&up)]
+        });
+        let [min, max] = [Scalar::min, Scalar::max].map(|f| {
+            [0, 1].map(|axis| {
+                corners
+                    .iter()
+                    .map(|corner| corner[axis])
+                    .reduce(f)
+                    .unwrap_or_default()
+            })
+        });
+
+        camera.center = camera.center
+            + right * (min[0] + max[0]) / 2.
+            + up * (min[1] + max[1]) / 2.;
+
+        let half_width = (max[0] - min[0]).max(max[1] - min[1]) / 2.;
+        camera.half_width = if half_width > Scalar::ZERO {
+            half_width / (1. - MARGIN * 2.)
+        } else {
+            Scalar::ONE
+        };
+
+        camera
+    }
+
+    /// Project a point onto the thumbnail
+    ///
+    /// Returns the position of the point, from -1 to 1 across the thumbnail,
+    /// and its depth.
+    fn project(&self, point: &Point<3>) -> [f64; 3] {
+        let offset = point - self.center;
+
+        [
+            offset.dot(&self.right) / self.half_width,
+            offset.dot(&self.up) / self.half_width,
+            offset.dot(&self.forward),
+        ]
+        .map(Scalar::into_f64)
+    }
+
+    /// Compute the brightness of a surface, in the same way the viewer does
+    ///
+    /// The light comes from the camera.
+    fn brightness(&self, normal: Vector<3>) -> f64 {
+        let cos = (-normal.dot(&self.forward)).into_f64().clamp(-1., 1.);
+        let angle = cos.acos() / (PI * 0.75);
+
+        (1. - angle).max(0.)
+    }
+}
+
+/// Find the samples that a triangle covers
+///
+/// Calls `sample` with the index of each sample and the depth of the triangle
+/// there.
+fn rasterize(
+    points: [[f64; 3]; 3],
+    samples: usize,
+    mut sample: impl FnMut(usize, f64),
+) {
+    let [a, b, c] = points;
+
+    let edge = |p: [f64; 3], q: [f64; 3], x: f64, y: f64| {
+        (q[0] - p[0]) * (y - p[1]) - (q[1] - p[1]) * (x - p[0])
+    };
+    let area = edge(a, b, c[0], c[1]);
+    if area == 0. {
+        return;
+    }
+
+    let min = |i: usize| a[i].min(b[i]).min(c[i]);
+    let max = |i: usize| a[i].max(b[i]).max(c[i]);
+    let last = samples as f64 - 1.;
+    let [x_min, y_min] = [0, 1].map(|i| min(i).ceil().clamp(0., last) as usize);
+    let [x_max, y_max] =
+        [0, 1].map(|i| max(i).floor().clamp(0., last) as usize);
+    if min(0) > last || min(1) > last || max(0) < 0. || max(1) < 0. {
+        return;
+    }
+
+    for y in y_min..=y_max {
+        for x in x_min..=x_max {
+            let [px, py] = [x as f64, y as f64];
+
+            // The weights are positive within the triangle, regardless of its
+            // winding.
+            let wa = edge(b, c, px, py) / area;
+            let wb = edge(c, a, px, py) / area;
+            let wc = edge(a, b, px, py) / area;
+            if wa < 0. || wb < 0. || wc < 0. {
+                continue;
+            }
+
+            let depth = a[2] * wa + b[2] * wb + c[2] * wc;
+            sample(y * samples + x, depth);
+        }
+    }
+}
@@ -0,0 +1,299 @@
+//! Views of parts, with hidden lines removed
+//!
+//! See [`View`].
+
+use std::collections::BTreeMap;
+
+use fj_math::{Aabb, Point, Scalar, Vector};
+
+use crate::{
+    normals::CREASE_ANGLE,
+    profile::{chain, layer_name, Frame, Layer},
+    Part,
+};
+
+/// The relative tolerance used for barycentric coordinates
+const EPSILON: f64 = 1e-9;
+
+/// A view of a model, as used in technical drawings
+///
+/// Views are orthographic projections along one of the axes of the model.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum View {
+    /// Look at the model from the front, along the y-axis
+    ///
+    /// The x-axis points to the right, the z-axis up.
+    #[default]
+    Front,
+
+    /// Look down on the model, against the z-axis
+    ///
+    /// The x-axis points to the right, the y-axis up.
+    Top,
+
+    /// Look at the model from the right side, against the x-axis
+    ///
+    /// The y-axis points to the right, the z-axis up.
+    Side,
+}
+
+impl View {
+    /// The frame of the view, whose normal points towards the viewer
+    fn frame(&self) -> Frame {
+        let [x, y] = match self {
+            Self::Front => [Vector::unit_x(), Vector::unit_z()],
+            Self::Top => [Vector::unit_x(), Vector::unit_y()],
+            Self::Side => [Vector::unit_y(), Vector::unit_z()],
+        };
+
+        Frame::new(Point::origin(), x, y).expect("Axes of a view span a plane")
+    }
+}
+
+/// Compute the visible lines of the parts, each part in its own layer
+///
+/// Lines are drawn where the triangles of a part meet at a sharp angle, along
+/// the boundary of the part's mesh, and where the mesh turns away from the
+/// viewer. Each of those lines is drawn where no triangle of any part is in
+/// front of it.
+pub fn layers(parts: &[Part], view: View) -> Vec<Layer> {
+    let frame = view.frame();
+
+    let occluders = parts
+        .iter()
+        .flat_map(|part| part.mesh.triangles())
+        .filter_map(|triangle| Occluder::new(triangle.inner.points(), &frame))
+        .collect::<Vec<_>>();
+
+    // Depths and positions that differ by less than this are considered the
+    // same.
+    let resolution = {
+        let points = parts.iter().flat_map(|part| part.mesh.vertices());
+        let aabb = Aabb::<3>::from_points(points);
+        let size = (aabb.max - aabb.min).magnitude();
+
+        if size == Scalar::ZERO {
+            Scalar::ONE
+        } else {
+            size * 1e-9
+        }
+    };
+
+    parts
+        .iter()
+        .map(|part| {
+            let segments = lines(part, &frame)
+                .into_iter()
+                .flat_map(|line| {
+                    visible_segments(line, &frame, &occluders, resolution)
+                })
+                .map(|segment| segment.map(|point| frame.point(point)))
+                .collect();
+
+            Layer {
+                name: layer_name(part.name),
+                entities: chain(segments, resolution),
+            }
+        })
+        .collect()
+}
+
+/// Find the edges of a part's mesh that are drawn, if visible
+fn lines(part: &Part, frame: &Frame) -> Vec<[Point<3>; 2]> {
+    let mut normals_by_edge: BTreeMap<_, Vec<_>> = BTreeMap::new();
+    for triangle in part.mesh.triangles() {
+        let points = triangle.inner.points();
+        let normal = triangle.inner.normal();
+
+        for i in 0..3 {
+            let mut edge = [points[i], points[(i + 1) % 3]];
+            edge.sort();
+
+            normals_by_edge.entry(edge).or_default().push(normal);
+        }
+    }
+
+    let max_cos = Scalar::from(CREASE_ANGLE.to_radians().cos());
+    let towards_viewer = |normal: &Vector<3>| {
+        frame.distance(Point::origin() + *normal) > Scalar::ZERO
+    };
+
+    normals_by_edge
+        .into_iter()
+        .filter(|(_, normals)| match normals.as_slice() {
+            [a, b] => {
+                let is_sharp = a.dot(b) < max_cos;
+                let is_silhouette = towards_viewer(a) != towards_viewer(b);
+
+                is_sharp || is_silhouette
+            }
+            _ => true,
+        })
+        .map(|(edge, _)| edge)
+        .collect()
+}
+
+/// A triangle, as seen from the viewer
+struct Occluder {
+    points: [Point<2>; 3],
+    depths: [Scalar; 3],
+    aabb: Aabb<2>,
+    max_depth: Scalar,
+}
+
+impl Occluder {
+    /// Create an occluder from a triangle
+    ///
+    /// Returns `None`, if the triangle is seen edge-on, in which case it can't
+    /// hide anything.
+    fn new(points: [Point<3>; 3], frame: &Frame) -> Option<Self> {
+        let depths = points.map(|point| frame.distance(point));
+        let points = points.map(|point| frame.point(point));
+
+        let [a, b, c] = points;
+        if (b - a).cross2d(&(c - a)) == Scalar::ZERO {
+            return None;
+        }
+
+        let aabb = Aabb::<2>::from_points(points);
+        let max_depth = depths.into_iter().max().expect("Triangle has points");
+
+        Some(Self {
+            points,
+            depths,
+            aabb,
+            max_depth,
+        })
+    }
+
+    /// Compute the barycentric coordinates of a point
+    ///
+    /// The result is also defined for points outside of the triangle.
+    fn barycentric(&self, point: Point<2>) -> [Scalar; 3] {
+        let [a, b, c] = self.points;
+        let area = (b - a).cross2d(&(c - a));
+
+        let u = (c - b).cross2d(&(point - b)) / area;
+        let v = (a - c).cross2d(&(point - c)) / area;
+
+        [u, v, Scalar::ONE - u - v]
+    }
+
+    /// Compute the depth of the triangle's plane at a point
+    fn depth_at(&self, point: Point<2>) -> Scalar {
+        self.barycentric(point)
+            .into_iter()
+            .zip(self.depths)
+            .fold(Scalar::ZERO, |sum, (weight, depth)| sum + weight * depth)
+    }
+}
+
+/// Compute the parts of a line that no occluder is in front of
+fn visible_segments(
+    line: [Point<3>; 2],
+    frame: &Frame,
+    occluders: &[Occluder],
+    resolution: Scalar,
+) -> Vec<[Point<3>; 2]> {
+    let points = line.map(|point| frame.point(point));
+    let depths = line.map(|point| frame.distance(point));
+
+    let mut hidden = occluders
+        .iter()
+        .filter_map(|occluder| {
+            hidden_interval(points, depths, occluder, resolution)
+        })
+        .collect::<Vec<_>>();
+    hidden.sort_by_key(|&[min, _]| min);
+
+    let mut visible = Vec::new();
+    let mut t = Scalar::ZERO;
+    for [min, max] in hidden {
+        if min > t {
+            visible.push([t, min]);
+        }
+        t = t.max(max);
+    }
+    if t < Scalar::ONE {
+        visible.push([t, Scalar::ONE]);
+    }
+
+    let [start, end] = line;
+    let length = (points[1] - points[0]).magnitude();
+    visible
+        .into_iter()
+        .filter(|&[min, max]| (max - min) * length > resolution)
+        .map(|interval| interval.map(|t| start + (end - start) * t))
+        .collect()
+}
+
+/// Compute the interval of a line, in which an occluder is in front of it
+///
+/// The line is given by the projections of its end points, and their depths.
+/// The interval is given in terms of the parameter of the line, which goes
+/// from 0 at its start to 1 at its end.
+fn hidden_interval(
+    points: [Point<2>; 2],
+    depths: [Scalar; 2],
+    occluder: &Occluder,
+    resolution: Scalar,
+) -> Option<[Scalar; 2]> {
+    let aabb = Aabb::<2>::from_points(points);
+    let overlaps = (0..2).all(|i| {
+        aabb.min.coords.components[i] <= occluder.aabb.max.coords.components[i]
+            && aabb.max.coords.components[i]
+                >= occluder.aabb.min.coords.components[i]
+    });
+    let min_depth = depths.into_iter().min().expect("Line has points");
+    if !overlaps || occluder.max_depth <= min_depth + resolution {
+        return None;
+    }
+
+    // Barycentric coordinates and depths vary linearly along the line, so the
+    // interval can be narrowed down by one linear condition after the other.
+    let mut interval = [Scalar::ZERO, Scalar::ONE];
+
+    // Points on the boundary of the occluder count as inside, so lines that
+    // are behind the common edge of two triangles are hidden by them.
+    let barycentric = points.map(|point| occluder.barycentric(point));
+    for i in 0..3 {
+        let values = barycentric.map(|coords| coords[i]);
+        narrow(&mut interval, values, Scalar::from(-EPSILON))?;
+    }
+
+    let in_front = [0, 1].map(|i| occluder.depth_at(points[i]) - depths[i]);
+    narrow(&mut interval, in_front, resolution)?;
+
+    Some(interval)
+}
+
+/// Narrow down an interval to where a linear function exceeds a threshold
+///
+/// The function is given by its values at 0 and 1. Returns `None`, if the
+/// interval becomes empty.
+fn narrow(
+    interval: &mut [Scalar; 2],
+    values: [Scalar; 2],
+    threshold: Scalar,
+) -> Option<()> {
+    let [a, b] = values;
+
+    if a <= threshold && b <= threshold {
+        return None;
+    }
+    if a <= threshold || b <= threshold {
+        let t = (threshold - a) / (b - a);
+
+        if a > threshold {
+            interval[1] = interval[1].min(t);
+        } else {
+            interval[0] = interval[0].max(t);
+        }
+    }
+
+    if interval[0] >= interval[1] {
+        return None;
+    }
+
+    Some(())
+}
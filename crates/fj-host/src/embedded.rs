@@ -0,0 +1,104 @@
+use std::{
+    fs, io,
+    path::{Path, PathBuf},
+};
+
+/// A model whose source code is embedded into the application
+///
+/// Models are loaded from the file system. Before an embedded model can be
+/// loaded, it needs to be unpacked into a directory, using
+/// [`EmbeddedModel::unpack`]. The resulting crate can then be loaded like any
+/// other model, using [`Model::new`].
+///
+/// [`Model::new`]: crate::Model::new
+#[derive(Clone, Copy, Debug)]
+pub struct EmbeddedModel {
+    /// The name of the model
+    ///
+    /// Used as the name of the model's package, and of the directory it is
+    /// unpacked into.
+    pub name: &'static str,
+
+    /// The files that make up the model, apart from `Cargo.toml`
+    ///
+    /// Each file is represented by its path, relative to the root directory of
+    /// the model's crate, and its contents. `Cargo.toml` is generated when the
+    /// model is unpacked, as the model's dependency on `fj` depends on where
+    /// it is unpacked.
+    pub files: &'static [(&'static str, &'static str)],
+}
+
+impl EmbeddedModel {
+    /// Unpack the model into the given directory
+    ///
+    /// The model's crate is placed in a subdirectory named after the model.
+    /// Returns the path to that crate, which can be passed to [`Model::new`].
+    ///
+    /// Files that already exist are left as they are, except for `Cargo.toml`,
+    /// which is always regenerated. This way, the user can make changes to the
+    /// model, without those being overwritten when it is opened again.
+    ///
+    /// [`Model::new`]: crate::Model::new
+    pub fn unpack(
+        &self,
+        dir: impl AsRef<Path>,
+        fj: &FjDependency,
+    ) -> io::Result<PathBuf> {
+        let crate_dir = dir.as_ref().join(self.name);
+
+        for (path, contents) in self.files {
+            let path = crate_dir.join(path);
+            if path.exists() {
+                continue;
+            }
+
+            if let Some(parent) = path.parent() {
+                fs::create_dir_all(parent)?;
+            }
+            fs::write(path, contents)?;
+        }
+
+        fs::create_dir_all(&crate_dir)?;
+        fs::write(crate_dir.join("Cargo.toml"), self.manifest(fj))?;
+
+        Ok(crate_dir)
+    }
+
+    fn manifest(&self, fj: &FjDependency) -> String {
+        let fj = match fj {
+            // TOML basic strings use the same escape sequences as Rust, for
+            // all characters that can reasonably appear in a path.
+            FjDependency::Path(path) => {
+                format!("path = {:?}", path.display().to_string())
+            }
+            FjDependency::Version(version) => {
+                format!("version = {:?}", format!("={version}"))
+            }
+        };
+
+        format!(
+            "[package]\n\
+            name = {name:?}\n\
+            version = \"0.1.0\"\n\
+            edition = \"2021\"\n\
+            \n\
+            # Don't become part of a workspace that the model happens to be\n\
+            # unpacked into.\n\
+            [workspace]\n\
+            \n\
+            [dependencies.fj]\n\
+            {fj}\n",
+            name = self.name,
+        )
+    }
+}
+
+/// Where an unpacked [`EmbeddedModel`] gets the `fj` crate from
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum FjDependency {
+    /// Use the `fj` crate at the given path
+    Path(PathBuf),
+
+    /// Use the given version of the `fj` crate from crates.io
+    Version(String),
+}
@@ -1,13 +1,26 @@
-use std::thread;
+use std::{
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+    thread::{self, JoinHandle},
+};
 
-use crossbeam_channel::{Receiver, SendError, Sender};
+use crossbeam_channel::{select, Receiver, SendError, Sender};
+use tracing::error;
 
 use crate::{Error, Evaluation, Model};
 
 /// Evaluates a model in a background thread
+///
+/// The thread is stopped when the `Evaluator` is shut down or dropped. Any
+/// evaluation that is in progress at that point is cancelled.
 pub struct Evaluator {
     trigger_tx: Sender<TriggerEvaluation>,
     event_rx: Receiver<ModelEvent>,
+    shutdown_tx: Option<Sender<Shutdown>>,
+    cancelled: Arc<AtomicBool>,
+    thread: Option<JoinHandle<()>>,
 }
 
 impl Evaluator {
@@ -15,42 +28,51 @@ impl Evaluator {
     pub fn from_model(model: Model) -> Self {
         let (event_tx, event_rx) = crossbeam_channel::bounded(0);
         let (trigger_tx, trigger_rx) = crossbeam_channel::bounded(0);
+        let (shutdown_tx, shutdown_rx) = crossbeam_channel::bounded(0);
+        let cancelled = Arc::new(AtomicBool::new(false));
 
-        thread::spawn(move || {
-            while let Ok(TriggerEvaluation) = trigger_rx.recv() {
-                if let Err(SendError(_)) =
-                    event_tx.send(ModelEvent::ChangeDetected)
-                {
-                    break;
-                }
+        let thread = thread::spawn({
+            let cancelled = cancelled.clone();
 
-                let evaluation = match model.evaluate() {
-                    Ok(evaluation) => evaluation,
-                    Err(err) => {
-                        if let Err(SendError(_)) =
-                            event_tx.send(ModelEvent::Error(err))
-                        {
+            move || loop {
+                // The shutdown channel is never sent to. It is disconnected,
+                // once the `Evaluator` is shut down.
+                select! {
+                    recv(trigger_rx) -> trigger => {
+                        if trigger.is_err() {
                             break;
                         }
-                        continue;
                     }
-                };
+                    recv(shutdown_rx) -> _ => break,
+                }
+                if cancelled.load(Ordering::SeqCst) {
+                    break;
+                }
 
                 if let Err(SendError(_)) =
-                    event_tx.send(ModelEvent::Evaluation(evaluation))
+                    event_tx.send(ModelEvent::ChangeDetected)
                 {
                     break;
+                }
+
+                let event = match model.evaluate_unless_cancelled(&cancelled) {
+                    Ok(evaluation) => ModelEvent::Evaluation(evaluation),
+                    Err(Error::Cancelled) => break,
+                    Err(err) => ModelEvent::Error(err),
                 };
-            }
 
-            // The channel is disconnected, which means this instance of
-            // `Evaluator`, as well as all `Sender`s created from it, have been
-            // dropped. We're done.
+                if let Err(SendError(_)) = event_tx.send(event) {
+                    break;
+                }
+            }
         });
 
         Self {
-            event_rx,
             trigger_tx,
+            event_rx,
+            shutdown_tx: Some(shutdown_tx),
+            cancelled,
+            thread: Some(thread),
         }
     }
 
@@ -63,12 +85,54 @@ impl Evaluator {
     pub fn events(&self) -> Receiver<ModelEvent> {
         self.event_rx.clone()
     }
+
+    /// Shut down the evaluator
+    ///
+    /// Cancels the evaluation that is currently in progress, if any, and waits
+    /// for the background thread to finish. Returns the events that the thread
+    /// emitted in the meantime, which have not been received otherwise.
+    ///
+    /// Once the evaluator is shut down, the channels returned by
+    /// [`Evaluator::trigger`] and [`Evaluator::events`] are disconnected.
+    pub fn shutdown(mut self) -> Vec<ModelEvent> {
+        self.stop()
+    }
+
+    fn stop(&mut self) -> Vec<ModelEvent> {
+        let Some(thread) = self.thread.take() else {
+            return Vec::new();
+        };
+
+        self.cancelled.store(true, Ordering::SeqCst);
+        self.shutdown_tx = None;
+
+        // The thread might be waiting to send an event. Receive events until
+        // it's done and the channel is disconnected, so it isn't blocked
+        // forever.
+        let events = self.event_rx.iter().collect();
+
+        if thread.join().is_err() {
+            error!("Evaluator thread panicked");
+        }
+
+        events
+    }
+}
+
+impl Drop for Evaluator {
+    fn drop(&mut self) {
+        self.stop();
+    }
 }
 
 /// Command received by [`Evaluator`] through its channel
 pub struct TriggerEvaluation;
 
+/// Signals the thread of [`Evaluator`] to shut down, by being disconnected
+enum Shutdown {}
+
 /// An event emitted by [`Evaluator`]
+#[allow(clippy::large_enum_variant)]
 pub enum ModelEvent {
     /// A change in the model has been detected
     ChangeDetected,
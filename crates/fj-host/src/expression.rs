@@ -0,0 +1,308 @@
+//! Simple arithmetic expressions in parameter values
+//!
+//! See [`Parameters::evaluate`].
+//!
+//! [`Parameters::evaluate`]: crate::Parameters::evaluate
+
+use std::{iter::Peekable, str::Chars};
+
+/// An arithmetic expression
+///
+/// Expressions consist of numbers, names of other parameters, the operators
+/// `+`, `-`, `*`, `/`, and `^`, and parentheses.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Expression {
+    /// A number
+    Number(f64),
+
+    /// The value of another parameter
+    Parameter(String),
+
+    /// A negated expression
+    Negate(Box<Expression>),
+
+    /// An operator applied to two expressions
+    Binary(Box<Expression>, Operator, Box<Expression>),
+}
+
+impl Expression {
+    /// Parse an expression
+    ///
+    /// Returns `None`, if the input is not a valid expression.
+    pub fn parse(input: &str) -> Option<Self> {
+        let mut tokens = Tokens::new(input).collect::<Option<Vec<_>>>()?;
+        tokens.reverse();
+
+        let expression = parse_sum(&mut tokens)?;
+
+        if tokens.is_empty() {
+            Some(expression)
+        } else {
+            None
+        }
+    }
+
+    /// Evaluate the expression
+    ///
+    /// Parameters are looked up using `parameter`.
+    pub fn evaluate<E>(
+        &self,
+        parameter: &mut impl FnMut(&str) -> Result<f64, E>,
+    ) -> Result<f64, E> {
+        let value = match self {
+            Self::Number(number) => *number,
+            Self::Parameter(name) => parameter(name)?,
+            Self::Negate(expression) => -expression.evaluate(parameter)?,
+            Self::Binary(a, operator, b) => {
+                let a = a.evaluate(parameter)?;
+                let b = b.evaluate(parameter)?;
+
+                match operator {
+                    Operator::Add => a + b,
+                    Operator::Subtract => a - b,
+                    Operator::Multiply => a * b,
+                    Operator::Divide => a / b,
+                    Operator::Power => a.powf(b),
+                }
+            }
+        };
+
+        Ok(value)
+    }
+}
+
+/// An operator in an [`Expression`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Operator {
+    /// Addition
+    Add,
+
+    /// Subtraction
+    Subtract,
+
+    /// Multiplication
+    Multiply,
+
+    /// Division
+    Divide,
+
+    /// Exponentiation
+    Power,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Number(f64),
+    Name(String),
+    Operator(Operator),
+    Open,
+    Close,
+}
+
+struct Tokens<'a> {
+    chars: Peekable<Chars<'a>>,
+}
+
+impl<'a> Tokens<'a> {
+    fn new(input: &'a str) -> Self {
+        Self {
+            chars: input.chars().peekable(),
+        }
+    }
+
+    fn take_while(&mut self, token: &mut String, f: impl Fn(char) -> bool) {
+        while let Some(&c) = self.chars.peek() {
+            if !f(c) {
+                break;
+            }
+            token.push(c);
+            self.chars.next();
+        }
+    }
+}
+
+impl Iterator for Tokens<'_> {
+    /// A token, or `None`, if the input contains an invalid character
+    type Item = Option<Token>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while self.chars.next_if(|c| c.is_whitespace()).is_some() {}
+
+        let c = *self.chars.peek()?;
+
+        let token = if c.is_ascii_digit() || c == '.' {
+            let mut number = String::new();
+            self.take_while(&mut number, |c| c.is_ascii_digit() || c == '.');
+
+            // Exponents, as in `1e-3`
+            if let Some(e) = self.chars.next_if(|&c| c == 'e' || c == 'E') {
+                number.push(e);
+                if let Some(sign) =
+                    self.chars.next_if(|&c| c == '-' || c == '+')
+                {
+                    number.push(sign);
+                }
+                self.take_while(&mut number, |c| c.is_ascii_digit());
+            }
+
+            number.parse().ok().map(Token::Number)
+        } else if c.is_alphabetic() || c == '_' {
+            let mut name = String::new();
+            self.take_while(&mut name, |c| c.is_alphanumeric() || c == '_');
+            Some(Token::Name(name))
+        } else {
+            self.chars.next();
+            match c {
+                '+' => Some(Token::Operator(Operator::Add)),
+                '-' => Some(Token::Operator(Operator::Subtract)),
+                '*' => Some(Token::Operator(Operator::Multiply)),
+                '/' => Some(Token::Operator(Operator::Divide)),
+                '^' => Some(Token::Operator(Operator::Power)),
+                '(' => Some(Token::Open),
+                ')' => Some(Token::Close),
+                _ => None,
+            }
+        };
+
+        Some(token)
+    }
+}
+
+// The parsing functions below take the tokens in reverse order, so the next
+// token can be popped off the end.
+
+fn parse_sum(tokens: &mut Vec<Token>) -> Option<Expression> {
+    let mut expression = parse_product(tokens)?;
+
+    while let Some(&Token::Operator(
+        operator @ (Operator::Add | Operator::Subtract),
+    )) = tokens.last()
+    {
+        tokens.pop();
+        let b = parse_product(tokens)?;
+        expression = Expression::Binary(expression.into(), operator, b.into());
+    }
+
+    Some(expression)
+}
+
+fn parse_product(tokens: &mut Vec<Token>) -> Option<Expression> {
+    let mut expression = parse_negation(tokens)?;
+
+    while let Some(&Token::Operator(
+        operator @ (Operator::Multiply | Operator::Divide),
+    )) = tokens.last()
+    {
+        tokens.pop();
+        let b = parse_negation(tokens)?;
+        expression = Expression::Binary(expression.into(), operator, b.into());
+    }
+
+    Some(expression)
+}
+
+fn parse_negation(tokens: &mut Vec<Token>) -> Option<Expression> {
+    if tokens.last() == Some(&Token::Operator(Operator::Subtract)) {
+        tokens.pop();
+        let expression = parse_negation(tokens)?;
+        return Some(Expression::Negate(expression.into()));
+    }
+
+    parse_power(tokens)
+}
+
+fn parse_power(tokens: &mut Vec<Token>) -> Option<Expression> {
+    let base = parse_atom(tokens)?;
+
+    if tokens.last() == Some(&Token::Operator(Operator::Power)) {
+        tokens.pop();
+
+        // Exponentiation is right-associative, and binds tighter than a
+        // negation of its base, but not of its exponent.
+        let exponent = parse_negation(tokens)?;
+        return Some(Expression::Binary(
+            base.into(),
+            Operator::Power,
+            exponent.into(),
+        ));
+    }
+
+    Some(base)
+}
+
+fn parse_atom(tokens: &mut Vec<Token>) -> Option<Expression> {
+    match tokens.pop()? {
+        Token::Number(number) => Some(Expression::Number(number)),
+        Token::Name(name) => Some(Expression::Parameter(name)),
+        Token::Open => {
+            let expression = parse_sum(tokens)?;
+            match tokens.pop()? {
+                Token::Close => Some(expression),
+                _ => None,
+            }
+        }
+        Token::Operator(_) | Token::Close => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Expression;
+
+    fn evaluate(input: &str) -> f64 {
+        Expression::parse(input)
+            .unwrap()
+            .evaluate(&mut |name| match name {
+                "x" => Ok(3.),
+                _ => Err(()),
+            })
+            .unwrap()
+    }
+
+    #[test]
+    fn precedence() {
+        assert_eq!(evaluate("1 + 2 * 3"), 7.);
+        assert_eq!(evaluate("(1 + 2) * 3"), 9.);
+        assert_eq!(evaluate("1 - 2 - 3"), -4.);
+        assert_eq!(evaluate("8 / 4 / 2"), 1.);
+        assert_eq!(evaluate("2 * 3 ^ 2"), 18.);
+    }
+
+    #[test]
+    fn power_is_right_associative() {
+        assert_eq!(evaluate("2 ^ 3 ^ 2"), 512.);
+        assert_eq!(evaluate("(2 ^ 3) ^ 2"), 64.);
+    }
+
+    #[test]
+    fn unary_minus() {
+        assert_eq!(evaluate("-3"), -3.);
+        assert_eq!(evaluate("--3"), 3.);
+        assert_eq!(evaluate("-2 ^ 2"), -4.);
+        assert_eq!(evaluate("2 ^ -1"), 0.5);
+        assert_eq!(evaluate("1 - -1"), 2.);
+    }
+
+    #[test]
+    fn numbers() {
+        assert_eq!(evaluate("1.5e3"), 1500.);
+        assert_eq!(evaluate("2.5E-1"), 0.25);
+    }
+
+    #[test]
+    fn parameters() {
+        assert_eq!(evaluate("x"), 3.);
+        assert_eq!(evaluate("2 * x + 1"), 7.);
+        assert!(Expression::parse("y")
+            .unwrap()
+            .evaluate(&mut |_| Err(()))
+            .is_err());
+    }
+
+    #[test]
+    fn invalid() {
+        for input in ["", "1 +", "(1", "1)", "1 2", "1 # 2", "* 2"] {
+            assert_eq!(Expression::parse(input), None, "{input}");
+        }
+    }
+}
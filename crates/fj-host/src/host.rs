@@ -3,9 +3,13 @@ use crossbeam_channel::Receiver;
 use crate::{Error, Evaluator, Model, ModelEvent, Watcher};
 
 /// A Fornjot model host
+///
+/// Dropping the host shuts it down, like [`Host::shutdown`] does.
 pub struct Host {
+    // The evaluator needs to be shut down before the watcher. The fields are
+    // dropped in the order of declaration.
     evaluator: Evaluator,
-    _watcher: Watcher,
+    watcher: Watcher,
 }
 
 impl Host {
@@ -18,14 +22,22 @@ impl Host {
         let evaluator = Evaluator::from_model(model);
         let watcher = Watcher::watch_model(&watch_path, &evaluator)?;
 
-        Ok(Self {
-            evaluator,
-            _watcher: watcher,
-        })
+        Ok(Self { evaluator, watcher })
     }
 
     /// Access a channel with evaluation events
     pub fn events(&self) -> Receiver<ModelEvent> {
         self.evaluator.events()
     }
+
+    /// Shut down the host
+    ///
+    /// Cancels the evaluation in progress, stops watching the model, and waits
+    /// for all threads to finish. Returns the events that were emitted in the
+    /// meantime, and have not been received from [`Host::events`].
+    pub fn shutdown(self) -> Vec<ModelEvent> {
+        let events = self.evaluator.shutdown();
+        self.watcher.shutdown();
+        events
+    }
 }
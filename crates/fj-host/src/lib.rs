@@ -15,7 +15,9 @@
 
 #![warn(missing_docs)]
 
+mod embedded;
 mod evaluator;
+mod expression;
 mod host;
 mod model;
 mod parameters;
@@ -23,9 +25,10 @@ mod platform;
 mod watcher;
 
 pub use self::{
+    embedded::{EmbeddedModel, FjDependency},
     evaluator::{Evaluator, ModelEvent},
     host::Host,
     model::{Error, Evaluation, Model},
-    parameters::Parameters,
+    parameters::{ParameterError, Parameters},
     watcher::Watcher,
 };
@@ -1,14 +1,17 @@
 use std::{
-    io,
+    io::{self, Read},
     path::{Path, PathBuf},
-    process::Command,
+    process::{Command, Stdio},
     str,
+    sync::atomic::{AtomicBool, Ordering},
+    thread,
+    time::{Duration, Instant},
 };
 
 use fj::{abi, version::Version};
 use tracing::{debug, warn};
 
-use crate::{platform::HostPlatform, Parameters};
+use crate::{platform::HostPlatform, ParameterError, Parameters};
 
 /// Represents a Fornjot model
 pub struct Model {
@@ -16,6 +19,7 @@ pub struct Model {
     lib_path: PathBuf,
     manifest_path: PathBuf,
     parameters: Parameters,
+    seed: u64,
 }
 
 impl Model {
@@ -51,9 +55,45 @@ impl Model {
             lib_path,
             manifest_path: pkg.manifest_path.as_std_path().to_path_buf(),
             parameters,
+            seed: 0,
         })
     }
 
+    /// Pass a different seed for random numbers to the model
+    ///
+    /// The seed is the same for every evaluation, so models that generate
+    /// random numbers from it produce the same shape each time. It is 0, unless
+    /// a different one is set here.
+    pub fn with_seed(mut self, seed: u64) -> Self {
+        self.seed = seed;
+        self
+    }
+
+    /// Access the root directory of the model's crate
+    pub fn path(&self) -> &Path {
+        self.src_path
+            .parent()
+            .expect("Source directory is located within crate directory")
+    }
+
+    /// Compute the path that a thumbnail of the model is written to
+    ///
+    /// Thumbnails are PNG images in the root directory of the model's crate,
+    /// where file browsers and model galleries can pick them up.
+    pub fn thumbnail_path(&self) -> PathBuf {
+        self.path().join("thumbnail.png")
+    }
+
+    /// Access the parameters that are passed to the model
+    pub fn parameters(&self) -> &Parameters {
+        &self.parameters
+    }
+
+    /// Access the seed for random numbers that is passed to the model
+    pub fn seed(&self) -> u64 {
+        self.seed
+    }
+
     /// Access the path that needs to be watched for changes
     pub fn watch_path(&self) -> PathBuf {
         self.src_path.clone()
@@ -61,24 +101,25 @@ impl Model {
 
     /// Evaluate the model
     pub fn evaluate(&self) -> Result<Evaluation, Error> {
-        let manifest_path = self.manifest_path.display().to_string();
+        self.evaluate_unless_cancelled(&AtomicBool::new(false))
+    }
 
-        let cargo_output = Command::new("cargo")
-            .arg("rustc")
-            .args(["--manifest-path", &manifest_path])
-            .args(["--crate-type", "cdylib"])
-            .output()?;
+    /// Evaluate the model, unless the evaluation is cancelled
+    ///
+    /// The model is compiled by Cargo, which is killed as soon as `cancelled`
+    /// is set. [`Error::Cancelled`] is returned in that case. Once the model
+    /// has been compiled, its evaluation can no longer be cancelled.
+    pub fn evaluate_unless_cancelled(
+        &self,
+        cancelled: &AtomicBool,
+    ) -> Result<Evaluation, Error> {
+        let start = Instant::now();
 
-        if !cargo_output.status.success() {
-            let output =
-                String::from_utf8(cargo_output.stderr).unwrap_or_else(|_| {
-                    String::from("Failed to fetch command output")
-                });
+        let parameters = self.parameters.evaluate()?;
 
-            return Err(Error::Compile { output });
-        }
+        let cargo_output = self.compile(cancelled)?;
 
-        let seconds_taken = str::from_utf8(&cargo_output.stderr)
+        let seconds_taken = str::from_utf8(&cargo_output)
             .unwrap()
             .rsplit_once(' ')
             .unwrap()
@@ -146,7 +187,7 @@ impl Model {
                 .get(abi::INIT_FUNCTION_NAME.as_bytes())
                 .map_err(Error::LoadingInit)?;
 
-            let mut host = Host::new(&self.parameters);
+            let mut host = Host::new(&parameters, self.seed);
 
             match init(&mut abi::Host::from(&mut host)) {
                 abi::ffi_safe::Result::Ok(_metadata) => {}
@@ -157,16 +198,72 @@ impl Model {
 
             let model = host.take_model().ok_or(Error::NoModelRegistered)?;
 
+            check_capabilities(&model.metadata().capabilities)?;
+
             model.shape(&host).map_err(Error::Shape)?
         };
 
         Ok(Evaluation {
             shape,
             compile_time: seconds_taken.into(),
+            evaluation_time: start.elapsed(),
         })
     }
 }
 
+impl Model {
+    /// Compile the model, returning the output of Cargo
+    fn compile(&self, cancelled: &AtomicBool) -> Result<Vec<u8>, Error> {
+        let manifest_path = self.manifest_path.display().to_string();
+
+        let mut cargo = Command::new("cargo")
+            .arg("rustc")
+            .args(["--manifest-path", &manifest_path])
+            .args(["--crate-type", "cdylib"])
+            .stdout(Stdio::null())
+            .stderr(Stdio::piped())
+            .spawn()?;
+
+        // Cargo blocks once the pipe is full, so its output needs to be read
+        // while it's running.
+        let mut stderr = cargo.stderr.take().expect("Output is piped");
+        let output = thread::spawn(move || {
+            let mut output = Vec::new();
+            stderr.read_to_end(&mut output).map(|_| output)
+        });
+
+        let status = loop {
+            if let Some(status) = cargo.try_wait()? {
+                break status;
+            }
+
+            if cancelled.load(Ordering::SeqCst) {
+                // The processes that Cargo started might keep running for a
+                // bit, and keep the pipe open. The thread reading from it
+                // finishes on its own, once they're done, so it's not joined
+                // here.
+                cargo.kill()?;
+                cargo.wait()?;
+                return Err(Error::Cancelled);
+            }
+
+            thread::sleep(Duration::from_millis(10));
+        };
+
+        let output = output.join().expect("Failed to read Cargo output")?;
+
+        if !status.success() {
+            let output = String::from_utf8(output).unwrap_or_else(|_| {
+                String::from("Failed to fetch command output")
+            });
+
+            return Err(Error::Compile { output });
+        }
+
+        Ok(output)
+    }
+}
+
 /// The result of evaluating a model
 ///
 /// See [`Model::evaluate`].
@@ -176,17 +273,22 @@ pub struct Evaluation {
 
     /// The time it took to compile the shape, from the Cargo output
     pub compile_time: String,
+
+    /// The time it took to evaluate the model, including its compilation
+    pub evaluation_time: Duration,
 }
 
 pub struct Host<'a> {
     args: &'a Parameters,
+    seed: u64,
     model: Option<Box<dyn fj::models::Model>>,
 }
 
 impl<'a> Host<'a> {
-    pub fn new(parameters: &'a Parameters) -> Self {
+    pub fn new(parameters: &'a Parameters, seed: u64) -> Self {
         Self {
             args: parameters,
+            seed,
             model: None,
         }
     }
@@ -206,6 +308,63 @@ impl<'a> fj::models::Context for Host<'a> {
     fn get_argument(&self, name: &str) -> Option<&str> {
         self.args.get(name).map(|s| s.as_str())
     }
+
+    fn seed(&self) -> u64 {
+        self.seed
+    }
+}
+
+/// Check that the host provides all capabilities that a model requires
+///
+/// This happens before the model is evaluated, so that missing capabilities
+/// are reported right away, instead of causing a confusing error in the middle
+/// of the evaluation.
+fn check_capabilities(
+    capabilities: &fj::models::Capabilities,
+) -> Result<(), Error> {
+    let mut missing = Vec::new();
+
+    // The kernel doesn't support boolean operations between solids, and there
+    // is no text rendering that would make use of fonts. Once those are
+    // added, these checks need to be updated.
+    if capabilities.needs_booleans {
+        missing.push("boolean operations between solids".to_owned());
+    }
+    if capabilities.needs_fonts {
+        missing.push("fonts".to_owned());
+    }
+
+    if let Some(min_version) = &capabilities.min_kernel_version {
+        let kernel_version = fj::version::VERSION_PKG.to_string();
+
+        let is_compatible = match (
+            cargo_metadata::semver::Version::parse(min_version),
+            cargo_metadata::semver::Version::parse(&kernel_version),
+        ) {
+            (Ok(min_version), Ok(kernel_version)) => {
+                kernel_version >= min_version
+            }
+            _ => false,
+        };
+
+        if !is_compatible {
+            missing.push(format!(
+                "kernel version {min_version} (available: {kernel_version})"
+            ));
+        }
+    }
+
+    if capabilities.supports_cancellation {
+        // The host never cancels an evaluation, but there's no harm in the
+        // model being able to handle it.
+        debug!("Model supports cancellation");
+    }
+
+    if !missing.is_empty() {
+        return Err(Error::MissingCapabilities { missing });
+    }
+
+    Ok(())
 }
 
 fn package_associated_with_directory<'m>(
@@ -295,6 +454,10 @@ pub enum Error {
         output: String,
     },
 
+    /// The evaluation was cancelled before it finished
+    #[error("Evaluation of model was cancelled")]
+    Cancelled,
+
     /// I/O error while loading the model
     #[error("I/O error while loading model")]
     Io(#[from] io::Error),
@@ -308,10 +471,24 @@ pub enum Error {
     #[error("No model was registered")]
     NoModelRegistered,
 
+    /// The model requires capabilities that the host doesn't provide
+    #[error(
+        "Model requires capabilities that are not available: {}",
+        missing.join(", ")
+    )]
+    MissingCapabilities {
+        /// Descriptions of the missing capabilities
+        missing: Vec<String>,
+    },
+
     /// An error was returned from [`fj::models::Model::shape()`].
     #[error("Unable to determine the model's geometry")]
     Shape(#[source] fj::models::Error),
 
+    /// The parameters could not be evaluated
+    #[error("Error evaluating parameters")]
+    Parameters(#[from] ParameterError),
+
     /// Error while watching the model code for changes
     #[error("Error watching model for changes")]
     Notify(#[from] notify::Error),
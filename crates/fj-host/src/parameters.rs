@@ -3,6 +3,10 @@ use std::{
     ops::{Deref, DerefMut},
 };
 
+use thiserror::Error;
+
+use crate::expression::Expression;
+
 /// Parameters that are passed to a model.
 #[derive(Debug, Clone, Eq, PartialEq)]
 pub struct Parameters(pub HashMap<String, String>);
@@ -23,6 +27,90 @@ impl Parameters {
         self.0.insert(key.into(), value.to_string());
         self
     }
+
+    /// Evaluate the arithmetic expressions in the parameter values
+    ///
+    /// Values that start with `=` are expressions. Values like `=25.4*3`, or
+    /// `=height/2`, which refers to the parameter `height`, are replaced with
+    /// the number they evaluate to. All other values are left as they are, for
+    /// the model to interpret.
+    pub fn evaluate(&self) -> Result<Self, ParameterError> {
+        let mut evaluated = Self::empty();
+
+        for (key, value) in &self.0 {
+            let value = if value.starts_with('=') {
+                self.evaluate_parameter(key, &mut Vec::new())?.to_string()
+            } else {
+                value.clone()
+            };
+
+            evaluated.0.insert(key.clone(), value);
+        }
+
+        Ok(evaluated)
+    }
+
+    fn evaluate_parameter<'a>(
+        &'a self,
+        key: &'a str,
+        evaluating: &mut Vec<&'a str>,
+    ) -> Result<f64, ParameterError> {
+        let value = &self.0[key];
+
+        let Some(expression) = value.strip_prefix('=') else {
+            return value
+                .trim()
+                .parse()
+                .map_err(|_| ParameterError::NotANumber(key.to_owned()));
+        };
+        let expression = Expression::parse(expression)
+            .ok_or_else(|| ParameterError::Invalid(key.to_owned()))?;
+
+        if evaluating.contains(&key) {
+            return Err(ParameterError::Cycle(key.to_owned()));
+        }
+
+        evaluating.push(key);
+        let value =
+            expression.evaluate(&mut |name| match self.0.get_key_value(name) {
+                Some((name, _)) => self.evaluate_parameter(name, evaluating),
+                None => Err(ParameterError::Unknown(name.to_owned())),
+            });
+        evaluating.pop();
+
+        let value = value?;
+        if !value.is_finite() {
+            return Err(ParameterError::NotFinite(key.to_owned()));
+        }
+
+        Ok(value)
+    }
+}
+
+/// Error evaluating the expressions in [`Parameters`]
+///
+/// See [`Parameters::evaluate`].
+#[derive(Debug, Error)]
+pub enum ParameterError {
+    /// The value of a parameter is not a valid expression
+    #[error("Parameter `{0}` is not a valid expression")]
+    Invalid(String),
+
+    /// An expression refers to a parameter that doesn't exist
+    #[error("Parameter `{0}` does not exist")]
+    Unknown(String),
+
+    /// An expression refers to a parameter that doesn't have a numeric value
+    #[error("Parameter `{0}` is not a number")]
+    NotANumber(String),
+
+    /// A parameter refers to itself, directly or through other parameters
+    #[error("Parameter `{0}` refers to itself")]
+    Cycle(String),
+
+    /// The value of a parameter is infinite or not a number
+    #[error("Parameter `{0}` does not evaluate to a finite number")]
+    NotFinite(String),
 }
 
 impl Deref for Parameters {
@@ -38,3 +126,86 @@ impl DerefMut for Parameters {
         &mut self.0
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{ParameterError, Parameters};
+
+    fn evaluate(
+        parameters: &[(&str, &str)],
+    ) -> Result<Parameters, ParameterError> {
+        let mut values = Parameters::empty();
+        for (key, value) in parameters {
+            values.insert(*key, value);
+        }
+        values.evaluate()
+    }
+
+    #[test]
+    fn values_without_prefix_are_left_alone() {
+        let evaluated = evaluate(&[
+            ("date", "2024-01-01"),
+            ("range", "1-2"),
+            ("diameter", "25.40"),
+        ])
+        .unwrap();
+
+        assert_eq!(evaluated["date"], "2024-01-01");
+        assert_eq!(evaluated["range"], "1-2");
+        assert_eq!(evaluated["diameter"], "25.40");
+    }
+
+    #[test]
+    fn expressions_refer_to_parameters() {
+        let evaluated = evaluate(&[
+            ("height", "10"),
+            ("width", "=height / 2"),
+            ("depth", "=width * 3 + height"),
+        ])
+        .unwrap();
+
+        assert_eq!(evaluated["height"], "10");
+        assert_eq!(evaluated["width"], "5");
+        assert_eq!(evaluated["depth"], "25");
+    }
+
+    #[test]
+    fn invalid_expressions() {
+        assert!(matches!(
+            evaluate(&[("a", "=1 +")]),
+            Err(ParameterError::Invalid(key)) if key == "a"
+        ));
+        assert!(matches!(
+            evaluate(&[("a", "=b")]),
+            Err(ParameterError::Unknown(key)) if key == "b"
+        ));
+        assert!(matches!(
+            evaluate(&[("a", "=b"), ("b", "text")]),
+            Err(ParameterError::NotANumber(key)) if key == "b"
+        ));
+    }
+
+    #[test]
+    fn cycle() {
+        assert!(matches!(
+            evaluate(&[("a", "=a + 1")]),
+            Err(ParameterError::Cycle(key)) if key == "a"
+        ));
+        assert!(matches!(
+            evaluate(&[("a", "=b"), ("b", "=c"), ("c", "=a")]),
+            Err(ParameterError::Cycle(_))
+        ));
+    }
+
+    #[test]
+    fn not_finite() {
+        assert!(matches!(
+            evaluate(&[("a", "=1 / 0")]),
+            Err(ParameterError::NotFinite(key)) if key == "a"
+        ));
+        assert!(matches!(
+            evaluate(&[("a", "=(0 - 1) ^ 0.5")]),
+            Err(ParameterError::NotFinite(key)) if key == "a"
+        ));
+    }
+}
@@ -1,12 +1,22 @@
-use std::{collections::HashSet, ffi::OsStr, path::Path, thread};
+use std::{
+    collections::HashSet,
+    ffi::OsStr,
+    path::Path,
+    thread::{self, JoinHandle},
+};
 
 use notify::Watcher as _;
+use tracing::error;
 
 use crate::{evaluator::TriggerEvaluation, Error, Evaluator};
 
 /// Watches a model for changes, reloading it continually
+///
+/// Watching stops when the `Watcher` is shut down or dropped. Changes that are
+/// detected after the [`Evaluator`] has been shut down are ignored.
 pub struct Watcher {
-    _watcher: Box<dyn notify::Watcher>,
+    watcher: Option<Box<dyn notify::Watcher>>,
+    initial_trigger: Option<JoinHandle<()>>,
 }
 
 impl Watcher {
@@ -60,14 +70,9 @@ impl Watcher {
                         }
                     }
 
-                    // This will panic, if the other end is disconnected, which
-                    // is probably the result of a panic on that thread, or the
-                    // application is being shut down.
-                    //
-                    // Either way, not much we can do about it here.
-                    watch_tx
-                        .send(TriggerEvaluation)
-                        .expect("Channel is disconnected");
+                    // This fails, if the evaluator has been shut down. Then
+                    // there's nobody left who is interested in the change.
+                    let _ = watch_tx.send(TriggerEvaluation);
                 }
             },
         )?;
@@ -79,18 +84,40 @@ impl Watcher {
         // watching.
         //
         // This happens in a separate thread, because the channel is bounded and
-        // has no buffer.
-        //
-        // Will panic, if the receiving end has panicked. Not much we can do
-        // about that, if it happened.
-        thread::spawn(move || {
-            watch_tx_2
-                .send(TriggerEvaluation)
-                .expect("Channel is disconnected")
+        // has no buffer. Like above, failing to send just means that the
+        // evaluator has been shut down.
+        let initial_trigger = thread::spawn(move || {
+            let _ = watch_tx_2.send(TriggerEvaluation);
         });
 
         Ok(Self {
-            _watcher: Box::new(watcher),
+            watcher: Some(Box::new(watcher)),
+            initial_trigger: Some(initial_trigger),
         })
     }
+
+    /// Stop watching the model
+    ///
+    /// Waits until the initial evaluation has been triggered. If the
+    /// [`Evaluator`] is still running and busy, this blocks until it is ready
+    /// to receive the trigger, so it's best to shut it down first.
+    pub fn shutdown(mut self) {
+        self.stop();
+    }
+
+    fn stop(&mut self) {
+        self.watcher = None;
+
+        if let Some(initial_trigger) = self.initial_trigger.take() {
+            if initial_trigger.join().is_err() {
+                error!("Thread triggering initial evaluation panicked");
+            }
+        }
+    }
+}
+
+impl Drop for Watcher {
+    fn drop(&mut self) {
+        self.stop();
+    }
 }
@@ -0,0 +1,228 @@
+//! Turn the segments read from a DXF file into kernel objects
+//!
+//! Drawings don't have any notion of faces. They are just a collection of
+//! segments, in no particular order or direction. Those segments are chained
+//! into closed contours here, and contours that lie within other contours
+//! become the holes of the faces.
+
+use fj_interop::mesh::Color;
+use fj_kernel::{
+    algorithms::{
+        point_in_face::{polygon_segments, winding_number},
+        reverse::Reverse,
+    },
+    builder::CycleBuilder,
+    insert::Insert,
+    objects::{Cycle, Face, Objects, Sketch, Surface},
+    partial::HasPartial,
+    services::Service,
+    storage::Handle,
+};
+use fj_math::{signed_area, Point, Scalar, Vector};
+
+use crate::Error;
+
+use super::{Circle, Segment};
+
+/// Distances below this are considered zero
+///
+/// Drawings are written with limited precision. Segments that are supposed to
+/// connect, usually don't exactly.
+const TOLERANCE: f64 = 1e-6;
+
+/// Build a sketch from the segments and circles of a drawing
+pub fn sketch(
+    segments: Vec<Segment>,
+    circles: Vec<Circle>,
+    surface: Handle<Surface>,
+    objects: &mut Service<Objects>,
+) -> Result<Handle<Sketch>, Error> {
+    let mut contours = contours(segments)?;
+    contours.extend(circles.into_iter().map(Contour::Circle));
+
+    if contours.is_empty() {
+        return Err(Error::NoContours);
+    }
+
+    // A contour is a hole, if it's nested within an odd number of other
+    // contours. Contours can't intersect, so checking a single point of each
+    // is enough.
+    let polygons = contours.iter().map(Contour::polygon).collect::<Vec<_>>();
+    let containers = polygons
+        .iter()
+        .enumerate()
+        .map(|(i, polygon)| {
+            (0..polygons.len())
+                .filter(|&j| {
+                    j != i
+                        && winding_number(
+                            polygon_segments(&polygons[j]),
+                            polygon[0],
+                        ) != 0
+                })
+                .collect::<Vec<_>>()
+        })
+        .collect::<Vec<_>>();
+
+    let mut faces = Vec::new();
+    for (i, contour) in contours.iter().enumerate() {
+        let depth = containers[i].len();
+        if depth % 2 == 1 {
+            continue;
+        }
+
+        // The holes of this face are the contours that are nested exactly one
+        // level deeper, within it.
+        let interiors = (0..contours.len())
+            .filter(|&j| {
+                containers[j].len() == depth + 1 && containers[j].contains(&i)
+            })
+            .map(|j| {
+                cycle(&contours[j], surface.clone(), objects).reverse(objects)
+            })
+            .collect::<Vec<_>>();
+        let exterior = cycle(contour, surface.clone(), objects);
+
+        faces.push(
+            Face::new(exterior, interiors, Color::default()).insert(objects),
+        );
+    }
+
+    Ok(Sketch::new(faces).insert(objects))
+}
+
+/// A closed contour
+enum Contour {
+    /// A chain of segments, wound counter-clockwise
+    Segments(Vec<Segment>),
+
+    /// A full circle
+    Circle(Circle),
+}
+
+impl Contour {
+    /// Approximate the contour by a polygon
+    ///
+    /// The polygon is only good enough to determine how contours are nested.
+    fn polygon(&self) -> Vec<Point<2>> {
+        match self {
+            Self::Segments(segments) => segments
+                .iter()
+                .flat_map(|segment| match *segment {
+                    Segment::Line([start, _]) => vec![start],
+                    Segment::Arc([start, through, _]) => vec![start, through],
+                })
+                .collect(),
+            Self::Circle(circle) => (0..16)
+                .map(|i| {
+                    let angle = Scalar::TAU * i as f64 / 16.;
+                    let (sin, cos) = angle.sin_cos();
+                    circle.center + Vector::from([cos, sin]) * circle.radius
+                })
+                .collect(),
+        }
+    }
+}
+
+/// Chain the segments into closed contours
+fn contours(segments: Vec<Segment>) -> Result<Vec<Contour>, Error> {
+    let mut remaining = segments
+        .into_iter()
+        .filter(|segment| distance(segment.start(), segment.end()) > TOLERANCE)
+        .collect::<Vec<_>>();
+    remaining.reverse();
+
+    let mut contours = Vec::new();
+    while let Some(first) = remaining.pop() {
+        let start = first.start();
+        let mut chain = vec![first];
+
+        loop {
+            let end = chain[chain.len() - 1].end();
+            if chain.len() > 1 && distance(end, start) <= TOLERANCE {
+                break;
+            }
+
+            // Segments can be drawn in either direction, so they might need
+            // to be reversed to continue the chain.
+            let next = remaining.iter().enumerate().find_map(|(i, segment)| {
+                if distance(segment.start(), end) <= TOLERANCE {
+                    Some((i, *segment))
+                } else if distance(segment.end(), end) <= TOLERANCE {
+                    Some((i, segment.reversed()))
+                } else {
+                    None
+                }
+            });
+            let Some((i, segment)) = next else {
+                return Err(Error::OpenContour([end.u, end.v].map(Into::into)));
+            };
+            remaining.remove(i);
+
+            // Connected segments need to share their points exactly.
+            chain.push(segment.with_start(end));
+        }
+
+        let last = chain.len() - 1;
+        chain[last] = chain[last].with_end(start);
+
+        if signed_area(&Contour::Segments(chain.clone()).polygon())
+            < Scalar::ZERO
+        {
+            chain = chain.into_iter().rev().map(Segment::reversed).collect();
+        }
+
+        contours.push(Contour::Segments(chain));
+    }
+
+    Ok(contours)
+}
+
+/// Build the cycle of a contour
+///
+/// Like the contour, the cycle is wound counter-clockwise.
+fn cycle(
+    contour: &Contour,
+    surface: Handle<Surface>,
+    objects: &mut Service<Objects>,
+) -> Handle<Cycle> {
+    let mut cycle = Cycle::partial();
+
+    match contour {
+        Contour::Segments(segments) => {
+            for (i, segment) in segments.iter().enumerate() {
+                cycle = match *segment {
+                    Segment::Line(_) if i == segments.len() - 1 => {
+                        cycle.close_with_line_segment()
+                    }
+                    Segment::Line([start, end]) => {
+                        // A chain that's already started continues from its
+                        // last point.
+                        let points = if i == 0 { vec![start] } else { vec![] };
+                        cycle.with_poly_chain_from_points(
+                            surface.clone(),
+                            points.into_iter().chain([end]),
+                        )
+                    }
+                    Segment::Arc(points) => {
+                        cycle.with_arc_from_points(surface.clone(), points)
+                    }
+                };
+            }
+        }
+        Contour::Circle(circle) => {
+            cycle = cycle.with_circle(
+                surface,
+                circle.center,
+                circle.radius,
+                objects,
+            );
+        }
+    }
+
+    cycle.build(objects).insert(objects)
+}
+
+fn distance(a: Point<2>, b: Point<2>) -> f64 {
+    (b - a).magnitude().into_f64()
+}
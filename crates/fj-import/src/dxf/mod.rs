@@ -0,0 +1,525 @@
+//! Import from DXF
+//!
+//! DXF files are made up of groups, each of which is a group code on one line,
+//! followed by a value on the next. This module reads the entities of a file
+//! into line segments, arcs, and circles. Those are then assembled into
+//! closed contours, and turned into kernel objects (see [`build`]).
+//!
+//! Entities that don't describe geometry, like texts or dimensions, are
+//! ignored, as are block references. Curves that can't be represented by
+//! lines and arcs, like splines and ellipses, are rejected.
+
+mod build;
+
+use fj_kernel::{
+    objects::{Objects, Sketch, Surface},
+    services::Service,
+    storage::Handle,
+};
+use fj_math::{Point, Scalar, Vector};
+
+use crate::Error;
+
+/// Read the closed contours from the contents of a DXF file into a sketch
+pub fn read(
+    source: &str,
+    surface: Handle<Surface>,
+    objects: &mut Service<Objects>,
+) -> Result<Handle<Sketch>, Error> {
+    let groups = groups(source)?;
+    let scale = length_scale(&groups)?;
+
+    let mut reader = Reader {
+        groups: &groups,
+        next: 0,
+    };
+    reader.skip_to_section("ENTITIES");
+
+    let mut segments = Vec::new();
+    let mut circles = Vec::new();
+    while let Some(entity) = reader.entity() {
+        match entity.kind {
+            "ENDSEC" => break,
+            "LINE" => {
+                let start = entity.point(10)?;
+                let end = entity.point(11)?;
+                segments.push(Segment::Line([start, end]));
+            }
+            "ARC" => {
+                let center = entity.point(10)?;
+                let radius = entity.scalar(40)?;
+                let [start, end] = [50, 51].map(|code| {
+                    entity.scalar(code).map(|angle| angle.to_radians())
+                });
+                let (start, end) = (start?, end?);
+
+                // Arcs are always counter-clockwise.
+                let mut span = (end - start).rem_euclid(std::f64::consts::TAU);
+                if span == 0. {
+                    span = std::f64::consts::TAU;
+                }
+                let points = [start, start + span / 2., start + span]
+                    .map(|angle| point_on_circle(center, radius, angle));
+
+                if span == std::f64::consts::TAU {
+                    circles.push(Circle { center, radius });
+                } else {
+                    segments.push(Segment::Arc(points));
+                }
+            }
+            "CIRCLE" => {
+                let center = entity.point(10)?;
+                let radius = entity.scalar(40)?;
+                circles.push(Circle { center, radius });
+            }
+            "LWPOLYLINE" => {
+                let closed = entity.flags()? & 1 != 0;
+
+                // The vertices are given by repeated coordinates, each of
+                // which may be followed by a bulge.
+                let mut vertices = Vec::new();
+                for &(code, value) in &entity.groups {
+                    match code {
+                        10 => vertices
+                            .push(([entity.parse(code, value)?, 0.], 0.)),
+                        20 | 42 => {
+                            let value = entity.parse(code, value)?;
+                            let Some(vertex) = vertices.last_mut() else {
+                                return Err(entity.error(
+                                    "expected x-coordinate before other values",
+                                ));
+                            };
+
+                            if code == 20 {
+                                vertex.0[1] = value;
+                            } else {
+                                vertex.1 = value;
+                            }
+                        }
+                        _ => {}
+                    }
+                }
+                let vertices = vertices
+                    .into_iter()
+                    .map(|(point, bulge)| (Point::from(point), bulge))
+                    .collect();
+
+                segments.extend(polyline(vertices, closed));
+            }
+            "POLYLINE" => {
+                let flags = entity.flags()?;
+                if flags & (8 | 16 | 64) != 0 {
+                    return Err(Error::Unsupported(
+                        "3D polylines and polygon meshes".into(),
+                    ));
+                }
+                let closed = flags & 1 != 0;
+
+                let mut vertices = Vec::new();
+                while let Some(vertex) = reader.entity() {
+                    match vertex.kind {
+                        "VERTEX" => {
+                            let point = vertex.point(10)?;
+                            let bulge = vertex.optional_scalar(42)?;
+                            vertices.push((point, bulge.unwrap_or(0.)));
+                        }
+                        "SEQEND" => break,
+                        _ => {
+                            return Err(
+                                vertex.error("expected VERTEX or SEQEND")
+                            )
+                        }
+                    }
+                }
+
+                segments.extend(polyline(vertices, closed));
+            }
+            "ELLIPSE" | "SPLINE" => {
+                return Err(Error::Unsupported(format!(
+                    "{} entities",
+                    entity.kind
+                )));
+            }
+            _ => {}
+        }
+    }
+
+    let segments = segments.into_iter().map(|segment| segment.scaled(scale));
+    let circles = circles.into_iter().map(|circle| Circle {
+        center: scaled(circle.center, scale),
+        radius: circle.radius * scale,
+    });
+
+    build::sketch(segments.collect(), circles.collect(), surface, objects)
+}
+
+/// A segment of a contour
+#[derive(Clone, Copy, Debug)]
+enum Segment {
+    /// A line segment, from its start to its end
+    Line([Point<2>; 2]),
+
+    /// A circular arc, from its start, through its middle, to its end
+    Arc([Point<2>; 3]),
+}
+
+impl Segment {
+    fn start(&self) -> Point<2> {
+        match self {
+            Self::Line([start, _]) => *start,
+            Self::Arc([start, _, _]) => *start,
+        }
+    }
+
+    fn end(&self) -> Point<2> {
+        match self {
+            Self::Line([_, end]) => *end,
+            Self::Arc([_, _, end]) => *end,
+        }
+    }
+
+    fn reversed(self) -> Self {
+        match self {
+            Self::Line([start, end]) => Self::Line([end, start]),
+            Self::Arc([start, through, end]) => {
+                Self::Arc([end, through, start])
+            }
+        }
+    }
+
+    fn with_start(self, start: Point<2>) -> Self {
+        match self {
+            Self::Line([_, end]) => Self::Line([start, end]),
+            Self::Arc([_, through, end]) => Self::Arc([start, through, end]),
+        }
+    }
+
+    fn with_end(self, end: Point<2>) -> Self {
+        match self {
+            Self::Line([start, _]) => Self::Line([start, end]),
+            Self::Arc([start, through, _]) => Self::Arc([start, through, end]),
+        }
+    }
+
+    fn scaled(self, scale: f64) -> Self {
+        match self {
+            Self::Line(points) => {
+                Self::Line(points.map(|point| scaled(point, scale)))
+            }
+            Self::Arc(points) => {
+                Self::Arc(points.map(|point| scaled(point, scale)))
+            }
+        }
+    }
+}
+
+/// A full circle, which is a closed contour on its own
+#[derive(Clone, Copy, Debug)]
+struct Circle {
+    center: Point<2>,
+    radius: f64,
+}
+
+/// Convert polyline vertices into segments
+///
+/// The bulge of a vertex describes the segment to the next vertex. It is the
+/// tangent of a quarter of the angle that the segment spans, which makes it
+/// zero for line segments, and positive for counter-clockwise arcs.
+fn polyline(vertices: Vec<(Point<2>, f64)>, closed: bool) -> Vec<Segment> {
+    let mut next = vertices.iter().skip(1).collect::<Vec<_>>();
+    if closed {
+        next.extend(vertices.first());
+    }
+
+    vertices
+        .iter()
+        .zip(next)
+        .filter(|((start, _), (end, _))| start != end)
+        .map(|(&(start, bulge), &(end, _))| {
+            if bulge == 0. {
+                return Segment::Line([start, end]);
+            }
+
+            // The middle of the arc is the chord's midpoint, moved to the
+            // right of the chord by the sagitta.
+            let chord = end - start;
+            let right = Vector::from([chord.v, -chord.u]).normalize();
+            let sagitta = chord.magnitude() * bulge / 2.;
+            let through = start + chord / 2. + right * sagitta;
+
+            Segment::Arc([start, through, end])
+        })
+        .collect()
+}
+
+/// Determine the factor that converts lengths from the file into millimeters
+///
+/// Files that don't specify their unit are assumed to be in millimeters.
+fn length_scale(groups: &[Group]) -> Result<f64, Error> {
+    let Some(i) = groups
+        .iter()
+        .position(|group| group.code == 9 && group.value == "$INSUNITS")
+    else {
+        return Ok(1.);
+    };
+    let Some(group) = groups.get(i + 1).filter(|group| group.code == 70) else {
+        return Ok(1.);
+    };
+
+    let unit = group.value.parse::<u32>().map_err(|_| Error::Syntax {
+        line: group.line,
+        message: "expected integer unit code".into(),
+    })?;
+
+    match unit {
+        0 | 4 => Ok(1.),
+        1 => Ok(25.4),
+        2 => Ok(304.8),
+        5 => Ok(10.),
+        6 => Ok(1000.),
+        unit => Err(Error::Unsupported(format!("unit code {unit}"))),
+    }
+}
+
+fn scaled(point: Point<2>, scale: f64) -> Point<2> {
+    Point::origin() + point.coords * scale
+}
+
+fn point_on_circle(center: Point<2>, radius: f64, angle: f64) -> Point<2> {
+    let (sin, cos) = Scalar::from(angle).sin_cos();
+    center + Vector::from([cos, sin]) * radius
+}
+
+/// A group code and its value
+struct Group<'r> {
+    code: i32,
+    value: &'r str,
+
+    /// The line that the group code is on
+    line: usize,
+}
+
+/// Split the file into groups
+fn groups(source: &str) -> Result<Vec<Group<'_>>, Error> {
+    let mut lines = source.lines().enumerate();
+    let mut groups = Vec::new();
+
+    while let Some((i, code)) = lines.next() {
+        let line = i + 1;
+        let code = code.trim();
+        if code.is_empty() {
+            continue;
+        }
+
+        let code = code.parse().map_err(|_| Error::Syntax {
+            line,
+            message: format!("expected group code, found `{code}`"),
+        })?;
+        let (_, value) = lines.next().ok_or(Error::Syntax {
+            line,
+            message: "expected value after group code".into(),
+        })?;
+
+        groups.push(Group {
+            code,
+            value: value.trim(),
+            line,
+        });
+    }
+
+    Ok(groups)
+}
+
+/// Reads entities from the groups of a file
+struct Reader<'r> {
+    groups: &'r [Group<'r>],
+    next: usize,
+}
+
+impl<'r> Reader<'r> {
+    /// Skip ahead to the start of the section with the given name
+    ///
+    /// If there is no such section, all groups are skipped.
+    fn skip_to_section(&mut self, name: &str) {
+        while let Some(group) = self.groups.get(self.next) {
+            self.next += 1;
+
+            let is_name = group.code == 2 && group.value == name;
+            let follows_section = self.next >= 2
+                && self.groups[self.next - 2].code == 0
+                && self.groups[self.next - 2].value == "SECTION";
+            if is_name && follows_section {
+                return;
+            }
+        }
+    }
+
+    /// Read the next entity
+    ///
+    /// Returns `None`, if there are no entities left.
+    fn entity(&mut self) -> Option<Entity<'r>> {
+        let start = self.groups.get(self.next)?;
+        self.next += 1;
+
+        let mut groups = Vec::new();
+        while let Some(group) = self.groups.get(self.next) {
+            if group.code == 0 {
+                break;
+            }
+
+            groups.push((group.code, group.value));
+            self.next += 1;
+        }
+
+        Some(Entity {
+            kind: start.value,
+            groups,
+            line: start.line,
+        })
+    }
+}
+
+/// An entity, made up of its type and the groups that follow it
+struct Entity<'r> {
+    kind: &'r str,
+    groups: Vec<(i32, &'r str)>,
+
+    /// The line that the entity starts on
+    line: usize,
+}
+
+impl Entity<'_> {
+    /// Read the point whose x-coordinate has the given group code
+    ///
+    /// The group code of the y-coordinate is 10 higher. The z-coordinate is
+    /// ignored.
+    fn point(&self, code: i32) -> Result<Point<2>, Error> {
+        Ok(Point::from([self.scalar(code)?, self.scalar(code + 10)?]))
+    }
+
+    fn scalar(&self, code: i32) -> Result<f64, Error> {
+        self.optional_scalar(code)?.ok_or_else(|| {
+            self.error(&format!("missing value for group code {code}"))
+        })
+    }
+
+    fn optional_scalar(&self, code: i32) -> Result<Option<f64>, Error> {
+        self.groups
+            .iter()
+            .find(|(c, _)| *c == code)
+            .map(|&(code, value)| self.parse(code, value))
+            .transpose()
+    }
+
+    fn flags(&self) -> Result<u32, Error> {
+        match self.groups.iter().find(|(code, _)| *code == 70) {
+            Some((_, value)) => value
+                .parse()
+                .map_err(|_| self.error("expected integer flags")),
+            None => Ok(0),
+        }
+    }
+
+    fn parse(&self, code: i32, value: &str) -> Result<f64, Error> {
+        value.parse().map_err(|_| {
+            self.error(&format!(
+                "expected number for group code {code}, found `{value}`"
+            ))
+        })
+    }
+
+    fn error(&self, message: &str) -> Error {
+        Error::Syntax {
+            line: self.line,
+            message: format!("{}: {message}", self.kind),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use fj_kernel::services::{ServiceValidationExt, Services};
+
+    use crate::Error;
+
+    #[test]
+    fn read_faces_with_holes() -> anyhow::Result<()> {
+        let mut services = Services::new();
+        let surface = services.objects.surfaces.xy_plane();
+
+        // A rectangle with a round top and a circular hole, next to a square
+        // with a bulged side. The left side of the rectangle is drawn in the
+        // opposite direction of the others.
+        let source = "\
+0\nSECTION\n2\nENTITIES
+0\nLINE\n8\n0\n10\n0\n20\n0\n11\n20\n21\n0
+0\nLINE\n8\n0\n10\n20\n20\n0\n11\n20\n21\n10
+0\nARC\n8\n0\n10\n10\n20\n10\n40\n10\n50\n0\n51\n180
+0\nLINE\n8\n0\n10\n0\n20\n0\n11\n0\n21\n10
+0\nCIRCLE\n8\n0\n10\n10\n20\n8\n40\n2
+0\nTEXT\n8\n0\n10\n0\n20\n0\n1\nIgnored
+0\nLWPOLYLINE\n8\n0\n90\n4\n70\n1
+10\n30\n20\n0\n10\n40\n20\n0\n42\n0.5\n10\n40\n20\n10\n10\n30\n20\n10
+0\nENDSEC\n0\nEOF
+";
+
+        let sketch = super::read(source, surface, &mut services.objects)?;
+        assert!(services.validation.lock().take_errors().is_empty());
+
+        let mut interiors = sketch
+            .faces()
+            .into_iter()
+            .map(|face| face.interiors().count())
+            .collect::<Vec<_>>();
+        interiors.sort();
+        assert_eq!(interiors, [0, 1]);
+
+        Ok(())
+    }
+
+    #[test]
+    fn convert_units() -> anyhow::Result<()> {
+        let mut services = Services::new();
+        let surface = services.objects.surfaces.xy_plane();
+
+        let source = "\
+0\nSECTION\n2\nHEADER\n9\n$INSUNITS\n70\n1\n0\nENDSEC
+0\nSECTION\n2\nENTITIES
+0\nLWPOLYLINE\n8\n0\n90\n3\n70\n1
+10\n0\n20\n0\n10\n1\n20\n0\n10\n0\n20\n1
+0\nENDSEC\n0\nEOF
+";
+
+        let sketch = super::read(source, surface, &mut services.objects)?;
+
+        let max = sketch
+            .faces()
+            .into_iter()
+            .flat_map(|face| face.exterior().half_edges())
+            .flat_map(|half_edge| half_edge.vertices().clone())
+            .map(|vertex| vertex.surface_form().position().u.into_f64())
+            .fold(0., f64::max);
+
+        // Lengths are converted from inches into millimeters.
+        assert_eq!(max, 25.4);
+
+        Ok(())
+    }
+
+    #[test]
+    fn reject_open_contour() {
+        let mut services = Services::new();
+        let surface = services.objects.surfaces.xy_plane();
+
+        let source = "\
+0\nSECTION\n2\nENTITIES
+0\nLINE\n10\n0\n20\n0\n11\n1\n21\n0
+0\nLINE\n10\n1\n20\n0\n11\n1\n21\n1
+0\nENDSEC\n0\nEOF
+";
+
+        let result = super::read(source, surface, &mut services.objects);
+
+        assert!(matches!(result, Err(Error::OpenContour([1., 1.]))));
+    }
+}
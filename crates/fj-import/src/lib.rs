@@ -0,0 +1,268 @@
+//! # Fornjot Importer
+//!
+//! This library is part of the [Fornjot] ecosystem. Fornjot is an open-source,
+//! code-first CAD application; and collection of libraries that make up the CAD
+//! application, but can be used independently.
+//!
+//! This library is an internal component of Fornjot. It is not relevant to end
+//! users that just want to create CAD models.
+//!
+//! The purpose of this library is to import boundary representations from
+//! external file formats, and turn them into objects of the Fornjot kernel.
+//! That way, models can make use of geometry that has been created elsewhere,
+//! like the models of purchased parts that manufacturers provide.
+//!
+//! Triangle meshes can be imported too. As they only approximate the shapes
+//! they describe, they are not turned into kernel objects, but can serve as
+//! reference geometry.
+//!
+//! 2D drawings can be imported as sketches, to be extruded or otherwise used
+//! like sketches that are defined in code.
+//!
+//! [Fornjot]: https://www.fornjot.app/
+
+#![warn(missing_docs)]
+
+mod dxf;
+mod mesh;
+mod step;
+
+use std::{
+    fs::{self, File},
+    io::BufReader,
+    path::Path,
+};
+
+use fj_interop::mesh::Mesh;
+use fj_kernel::{
+    insert::Insert,
+    objects::{Objects, Shell, Sketch, Solid, Surface},
+    serialize::{Snapshot, SnapshotError},
+    services::Service,
+    storage::Handle,
+};
+use fj_math::Point;
+use thiserror::Error;
+
+/// Import the solids from the file at the given path
+///
+/// STEP files and shapes that have been saved by Fornjot (`.fjshape`) are
+/// supported. The case insensitive file extension of the provided path is used
+/// to recognize them.
+///
+/// See [`import_step`] for which parts of STEP files are supported.
+pub fn import(
+    path: &Path,
+    objects: &mut Service<Objects>,
+) -> Result<Vec<Handle<Solid>>, Error> {
+    match path.extension() {
+        Some(extension)
+            if extension.eq_ignore_ascii_case("STEP")
+                || extension.eq_ignore_ascii_case("STP") =>
+        {
+            // The exchange structure is restricted to ASCII. Some applications
+            // don't care and write names in other encodings, but those are of
+            // no interest here anyway.
+            let source = fs::read(path)?;
+            import_step(&String::from_utf8_lossy(&source), objects)
+        }
+        Some(extension) if extension.eq_ignore_ascii_case("FJSHAPE") => {
+            let file = BufReader::new(File::open(path)?);
+            import_snapshot(&Snapshot::read(file)?, objects)
+        }
+        Some(extension) => Err(Error::InvalidExtension(
+            extension.to_string_lossy().into_owned(),
+        )),
+        None => Err(Error::NoExtension),
+    }
+}
+
+/// Import the solids from a snapshot of a shape
+///
+/// Every set of faces within the snapshot, usually one per part of the shape,
+/// becomes a [`Solid`].
+pub fn import_snapshot(
+    snapshot: &Snapshot,
+    objects: &mut Service<Objects>,
+) -> Result<Vec<Handle<Solid>>, Error> {
+    let solids = snapshot
+        .restore(objects)?
+        .into_iter()
+        .map(|(_, faces)| {
+            let shell = Shell::new(faces).insert(objects);
+            Solid::new([shell]).insert(objects)
+        })
+        .collect::<Vec<_>>();
+
+    if solids.is_empty() {
+        return Err(Error::NoSolids);
+    }
+
+    Ok(solids)
+}
+
+/// Import the solids from the contents of a STEP file
+///
+/// Every `MANIFOLD_SOLID_BREP` in the file becomes a [`Solid`]. Only planar,
+/// cylindrical, and conical faces are supported, which must be bounded by lines
+/// and circles. Cylindrical and conical faces need to be split by a seam edge,
+/// or into multiple faces, as is common practice.
+///
+/// Lengths are converted into millimeters, which is the unit that Fornjot
+/// models use by convention.
+pub fn import_step(
+    source: &str,
+    objects: &mut Service<Objects>,
+) -> Result<Vec<Handle<Solid>>, Error> {
+    step::read(source, objects)
+}
+
+/// Import the triangle mesh from the file at the given path
+///
+/// STL and OBJ files are supported. The case insensitive file extension of the
+/// provided path is used to recognize them.
+///
+/// Triangles that don't span any area are skipped. Returns an error, if no
+/// triangles are left.
+pub fn import_mesh(path: &Path) -> Result<Mesh<Point<3>>, Error> {
+    let mesh = match path.extension() {
+        Some(extension) if extension.eq_ignore_ascii_case("STL") => {
+            import_stl(&fs::read(path)?)
+        }
+        Some(extension) if extension.eq_ignore_ascii_case("OBJ") => {
+            let source = fs::read(path)?;
+            import_obj(&String::from_utf8_lossy(&source))
+        }
+        Some(extension) => Err(Error::InvalidExtension(
+            extension.to_string_lossy().into_owned(),
+        )),
+        None => Err(Error::NoExtension),
+    }?;
+
+    if mesh.triangles().next().is_none() {
+        return Err(Error::NoTriangles);
+    }
+
+    Ok(mesh)
+}
+
+/// Import the triangle mesh from the contents of an STL file
+///
+/// Both ASCII and binary files are supported. Normals and attributes are
+/// ignored.
+pub fn import_stl(source: &[u8]) -> Result<Mesh<Point<3>>, Error> {
+    mesh::stl::read(source)
+}
+
+/// Import the triangle mesh from the contents of an OBJ file
+///
+/// All faces of the file end up in the same mesh. Faces with more than three
+/// vertices are expected to be convex.
+pub fn import_obj(source: &str) -> Result<Mesh<Point<3>>, Error> {
+    mesh::obj::read(source)
+}
+
+/// Import the closed contours from the file at the given path into a sketch
+///
+/// Currently, only DXF files are supported. The case insensitive file extension
+/// of the provided path is used to recognize them.
+///
+/// See [`import_dxf`] for which parts of DXF files are supported.
+pub fn import_sketch(
+    path: &Path,
+    surface: Handle<Surface>,
+    objects: &mut Service<Objects>,
+) -> Result<Handle<Sketch>, Error> {
+    match path.extension() {
+        Some(extension) if extension.eq_ignore_ascii_case("DXF") => {
+            let source = fs::read(path)?;
+            import_dxf(&String::from_utf8_lossy(&source), surface, objects)
+        }
+        Some(extension) => Err(Error::InvalidExtension(
+            extension.to_string_lossy().into_owned(),
+        )),
+        None => Err(Error::NoExtension),
+    }
+}
+
+/// Import the closed contours from the contents of an ASCII DXF file
+///
+/// Lines, arcs, circles, and polylines (including their arc segments) are read
+/// from the `ENTITIES` section, and chained into closed contours. The
+/// coordinates of the drawing become coordinates on the provided surface. Each
+/// contour becomes a face, with the contours that lie within it as its holes.
+///
+/// Lengths are converted into millimeters, according to the `$INSUNITS` header
+/// variable. Entities that don't describe geometry, like texts and dimensions,
+/// are ignored, while splines and ellipses can't be imported.
+pub fn import_dxf(
+    source: &str,
+    surface: Handle<Surface>,
+    objects: &mut Service<Objects>,
+) -> Result<Handle<Sketch>, Error> {
+    dxf::read(source, surface, objects)
+}
+
+/// An error that can occur while importing
+#[derive(Debug, Error)]
+pub enum Error {
+    /// No extension specified
+    #[error("no extension specified")]
+    NoExtension,
+
+    /// Unrecognized extension found
+    #[error("unrecognized extension found `{0:?}`")]
+    InvalidExtension(String),
+
+    /// I/O error whilst importing from file
+    #[error("I/O error whilst importing from file")]
+    Io(#[from] std::io::Error),
+
+    /// The snapshot of a shape can't be read or restored
+    #[error("invalid snapshot")]
+    Snapshot(#[from] SnapshotError),
+
+    /// The file is not well-formed
+    #[error("syntax error in line {line}: {message}")]
+    Syntax {
+        /// The line the error was found in
+        line: usize,
+
+        /// A description of the error
+        message: String,
+    },
+
+    /// An entity is referenced, but not defined
+    #[error("entity #{0} is referenced, but not defined")]
+    MissingEntity(u64),
+
+    /// An entity doesn't have the expected type or parameters
+    #[error("entity #{id} is invalid: {message}")]
+    InvalidEntity {
+        /// The id of the entity
+        id: u64,
+
+        /// A description of what is wrong with the entity
+        message: String,
+    },
+
+    /// The file uses geometry or topology that can't be imported
+    #[error("unsupported: {0}")]
+    Unsupported(String),
+
+    /// The file doesn't contain any solids
+    #[error("file doesn't contain any solids")]
+    NoSolids,
+
+    /// A contour of the drawing is not closed
+    #[error("contour is not closed at {0:?}")]
+    OpenContour([f64; 2]),
+
+    /// The file doesn't contain any closed contours
+    #[error("file doesn't contain any closed contours")]
+    NoContours,
+
+    /// The file doesn't contain any triangles
+    #[error("file doesn't contain any triangles")]
+    NoTriangles,
+}
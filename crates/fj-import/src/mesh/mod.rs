@@ -0,0 +1,43 @@
+//! Import of triangle meshes
+//!
+//! Triangle meshes only approximate the shapes they describe, so they can't be
+//! turned into kernel objects. They are imported as [`Mesh`]es instead, which
+//! is good enough to display them, or to check other shapes against them.
+
+pub mod obj;
+pub mod stl;
+
+use fj_interop::mesh::{Color, Mesh};
+use fj_math::{Point, Triangle};
+
+use crate::Error;
+
+/// Parse the coordinates of a point
+///
+/// ASCII STL and OBJ files specify points in the same way.
+fn parse_point<'r>(
+    mut words: impl Iterator<Item = &'r str>,
+    line: usize,
+) -> Result<Point<3>, Error> {
+    let mut coord = || {
+        words
+            .next()
+            .and_then(|word| word.parse::<f64>().ok())
+            .ok_or_else(|| Error::Syntax {
+                line,
+                message: "expected 3 coordinates".into(),
+            })
+    };
+
+    Ok(Point::from([coord()?, coord()?, coord()?]))
+}
+
+/// Add a triangle to a mesh, unless it is degenerate
+///
+/// Meshes from other applications commonly contain triangles without area.
+/// They don't contribute anything to the shape, so they are skipped.
+fn push_triangle(mesh: &mut Mesh<Point<3>>, points: [Point<3>; 3]) {
+    if let Ok(triangle) = Triangle::from_points(points) {
+        mesh.push_triangle(triangle, Color::default());
+    }
+}
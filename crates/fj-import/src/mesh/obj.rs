@@ -0,0 +1,119 @@
+//! Import from OBJ
+//!
+//! Only the vertices and faces of the geometry are read. Faces with more than
+//! three vertices are split into triangles, under the assumption that they are
+//! convex. Texture coordinates, normals, groups, and materials are ignored.
+
+use fj_interop::mesh::Mesh;
+use fj_math::Point;
+
+use crate::Error;
+
+use super::{parse_point, push_triangle};
+
+/// Read the triangles from the contents of an OBJ file
+pub fn read(source: &str) -> Result<Mesh<Point<3>>, Error> {
+    let mut mesh = Mesh::new();
+    let mut vertices = Vec::new();
+
+    for (i, line) in source.lines().enumerate() {
+        let line_number = i + 1;
+        let mut words = line.split_whitespace();
+
+        match words.next() {
+            Some("v") => {
+                vertices.push(parse_point(words, line_number)?);
+            }
+            Some("f") => {
+                let points = words
+                    .map(|word| vertex(word, &vertices, line_number))
+                    .collect::<Result<Vec<_>, _>>()?;
+
+                if points.len() < 3 {
+                    return Err(Error::Syntax {
+                        line: line_number,
+                        message: "expected at least 3 vertices per face".into(),
+                    });
+                }
+
+                for pair in points[1..].windows(2) {
+                    push_triangle(&mut mesh, [points[0], pair[0], pair[1]]);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    Ok(mesh)
+}
+
+/// Look up the vertex that a vertex of a face refers to
+///
+/// The vertex is specified by its index, optionally followed by the indices of
+/// its texture coordinates and normal, separated by slashes. Indices start at
+/// one, while negative indices count back from the latest vertex.
+fn vertex(
+    word: &str,
+    vertices: &[Point<3>],
+    line: usize,
+) -> Result<Point<3>, Error> {
+    let error = |message: String| Error::Syntax { line, message };
+
+    let index = word.split('/').next().unwrap_or_default();
+    let index = index
+        .parse::<i64>()
+        .map_err(|_| error(format!("invalid vertex index `{index}`")))?;
+
+    let resolved = if index < 0 {
+        vertices.len() as i64 + index
+    } else {
+        index - 1
+    };
+
+    usize::try_from(resolved)
+        .ok()
+        .and_then(|index| vertices.get(index))
+        .copied()
+        .ok_or_else(|| error(format!("vertex {index} is not defined")))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::Error;
+
+    #[test]
+    fn read_faces() -> anyhow::Result<()> {
+        let source = "\
+# A square, and a triangle on top of it
+o square
+v 0 0 0
+v 1 0 0
+v 1 1 0
+v 0 1 0
+vn 0 0 1
+f 1//1 2//1 3//1 4//1
+v 0.5 0.5 1
+f -4/1 -3/2 -1/3
+";
+
+        let mesh = super::read(source)?;
+
+        assert_eq!(mesh.triangles().count(), 3);
+        assert_eq!(mesh.vertices().count(), 5);
+
+        Ok(())
+    }
+
+    #[test]
+    fn reject_undefined_vertex() {
+        let source = "\
+v 0 0 0
+v 1 0 0
+f 1 2 3
+";
+
+        let result = super::read(source);
+
+        assert!(matches!(result, Err(Error::Syntax { line: 3, .. })));
+    }
+}
@@ -0,0 +1,184 @@
+//! Import from STL
+//!
+//! Both variants of the format are supported. Binary files are recognized by
+//! their size, which is determined by the number of triangles in their header.
+//! All other files are expected to be ASCII files.
+
+use fj_interop::mesh::Mesh;
+use fj_math::Point;
+
+use crate::Error;
+
+use super::{parse_point, push_triangle};
+
+/// Read the triangles from the contents of an STL file
+pub fn read(source: &[u8]) -> Result<Mesh<Point<3>>, Error> {
+    match binary_triangle_count(source) {
+        Some(count) => Ok(read_binary(&source[HEADER_SIZE..], count)),
+        None => read_ascii(&String::from_utf8_lossy(source)),
+    }
+}
+
+/// The size of the header of a binary file, including the triangle count
+const HEADER_SIZE: usize = 84;
+
+/// The size of a triangle in a binary file
+const TRIANGLE_SIZE: usize = 50;
+
+/// Determine the number of triangles, if the file is a binary file
+///
+/// ASCII files are supposed to start with `solid`, but some binary files do
+/// so too. The size of a binary file is the only reliable way to tell.
+fn binary_triangle_count(source: &[u8]) -> Option<usize> {
+    let count = source.get(80..HEADER_SIZE)?;
+    let count =
+        u32::from_le_bytes(count.try_into().expect("Slice has 4 bytes"));
+    let count = usize::try_from(count).ok()?;
+
+    let size = count.checked_mul(TRIANGLE_SIZE)?.checked_add(HEADER_SIZE)?;
+    (size == source.len()).then_some(count)
+}
+
+fn read_binary(triangles: &[u8], count: usize) -> Mesh<Point<3>> {
+    let mut mesh = Mesh::new();
+
+    for triangle in triangles.chunks_exact(TRIANGLE_SIZE).take(count) {
+        // The triangle starts with its normal, which is redundant, and ends
+        // with an attribute that is not used consistently across applications.
+        let points = [0, 1, 2].map(|i| {
+            let offset = 12 + i * 12;
+            let coords = [0, 1, 2].map(|j| {
+                let start = offset + j * 4;
+                let bytes = triangle[start..start + 4]
+                    .try_into()
+                    .expect("Slice has 4 bytes");
+                f64::from(f32::from_le_bytes(bytes))
+            });
+            Point::from(coords)
+        });
+
+        push_triangle(&mut mesh, points);
+    }
+
+    mesh
+}
+
+fn read_ascii(source: &str) -> Result<Mesh<Point<3>>, Error> {
+    let mut mesh = Mesh::new();
+    let mut points = Vec::new();
+
+    for (i, line) in source.lines().enumerate() {
+        let line_number = i + 1;
+        let mut words = line.split_whitespace();
+
+        match words.next() {
+            Some("vertex") => {
+                let point = parse_point(words, line_number)?;
+                points.push(point);
+            }
+            Some("endloop") => {
+                let points = std::mem::take(&mut points);
+                let points: [Point<3>; 3] =
+                    points.try_into().map_err(|points: Vec<_>| {
+                        Error::Syntax {
+                            line: line_number,
+                            message: format!(
+                                "expected 3 vertices per facet, found {}",
+                                points.len()
+                            ),
+                        }
+                    })?;
+
+                push_triangle(&mut mesh, points);
+            }
+            _ => {}
+        }
+    }
+
+    Ok(mesh)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::Error;
+
+    #[test]
+    fn read_ascii() -> anyhow::Result<()> {
+        let source = "\
+solid tetrahedron
+  facet normal 0 0 -1
+    outer loop
+      vertex 0 0 0
+      vertex 0 1 0
+      vertex 1 0 0
+    endloop
+  endfacet
+  facet normal 0 0 0
+    outer loop
+      vertex 0 0 0
+      vertex 1 0 0
+      vertex 2 0 0
+    endloop
+  endfacet
+  facet normal 0 -1 0
+    outer loop
+      vertex 0 0 0
+      vertex 1 0 0
+      vertex 0 0 1
+    endloop
+  endfacet
+endsolid tetrahedron
+";
+
+        let mesh = super::read(source.as_bytes())?;
+
+        // The degenerate facet is skipped.
+        assert_eq!(mesh.triangles().count(), 2);
+        assert_eq!(mesh.vertices().count(), 4);
+
+        Ok(())
+    }
+
+    #[test]
+    fn read_binary() -> anyhow::Result<()> {
+        // Binary files may start with `solid` too.
+        let mut source = b"solid".to_vec();
+        source.resize(80, 0);
+        source.extend(1_u32.to_le_bytes());
+        for value in [0., 0., 1., 0., 0., 0., 1., 0., 0., 0., 1., 0.] {
+            source.extend((value as f32).to_le_bytes());
+        }
+        source.extend([0, 0]);
+
+        let mesh = super::read(&source)?;
+
+        let [triangle] = &mesh.triangles().collect::<Vec<_>>()[..] else {
+            panic!("Expected one triangle");
+        };
+        assert_eq!(
+            triangle.inner.points().map(|point| point.coords.components),
+            [[0., 0., 0.], [1., 0., 0.], [0., 1., 0.]]
+                .map(|coords| coords.map(Into::into)),
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn reject_incomplete_facet() {
+        let source = "\
+solid broken
+  facet normal 0 0 1
+    outer loop
+      vertex 0 0 0
+      vertex 1 0 0
+    endloop
+  endfacet
+endsolid broken
+";
+
+        let result = super::read(source.as_bytes());
+
+        assert!(matches!(result, Err(Error::Syntax { line: 6, .. })));
+    }
+}
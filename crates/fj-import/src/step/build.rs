@@ -0,0 +1,793 @@
+//! Turn the shells read from a STEP file into kernel objects
+//!
+//! The kernel is much stricter than STEP files about the consistency of
+//! geometry. Vertices must lie precisely on the surfaces and curves they are
+//! part of, and curves that are shared by multiple faces must be parametrized
+//! the same way in all of them. Much of the code in here deals with
+//! establishing that.
+
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    f64::consts::TAU,
+};
+
+use fj_interop::mesh::Color;
+use fj_kernel::{
+    geometry::{
+        path::{GlobalPath, SurfacePath},
+        surface::SurfaceGeometry,
+    },
+    insert::Insert,
+    objects::{
+        Curve as KernelCurve, Cycle, Face as KernelFace, GlobalCurve,
+        GlobalEdge, GlobalVertex, HalfEdge, Objects, Shell as KernelShell,
+        Solid, Surface as KernelSurface, SurfaceVertex, Vertex,
+    },
+    services::Service,
+    storage::Handle,
+};
+use fj_math::{signed_area, Circle, Cone, Line, Point, Scalar, Vector};
+
+use crate::Error;
+
+use super::{Curve, Frame, Shell, Surface};
+
+/// Distances below this are considered zero
+///
+/// STEP files are written with limited precision. Geometry that is supposed to
+/// be identical, usually isn't exactly.
+const TOLERANCE: f64 = 1e-6;
+
+/// Build a solid from the shell that bounds it
+pub fn solid(
+    shell: &Shell,
+    objects: &mut Service<Objects>,
+) -> Result<Handle<Solid>, Error> {
+    let surfaces = canonical_surfaces(shell);
+    let circles = canonical_circles(shell, &surfaces)?;
+    let positions = refine_positions(shell, &surfaces, &circles);
+
+    let mut builder = Builder {
+        shell,
+        positions,
+        ranges: BTreeMap::new(),
+        aligned: BTreeSet::new(),
+        global_vertices: BTreeMap::new(),
+        global_edges: BTreeMap::new(),
+    };
+    for (&id, edge) in &shell.edges {
+        let range = builder.range(id, circles.get(&id))?;
+        builder.ranges.insert(id, range);
+
+        let curve = GlobalCurve.insert(objects);
+        let vertices = edge
+            .vertices
+            .map(|vertex| builder.global_vertex(vertex, objects));
+        let edge = GlobalEdge::new(curve.clone(), vertices).insert(objects);
+
+        builder.global_edges.insert(id, (curve, edge));
+    }
+
+    // The range of a circle on a cylinder or cone needs to be aligned with the
+    // coordinates of that surface. Build those first, so all other faces use
+    // the aligned ranges.
+    let mut order = (0..shell.faces.len()).collect::<Vec<_>>();
+    order.sort_by_key(|&i| matches!(surfaces[i], Surface::Plane(_)));
+
+    let mut faces = Vec::new();
+    for i in order {
+        faces.push(builder.face(i, surfaces[i], &circles, objects)?);
+    }
+
+    let shell = KernelShell::new(faces).insert(objects);
+    Ok(Solid::new([shell]).insert(objects))
+}
+
+/// Determine the surface of each face, merging identical cylinders and cones
+///
+/// The coordinates of a cylinder or cone depend on the coordinate system it is
+/// defined in. Faces that are on the same surface need to use the same one, or
+/// the circles they share can't be parametrized consistently.
+fn canonical_surfaces(shell: &Shell) -> Vec<Surface> {
+    let mut curved: Vec<Surface> = Vec::new();
+
+    shell
+        .faces
+        .iter()
+        .map(|face| {
+            if let Surface::Plane(_) = face.surface {
+                return face.surface;
+            }
+
+            let existing = curved
+                .iter()
+                .find(|other| is_same_surface(other, &face.surface));
+
+            match existing {
+                Some(&surface) => surface,
+                None => {
+                    curved.push(face.surface);
+                    face.surface
+                }
+            }
+        })
+        .collect()
+}
+
+/// Determine whether two curved surfaces are the same
+///
+/// They may be defined in different coordinate systems.
+fn is_same_surface(a: &Surface, b: &Surface) -> bool {
+    let is_parallel = |a: &Frame, b: &Frame| {
+        a.z.cross(&b.z).magnitude() < Scalar::from(TOLERANCE)
+    };
+
+    match (*a, *b) {
+        (Surface::Cylinder(a, a_radius), Surface::Cylinder(b, b_radius)) => {
+            (a_radius - b_radius).abs() < Scalar::from(TOLERANCE)
+                && is_on_axis(&a, b.origin)
+                && is_parallel(&a, &b)
+        }
+        (
+            Surface::Cone(a, a_radius, a_angle),
+            Surface::Cone(b, b_radius, b_angle),
+        ) => {
+            // Unlike a cylinder, a cone is different, if its axis is reversed.
+            (a_angle - b_angle).abs() < Scalar::from(TOLERANCE)
+                && (cone(&a, a_radius, a_angle).apex()
+                    - cone(&b, b_radius, b_angle).apex())
+                .magnitude()
+                    < Scalar::from(TOLERANCE)
+                && is_parallel(&a, &b)
+                && a.z.dot(&b.z) > Scalar::ZERO
+        }
+        _ => false,
+    }
+}
+
+/// Determine the geometry of each circle
+///
+/// Circles on cylinders and cones use the coordinate system of that surface, so
+/// their curve coordinates are the same as the surface's u-coordinates.
+fn canonical_circles(
+    shell: &Shell,
+    surfaces: &[Surface],
+) -> Result<BTreeMap<u64, CircleGeometry>, Error> {
+    let mut circles = BTreeMap::new();
+
+    for (face, surface) in shell.faces.iter().zip(surfaces) {
+        let (axis, kind) = match *surface {
+            Surface::Plane(_) => continue,
+            Surface::Cylinder(frame, _) => (frame, "cylindrical"),
+            Surface::Cone(frame, ..) => (frame, "conical"),
+        };
+
+        for bound in &face.bounds {
+            for &(id, _) in &bound.edges {
+                let Curve::Circle { frame, radius, .. } =
+                    shell.edges[&id].curve
+                else {
+                    continue;
+                };
+                if circles.contains_key(&id) {
+                    continue;
+                }
+
+                let height = (frame.origin - axis.origin).dot(&axis.z);
+                let surface_radius = radius_at(surface, height);
+
+                if (radius - surface_radius).abs() > Scalar::from(TOLERANCE)
+                    || !is_on_axis(&axis, frame.origin)
+                    || frame.z.cross(&axis.z).magnitude()
+                        > Scalar::from(TOLERANCE)
+                {
+                    return Err(Error::Unsupported(format!(
+                        "circle that is not a cross-section of its {kind} \
+                        face (#{id})"
+                    )));
+                }
+
+                circles.insert(
+                    id,
+                    CircleGeometry {
+                        center: axis.origin + axis.z * height,
+                        a: axis.x,
+                        b: axis.y,
+                        radius: surface_radius,
+                    },
+                );
+            }
+        }
+    }
+
+    for (&id, edge) in &shell.edges {
+        if let Curve::Circle { frame, radius, .. } = edge.curve {
+            circles.entry(id).or_insert(CircleGeometry {
+                center: frame.origin,
+                a: frame.x,
+                b: frame.y,
+                radius,
+            });
+        }
+    }
+
+    Ok(circles)
+}
+
+/// Move the vertices, so they lie precisely on their surfaces and curves
+///
+/// Projects each vertex onto each of those in turn, until it settles where
+/// they intersect.
+fn refine_positions(
+    shell: &Shell,
+    surfaces: &[Surface],
+    circles: &BTreeMap<u64, CircleGeometry>,
+) -> BTreeMap<u64, Point<3>> {
+    const ITERATIONS: usize = 64;
+
+    let mut constraints = BTreeMap::<u64, Vec<Constraint>>::new();
+    for (face, &surface) in shell.faces.iter().zip(surfaces) {
+        let vertices = face
+            .bounds
+            .iter()
+            .flat_map(|bound| &bound.edges)
+            .flat_map(|(id, _)| shell.edges[id].vertices)
+            .collect::<BTreeSet<_>>();
+
+        for vertex in vertices {
+            constraints
+                .entry(vertex)
+                .or_default()
+                .push(Constraint::Surface(surface));
+        }
+    }
+    for (id, &circle) in circles {
+        for vertex in shell.edges[id].vertices {
+            constraints
+                .entry(vertex)
+                .or_default()
+                .push(Constraint::Circle(circle));
+        }
+    }
+
+    shell
+        .vertices
+        .iter()
+        .map(|(&id, &position)| {
+            let constraints = constraints.get(&id).map_or(&[][..], Vec::as_ref);
+
+            let mut position = position;
+            for _ in 0..ITERATIONS {
+                for constraint in constraints {
+                    position = constraint.project(position);
+                }
+            }
+
+            (id, position)
+        })
+        .collect()
+}
+
+struct Builder<'r> {
+    shell: &'r Shell,
+    positions: BTreeMap<u64, Point<3>>,
+
+    /// The curve coordinates of the start and end vertex of each edge
+    ranges: BTreeMap<u64, [Scalar; 2]>,
+
+    /// The circles whose ranges have been aligned with a cylinder or cone
+    aligned: BTreeSet<u64>,
+
+    global_vertices: BTreeMap<u64, Handle<GlobalVertex>>,
+    global_edges: BTreeMap<u64, (Handle<GlobalCurve>, Handle<GlobalEdge>)>,
+}
+
+impl Builder<'_> {
+    fn global_vertex(
+        &mut self,
+        id: u64,
+        objects: &mut Service<Objects>,
+    ) -> Handle<GlobalVertex> {
+        let position = self.positions[&id];
+
+        self.global_vertices
+            .entry(id)
+            .or_insert_with(|| GlobalVertex::new(position).insert(objects))
+            .clone()
+    }
+
+    /// Compute the curve coordinates of the vertices of an edge
+    ///
+    /// Lines go from 0 to 1. Circles start at the angle of their start vertex,
+    /// and sweep towards the end vertex, in the direction of the edge.
+    fn range(
+        &self,
+        id: u64,
+        circle: Option<&CircleGeometry>,
+    ) -> Result<[Scalar; 2], Error> {
+        let edge = &self.shell.edges[&id];
+        let [start, end] = edge.vertices.map(|vertex| self.positions[&vertex]);
+
+        let degenerate =
+            || Error::Unsupported(format!("edge of length zero (#{id})"));
+
+        match (&edge.curve, circle) {
+            (
+                Curve::Circle {
+                    frame, same_sense, ..
+                },
+                Some(circle),
+            ) => {
+                let direction = if (frame.z.dot(&circle.normal())
+                    > Scalar::ZERO)
+                    == *same_sense
+                {
+                    1.
+                } else {
+                    -1.
+                };
+
+                let [start, end] = [start, end].map(|p| circle.angle(p));
+                let sweep = if edge.vertices[0] == edge.vertices[1] {
+                    TAU
+                } else {
+                    ((end - start).into_f64() * direction).rem_euclid(TAU)
+                };
+                if sweep < TOLERANCE {
+                    return Err(degenerate());
+                }
+
+                Ok([start, start + sweep * direction])
+            }
+            _ => {
+                if (end - start).magnitude() < Scalar::from(TOLERANCE) {
+                    return Err(degenerate());
+                }
+
+                Ok([Scalar::ZERO, Scalar::ONE])
+            }
+        }
+    }
+
+    fn face(
+        &mut self,
+        i: usize,
+        surface: Surface,
+        circles: &BTreeMap<u64, CircleGeometry>,
+        objects: &mut Service<Objects>,
+    ) -> Result<Handle<KernelFace>, Error> {
+        let face = &self.shell.faces[i];
+
+        let geometry = match surface {
+            Surface::Plane(frame) => SurfaceGeometry::Swept {
+                u: GlobalPath::Line(Line::from_origin_and_direction(
+                    frame.origin,
+                    frame.x,
+                )),
+                v: frame.y,
+            },
+            Surface::Cylinder(frame, radius) => SurfaceGeometry::Swept {
+                u: GlobalPath::Circle(Circle::new(
+                    frame.origin,
+                    frame.x * radius,
+                    frame.y * radius,
+                )),
+                v: frame.z,
+            },
+            Surface::Cone(frame, radius, semi_angle) => {
+                SurfaceGeometry::Cone(cone(&frame, radius, semi_angle))
+            }
+        };
+        let kernel_surface = KernelSurface::new(geometry).insert(objects);
+
+        let mut cycles = Vec::new();
+        for bound in &face.bounds {
+            let edges = match surface {
+                Surface::Plane(_) => bound.edges.clone(),
+                Surface::Cylinder(..) | Surface::Cone(..) => {
+                    self.align_circles(bound)?
+                }
+            };
+            let corners =
+                self.corners(bound.id, &edges, surface, circles.keys())?;
+
+            let surface_vertices = corners
+                .iter()
+                .map(|&(vertex, position)| {
+                    let global_form = self.global_vertices[&vertex].clone();
+                    SurfaceVertex::new(
+                        position,
+                        kernel_surface.clone(),
+                        global_form,
+                    )
+                    .insert(objects)
+                })
+                .collect::<Vec<_>>();
+
+            let mut half_edges = Vec::new();
+            for (j, &(id, forward)) in edges.iter().enumerate() {
+                let k = (j + 1) % edges.len();
+                let [back, front] =
+                    [&surface_vertices[j], &surface_vertices[k]];
+
+                let [start, end] = self.ranges[&id];
+                let [t_back, t_front] =
+                    if forward { [start, end] } else { [end, start] };
+
+                let path = match (surface, circles.get(&id)) {
+                    (Surface::Plane(frame), Some(circle)) => {
+                        SurfacePath::Circle(circle.in_plane(&frame))
+                    }
+                    // Circles on cylinders and cones, and lines on any surface,
+                    // are lines in surface coordinates.
+                    _ => SurfacePath::Line(Line::from_points_with_line_coords(
+                        [
+                            (t_back, back.position()),
+                            (t_front, front.position()),
+                        ]
+                        .map(|(t, point)| (Point::from([t]), point)),
+                    )),
+                };
+
+                let (global_curve, global_edge) =
+                    self.global_edges[&id].clone();
+                let curve = KernelCurve::new(
+                    kernel_surface.clone(),
+                    path,
+                    global_curve,
+                )
+                .insert(objects);
+
+                let vertices = [(t_back, back), (t_front, front)].map(
+                    |(t, surface_form)| {
+                        Vertex::new([t], curve.clone(), surface_form.clone())
+                            .insert(objects)
+                    },
+                );
+
+                half_edges
+                    .push(HalfEdge::new(vertices, global_edge).insert(objects));
+            }
+
+            cycles.push((bound.outer, half_edges));
+        }
+
+        // The exterior is usually marked as such. If it isn't, it's the cycle
+        // that encloses all others.
+        let exterior = match cycles.iter().filter(|(outer, _)| *outer).count() {
+            1 => cycles.iter().position(|(outer, _)| *outer),
+            _ => cycles
+                .iter()
+                .enumerate()
+                .max_by_key(|(_, (_, half_edges))| area(half_edges).abs())
+                .map(|(i, _)| i),
+        };
+        let Some(exterior) = exterior else {
+            return Err(Error::Unsupported(format!(
+                "face without boundary (#{})",
+                face.bounds.first().map_or(0, |bound| bound.id)
+            )));
+        };
+
+        let mut cycles = cycles
+            .into_iter()
+            .map(|(_, half_edges)| Cycle::new(half_edges).insert(objects))
+            .collect::<Vec<_>>();
+        let exterior = cycles.remove(exterior);
+
+        Ok(KernelFace::new(exterior, cycles, Color::default()).insert(objects))
+    }
+
+    /// Align the ranges of the circles in a boundary on a cylinder or cone
+    ///
+    /// The u-coordinates along the boundary must be continuous, so the range of
+    /// each circle must start where the previous one ended. Ranges are shifted
+    /// by full turns, to make that happen. The boundary is rotated to start
+    /// with a circle.
+    fn align_circles(
+        &mut self,
+        bound: &super::Bound,
+    ) -> Result<Vec<(u64, bool)>, Error> {
+        let is_circle = |&(id, _): &(u64, bool)| {
+            matches!(self.shell.edges[&id].curve, Curve::Circle { .. })
+        };
+
+        let mut edges = bound.edges.clone();
+        let Some(first) = edges.iter().position(is_circle) else {
+            return Err(Error::Unsupported(format!(
+                "curved face without circular edges (#{})",
+                bound.id
+            )));
+        };
+        edges.rotate_left(first);
+
+        let oriented = |range: [Scalar; 2], forward: bool| {
+            let [start, end] = range;
+            if forward {
+                [start, end]
+            } else {
+                [end, start]
+            }
+        };
+
+        let [mut u, _] = oriented(self.ranges[&edges[0].0], edges[0].1);
+        let start = u;
+
+        for &(id, forward) in &edges {
+            if !is_circle(&(id, forward)) {
+                continue;
+            }
+
+            let [back, _] = oriented(self.ranges[&id], forward);
+            let turns = ((u - back).into_f64() / TAU).round();
+            if turns != 0. {
+                if self.aligned.contains(&id) {
+                    return Err(Error::Unsupported(format!(
+                        "circle shared by multiple curved faces (#{id})"
+                    )));
+                }
+
+                let range = self.ranges.get_mut(&id).expect("Range exists");
+                *range = range.map(|t| t + turns * TAU);
+            }
+            self.aligned.insert(id);
+
+            let [_, front] = oriented(self.ranges[&id], forward);
+            u = front;
+        }
+
+        if (u - start).abs() > Scalar::from(TOLERANCE) {
+            return Err(Error::Unsupported(format!(
+                "curved face without seam edge (#{})",
+                bound.id
+            )));
+        }
+
+        Ok(edges)
+    }
+
+    /// Compute the surface coordinates of the corners of a boundary
+    ///
+    /// Corner `i` is where edge `i` starts.
+    fn corners<'c>(
+        &self,
+        bound: u64,
+        edges: &[(u64, bool)],
+        surface: Surface,
+        circles: impl Iterator<Item = &'c u64> + Clone,
+    ) -> Result<Vec<(u64, Point<2>)>, Error> {
+        let vertex = |&(id, forward): &(u64, bool), front: bool| {
+            let [start, end] = self.shell.edges[&id].vertices;
+            if forward != front {
+                start
+            } else {
+                end
+            }
+        };
+
+        let mut corners = Vec::new();
+        for (i, edge) in edges.iter().enumerate() {
+            let previous = &edges[(i + edges.len() - 1) % edges.len()];
+
+            let id = vertex(edge, false);
+            if vertex(previous, true) != id {
+                return Err(Error::InvalidEntity {
+                    id: bound,
+                    message: "edges of boundary are not connected".into(),
+                });
+            }
+
+            // On cylinders and cones, the u-coordinate is the curve
+            // coordinate of an adjacent circle, as those have been aligned.
+            let u = || {
+                let is_circle =
+                    |id: &u64| circles.clone().any(|circle| circle == id);
+                let [start, end] = self.ranges[&edge.0];
+                let [previous_start, previous_end] = self.ranges[&previous.0];
+
+                if is_circle(&edge.0) {
+                    Ok(if edge.1 { start } else { end })
+                } else if is_circle(&previous.0) {
+                    Ok(if previous.1 {
+                        previous_end
+                    } else {
+                        previous_start
+                    })
+                } else {
+                    Err(Error::Unsupported(format!(
+                        "curved face with adjacent straight edges (#{bound})"
+                    )))
+                }
+            };
+
+            let position = self.positions[&id];
+            let position = match surface {
+                Surface::Plane(frame) => {
+                    let offset = position - frame.origin;
+                    Point::from([offset.dot(&frame.x), offset.dot(&frame.y)])
+                }
+                Surface::Cylinder(frame, _) => {
+                    let v = (position - frame.origin).dot(&frame.z);
+                    Point::from([u()?, v])
+                }
+                Surface::Cone(frame, radius, semi_angle) => {
+                    let cone = cone(&frame, radius, semi_angle);
+                    let v = (position - cone.apex()).dot(&cone.axis())
+                        / cone.axis().dot(&cone.axis());
+                    Point::from([u()?, v])
+                }
+            };
+
+            corners.push((id, position));
+        }
+
+        Ok(corners)
+    }
+}
+
+/// A circle, defined by its center and an orthonormal basis
+#[derive(Clone, Copy)]
+struct CircleGeometry {
+    center: Point<3>,
+    a: Vector<3>,
+    b: Vector<3>,
+    radius: Scalar,
+}
+
+impl CircleGeometry {
+    fn normal(&self) -> Vector<3> {
+        self.a.cross(&self.b)
+    }
+
+    /// Compute the angle of a point on the circle, between 0 and 2π
+    fn angle(&self, point: Point<3>) -> Scalar {
+        let offset = point - self.center;
+        let angle = offset.dot(&self.b).atan2(offset.dot(&self.a));
+
+        if angle < Scalar::ZERO {
+            angle + Scalar::TAU
+        } else {
+            angle
+        }
+    }
+
+    /// Express the circle in the coordinates of a plane it is parallel to
+    ///
+    /// The angles of points on the circle stay the same.
+    fn in_plane(&self, plane: &Frame) -> Circle<2> {
+        let offset = self.center - plane.origin;
+        let center = Point::from([offset.dot(&plane.x), offset.dot(&plane.y)]);
+
+        let a = Vector::from([self.a.dot(&plane.x), self.a.dot(&plane.y)])
+            .normalize();
+        let [a_u, a_v] = a.components;
+        let b = if self.normal().dot(&plane.z) > Scalar::ZERO {
+            Vector::from([-a_v, a_u])
+        } else {
+            Vector::from([a_v, -a_u])
+        };
+
+        Circle::new(center, a * self.radius, b * self.radius)
+    }
+}
+
+/// Something that a vertex must lie on
+enum Constraint {
+    Surface(Surface),
+    Circle(CircleGeometry),
+}
+
+impl Constraint {
+    /// Find the point that is closest to the given one
+    fn project(&self, point: Point<3>) -> Point<3> {
+        match self {
+            Self::Surface(Surface::Plane(frame)) => {
+                point - frame.z * (point - frame.origin).dot(&frame.z)
+            }
+            Self::Surface(Surface::Cylinder(frame, radius)) => {
+                let offset = point - frame.origin;
+                let height = offset.dot(&frame.z);
+                let radial = offset - frame.z * height;
+
+                if radial.magnitude() == Scalar::ZERO {
+                    return point;
+                }
+
+                frame.origin + frame.z * height + radial.normalize() * *radius
+            }
+            Self::Surface(Surface::Cone(frame, radius, semi_angle)) => {
+                let apex = cone(frame, *radius, *semi_angle).apex();
+                let offset = point - apex;
+                let height = offset.dot(&frame.z);
+                let radial = offset - frame.z * height;
+
+                if radial.magnitude() == Scalar::ZERO {
+                    return point;
+                }
+
+                // The closest point is on the line from the apex, that is in
+                // the plane of the point and the axis.
+                let (sin, cos) = semi_angle.sin_cos();
+                let direction = frame.z * cos + radial.normalize() * sin;
+                apex + direction * offset.dot(&direction)
+            }
+            Self::Circle(circle) => {
+                let offset = point - circle.center;
+                let normal = circle.normal();
+                let radial = offset - normal * offset.dot(&normal);
+
+                if radial.magnitude() == Scalar::ZERO {
+                    return point;
+                }
+
+                circle.center + radial.normalize() * circle.radius
+            }
+        }
+    }
+}
+
+/// Build the geometry of a cone, as read from a STEP file
+///
+/// The kernel defines a cone by one of its circles. That's the circle at the
+/// origin of the frame, unless the apex is there. Then it's the circle at a
+/// height of one.
+fn cone(frame: &Frame, radius: Scalar, semi_angle: Scalar) -> Cone {
+    let tan = semi_angle.tan();
+    let apex = frame.origin - frame.z * (radius / tan);
+
+    let height = if radius > Scalar::from(TOLERANCE) {
+        Scalar::ZERO
+    } else {
+        Scalar::ONE
+    };
+    let circle_radius = radius + height * tan;
+
+    Cone::new(
+        apex,
+        frame.x * circle_radius,
+        frame.y * circle_radius,
+        frame.z * (height + radius / tan),
+    )
+}
+
+/// Compute the radius of the cross-section of a cylinder or cone
+///
+/// The height is measured along the z-axis of the surface's frame.
+fn radius_at(surface: &Surface, height: Scalar) -> Scalar {
+    match *surface {
+        Surface::Plane(_) => Scalar::ZERO,
+        Surface::Cylinder(_, radius) => radius,
+        Surface::Cone(_, radius, semi_angle) => {
+            radius + height * semi_angle.tan()
+        }
+    }
+}
+
+/// Determine whether a point is on the z-axis of a frame
+fn is_on_axis(frame: &Frame, point: Point<3>) -> bool {
+    let offset = point - frame.origin;
+    let radial = offset - frame.z * offset.dot(&frame.z);
+
+    radial.magnitude() < Scalar::from(TOLERANCE)
+}
+
+/// Compute the signed area that a cycle of half-edges encloses
+///
+/// Curved half-edges are sampled, which is accurate enough to tell cycles
+/// apart by their size.
+fn area(half_edges: &[Handle<HalfEdge>]) -> Scalar {
+    const SAMPLES_PER_CURVE: usize = 16;
+
+    let points = half_edges
+        .iter()
+        .flat_map(|half_edge| {
+            let num_samples = match half_edge.curve().path() {
+                SurfacePath::Line(_) => 1,
+                _ => SAMPLES_PER_CURVE,
+            };
+            half_edge.surface_samples(num_samples)
+        })
+        .collect::<Vec<_>>();
+
+    signed_area(&points)
+}
@@ -0,0 +1,663 @@
+//! Import from STEP
+//!
+//! STEP files describe solids through a graph of entities, from the solid down
+//! to the points that define its vertices. This module reads that graph in two
+//! steps: First, the topology and geometry of each shell are read into simple
+//! data structures. Then, those are turned into kernel objects (see
+//! [`build`]).
+//!
+//! The entities that are read are common to all application protocols that
+//! describe boundary representations (AP203, AP214, AP242). The rest of the
+//! file, like product structure, colors, or metadata, is ignored.
+
+mod build;
+mod parser;
+
+use std::collections::{btree_map::Entry, BTreeMap};
+
+use fj_kernel::{
+    objects::{Objects, Solid},
+    services::Service,
+    storage::Handle,
+};
+use fj_math::{Point, Scalar, Vector};
+
+use crate::Error;
+
+use self::parser::{Args, Data, Param};
+
+/// Read the solids from the contents of a STEP file
+pub fn read(
+    source: &str,
+    objects: &mut Service<Objects>,
+) -> Result<Vec<Handle<Solid>>, Error> {
+    let data = parser::parse(source)?;
+    let reader = Reader {
+        data: &data,
+        length_scale: context_scale(&data, Quantity::Length)?,
+        angle_scale: context_scale(&data, Quantity::PlaneAngle)?,
+    };
+
+    let mut solids = Vec::new();
+    for entity in data.entities() {
+        if entity.records.len() != 1
+            || entity.record("MANIFOLD_SOLID_BREP").is_none()
+        {
+            continue;
+        }
+
+        let solid = data.simple(entity.id)?;
+        let shell = reader.shell(solid.reference(1)?)?;
+        solids.push(build::solid(&shell, objects)?);
+    }
+
+    if solids.is_empty() {
+        return Err(Error::NoSolids);
+    }
+
+    Ok(solids)
+}
+
+/// Determine the factor that converts a quantity from the file into the units
+/// that Fornjot uses
+///
+/// STEP files can use different units in different contexts. This is rarely
+/// done in practice, so the unit of the first context is used for all of them.
+/// Files that don't define a unit are assumed to use millimeters and radians.
+fn context_scale(data: &Data, quantity: Quantity) -> Result<Scalar, Error> {
+    for entity in data.entities() {
+        let Some(context) = entity.record("GLOBAL_UNIT_ASSIGNED_CONTEXT")
+        else {
+            continue;
+        };
+
+        for unit in context.references(0)? {
+            if data.entity(unit)?.record(quantity.record()).is_some() {
+                return unit_scale(data, unit, quantity);
+            }
+        }
+    }
+
+    Ok(Scalar::ONE)
+}
+
+fn unit_scale(
+    data: &Data,
+    id: u64,
+    quantity: Quantity,
+) -> Result<Scalar, Error> {
+    let unit = data.entity(id)?;
+
+    if let Some(si_unit) = unit.record("SI_UNIT") {
+        let (name, scale) = match quantity {
+            Quantity::Length => ("METRE", 1e3),
+            Quantity::PlaneAngle => ("RADIAN", 1.),
+        };
+        if si_unit.enumeration(1)? != Some(name) {
+            return Err(
+                si_unit.invalid(format!("expected {} unit", quantity.name()))
+            );
+        }
+
+        let prefix = match si_unit.enumeration(0)? {
+            None => 1.,
+            Some("KILO") => 1e3,
+            Some("HECTO") => 1e2,
+            Some("DECA") => 1e1,
+            Some("DECI") => 1e-1,
+            Some("CENTI") => 1e-2,
+            Some("MILLI") => 1e-3,
+            Some("MICRO") => 1e-6,
+            Some("NANO") => 1e-9,
+            Some(prefix) => {
+                return Err(Error::Unsupported(format!(
+                    "unit prefix `{prefix}` (#{id})"
+                )))
+            }
+        };
+
+        return Ok(Scalar::from(prefix * scale));
+    }
+
+    // Units like inches or degrees are defined by their relation to another
+    // unit.
+    if let Some(conversion) = unit.record("CONVERSION_BASED_UNIT") {
+        let measure = data.simple(conversion.reference(1)?)?;
+        let invalid =
+            || measure.invalid(format!("expected {} measure", quantity.name()));
+        let value = match measure.param(0)? {
+            Param::Typed(_, params) => match *params.as_slice() {
+                [Param::Real(value)] => value,
+                [Param::Integer(value)] => value as f64,
+                _ => return Err(invalid()),
+            },
+            _ => return Err(invalid()),
+        };
+
+        return Ok(unit_scale(data, measure.reference(1)?, quantity)? * value);
+    }
+
+    Err(Error::Unsupported(format!(
+        "{} unit (#{id})",
+        quantity.name()
+    )))
+}
+
+/// A quantity whose unit is defined by a STEP file
+#[derive(Clone, Copy)]
+enum Quantity {
+    Length,
+    PlaneAngle,
+}
+
+impl Quantity {
+    fn name(&self) -> &'static str {
+        match self {
+            Self::Length => "length",
+            Self::PlaneAngle => "plane angle",
+        }
+    }
+
+    /// The record that marks a unit of this quantity
+    fn record(&self) -> &'static str {
+        match self {
+            Self::Length => "LENGTH_UNIT",
+            Self::PlaneAngle => "PLANE_ANGLE_UNIT",
+        }
+    }
+}
+
+/// Reads the entities that make up a shell
+struct Reader<'r> {
+    data: &'r Data,
+
+    /// The factor that converts lengths into millimeters
+    length_scale: Scalar,
+
+    /// The factor that converts angles into radians
+    angle_scale: Scalar,
+}
+
+impl Reader<'_> {
+    fn shell(&self, id: u64) -> Result<Shell, Error> {
+        let args = self.data.simple(id)?.expect(&["CLOSED_SHELL"])?;
+
+        let mut shell = Shell::default();
+        for face in args.references(1)? {
+            let face = self.face(face, &mut shell)?;
+            shell.faces.push(face);
+        }
+
+        Ok(shell)
+    }
+
+    fn face(&self, id: u64, shell: &mut Shell) -> Result<Face, Error> {
+        let args = self
+            .data
+            .simple(id)?
+            .expect(&["ADVANCED_FACE", "FACE_SURFACE"])?;
+
+        // Whether the face normal agrees with the surface normal is of no
+        // interest. Fornjot derives the orientation of faces from their
+        // boundaries, and those are already oriented accordingly.
+        let surface = self.surface(args.reference(2)?)?;
+        let bounds = args
+            .references(1)?
+            .into_iter()
+            .map(|bound| self.bound(bound, shell))
+            .collect::<Result<_, _>>()?;
+
+        Ok(Face { surface, bounds })
+    }
+
+    fn bound(&self, id: u64, shell: &mut Shell) -> Result<Bound, Error> {
+        let args = self
+            .data
+            .simple(id)?
+            .expect(&["FACE_OUTER_BOUND", "FACE_BOUND"])?;
+        let loop_ = self
+            .data
+            .simple(args.reference(1)?)?
+            .expect(&["EDGE_LOOP"])?;
+
+        let mut edges = Vec::new();
+        for oriented_edge in loop_.references(1)? {
+            let oriented_edge = self
+                .data
+                .simple(oriented_edge)?
+                .expect(&["ORIENTED_EDGE"])?;
+
+            let edge = oriented_edge.reference(3)?;
+            if !shell.edges.contains_key(&edge) {
+                let edge_curve = self.edge(edge, shell)?;
+                shell.edges.insert(edge, edge_curve);
+            }
+
+            edges.push((edge, oriented_edge.boolean(4)?));
+        }
+
+        if !args.boolean(2)? {
+            edges.reverse();
+            for (_, forward) in &mut edges {
+                *forward = !*forward;
+            }
+        }
+
+        Ok(Bound {
+            id,
+            outer: args.name == "FACE_OUTER_BOUND",
+            edges,
+        })
+    }
+
+    fn edge(&self, id: u64, shell: &mut Shell) -> Result<Edge, Error> {
+        let args = self.data.simple(id)?.expect(&["EDGE_CURVE"])?;
+
+        let vertices = [args.reference(1)?, args.reference(2)?];
+        for vertex in vertices {
+            if let Entry::Vacant(entry) = shell.vertices.entry(vertex) {
+                let point = self
+                    .data
+                    .simple(vertex)?
+                    .expect(&["VERTEX_POINT"])?
+                    .reference(1)?;
+                entry.insert(self.point(point)?);
+            }
+        }
+
+        let curve = self.curve(args.reference(3)?, args.boolean(4)?)?;
+
+        Ok(Edge { vertices, curve })
+    }
+
+    fn curve(&self, id: u64, same_sense: bool) -> Result<Curve, Error> {
+        let args = self.data.simple(id)?;
+
+        match args.name {
+            // Lines are fully defined by the vertices of their edges.
+            "LINE" => Ok(Curve::Line),
+            "CIRCLE" => Ok(Curve::Circle {
+                frame: self.frame(args.reference(1)?)?,
+                radius: self.length(args, 2)?,
+                same_sense,
+            }),
+            // Some applications wrap the curve, to provide its representation
+            // in the parameter space of the adjacent surfaces.
+            "SURFACE_CURVE" | "SEAM_CURVE" => {
+                self.curve(args.reference(1)?, same_sense)
+            }
+            name => Err(Error::Unsupported(format!(
+                "curve of type `{name}` (#{id})"
+            ))),
+        }
+    }
+
+    fn surface(&self, id: u64) -> Result<Surface, Error> {
+        let args = self.data.simple(id)?;
+
+        match args.name {
+            "PLANE" => Ok(Surface::Plane(self.frame(args.reference(1)?)?)),
+            "CYLINDRICAL_SURFACE" => Ok(Surface::Cylinder(
+                self.frame(args.reference(1)?)?,
+                self.length(args, 2)?,
+            )),
+            "CONICAL_SURFACE" => {
+                let semi_angle = self.angle(args, 3)?;
+                if semi_angle <= Scalar::ZERO || semi_angle >= Scalar::PI / 2. {
+                    return Err(
+                        args.invalid("semi-angle must be between 0 and 90°")
+                    );
+                }
+
+                Ok(Surface::Cone(
+                    self.frame(args.reference(1)?)?,
+                    self.length(args, 2)?,
+                    semi_angle,
+                ))
+            }
+            name => Err(Error::Unsupported(format!(
+                "surface of type `{name}` (#{id})"
+            ))),
+        }
+    }
+
+    fn frame(&self, id: u64) -> Result<Frame, Error> {
+        let args = self.data.simple(id)?.expect(&["AXIS2_PLACEMENT_3D"])?;
+
+        let origin = self.point(args.reference(1)?)?;
+        let z = match args.optional_reference(2)? {
+            Some(axis) => self.direction(axis)?,
+            None => Vector::unit_z(),
+        };
+        let x = match args.optional_reference(3)? {
+            Some(ref_direction) => self.direction(ref_direction)?,
+            None => Vector::unit_x(),
+        };
+
+        Ok(Frame::new(origin, z, x))
+    }
+
+    fn point(&self, id: u64) -> Result<Point<3>, Error> {
+        let args = self.data.simple(id)?.expect(&["CARTESIAN_POINT"])?;
+
+        match args.reals(1)?.as_slice() {
+            &[x, y, z] => {
+                Ok(Point::from([x, y, z]) * self.length_scale.into_f64())
+            }
+            _ => Err(args.invalid("expected 3-dimensional point")),
+        }
+    }
+
+    fn direction(&self, id: u64) -> Result<Vector<3>, Error> {
+        let args = self.data.simple(id)?.expect(&["DIRECTION"])?;
+
+        let direction = match args.reals(1)?.as_slice() {
+            &[x, y, z] => Vector::from([x, y, z]),
+            _ => return Err(args.invalid("expected 3-dimensional direction")),
+        };
+        if direction.magnitude() == Scalar::ZERO {
+            return Err(args.invalid("direction must not be zero"));
+        }
+
+        Ok(direction.normalize())
+    }
+
+    fn length(&self, args: Args, i: usize) -> Result<Scalar, Error> {
+        Ok(Scalar::from(args.real(i)?) * self.length_scale)
+    }
+
+    fn angle(&self, args: Args, i: usize) -> Result<Scalar, Error> {
+        Ok(Scalar::from(args.real(i)?) * self.angle_scale)
+    }
+}
+
+/// The topology and geometry of a shell, as read from a STEP file
+#[derive(Default)]
+struct Shell {
+    faces: Vec<Face>,
+
+    /// The edges of all faces, by the id of their `EDGE_CURVE`
+    edges: BTreeMap<u64, Edge>,
+
+    /// The positions of all vertices, by the id of their `VERTEX_POINT`
+    vertices: BTreeMap<u64, Point<3>>,
+}
+
+struct Face {
+    surface: Surface,
+    bounds: Vec<Bound>,
+}
+
+/// A boundary of a face
+///
+/// The edges are oriented, such that the face is on their left, when looking
+/// at its front.
+struct Bound {
+    id: u64,
+    outer: bool,
+
+    /// The ids of the edges, and whether they are traversed in their direction
+    edges: Vec<(u64, bool)>,
+}
+
+struct Edge {
+    vertices: [u64; 2],
+    curve: Curve,
+}
+
+enum Curve {
+    Line,
+    Circle {
+        frame: Frame,
+        radius: Scalar,
+
+        /// Whether the edge runs in the direction of the circle
+        same_sense: bool,
+    },
+}
+
+#[derive(Clone, Copy)]
+enum Surface {
+    Plane(Frame),
+    Cylinder(Frame, Scalar),
+
+    /// A cone, with its radius at the origin of the frame, and its semi-angle
+    ///
+    /// The radius grows along the z-axis.
+    Cone(Frame, Scalar, Scalar),
+}
+
+/// A right-handed coordinate system
+#[derive(Clone, Copy)]
+struct Frame {
+    origin: Point<3>,
+    x: Vector<3>,
+    y: Vector<3>,
+    z: Vector<3>,
+}
+
+impl Frame {
+    /// Create a frame from the z-axis and an approximation of the x-axis
+    fn new(origin: Point<3>, z: Vector<3>, x: Vector<3>) -> Self {
+        let z = z.normalize();
+
+        // The x-axis only needs to be approximately perpendicular. If it isn't
+        // at all, any perpendicular axis will do.
+        let mut x = x - z * x.dot(&z);
+        if x.magnitude() < Scalar::from(1e-9) {
+            x = z.cross(&Vector::unit_x());
+            if x.magnitude() < Scalar::from(1e-9) {
+                x = z.cross(&Vector::unit_y());
+            }
+        }
+        let x = x.normalize();
+
+        Self {
+            origin,
+            x,
+            y: z.cross(&x),
+            z,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{collections::BTreeSet, f64::consts::FRAC_PI_4};
+
+    use fj_kernel::{
+        geometry::surface::SurfaceGeometry,
+        services::{ServiceValidationExt, Services},
+    };
+    use fj_math::{Point, Scalar};
+
+    use crate::Error;
+
+    #[test]
+    fn read_cylinder() -> anyhow::Result<()> {
+        let mut services = Services::new();
+
+        let solids = super::read(CYLINDER, &mut services.objects)?;
+        assert!(services.validation.lock().take_errors().is_empty());
+
+        let [solid] = solids.as_slice() else {
+            panic!("Expected one solid");
+        };
+        let vertices = solid
+            .shells()
+            .flat_map(|shell| shell.faces())
+            .flat_map(|face| face.exterior().half_edges())
+            .flat_map(|half_edge| half_edge.vertices().clone())
+            .map(|vertex| {
+                vertex
+                    .global_form()
+                    .position()
+                    .coords
+                    .components
+                    .map(|c| c.into_f64().round() as i64)
+            })
+            .collect::<BTreeSet<_>>();
+
+        // Lengths are converted from inches into millimeters.
+        assert_eq!(vertices, BTreeSet::from([[127, 0, 0], [127, 0, 254]]));
+
+        Ok(())
+    }
+
+    #[test]
+    fn read_cone() -> anyhow::Result<()> {
+        let mut services = Services::new();
+
+        let solids = super::read(CONE, &mut services.objects)?;
+        assert!(services.validation.lock().take_errors().is_empty());
+
+        let [solid] = solids.as_slice() else {
+            panic!("Expected one solid");
+        };
+        let cones = solid
+            .shells()
+            .flat_map(|shell| shell.faces())
+            .filter_map(|face| match face.surface().geometry() {
+                SurfaceGeometry::Cone(cone) => Some(cone),
+                _ => None,
+            })
+            .collect::<Vec<_>>();
+
+        // The semi-angle is converted from degrees into radians.
+        let [cone] = cones.as_slice() else {
+            panic!("Expected one conical face");
+        };
+        assert!((cone.half_angle() - FRAC_PI_4).abs() < Scalar::from(1e-9));
+        assert!(
+            (cone.apex() - Point::from([0., 0., -5.])).magnitude()
+                < Scalar::from(1e-9)
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn reject_unsupported_surface() {
+        let mut services = Services::new();
+
+        let source = CYLINDER.replace(
+            "CYLINDRICAL_SURFACE('',#4,5.)",
+            "SPHERICAL_SURFACE('',#4,5.)",
+        );
+        let result = super::read(&source, &mut services.objects);
+
+        assert!(matches!(result, Err(Error::Unsupported(_))));
+    }
+
+    /// A cylinder, with its side split along a seam edge
+    const CYLINDER: &str = "\
+ISO-10303-21;
+HEADER;
+FILE_DESCRIPTION((''),'2;1');
+FILE_NAME('cylinder.step','',(''),(''),'','','');
+FILE_SCHEMA(('AUTOMOTIVE_DESIGN'));
+ENDSEC;
+DATA;
+#1=CARTESIAN_POINT('',(0.,0.,0.));
+#2=DIRECTION('',(0.,0.,1.));
+#3=DIRECTION('',(1.,0.,0.));
+#4=AXIS2_PLACEMENT_3D('',#1,#2,#3);
+#5=CARTESIAN_POINT('',(0.,0.,10.));
+#6=AXIS2_PLACEMENT_3D('',#5,#2,#3);
+#10=CARTESIAN_POINT('',(5.,0.,0.));
+#11=VERTEX_POINT('',#10);
+#12=CARTESIAN_POINT('',(5.,0.,10.));
+#13=VERTEX_POINT('',#12);
+#20=CIRCLE('',#4,5.);
+#21=CIRCLE('',#6,5.);
+#22=LINE('',#10,#23);
+#23=VECTOR('',#2,10.);
+#30=EDGE_CURVE('',#11,#11,#20,.T.);
+#31=EDGE_CURVE('',#13,#13,#21,.T.);
+#32=EDGE_CURVE('',#11,#13,#22,.T.);
+#40=PLANE('',#4);
+#41=EDGE_LOOP('',(#42));
+#42=ORIENTED_EDGE('',*,*,#30,.F.);
+#43=FACE_OUTER_BOUND('',#41,.T.);
+#44=ADVANCED_FACE('',(#43),#40,.F.);
+#50=PLANE('',#6);
+#51=EDGE_LOOP('',(#52));
+#52=ORIENTED_EDGE('',*,*,#31,.T.);
+#53=FACE_OUTER_BOUND('',#51,.T.);
+#54=ADVANCED_FACE('',(#53),#50,.T.);
+#60=CYLINDRICAL_SURFACE('',#4,5.);
+#61=EDGE_LOOP('',(#62,#63,#64,#65));
+#62=ORIENTED_EDGE('',*,*,#30,.T.);
+#63=ORIENTED_EDGE('',*,*,#32,.T.);
+#64=ORIENTED_EDGE('',*,*,#31,.F.);
+#65=ORIENTED_EDGE('',*,*,#32,.F.);
+#66=FACE_OUTER_BOUND('',#61,.T.);
+#67=ADVANCED_FACE('',(#66),#60,.T.);
+#70=CLOSED_SHELL('',(#44,#54,#67));
+#71=MANIFOLD_SOLID_BREP('',#70);
+#79=(GEOMETRIC_REPRESENTATION_CONTEXT(3)GLOBAL_UNIT_ASSIGNED_CONTEXT((#80))REPRESENTATION_CONTEXT('',''));
+#80=(CONVERSION_BASED_UNIT('INCH',#81)LENGTH_UNIT()NAMED_UNIT(#82));
+#81=LENGTH_MEASURE_WITH_UNIT(LENGTH_MEASURE(25.4),#83);
+#82=DIMENSIONAL_EXPONENTS(1.,0.,0.,0.,0.,0.,0.);
+#83=(LENGTH_UNIT()NAMED_UNIT(*)SI_UNIT(.MILLI.,.METRE.));
+ENDSEC;
+END-ISO-10303-21;
+";
+
+    /// A frustum, widening from a radius of 5 to 10, with its angles in degrees
+    const CONE: &str = "\
+ISO-10303-21;
+HEADER;
+FILE_DESCRIPTION((''),'2;1');
+FILE_NAME('cone.step','',(''),(''),'','','');
+FILE_SCHEMA(('AUTOMOTIVE_DESIGN'));
+ENDSEC;
+DATA;
+#1=CARTESIAN_POINT('',(0.,0.,0.));
+#2=DIRECTION('',(0.,0.,1.));
+#3=DIRECTION('',(1.,0.,0.));
+#4=AXIS2_PLACEMENT_3D('',#1,#2,#3);
+#5=CARTESIAN_POINT('',(0.,0.,5.));
+#6=AXIS2_PLACEMENT_3D('',#5,#2,#3);
+#10=CARTESIAN_POINT('',(5.,0.,0.));
+#11=VERTEX_POINT('',#10);
+#12=CARTESIAN_POINT('',(10.,0.,5.));
+#13=VERTEX_POINT('',#12);
+#20=CIRCLE('',#4,5.);
+#21=CIRCLE('',#6,10.);
+#22=LINE('',#10,#23);
+#23=VECTOR('',#24,7.0710678);
+#24=DIRECTION('',(0.70710678,0.,0.70710678));
+#30=EDGE_CURVE('',#11,#11,#20,.T.);
+#31=EDGE_CURVE('',#13,#13,#21,.T.);
+#32=EDGE_CURVE('',#11,#13,#22,.T.);
+#40=PLANE('',#4);
+#41=EDGE_LOOP('',(#42));
+#42=ORIENTED_EDGE('',*,*,#30,.F.);
+#43=FACE_OUTER_BOUND('',#41,.T.);
+#44=ADVANCED_FACE('',(#43),#40,.F.);
+#50=PLANE('',#6);
+#51=EDGE_LOOP('',(#52));
+#52=ORIENTED_EDGE('',*,*,#31,.T.);
+#53=FACE_OUTER_BOUND('',#51,.T.);
+#54=ADVANCED_FACE('',(#53),#50,.T.);
+#60=CONICAL_SURFACE('',#4,5.,45.);
+#61=EDGE_LOOP('',(#62,#63,#64,#65));
+#62=ORIENTED_EDGE('',*,*,#30,.T.);
+#63=ORIENTED_EDGE('',*,*,#32,.T.);
+#64=ORIENTED_EDGE('',*,*,#31,.F.);
+#65=ORIENTED_EDGE('',*,*,#32,.F.);
+#66=FACE_OUTER_BOUND('',#61,.T.);
+#67=ADVANCED_FACE('',(#66),#60,.T.);
+#70=CLOSED_SHELL('',(#44,#54,#67));
+#71=MANIFOLD_SOLID_BREP('',#70);
+#79=(GEOMETRIC_REPRESENTATION_CONTEXT(3)GLOBAL_UNIT_ASSIGNED_CONTEXT((#80,#81))REPRESENTATION_CONTEXT('',''));
+#80=(LENGTH_UNIT()NAMED_UNIT(*)SI_UNIT(.MILLI.,.METRE.));
+#81=(CONVERSION_BASED_UNIT('DEGREE',#82)NAMED_UNIT(#83)PLANE_ANGLE_UNIT());
+#82=PLANE_ANGLE_MEASURE_WITH_UNIT(PLANE_ANGLE_MEASURE(0.0174532925199433),#84);
+#83=DIMENSIONAL_EXPONENTS(0.,0.,0.,0.,0.,0.,0.);
+#84=(NAMED_UNIT(*)PLANE_ANGLE_UNIT()SI_UNIT($,.RADIAN.));
+ENDSEC;
+END-ISO-10303-21;
+";
+}
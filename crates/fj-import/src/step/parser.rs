@@ -0,0 +1,597 @@
+//! Parser for the exchange structure of STEP files
+//!
+//! The exchange structure is defined in ISO 10303-21. It consists of a header
+//! section, which is skipped, and one or more data sections, which contain the
+//! entity instances this parser is interested in.
+
+use std::{collections::BTreeMap, iter::Peekable, str::CharIndices};
+
+use crate::Error;
+
+/// The entity instances from the data sections of a STEP file
+pub struct Data {
+    entities: BTreeMap<u64, Entity>,
+}
+
+impl Data {
+    /// Access the entity with the given id
+    pub fn entity(&self, id: u64) -> Result<&Entity, Error> {
+        self.entities.get(&id).ok_or(Error::MissingEntity(id))
+    }
+
+    /// Access the simple entity with the given id
+    ///
+    /// Returns an error, if the entity is a complex one.
+    pub fn simple(&self, id: u64) -> Result<Args<'_>, Error> {
+        match self.entity(id)?.records.as_slice() {
+            [record] => Ok(Args {
+                id,
+                name: &record.name,
+                params: &record.params,
+            }),
+            _ => Err(Error::InvalidEntity {
+                id,
+                message: "expected simple entity, found complex one".into(),
+            }),
+        }
+    }
+
+    /// Iterate over all entities, ordered by id
+    pub fn entities(&self) -> impl Iterator<Item = &Entity> {
+        self.entities.values()
+    }
+}
+
+/// An entity instance
+///
+/// Simple entities consist of a single record, complex ones of multiple.
+pub struct Entity {
+    pub id: u64,
+    pub records: Vec<Record>,
+}
+
+impl Entity {
+    /// Access the record with the given name
+    pub fn record(&self, name: &str) -> Option<Args<'_>> {
+        self.records
+            .iter()
+            .find(|record| record.name == name)
+            .map(|record| Args {
+                id: self.id,
+                name: &record.name,
+                params: &record.params,
+            })
+    }
+}
+
+/// A named list of parameters
+pub struct Record {
+    pub name: String,
+    pub params: Vec<Param>,
+}
+
+/// A parameter of a record
+#[derive(Debug, PartialEq)]
+pub enum Param {
+    Integer(i64),
+    Real(f64),
+    String(String),
+    Enumeration(String),
+    Reference(u64),
+    List(Vec<Param>),
+    Typed(String, Vec<Param>),
+    Binary,
+    Unset,
+    Derived,
+}
+
+/// The parameters of a record, with convenient access
+#[derive(Clone, Copy)]
+pub struct Args<'r> {
+    pub id: u64,
+    pub name: &'r str,
+    pub params: &'r [Param],
+}
+
+impl<'r> Args<'r> {
+    /// Return an error, unless the record has one of the given names
+    pub fn expect(self, names: &[&str]) -> Result<Self, Error> {
+        if names.contains(&self.name) {
+            return Ok(self);
+        }
+
+        Err(self.invalid(format!(
+            "expected {}, found `{}`",
+            names.join(" or "),
+            self.name
+        )))
+    }
+
+    pub fn param(&self, i: usize) -> Result<&'r Param, Error> {
+        self.params.get(i).ok_or_else(|| {
+            self.invalid(format!("expected at least {} parameters", i + 1))
+        })
+    }
+
+    pub fn reference(&self, i: usize) -> Result<u64, Error> {
+        match self.param(i)? {
+            &Param::Reference(id) => Ok(id),
+            param => Err(self.unexpected(i, "reference", param)),
+        }
+    }
+
+    pub fn optional_reference(&self, i: usize) -> Result<Option<u64>, Error> {
+        match self.param(i)? {
+            Param::Unset => Ok(None),
+            _ => self.reference(i).map(Some),
+        }
+    }
+
+    pub fn references(&self, i: usize) -> Result<Vec<u64>, Error> {
+        self.list(i)?
+            .iter()
+            .map(|param| match param {
+                &Param::Reference(id) => Ok(id),
+                param => Err(self.unexpected(i, "list of references", param)),
+            })
+            .collect()
+    }
+
+    pub fn real(&self, i: usize) -> Result<f64, Error> {
+        match self.param(i)? {
+            &Param::Real(value) => Ok(value),
+            &Param::Integer(value) => Ok(value as f64),
+            param => Err(self.unexpected(i, "real", param)),
+        }
+    }
+
+    pub fn reals(&self, i: usize) -> Result<Vec<f64>, Error> {
+        self.list(i)?
+            .iter()
+            .map(|param| match param {
+                &Param::Real(value) => Ok(value),
+                &Param::Integer(value) => Ok(value as f64),
+                param => Err(self.unexpected(i, "list of reals", param)),
+            })
+            .collect()
+    }
+
+    pub fn boolean(&self, i: usize) -> Result<bool, Error> {
+        match self.param(i)? {
+            Param::Enumeration(value) if value == "T" => Ok(true),
+            Param::Enumeration(value) if value == "F" => Ok(false),
+            param => Err(self.unexpected(i, "boolean", param)),
+        }
+    }
+
+    pub fn enumeration(&self, i: usize) -> Result<Option<&'r str>, Error> {
+        match self.param(i)? {
+            Param::Enumeration(value) => Ok(Some(value)),
+            Param::Unset => Ok(None),
+            param => Err(self.unexpected(i, "enumeration", param)),
+        }
+    }
+
+    pub fn list(&self, i: usize) -> Result<&'r [Param], Error> {
+        match self.param(i)? {
+            Param::List(params) => Ok(params),
+            param => Err(self.unexpected(i, "list", param)),
+        }
+    }
+
+    pub fn invalid(&self, message: impl Into<String>) -> Error {
+        Error::InvalidEntity {
+            id: self.id,
+            message: format!("`{}`: {}", self.name, message.into()),
+        }
+    }
+
+    fn unexpected(&self, i: usize, expected: &str, found: &Param) -> Error {
+        self.invalid(format!(
+            "expected {expected} as parameter {}, found {found:?}",
+            i + 1
+        ))
+    }
+}
+
+/// Parse the exchange structure of a STEP file
+pub fn parse(source: &str) -> Result<Data, Error> {
+    let mut parser = Parser {
+        tokens: lex(source)?,
+        next: 0,
+    };
+    let mut entities = BTreeMap::new();
+    let mut in_data_section = false;
+
+    while let Some(token) = parser.peek() {
+        match token {
+            Token::Keyword(keyword) if keyword == "DATA" => {
+                parser.skip_statement();
+                in_data_section = true;
+            }
+            Token::Keyword(keyword) if keyword == "ENDSEC" => {
+                parser.skip_statement();
+                in_data_section = false;
+            }
+            &Token::Instance(id) if in_data_section => {
+                parser.advance();
+                parser.expect(Token::Equals)?;
+
+                let records = if parser.peek() == Some(&Token::Open) {
+                    parser.advance();
+
+                    let mut records = Vec::new();
+                    while parser.peek() != Some(&Token::Close) {
+                        records.push(parser.record()?);
+                    }
+                    parser.advance();
+
+                    records
+                } else {
+                    vec![parser.record()?]
+                };
+                parser.expect(Token::Semicolon)?;
+
+                if entities.insert(id, Entity { id, records }).is_some() {
+                    return Err(parser.error(format!(
+                        "entity #{id} is defined more than once"
+                    )));
+                }
+            }
+            _ => parser.skip_statement(),
+        }
+    }
+
+    Ok(Data { entities })
+}
+
+struct Parser {
+    tokens: Vec<(Token, usize)>,
+    next: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.next).map(|(token, _)| token)
+    }
+
+    fn advance(&mut self) -> Option<Token> {
+        let (token, _) = self.tokens.get(self.next)?.clone();
+        self.next += 1;
+        Some(token)
+    }
+
+    fn expect(&mut self, expected: Token) -> Result<(), Error> {
+        match self.advance() {
+            Some(token) if token == expected => Ok(()),
+            token => Err(self.error(format!(
+                "expected {expected:?}, found {}",
+                describe(token.as_ref())
+            ))),
+        }
+    }
+
+    fn skip_statement(&mut self) {
+        while let Some(token) = self.advance() {
+            if token == Token::Semicolon {
+                break;
+            }
+        }
+    }
+
+    fn record(&mut self) -> Result<Record, Error> {
+        let name = match self.advance() {
+            Some(Token::Keyword(name)) => name,
+            token => {
+                return Err(self.error(format!(
+                    "expected entity type, found {}",
+                    describe(token.as_ref())
+                )))
+            }
+        };
+        let params = self.params()?;
+
+        Ok(Record { name, params })
+    }
+
+    fn params(&mut self) -> Result<Vec<Param>, Error> {
+        self.expect(Token::Open)?;
+
+        let mut params = Vec::new();
+        if self.peek() == Some(&Token::Close) {
+            self.advance();
+            return Ok(params);
+        }
+
+        loop {
+            params.push(self.param()?);
+
+            match self.advance() {
+                Some(Token::Comma) => continue,
+                Some(Token::Close) => break,
+                token => {
+                    return Err(self.error(format!(
+                        "expected `,` or `)`, found {}",
+                        describe(token.as_ref())
+                    )))
+                }
+            }
+        }
+
+        Ok(params)
+    }
+
+    fn param(&mut self) -> Result<Param, Error> {
+        let param = match self.peek() {
+            Some(Token::Open) => return self.params().map(Param::List),
+            Some(Token::Keyword(_)) => {
+                let record = self.record()?;
+                return Ok(Param::Typed(record.name, record.params));
+            }
+            _ => match self.advance() {
+                Some(Token::Integer(value)) => Param::Integer(value),
+                Some(Token::Real(value)) => Param::Real(value),
+                Some(Token::String(value)) => Param::String(value),
+                Some(Token::Enumeration(value)) => Param::Enumeration(value),
+                Some(Token::Instance(id)) => Param::Reference(id),
+                Some(Token::Binary) => Param::Binary,
+                Some(Token::Dollar) => Param::Unset,
+                Some(Token::Asterisk) => Param::Derived,
+                token => {
+                    return Err(self.error(format!(
+                        "expected parameter, found {}",
+                        describe(token.as_ref())
+                    )))
+                }
+            },
+        };
+
+        Ok(param)
+    }
+
+    fn error(&self, message: String) -> Error {
+        let line = self
+            .tokens
+            .get(self.next.saturating_sub(1))
+            .map_or(0, |&(_, line)| line);
+
+        Error::Syntax { line, message }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+enum Token {
+    Keyword(String),
+    Instance(u64),
+    Integer(i64),
+    Real(f64),
+    String(String),
+    Enumeration(String),
+    Binary,
+    Open,
+    Close,
+    Comma,
+    Semicolon,
+    Equals,
+    Dollar,
+    Asterisk,
+    Other(char),
+}
+
+fn describe(token: Option<&Token>) -> String {
+    match token {
+        Some(token) => format!("{token:?}"),
+        None => "end of file".into(),
+    }
+}
+
+fn lex(source: &str) -> Result<Vec<(Token, usize)>, Error> {
+    let mut chars = source.char_indices().peekable();
+    let mut tokens = Vec::new();
+    let mut line = 1;
+
+    while let Some((_, c)) = chars.next() {
+        let token = match c {
+            '\n' => {
+                line += 1;
+                continue;
+            }
+            c if c.is_whitespace() => continue,
+            '/' if chars.next_if(|&(_, c)| c == '*').is_some() => {
+                let mut previous = ' ';
+                loop {
+                    match chars.next() {
+                        Some((_, '/')) if previous == '*' => break,
+                        Some((_, c)) => {
+                            if c == '\n' {
+                                line += 1;
+                            }
+                            previous = c;
+                        }
+                        None => {
+                            return Err(Error::Syntax {
+                                line,
+                                message: "unterminated comment".into(),
+                            })
+                        }
+                    }
+                }
+                continue;
+            }
+            '(' => Token::Open,
+            ')' => Token::Close,
+            ',' => Token::Comma,
+            ';' => Token::Semicolon,
+            '=' => Token::Equals,
+            '$' => Token::Dollar,
+            '*' => Token::Asterisk,
+            '#' => {
+                let digits = take_while(&mut chars, |c| c.is_ascii_digit());
+                let id = digits.parse().map_err(|_| Error::Syntax {
+                    line,
+                    message: "expected entity id after `#`".into(),
+                })?;
+                Token::Instance(id)
+            }
+            '\'' => {
+                let mut value = String::new();
+                loop {
+                    match chars.next() {
+                        Some((_, '\'')) => {
+                            if chars.next_if(|&(_, c)| c == '\'').is_none() {
+                                break;
+                            }
+                            value.push('\'');
+                        }
+                        // Line breaks within strings are not part of them.
+                        Some((_, '\n')) => line += 1,
+                        Some((_, '\r')) => {}
+                        Some((_, c)) => value.push(c),
+                        None => {
+                            return Err(Error::Syntax {
+                                line,
+                                message: "unterminated string".into(),
+                            })
+                        }
+                    }
+                }
+                Token::String(value)
+            }
+            '"' => {
+                take_while(&mut chars, |c| c != '"');
+                if chars.next().is_none() {
+                    return Err(Error::Syntax {
+                        line,
+                        message: "unterminated binary".into(),
+                    });
+                }
+                Token::Binary
+            }
+            '.' if matches!(chars.peek(), Some(&(_, c)) if c.is_alphabetic()) =>
+            {
+                let value = take_while(&mut chars, |c| {
+                    c.is_ascii_alphanumeric() || c == '_'
+                });
+                if chars.next_if(|&(_, c)| c == '.').is_none() {
+                    return Err(Error::Syntax {
+                        line,
+                        message: format!("unterminated enumeration `{value}`"),
+                    });
+                }
+                Token::Enumeration(value)
+            }
+            c if c.is_ascii_alphabetic() || c == '!' => {
+                let mut keyword = c.to_string();
+                keyword.push_str(&take_while(&mut chars, |c| {
+                    c.is_ascii_alphanumeric() || c == '_'
+                }));
+                Token::Keyword(keyword.to_ascii_uppercase())
+            }
+            c if c.is_ascii_digit()
+                || ((c == '-' || c == '+')
+                    && matches!(chars.peek(), Some(&(_, c)) if c.is_ascii_digit())) =>
+            {
+                let mut number = c.to_string();
+                number.push_str(&take_while(&mut chars, |c| {
+                    c.is_ascii_digit() || c == '.'
+                }));
+                if let Some((_, e)) =
+                    chars.next_if(|&(_, c)| c == 'E' || c == 'e')
+                {
+                    number.push(e);
+                    if let Some((_, sign)) =
+                        chars.next_if(|&(_, c)| c == '-' || c == '+')
+                    {
+                        number.push(sign);
+                    }
+                    number.push_str(&take_while(&mut chars, |c| {
+                        c.is_ascii_digit()
+                    }));
+                }
+
+                let invalid = || Error::Syntax {
+                    line,
+                    message: format!("invalid number `{number}`"),
+                };
+                if number.contains(['.', 'E', 'e']) {
+                    Token::Real(number.parse().map_err(|_| invalid())?)
+                } else {
+                    Token::Integer(number.parse().map_err(|_| invalid())?)
+                }
+            }
+            c => Token::Other(c),
+        };
+
+        tokens.push((token, line));
+    }
+
+    Ok(tokens)
+}
+
+fn take_while(
+    chars: &mut Peekable<CharIndices>,
+    predicate: impl Fn(char) -> bool,
+) -> String {
+    let mut taken = String::new();
+    while let Some((_, c)) = chars.next_if(|&(_, c)| predicate(c)) {
+        taken.push(c);
+    }
+    taken
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::Error;
+
+    use super::{parse, Param};
+
+    #[test]
+    fn parse_entities() -> anyhow::Result<()> {
+        let data = parse(
+            "ISO-10303-21;\n\
+            HEADER;\n\
+            FILE_NAME('a;b','2022-12-01T00:00:00',(''),(''),'','','');\n\
+            ENDSEC;\n\
+            DATA;\n\
+            /* A comment */\n\
+            #1=CARTESIAN_POINT('it''s',(0.,-1.5,2.E+01));\n\
+            #2=EDGE_CURVE('',#3,$,*,.T.);\n\
+            #3=(LENGTH_UNIT() NAMED_UNIT(*) SI_UNIT(.MILLI.,.METRE.));\n\
+            #4=MEASURE(LENGTH_MEASURE(25.4),#3);\n\
+            ENDSEC;\n\
+            END-ISO-10303-21;\n",
+        )?;
+
+        let point = data.simple(1)?;
+        assert_eq!(point.name, "CARTESIAN_POINT");
+        assert_eq!(point.param(0)?, &Param::String("it's".into()));
+        assert_eq!(point.reals(1)?, [0., -1.5, 20.]);
+
+        let edge = data.simple(2)?;
+        assert_eq!(edge.reference(1)?, 3);
+        assert_eq!(edge.optional_reference(2)?, None);
+        assert_eq!(edge.param(3)?, &Param::Derived);
+        assert!(edge.boolean(4)?);
+
+        let unit = data.entity(3)?;
+        assert_eq!(unit.records.len(), 3);
+        let si_unit = unit.record("SI_UNIT").unwrap();
+        assert_eq!(si_unit.enumeration(0)?, Some("MILLI"));
+
+        let measure = data.simple(4)?;
+        assert_eq!(
+            measure.param(0)?,
+            &Param::Typed("LENGTH_MEASURE".into(), vec![Param::Real(25.4)])
+        );
+
+        assert!(matches!(data.entity(5), Err(Error::MissingEntity(5))));
+
+        Ok(())
+    }
+
+    #[test]
+    fn report_line_of_syntax_error() {
+        let result = parse("DATA;\n#1=POINT(1.,2.);\n#2=POINT(1.,;\nENDSEC;");
+        assert!(matches!(result, Err(Error::Syntax { line: 3, .. })));
+    }
+}
@@ -0,0 +1,62 @@
+//! Results of analyzing a triangle mesh
+
+use fj_math::Scalar;
+
+use crate::mesh::Color;
+
+/// Scalar values, one for each triangle of a mesh
+///
+/// Used to visualize the results of an analysis, by coloring each triangle
+/// according to its value.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct TriangleValues {
+    /// The values, in the order of the triangles of the mesh
+    ///
+    /// Triangles for which the analysis didn't produce a value, have `None`.
+    pub values: Vec<Option<Scalar>>,
+
+    /// The values that map to the low and the high end of the color scale
+    ///
+    /// Values outside of this range get the color of the nearest end.
+    pub range: [Scalar; 2],
+}
+
+impl TriangleValues {
+    /// Compute the color of each triangle
+    ///
+    /// Low values are red, values in the middle of the range are yellow, and
+    /// high values are green. Triangles without value are gray.
+    pub fn colors(&self) -> impl Iterator<Item = Color> + '_ {
+        let [low, high] = self.range.map(Scalar::into_f64);
+
+        self.values.iter().map(move |value| {
+            let Some(value) = value else {
+                return Color([128, 128, 128, 255]);
+            };
+
+            let t = if high > low {
+                ((value.into_f64() - low) / (high - low)).clamp(0., 1.)
+            } else {
+                0.5
+            };
+
+            let red = (2. - 2. * t).min(1.);
+            let green = (2. * t).min(1.);
+
+            Color([(red * 255.) as u8, (green * 255.) as u8, 0, 255])
+        })
+    }
+}
+
+/// How two triangle meshes relate to each other in space
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash, Ord, PartialOrd)]
+pub enum Interference {
+    /// The bounding boxes of the meshes don't overlap
+    Clear,
+
+    /// The bounding boxes of the meshes overlap, but the meshes don't
+    Close,
+
+    /// The meshes intersect or touch, or one of them encloses the other
+    Interfering,
+}
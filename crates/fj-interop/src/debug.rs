@@ -4,6 +4,8 @@
 //! ecosystem. The types in here aren't very useful in themselves, but they
 //! define an interface that other crates use to communicate between each other.
 
+use std::time::Duration;
+
 use fj_math::{Point, Segment};
 
 /// Debug info from the CAD kernel that can be visualized
@@ -11,6 +13,12 @@ use fj_math::{Point, Segment};
 pub struct DebugInfo {
     /// Rays being used during face triangulation
     pub triangle_edge_checks: Vec<TriangleEdgeCheck>,
+
+    /// Timings of the operations that were evaluated to create the shape
+    pub operation_timings: Vec<OperationTiming>,
+
+    /// Faces that could not be triangulated by the primary algorithm
+    pub triangulation_failures: Vec<TriangulationFailure>,
 }
 
 impl DebugInfo {
@@ -26,6 +34,8 @@ impl DebugInfo {
     /// allocations.
     pub fn clear(&mut self) {
         self.triangle_edge_checks.clear();
+        self.operation_timings.clear();
+        self.triangulation_failures.clear();
     }
 }
 
@@ -48,3 +58,56 @@ impl TriangleEdgeCheck {
         }
     }
 }
+
+/// Record of a face that the primary triangulation algorithm failed on
+///
+/// The face has been triangulated using a simpler fallback algorithm instead.
+/// The result of that might not be correct.
+#[derive(Clone, Debug)]
+pub struct TriangulationFailure {
+    /// The error that the primary triangulation algorithm returned
+    pub error: String,
+
+    /// The points of the face's exterior
+    pub exterior: Vec<Point<3>>,
+}
+
+/// Record of how long it took to evaluate an operation
+///
+/// Operations form a tree, so each timing includes the timings of the
+/// operations that were evaluated as part of it.
+#[derive(Clone, Debug)]
+pub struct OperationTiming {
+    /// The name of the operation
+    pub name: String,
+
+    /// The time it took to evaluate the operation, including its children
+    pub duration: Duration,
+
+    /// The timings of the operations evaluated as part of this one
+    pub children: Vec<OperationTiming>,
+}
+
+impl OperationTiming {
+    /// Compute the time spent in this operation, excluding its children
+    pub fn self_duration(&self) -> Duration {
+        let children = self.children.iter().map(|child| child.duration).sum();
+        self.duration.saturating_sub(children)
+    }
+
+    /// Find the operation that spent the most time on its own
+    ///
+    /// Considers this operation and all of its descendants.
+    pub fn slowest(&self) -> &OperationTiming {
+        self.children.iter().map(OperationTiming::slowest).fold(
+            self,
+            |slowest, timing| {
+                if timing.self_duration() > slowest.self_duration() {
+                    timing
+                } else {
+                    slowest
+                }
+            },
+        )
+    }
+}
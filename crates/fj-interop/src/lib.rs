@@ -14,7 +14,10 @@
 
 #![warn(missing_docs)]
 
+pub mod analysis;
 pub mod debug;
 pub mod ext;
+pub mod mass_properties;
 pub mod mesh;
 pub mod processed_shape;
+pub mod units;
@@ -0,0 +1,26 @@
+//! Mass properties of a shape
+
+use fj_math::{Point, Scalar};
+
+/// The mass properties of a shape
+///
+/// All values assume a uniform density of one. To get the properties for
+/// another density, multiply the volume and inertia tensor with it.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct MassProperties {
+    /// The volume of the shape
+    ///
+    /// This is zero, if the shape doesn't enclose a volume.
+    pub volume: Scalar,
+
+    /// The area of the surface of the shape
+    pub surface_area: Scalar,
+
+    /// The center of mass of the shape
+    ///
+    /// If the shape doesn't enclose a volume, this is the origin.
+    pub center_of_mass: Point<3>,
+
+    /// The inertia tensor of the shape, relative to its center of mass
+    pub inertia: [[Scalar; 3]; 3],
+}
@@ -1,8 +1,8 @@
 //! A triangle mesh
 
-use std::{collections::HashMap, hash::Hash};
+use std::{collections::HashMap, fmt, hash::Hash, sync::Arc};
 
-use fj_math::Point;
+use fj_math::{Point, Vector};
 
 /// A triangle mesh
 #[derive(Clone, Debug)]
@@ -67,7 +67,7 @@ where
 
     /// Access the triangles of the mesh
     pub fn triangles(&self) -> impl Iterator<Item = Triangle> + '_ {
-        self.triangles.iter().copied()
+        self.triangles.iter().cloned()
     }
 }
 
@@ -78,16 +78,47 @@ impl Mesh<Point<3>> {
         triangle: impl Into<fj_math::Triangle<3>>,
         color: Color,
     ) {
-        let triangle = triangle.into();
+        self.push(Triangle {
+            inner: triangle.into(),
+            normals: None,
+            color,
+            attributes: Attributes::default(),
+            origin: Origin::default(),
+        });
+    }
 
-        for point in triangle.points() {
+    /// Add a triangle to the mesh, along with the surface normals at its points
+    ///
+    /// The normals are expected in the same order as the points.
+    pub fn push_triangle_with_normals(
+        &mut self,
+        triangle: impl Into<fj_math::Triangle<3>>,
+        normals: [Vector<3>; 3],
+        color: Color,
+    ) {
+        self.push(Triangle {
+            inner: triangle.into(),
+            normals: Some(normals),
+            color,
+            attributes: Attributes::default(),
+            origin: Origin::default(),
+        });
+    }
+
+    fn push(&mut self, triangle: Triangle) {
+        for point in triangle.inner.points() {
             self.push_vertex(point);
         }
 
-        self.triangles.push(Triangle {
-            inner: triangle,
-            color,
-        });
+        self.triangles.push(triangle);
+    }
+}
+
+impl Extend<Triangle> for Mesh<Point<3>> {
+    fn extend<T: IntoIterator<Item = Triangle>>(&mut self, triangles: T) {
+        for triangle in triangles {
+            self.push(triangle);
+        }
     }
 }
 
@@ -109,14 +140,110 @@ pub type Index = u32;
 
 /// A triangle
 ///
-/// Extension of [`fj_math::Triangle`] that also includes a color.
-#[derive(Clone, Copy, Debug, Eq, PartialEq, Ord, PartialOrd)]
+/// Extension of [`fj_math::Triangle`] that also includes a color, and the
+/// attributes of the face it was triangulated from.
+#[derive(Clone, Debug, Eq, PartialEq, Ord, PartialOrd)]
 pub struct Triangle {
     /// The points of the triangle
     pub inner: fj_math::Triangle<3>,
 
+    /// The normals of the surface at the points of the triangle, if known
+    ///
+    /// Triangles that approximate a curved surface need these to be shaded
+    /// smoothly. Triangles of meshes that weren't triangulated from a shape,
+    /// like imported ones, don't have them.
+    pub normals: Option<[Vector<3>; 3]>,
+
     /// The color of the triangle
     pub color: Color,
+
+    /// The attributes of the face that the triangle is part of
+    pub attributes: Attributes,
+
+    /// The kernel objects that the triangle was created from
+    pub origin: Origin,
+}
+
+impl Triangle {
+    /// Access the normals at the points of the triangle
+    ///
+    /// Falls back to the normal of the flat triangle, if the normals of the
+    /// surface are not known.
+    pub fn vertex_normals(&self) -> [Vector<3>; 3] {
+        self.normals.unwrap_or_else(|| [self.inner.normal(); 3])
+    }
+}
+
+/// Metadata of a face, that is carried along with its geometry
+///
+/// Models attach attributes to their faces, to name them or assign materials.
+/// Operations that derive faces from other faces keep the attributes, and
+/// triangulation passes them on to every triangle of a face. The strings are
+/// shared, so copying attributes around is cheap.
+#[derive(Clone, Debug, Default, Eq, PartialEq, Hash, Ord, PartialOrd)]
+pub struct Attributes {
+    /// The name of the face
+    pub name: Option<Arc<str>>,
+
+    /// The material that the face is made of
+    ///
+    /// This is an identifier chosen by the model. Exporters use it to assign
+    /// materials, for example in 3MF and glTF files.
+    pub material: Option<Arc<str>>,
+}
+
+/// The kernel objects that a triangle was created from
+///
+/// Triangles that weren't triangulated from a shape, or whose shape no longer
+/// exists, like those of cached or imported meshes, don't know their origin.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, Hash, Ord, PartialOrd)]
+pub struct Origin {
+    /// The face that the triangle is part of
+    pub face: Option<ObjectId>,
+
+    /// The edges that the sides of the triangle lie on
+    ///
+    /// The side at index `i` connects the points at indices `i` and `i + 1`
+    /// (wrapping around) of the triangle. Sides within the face don't lie on
+    /// any edge.
+    pub edges: [Option<ObjectId>; 3],
+
+    /// The vertices that the points of the triangle lie on
+    ///
+    /// Points that aren't on a vertex of the face don't have one.
+    pub vertices: [Option<ObjectId>; 3],
+}
+
+impl Origin {
+    /// Adapt the origin to the winding of its triangle being reversed
+    ///
+    /// Expects the winding to be reversed by swapping the last two points,
+    /// turning the points `[a, b, c]` into `[a, c, b]`. This changes which
+    /// side is at which index.
+    pub fn reverse(self) -> Self {
+        let [ab, bc, ca] = self.edges;
+        let [a, b, c] = self.vertices;
+
+        Self {
+            face: self.face,
+            edges: [ca, bc, ab],
+            vertices: [a, c, b],
+        }
+    }
+}
+
+/// The ID of a kernel object
+///
+/// IDs are unique among the objects that exist at the same time, and are only
+/// meaningful within the process that created the objects.
+#[derive(Clone, Copy, Eq, PartialEq, Hash, Ord, PartialOrd)]
+pub struct ObjectId(pub u64);
+
+impl fmt::Debug for ObjectId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let id = self.0;
+        write!(f, "object id {id:#x}")
+    }
 }
 
 /// RGBA color
@@ -1,8 +1,10 @@
 //! A processed shape
 
-use fj_math::{Aabb, Point};
+use fj_math::{Aabb, Point, Scalar};
 
-use crate::{debug::DebugInfo, mesh::Mesh};
+use crate::{
+    debug::DebugInfo, mass_properties::MassProperties, mesh::Mesh, units::Units,
+};
 
 /// A processed shape
 #[derive(Clone, Debug)]
@@ -10,9 +12,33 @@ pub struct ProcessedShape {
     /// The axis-aligned bounding box of the shape
     pub aabb: Aabb<3>,
 
+    /// The tolerance that was used to triangulate the shape
+    pub tolerance: Scalar,
+
     /// The triangle mesh that approximates the original shape
     pub mesh: Mesh<Point<3>>,
 
+    /// The parts that make up the shape
+    ///
+    /// Their meshes together make up [`ProcessedShape::mesh`].
+    pub parts: Vec<ProcessedPart>,
+
+    /// The mass properties of the shape, computed from its triangle mesh
+    pub mass_properties: MassProperties,
+
     /// The debug info generated while processing the shape
     pub debug_info: DebugInfo,
 }
+
+/// A processed part of a shape
+#[derive(Clone, Debug)]
+pub struct ProcessedPart {
+    /// The name of the part
+    pub name: String,
+
+    /// The triangle mesh that approximates the part
+    pub mesh: Mesh<Point<3>>,
+
+    /// The units that the lengths of the part are given in
+    pub units: Units,
+}
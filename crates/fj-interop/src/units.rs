@@ -0,0 +1,35 @@
+//! Units of length
+
+/// The units that the lengths of a shape are given in
+///
+/// Shapes are processed in the units they are defined in. The units are only
+/// taken into account when exporting, where they are recorded in the file, or
+/// the shape is converted into other units.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, Hash, Ord, PartialOrd)]
+pub enum Units {
+    /// Millimeters
+    #[default]
+    Millimeters,
+
+    /// Inches
+    Inches,
+
+    /// Meters
+    Meters,
+}
+
+impl Units {
+    /// The length of one unit, in millimeters
+    pub fn millimeters(self) -> f64 {
+        match self {
+            Self::Millimeters => 1.,
+            Self::Inches => 25.4,
+            Self::Meters => 1000.,
+        }
+    }
+
+    /// The factor that converts lengths in these units into `target`
+    pub fn factor(self, target: Units) -> f64 {
+        self.millimeters() / target.millimeters()
+    }
+}
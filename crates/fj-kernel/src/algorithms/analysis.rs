@@ -0,0 +1,713 @@
+//! Analysis of triangulated shapes
+//!
+//! Most functions in this module compute one value per triangle of a mesh. The
+//! values are returned in the same order as [`Mesh::triangles`] returns the
+//! triangles.
+
+use std::collections::{BTreeMap, BTreeSet};
+
+use fj_interop::{analysis::Interference, debug::DebugInfo, mesh::Mesh};
+use fj_math::{Aabb, Point, Scalar, Triangle, Vector};
+
+use crate::{
+    geometry::surface::Curvature,
+    objects::{Face, FaceSet},
+};
+
+use super::{
+    approx::{Approx, Tolerance},
+    triangulate::triangulate_face,
+};
+
+/// Compute the draft angle of each triangle, relative to a pull direction
+///
+/// The draft angle is the angle between a triangle and the pull direction, in
+/// radians. It is positive for triangles that face in the pull direction, zero
+/// for triangles that are parallel to it, and negative for triangles that face
+/// against it. See [`DraftClass`], for how to interpret those angles.
+///
+/// Expects the triangles of the mesh to face outward.
+pub fn draft_angles(
+    mesh: &Mesh<Point<3>>,
+    pull_direction: impl Into<Vector<3>>,
+) -> Vec<Scalar> {
+    let pull_direction = pull_direction.into().normalize();
+
+    mesh.triangles()
+        .map(|triangle| {
+            let normal = triangle.inner.normal();
+            let sin = normal.dot(&pull_direction).into_f64().clamp(-1., 1.);
+            Scalar::from(sin.asin())
+        })
+        .collect()
+}
+
+/// The classification of a triangle by its draft angle
+///
+/// See [`draft_angles`].
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash, Ord, PartialOrd)]
+pub enum DraftClass {
+    /// The triangle has at least the required draft
+    Drafted,
+
+    /// The triangle has some draft, but not enough, or none at all
+    Insufficient,
+
+    /// The triangle faces against the pull direction
+    ///
+    /// A shape with such triangles can't be pulled out of a mold in that
+    /// direction, unless those triangles are located on the other half of the
+    /// mold.
+    Undercut,
+}
+
+impl DraftClass {
+    /// Classify a draft angle, given the minimum draft angle that is required
+    ///
+    /// Angles are in radians.
+    pub fn from_angle(
+        angle: impl Into<Scalar>,
+        min_draft: impl Into<Scalar>,
+    ) -> Self {
+        let angle = angle.into();
+
+        if angle >= min_draft.into() {
+            Self::Drafted
+        } else if angle >= Scalar::ZERO {
+            Self::Insufficient
+        } else {
+            Self::Undercut
+        }
+    }
+}
+
+/// Estimate the wall thickness at each triangle
+///
+/// A ray is cast from the center of each triangle into the shape, against the
+/// direction of the triangle's normal. The distance to the next triangle it
+/// hits is the wall thickness at that triangle.
+///
+/// Returns `None` for triangles whose ray doesn't hit anything, which can only
+/// happen if the mesh is not closed.
+pub fn wall_thickness(mesh: &Mesh<Point<3>>) -> Vec<Option<Scalar>> {
+    let triangles = mesh
+        .triangles()
+        .map(|triangle| triangle.inner)
+        .collect::<Vec<_>>();
+
+    // The rays need to point into the shape. If the triangles of the mesh face
+    // inward, the enclosed volume comes out negative, and the rays need to
+    // point the other way.
+    let signed_volume = triangles
+        .iter()
+        .map(|triangle| {
+            let [a, b, c] = triangle.points().map(|point| point.coords);
+            a.dot(&b.cross(&c))
+        })
+        .fold(Scalar::ZERO, |sum, volume| sum + volume);
+    let inward = if signed_volume < Scalar::ZERO {
+        Scalar::ONE
+    } else {
+        -Scalar::ONE
+    };
+
+    triangles
+        .iter()
+        .enumerate()
+        .map(|(i, triangle)| {
+            let [a, b, c] = triangle.points();
+            let origin = a + ((b - a) + (c - a)) / Scalar::from(3.);
+            let direction = triangle.normal() * inward;
+
+            triangles
+                .iter()
+                .enumerate()
+                .filter(|&(j, _)| j != i)
+                .filter_map(|(_, other)| {
+                    other.cast_local_ray(origin, direction, f64::MAX, false)
+                })
+                // Neighboring triangles in the same plane can be hit right at
+                // the origin of the ray. Those don't count.
+                .filter(|&distance| distance > Scalar::from(f64::EPSILON))
+                .min()
+        })
+        .collect()
+}
+
+/// Check each triangle for problems with printing it
+///
+/// Triangles that face down at an angle that is steeper than `max_overhang`,
+/// in radians relative to a vertical wall, need support material. Triangles
+/// at the bottom of the mesh, that rest on the build plate, don't count. Where
+/// the wall thickness (see [`wall_thickness`]) is below `min_thickness`, the
+/// printer might not be able to print the wall at all.
+///
+/// Expects the triangles of the mesh to face outward.
+pub fn printability(
+    mesh: &Mesh<Point<3>>,
+    build_direction: impl Into<Vector<3>>,
+    max_overhang: impl Into<Scalar>,
+    min_thickness: impl Into<Scalar>,
+) -> Vec<Printability> {
+    let build_direction = build_direction.into().normalize();
+    let max_overhang = max_overhang.into();
+    let min_thickness = min_thickness.into();
+
+    let heights = mesh
+        .vertices()
+        .map(|point| point.coords.dot(&build_direction))
+        .collect::<Vec<_>>();
+    let bottom = heights.iter().copied().min();
+    let epsilon = heights
+        .iter()
+        .copied()
+        .max()
+        .zip(bottom)
+        .map_or(Scalar::ZERO, |(top, bottom)| (top - bottom) * 1e-9);
+
+    let rests_on_build_plate = |triangle: &Triangle<3>| {
+        bottom.is_some_and(|bottom| {
+            triangle.points().iter().all(|point| {
+                point.coords.dot(&build_direction) - bottom <= epsilon
+            })
+        })
+    };
+
+    let overhangs = draft_angles(mesh, build_direction)
+        .into_iter()
+        .zip(mesh.triangles())
+        .map(|(angle, triangle)| {
+            -angle > max_overhang && !rests_on_build_plate(&triangle.inner)
+        });
+
+    overhangs
+        .zip(wall_thickness(mesh))
+        .map(|(overhang, thickness)| Printability {
+            overhang,
+            too_thin: thickness.is_some_and(|t| t < min_thickness),
+        })
+        .collect()
+}
+
+/// The problems of a triangle with being printed
+///
+/// See [`printability`].
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, Hash)]
+pub struct Printability {
+    /// The triangle overhangs too much, to be printed without support
+    pub overhang: bool,
+
+    /// The wall at the triangle is too thin to be printed
+    pub too_thin: bool,
+}
+
+impl Printability {
+    /// Check whether the triangle can be printed without problems
+    pub fn is_printable(&self) -> bool {
+        !self.overhang && !self.too_thin
+    }
+}
+
+/// Compute the curvature at each triangle
+///
+/// Triangles that are part of the triangulation of one of the given faces,
+/// with the given tolerance, get the exact curvature of the face's surface at
+/// their center. All other triangles, like those of imported meshes or of the
+/// results of boolean operations, get an estimate that is computed from the
+/// mesh itself (see [`mesh_curvature`]).
+///
+/// Expects the triangles of the mesh to face outward. The mean curvature is
+/// positive, where the shape is convex.
+pub fn curvature<'r>(
+    mesh: &Mesh<Point<3>>,
+    faces: impl IntoIterator<Item = (&'r FaceSet, Tolerance)>,
+) -> Vec<Curvature> {
+    let mut exact = BTreeMap::new();
+    for (faces, tolerance) in faces {
+        for face in faces {
+            exact.extend(face_curvature(face, tolerance));
+        }
+    }
+
+    mesh.triangles()
+        .zip(mesh_curvature(mesh))
+        .map(|(triangle, estimate)| {
+            exact
+                .get(&triangle.inner.normalize())
+                .copied()
+                .unwrap_or(estimate)
+        })
+        .collect()
+}
+
+/// Estimate the curvature at each triangle from the mesh alone
+///
+/// The mean curvature is derived from the angles between adjacent triangles,
+/// the Gaussian curvature from the angles around each vertex. Both are
+/// concentrated on the edges and vertices of the mesh, so sharp edges of the
+/// shape show up as strongly curved, as do the triangles of a coarsely
+/// approximated curved surface.
+///
+/// Edges and vertices on the boundary of a mesh that is not closed don't
+/// contribute to the curvature. Expects the triangles of the mesh to face
+/// outward. The mean curvature is positive, where the shape is convex.
+pub fn mesh_curvature(mesh: &Mesh<Point<3>>) -> Vec<Curvature> {
+    let triangles = mesh
+        .triangles()
+        .map(|triangle| triangle.inner)
+        .collect::<Vec<_>>();
+    let areas = triangles
+        .iter()
+        .map(|triangle| {
+            let [a, b, c] = triangle.points();
+            (b - a).cross(&(c - a)).magnitude() / 2.
+        })
+        .collect::<Vec<_>>();
+
+    let mut edges = BTreeMap::<_, Vec<_>>::new();
+    for (i, triangle) in triangles.iter().enumerate() {
+        let [a, b, c] = triangle.points();
+        for [a, b] in [[a, b], [b, c], [c, a]] {
+            edges.entry([a.min(b), a.max(b)]).or_default().push(i);
+        }
+    }
+
+    // Each edge contributes its length, times the angle between the adjacent
+    // triangles, to the integral of the mean curvature. Half of that is split
+    // between those triangles.
+    let mut mean = vec![Scalar::ZERO; triangles.len()];
+    let mut boundary = BTreeSet::new();
+    for (&[a, b], adjacent) in &edges {
+        let &[i, j] = adjacent.as_slice() else {
+            boundary.insert(a);
+            boundary.insert(b);
+            continue;
+        };
+
+        let angle = dihedral_angle(&triangles[i], &triangles[j], [a, b]);
+        let contribution = (b - a).magnitude() * angle / 4.;
+        mean[i] += contribution;
+        mean[j] += contribution;
+    }
+
+    // The Gaussian curvature at a vertex is its angle defect, divided by its
+    // share of the area of the adjacent triangles.
+    let mut vertices = BTreeMap::<_, (Scalar, Scalar)>::new();
+    for (triangle, &area) in triangles.iter().zip(&areas) {
+        let [a, b, c] = triangle.points();
+        for [a, b, c] in [[a, b, c], [b, c, a], [c, a, b]] {
+            let [ab, ac] = [b - a, c - a];
+            let angle = ab.cross(&ac).magnitude().atan2(ab.dot(&ac));
+
+            let (angles, vertex_area) = vertices.entry(a).or_default();
+            *angles += angle;
+            *vertex_area += area / 3.;
+        }
+    }
+    let gaussian_at_vertex = |point: Point<3>| {
+        let (angles, area) = vertices[&point];
+        if boundary.contains(&point) || area == Scalar::ZERO {
+            return Scalar::ZERO;
+        }
+
+        (Scalar::TAU - angles) / area
+    };
+
+    triangles
+        .iter()
+        .zip(areas)
+        .zip(mean)
+        .map(|((triangle, area), mean)| {
+            let gaussian = triangle
+                .points()
+                .into_iter()
+                .map(gaussian_at_vertex)
+                .fold(Scalar::ZERO, |sum, gaussian| sum + gaussian)
+                / 3.;
+            let mean = if area == Scalar::ZERO {
+                Scalar::ZERO
+            } else {
+                mean / area
+            };
+
+            Curvature { gaussian, mean }
+        })
+        .collect()
+}
+
+/// Compute the exact curvature at the center of each triangle of a face
+///
+/// Returns the triangles in normalized form, so they can be looked up
+/// regardless of which point they start with.
+fn face_curvature(
+    face: &Face,
+    tolerance: Tolerance,
+) -> Vec<(Triangle<3>, Curvature)> {
+    let approx = face.approx(tolerance);
+    let surface = face.surface().geometry();
+
+    triangulate_face(&approx, &mut DebugInfo::new())
+        .into_iter()
+        .filter_map(|points| {
+            let triangle =
+                Triangle::from_points(points.map(|point| point.point_global))
+                    .ok()?;
+            let center =
+                points.iter().fold(Point::origin(), |center, point| {
+                    center + point.point_surface.coords / 3.
+                });
+
+            // The surface normal doesn't necessarily point outward.
+            let mut curvature = surface.curvature_at(center);
+            if surface.normal_at(center).dot(&triangle.normal()) < Scalar::ZERO
+            {
+                curvature.mean = -curvature.mean;
+            }
+
+            Some((triangle.normalize(), curvature))
+        })
+        .collect()
+}
+
+/// Compute the signed angle between two triangles that share an edge
+///
+/// The angle is positive, if the triangles form a convex edge.
+fn dihedral_angle(
+    a: &Triangle<3>,
+    b: &Triangle<3>,
+    edge: [Point<3>; 2],
+) -> Scalar {
+    let [normal_a, normal_b] = [a, b].map(Triangle::normal);
+    let angle = normal_a
+        .cross(&normal_b)
+        .magnitude()
+        .atan2(normal_a.dot(&normal_b));
+
+    let Some(&opposite) = b.points().iter().find(|point| !edge.contains(point))
+    else {
+        return Scalar::ZERO;
+    };
+
+    if normal_a.dot(&(opposite - edge[0])) > Scalar::ZERO {
+        -angle
+    } else {
+        angle
+    }
+}
+
+/// Determine whether two meshes interfere with each other
+///
+/// Only the triangles within the overlap of the meshes' bounding boxes are
+/// checked against each other, so this is cheap for meshes that are far apart.
+/// Checking whether one mesh encloses the other expects the meshes to be
+/// closed.
+pub fn interference(a: &Mesh<Point<3>>, b: &Mesh<Point<3>>) -> Interference {
+    let (Some(aabb_a), Some(aabb_b)) = (mesh_aabb(a), mesh_aabb(b)) else {
+        return Interference::Clear;
+    };
+    let Some(overlap) = intersection(&aabb_a, &aabb_b) else {
+        return Interference::Clear;
+    };
+
+    let triangles_in_overlap = |mesh: &Mesh<Point<3>>| {
+        mesh.triangles()
+            .map(|triangle| {
+                let aabb = Aabb::<3>::from_points(triangle.inner.points());
+                (triangle.inner, aabb)
+            })
+            .filter(|(_, aabb)| intersection(aabb, &overlap).is_some())
+            .collect::<Vec<_>>()
+    };
+    let triangles_a = triangles_in_overlap(a);
+    let triangles_b = triangles_in_overlap(b);
+
+    for (triangle_a, aabb_a) in &triangles_a {
+        for (triangle_b, aabb_b) in &triangles_b {
+            if intersection(aabb_a, aabb_b).is_some()
+                && triangles_intersect(triangle_a, triangle_b)
+            {
+                return Interference::Interfering;
+            }
+        }
+    }
+
+    // The surfaces of the meshes don't intersect, so if one point of a mesh is
+    // inside of the other, all of them are.
+    let encloses = |outer: &Mesh<Point<3>>, inner: &Mesh<Point<3>>| {
+        inner
+            .vertices()
+            .next()
+            .is_some_and(|point| contains(outer, point))
+    };
+    if encloses(a, b) || encloses(b, a) {
+        return Interference::Interfering;
+    }
+
+    Interference::Close
+}
+
+fn mesh_aabb(mesh: &Mesh<Point<3>>) -> Option<Aabb<3>> {
+    mesh.vertices().next()?;
+    Some(Aabb::<3>::from_points(mesh.vertices()))
+}
+
+/// Compute the intersection of two bounding boxes, if they overlap
+fn intersection(a: &Aabb<3>, b: &Aabb<3>) -> Option<Aabb<3>> {
+    let min =
+        Point::from([0, 1, 2].map(|i| {
+            a.min.coords.components[i].max(b.min.coords.components[i])
+        }));
+    let max =
+        Point::from([0, 1, 2].map(|i| {
+            a.max.coords.components[i].min(b.max.coords.components[i])
+        }));
+
+    if (0..3).any(|i| min.coords.components[i] > max.coords.components[i]) {
+        return None;
+    }
+
+    Some(Aabb { min, max })
+}
+
+/// Determine whether two triangles intersect or touch
+///
+/// Two triangles intersect, if an edge of either one passes through the other.
+fn triangles_intersect(a: &Triangle<3>, b: &Triangle<3>) -> bool {
+    let edge_passes_through = |edges: &Triangle<3>, other: &Triangle<3>| {
+        let points = edges.points();
+        (0..3).any(|i| {
+            let start = points[i];
+            let end = points[(i + 1) % 3];
+
+            other.cast_local_ray(start, end - start, 1., true).is_some()
+        })
+    };
+
+    edge_passes_through(a, b) || edge_passes_through(b, a)
+}
+
+/// Determine whether a closed mesh contains a point
+///
+/// A ray that is cast from a point inside of the mesh crosses its surface an
+/// odd number of times.
+fn contains(mesh: &Mesh<Point<3>>, point: Point<3>) -> bool {
+    // An arbitrary direction that is unlikely to graze any edges of a mesh
+    // that is aligned to the axes.
+    let direction = Vector::from([0.5773, 0.6107, 0.5418]);
+
+    let hits = mesh
+        .triangles()
+        .filter(|triangle| {
+            triangle
+                .inner
+                .cast_local_ray(point, direction, f64::MAX, false)
+                .is_some()
+        })
+        .count();
+
+    hits % 2 == 1
+}
+
+#[cfg(test)]
+mod tests {
+    use fj_interop::{analysis::Interference, mesh::Mesh};
+    use fj_math::{Point, Scalar};
+
+    use crate::{
+        algorithms::{
+            approx::Tolerance, sweep::Sweep, triangulate::Triangulate,
+        },
+        builder::FaceBuilder,
+        insert::Insert,
+        objects::{Face, Sketch},
+        partial::HasPartial,
+        services::Services,
+    };
+
+    use super::DraftClass;
+
+    #[test]
+    fn draft_and_thickness_of_cuboid() {
+        let mut services = Services::new();
+
+        let surface = services.objects.surfaces.xy_plane();
+        let cuboid = Sketch::builder()
+            .with_polygon_from_points(
+                surface,
+                [[-1., -2.], [1., -2.], [1., 2.], [-1., 2.]],
+                &mut services.objects,
+            )
+            .build(&mut services.objects)
+            .sweep([0., 0., 6.], &mut services.objects);
+
+        let tolerance = Tolerance::from_scalar(0.001).unwrap();
+        let mesh = (&*cuboid, tolerance).triangulate();
+
+        let draft_angles = super::draft_angles(&mesh, [0., 0., 1.]);
+        let thickness = super::wall_thickness(&mesh);
+
+        for ((triangle, angle), thickness) in
+            mesh.triangles().zip(draft_angles).zip(thickness)
+        {
+            let normal = triangle.inner.normal();
+            let min_draft = Scalar::from(1_f64.to_radians());
+
+            let (expected_class, expected_thickness) =
+                if normal.z.abs() == Scalar::ONE {
+                    let class = if normal.z > Scalar::ZERO {
+                        DraftClass::Drafted
+                    } else {
+                        DraftClass::Undercut
+                    };
+                    (class, 6.)
+                } else if normal.x.abs() == Scalar::ONE {
+                    (DraftClass::Insufficient, 2.)
+                } else {
+                    (DraftClass::Insufficient, 4.)
+                };
+
+            assert_eq!(
+                DraftClass::from_angle(angle, min_draft),
+                expected_class
+            );
+            assert_eq!(thickness, Some(Scalar::from(expected_thickness)));
+        }
+    }
+
+    #[test]
+    fn interference_of_cubes() {
+        let mut services = Services::new();
+
+        let mut cube = |min: f64, size: f64| {
+            let max = min + size;
+            let surface = services.objects.surfaces.xy_plane();
+            let cube = Sketch::builder()
+                .with_polygon_from_points(
+                    surface,
+                    [[min, min], [max, min], [max, max], [min, max]],
+                    &mut services.objects,
+                )
+                .build(&mut services.objects)
+                .sweep([0., 0., size], &mut services.objects);
+
+            let tolerance = Tolerance::from_scalar(0.001).unwrap();
+            (&*cube, tolerance).triangulate()
+        };
+
+        let reference = cube(0., 4.);
+
+        // Far away
+        assert_eq!(
+            super::interference(&reference, &cube(10., 1.)),
+            Interference::Clear
+        );
+        // Within the bounding box of two cubes, but between them
+        let mut pair = cube(0., 1.);
+        for triangle in raised(&cube(3., 1.), 3.).triangles() {
+            pair.push_triangle(triangle.inner, triangle.color);
+        }
+        assert_eq!(
+            super::interference(&pair, &cube(1.5, 1.)),
+            Interference::Close
+        );
+        // Intersecting
+        assert_eq!(
+            super::interference(&reference, &cube(3., 2.)),
+            Interference::Interfering
+        );
+        // Enclosed
+        assert_eq!(
+            super::interference(&reference, &raised(&cube(1., 1.), 1.)),
+            Interference::Interfering
+        );
+    }
+
+    #[test]
+    fn printability_of_cubes() {
+        let mut services = Services::new();
+
+        let mut cube = |size: f64| {
+            let surface = services.objects.surfaces.xy_plane();
+            let cube = Sketch::builder()
+                .with_polygon_from_points(
+                    surface,
+                    [[0., 0.], [size, 0.], [size, size], [0., size]],
+                    &mut services.objects,
+                )
+                .build(&mut services.objects)
+                .sweep([0., 0., size], &mut services.objects);
+
+            let tolerance = Tolerance::from_scalar(0.001).unwrap();
+            (&*cube, tolerance).triangulate()
+        };
+
+        // A large cube on the build plate, and a small one floating above it
+        let mut mesh = cube(4.);
+        for triangle in raised(&cube(1.), 6.).triangles() {
+            mesh.push_triangle(triangle.inner, triangle.color);
+        }
+
+        let printability =
+            super::printability(&mesh, [0., 0., 1.], 45_f64.to_radians(), 2.);
+
+        for (triangle, printability) in mesh.triangles().zip(printability) {
+            let [a, b, c] = triangle.inner.points();
+            let is_small = a.z >= Scalar::from(6.);
+            let is_bottom = [a, b, c].iter().all(|point| point.z == a.z)
+                && triangle.inner.normal().z < Scalar::ZERO;
+
+            assert_eq!(printability.overhang, is_small && is_bottom);
+            assert_eq!(printability.too_thin, is_small);
+        }
+    }
+
+    #[test]
+    fn curvature_of_cylinder() {
+        let mut services = Services::new();
+
+        let surface = services.objects.surfaces.xy_plane();
+        let disc = Face::partial()
+            .with_exterior_circle(surface, [0., 0.], 2., &mut services.objects)
+            .build(&mut services.objects)
+            .insert(&mut services.objects);
+        let cylinder = Sketch::builder()
+            .with_faces([disc])
+            .build(&mut services.objects)
+            .sweep([0., 0., 3.], &mut services.objects);
+        let shell = cylinder.shells().next().unwrap();
+
+        let tolerance = Tolerance::from_scalar(0.1).unwrap();
+        let mesh = (shell.faces(), tolerance).triangulate();
+
+        let exact = super::curvature(&mesh, [(shell.faces(), tolerance)]);
+        let estimate = super::mesh_curvature(&mesh);
+        assert_eq!(super::curvature(&mesh, []), estimate);
+
+        for ((triangle, exact), estimate) in
+            mesh.triangles().zip(exact).zip(estimate)
+        {
+            let is_side = triangle.inner.normal().z.abs() < Scalar::ONE;
+            let expected_mean = if is_side { 0.25 } else { 0. };
+
+            assert!(exact.gaussian.abs() < Scalar::from(1e-6));
+            assert!((exact.mean - expected_mean).abs() < Scalar::from(1e-6));
+
+            assert!(estimate.mean >= Scalar::ZERO);
+            if is_side {
+                assert!(estimate.mean > Scalar::ZERO);
+            }
+        }
+    }
+
+    fn raised(mesh: &Mesh<Point<3>>, z: f64) -> Mesh<Point<3>> {
+        let mut raised = Mesh::new();
+        for triangle in mesh.triangles() {
+            let points = triangle
+                .inner
+                .points()
+                .map(|point| point + fj_math::Vector::from([0., 0., z]));
+            raised.push_triangle(points, triangle.color);
+        }
+        raised
+    }
+}
@@ -11,13 +11,21 @@
 
 use std::collections::BTreeMap;
 
+use fj_math::Point;
+
 use crate::{
-    geometry::path::{GlobalPath, SurfacePath},
+    geometry::{
+        path::{GlobalPath, SurfacePath},
+        surface::SurfaceGeometry,
+    },
     objects::{Curve, GlobalCurve},
     storage::{Handle, ObjectId},
 };
 
-use super::{path::RangeOnPath, Approx, ApproxPoint, Tolerance};
+use super::{
+    path::{distance_to_chord, RangeOnPath},
+    Approx, ApproxPoint, Tolerance,
+};
 
 impl Approx for (&Handle<Curve>, RangeOnPath) {
     type Approximation = CurveApprox;
@@ -56,19 +64,49 @@ fn approx_global_curve(
     range: RangeOnPath,
     tolerance: impl Into<Tolerance>,
 ) -> GlobalCurveApprox {
-    // There are different cases of varying complexity. Circles are the hard
-    // part here, as they need to be approximated, while lines don't need to be.
+    // There are different cases of varying complexity. Circles, ellipses, and
+    // NURBS curves are the hard part here, as they need to be approximated,
+    // while lines don't need to be.
     //
     // This will probably all be unified eventually, as `SurfacePath` and
     // `GlobalPath` grow APIs that are better suited to implementing this code
     // in a more abstract way.
-    let points = match (curve.path(), curve.surface().geometry().u) {
-        (SurfacePath::Circle(_), GlobalPath::Circle(_)) => {
+    let points = match (curve.path(), curve.surface().geometry()) {
+        (
+            _,
+            surface @ (SurfaceGeometry::Nurbs(_)
+            | SurfaceGeometry::Ruled { .. }
+            | SurfaceGeometry::Cone(_)),
+        ) => {
+            approx_on_curved_surface(&curve.path(), &surface, range, tolerance)
+        }
+        (
+            SurfacePath::Circle(_)
+            | SurfacePath::Ellipse(_)
+            | SurfacePath::Nurbs(_),
+            SurfaceGeometry::Swept {
+                u:
+                    GlobalPath::Circle(_)
+                    | GlobalPath::Ellipse(_)
+                    | GlobalPath::Helix(_)
+                    | GlobalPath::Nurbs(_),
+                ..
+            },
+        ) => {
             todo!(
-                "Approximating a circle on a curved surface not supported yet."
+                "Approximating a curved path on a curved surface not supported \
+                yet."
             )
         }
-        (SurfacePath::Circle(_), GlobalPath::Line(_)) => {
+        (
+            SurfacePath::Circle(_)
+            | SurfacePath::Ellipse(_)
+            | SurfacePath::Nurbs(_),
+            SurfaceGeometry::Swept {
+                u: GlobalPath::Line(_),
+                ..
+            },
+        ) => {
             (curve.path(), range)
                 .approx_with_cache(tolerance, &mut ())
                 .into_iter()
@@ -96,14 +134,13 @@ fn approx_global_curve(
                 })
                 .collect()
         }
-        (SurfacePath::Line(line), _) => {
+        (SurfacePath::Line(line), SurfaceGeometry::Swept { u, .. }) => {
             let range_u =
                 RangeOnPath::from(range.boundary.map(|point_curve| {
                     [curve.path().point_from_path_coords(point_curve).u]
                 }));
 
-            let approx_u = (curve.surface().geometry().u, range_u)
-                .approx_with_cache(tolerance, &mut ());
+            let approx_u = (u, range_u).approx_with_cache(tolerance, &mut ());
 
             let mut points = Vec::new();
             for (u, _) in approx_u {
@@ -129,6 +166,65 @@ fn approx_global_curve(
     GlobalCurveApprox { points }
 }
 
+/// Approximate a path on a NURBS surface, ruled surface, or cone
+///
+/// The approximation of the path in surface coordinates is not good enough
+/// here, as the surface might distort it. Segments of that approximation are
+/// subdivided, until they're within the tolerance in global coordinates.
+fn approx_on_curved_surface(
+    path: &SurfacePath,
+    surface: &SurfaceGeometry,
+    range: RangeOnPath,
+    tolerance: impl Into<Tolerance>,
+) -> Vec<(Point<1>, Point<3>)> {
+    const MAX_DEPTH: usize = 16;
+
+    let tolerance = tolerance.into();
+    let [start, end] = range.boundary;
+
+    let point_global = |point_curve: Point<1>| {
+        surface
+            .point_from_surface_coords(path.point_from_path_coords(point_curve))
+    };
+
+    let mut params = vec![start];
+    params.extend(
+        (path.clone(), range)
+            .approx_with_cache(tolerance, &mut ())
+            .into_iter()
+            .map(|(point_curve, _)| point_curve),
+    );
+    params.push(end);
+
+    let mut points = Vec::new();
+    for segment in params.windows(2) {
+        let mut stack = vec![(segment[1], 0)];
+        let mut a = segment[0];
+
+        while let Some((b, depth)) = stack.pop() {
+            let middle = Point::from([(a.t + b.t) / 2.]);
+            let distance = distance_to_chord(
+                point_global(middle),
+                point_global(a),
+                point_global(b),
+            );
+
+            if depth < MAX_DEPTH && distance > tolerance.inner() {
+                stack.push((b, depth + 1));
+                stack.push((middle, depth + 1));
+                continue;
+            }
+
+            points.push((b, point_global(b)));
+            a = b;
+        }
+    }
+
+    // The boundaries of the range are not part of the approximation.
+    points.pop();
+    points
+}
+
 /// An approximation of a [`Curve`]
 #[derive(Debug, Eq, PartialEq, Hash, Ord, PartialOrd)]
 pub struct CurveApprox {
@@ -181,7 +277,17 @@ impl CurveCache {
         handle: Handle<GlobalCurve>,
         range: RangeOnPath,
     ) -> Option<GlobalCurveApprox> {
-        self.inner.get(&(handle.id(), range)).cloned()
+        if let Some(approx) = self.inner.get(&(handle.id(), range)) {
+            return Some(approx.clone());
+        }
+
+        // The half-edges that share a global curve approximate it in opposite
+        // directions. Reusing the approximation of the reverse range makes sure
+        // they end up with identical points.
+        self.inner
+            .get(&(handle.id(), range.reverse()))
+            .cloned()
+            .map(GlobalCurveApprox::reverse)
     }
 }
 
@@ -192,6 +298,14 @@ pub struct GlobalCurveApprox {
     pub points: Vec<ApproxPoint<1>>,
 }
 
+impl GlobalCurveApprox {
+    /// Reverse the order of the approximation
+    pub fn reverse(mut self) -> Self {
+        self.points.reverse();
+        self
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use std::f64::consts::TAU;
@@ -259,7 +373,7 @@ mod tests {
         let mut services = Services::new();
 
         let path = GlobalPath::circle_from_radius(1.);
-        let surface = PartialSurface::from_axes(path, [0., 0., 1.])
+        let surface = PartialSurface::from_axes(path.clone(), [0., 0., 1.])
             .build(&services.objects)
             .insert(&mut services.objects);
         let mut curve = PartialCurve {
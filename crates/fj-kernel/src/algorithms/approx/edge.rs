@@ -5,7 +5,12 @@
 //! approximations are usually used to build cycle approximations, and this way,
 //! the caller doesn't have to call with duplicate vertices.
 
-use crate::objects::HalfEdge;
+use std::{
+    cmp::Ordering,
+    hash::{Hash, Hasher},
+};
+
+use crate::{objects::HalfEdge, storage::ObjectId};
 
 use super::{
     curve::{CurveApprox, CurveCache},
@@ -36,21 +41,39 @@ impl Approx for &HalfEdge {
         HalfEdgeApprox {
             first,
             curve_approx,
+            edge: self.global_form().id(),
+            vertex: a.global_form().id(),
         }
     }
 }
 
 /// An approximation of an [`HalfEdge`]
-#[derive(Debug, Eq, PartialEq, Hash, Ord, PartialOrd)]
+///
+/// Approximations are compared by their geometry only. The objects they refer
+/// to are left out, as their IDs depend on where the objects are stored, and
+/// would make the order of approximations unpredictable.
+#[derive(Debug)]
 pub struct HalfEdgeApprox {
     /// The point that approximates the first vertex of the curve
     pub first: ApproxPoint<2>,
 
     /// The approximation of the edge's curve
     pub curve_approx: CurveApprox,
+
+    /// The global form of the approximated edge
+    ///
+    /// Both half-edges of an edge share it.
+    pub edge: ObjectId,
+
+    /// The global form of the first vertex of the edge
+    pub vertex: ObjectId,
 }
 
 impl HalfEdgeApprox {
+    fn geometry(&self) -> (&ApproxPoint<2>, &CurveApprox) {
+        (&self.first, &self.curve_approx)
+    }
+
     /// Compute the points that approximate the edge
     pub fn points(&self) -> Vec<ApproxPoint<2>> {
         let mut points = Vec::new();
@@ -61,3 +84,29 @@ impl HalfEdgeApprox {
         points
     }
 }
+
+impl Eq for HalfEdgeApprox {}
+
+impl PartialEq for HalfEdgeApprox {
+    fn eq(&self, other: &Self) -> bool {
+        self.geometry() == other.geometry()
+    }
+}
+
+impl Hash for HalfEdgeApprox {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.geometry().hash(state);
+    }
+}
+
+impl Ord for HalfEdgeApprox {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.geometry().cmp(&other.geometry())
+    }
+}
+
+impl PartialOrd for HalfEdgeApprox {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
@@ -4,15 +4,21 @@
 
 use std::collections::BTreeSet;
 
-use fj_interop::mesh::Color;
+use fj_interop::mesh::{Attributes, Color};
+use fj_math::{Point, Scalar, Vector};
 
 use crate::{
+    algorithms::point_in_face::{LocatePoint, PointLocation},
+    geometry::surface::SurfaceGeometry,
     objects::{Face, FaceSet, Handedness},
+    storage::{Handle, ObjectId},
+    tolerance::ModelTolerance,
     validate::ValidationConfig,
 };
 
 use super::{
-    curve::CurveCache, cycle::CycleApprox, Approx, ApproxPoint, Tolerance,
+    curve::CurveCache, cycle::CycleApprox, path::angle_between, Approx,
+    ApproxPoint, Tolerance,
 };
 
 impl Approx for &FaceSet {
@@ -31,7 +37,8 @@ impl Approx for &FaceSet {
             .map(|face| face.approx_with_cache(tolerance, cache))
             .collect();
 
-        let min_distance = ValidationConfig::default().distinct_min_distance;
+        let min_distance =
+            ValidationConfig::default().distinct_min_distance.inner();
         let mut all_points: BTreeSet<ApproxPoint<2>> = BTreeSet::new();
 
         // Run some validation code on the approximation.
@@ -79,18 +86,12 @@ impl Approx for &Face {
     ) -> Self::Approximation {
         let tolerance = tolerance.into();
 
-        // Curved faces whose curvature is not fully defined by their edges
-        // are not supported yet. For that reason, we can fully ignore `face`'s
-        // `surface` field and just pass the edges to `Self::for_edges`.
+        // The curvature of swept surfaces, like the cylinder, is fully defined
+        // by the edges that border them. The edge approximations are
+        // sufficient to triangulate those surfaces.
         //
-        // An example of a curved face that is supported, is the cylinder. Its
-        // curvature is fully defined be the edges (circles) that border it. The
-        // circle approximations are sufficient to triangulate the surface.
-        //
-        // An example of a curved face that is currently not supported, and thus
-        // doesn't need to be handled here, is a sphere. A spherical face would
-        // would need to provide its own approximation, as the edges that bound
-        // it have nothing to do with its curvature.
+        // That is not the case for NURBS surfaces. Those need additional points
+        // from within the face, to approximate their curvature.
 
         let exterior = self.exterior().approx_with_cache(tolerance, cache);
 
@@ -100,15 +101,159 @@ impl Approx for &Face {
             interiors.insert(cycle);
         }
 
-        FaceApprox {
+        let mut approx = FaceApprox {
             exterior,
             interiors,
+            surface_points: BTreeSet::new(),
+            surface: self.surface().geometry(),
             color: self.color(),
+            attributes: self.attributes().clone(),
             coord_handedness: self.coord_handedness(),
+            face: None,
+        };
+
+        let surface = self.surface().geometry();
+        if let SurfaceGeometry::Nurbs(_)
+        | SurfaceGeometry::Ruled { .. }
+        | SurfaceGeometry::Cone(_) = surface
+        {
+            approx.surface_points =
+                approx_surface(&surface, &approx, tolerance);
         }
+
+        approx
     }
 }
 
+impl Approx for &Handle<Face> {
+    type Approximation = FaceApprox;
+    type Cache = CurveCache;
+
+    fn approx_with_cache(
+        self,
+        tolerance: impl Into<Tolerance>,
+        cache: &mut Self::Cache,
+    ) -> Self::Approximation {
+        let face: &Face = self;
+
+        FaceApprox {
+            face: Some(self.id()),
+            ..face.approx_with_cache(tolerance, cache)
+        }
+    }
+}
+
+/// Approximate a NURBS surface, ruled surface, or cone within a face
+///
+/// The surface is sampled on a uniform grid, which is refined until the
+/// surface is flat enough within each cell. The grid points that are well
+/// within the face become part of the approximation.
+fn approx_surface(
+    surface: &SurfaceGeometry,
+    face: &FaceApprox,
+    tolerance: Tolerance,
+) -> BTreeSet<ApproxPoint<2>> {
+    const MAX_CELLS: usize = 64;
+
+    let points = face.exterior.points();
+    let bounds = match points.first() {
+        Some(first) => points.iter().fold(
+            [first.local_form, first.local_form],
+            |[min, max], point| {
+                let point = point.local_form;
+                [
+                    Point::from([
+                        Ord::min(min.u, point.u),
+                        Ord::min(min.v, point.v),
+                    ]),
+                    Point::from([
+                        Ord::max(max.u, point.u),
+                        Ord::max(max.v, point.v),
+                    ]),
+                ]
+            },
+        ),
+        None => return BTreeSet::new(),
+    };
+    let [min, max] = bounds;
+
+    let grid_point = |cells: usize, i: usize, j: usize| {
+        let [i, j, cells] = [i, j, cells].map(|n| Scalar::from(n as f64));
+        Point::from([
+            min.u + (max.u - min.u) * i / cells,
+            min.v + (max.v - min.v) * j / cells,
+        ])
+    };
+
+    // Each cell is split into two triangles by the triangulation. The surface
+    // is considered flat enough within a cell, if its center is within the
+    // tolerance of the center of the cell's corners.
+    let is_flat_enough = |cells: usize| {
+        (0..cells).all(|i| {
+            (0..cells).all(|j| {
+                let corners = [[i, j], [i + 1, j], [i, j + 1], [i + 1, j + 1]]
+                    .map(|[i, j]| {
+                        surface
+                            .point_from_surface_coords(grid_point(cells, i, j))
+                    });
+                let center_of_corners = corners
+                    .iter()
+                    .fold(Vector::from([0., 0., 0.]), |sum, point| {
+                        sum + point.coords
+                    })
+                    / 4.;
+
+                let center_coords = grid_point(cells * 2, i * 2 + 1, j * 2 + 1);
+                let center = surface.point_from_surface_coords(center_coords);
+
+                let deviates = (center.coords - center_of_corners).magnitude()
+                    > tolerance.inner();
+                let turns = tolerance.max_angle().is_some_and(|max_angle| {
+                    let normal = surface.normal_at(center_coords);
+                    [[i, j], [i + 1, j], [i, j + 1], [i + 1, j + 1]]
+                        .into_iter()
+                        .any(|[i, j]| {
+                            let corner = grid_point(cells, i, j);
+                            angle_between(normal, surface.normal_at(corner))
+                                > max_angle
+                        })
+                });
+
+                !deviates && !turns
+            })
+        })
+    };
+
+    let mut cells = 1;
+    while cells < MAX_CELLS && !is_flat_enough(cells) {
+        cells *= 2;
+    }
+
+    // Points that are too close to the boundary would result in sliver
+    // triangles.
+    let cell_size = Ord::min(max.u - min.u, max.v - min.v) / cells as f64;
+    let margin = match ModelTolerance::from_scalar(cell_size / 4.) {
+        Ok(margin) => margin,
+        Err(_) => return BTreeSet::new(),
+    };
+
+    let mut surface_points = BTreeSet::new();
+    for i in 1..cells {
+        for j in 1..cells {
+            let point = grid_point(cells, i, j);
+
+            if face.locate_point(point, margin) == PointLocation::Inside {
+                surface_points.insert(ApproxPoint::new(
+                    point,
+                    surface.point_from_surface_coords(point),
+                ));
+            }
+        }
+    }
+
+    surface_points
+}
+
 /// An approximation of a [`Face`]
 #[derive(Debug, Eq, PartialEq, Ord, PartialOrd)]
 pub struct FaceApprox {
@@ -118,11 +263,26 @@ pub struct FaceApprox {
     /// Approximations of the interior cycles
     pub interiors: BTreeSet<CycleApprox>,
 
+    /// Points within the face, that approximate the curvature of its surface
+    ///
+    /// This is empty for faces on surfaces whose curvature is fully defined by
+    /// the face's edges.
+    pub surface_points: BTreeSet<ApproxPoint<2>>,
+
+    /// The geometry of the surface that the face is defined on
+    pub surface: SurfaceGeometry,
+
     /// The color of the approximated face
     pub color: Color,
 
+    /// The attributes of the approximated face
+    pub attributes: Attributes,
+
     /// The handedness of the approximated face's front-side coordinate system
     pub coord_handedness: Handedness,
+
+    /// The approximated face, if it was approximated through its handle
+    pub face: Option<ObjectId>,
 }
 
 impl FaceApprox {
@@ -136,6 +296,8 @@ impl FaceApprox {
             points.extend(cycle_approx.points());
         }
 
+        points.extend(self.surface_points.iter().cloned());
+
         points
     }
 }
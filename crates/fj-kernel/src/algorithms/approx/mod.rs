@@ -15,7 +15,7 @@ use std::{
     cmp::Ordering,
     fmt::Debug,
     hash::{Hash, Hasher},
-    rc::Rc,
+    sync::Arc,
 };
 
 use fj_math::Point;
@@ -62,7 +62,7 @@ pub struct ApproxPoint<const D: usize> {
     pub global_form: Point<3>,
 
     /// The optional source of the point
-    pub source: Option<Rc<dyn Source>>,
+    pub source: Option<Arc<dyn Source>>,
 }
 
 impl<const D: usize> ApproxPoint<D> {
@@ -78,7 +78,7 @@ impl<const D: usize> ApproxPoint<D> {
     /// Attach a source to the point
     pub fn with_source(self, source: impl Source) -> Self {
         Self {
-            source: Some(Rc::new(source)),
+            source: Some(Arc::new(source)),
             ..self
         }
     }
@@ -117,6 +117,6 @@ impl<const D: usize> PartialOrd for ApproxPoint<D> {
 }
 
 /// The source of an [`ApproxPoint`]
-pub trait Source: Any + Debug {}
+pub trait Source: Any + Debug + Send + Sync {}
 
 impl Source for (Handle<Curve>, Point<1>) {}
@@ -30,7 +30,7 @@
 
 use std::iter;
 
-use fj_math::{Circle, Point, Scalar, Sign};
+use fj_math::{Circle, Ellipse, Helix, Nurbs, Point, Scalar, Sign, Vector};
 
 use crate::geometry::path::{GlobalPath, SurfacePath};
 
@@ -51,7 +51,13 @@ impl Approx for (SurfacePath, RangeOnPath) {
             SurfacePath::Circle(circle) => {
                 approx_circle(&circle, range, tolerance.into())
             }
+            SurfacePath::Ellipse(ellipse) => {
+                approx_ellipse(&ellipse, range, tolerance.into())
+            }
             SurfacePath::Line(_) => vec![],
+            SurfacePath::Nurbs(nurbs) => {
+                approx_nurbs(&nurbs, range, tolerance.into())
+            }
         }
     }
 }
@@ -71,7 +77,16 @@ impl Approx for (GlobalPath, RangeOnPath) {
             GlobalPath::Circle(circle) => {
                 approx_circle(&circle, range, tolerance.into())
             }
+            GlobalPath::Ellipse(ellipse) => {
+                approx_ellipse(&ellipse, range, tolerance.into())
+            }
+            GlobalPath::Helix(helix) => {
+                approx_helix(&helix, range, tolerance.into())
+            }
             GlobalPath::Line(_) => vec![],
+            GlobalPath::Nurbs(nurbs) => {
+                approx_nurbs(&nurbs, range, tolerance.into())
+            }
         }
     }
 }
@@ -83,6 +98,15 @@ pub struct RangeOnPath {
     pub boundary: [Point<1>; 2],
 }
 
+impl RangeOnPath {
+    /// Reverse the direction of the range
+    pub fn reverse(self) -> Self {
+        let [a, b] = self.boundary;
+
+        Self { boundary: [b, a] }
+    }
+}
+
 impl<T> From<[T; 2]> for RangeOnPath
 where
     T: Into<Point<1>>,
@@ -115,6 +139,175 @@ fn approx_circle<const D: usize>(
     points
 }
 
+/// Approximate an ellipse
+///
+/// `tolerance` specifies how much the approximation is allowed to deviate
+/// from the ellipse.
+fn approx_ellipse<const D: usize>(
+    ellipse: &Ellipse<D>,
+    range: impl Into<RangeOnPath>,
+    tolerance: Tolerance,
+) -> Vec<(Point<1>, Point<D>)> {
+    let range = range.into();
+
+    let params = PathApproxParams::for_ellipse(ellipse, tolerance);
+    let mut points = Vec::new();
+
+    for point_curve in params.points(range) {
+        let point_global = ellipse.point_from_ellipse_coords(point_curve);
+        points.push((point_curve, point_global));
+    }
+
+    points
+}
+
+/// Approximate a helix
+///
+/// `tolerance` specifies how much the approximation is allowed to deviate
+/// from the helix.
+fn approx_helix(
+    helix: &Helix,
+    range: impl Into<RangeOnPath>,
+    tolerance: Tolerance,
+) -> Vec<(Point<1>, Point<3>)> {
+    let range = range.into();
+
+    let params = PathApproxParams::for_helix(helix, tolerance);
+    let mut points = Vec::new();
+
+    for point_curve in params.points(range) {
+        let point_global = helix.point_from_helix_coords(point_curve);
+        points.push((point_curve, point_global));
+    }
+
+    points
+}
+
+/// Approximate a NURBS curve
+///
+/// `tolerance` specifies how much the approximation is allowed to deviate
+/// from the curve.
+///
+/// The curve is subdivided adaptively. To keep the result deterministic, the
+/// subdivision always covers the full range of the curve, and only the points
+/// within `range` are returned.
+fn approx_nurbs<const D: usize>(
+    nurbs: &Nurbs<D>,
+    range: impl Into<RangeOnPath>,
+    tolerance: Tolerance,
+) -> Vec<(Point<1>, Point<D>)> {
+    let range = range.into();
+
+    let [a, b] = range.boundary.map(|point| point.t);
+    let [min, max] = if a < b { [a, b] } else { [b, a] };
+
+    let mut points = nurbs_params(nurbs, tolerance)
+        .into_iter()
+        .filter(|&t| t > min && t < max)
+        .map(|t| (Point::from([t]), nurbs.point_from_nurbs_coords([t])))
+        .collect::<Vec<_>>();
+
+    if a > b {
+        points.reverse();
+    }
+
+    points
+}
+
+/// Compute the parameters at which a NURBS curve is approximated
+///
+/// Returns the parameters in ascending order, including both ends of the range
+/// of the curve.
+fn nurbs_params<const D: usize>(
+    nurbs: &Nurbs<D>,
+    tolerance: Tolerance,
+) -> Vec<Scalar> {
+    // Each subdivision halves the length of a span. This limit keeps
+    // degenerate curves from exhausting our resources.
+    const MAX_DEPTH: usize = 20;
+
+    fn subdivide<const D: usize>(
+        nurbs: &Nurbs<D>,
+        [a, b]: [Scalar; 2],
+        tolerance: Tolerance,
+        depth: usize,
+        params: &mut Vec<Scalar>,
+    ) {
+        if depth >= MAX_DEPTH {
+            return;
+        }
+
+        let start = nurbs.point_from_nurbs_coords([a]);
+        let end = nurbs.point_from_nurbs_coords([b]);
+
+        // Sampling a few points per span is not a guarantee that the span
+        // doesn't deviate anywhere, but since each span is a single rational
+        // polynomial of low degree, it's good enough in practice.
+        let deviates = [0.25, 0.5, 0.75].into_iter().any(|f| {
+            let point = nurbs.point_from_nurbs_coords([a + (b - a) * f]);
+            distance_to_chord(point, start, end) > tolerance.inner()
+        });
+        let turns = tolerance.max_angle().is_some_and(|max_angle| {
+            let [start, end] = [a, b].map(|t| nurbs.derivative_at([t]));
+            angle_between(start, end) > max_angle
+        });
+        if !deviates && !turns {
+            return;
+        }
+
+        let middle = (a + b) / 2.;
+        subdivide(nurbs, [a, middle], tolerance, depth + 1, params);
+        params.push(middle);
+        subdivide(nurbs, [middle, b], tolerance, depth + 1, params);
+    }
+
+    let spans = nurbs.spans();
+
+    let mut params = vec![spans[0]];
+    for span in spans.windows(2) {
+        subdivide(nurbs, [span[0], span[1]], tolerance, 0, &mut params);
+        params.push(span[1]);
+    }
+
+    params
+}
+
+/// Compute the angle between two vectors, in radians
+///
+/// Returns zero, if either vector has zero length.
+pub(super) fn angle_between<const D: usize>(
+    a: Vector<D>,
+    b: Vector<D>,
+) -> Scalar {
+    let magnitudes = a.magnitude() * b.magnitude();
+    if magnitudes == Scalar::ZERO {
+        return Scalar::ZERO;
+    }
+
+    let cos = (a.dot(&b) / magnitudes).into_f64().clamp(-1., 1.);
+    Scalar::from(cos.acos())
+}
+
+pub(super) fn distance_to_chord<const D: usize>(
+    point: Point<D>,
+    start: Point<D>,
+    end: Point<D>,
+) -> Scalar {
+    let chord = end - start;
+    let length_squared = chord.dot(&chord);
+
+    let t = if length_squared == Scalar::ZERO {
+        Scalar::ZERO
+    } else {
+        Ord::min(
+            ((point - start).dot(&chord) / length_squared).max(Scalar::ZERO),
+            Scalar::ONE,
+        )
+    };
+
+    point.distance_to(&(start + chord * t))
+}
+
 struct PathApproxParams {
     increment: Scalar,
 }
@@ -124,14 +317,37 @@ impl PathApproxParams {
         circle: &Circle<D>,
         tolerance: impl Into<Tolerance>,
     ) -> Self {
-        let radius = circle.a().magnitude();
+        Self::for_radius(circle.a().magnitude(), tolerance)
+    }
 
-        let num_vertices_to_approx_full_circle = Scalar::max(
-            Scalar::PI
-                / (Scalar::ONE - (tolerance.into().inner() / radius)).acos(),
-            3.,
-        )
-        .ceil();
+    pub fn for_ellipse<const D: usize>(
+        ellipse: &Ellipse<D>,
+        tolerance: impl Into<Tolerance>,
+    ) -> Self {
+        // The ellipse deviates less from a chord than a circle with its major
+        // radius would, for the same increment.
+        Self::for_radius(ellipse.major_radius(), tolerance)
+    }
+
+    pub fn for_helix(helix: &Helix, tolerance: impl Into<Tolerance>) -> Self {
+        // The helix advances along its axis linearly, so a chord deviates from
+        // it just as much as it would from a circle with the same radius.
+        Self::for_radius(helix.radius(), tolerance)
+    }
+
+    fn for_radius(radius: Scalar, tolerance: impl Into<Tolerance>) -> Self {
+        let tolerance = tolerance.into();
+
+        let num_vertices_for_distance =
+            Scalar::PI / (Scalar::ONE - (tolerance.inner() / radius)).acos();
+        let num_vertices_for_angle = tolerance
+            .max_angle()
+            .map_or(Scalar::ZERO, |max_angle| Scalar::TAU / max_angle);
+
+        let num_vertices_to_approx_full_circle = num_vertices_for_distance
+            .max(num_vertices_for_angle)
+            .max(Scalar::from(3.))
+            .ceil();
 
         let increment = Scalar::TAU / num_vertices_to_approx_full_circle;
 
@@ -154,8 +370,13 @@ impl PathApproxParams {
 
         // We can't generate a point exactly at the boundaries of the range as
         // part of the approximation. Make sure we stay inside the range.
-        let min = min.floor() + 1.;
-        let max = max.ceil() - 1.;
+        //
+        // A boundary that should be on one of the points might end up just
+        // beyond it, due to floating-point inaccuracy. That point would then
+        // be almost identical to the boundary, so keep some distance.
+        let margin = Scalar::from(1e-9);
+        let min = (min + margin).floor() + 1.;
+        let max = (max - margin).ceil() - 1.;
 
         let [start, end] = match direction {
             Sign::Negative => [max, min],
@@ -185,11 +406,13 @@ impl PathApproxParams {
 mod tests {
     use std::f64::consts::TAU;
 
-    use fj_math::{Circle, Point, Scalar};
+    use fj_math::{Circle, Nurbs, Point, Scalar};
 
     use crate::algorithms::approx::{path::RangeOnPath, Tolerance};
 
-    use super::PathApproxParams;
+    use super::{
+        angle_between, approx_nurbs, distance_to_chord, PathApproxParams,
+    };
 
     #[test]
     fn increment_for_circle() {
@@ -210,6 +433,25 @@ mod tests {
         }
     }
 
+    #[test]
+    fn increment_for_circle_with_max_angle() -> anyhow::Result<()> {
+        let circle = Circle::from_center_and_radius([0., 0.], 1.);
+
+        // The angle requires more vertices than the distance.
+        let tolerance =
+            Tolerance::from_scalar(0.5)?.with_max_angle(TAU / 8.)?;
+        let params = PathApproxParams::for_circle(&circle, tolerance);
+        assert_eq!(params.increment(), Scalar::TAU / 8.);
+
+        // The distance requires more vertices than the angle.
+        let tolerance =
+            Tolerance::from_scalar(0.01)?.with_max_angle(TAU / 8.)?;
+        let params = PathApproxParams::for_circle(&circle, tolerance);
+        assert_eq!(params.increment(), Scalar::TAU / 23.);
+
+        Ok(())
+    }
+
     #[test]
     fn points_for_circle() {
         // At the chosen values for radius and tolerance (see below), the
@@ -258,4 +500,50 @@ mod tests {
             assert_eq!(points, expected_points);
         }
     }
+
+    #[test]
+    fn points_for_nurbs() -> anyhow::Result<()> {
+        let nurbs = Nurbs::from_control_points(
+            3,
+            [[0., 0.], [1., 2.], [2., -2.], [3., 1.], [4., 0.]],
+        );
+        let tolerance = Tolerance::from_scalar(0.01)?;
+
+        let points = approx_nurbs(&nurbs, [[0.], [1.]], tolerance);
+
+        // The chord between each pair of neighboring points must not deviate
+        // from the curve by more than the tolerance.
+        for window in points.windows(2) {
+            let [(a_curve, a), (b_curve, b)] = [window[0], window[1]];
+            let middle =
+                nurbs.point_from_nurbs_coords([(a_curve.t + b_curve.t) / 2.]);
+            assert!(distance_to_chord(middle, a, b) <= tolerance.inner());
+        }
+
+        // Approximating part of the curve yields the same points, in the
+        // requested order.
+        let part = approx_nurbs(&nurbs, [[0.8], [0.2]], tolerance);
+        let expected = points
+            .iter()
+            .copied()
+            .filter(|(point, _)| point.t > 0.2.into() && point.t < 0.8.into())
+            .rev()
+            .collect::<Vec<_>>();
+        assert_eq!(part, expected);
+
+        // Limiting the angle refines the approximation where the curve turns
+        // sharply.
+        let max_angle = Scalar::from(0.1);
+        let tolerance = tolerance.with_max_angle(max_angle)?;
+        let refined = approx_nurbs(&nurbs, [[0.], [1.]], tolerance);
+        assert!(refined.len() > points.len());
+
+        for window in refined.windows(2) {
+            let [(a, _), (b, _)] = [window[0], window[1]];
+            let [a, b] = [a, b].map(|point| nurbs.derivative_at(point));
+            assert!(angle_between(a, b) <= max_angle);
+        }
+
+        Ok(())
+    }
 }
@@ -9,8 +9,15 @@ use fj_math::Scalar;
 /// A tolerance value is used during approximation. It defines the maximum
 /// allowed deviation of the approximation from the actual shape.
 ///
-/// The `Tolerance` type enforces that the tolerance value is always larger than
-/// zero, which is an attribute that the approximation code relies on.
+/// Optionally, it also defines the maximum angle by which the direction of an
+/// approximated curve, or the normal of an approximated surface, may change
+/// between adjacent points. Where a shape is strongly curved, like a small
+/// fillet, this results in a finer approximation than the deviation alone
+/// would, without affecting flat or gently curved parts of the shape.
+///
+/// The `Tolerance` type enforces that the tolerance value, and the maximum
+/// angle, are always larger than zero, which is an attribute that the
+/// approximation code relies on.
 ///
 /// # Failing [`From`]/[`Into`] implementation
 ///
@@ -22,7 +29,10 @@ use fj_math::Scalar;
 /// documentation doesn't provide any actual reasoning for this requirement, I'm
 /// feeling free to just ignore it.
 #[derive(Clone, Copy, Debug, Eq, PartialEq, Hash, Ord, PartialOrd)]
-pub struct Tolerance(Scalar);
+pub struct Tolerance {
+    distance: Scalar,
+    max_angle: Option<Scalar>,
+}
 
 impl Tolerance {
     /// Construct a `Tolerance` from a [`Scalar`]
@@ -37,12 +47,40 @@ impl Tolerance {
             return Err(InvalidTolerance(scalar));
         }
 
-        Ok(Self(scalar))
+        Ok(Self {
+            distance: scalar,
+            max_angle: None,
+        })
+    }
+
+    /// Limit the angle between adjacent segments of the approximation
+    ///
+    /// The angle is in radians. Returns an error, if it is not larger than
+    /// zero.
+    pub fn with_max_angle(
+        self,
+        max_angle: impl Into<Scalar>,
+    ) -> Result<Self, InvalidTolerance> {
+        let max_angle = max_angle.into();
+
+        if max_angle <= Scalar::ZERO {
+            return Err(InvalidTolerance(max_angle));
+        }
+
+        Ok(Self {
+            max_angle: Some(max_angle),
+            ..self
+        })
     }
 
     /// Return the [`Scalar`] that defines the tolerance
     pub fn inner(&self) -> Scalar {
-        self.0
+        self.distance
+    }
+
+    /// Return the maximum angle between adjacent segments, if any
+    pub fn max_angle(&self) -> Option<Scalar> {
+        self.max_angle
     }
 }
 
@@ -59,4 +97,4 @@ where
 /// Error converting scalar to tolerance
 #[derive(Debug, thiserror::Error)]
 #[error("Invalid tolerance ({0}); must be above zero")]
-pub struct InvalidTolerance(Scalar);
+pub struct InvalidTolerance(pub(crate) Scalar);
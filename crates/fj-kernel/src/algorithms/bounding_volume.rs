@@ -0,0 +1,436 @@
+//! Bounding volumes of objects
+//!
+//! The bounding volumes are computed from the exact geometry of the objects.
+//! Unlike bounding volumes computed from an approximation, they don't depend on
+//! a tolerance value, and contain curved edges completely.
+
+use fj_math::{Aabb, Point, Scalar, Vector};
+
+use crate::{
+    geometry::{
+        path::{GlobalPath, SurfacePath},
+        surface::SurfaceGeometry,
+    },
+    objects::{Cycle, Face, FaceSet, HalfEdge, Shell, Sketch, Solid},
+};
+
+/// Compute a bounding volume for an object
+pub trait BoundingVolume<const D: usize> {
+    /// Compute an axis-aligned bounding box (AABB)
+    ///
+    /// Return `None`, if no AABB can be computed (if the object is empty).
+    fn aabb(&self) -> Option<Aabb<D>>;
+}
+
+impl BoundingVolume<2> for HalfEdge {
+    fn aabb(&self) -> Option<Aabb<2>> {
+        let points = match self.curve().path() {
+            SurfacePath::Line(_) => self
+                .vertices()
+                .iter()
+                .map(|vertex| vertex.surface_form().position())
+                .collect(),
+            SurfacePath::Circle(circle) => arc_extrema(
+                circle.center(),
+                circle.a(),
+                circle.b(),
+                self.curve_range(),
+            ),
+            SurfacePath::Ellipse(ellipse) => arc_extrema(
+                ellipse.center(),
+                ellipse.a(),
+                ellipse.b(),
+                self.curve_range(),
+            ),
+            // A NURBS curve is contained in the convex hull of its control
+            // points. That isn't a tight bound, but it's a correct one.
+            SurfacePath::Nurbs(nurbs) => nurbs.control_points().to_vec(),
+        };
+
+        aabb_from_points(points)
+    }
+}
+
+impl BoundingVolume<2> for Cycle {
+    fn aabb(&self) -> Option<Aabb<2>> {
+        merge(self.half_edges().map(|half_edge| half_edge.aabb()))
+    }
+}
+
+impl BoundingVolume<3> for Face {
+    fn aabb(&self) -> Option<Aabb<3>> {
+        let surface = self.surface().geometry();
+
+        let ranges = || {
+            let Aabb { min, max } = self.exterior().aabb()?;
+            Some(([min.u, max.u], [min.v, max.v]))
+        };
+
+        let (u, v) = match &surface {
+            SurfaceGeometry::Swept { u, v } => (u, v),
+            // Like a NURBS curve, a NURBS surface is contained in the convex
+            // hull of its control points.
+            SurfaceGeometry::Nurbs(nurbs) => {
+                return aabb_from_points(
+                    nurbs.control_points().iter().flatten().copied(),
+                );
+            }
+            // Each point of a ruled surface is an affine combination of a
+            // point on each path. Bounding the paths by boxes, the surface is
+            // bounded by the same combinations of the corners of those boxes.
+            SurfaceGeometry::Ruled { a, b } => {
+                let (range_u, range_v) = ranges()?;
+                let a = aabb_from_points(path_extrema(a, range_u))?;
+                let b = aabb_from_points(path_extrema(b, range_u))?;
+
+                let points = range_v
+                    .into_iter()
+                    .flat_map(|t| {
+                        a.vertices().into_iter().flat_map(move |a| {
+                            b.vertices()
+                                .into_iter()
+                                .map(move |b| a + (b - a) * t)
+                        })
+                    })
+                    .collect::<Vec<_>>();
+
+                return aabb_from_points(points);
+            }
+            // The cross-sections of a cone are circles, which scale linearly
+            // with the distance from the apex. Bounding the arcs at both ends
+            // of the face bounds everything in between.
+            SurfaceGeometry::Cone(cone) => {
+                let (range_u, range_v) = ranges()?;
+
+                let points = range_v
+                    .into_iter()
+                    .flat_map(|t| {
+                        arc_extrema(
+                            cone.apex() + cone.axis() * t,
+                            cone.a() * t,
+                            cone.b() * t,
+                            range_u,
+                        )
+                    })
+                    .collect::<Vec<_>>();
+
+                return aabb_from_points(points);
+            }
+        };
+
+        let (curve, range_v) = match u {
+            // The surface is a plane, which maps the edges of the face into 3D
+            // space without distorting them. Each edge is bounded individually.
+            GlobalPath::Line(_) => {
+                let points = self
+                    .exterior()
+                    .half_edges()
+                    .flat_map(|half_edge| {
+                        let to_3d = |point: Point<2>| {
+                            surface.point_from_surface_coords(point)
+                        };
+                        let arc = |center, a, b| {
+                            arc_extrema(
+                                to_3d(center),
+                                surface.vector_from_surface_coords(a),
+                                surface.vector_from_surface_coords(b),
+                                half_edge.curve_range(),
+                            )
+                        };
+
+                        match half_edge.curve().path() {
+                            SurfacePath::Line(_) => half_edge
+                                .vertices()
+                                .iter()
+                                .map(|vertex| {
+                                    to_3d(vertex.surface_form().position())
+                                })
+                                .collect(),
+                            SurfacePath::Circle(circle) => {
+                                arc(circle.center(), circle.a(), circle.b())
+                            }
+                            SurfacePath::Ellipse(ellipse) => {
+                                arc(ellipse.center(), ellipse.a(), ellipse.b())
+                            }
+                            SurfacePath::Nurbs(nurbs) => nurbs
+                                .control_points()
+                                .iter()
+                                .map(|&point| to_3d(point))
+                                .collect(),
+                        }
+                    })
+                    .collect::<Vec<_>>();
+
+                return aabb_from_points(points);
+            }
+            // The surface is swept from a curve. Bound the part of the curve
+            // that the face covers.
+            _ => {
+                let (range_u, range_v) = ranges()?;
+                (path_extrema(u, range_u), range_v)
+            }
+        };
+
+        // Then sweep the bounded curve along the v-axis.
+        let points = range_v
+            .into_iter()
+            .flat_map(|t| {
+                let offset = *v * t;
+                curve.iter().map(move |&point| point + offset)
+            })
+            .collect::<Vec<_>>();
+
+        aabb_from_points(points)
+    }
+}
+
+impl BoundingVolume<3> for FaceSet {
+    fn aabb(&self) -> Option<Aabb<3>> {
+        merge(self.into_iter().map(|face| face.aabb()))
+    }
+}
+
+impl BoundingVolume<3> for Shell {
+    fn aabb(&self) -> Option<Aabb<3>> {
+        self.faces().aabb()
+    }
+}
+
+impl BoundingVolume<3> for Sketch {
+    fn aabb(&self) -> Option<Aabb<3>> {
+        self.faces().aabb()
+    }
+}
+
+impl BoundingVolume<3> for Solid {
+    fn aabb(&self) -> Option<Aabb<3>> {
+        merge(self.shells().map(|shell| shell.aabb()))
+    }
+}
+
+trait HalfEdgeExt {
+    fn curve_range(&self) -> [Scalar; 2];
+}
+
+impl HalfEdgeExt for HalfEdge {
+    fn curve_range(&self) -> [Scalar; 2] {
+        let [a, b] = self.vertices();
+        [a, b].map(|vertex| vertex.position().t)
+    }
+}
+
+/// Compute points that bound a path within a range
+fn path_extrema(path: &GlobalPath, range: [Scalar; 2]) -> Vec<Point<3>> {
+    match path {
+        GlobalPath::Line(_) => {
+            range.map(|t| path.point_from_path_coords([t])).to_vec()
+        }
+        GlobalPath::Circle(circle) => {
+            arc_extrema(circle.center(), circle.a(), circle.b(), range)
+        }
+        GlobalPath::Ellipse(ellipse) => {
+            arc_extrema(ellipse.center(), ellipse.a(), ellipse.b(), range)
+        }
+        GlobalPath::Helix(helix) => {
+            // The helix stays on a cylinder around its axis. Bounding its
+            // turns as circles, and adding the rise along the axis, covers
+            // all of it.
+            let rise = range.map(|t| helix.pitch() * (t / Scalar::TAU));
+            arc_extrema(helix.center(), helix.a(), helix.b(), range)
+                .into_iter()
+                .flat_map(|point| rise.map(|rise| point + rise))
+                .collect()
+        }
+        GlobalPath::Nurbs(nurbs) => nurbs.control_points().to_vec(),
+    }
+}
+
+/// Compute the points of an arc that bound it along each axis
+///
+/// The arc is defined by the points `center + a * cos(t) + b * sin(t)` for all
+/// `t` within `range`. Besides its end points, the extreme points of the arc
+/// along each axis are returned, if they lie within the range.
+fn arc_extrema<const D: usize>(
+    center: Point<D>,
+    a: Vector<D>,
+    b: Vector<D>,
+    range: [Scalar; 2],
+) -> Vec<Point<D>> {
+    let [start, end] = range;
+    let (start, end) = if start <= end {
+        (start, end)
+    } else {
+        (end, start)
+    };
+
+    let point = |t: Scalar| {
+        let (sin, cos) = t.sin_cos();
+        center + a * cos + b * sin
+    };
+
+    let mut points = vec![point(start), point(end)];
+
+    for (a, b) in a.components.into_iter().zip(b.components) {
+        // The derivative of the coordinate is `b * cos(t) - a * sin(t)`, which
+        // becomes zero at these angles.
+        let extremum = b.atan2(a);
+
+        for t in [extremum, extremum + Scalar::PI] {
+            // Move the angle into the first full turn from the start of the arc.
+            let turns = ((t - start) / Scalar::TAU).floor();
+            let t = t - turns * Scalar::TAU;
+
+            if t <= end {
+                points.push(point(t));
+            }
+        }
+    }
+
+    points
+}
+
+fn aabb_from_points<const D: usize>(
+    points: impl IntoIterator<Item = Point<D>>,
+) -> Option<Aabb<D>> {
+    points.into_iter().fold(None, |aabb, point| {
+        let aabb = match aabb {
+            Some(aabb) => aabb,
+            None => Aabb {
+                min: point,
+                max: point,
+            },
+        };
+
+        let mut min = aabb.min;
+        let mut max = aabb.max;
+        for i in 0..D {
+            let c = point.coords.components[i];
+            min.coords.components[i] = Ord::min(min.coords.components[i], c);
+            max.coords.components[i] = Ord::max(max.coords.components[i], c);
+        }
+
+        Some(Aabb { min, max })
+    })
+}
+
+fn merge<const D: usize>(
+    aabbs: impl IntoIterator<Item = Option<Aabb<D>>>,
+) -> Option<Aabb<D>> {
+    aabb_from_points(
+        aabbs
+            .into_iter()
+            .flatten()
+            .flat_map(|Aabb { min, max }| [min, max]),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use fj_math::{Aabb, Point, Scalar};
+
+    use crate::{
+        algorithms::{
+            approx::Tolerance, sweep::Sweep, triangulate::Triangulate,
+        },
+        builder::{CycleBuilder, FaceBuilder},
+        insert::Insert,
+        objects::{Cycle, Face, Sketch},
+        partial::HasPartial,
+        services::Services,
+    };
+
+    use super::BoundingVolume;
+
+    #[test]
+    fn arc() {
+        let mut services = Services::new();
+
+        let surface = services.objects.surfaces.xy_plane();
+        let cycle = Cycle::partial()
+            .with_arc_from_points(
+                surface.clone(),
+                [[1., 0.], [0., 1.], [-1., 0.]],
+            )
+            .close_with_line_segment()
+            .build(&mut services.objects)
+            .insert(&mut services.objects);
+
+        // The vertices of the arc only reach up to `v == 0`. The apex of the
+        // arc is what determines the upper bound.
+        let aabb = cycle.aabb().expect("Expected non-empty cycle");
+        assert_aabb_eq(aabb, [-1., 0.], [1., 1.]);
+    }
+
+    #[test]
+    fn circle_on_rotated_plane() {
+        let mut services = Services::new();
+
+        let surface = services.objects.surfaces.yz_plane();
+        let face = Face::partial()
+            .with_exterior_circle(surface, [1., 1.], 2., &mut services.objects)
+            .build(&mut services.objects)
+            .insert(&mut services.objects);
+
+        let aabb = face.aabb().expect("Expected non-empty face");
+        assert_aabb_eq(aabb, [0., -1., -1.], [0., 3., 3.]);
+    }
+
+    #[test]
+    fn cylinder() {
+        let mut services = Services::new();
+
+        let surface = services.objects.surfaces.xy_plane();
+        let face = Face::partial()
+            .with_exterior_circle(surface, [0., 0.], 1., &mut services.objects)
+            .build(&mut services.objects)
+            .insert(&mut services.objects);
+        let solid = Sketch::new([face])
+            .insert(&mut services.objects)
+            .sweep([0., 0., 2.], &mut services.objects);
+
+        let aabb = solid.aabb().expect("Expected non-empty solid");
+        assert_aabb_eq(aabb, [-1., -1., 0.], [1., 1., 2.]);
+    }
+
+    #[test]
+    fn elliptic_cylinder() -> anyhow::Result<()> {
+        let mut services = Services::new();
+
+        let surface = services.objects.surfaces.xy_plane();
+        let face = Face::partial()
+            .with_exterior(Cycle::partial().with_ellipse(
+                surface,
+                [0., 0.],
+                [2., 1.],
+                &mut services.objects,
+            ))
+            .build(&mut services.objects)
+            .insert(&mut services.objects);
+        let solid = Sketch::new([face])
+            .insert(&mut services.objects)
+            .sweep([0., 0., 1.], &mut services.objects);
+
+        let aabb = solid.aabb().expect("Expected non-empty solid");
+        assert_aabb_eq(aabb, [-2., -1., 0.], [2., 1., 1.]);
+
+        // The swept surface can be approximated, too.
+        let mesh = (&*solid, Tolerance::from_scalar(0.01)?).triangulate();
+        assert!(mesh.triangles().count() > 0);
+
+        Ok(())
+    }
+
+    fn assert_aabb_eq<const D: usize>(
+        aabb: Aabb<D>,
+        min: impl Into<Point<D>>,
+        max: impl Into<Point<D>>,
+    ) {
+        let [min, max] = [min.into(), max.into()];
+
+        assert!(
+            aabb.min.distance_to(&min) < Scalar::from(1e-12)
+                && aabb.max.distance_to(&max) < Scalar::from(1e-12),
+            "Expected AABB from {min:?} to {max:?}, got {aabb:?}"
+        );
+    }
+}
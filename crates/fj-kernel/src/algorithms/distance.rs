@@ -0,0 +1,502 @@
+//! Minimum distance between objects
+//!
+//! See [`Distance`].
+
+use fj_math::{Point, Scalar, Segment, Triangle, Vector};
+
+use crate::objects::{Face, HalfEdge, Solid};
+
+use super::{
+    approx::{Approx, Tolerance},
+    point_in_solid::{encloses, face_triangles},
+    triangulate::Triangulate,
+};
+
+/// Compute the minimum distance between two objects
+///
+/// Both objects are approximated using the provided tolerance, and the distance
+/// is computed between those approximations. The result is therefore only
+/// accurate to within the tolerance.
+///
+/// Solids are treated as volumes, not just as their boundaries. If any part of
+/// the other object is located within a solid, the distance between them is
+/// zero.
+pub trait Distance<Other: ?Sized> {
+    /// Compute the minimum distance between `self` and `other`
+    fn distance(
+        &self,
+        other: &Other,
+        tolerance: impl Into<Tolerance>,
+    ) -> MinimumDistance;
+}
+
+macro_rules! impl_distance {
+    ($($a:ty, $b:ty;)*) => {
+        $(
+            impl Distance<$b> for $a {
+                fn distance(
+                    &self,
+                    other: &$b,
+                    tolerance: impl Into<Tolerance>,
+                ) -> MinimumDistance {
+                    let tolerance = tolerance.into();
+                    minimum_distance(
+                        &self.primitives(tolerance),
+                        &other.primitives(tolerance),
+                    )
+                }
+            }
+        )*
+    };
+}
+
+impl_distance!(
+    Face, Face;
+    Face, HalfEdge;
+    Face, Solid;
+    HalfEdge, Face;
+    HalfEdge, HalfEdge;
+    HalfEdge, Solid;
+    Solid, Face;
+    Solid, HalfEdge;
+    Solid, Solid;
+);
+
+/// The minimum distance between two objects
+///
+/// Returned by [`Distance::distance`].
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash, Ord, PartialOrd)]
+pub struct MinimumDistance {
+    /// The distance between the objects
+    pub distance: Scalar,
+
+    /// The points on both objects that are closest to each other
+    ///
+    /// The first point is on `self`, the second one on `other`. If the objects
+    /// overlap, both points are the same.
+    pub points: [Point<3>; 2],
+}
+
+impl MinimumDistance {
+    fn between(a: Point<3>, b: Point<3>) -> Self {
+        Self {
+            distance: (b - a).magnitude(),
+            points: [a, b],
+        }
+    }
+
+    fn swap(self) -> Self {
+        let [a, b] = self.points;
+        Self {
+            distance: self.distance,
+            points: [b, a],
+        }
+    }
+
+    fn closest(self, other: Self) -> Self {
+        if other.distance < self.distance {
+            other
+        } else {
+            self
+        }
+    }
+}
+
+/// The primitives that approximate an object
+struct Primitives {
+    segments: Vec<Segment<3>>,
+    triangles: Vec<Triangle<3>>,
+
+    /// Indicates whether the triangles bound a volume
+    is_solid: bool,
+}
+
+impl Primitives {
+    fn any_point(&self) -> Option<Point<3>> {
+        self.segments
+            .first()
+            .map(|segment| segment.points()[0])
+            .or_else(|| {
+                self.triangles.first().map(|triangle| triangle.points()[0])
+            })
+    }
+}
+
+trait ToPrimitives {
+    fn primitives(&self, tolerance: Tolerance) -> Primitives;
+}
+
+impl ToPrimitives for HalfEdge {
+    fn primitives(&self, tolerance: Tolerance) -> Primitives {
+        let mut points = self
+            .approx(tolerance)
+            .points()
+            .into_iter()
+            .map(|point| point.global_form)
+            .collect::<Vec<_>>();
+        points.push(self.vertices()[1].global_form().position());
+
+        let segments = points
+            .windows(2)
+            .map(|points| Segment::from_points([points[0], points[1]]))
+            .collect();
+
+        Primitives {
+            segments,
+            triangles: Vec::new(),
+            is_solid: false,
+        }
+    }
+}
+
+impl ToPrimitives for Face {
+    fn primitives(&self, tolerance: Tolerance) -> Primitives {
+        let triangles = self
+            .approx(tolerance)
+            .triangulate()
+            .triangles()
+            .map(|triangle| triangle.inner)
+            .collect();
+
+        Primitives {
+            segments: Vec::new(),
+            triangles,
+            is_solid: false,
+        }
+    }
+}
+
+impl ToPrimitives for Solid {
+    fn primitives(&self, tolerance: Tolerance) -> Primitives {
+        let triangles = face_triangles(self, tolerance)
+            .into_iter()
+            .flat_map(|(_, triangles)| triangles)
+            .collect();
+
+        Primitives {
+            segments: Vec::new(),
+            triangles,
+            is_solid: true,
+        }
+    }
+}
+
+fn minimum_distance(a: &Primitives, b: &Primitives) -> MinimumDistance {
+    let mut closest: Option<MinimumDistance> = None;
+    let mut update = |distance: MinimumDistance| {
+        closest = Some(match closest {
+            Some(closest) => closest.closest(distance),
+            None => distance,
+        });
+    };
+
+    for &s in &a.segments {
+        for &t in &b.segments {
+            update(segment_segment(s, t));
+        }
+        for t in &b.triangles {
+            update(segment_triangle(s, t));
+        }
+    }
+    for s in &a.triangles {
+        for &t in &b.segments {
+            update(segment_triangle(t, s).swap());
+        }
+        for t in &b.triangles {
+            update(triangle_triangle(s, t));
+        }
+    }
+
+    let closest =
+        closest.expect("Can't compute distance to object without geometry");
+
+    if closest.distance > Scalar::ZERO {
+        // The boundaries don't touch, but one object might still be located
+        // entirely within the other.
+        if let Some(point) = a.any_point() {
+            if b.is_solid && encloses(&b.triangles, point) {
+                return MinimumDistance::between(point, point);
+            }
+        }
+        if let Some(point) = b.any_point() {
+            if a.is_solid && encloses(&a.triangles, point) {
+                return MinimumDistance::between(point, point);
+            }
+        }
+    }
+
+    closest
+}
+
+fn triangle_triangle(a: &Triangle<3>, b: &Triangle<3>) -> MinimumDistance {
+    // If the triangles intersect, an edge of one of them passes through the
+    // other. If they don't, the closest points lie on an edge of at least one
+    // of them. Either way, checking all edges against the other triangle
+    // covers it.
+    let from_a = edges(a).map(|edge| segment_triangle(edge, b));
+    let from_b = edges(b).map(|edge| segment_triangle(edge, a).swap());
+
+    from_a
+        .into_iter()
+        .chain(from_b)
+        .reduce(MinimumDistance::closest)
+        .expect("Triangles have edges")
+}
+
+fn segment_triangle(
+    segment: Segment<3>,
+    triangle: &Triangle<3>,
+) -> MinimumDistance {
+    let [p, q] = segment.points();
+    let [a, b, c] = triangle.points();
+    let normal = (b - a).cross(&(c - a));
+
+    // Check whether the segment passes through the triangle.
+    let [dp, dq] = [p, q].map(|point| normal.dot(&(point - a)));
+    if dp * dq < Scalar::ZERO {
+        let crossing = p + (q - p) * (dp / (dp - dq));
+
+        let is_inside = [(a, b), (b, c), (c, a)].into_iter().all(|(u, v)| {
+            (v - u).cross(&(crossing - u)).dot(&normal) >= Scalar::ZERO
+        });
+        if is_inside {
+            return MinimumDistance::between(crossing, crossing);
+        }
+    }
+
+    let from_endpoints = [p, q].map(|point| {
+        MinimumDistance::between(
+            point,
+            closest_point_on_triangle(triangle, point),
+        )
+    });
+    let from_edges = edges(triangle).map(|edge| segment_segment(segment, edge));
+
+    from_endpoints
+        .into_iter()
+        .chain(from_edges)
+        .reduce(MinimumDistance::closest)
+        .expect("Segment has endpoints")
+}
+
+/// Compute the closest points between two segments
+///
+/// Algorithm from Real-Time Collision Detection by Christer Ericson. See
+/// section 5.1.9, Closest Points of Two Line Segments.
+fn segment_segment(a: Segment<3>, b: Segment<3>) -> MinimumDistance {
+    let [p1, q1] = a.points();
+    let [p2, q2] = b.points();
+
+    let d1 = q1 - p1;
+    let d2 = q2 - p2;
+    let r = p1 - p2;
+
+    let aa = d1.dot(&d1);
+    let e = d2.dot(&d2);
+    let f = d2.dot(&r);
+
+    let clamp = |s: Scalar| s.max(Scalar::ZERO).min(Scalar::ONE);
+
+    let (s, t) = if aa == Scalar::ZERO && e == Scalar::ZERO {
+        (Scalar::ZERO, Scalar::ZERO)
+    } else if aa == Scalar::ZERO {
+        (Scalar::ZERO, clamp(f / e))
+    } else {
+        let c = d1.dot(&r);
+
+        if e == Scalar::ZERO {
+            (clamp(-c / aa), Scalar::ZERO)
+        } else {
+            let bb = d1.dot(&d2);
+            let denominator = aa * e - bb * bb;
+
+            // If the segments are parallel, any point on the first segment
+            // will do.
+            let s = if denominator != Scalar::ZERO {
+                clamp((bb * f - c * e) / denominator)
+            } else {
+                Scalar::ZERO
+            };
+            let t = (bb * s + f) / e;
+
+            if t < Scalar::ZERO {
+                (clamp(-c / aa), Scalar::ZERO)
+            } else if t > Scalar::ONE {
+                (clamp((bb - c) / aa), Scalar::ONE)
+            } else {
+                (s, t)
+            }
+        }
+    };
+
+    MinimumDistance::between(p1 + d1 * s, p2 + d2 * t)
+}
+
+/// Compute the point on a triangle that is closest to the given point
+///
+/// Algorithm from Real-Time Collision Detection by Christer Ericson. See
+/// section 5.1.5, Closest Point on Triangle to Point.
+pub(crate) fn closest_point_on_triangle(
+    triangle: &Triangle<3>,
+    point: Point<3>,
+) -> Point<3> {
+    let [a, b, c] = triangle.points();
+    let [ab, ac, ap] = [b - a, c - a, point - a];
+
+    let d1 = ab.dot(&ap);
+    let d2 = ac.dot(&ap);
+    if d1 <= Scalar::ZERO && d2 <= Scalar::ZERO {
+        return a;
+    }
+
+    let bp = point - b;
+    let d3 = ab.dot(&bp);
+    let d4 = ac.dot(&bp);
+    if d3 >= Scalar::ZERO && d4 <= d3 {
+        return b;
+    }
+
+    let vc = d1 * d4 - d3 * d2;
+    if vc <= Scalar::ZERO && d1 >= Scalar::ZERO && d3 <= Scalar::ZERO {
+        return a + ab * (d1 / (d1 - d3));
+    }
+
+    let cp = point - c;
+    let d5 = ab.dot(&cp);
+    let d6 = ac.dot(&cp);
+    if d6 >= Scalar::ZERO && d5 <= d6 {
+        return c;
+    }
+
+    let vb = d5 * d2 - d1 * d6;
+    if vb <= Scalar::ZERO && d2 >= Scalar::ZERO && d6 <= Scalar::ZERO {
+        return a + ac * (d2 / (d2 - d6));
+    }
+
+    let va = d3 * d6 - d5 * d4;
+    if va <= Scalar::ZERO && d4 - d3 >= Scalar::ZERO && d5 - d6 >= Scalar::ZERO
+    {
+        let w = (d4 - d3) / ((d4 - d3) + (d5 - d6));
+        return b + (c - b) * w;
+    }
+
+    let denominator = va + vb + vc;
+    let offset: Vector<3> = ab * (vb / denominator) + ac * (vc / denominator);
+    a + offset
+}
+
+fn edges(triangle: &Triangle<3>) -> [Segment<3>; 3] {
+    let [a, b, c] = triangle.points();
+    [[a, b], [b, c], [c, a]].map(Segment::from_points)
+}
+
+#[cfg(test)]
+mod tests {
+    use fj_math::{Point, Scalar};
+
+    use crate::{
+        algorithms::{
+            approx::Tolerance, sweep::Sweep, transform::TransformObject,
+        },
+        objects::{Sketch, Solid},
+        services::Services,
+        storage::Handle,
+    };
+
+    use super::Distance;
+
+    fn cube(
+        min: [f64; 3],
+        edge_length: f64,
+        services: &mut Services,
+    ) -> Handle<Solid> {
+        let [x, y, z] = min;
+        let surface = services
+            .objects
+            .surfaces
+            .xy_plane()
+            .translate([0., 0., z], &mut services.objects);
+
+        Sketch::builder()
+            .with_polygon_from_points(
+                surface,
+                [
+                    [x, y],
+                    [x + edge_length, y],
+                    [x + edge_length, y + edge_length],
+                    [x, y + edge_length],
+                ],
+                &mut services.objects,
+            )
+            .build(&mut services.objects)
+            .sweep([0., 0., edge_length], &mut services.objects)
+    }
+
+    #[test]
+    fn distance_between_solids() {
+        let mut services = Services::new();
+        let tolerance = Tolerance::from_scalar(0.001).unwrap();
+
+        let a = cube([0., 0., 0.], 1., &mut services);
+        let b = cube([3., 0.25, 0.25], 0.5, &mut services);
+
+        let distance = a.distance(&*b, tolerance);
+        assert_eq!(distance.distance, Scalar::from(2.));
+        assert_eq!(distance.points[0].x, Scalar::from(1.));
+        assert_eq!(distance.points[1].x, Scalar::from(3.));
+
+        let distance = b.distance(&*a, tolerance);
+        assert_eq!(distance.points[0].x, Scalar::from(3.));
+        assert_eq!(distance.points[1].x, Scalar::from(1.));
+
+        // One solid within the other.
+        let c = cube([0.25, 0.25, 0.25], 0.5, &mut services);
+        assert_eq!(a.distance(&*c, tolerance).distance, Scalar::ZERO);
+        assert_eq!(c.distance(&*a, tolerance).distance, Scalar::ZERO);
+    }
+
+    #[test]
+    fn distance_between_faces_and_edges() {
+        let mut services = Services::new();
+        let tolerance = Tolerance::from_scalar(0.001).unwrap();
+
+        let a = cube([0., 0., 0.], 1., &mut services);
+        let b = cube([2., 2., 2.], 1., &mut services);
+
+        let top_of_a = a
+            .shells()
+            .flat_map(|shell| shell.faces().clone())
+            .find(|face| {
+                face.exterior().half_edges().all(|half_edge| {
+                    half_edge.vertices().iter().all(|vertex| {
+                        vertex.global_form().position().z == Scalar::ONE
+                    })
+                })
+            })
+            .unwrap();
+        let edge_of_b = b
+            .shells()
+            .flat_map(|shell| shell.faces().clone())
+            .flat_map(|face| {
+                face.exterior().half_edges().cloned().collect::<Vec<_>>()
+            })
+            .find(|half_edge| {
+                half_edge.vertices().iter().all(|vertex| {
+                    let position = vertex.global_form().position();
+                    position.x == Scalar::from(2.)
+                        && position.y == Scalar::from(2.)
+                })
+            })
+            .unwrap();
+
+        let distance = top_of_a.distance(&*edge_of_b, tolerance);
+        assert_eq!(distance.distance, Scalar::from(3_f64.sqrt()));
+        assert_eq!(
+            distance.points,
+            [Point::from([1., 1., 1.]), Point::from([2., 2., 2.])]
+        );
+
+        let distance = edge_of_b.distance(&*a, tolerance);
+        assert_eq!(distance.distance, Scalar::from(3_f64.sqrt()));
+    }
+}
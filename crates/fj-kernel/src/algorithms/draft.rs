@@ -0,0 +1,418 @@
+//! Sweeping faces with a draft angle
+//!
+//! Molded and cast parts need tapered side walls, so they can be released from
+//! the mold. A draft angle tilts the side faces of a sweep by that angle, which
+//! shrinks (or, for negative angles, grows) the face towards the end of the
+//! sweep.
+
+use fj_math::{Point, Scalar, Vector, Winding};
+
+use crate::{
+    algorithms::{
+        approx::Tolerance, loft::polygon_from_cycle, reverse::Reverse,
+        transform::TransformObject,
+    },
+    builder::{CycleBuilder, SurfaceBuilder},
+    geometry::{path::GlobalPath, surface::SurfaceGeometry},
+    insert::Insert,
+    objects::{Cycle, Face, Objects, Shell, Solid, Surface},
+    partial::{
+        HasPartial, PartialCycle, PartialGlobalVertex, PartialSurface,
+        PartialSurfaceVertex,
+    },
+    services::Service,
+    storage::Handle,
+};
+
+/// Sweep a face along a straight path, tapering its side faces
+///
+/// `angle` is the angle (in radians) between the side faces and the path. For
+/// positive angles, the face shrinks towards the end of the sweep. For negative
+/// ones, it grows. Holes in the face are drafted along with its exterior, so
+/// they grow where the face shrinks.
+///
+/// The corners of the face stay sharp, and each straight edge results in one
+/// planar side face. Curved half-edges are approximated with straight ones,
+/// using the provided tolerance.
+///
+/// # Implementation Note
+///
+/// The face must be defined on a plane. Like for [`offset_cycle`], distances
+/// are measured in surface coordinates, which only matches distance in model
+/// space for surfaces with orthonormal axes (like the basic planes).
+///
+/// [`offset_cycle`]: crate::algorithms::offset::offset_cycle
+pub fn sweep_with_draft(
+    face: &Handle<Face>,
+    path: impl Into<Vector<3>>,
+    angle: impl Into<Scalar>,
+    tolerance: impl Into<Tolerance>,
+    objects: &mut Service<Objects>,
+) -> Result<Handle<Solid>, DraftError> {
+    let path = path.into();
+    let angle = angle.into();
+    let tolerance = tolerance.into();
+
+    if angle.abs() >= Scalar::PI / 2. {
+        return Err(DraftError::InvalidAngle(angle));
+    }
+    if path.magnitude() == Scalar::ZERO {
+        return Err(DraftError::ZeroPath);
+    }
+
+    // Looking at the face against its normal, the face is to the left of all
+    // of its cycles.
+    let (normal, material_side) = {
+        let normal = surface_normal(face.surface())?;
+        match face.exterior().winding() {
+            Winding::Ccw => (normal, Scalar::ONE),
+            Winding::Cw => (-normal, -Scalar::ONE),
+        }
+    };
+    let distance =
+        path.magnitude() * Scalar::from(angle.into_f64().tan()) * material_side;
+
+    let top_surface = face.surface().clone().translate(path, objects);
+
+    let mut bottom = Vec::new();
+    let mut top = Vec::new();
+    for cycle in face.all_cycles() {
+        let cycle = polygon_from_cycle(cycle, tolerance, objects);
+
+        let points = cycle
+            .half_edges()
+            .map(|half_edge| half_edge.back().surface_form().position())
+            .collect::<Vec<_>>();
+        let points = offset_polygon(&points, distance)?;
+
+        top.push(
+            Cycle::partial()
+                .with_poly_chain_from_points(top_surface.clone(), points)
+                .close_with_line_segment()
+                .build(objects)
+                .insert(objects),
+        );
+        bottom.push(cycle);
+    }
+
+    // The side faces are oriented according to the direction of the edges of
+    // the bottom face. That only points them outward, if the sweep goes in the
+    // direction of the face's normal.
+    let along_normal = normal.dot(&path) > Scalar::ZERO;
+
+    let mut faces = Vec::new();
+    for (bottom, top) in bottom.iter().zip(&top) {
+        let [bottom, top] = [bottom, top].map(global_points);
+
+        let num_vertices = bottom.len();
+        for i in 0..num_vertices {
+            let j = (i + 1) % num_vertices;
+
+            let points = if along_normal {
+                [bottom[i], bottom[j], top[j], top[i]]
+            } else {
+                [bottom[j], bottom[i], top[i], top[j]]
+            };
+            faces.push(quadrilateral(points, face, objects));
+        }
+    }
+
+    let [bottom, top] = [bottom, top].map(|mut cycles| {
+        let exterior = cycles.remove(0);
+        Face::partial()
+            .with_exterior(exterior)
+            .with_interiors(cycles)
+            .with_color(face.color())
+            .with_attributes(face.attributes().clone())
+            .build(objects)
+            .insert(objects)
+    });
+    if along_normal {
+        faces.push(bottom.reverse(objects));
+        faces.push(top);
+    } else {
+        faces.push(bottom);
+        faces.push(top.reverse(objects));
+    }
+
+    let shell = Shell::builder().with_faces(faces).build(objects);
+    Ok(Solid::builder().with_shells([shell]).build(objects))
+}
+
+/// An error that can occur while sweeping with a draft angle
+#[derive(Clone, Debug, Eq, PartialEq, thiserror::Error)]
+pub enum DraftError {
+    /// The draft angle is not between -90 and 90 degrees
+    #[error("Draft angle must be between -90 and 90 degrees (is {0} rad)")]
+    InvalidAngle(Scalar),
+
+    /// The path of the sweep has zero length
+    #[error("Can't sweep along a path of zero length")]
+    ZeroPath,
+
+    /// The face is not defined on a plane
+    #[error("Only faces that are defined on planes can be swept with draft")]
+    NonPlanarFace,
+
+    /// The draft closes up a feature of the face
+    #[error(
+        "Draft is too large for the face; the end of the sweep would \
+        intersect itself"
+    )]
+    DraftTooLarge,
+}
+
+/// Move each edge of the polygon by `distance` to its left
+///
+/// Adjacent edges are extended or trimmed, so they still meet at a sharp
+/// corner. This keeps the number of vertices, and the directions of the edges.
+fn offset_polygon(
+    points: &[Point<2>],
+    distance: Scalar,
+) -> Result<Vec<Point<2>>, DraftError> {
+    let num_points = points.len();
+
+    let offset = (0..num_points)
+        .map(|i| {
+            let prev = points[(i + num_points - 1) % num_points];
+            let point = points[i];
+            let next = points[(i + 1) % num_points];
+
+            let [prev_normal, next_normal] = [point - prev, next - point]
+                .map(|edge| left_normal(edge.normalize()));
+
+            // This is the offset vector that has a component of `distance`
+            // along both normals.
+            let denominator = Scalar::ONE + prev_normal.dot(&next_normal);
+            if denominator < Scalar::from(EPSILON) {
+                return Err(DraftError::DraftTooLarge);
+            }
+
+            Ok(point + (prev_normal + next_normal) * distance / denominator)
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+
+    // If the offset flips an edge around, the polygon intersects itself.
+    for i in 0..num_points {
+        let j = (i + 1) % num_points;
+
+        let original = points[j] - points[i];
+        let moved = offset[j] - offset[i];
+
+        if original.dot(&moved) <= Scalar::ZERO {
+            return Err(DraftError::DraftTooLarge);
+        }
+    }
+
+    Ok(offset)
+}
+
+fn left_normal(direction: Vector<2>) -> Vector<2> {
+    Vector::from([-direction.v, direction.u])
+}
+
+fn global_points(cycle: &Handle<Cycle>) -> Vec<Point<3>> {
+    cycle
+        .half_edges()
+        .map(|half_edge| half_edge.back().global_form().position())
+        .collect()
+}
+
+fn surface_normal(surface: &Surface) -> Result<Vector<3>, DraftError> {
+    match surface.geometry() {
+        SurfaceGeometry::Swept {
+            u: GlobalPath::Line(line),
+            v,
+        } => Ok(line.direction().cross(&v)),
+        _ => Err(DraftError::NonPlanarFace),
+    }
+}
+
+/// Build a planar face from four points
+///
+/// The fourth point must be in the plane of the other three. The new face
+/// takes its color and attributes from `face`.
+fn quadrilateral(
+    points: [Point<3>; 4],
+    face: &Face,
+    objects: &mut Service<Objects>,
+) -> Handle<Face> {
+    let [a, b, c, d] = points;
+
+    let surface = PartialSurface::plane_from_points([a, b, d])
+        .build(objects)
+        .insert(objects);
+
+    // The surface coordinates of the third point follow from solving
+    // `c - a = u * (b - a) + v * (d - a)`.
+    let position_c = {
+        let [u, v, w] = [b - a, d - a, c - a];
+
+        let [uu, uv, vv] = [u.dot(&u), u.dot(&v), v.dot(&v)];
+        let [wu, wv] = [w.dot(&u), w.dot(&v)];
+        let determinant = uu * vv - uv * uv;
+
+        [
+            (wu * vv - wv * uv) / determinant,
+            (wv * uu - wu * uv) / determinant,
+        ]
+    };
+
+    // Converting surface coordinates back into global ones isn't exact. The
+    // side faces share their vertices with their neighbors and the caps, so
+    // use the exact positions instead.
+    let vertices = [
+        ([Scalar::ZERO, Scalar::ZERO], a),
+        ([Scalar::ONE, Scalar::ZERO], b),
+        (position_c, c),
+        ([Scalar::ZERO, Scalar::ONE], d),
+    ]
+    .map(|(position, point)| PartialSurfaceVertex {
+        position: Some(position.into()),
+        surface: Some(surface.clone()),
+        global_form: PartialGlobalVertex {
+            position: Some(point),
+        }
+        .into(),
+    });
+
+    Face::partial()
+        .with_exterior(
+            PartialCycle::default()
+                .with_poly_chain(vertices)
+                .close_with_line_segment(),
+        )
+        .with_color(face.color())
+        .with_attributes(face.attributes().clone())
+        .build(objects)
+        .insert(objects)
+}
+
+const EPSILON: f64 = 1e-9;
+
+#[cfg(test)]
+mod tests {
+    use fj_math::{Point, Scalar};
+
+    use crate::{
+        algorithms::{approx::Tolerance, triangulate::Triangulate},
+        builder::CycleBuilder,
+        insert::Insert,
+        iter::ObjectIters,
+        objects::{Cycle, Face},
+        partial::HasPartial,
+        services::Services,
+    };
+
+    use super::{sweep_with_draft, DraftError};
+
+    const SQUARE: [[f64; 2]; 4] = [[-1., -1.], [1., -1.], [1., 1.], [-1., 1.]];
+
+    #[test]
+    fn draft_shrinks_square() -> anyhow::Result<()> {
+        let mut services = Services::new();
+
+        let surface = services.objects.surfaces.xy_plane();
+        let face = Face::partial()
+            .with_exterior(
+                Cycle::partial()
+                    .with_poly_chain_from_points(surface, SQUARE)
+                    .close_with_line_segment(),
+            )
+            .build(&mut services.objects)
+            .insert(&mut services.objects);
+
+        // An angle of 45 degrees moves each edge inwards by the height of the
+        // sweep.
+        let solid = sweep_with_draft(
+            &face,
+            [0., 0., 0.5],
+            Scalar::PI / 4.,
+            0.01,
+            &mut services.objects,
+        )?;
+        assert_eq!(solid.face_iter().count(), 6);
+
+        let expected_top = [
+            Point::from([0.5, 0.5, 0.5]),
+            Point::from([-0.5, 0.5, 0.5]),
+            Point::from([-0.5, -0.5, 0.5]),
+            Point::from([0.5, -0.5, 0.5]),
+        ];
+        for expected in expected_top {
+            assert!(solid.global_vertex_iter().any(|vertex| {
+                vertex.position().distance_to(&expected) < Scalar::from(1e-9)
+            }));
+        }
+
+        let mesh = (&*solid, Tolerance::from_scalar(0.01)?).triangulate();
+        assert_eq!(mesh.triangles().count(), 6 * 2);
+
+        Ok(())
+    }
+
+    #[test]
+    fn draft_grows_holes() -> anyhow::Result<()> {
+        let mut services = Services::new();
+
+        let surface = services.objects.surfaces.xy_plane();
+        let exterior = Cycle::partial()
+            .with_poly_chain_from_points(surface.clone(), SQUARE)
+            .close_with_line_segment()
+            .build(&mut services.objects)
+            .insert(&mut services.objects);
+        let interior = Cycle::partial()
+            .with_poly_chain_from_points(
+                surface,
+                SQUARE.map(|[x, y]| [x / 4., y / 4.]).into_iter().rev(),
+            )
+            .close_with_line_segment()
+            .build(&mut services.objects)
+            .insert(&mut services.objects);
+        let face = Face::partial()
+            .with_exterior(exterior)
+            .with_interiors([interior])
+            .build(&mut services.objects)
+            .insert(&mut services.objects);
+
+        let solid = sweep_with_draft(
+            &face,
+            [0., 0., -0.5],
+            Scalar::PI / 4.,
+            0.01,
+            &mut services.objects,
+        )?;
+        assert_eq!(solid.face_iter().count(), 10);
+
+        let expected = Point::from([0.75, 0.75, -0.5]);
+        assert!(solid.global_vertex_iter().any(|vertex| {
+            vertex.position().distance_to(&expected) < Scalar::from(1e-9)
+        }));
+
+        Ok(())
+    }
+
+    #[test]
+    fn draft_too_large() {
+        let mut services = Services::new();
+
+        let surface = services.objects.surfaces.xy_plane();
+        let face = Face::partial()
+            .with_exterior(
+                Cycle::partial()
+                    .with_poly_chain_from_points(surface, SQUARE)
+                    .close_with_line_segment(),
+            )
+            .build(&mut services.objects)
+            .insert(&mut services.objects);
+
+        let result = sweep_with_draft(
+            &face,
+            [0., 0., 2.],
+            Scalar::PI / 4.,
+            0.01,
+            &mut services.objects,
+        );
+        assert_eq!(result, Err(DraftError::DraftTooLarge));
+    }
+}
@@ -0,0 +1,424 @@
+//! Convex hulls
+//!
+//! The convex hull of a set of points is the smallest convex solid that
+//! contains all of them. It is useful as simple bounding geometry, for example
+//! as a collision shape.
+
+use std::collections::{BTreeMap, BTreeSet};
+
+use fj_interop::mesh::{Attributes, Color};
+use fj_math::{Aabb, Point, Scalar, Vector};
+
+use crate::{
+    algorithms::{approx::Tolerance, triangulate::Triangulate},
+    objects::{Objects, Shell, Solid},
+    services::Service,
+    storage::Handle,
+};
+
+use super::polyhedron::{build_face, polygon_normal};
+
+/// Compute the convex hull of a set of points
+///
+/// Returns a solid with a single shell. Coplanar parts of the hull are merged
+/// into a single face, and points that don't end up as corners of the hull are
+/// ignored.
+pub fn convex_hull(
+    points: impl IntoIterator<Item = impl Into<Point<3>>>,
+    objects: &mut Service<Objects>,
+) -> Result<Handle<Solid>, HullError> {
+    let points = points
+        .into_iter()
+        .map(Into::into)
+        .collect::<BTreeSet<_>>()
+        .into_iter()
+        .collect::<Vec<_>>();
+
+    let hull = Hull::new(&points)?;
+
+    let faces = hull
+        .polygons()
+        .into_iter()
+        .map(|polygon| {
+            let polygon =
+                polygon.into_iter().map(|i| points[i]).collect::<Vec<_>>();
+            let normal = polygon_normal(&polygon);
+
+            build_face(
+                normal,
+                &[polygon],
+                Color::default(),
+                Attributes::default(),
+                objects,
+            )
+        })
+        .collect::<Vec<_>>();
+
+    let shell = Shell::builder().with_faces(faces).build(objects);
+    Ok(Solid::builder().with_shells([shell]).build(objects))
+}
+
+/// Compute the convex hull of a number of solids
+///
+/// The hull is computed from the approximations of the solids. Curved faces
+/// are approximated from the inside, so the hull can be smaller than the
+/// solids by up to `tolerance`.
+pub fn convex_hull_of_solids<'r>(
+    solids: impl IntoIterator<Item = &'r Solid>,
+    tolerance: impl Into<Tolerance>,
+    objects: &mut Service<Objects>,
+) -> Result<Handle<Solid>, HullError> {
+    let tolerance = tolerance.into();
+
+    let mut points = Vec::new();
+    for solid in solids {
+        let mesh = (solid, tolerance).triangulate();
+        points.extend(mesh.vertices());
+    }
+
+    convex_hull(points, objects)
+}
+
+/// An error that can occur while computing a convex hull
+#[derive(Clone, Debug, Eq, PartialEq, thiserror::Error)]
+pub enum HullError {
+    /// All points are located in a common plane, so they don't enclose a
+    /// volume
+    #[error("Convex hull requires at least 4 points that aren't coplanar")]
+    Degenerate,
+}
+
+/// A triangulated convex hull, referring to points by index
+struct Hull<'r> {
+    points: &'r [Point<3>],
+    triangles: Vec<[usize; 3]>,
+    epsilon: Scalar,
+}
+
+impl<'r> Hull<'r> {
+    fn new(points: &'r [Point<3>]) -> Result<Self, HullError> {
+        let aabb = Aabb::<3>::from_points(points.iter().copied());
+        let epsilon = aabb.size().magnitude() * Scalar::from(1e-9);
+
+        let [a, b, c, d] = initial_tetrahedron(points, epsilon)
+            .ok_or(HullError::Degenerate)?;
+
+        // Orient the triangles of the tetrahedron so they face outward.
+        let (b, c) = if signed_volume(points, [a, b, c, d]) > Scalar::ZERO {
+            (c, b)
+        } else {
+            (b, c)
+        };
+
+        let mut hull = Self {
+            points,
+            triangles: vec![[a, b, c], [a, d, b], [b, d, c], [c, d, a]],
+            epsilon,
+        };
+
+        for point in 0..points.len() {
+            if ![a, b, c, d].contains(&point) {
+                hull.add_point(point);
+            }
+        }
+
+        Ok(hull)
+    }
+
+    fn add_point(&mut self, point: usize) {
+        let (visible, hidden): (Vec<_>, Vec<_>) =
+            self.triangles.iter().partition(|&&triangle| {
+                self.distance(triangle, point) > self.epsilon
+            });
+
+        if visible.is_empty() {
+            // The point is inside of the hull already.
+            return;
+        }
+
+        // The edges that border the visible region, with the direction they
+        // have in the visible triangles.
+        let visible_edges = visible
+            .iter()
+            .flat_map(|&triangle| edges(triangle))
+            .collect::<BTreeSet<_>>();
+        let horizon = visible_edges
+            .iter()
+            .filter(|[a, b]| !visible_edges.contains(&[*b, *a]));
+
+        let mut triangles = hidden;
+        triangles.extend(horizon.map(|&[a, b]| [a, b, point]));
+        self.triangles = triangles;
+    }
+
+    /// Merge coplanar triangles, returning the polygons that bound the hull
+    fn polygons(&self) -> Vec<Vec<usize>> {
+        let mut groups = UnionFind::new(self.triangles.len());
+
+        let mut triangle_by_edge = BTreeMap::new();
+        for (i, &triangle) in self.triangles.iter().enumerate() {
+            for edge in edges(triangle) {
+                triangle_by_edge.insert(edge, i);
+            }
+        }
+        for (&[a, b], &i) in &triangle_by_edge {
+            let j = triangle_by_edge[&[b, a]];
+            let opposite = self.triangles[j]
+                .into_iter()
+                .find(|&point| point != a && point != b)
+                .expect("Triangle has three distinct points");
+
+            if self.distance(self.triangles[i], opposite).abs() <= self.epsilon
+            {
+                groups.union(i, j);
+            }
+        }
+
+        let mut boundaries = BTreeMap::new();
+        for (i, &triangle) in self.triangles.iter().enumerate() {
+            let boundary = boundaries
+                .entry(groups.find(i))
+                .or_insert_with(BTreeSet::new);
+            for [a, b] in edges(triangle) {
+                // Edges shared by two triangles of the same group are inside of
+                // the polygon.
+                if !boundary.remove(&[b, a]) {
+                    boundary.insert([a, b]);
+                }
+            }
+        }
+
+        boundaries
+            .into_values()
+            .map(|boundary| {
+                let boundary = boundary
+                    .into_iter()
+                    .map(|[a, b]| (a, b))
+                    .collect::<BTreeMap<_, _>>();
+                let start = *boundary.keys().next().expect("Empty polygon");
+
+                let mut polygon = vec![start];
+                let mut current = boundary[&start];
+                while current != start {
+                    polygon.push(current);
+                    current = boundary[&current];
+                }
+
+                self.remove_collinear(polygon)
+            })
+            .collect()
+    }
+
+    /// Remove the points that are located on an edge of a polygon
+    ///
+    /// These points are shared by the two polygons next to the edge, so both
+    /// remove them, and the polygons still fit together.
+    fn remove_collinear(&self, polygon: Vec<usize>) -> Vec<usize> {
+        let n = polygon.len();
+
+        (0..n)
+            .filter(|&i| {
+                let prev = self.points[polygon[(i + n - 1) % n]];
+                let point = self.points[polygon[i]];
+                let next = self.points[polygon[(i + 1) % n]];
+
+                let direction = (next - prev).normalize();
+                (point - prev).cross(&direction).magnitude() > self.epsilon
+            })
+            .map(|i| polygon[i])
+            .collect()
+    }
+
+    /// Compute the distance of a point in front of the plane of a triangle
+    fn distance(&self, [a, b, c]: [usize; 3], point: usize) -> Scalar {
+        let [a, b, c] = [a, b, c].map(|i| self.points[i]);
+        let normal = (b - a).cross(&(c - a)).normalize();
+
+        normal.dot(&(self.points[point] - a))
+    }
+}
+
+fn initial_tetrahedron(
+    points: &[Point<3>],
+    epsilon: Scalar,
+) -> Option<[usize; 4]> {
+    let farthest = |distance: &dyn Fn(Point<3>) -> Scalar| {
+        (0..points.len())
+            .max_by_key(|&i| distance(points[i]))
+            .filter(|&i| distance(points[i]) > epsilon)
+    };
+
+    let a = 0;
+    let b = farthest(&|point| (point - points[a]).magnitude())?;
+
+    let ab = (points[b] - points[a]).normalize();
+    let c = farthest(&|point| (point - points[a]).cross(&ab).magnitude())?;
+
+    let normal = ab.cross(&(points[c] - points[a])).normalize();
+    let d = farthest(&|point| (point - points[a]).dot(&normal).abs())?;
+
+    Some([a, b, c, d])
+}
+
+fn signed_volume(points: &[Point<3>], [a, b, c, d]: [usize; 4]) -> Scalar {
+    let [a, b, c, d] = [a, b, c, d].map(|i| points[i]);
+    let [ab, ac, ad]: [Vector<3>; 3] = [b - a, c - a, d - a];
+
+    ab.cross(&ac).dot(&ad)
+}
+
+fn edges([a, b, c]: [usize; 3]) -> [[usize; 2]; 3] {
+    [[a, b], [b, c], [c, a]]
+}
+
+struct UnionFind {
+    parents: Vec<usize>,
+}
+
+impl UnionFind {
+    fn new(len: usize) -> Self {
+        Self {
+            parents: (0..len).collect(),
+        }
+    }
+
+    fn find(&mut self, mut i: usize) -> usize {
+        while self.parents[i] != i {
+            self.parents[i] = self.parents[self.parents[i]];
+            i = self.parents[i];
+        }
+        i
+    }
+
+    fn union(&mut self, a: usize, b: usize) {
+        let a = self.find(a);
+        let b = self.find(b);
+        self.parents[a] = b;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use fj_math::{Point, Scalar};
+
+    use crate::{
+        algorithms::{
+            approx::Tolerance, sweep::Sweep, triangulate::Triangulate,
+        },
+        builder::FaceBuilder,
+        insert::Insert,
+        iter::ObjectIters,
+        objects::{Face, Sketch},
+        partial::HasPartial,
+        services::Services,
+    };
+
+    use super::{convex_hull, convex_hull_of_solids, HullError};
+
+    #[test]
+    fn hull_of_cube_with_interior_points() -> anyhow::Result<()> {
+        let mut services = Services::new();
+
+        let mut points = Vec::new();
+        for x in [0., 0.5, 1.] {
+            for y in [0., 0.5, 1.] {
+                for z in [0., 0.5, 1.] {
+                    points.push([x, y, z]);
+                }
+            }
+        }
+
+        let hull = convex_hull(points, &mut services.objects)?;
+
+        assert_eq!(hull.face_iter().count(), 6);
+        for face in hull.face_iter() {
+            assert_eq!(face.exterior().half_edges().count(), 4);
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn hull_contains_all_points() -> anyhow::Result<()> {
+        let mut services = Services::new();
+
+        // Points on a sphere, spread out using the golden angle.
+        let num_points = 50;
+        let points = (0..num_points)
+            .map(|i| {
+                let z = 1. - 2. * (i as f64 + 0.5) / num_points as f64;
+                let radius = (1. - z * z).sqrt();
+                let angle = i as f64 * 2.399_963_229_728_653;
+
+                [radius * angle.cos(), radius * angle.sin(), z]
+            })
+            .collect::<Vec<_>>();
+
+        let hull = convex_hull(points.clone(), &mut services.objects)?;
+
+        // Every point is either a corner of the hull, or inside of it. Since
+        // all points are on a sphere, they all need to be corners.
+        let corners = hull
+            .global_vertex_iter()
+            .map(|vertex| vertex.position())
+            .collect::<Vec<_>>();
+        for point in points {
+            let point = Point::from(point);
+            assert!(corners.contains(&point));
+        }
+
+        // The triangles of the hull must face outward.
+        let mesh = (&*hull, Tolerance::from_scalar(0.01)?).triangulate();
+        for triangle in mesh.triangles() {
+            let triangle = triangle.inner;
+            let [a, ..] = triangle.points();
+
+            assert!(triangle.normal().dot(&a.coords) > Scalar::ZERO);
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn hull_of_solids() -> anyhow::Result<()> {
+        let mut services = Services::new();
+
+        let surface = services.objects.surfaces.xy_plane();
+        let mut solids = Vec::new();
+        for x in [0., 3.] {
+            let face = Face::partial()
+                .with_exterior_polygon_from_points(
+                    surface.clone(),
+                    [[x, 0.], [x + 1., 0.], [x + 1., 1.], [x, 1.]],
+                )
+                .build(&mut services.objects)
+                .insert(&mut services.objects);
+            let solid = Sketch::builder()
+                .with_faces([face])
+                .build(&mut services.objects)
+                .sweep([0., 0., 1.], &mut services.objects);
+
+            solids.push(solid);
+        }
+
+        let hull = convex_hull_of_solids(
+            solids.iter().map(|solid| &**solid),
+            Tolerance::from_scalar(0.01)?,
+            &mut services.objects,
+        )?;
+
+        assert_eq!(hull.face_iter().count(), 6);
+
+        Ok(())
+    }
+
+    #[test]
+    fn hull_of_coplanar_points() {
+        let mut services = Services::new();
+
+        let points = [[0., 0., 0.], [1., 0., 0.], [1., 1., 0.], [0., 1., 0.]];
+        let result = convex_hull(points, &mut services.objects);
+
+        assert_eq!(result.err(), Some(HullError::Degenerate));
+    }
+}
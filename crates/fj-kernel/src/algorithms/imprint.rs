@@ -0,0 +1,541 @@
+//! Imprinting edges onto faces
+//!
+//! Imprinting splits a face along a curve that crosses it, or along the
+//! intersection with another face. The geometry doesn't change; the face is
+//! replaced by faces that together cover the same region. This makes it
+//! possible to refer to the regions separately, and is a step towards boolean
+//! operations.
+
+use fj_interop::ext::ArrayExt;
+use fj_math::{Line, Point, Segment};
+
+use crate::{
+    algorithms::{
+        intersect::{
+            CurveFaceIntersection, LineSegmentIntersection,
+            SurfaceSurfaceIntersection,
+        },
+        point_in_face::{
+            locate_in_polygon, winding_number, LocatePoint, PointLocation,
+        },
+    },
+    builder::CycleBuilder,
+    geometry::path::SurfacePath,
+    insert::Insert,
+    objects::{Curve, Cycle, Face, Objects, Surface},
+    partial::{HasPartial, PartialGlobalVertex, PartialSurfaceVertex},
+    services::Service,
+    storage::Handle,
+    tolerance::ModelTolerance,
+};
+
+/// Imprint a curve onto a face
+///
+/// Splits the face wherever the curve crosses it. The curve must be defined on
+/// the surface of the face. Returns the faces that replace the original one,
+/// which is returned unchanged, if the curve doesn't cross it.
+///
+/// The neighbors of the face are not updated. Where the curve meets the
+/// boundary of the face, the adjacent faces don't get the new vertices.
+pub fn imprint_curve(
+    face: &Handle<Face>,
+    curve: &Curve,
+    objects: &mut Service<Objects>,
+) -> Result<Vec<Handle<Face>>, ImprintError> {
+    if curve.surface().id() != face.surface().id() {
+        return Err(ImprintError::DifferentSurface);
+    }
+
+    let line = match curve.path() {
+        SurfacePath::Line(line) => line,
+        _ => return Err(ImprintError::CurvedEdge),
+    };
+
+    let chords = chords(face, &line)?;
+    split(face, &line, chords, objects)
+}
+
+/// Imprint the intersection with another face onto a face
+///
+/// Splits the face along the intersection. Returns the faces that replace the
+/// original one, which is returned unchanged, if the faces don't intersect.
+///
+/// The other face must cross the face completely, as the split would
+/// otherwise end within the face. See [`imprint_curve`] for further
+/// limitations.
+pub fn imprint_face(
+    face: &Handle<Face>,
+    other: &Face,
+    objects: &mut Service<Objects>,
+) -> Result<Vec<Handle<Face>>, ImprintError> {
+    let surfaces = [face.surface().clone(), other.surface().clone()];
+    let [curve, other_curve] =
+        match SurfaceSurfaceIntersection::compute(surfaces, objects) {
+            Some(intersection) => intersection.intersection_curves,
+            None => return Ok(vec![face.clone()]),
+        };
+
+    let line = match curve.path() {
+        SurfacePath::Line(line) => line,
+        _ => return Err(ImprintError::CurvedEdge),
+    };
+    if !has_straight_edges(other) {
+        return Err(ImprintError::CurvedEdge);
+    }
+
+    // Both curves represent the same global curve, so their curve coordinates
+    // are the same.
+    let covered = CurveFaceIntersection::compute(&other_curve, other);
+
+    let mut imprinted = Vec::new();
+    for chord in chords(face, &line)? {
+        let [start, end] = chord;
+        let overlaps = covered.intervals.iter().filter(|interval| {
+            interval.start.t < end.t && start.t < interval.end.t
+        });
+
+        for interval in overlaps {
+            if interval.start.t > start.t || interval.end.t < end.t {
+                return Err(ImprintError::PartialImprint);
+            }
+
+            imprinted.push(chord);
+        }
+    }
+
+    split(face, &line, imprinted, objects)
+}
+
+/// Compute the parts of the line that cross the face, in line coordinates
+fn chords(
+    face: &Face,
+    line: &Line<2>,
+) -> Result<Vec<[Point<1>; 2]>, ImprintError> {
+    if !has_straight_edges(face) {
+        return Err(ImprintError::CurvedEdge);
+    }
+
+    let tolerance = ModelTolerance::default();
+
+    let mut crossings = Vec::new();
+    for segment in face.all_cycles().flat_map(cycle_segments) {
+        match LineSegmentIntersection::compute(line, &segment) {
+            Some(LineSegmentIntersection::Point { point_on_line }) => {
+                crossings.push(point_on_line);
+            }
+            Some(LineSegmentIntersection::Coincident { points_on_line }) => {
+                crossings.extend(points_on_line);
+            }
+            None => {}
+        }
+    }
+
+    crossings.sort();
+    crossings.dedup_by(|a, b| {
+        tolerance.coincide(
+            line.point_from_line_coords(*a),
+            line.point_from_line_coords(*b),
+        )
+    });
+
+    let interiors = face.interiors().map(cycle_segments).collect::<Vec<_>>();
+
+    let mut chords = Vec::new();
+    for pair in crossings.windows(2) {
+        let chord = [pair[0], pair[1]];
+
+        // Between two consecutive crossings, the line is either completely
+        // inside of the face, completely outside, or runs along its boundary.
+        let middle = line.point_from_line_coords(Point {
+            coords: (chord[0].coords + chord[1].coords) / 2.,
+        });
+        if face.locate_point(middle, tolerance) != PointLocation::Inside {
+            continue;
+        }
+
+        for point in chord {
+            let point = line.point_from_line_coords(point);
+            let touches_interior = interiors.iter().any(|interior| {
+                locate_in_polygon([interior.clone()], point, tolerance.inner())
+                    == PointLocation::OnBoundary
+            });
+            if touches_interior {
+                return Err(ImprintError::TouchesInterior);
+            }
+        }
+
+        chords.push(chord);
+    }
+
+    Ok(chords)
+}
+
+/// Split the face along the provided chords of its exterior
+///
+/// The chords are given in coordinates on the line.
+fn split(
+    face: &Handle<Face>,
+    line: &Line<2>,
+    chords: Vec<[Point<1>; 2]>,
+    objects: &mut Service<Objects>,
+) -> Result<Vec<Handle<Face>>, ImprintError> {
+    if chords.is_empty() {
+        return Ok(vec![face.clone()]);
+    }
+
+    let tolerance = ModelTolerance::default();
+    let surface = face.surface();
+    let geometry = surface.geometry();
+
+    let mut regions = vec![face
+        .exterior()
+        .half_edges()
+        .map(|half_edge| {
+            let vertex = half_edge.back();
+            Corner {
+                position: vertex.surface_form().position(),
+                global: vertex.global_form().position(),
+            }
+        })
+        .collect::<Vec<_>>()];
+
+    for chord in chords {
+        let [start, end] = chord.map(|point| {
+            let position = line.point_from_line_coords(point);
+            Corner {
+                position,
+                global: geometry.point_from_surface_coords(position),
+            }
+        });
+        let middle = Point {
+            coords: (start.position.coords + end.position.coords) / 2.,
+        };
+
+        let i = regions
+            .iter()
+            .position(|region| {
+                locate_in_polygon(
+                    [region_segments(region)],
+                    middle,
+                    tolerance.inner(),
+                ) == PointLocation::Inside
+            })
+            .expect("Chord of face must be within one of its regions");
+
+        let mut region = regions.swap_remove(i);
+        let s = insert_corner(&mut region, start, tolerance);
+        let e = insert_corner(&mut region, end, tolerance);
+
+        let (a, b) = split_region(&region, s, e);
+        regions.push(a);
+        regions.push(b);
+    }
+
+    let faces = regions
+        .into_iter()
+        .map(|region| {
+            let segments = region_segments(&region);
+            let interiors = face
+                .interiors()
+                .filter(|interior| {
+                    let point = interior
+                        .half_edges()
+                        .next()
+                        .expect("Cycle must have half-edges")
+                        .back()
+                        .surface_form()
+                        .position();
+                    winding_number(segments.iter().copied(), point) != 0
+                })
+                .cloned()
+                .collect::<Vec<_>>();
+
+            let exterior = build_cycle(&region, surface, objects);
+            Face::new(exterior, interiors, face.color())
+                .with_attributes(face.attributes().clone())
+                .insert(objects)
+        })
+        .collect();
+
+    Ok(faces)
+}
+
+/// A corner of a region of the face
+#[derive(Clone, Copy)]
+struct Corner {
+    position: Point<2>,
+    global: Point<3>,
+}
+
+fn insert_corner(
+    region: &mut Vec<Corner>,
+    corner: Corner,
+    tolerance: ModelTolerance,
+) -> usize {
+    if let Some(i) = region
+        .iter()
+        .position(|c| tolerance.coincide(c.position, corner.position))
+    {
+        return i;
+    }
+
+    let i = (0..region.len())
+        .find(|&i| {
+            let segment = Segment::from_points([
+                region[i].position,
+                region[(i + 1) % region.len()].position,
+            ]);
+            locate_in_polygon(
+                [vec![segment]],
+                corner.position,
+                tolerance.inner(),
+            ) == PointLocation::OnBoundary
+        })
+        .expect("End of chord must be on the boundary of the region");
+
+    region.insert(i + 1, corner);
+    i + 1
+}
+
+fn split_region(
+    region: &[Corner],
+    start: usize,
+    end: usize,
+) -> (Vec<Corner>, Vec<Corner>) {
+    let walk = |from: usize, to: usize| {
+        let mut corners = Vec::new();
+        let mut i = from;
+        loop {
+            corners.push(region[i]);
+            if i == to {
+                break;
+            }
+            i = (i + 1) % region.len();
+        }
+        corners
+    };
+
+    (walk(start, end), walk(end, start))
+}
+
+fn build_cycle(
+    region: &[Corner],
+    surface: &Handle<Surface>,
+    objects: &mut Service<Objects>,
+) -> Handle<Cycle> {
+    let vertices = region.iter().map(|corner| PartialSurfaceVertex {
+        position: Some(corner.position),
+        surface: Some(surface.clone()),
+        global_form: PartialGlobalVertex {
+            position: Some(corner.global),
+        }
+        .into(),
+    });
+
+    Cycle::partial()
+        .with_poly_chain(vertices)
+        .close_with_line_segment()
+        .build(objects)
+        .insert(objects)
+}
+
+fn region_segments(region: &[Corner]) -> Vec<Segment<2>> {
+    (0..region.len())
+        .map(|i| {
+            Segment::from_points([
+                region[i].position,
+                region[(i + 1) % region.len()].position,
+            ])
+        })
+        .collect()
+}
+
+fn cycle_segments(cycle: &Handle<Cycle>) -> Vec<Segment<2>> {
+    cycle
+        .half_edges()
+        .map(|half_edge| {
+            Segment::from_points(
+                half_edge
+                    .vertices()
+                    .each_ref_ext()
+                    .map(|vertex| vertex.surface_form().position()),
+            )
+        })
+        .collect()
+}
+
+fn has_straight_edges(face: &Face) -> bool {
+    face.all_cycles()
+        .flat_map(|cycle| cycle.half_edges())
+        .all(|half_edge| {
+            matches!(half_edge.curve().path(), SurfacePath::Line(_))
+        })
+}
+
+/// An error imprinting onto a face
+#[derive(Debug, thiserror::Error)]
+pub enum ImprintError {
+    /// The curve is not defined on the surface of the face
+    #[error("Imprinted curve must be defined on the surface of the face")]
+    DifferentSurface,
+
+    /// A curve or edge is not straight
+    #[error("Imprinting only supports lines and faces with straight edges")]
+    CurvedEdge,
+
+    /// The imprint touches one of the holes of the face
+    #[error("Imprinting edges that end on a hole is not supported")]
+    TouchesInterior,
+
+    /// The imprint would end within the face
+    #[error("Imprinted face must cross the face completely")]
+    PartialImprint,
+}
+
+#[cfg(test)]
+mod tests {
+    use fj_math::{Point, Scalar};
+
+    use crate::{
+        builder::{CurveBuilder, FaceBuilder},
+        insert::Insert,
+        objects::{Curve, Face, Objects, Surface},
+        partial::{HasPartial, PartialCurve},
+        services::{Service, Services},
+        storage::Handle,
+    };
+
+    use super::ImprintError;
+
+    fn line(
+        surface: Handle<Surface>,
+        points: [[f64; 2]; 2],
+        objects: &mut Service<Objects>,
+    ) -> Handle<Curve> {
+        let mut curve = PartialCurve {
+            surface: Some(surface),
+            ..Default::default()
+        };
+        curve.update_as_line_from_points(points);
+        curve.build(objects).insert(objects)
+    }
+
+    fn exterior_points(face: &Face) -> Vec<Point<2>> {
+        face.exterior()
+            .half_edges()
+            .map(|half_edge| half_edge.back().surface_form().position())
+            .collect()
+    }
+
+    #[test]
+    fn imprint_curve() -> anyhow::Result<()> {
+        let mut services = Services::new();
+
+        let surface = services.objects.surfaces.xy_plane();
+        let face = Face::partial()
+            .with_exterior_polygon_from_points(
+                surface.clone(),
+                [[0., 0.], [2., 0.], [2., 2.], [0., 2.]],
+            )
+            .build(&mut services.objects)
+            .insert(&mut services.objects);
+
+        let curve = line(
+            surface.clone(),
+            [[1., -1.], [1., 3.]],
+            &mut services.objects,
+        );
+        let faces = super::imprint_curve(&face, &curve, &mut services.objects)?;
+
+        assert_eq!(faces.len(), 2);
+        for face in &faces {
+            let points = exterior_points(face);
+            assert_eq!(points.len(), 4);
+            assert!(points.contains(&Point::from([1., 0.])));
+            assert!(points.contains(&Point::from([1., 2.])));
+        }
+
+        // A curve that misses the face leaves it unchanged.
+        let curve = line(surface, [[3., -1.], [3., 3.]], &mut services.objects);
+        let faces = super::imprint_curve(&face, &curve, &mut services.objects)?;
+        assert_eq!(faces, vec![face]);
+
+        Ok(())
+    }
+
+    #[test]
+    fn imprint_curve_onto_face_with_hole() -> anyhow::Result<()> {
+        let mut services = Services::new();
+
+        let surface = services.objects.surfaces.xy_plane();
+        let face = Face::partial()
+            .with_exterior_polygon_from_points(
+                surface.clone(),
+                [[0., 0.], [4., 0.], [4., 4.], [0., 4.]],
+            )
+            .with_interior_polygon_from_points(
+                surface.clone(),
+                [[1., 1.], [1., 2.], [2., 2.], [2., 1.]],
+            )
+            .build(&mut services.objects)
+            .insert(&mut services.objects);
+
+        let curve =
+            line(surface.clone(), [[3., 0.], [3., 4.]], &mut services.objects);
+        let faces = super::imprint_curve(&face, &curve, &mut services.objects)?;
+
+        let num_interiors = faces
+            .iter()
+            .map(|face| {
+                let is_left = exterior_points(face)
+                    .iter()
+                    .any(|point| point.u < Scalar::from(3.));
+                (is_left, face.interiors().count())
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(faces.len(), 2);
+        assert!(num_interiors.contains(&(true, 1)));
+        assert!(num_interiors.contains(&(false, 0)));
+
+        let curve = line(surface, [[0., 0.], [4., 4.]], &mut services.objects);
+        let result = super::imprint_curve(&face, &curve, &mut services.objects);
+        assert!(matches!(result, Err(ImprintError::TouchesInterior)));
+
+        Ok(())
+    }
+
+    #[test]
+    fn imprint_face() -> anyhow::Result<()> {
+        let mut services = Services::new();
+
+        let face = Face::partial()
+            .with_exterior_polygon_from_points(
+                services.objects.surfaces.xy_plane(),
+                [[-1., -1.], [1., -1.], [1., 1.], [-1., 1.]],
+            )
+            .build(&mut services.objects)
+            .insert(&mut services.objects);
+
+        let crossing = Face::partial()
+            .with_exterior_polygon_from_points(
+                services.objects.surfaces.xz_plane(),
+                [[-2., -1.], [2., -1.], [2., 1.], [-2., 1.]],
+            )
+            .build(&mut services.objects);
+        let faces =
+            super::imprint_face(&face, &crossing, &mut services.objects)?;
+        assert_eq!(faces.len(), 2);
+
+        let partial = Face::partial()
+            .with_exterior_polygon_from_points(
+                services.objects.surfaces.xz_plane(),
+                [[0., -1.], [2., -1.], [2., 1.], [0., 1.]],
+            )
+            .build(&mut services.objects);
+        let result =
+            super::imprint_face(&face, &partial, &mut services.objects);
+        assert!(matches!(result, Err(ImprintError::PartialImprint)));
+
+        Ok(())
+    }
+}
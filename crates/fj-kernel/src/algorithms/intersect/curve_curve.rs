@@ -0,0 +1,707 @@
+use fj_math::{Point, Scalar};
+
+use crate::{
+    algorithms::approx::{path::RangeOnPath, Approx, Tolerance},
+    geometry::path::SurfacePath,
+    tolerance::ValidationTolerance,
+};
+
+/// An intersection between two [`SurfacePath`]s
+///
+/// Algorithms that build on intersections, like booleans, need to treat these
+/// cases very differently. Telling them apart after the fact, from a point
+/// alone, is unreliable. That's why the intersection is classified right here,
+/// where the paths are at hand.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Ord, PartialOrd)]
+pub enum CurveCurveIntersection {
+    /// The paths cross each other at a single point
+    Crossing {
+        /// The intersection point, in path coordinates on each of the paths
+        points_on_paths: [Point<1>; 2],
+    },
+
+    /// The paths touch at a single point, without crossing each other
+    Tangency {
+        /// The point of contact, in path coordinates on each of the paths
+        points_on_paths: [Point<1>; 2],
+    },
+
+    /// The paths coincide along a section
+    Overlap {
+        /// The section, as a range on each of the paths
+        ///
+        /// The boundaries correspond to each other: The first boundary of one
+        /// range is at the same position as the first boundary of the other.
+        ranges_on_paths: [RangeOnPath; 2],
+    },
+
+    /// The paths come within the tolerance of each other, without touching
+    ///
+    /// Whether they intersect can't be decided from the available precision.
+    /// The caller needs to decide how to treat this case.
+    Ambiguous {
+        /// The points of closest approach, in path coordinates on each path
+        points_on_paths: [Point<1>; 2],
+
+        /// The distance between the paths at those points
+        distance: Scalar,
+    },
+}
+
+impl CurveCurveIntersection {
+    /// Compute the intersections between two paths
+    ///
+    /// Only the parts of the paths within the given ranges are considered. The
+    /// paths are approximated, and candidates for intersections are found
+    /// between those approximations. The candidates are then refined and
+    /// classified on the actual paths.
+    ///
+    /// Paths closer to each other than the [`ValidationTolerance`] are
+    /// considered to touch. Paths that come closer than `tolerance`, without
+    /// touching, result in an [`CurveCurveIntersection::Ambiguous`]
+    /// intersection.
+    ///
+    /// # Implementation Note
+    ///
+    /// Overlaps are detected between the boundaries of the ranges only. If a
+    /// periodic path, like a circle, overlaps another path across the point
+    /// where its path coordinates wrap around, the overlap might be missed.
+    pub fn compute(
+        paths: [(&SurfacePath, RangeOnPath); 2],
+        tolerance: impl Into<Tolerance>,
+    ) -> Vec<Self> {
+        let tolerance = tolerance.into();
+
+        let [a, b] = paths.map(|(path, range)| {
+            let [start, end] = range.boundary;
+
+            let mut points = vec![start.t];
+            points.extend(
+                (path.clone(), range)
+                    .approx(tolerance)
+                    .into_iter()
+                    .map(|(point_curve, _)| point_curve.t),
+            );
+            points.push(end.t);
+
+            Approximation {
+                path,
+                range,
+                points,
+            }
+        });
+
+        let overlaps = find_overlaps([&a, &b]);
+        let mut intersections = overlaps
+            .iter()
+            .map(|&ranges_on_paths| Self::Overlap { ranges_on_paths })
+            .collect::<Vec<_>>();
+
+        for i in 0..a.points.len() - 1 {
+            for j in 0..b.points.len() - 1 {
+                let Some(intersection) =
+                    intersect_segments([&a, &b], [i, j], tolerance)
+                else {
+                    continue;
+                };
+                let [point_on_a, _] = intersection
+                    .points_on_paths()
+                    .expect("Segments intersect at points, not in overlaps");
+
+                let is_in_overlap = overlaps.iter().any(|[range, _]| {
+                    is_in_range(point_on_a.t, range, Scalar::from(1e-9))
+                });
+                if is_in_overlap {
+                    continue;
+                }
+
+                // Intersections at the boundary between two segments, or close
+                // to multiple segments, are found more than once.
+                let point = a.path.point_from_path_coords(point_on_a);
+                let is_duplicate = intersections.iter().any(|intersection| {
+                    let Some([other, _]) = intersection.points_on_paths()
+                    else {
+                        return false;
+                    };
+                    let other = a.path.point_from_path_coords(other);
+                    point.distance_to(&other) < tolerance.inner()
+                });
+                if is_duplicate {
+                    continue;
+                }
+
+                intersections.push(intersection);
+            }
+        }
+
+        intersections
+    }
+
+    fn points_on_paths(&self) -> Option<[Point<1>; 2]> {
+        match *self {
+            Self::Crossing { points_on_paths }
+            | Self::Tangency { points_on_paths }
+            | Self::Ambiguous {
+                points_on_paths, ..
+            } => Some(points_on_paths),
+            Self::Overlap { .. } => None,
+        }
+    }
+}
+
+/// A path, together with the path coordinates of its approximation
+struct Approximation<'r> {
+    path: &'r SurfacePath,
+    range: RangeOnPath,
+    points: Vec<Scalar>,
+}
+
+impl Approximation<'_> {
+    fn point(&self, t: Scalar) -> Point<2> {
+        self.path.point_from_path_coords([t])
+    }
+
+    fn contains(&self, t: Scalar) -> bool {
+        is_in_range(t, &self.range, Scalar::from(1e-9))
+    }
+
+    /// Find the point on the path that is closest to the given one
+    ///
+    /// Uses the closest point of the approximation as a starting point.
+    /// Returns the path coordinate of the closest point, and the distance.
+    fn project(&self, point: Point<2>) -> (Scalar, Scalar) {
+        let initial = self
+            .points
+            .iter()
+            .copied()
+            .min_by_key(|&t| self.point(t).distance_to(&point))
+            .expect("Approximation has at least two points");
+
+        self.project_from(point, initial)
+    }
+
+    /// Find the point on the path that is closest to the given one
+    ///
+    /// Starts the search at the given path coordinate.
+    fn project_from(
+        &self,
+        point: Point<2>,
+        initial: Scalar,
+    ) -> (Scalar, Scalar) {
+        let mut t = initial;
+
+        for _ in 0..MAX_ITERATIONS {
+            let derivative = self.path.derivative_at([t]);
+            let length_squared = derivative.dot(&derivative);
+            if length_squared == Scalar::ZERO {
+                break;
+            }
+
+            let step =
+                (point - self.point(t)).dot(&derivative) / length_squared;
+            t += step;
+
+            if step.abs() < Scalar::from(EPSILON) {
+                break;
+            }
+        }
+
+        (t, self.point(t).distance_to(&point))
+    }
+}
+
+/// Find the sections in which the paths coincide
+///
+/// Such a section starts and ends at a boundary of one of the ranges. So all
+/// boundaries that are on the other path are candidates, and the paths are
+/// sampled between them.
+fn find_overlaps(paths: [&Approximation; 2]) -> Vec<[RangeOnPath; 2]> {
+    const SAMPLES: usize = 8;
+
+    let touches = ValidationTolerance::default().inner();
+    let [a, b] = paths;
+
+    let mut contacts = Vec::new();
+    for t_a in a.range.boundary.map(|point| point.t) {
+        let (t_b, distance) = b.project(a.point(t_a));
+        if distance <= touches && b.contains(t_b) {
+            contacts.push([t_a, t_b]);
+        }
+    }
+    for t_b in b.range.boundary.map(|point| point.t) {
+        let (t_a, distance) = a.project(b.point(t_b));
+        if distance <= touches && a.contains(t_a) {
+            contacts.push([t_a, t_b]);
+        }
+    }
+
+    // Order the contacts along the direction of the first path.
+    let [start, end] = a.range.boundary;
+    let direction = if start <= end {
+        Scalar::ONE
+    } else {
+        -Scalar::ONE
+    };
+    contacts.sort_by_key(|[t_a, _]| *t_a * direction);
+    contacts.dedup_by(|[t_a, _], [other, _]| {
+        (*t_a - *other).abs() < Scalar::from(EPSILON)
+    });
+
+    let mut overlaps = Vec::new();
+    for pair in contacts.windows(2) {
+        let [[a_0, b_0], [a_1, b_1]] = [pair[0], pair[1]];
+
+        let coincide = (1..SAMPLES).all(|i| {
+            let f = Scalar::from(i as f64 / SAMPLES as f64);
+            let point = a.point(a_0 + (a_1 - a_0) * f);
+            let (_, distance) = b.project_from(point, b_0 + (b_1 - b_0) * f);
+            distance <= touches
+        });
+
+        if coincide {
+            overlaps.push([
+                RangeOnPath::from([[a_0], [a_1]]),
+                RangeOnPath::from([[b_0], [b_1]]),
+            ]);
+        }
+    }
+
+    overlaps
+}
+
+/// Find an intersection between two segments of the approximated paths
+///
+/// Segments that cross are refined into a crossing on the actual paths.
+/// Segments that come close to each other are searched for the point where the
+/// actual paths come closest.
+fn intersect_segments(
+    paths: [&Approximation; 2],
+    segments: [usize; 2],
+    tolerance: Tolerance,
+) -> Option<CurveCurveIntersection> {
+    let [a, b] = paths;
+    let [i, j] = segments;
+
+    let [[a_0, a_1], [b_0, b_1]] =
+        [(a, i), (b, j)].map(|(path, i)| [path.points[i], path.points[i + 1]]);
+    let [[p_0, p_1], [q_0, q_1]] =
+        [[a.point(a_0), a.point(a_1)], [b.point(b_0), b.point(b_1)]];
+
+    let (f_a, f_b, distance) = closest_points([p_0, p_1], [q_0, q_1]);
+
+    // Each approximation deviates from its path by up to the tolerance. The
+    // paths might come closer than the approximations do.
+    if distance > tolerance.inner() * 3. {
+        return None;
+    }
+
+    let initial = [a_0 + (a_1 - a_0) * f_a, b_0 + (b_1 - b_0) * f_b];
+
+    if distance == Scalar::ZERO {
+        if let Some([t_a, t_b]) = refine_crossing([a.path, b.path], initial) {
+            if a.contains(t_a) && b.contains(t_b) {
+                return classify([a, b], [t_a, t_b], tolerance);
+            }
+        }
+    }
+
+    // The approximations don't cross, or the crossing can't be refined, which
+    // happens if the paths are close to parallel. Either way, look for the
+    // point where the paths come closest.
+    let t_a = closest_approach(b, [a_0, a_1], a)?;
+    let (t_b, _) = b.project_from(a.point(t_a), initial[1]);
+
+    if !a.contains(t_a) || !b.contains(t_b) {
+        return None;
+    }
+
+    classify([a, b], [t_a, t_b], tolerance)
+}
+
+/// Classify a candidate for an intersection on the actual paths
+fn classify(
+    paths: [&Approximation; 2],
+    points: [Scalar; 2],
+    tolerance: Tolerance,
+) -> Option<CurveCurveIntersection> {
+    let [a, b] = paths;
+    let [t_a, t_b] = points;
+
+    let points_on_paths = [t_a, t_b].map(|t| Point::from([t]));
+    let distance = a.point(t_a).distance_to(&b.point(t_b));
+
+    if distance > ValidationTolerance::default().inner() {
+        if distance > tolerance.inner() {
+            return None;
+        }
+
+        return Some(CurveCurveIntersection::Ambiguous {
+            points_on_paths,
+            distance,
+        });
+    }
+
+    // The angle between the paths at the point of contact isn't a reliable
+    // criterion, as it can't be determined precisely close to a tangency.
+    // Instead, check whether the first path ends up on different sides of the
+    // second one, when stepping away from the point of contact.
+    let step = tolerance.inner() / a.path.derivative_at([t_a]).magnitude();
+    let [before, after] = [t_a - step, t_a + step].map(|t| {
+        let point = a.point(t);
+        let (t_b, _) = b.project_from(point, t_b);
+        b.path.derivative_at([t_b]).cross2d(&(point - b.point(t_b)))
+    });
+    if before.sign() == after.sign() {
+        return Some(CurveCurveIntersection::Tangency { points_on_paths });
+    }
+
+    Some(CurveCurveIntersection::Crossing { points_on_paths })
+}
+
+/// Compute the closest points between two line segments
+///
+/// Returns the positions of the closest points as fractions along each
+/// segment, and the distance between them.
+fn closest_points(
+    a: [Point<2>; 2],
+    b: [Point<2>; 2],
+) -> (Scalar, Scalar, Scalar) {
+    let [p_0, p_1] = a;
+    let [q_0, q_1] = b;
+
+    let r = p_1 - p_0;
+    let s = q_1 - q_0;
+
+    let denominator = r.cross2d(&s);
+    if denominator != Scalar::ZERO {
+        let offset = q_0 - p_0;
+        let f_a = offset.cross2d(&s) / denominator;
+        let f_b = offset.cross2d(&r) / denominator;
+
+        let range = Scalar::ZERO..=Scalar::ONE;
+        if range.contains(&f_a) && range.contains(&f_b) {
+            return (f_a, f_b, Scalar::ZERO);
+        }
+    }
+
+    // The segments don't cross, so the closest points include an end point of
+    // one of them.
+    let candidates = [
+        (Scalar::ZERO, project_on_segment(p_0, [q_0, q_1])),
+        (Scalar::ONE, project_on_segment(p_1, [q_0, q_1])),
+    ]
+    .into_iter()
+    .chain(
+        [
+            (Scalar::ZERO, project_on_segment(q_0, [p_0, p_1])),
+            (Scalar::ONE, project_on_segment(q_1, [p_0, p_1])),
+        ]
+        .map(|(f_b, f_a)| (f_a, f_b)),
+    );
+
+    candidates
+        .map(|(f_a, f_b)| {
+            let distance = (p_0 + r * f_a).distance_to(&(q_0 + s * f_b));
+            (f_a, f_b, distance)
+        })
+        .min_by_key(|&(_, _, distance)| distance)
+        .expect("There are always candidates")
+}
+
+/// Compute the position of the point on the segment closest to the given one
+///
+/// Returns the position as a fraction along the segment.
+fn project_on_segment(point: Point<2>, segment: [Point<2>; 2]) -> Scalar {
+    let [start, end] = segment;
+    let direction = end - start;
+
+    let length_squared = direction.dot(&direction);
+    if length_squared == Scalar::ZERO {
+        return Scalar::ZERO;
+    }
+
+    ((point - start).dot(&direction) / length_squared)
+        .max(Scalar::ZERO)
+        .min(Scalar::ONE)
+}
+
+/// Refine a crossing on the actual paths, using Newton's method
+///
+/// Returns `None`, if the method doesn't converge.
+fn refine_crossing(
+    paths: [&SurfacePath; 2],
+    initial: [Scalar; 2],
+) -> Option<[Scalar; 2]> {
+    let [a, b] = paths;
+    let [mut t_a, mut t_b] = initial;
+
+    for _ in 0..MAX_ITERATIONS {
+        let distance =
+            a.point_from_path_coords([t_a]) - b.point_from_path_coords([t_b]);
+        if distance.magnitude() < Scalar::from(EPSILON) {
+            return Some([t_a, t_b]);
+        }
+
+        // Solve `distance + d_a * step_a - d_b * step_b = 0` for the steps.
+        let d_a = a.derivative_at([t_a]);
+        let d_b = -b.derivative_at([t_b]);
+
+        let determinant = d_a.cross2d(&d_b);
+        if determinant == Scalar::ZERO {
+            return None;
+        }
+
+        let step_a = -distance.cross2d(&d_b) / determinant;
+        let step_b = -d_a.cross2d(&distance) / determinant;
+
+        t_a += step_a;
+        t_b += step_b;
+    }
+
+    None
+}
+
+/// Find the point on a section of one path, that is closest to another path
+///
+/// Uses a golden-section search, which doesn't require the distance to be
+/// differentiable. That matters, because the distance between paths that touch
+/// has a kink where they do.
+///
+/// Returns `None`, if the closest point is at the boundary of the section,
+/// unless that is also the boundary of the path's range. In that case, the
+/// closest point is in a neighboring section.
+fn closest_approach(
+    other: &Approximation,
+    section: [Scalar; 2],
+    path: &Approximation,
+) -> Option<Scalar> {
+    const GOLDEN_RATIO: f64 = 0.618_033_988_749_895;
+
+    let distance = |t: Scalar| other.project(path.point(t)).1;
+
+    let [start, end] = section;
+    let [mut low, mut high] = [start, end];
+
+    let mut x_1 = high - (high - low) * GOLDEN_RATIO;
+    let mut x_2 = low + (high - low) * GOLDEN_RATIO;
+    let mut d_1 = distance(x_1);
+    let mut d_2 = distance(x_2);
+
+    for _ in 0..MAX_ITERATIONS * 3 {
+        if d_1 < d_2 {
+            high = x_2;
+            x_2 = x_1;
+            d_2 = d_1;
+            x_1 = high - (high - low) * GOLDEN_RATIO;
+            d_1 = distance(x_1);
+        } else {
+            low = x_1;
+            x_1 = x_2;
+            d_1 = d_2;
+            x_2 = low + (high - low) * GOLDEN_RATIO;
+            d_2 = distance(x_2);
+        }
+    }
+
+    let t = (low + high) / 2.;
+
+    let slack = (end - start).abs() * 1e-6;
+    let is_at_boundary = [start, end].into_iter().any(|boundary| {
+        (t - boundary).abs() < slack
+            && !path.range.boundary.iter().any(|range_boundary| {
+                (boundary - range_boundary.t).abs() < slack
+            })
+    });
+    if is_at_boundary {
+        return None;
+    }
+
+    Some(t)
+}
+
+fn is_in_range(t: Scalar, range: &RangeOnPath, slack: Scalar) -> bool {
+    let [a, b] = range.boundary.map(|point| point.t);
+    let [min, max] = if a < b { [a, b] } else { [b, a] };
+
+    // Allow for some slack, to not lose intersections right at the boundary
+    // due to floating-point inaccuracies.
+    t >= min - slack && t <= max + slack
+}
+
+const MAX_ITERATIONS: usize = 32;
+const EPSILON: f64 = 1e-14;
+
+#[cfg(test)]
+mod tests {
+    use std::f64::consts::TAU;
+
+    use fj_math::{Nurbs, Point, Scalar};
+
+    use crate::{
+        algorithms::approx::path::RangeOnPath, geometry::path::SurfacePath,
+    };
+
+    use super::CurveCurveIntersection;
+
+    #[test]
+    fn line_and_nurbs() {
+        // A parabola, `y = x²`, for `x` from `-1` to `1`.
+        let parabola = SurfacePath::Nurbs(Nurbs::from_control_points(
+            2,
+            [[-1., 1.], [0., -1.], [1., 1.]],
+        ));
+        let line = SurfacePath::line_from_points([[-2., 0.25], [2., 0.25]]);
+
+        let intersections = CurveCurveIntersection::compute(
+            [
+                (&parabola, RangeOnPath::from([[0.], [1.]])),
+                (&line, RangeOnPath::from([[0.], [1.]])),
+            ],
+            0.01,
+        );
+
+        let expected = [[0.25, 0.375], [0.75, 0.625]];
+        assert_eq!(intersections.len(), expected.len());
+        for (intersection, expected) in intersections.iter().zip(expected) {
+            let CurveCurveIntersection::Crossing { points_on_paths } =
+                intersection
+            else {
+                panic!("Expected crossing, got {intersection:?}");
+            };
+
+            for (point, expected) in points_on_paths.iter().zip(expected) {
+                assert!((point.t - expected).abs() < Scalar::from(1e-12));
+            }
+        }
+    }
+
+    #[test]
+    fn circle_and_nurbs() {
+        let parabola = SurfacePath::Nurbs(Nurbs::from_control_points(
+            2,
+            [[-1., 1.], [0., -1.], [1., 1.]],
+        ));
+        let circle = SurfacePath::circle_from_radius(1.);
+
+        let intersections = CurveCurveIntersection::compute(
+            [
+                (&parabola, RangeOnPath::from([[0.], [1.]])),
+                (&circle, RangeOnPath::from([[0.], [TAU]])),
+            ],
+            0.01,
+        );
+
+        // The intersections are where `x² + x⁴ = 1`.
+        let x = ((5_f64.sqrt() - 1.) / 2.).sqrt();
+        let expected = [[-x, x * x], [x, x * x]].map(Point::from);
+
+        assert_eq!(intersections.len(), expected.len());
+        for intersection in intersections {
+            let CurveCurveIntersection::Crossing {
+                points_on_paths: [on_parabola, on_circle],
+            } = intersection
+            else {
+                panic!("Expected crossing, got {intersection:?}");
+            };
+
+            let point = parabola.point_from_path_coords(on_parabola);
+            assert!(expected
+                .iter()
+                .any(|expected| point.distance_to(expected) < 1e-12.into()));
+            assert!(
+                point.distance_to(&circle.point_from_path_coords(on_circle))
+                    < Scalar::from(1e-12)
+            );
+        }
+    }
+
+    #[test]
+    fn line_tangent_to_circle() {
+        let circle = SurfacePath::circle_from_radius(1.);
+        let line = SurfacePath::line_from_points([[-2., 1.], [2., 1.]]);
+
+        let intersections = CurveCurveIntersection::compute(
+            [
+                (&line, RangeOnPath::from([[0.], [1.]])),
+                (&circle, RangeOnPath::from([[0.], [TAU]])),
+            ],
+            0.01,
+        );
+
+        assert_eq!(intersections.len(), 1);
+        let CurveCurveIntersection::Tangency {
+            points_on_paths: [on_line, on_circle],
+        } = intersections[0]
+        else {
+            panic!("Expected tangency, got {intersections:?}");
+        };
+
+        let expected = Point::from([0., 1.]);
+        assert!(
+            line.point_from_path_coords(on_line).distance_to(&expected)
+                < Scalar::from(1e-6)
+        );
+        assert!(
+            circle
+                .point_from_path_coords(on_circle)
+                .distance_to(&expected)
+                < Scalar::from(1e-6)
+        );
+    }
+
+    #[test]
+    fn overlapping_lines() {
+        let a = SurfacePath::line_from_points([[0., 0.], [2., 0.]]);
+        let b = SurfacePath::line_from_points([[1., 0.], [3., 0.]]);
+
+        let intersections = CurveCurveIntersection::compute(
+            [
+                (&a, RangeOnPath::from([[0.], [1.]])),
+                (&b, RangeOnPath::from([[0.], [1.]])),
+            ],
+            0.01,
+        );
+
+        assert_eq!(
+            intersections,
+            vec![CurveCurveIntersection::Overlap {
+                ranges_on_paths: [
+                    RangeOnPath::from([[0.5], [1.]]),
+                    RangeOnPath::from([[0.], [0.5]]),
+                ]
+            }]
+        );
+    }
+
+    #[test]
+    fn near_miss_within_tolerance() {
+        let circle = SurfacePath::circle_from_radius(1.);
+        let line = SurfacePath::line_from_points([[-2., 1.005], [2., 1.005]]);
+
+        let intersections = CurveCurveIntersection::compute(
+            [
+                (&line, RangeOnPath::from([[0.], [1.]])),
+                (&circle, RangeOnPath::from([[0.], [TAU]])),
+            ],
+            0.01,
+        );
+
+        assert_eq!(intersections.len(), 1);
+        let CurveCurveIntersection::Ambiguous { distance, .. } =
+            intersections[0]
+        else {
+            panic!("Expected ambiguous intersection, got {intersections:?}");
+        };
+        assert!((distance - 0.005).abs() < Scalar::from(1e-6));
+
+        // Outside of the tolerance, there is no intersection at all.
+        let intersections = CurveCurveIntersection::compute(
+            [
+                (&line, RangeOnPath::from([[0.], [1.]])),
+                (&circle, RangeOnPath::from([[0.], [TAU]])),
+            ],
+            0.001,
+        );
+        assert!(intersections.is_empty());
+    }
+}
@@ -0,0 +1,1109 @@
+use fj_math::{
+    Circle, Cone, Line, Nurbs, Plane, Point, Scalar, Segment, Vector,
+};
+
+use crate::{
+    algorithms::{
+        approx::{face::FaceApprox, Approx, Tolerance},
+        point_in_face::{cycle_segments, locate_in_polygon, PointLocation},
+    },
+    geometry::{
+        path::{GlobalPath, SurfacePath},
+        surface::SurfaceGeometry,
+    },
+    insert::Insert,
+    objects::{Curve, Face, GlobalCurve, Objects},
+    services::Service,
+    storage::Handle,
+};
+
+use super::{
+    surface_surface::{
+        plane_from_surface, plane_nurbs, point_to_plane_coords,
+        vector_to_plane_coords,
+    },
+    CurveFaceIntersection, CurveFaceIntersectionInterval,
+};
+
+/// A curve along which two faces intersect
+#[derive(Clone, Debug, Eq, PartialEq, Hash, Ord, PartialOrd)]
+pub struct IntersectionCurve {
+    /// The intersection curve, on the surface of each face
+    ///
+    /// Both curves share their global form, and the same curve coordinates
+    /// refer to the same point on both of them.
+    pub curves: [Handle<Curve>; 2],
+
+    /// The intervals along the curve, where it is within both faces
+    pub intervals: CurveFaceIntersection,
+
+    /// Indicate whether the surfaces of the faces touch along the curve
+    ///
+    /// If this is `true`, the surfaces are tangential to each other along the
+    /// curve, and don't cross each other there.
+    pub is_tangential: bool,
+}
+
+/// Compute the curves along which two faces intersect
+///
+/// Intersections between two planar faces are exact. Intersections between a
+/// planar face and a face on a surface that was swept from a circle or an
+/// ellipse, a cone, or a NURBS surface, are approximated by polylines within
+/// the given tolerance, unless they are straight lines, or circles around the
+/// axis of a cone. The boundaries of the
+/// faces are approximated with the same tolerance.
+///
+/// Faces that only touch each other are considered to intersect. Returns an
+/// empty list, if the faces don't intersect.
+pub fn intersect(
+    faces: [&Face; 2],
+    tolerance: impl Into<Tolerance>,
+    objects: &mut Service<Objects>,
+) -> Result<Vec<IntersectionCurve>, IntersectError> {
+    let tolerance = tolerance.into();
+
+    let approxes = faces.map(|face| face.approx(tolerance));
+    let [a, b] = faces.map(|face| face.surface().geometry());
+
+    let paths = match (plane_from_surface(&a), plane_from_surface(&b)) {
+        (Some(a), Some(b)) => plane_plane([a, b], &approxes, tolerance)?,
+        (Some(plane), None) => {
+            plane_other(&plane, &b, &approxes[1], tolerance)?
+        }
+        (None, Some(plane)) => {
+            plane_other(&plane, &a, &approxes[0], tolerance)?
+                .into_iter()
+                .map(|path| {
+                    let [on_plane, on_other] = path.paths;
+                    IntersectionPath {
+                        paths: [on_other, on_plane],
+                        ..path
+                    }
+                })
+                .collect()
+        }
+        (None, None) => return Err(IntersectError::UnsupportedSurfaces),
+    };
+
+    let mut curves = Vec::new();
+
+    for path in paths {
+        let intervals = {
+            let [a, b] = [0, 1].map(|i| {
+                let polyline =
+                    polyline_on_face(&path.paths[i], &approxes[i], tolerance);
+                intervals_on_face(&polyline, &approxes[i], tolerance.inner())
+            });
+            a.merge(&b)
+        };
+
+        if intervals.is_empty() {
+            continue;
+        }
+
+        let global_form = GlobalCurve.insert(objects);
+        let [path_a, path_b] = path.paths;
+        let [surface_a, surface_b] = faces.map(|face| face.surface().clone());
+
+        let curves_on_faces = [(surface_a, path_a), (surface_b, path_b)].map(
+            |(surface, path)| {
+                Curve::new(surface, path, global_form.clone()).insert(objects)
+            },
+        );
+
+        curves.push(IntersectionCurve {
+            curves: curves_on_faces,
+            intervals,
+            is_tangential: path.is_tangential,
+        });
+    }
+
+    Ok(curves)
+}
+
+/// An error computing the intersection of two faces
+#[derive(Debug, Eq, PartialEq, thiserror::Error)]
+pub enum IntersectError {
+    /// The faces are on the same plane
+    ///
+    /// Their intersection is a region, not a set of curves.
+    #[error("Faces are on the same plane")]
+    CoincidentSurfaces,
+
+    /// The combination of surfaces is not supported
+    #[error(
+        "Only intersections that involve at least one planar face are supported"
+    )]
+    UnsupportedSurfaces,
+}
+
+/// An intersection between two surfaces, given in the coordinates of each
+struct IntersectionPath {
+    paths: [SurfacePath; 2],
+    is_tangential: bool,
+}
+
+fn plane_plane(
+    planes: [Plane; 2],
+    approxes: &[FaceApprox; 2],
+    tolerance: Tolerance,
+) -> Result<Vec<IntersectionPath>, IntersectError> {
+    // Algorithm from Real-Time Collision Detection by Christer Ericson. See
+    // section 5.4.4, Intersection of Two Planes.
+
+    let [a, b] = &planes;
+
+    let (a_distance, a_normal) = a.constant_normal_form();
+    let (b_distance, b_normal) = b.constant_normal_form();
+
+    let direction = a_normal.cross(&b_normal);
+
+    // The planes are parallel, if their normals are. Within the faces, the
+    // planes are no further from each other than the tolerance then.
+    let size =
+        approxes
+            .iter()
+            .zip(&planes)
+            .flat_map(|(approx, plane)| {
+                approx.exterior.points().into_iter().map(|point| {
+                    (point.global_form - plane.origin()).magnitude()
+                })
+            })
+            .fold(Scalar::ZERO, Scalar::max);
+    if direction.magnitude() * size <= tolerance.inner() {
+        let distance = b_normal.dot(&(a.origin() - b.origin()));
+
+        if distance.abs() <= tolerance.inner() {
+            return Err(IntersectError::CoincidentSurfaces);
+        }
+
+        return Ok(Vec::new());
+    }
+
+    let origin = (b_normal * a_distance - a_normal * b_distance)
+        .cross(&direction)
+        / direction.dot(&direction);
+    let origin = Point { coords: origin };
+
+    let paths = planes.map(|plane| {
+        SurfacePath::Line(Line::from_origin_and_direction(
+            point_to_plane_coords(&plane, origin),
+            vector_to_plane_coords(&plane, direction),
+        ))
+    });
+
+    Ok(vec![IntersectionPath {
+        paths,
+        is_tangential: false,
+    }])
+}
+
+/// Intersect a plane with a surface that is not a plane
+///
+/// Returns the intersection paths on the plane, followed by those on the other
+/// surface.
+fn plane_other(
+    plane: &Plane,
+    other: &SurfaceGeometry,
+    other_approx: &FaceApprox,
+    tolerance: Tolerance,
+) -> Result<Vec<IntersectionPath>, IntersectError> {
+    match other {
+        SurfaceGeometry::Swept {
+            u: GlobalPath::Circle(circle),
+            v,
+        } => Ok(plane_swept(
+            plane,
+            circle.center(),
+            [circle.a(), circle.b()],
+            *v,
+            other_approx,
+            tolerance,
+        )),
+        SurfaceGeometry::Swept {
+            u: GlobalPath::Ellipse(ellipse),
+            v,
+        } => Ok(plane_swept(
+            plane,
+            ellipse.center(),
+            [ellipse.a(), ellipse.b()],
+            *v,
+            other_approx,
+            tolerance,
+        )),
+        SurfaceGeometry::Cone(cone) => {
+            Ok(plane_cone(plane, cone, other_approx, tolerance))
+        }
+        SurfaceGeometry::Nurbs(nurbs) => Ok(plane_nurbs(plane, nurbs)
+            .map(|paths| IntersectionPath {
+                paths,
+                is_tangential: false,
+            })
+            .into_iter()
+            .collect()),
+        SurfaceGeometry::Swept { .. } | SurfaceGeometry::Ruled { .. } => {
+            Err(IntersectError::UnsupportedSurfaces)
+        }
+    }
+}
+
+/// Intersect a plane with a surface that was swept from a circle or ellipse
+///
+/// The swept surface is given by the center and the two axes of the curve it
+/// was swept from, as well as the vector it was swept along.
+fn plane_swept(
+    plane: &Plane,
+    center: Point<3>,
+    [a, b]: [Vector<3>; 2],
+    v: Vector<3>,
+    approx: &FaceApprox,
+    tolerance: Tolerance,
+) -> Vec<IntersectionPath> {
+    let point_on_surface = |u: Scalar, t: Scalar| {
+        let (sin, cos) = u.sin_cos();
+        center + a * cos + b * sin + v * t
+    };
+
+    // The surface coordinates of the face determine which part of the surface
+    // needs to be covered.
+    let points = approx.exterior.points();
+    let u_start = points
+        .iter()
+        .map(|point| point.local_form.u)
+        .min()
+        .unwrap_or(Scalar::ZERO);
+    let [t_min, t_max] = [
+        points.iter().map(|point| point.local_form.v).min(),
+        points.iter().map(|point| point.local_form.v).max(),
+    ]
+    .map(|t| t.unwrap_or(Scalar::ZERO));
+
+    // The signed distance of a point on the swept surface from the plane is
+    // `k + p * cos(u) + q * sin(u) + w * t`.
+    let (distance, normal) = plane.constant_normal_form();
+    let k = normal.dot(&center.coords) - distance;
+    let [p, q, w] = [a, b, v].map(|vector| normal.dot(&vector));
+
+    if w.abs() * (t_max - t_min) > tolerance.inner() {
+        // The plane is not parallel to the sweep. It crosses every line that
+        // the surface was swept along once, which makes the intersection a
+        // closed curve around the surface.
+        let t_at = |u: Scalar| {
+            let (sin, cos) = u.sin_cos();
+            -(k + p * cos + q * sin) / w
+        };
+
+        let radius = [a - v * p / w, b - v * q / w]
+            .map(|axis| axis.magnitude())
+            .into_iter()
+            .fold(Scalar::ZERO, Scalar::max);
+        let num_points = num_points_for_full_turn(radius, tolerance);
+
+        let on_swept = (0..=num_points)
+            .map(|i| {
+                let u = u_start
+                    + Scalar::TAU * Scalar::from(i as f64)
+                        / Scalar::from(num_points as f64);
+                Point::from([u, t_at(u)])
+            })
+            .collect::<Vec<_>>();
+        let on_plane = on_swept
+            .iter()
+            .map(|point| {
+                point_to_plane_coords(plane, point_on_surface(point.u, point.v))
+            })
+            .collect::<Vec<_>>();
+
+        return vec![IntersectionPath {
+            paths: [
+                SurfacePath::Nurbs(Nurbs::from_control_points(1, on_plane)),
+                SurfacePath::Nurbs(Nurbs::from_control_points(1, on_swept)),
+            ],
+            is_tangential: false,
+        }];
+    }
+
+    // The plane is parallel to the sweep. It intersects the surface along the
+    // lines that the surface was swept along, where `k + p * cos(u) +
+    // q * sin(u)` is zero. That sum can be written as `k + r * cos(u - phi)`.
+    let r = Vector::from([p, q]).magnitude();
+    if r <= tolerance.inner() || k.abs() > r + tolerance.inner() {
+        return Vec::new();
+    }
+    let phi = q.atan2(p);
+
+    let is_tangential = (k.abs() - r).abs() <= tolerance.inner();
+    let offset = (-k / r).max(-Scalar::ONE).min(Scalar::ONE).acos();
+    let angles = if is_tangential {
+        vec![phi + offset]
+    } else {
+        vec![phi + offset, phi - offset]
+    };
+
+    angles
+        .into_iter()
+        .map(|u| {
+            // Move the angle into the range of the face.
+            let u = u_start
+                + Scalar::from(
+                    (u - u_start).into_f64().rem_euclid(Scalar::TAU.into_f64()),
+                );
+
+            let on_swept = Line::from_origin_and_direction(
+                Point::from([u, Scalar::ZERO]),
+                Vector::from([0., 1.]),
+            );
+            let on_plane = Line::from_origin_and_direction(
+                point_to_plane_coords(plane, point_on_surface(u, Scalar::ZERO)),
+                vector_to_plane_coords(plane, v),
+            );
+
+            IntersectionPath {
+                paths: [
+                    SurfacePath::Line(on_plane),
+                    SurfacePath::Line(on_swept),
+                ],
+                is_tangential,
+            }
+        })
+        .collect()
+}
+
+/// Intersect a plane with a cone
+///
+/// Planes through the apex intersect the cone along straight lines, planes that
+/// are perpendicular to the axis along a circle. Those cases are exact. All
+/// other intersections are conic sections, which are approximated by
+/// polylines.
+fn plane_cone(
+    plane: &Plane,
+    cone: &Cone,
+    approx: &FaceApprox,
+    tolerance: Tolerance,
+) -> Vec<IntersectionPath> {
+    // The surface coordinates of the face determine which part of the surface
+    // needs to be covered.
+    let points = approx.exterior.points();
+    let u_start = points
+        .iter()
+        .map(|point| point.local_form.u)
+        .min()
+        .unwrap_or(Scalar::ZERO);
+    let [v_min, v_max] = [
+        points.iter().map(|point| point.local_form.v).min(),
+        points.iter().map(|point| point.local_form.v).max(),
+    ]
+    .map(|v| v.unwrap_or(Scalar::ZERO));
+    let v_extent = Scalar::max(v_min.abs(), v_max.abs());
+
+    // The signed distance of a point on the cone from the plane is
+    // `k + v * (w + p * cos(u) + q * sin(u))`. The sum in parentheses can be
+    // written as `w + r * cos(u - phi)`.
+    let (distance, normal) = plane.constant_normal_form();
+    let k = normal.dot(&cone.apex().coords) - distance;
+    let [p, q, w] = [cone.a(), cone.b(), cone.axis()].map(|v| normal.dot(&v));
+    let r = Vector::from([p, q]).magnitude();
+    let phi = q.atan2(p);
+
+    let line_direction = |u: Scalar| {
+        let (sin, cos) = u.sin_cos();
+        cone.axis() + cone.a() * cos + cone.b() * sin
+    };
+
+    if k.abs() <= tolerance.inner() {
+        // The plane goes through the apex. It intersects the cone along the
+        // lines from the apex, where `w + r * cos(u - phi)` is zero.
+        let is_tangential =
+            ((w.abs() - r) * v_extent).abs() <= tolerance.inner();
+        if !is_tangential && w.abs() > r {
+            return Vec::new();
+        }
+
+        let offset = (-w / r).max(-Scalar::ONE).min(Scalar::ONE).acos();
+        let angles = if is_tangential {
+            vec![phi + offset]
+        } else {
+            vec![phi + offset, phi - offset]
+        };
+
+        return angles
+            .into_iter()
+            .map(|u| {
+                // Move the angle into the range of the face.
+                let u = u_start
+                    + Scalar::from(
+                        (u - u_start)
+                            .into_f64()
+                            .rem_euclid(Scalar::TAU.into_f64()),
+                    );
+
+                let on_cone = Line::from_origin_and_direction(
+                    Point::from([u, Scalar::ZERO]),
+                    Vector::from([0., 1.]),
+                );
+                let on_plane = Line::from_origin_and_direction(
+                    point_to_plane_coords(plane, cone.apex()),
+                    vector_to_plane_coords(plane, line_direction(u)),
+                );
+
+                IntersectionPath {
+                    paths: [
+                        SurfacePath::Line(on_plane),
+                        SurfacePath::Line(on_cone),
+                    ],
+                    is_tangential,
+                }
+            })
+            .collect();
+    }
+
+    // A plane that is perpendicular to the axis intersects the cone along a
+    // circle, which is a line of constant v in surface coordinates. The
+    // circle starts at the start of the face, like the line does.
+    if w != Scalar::ZERO && (k / w).abs() * r <= tolerance.inner() {
+        let v = -k / w;
+        let (sin, cos) = u_start.sin_cos();
+        let a = (cone.a() * cos + cone.b() * sin) * v;
+        let b = (cone.b() * cos - cone.a() * sin) * v;
+
+        if let Some(circle) = circle_in_plane(
+            plane,
+            cone.apex() + cone.axis() * v,
+            [a, b],
+            tolerance,
+        ) {
+            let on_cone = Line::from_origin_and_direction(
+                Point::from([u_start, v]),
+                Vector::from([1., 0.]),
+            );
+
+            return vec![IntersectionPath {
+                paths: [
+                    SurfacePath::Circle(circle),
+                    SurfacePath::Line(on_cone),
+                ],
+                is_tangential: false,
+            }];
+        }
+    }
+
+    // In general, the plane intersects each line from the apex once, at most.
+    // Where it doesn't, the intersection is open, and runs off to infinity.
+    // Only the parts of it near the face are kept.
+    let margin = v_max - v_min;
+    let num_points =
+        num_points_for_full_turn(cone.radius() * v_extent, tolerance);
+
+    let mut runs = Vec::new();
+    let mut run = Vec::new();
+    for i in 0..=num_points {
+        let u = u_start
+            + Scalar::TAU * Scalar::from(i as f64)
+                / Scalar::from(num_points as f64);
+        let (sin, cos) = u.sin_cos();
+        let denominator = w + p * cos + q * sin;
+
+        let v = (denominator != Scalar::ZERO).then(|| -k / denominator);
+        match v {
+            Some(v) if v >= v_min - margin && v <= v_max + margin => {
+                run.push(Point::from([u, v]));
+            }
+            _ => runs.push(std::mem::take(&mut run)),
+        }
+    }
+    runs.push(run);
+
+    runs.into_iter()
+        .filter(|run| run.len() > 1)
+        .map(|on_cone| {
+            let on_plane = on_cone
+                .iter()
+                .map(|&point| {
+                    point_to_plane_coords(
+                        plane,
+                        cone.point_from_surface_coords(point),
+                    )
+                })
+                .collect::<Vec<_>>();
+
+            IntersectionPath {
+                paths: [
+                    SurfacePath::Nurbs(Nurbs::from_control_points(1, on_plane)),
+                    SurfacePath::Nurbs(Nurbs::from_control_points(1, on_cone)),
+                ],
+                is_tangential: false,
+            }
+        })
+        .collect()
+}
+
+/// Express a circle in the coordinates of a plane that contains it
+///
+/// Returns `None`, if the axes of the plane distort the circle into an ellipse.
+fn circle_in_plane(
+    plane: &Plane,
+    center: Point<3>,
+    [a, b]: [Vector<3>; 2],
+    tolerance: Tolerance,
+) -> Option<Circle<2>> {
+    let center = point_to_plane_coords(plane, center);
+    let a_in_plane = vector_to_plane_coords(plane, a);
+    if a_in_plane.magnitude() == Scalar::ZERO {
+        return None;
+    }
+
+    // Constructing `b` from `a` makes the circle exact, as far as the circle
+    // is concerned. Check that it's still the same circle in 3D.
+    let [a_u, a_v] = a_in_plane.components;
+    let b_in_plane = if vector_to_plane_coords(plane, b).cross2d(&a_in_plane)
+        < Scalar::ZERO
+    {
+        Vector::from([-a_v, a_u])
+    } else {
+        Vector::from([a_v, -a_u])
+    };
+    let b_in_3d = plane.u() * b_in_plane.u + plane.v() * b_in_plane.v;
+    if (b_in_3d - b).magnitude() > tolerance.inner() {
+        return None;
+    }
+
+    Some(Circle::new(center, a_in_plane, b_in_plane))
+}
+
+/// Compute the number of segments that approximate a full turn around a circle
+fn num_points_for_full_turn(radius: Scalar, tolerance: Tolerance) -> usize {
+    let ratio = (tolerance.inner() / radius).min(Scalar::ONE);
+    let num_points = Scalar::max(Scalar::PI / (Scalar::ONE - ratio).acos(), 3.);
+
+    num_points.ceil().into_f64() as usize
+}
+
+/// Convert a path into a polyline, in the region of a face
+///
+/// Returns the points of the polyline, together with their curve coordinates.
+/// Lines are clipped to the extent of the face. Circles are approximated over
+/// a full turn. All other paths must be polylines, represented as NURBS curves
+/// of degree one.
+fn polyline_on_face(
+    path: &SurfacePath,
+    approx: &FaceApprox,
+    tolerance: Tolerance,
+) -> Vec<(Scalar, Point<2>)> {
+    match path {
+        SurfacePath::Line(line) => {
+            let params = approx
+                .exterior
+                .points()
+                .into_iter()
+                .map(|point| line.point_to_line_coords(point.local_form).t)
+                .collect::<Vec<_>>();
+            let [min, max] =
+                [params.iter().copied().min(), params.iter().copied().max()]
+                    .map(|t| t.unwrap_or(Scalar::ZERO));
+
+            [min, max]
+                .map(|t| (t, line.point_from_line_coords([t])))
+                .to_vec()
+        }
+        SurfacePath::Nurbs(nurbs) => {
+            // The parameters of the control points of a polyline are evenly
+            // spaced within the range `0..=1`.
+            let points = nurbs.control_points();
+            let last = Scalar::from((points.len() - 1) as f64);
+
+            points
+                .iter()
+                .enumerate()
+                .map(|(i, &point)| (Scalar::from(i as f64) / last, point))
+                .collect()
+        }
+        SurfacePath::Circle(circle) => {
+            let num_points =
+                num_points_for_full_turn(circle.radius(), tolerance);
+
+            (0..=num_points)
+                .map(|i| {
+                    let t = Scalar::TAU * Scalar::from(i as f64)
+                        / Scalar::from(num_points as f64);
+                    (t, circle.point_from_circle_coords([t]))
+                })
+                .collect()
+        }
+        SurfacePath::Ellipse(_) => {
+            unreachable!("Intersection paths are lines, circles, or polylines")
+        }
+    }
+}
+
+/// Compute the intervals in which a polyline is within a face
+fn intervals_on_face(
+    polyline: &[(Scalar, Point<2>)],
+    approx: &FaceApprox,
+    tolerance: Scalar,
+) -> CurveFaceIntersection {
+    let cycles = [&approx.exterior]
+        .into_iter()
+        .chain(&approx.interiors)
+        .map(cycle_segments)
+        .collect::<Vec<_>>();
+
+    // The polyline can only enter or leave the face where it crosses the
+    // boundary.
+    let mut params = polyline.iter().map(|&(t, _)| t).collect::<Vec<_>>();
+    for window in polyline.windows(2) {
+        let [(t_a, a), (t_b, b)] = [window[0], window[1]];
+
+        for segment in cycles.iter().flatten() {
+            params.extend(
+                crossings(Segment::from_points([a, b]), segment, tolerance)
+                    .into_iter()
+                    .map(|s| t_a + (t_b - t_a) * s),
+            );
+        }
+    }
+    params.sort();
+    params.dedup();
+
+    let mut intervals: Vec<CurveFaceIntersectionInterval> = Vec::new();
+    for window in params.windows(2) {
+        let [start, end] = [window[0], window[1]];
+
+        let middle = point_on_polyline(polyline, (start + end) / 2.);
+        let location =
+            locate_in_polygon(cycles.iter().cloned(), middle, tolerance);
+        if location == PointLocation::Outside {
+            continue;
+        }
+
+        match intervals.last_mut() {
+            Some(interval) if interval.end.t == start => {
+                interval.end = Point::from([end]);
+            }
+            _ => intervals.push([[start], [end]].into()),
+        }
+    }
+
+    CurveFaceIntersection::from_intervals(intervals)
+}
+
+/// Compute where a segment crosses another one
+///
+/// Returns the crossings as parameters along `segment`, in the range `0..=1`.
+/// If the segments overlap, returns the parameters of the overlap's ends.
+fn crossings(
+    segment: Segment<2>,
+    other: &Segment<2>,
+    tolerance: Scalar,
+) -> Vec<Scalar> {
+    let cross = |a: Vector<2>, b: Vector<2>| a.u * b.v - a.v * b.u;
+
+    let [a, b] = segment.points();
+    let [c, d] = other.points();
+    let [ab, cd, ac] = [b - a, d - c, c - a];
+
+    let is_in_range = |s: &Scalar| *s >= Scalar::ZERO && *s <= Scalar::ONE;
+
+    let denom = cross(ab, cd);
+    if denom.abs()
+        > Scalar::from(f64::EPSILON) * ab.magnitude() * cd.magnitude()
+    {
+        let s = cross(ac, cd) / denom;
+        let r = cross(ac, ab) / denom;
+
+        return Some(s)
+            .filter(|_| is_in_range(&r))
+            .filter(is_in_range)
+            .into_iter()
+            .collect();
+    }
+
+    // The segments are parallel. They only cross, if they are on the same
+    // line.
+    let length_squared = ab.dot(&ab);
+    if length_squared == Scalar::ZERO
+        || cross(ab, ac).abs() / ab.magnitude() > tolerance
+    {
+        return Vec::new();
+    }
+
+    [c, d]
+        .map(|point| (point - a).dot(&ab) / length_squared)
+        .into_iter()
+        .filter(is_in_range)
+        .collect()
+}
+
+fn point_on_polyline(polyline: &[(Scalar, Point<2>)], t: Scalar) -> Point<2> {
+    for window in polyline.windows(2) {
+        let [(t_a, a), (t_b, b)] = [window[0], window[1]];
+
+        if t >= t_a && t <= t_b {
+            return a + (b - a) * ((t - t_a) / (t_b - t_a));
+        }
+    }
+
+    unreachable!("Parameter is within the range of the polyline")
+}
+
+#[cfg(test)]
+mod tests {
+    use std::f64::consts::TAU;
+
+    use fj_math::{Cone, Line, Point, Scalar, Vector};
+
+    use crate::{
+        algorithms::{approx::Tolerance, sweep::Sweep},
+        builder::{FaceBuilder, SurfaceBuilder},
+        geometry::{
+            path::{GlobalPath, SurfacePath},
+            surface::SurfaceGeometry,
+        },
+        insert::Insert,
+        objects::{Curve, Face, Objects},
+        partial::{HasPartial, PartialSurface},
+        services::{Service, Services},
+        storage::Handle,
+    };
+
+    use super::IntersectError;
+
+    fn point_on_curve(curve: &Curve, point: Point<1>) -> Point<3> {
+        let point = curve.path().point_from_path_coords(point);
+        curve.surface().geometry().point_from_surface_coords(point)
+    }
+
+    fn square(
+        origin: [f64; 3],
+        axes: [[f64; 3]; 2],
+        objects: &mut Service<Objects>,
+    ) -> Handle<Face> {
+        let [u, v] = axes;
+        let surface = PartialSurface::from_axes(
+            GlobalPath::Line(Line::from_origin_and_direction(
+                Point::from(origin),
+                Vector::from(u),
+            )),
+            v,
+        )
+        .build(objects)
+        .insert(objects);
+
+        Face::partial()
+            .with_exterior_polygon_from_points(
+                surface,
+                [[-1., -1.], [1., -1.], [1., 1.], [-1., 1.]],
+            )
+            .build(objects)
+            .insert(objects)
+    }
+
+    /// The side face of a cylinder around the z-axis
+    ///
+    /// The cylinder has a radius of one, and goes from `z = 0` to `z = 1`.
+    fn cylinder(objects: &mut Service<Objects>) -> Handle<Face> {
+        let surface = objects.surfaces.xy_plane();
+        let disc = Face::partial()
+            .with_exterior_circle(surface, [0., 0.], 1., objects)
+            .build(objects)
+            .insert(objects);
+
+        let shell = disc.sweep([0., 0., 1.], objects);
+        shell
+            .faces()
+            .into_iter()
+            .find(|face| {
+                matches!(
+                    face.surface().geometry(),
+                    SurfaceGeometry::Swept {
+                        u: GlobalPath::Circle(_),
+                        ..
+                    }
+                )
+            })
+            .expect("Expected cylinder to have a curved side face")
+            .clone()
+    }
+
+    /// A face on a cone around the z-axis
+    ///
+    /// The apex of the cone is at `z = 2`, and its radius is one at `z = 0`.
+    /// The face goes from there to `z = 1`.
+    fn cone(objects: &mut Service<Objects>) -> Handle<Face> {
+        let surface = PartialSurface::from_cone(Cone::new(
+            [0., 0., 2.],
+            [1., 0., 0.],
+            [0., -1., 0.],
+            [0., 0., -2.],
+        ))
+        .build(objects)
+        .insert(objects);
+
+        Face::partial()
+            .with_exterior_polygon_from_points(
+                surface,
+                [[0., 0.5], [TAU, 0.5], [TAU, 1.], [0., 1.]],
+            )
+            .build(objects)
+            .insert(objects)
+    }
+
+    #[test]
+    fn planar_planar() -> anyhow::Result<()> {
+        let mut services = Services::new();
+        let tolerance = Tolerance::from_scalar(0.001)?;
+
+        let a = square(
+            [0., 0., 0.],
+            [[1., 0., 0.], [0., 1., 0.]],
+            &mut services.objects,
+        );
+        let b = square(
+            [0.5, 0., 0.],
+            [[0., 1., 0.], [0., 0., 1.]],
+            &mut services.objects,
+        );
+
+        let curves =
+            super::intersect([&a, &b], tolerance, &mut services.objects)?;
+        assert_eq!(curves.len(), 1);
+
+        let curve = &curves[0];
+        assert!(!curve.is_tangential);
+        assert_eq!(curve.intervals.intervals.len(), 1);
+
+        // The faces intersect along the line `x = 0.5`, `z = 0`, within
+        // `-1 <= y <= 1`.
+        let interval = curve.intervals.intervals[0];
+        for curve in &curve.curves {
+            let mut ends = [interval.start, interval.end]
+                .map(|point| point_on_curve(curve, point));
+            ends.sort();
+
+            for (end, expected) in ends
+                .into_iter()
+                .zip([Point::from([0.5, -1., 0.]), Point::from([0.5, 1., 0.])])
+            {
+                assert!((end - expected).magnitude() < Scalar::from(1e-12));
+            }
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn planar_planar_coincident() -> anyhow::Result<()> {
+        let mut services = Services::new();
+        let tolerance = Tolerance::from_scalar(0.001)?;
+
+        let a = square(
+            [0., 0., 0.],
+            [[1., 0., 0.], [0., 1., 0.]],
+            &mut services.objects,
+        );
+        let b = square(
+            [0.5, 0.5, 0.],
+            [[0., 1., 0.], [1., 0., 0.]],
+            &mut services.objects,
+        );
+        let c = square(
+            [0., 0., 1.],
+            [[1., 0., 0.], [0., 1., 0.]],
+            &mut services.objects,
+        );
+
+        assert_eq!(
+            super::intersect([&a, &b], tolerance, &mut services.objects),
+            Err(IntersectError::CoincidentSurfaces),
+        );
+
+        // Parallel faces don't intersect.
+        assert_eq!(
+            super::intersect([&a, &c], tolerance, &mut services.objects),
+            Ok(Vec::new()),
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn planar_swept() -> anyhow::Result<()> {
+        let mut services = Services::new();
+        let tolerance = Tolerance::from_scalar(0.001)?;
+
+        let cylinder = cylinder(&mut services.objects);
+        let plane = square(
+            [0., 0., 0.5],
+            [[2., 0., 0.], [0., 2., 0.]],
+            &mut services.objects,
+        );
+
+        let curves = super::intersect(
+            [&plane, &cylinder],
+            tolerance,
+            &mut services.objects,
+        )?;
+        assert_eq!(curves.len(), 1);
+
+        // The intersection is a circle at the height of the plane.
+        let curve = &curves[0];
+        assert!(!curve.is_tangential);
+        assert_eq!(curve.intervals.intervals.len(), 1);
+
+        let interval = curve.intervals.intervals[0];
+        for curve in &curve.curves {
+            let points = [interval.start, interval.end]
+                .map(|point| point_on_curve(curve, point));
+
+            for point in points {
+                assert!((point.z - 0.5).abs() < Scalar::from(1e-9));
+                assert!(
+                    (point.coords.xy().magnitude() - 1.).abs()
+                        < Scalar::from(1e-9)
+                );
+            }
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn planar_swept_tangential() -> anyhow::Result<()> {
+        let mut services = Services::new();
+        let tolerance = Tolerance::from_scalar(0.001)?;
+
+        let cylinder = cylinder(&mut services.objects);
+
+        // A plane parallel to the cylinder axis, touching it at `y = 1`.
+        let touching = square(
+            [0., 1., 0.],
+            [[1., 0., 0.], [0., 0., 1.]],
+            &mut services.objects,
+        );
+        // A plane parallel to the cylinder axis, cutting through it.
+        let cutting = square(
+            [0., 0.5, 0.],
+            [[1., 0., 0.], [0., 0., 1.]],
+            &mut services.objects,
+        );
+
+        let curves = super::intersect(
+            [&touching, &cylinder],
+            tolerance,
+            &mut services.objects,
+        )?;
+        assert_eq!(curves.len(), 1);
+        assert!(curves[0].is_tangential);
+
+        let curves = super::intersect(
+            [&cylinder, &cutting],
+            tolerance,
+            &mut services.objects,
+        )?;
+        assert_eq!(curves.len(), 2);
+        assert!(curves.iter().all(|curve| !curve.is_tangential));
+
+        Ok(())
+    }
+
+    #[test]
+    fn planar_cone() -> anyhow::Result<()> {
+        let mut services = Services::new();
+        let tolerance = Tolerance::from_scalar(0.001)?;
+
+        let cone = cone(&mut services.objects);
+        let perpendicular = square(
+            [0., 0., 0.5],
+            [[2., 0., 0.], [0., 2., 0.]],
+            &mut services.objects,
+        );
+        let tilted = square(
+            [0., 0., 0.5],
+            [[2., 0., 0.2], [0., 2., 0.]],
+            &mut services.objects,
+        );
+
+        // A plane perpendicular to the axis intersects the cone along a
+        // circle, at the height of the plane.
+        let curves = super::intersect(
+            [&perpendicular, &cone],
+            tolerance,
+            &mut services.objects,
+        )?;
+        assert_eq!(curves.len(), 1);
+
+        let curve = &curves[0];
+        assert!(matches!(curve.curves[0].path(), SurfacePath::Circle(_)));
+        assert_eq!(curve.intervals.intervals.len(), 1);
+
+        let interval = curve.intervals.intervals[0];
+        for curve in &curve.curves {
+            let points = [interval.start, interval.end]
+                .map(|point| point_on_curve(curve, point));
+
+            for point in points {
+                assert!((point.z - 0.5).abs() < Scalar::from(1e-9));
+                assert!(
+                    (point.coords.xy().magnitude() - 0.75).abs()
+                        < Scalar::from(1e-9)
+                );
+            }
+        }
+
+        // A tilted plane intersects it along an ellipse.
+        let curves = super::intersect(
+            [&cone, &tilted],
+            tolerance,
+            &mut services.objects,
+        )?;
+        assert_eq!(curves.len(), 1);
+        assert!(matches!(curves[0].curves[1].path(), SurfacePath::Nurbs(_)));
+
+        Ok(())
+    }
+
+    #[test]
+    fn planar_cone_through_apex() -> anyhow::Result<()> {
+        let mut services = Services::new();
+        let tolerance = Tolerance::from_scalar(0.001)?;
+
+        let cone = cone(&mut services.objects);
+        let plane = square(
+            [0., 0., 1.],
+            [[0., 2., 0.], [0., 0., 2.]],
+            &mut services.objects,
+        );
+
+        // The plane contains the axis, and intersects the cone along two of
+        // the lines from the apex.
+        let curves = super::intersect(
+            [&plane, &cone],
+            tolerance,
+            &mut services.objects,
+        )?;
+        assert_eq!(curves.len(), 2);
+
+        for curve in &curves {
+            assert!(!curve.is_tangential);
+            assert!(curve
+                .curves
+                .iter()
+                .all(|curve| matches!(curve.path(), SurfacePath::Line(_))));
+
+            let interval = curve.intervals.intervals[0];
+            for curve in &curve.curves {
+                let points = [interval.start, interval.end]
+                    .map(|point| point_on_curve(curve, point));
+
+                for point in points {
+                    assert!(point.x.abs() < Scalar::from(1e-9));
+                    assert!(
+                        (point.y.abs() - (Scalar::ONE - point.z / 2.)).abs()
+                            < Scalar::from(1e-9)
+                    );
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
@@ -1,117 +1,77 @@
 //! Intersection between faces and points in 2D
 
-use fj_math::Point;
+use fj_math::{Point, Segment};
 
 use crate::{
+    algorithms::{
+        approx::Approx,
+        point_in_face::{locate_in_polygon, LocatePoint, PointLocation},
+    },
     objects::{Face, HalfEdge, Vertex},
     storage::Handle,
+    tolerance::ModelTolerance,
 };
 
-use super::{
-    ray_segment::RaySegmentIntersection, HorizontalRayToTheRight, Intersect,
-};
+use super::Intersect;
 
 impl Intersect for (&Handle<Face>, &Point<2>) {
     type Intersection = FacePointIntersection;
 
     fn intersect(self) -> Option<Self::Intersection> {
         let (face, point) = self;
+        let tolerance = ModelTolerance::default();
 
-        let ray = HorizontalRayToTheRight { origin: *point };
-
-        let mut num_hits = 0;
-
-        for cycle in face.all_cycles() {
-            // We need to properly detect the ray passing the boundary at the
-            // "seam" of the polygon, i.e. the vertex between the last and the
-            // first segment. The logic in the loop properly takes care of that,
-            // as long as we initialize the `previous_hit` variable with the
-            // result of the last segment.
-            let mut previous_hit = cycle
-                .half_edges()
-                .last()
-                .cloned()
-                .and_then(|edge| (&ray, &edge).intersect());
-
-            for half_edge in cycle.half_edges() {
-                let hit = (&ray, half_edge).intersect();
-
-                let count_hit = match (hit, previous_hit) {
-                    (
-                        Some(RaySegmentIntersection::RayStartsOnSegment),
-                        _,
-                    ) => {
-                        // If the ray starts on the boundary of the face,
-                        // there's nothing to else check.
-                        return Some(FacePointIntersection::PointIsOnEdge(
-                            half_edge.clone()
-                        ));
-                    }
-                    (Some(RaySegmentIntersection::RayStartsOnOnFirstVertex), _) => {
-                        let vertex = half_edge.vertices()[0].clone();
-                        return Some(
-                            FacePointIntersection::PointIsOnVertex(vertex)
-                        );
-                    }
-                    (Some(RaySegmentIntersection::RayStartsOnSecondVertex), _) => {
-                        let vertex = half_edge.vertices()[1].clone();
-                        return Some(
-                            FacePointIntersection::PointIsOnVertex(vertex)
-                        );
-                    }
-                    (Some(RaySegmentIntersection::RayHitsSegment), _) => {
-                        // We're hitting a segment right-on. Clear case.
-                        true
-                    }
-                    (
-                        Some(RaySegmentIntersection::RayHitsUpperVertex),
-                        Some(RaySegmentIntersection::RayHitsLowerVertex),
-                    )
-                    | (
-                        Some(RaySegmentIntersection::RayHitsLowerVertex),
-                        Some(RaySegmentIntersection::RayHitsUpperVertex),
-                    ) => {
-                        // If we're hitting a vertex, only count it if we've hit
-                        // the other kind of vertex right before.
-                        //
-                        // That means, we're passing through the polygon
-                        // boundary at where two edges touch. Depending on the
-                        // order in which edges are checked, we're seeing this
-                        // as a hit to one edge's lower/upper vertex, then the
-                        // other edge's opposite vertex.
-                        //
-                        // If we're seeing two of the same vertices in a row,
-                        // we're not actually passing through the polygon
-                        // boundary. Then we're just touching a vertex without
-                        // passing through anything.
-                        true
-                    }
-                    (Some(RaySegmentIntersection::RayHitsSegmentAndAreParallel), _) => {
-                        // A parallel edge must be completely ignored. Its
-                        // presence won't change anything, so we can treat it as
-                        // if it wasn't there, and its neighbors were connected
-                        // to each other.
-                        continue;
-                    }
-                    _ => {
-                        // Any other case is not a valid hit.
-                        false
-                    }
-                };
-
-                if count_hit {
-                    num_hits += 1;
-                }
+        match face.locate_point(*point, tolerance) {
+            PointLocation::Inside => {
+                return Some(FacePointIntersection::PointIsInsideFace)
+            }
+            PointLocation::Outside => return None,
+            PointLocation::OnBoundary => {}
+        }
 
-                previous_hit = hit;
+        // The point is on the boundary. Figure out where exactly.
+        let half_edges = face
+            .all_cycles()
+            .flat_map(|cycle| cycle.half_edges())
+            .collect::<Vec<_>>();
+
+        for half_edge in &half_edges {
+            for vertex in half_edge.vertices() {
+                let position = vertex.surface_form().position();
+                if tolerance.coincide(position, *point) {
+                    return Some(FacePointIntersection::PointIsOnVertex(
+                        vertex.clone(),
+                    ));
+                }
             }
         }
 
-        if num_hits % 2 == 1 {
-            Some(FacePointIntersection::PointIsInsideFace)
-        } else {
-            None
+        for half_edge in half_edges {
+            let mut points = half_edge
+                .approx(tolerance)
+                .points()
+                .into_iter()
+                .map(|point| point.local_form)
+                .collect::<Vec<_>>();
+            points.push(half_edge.front().surface_form().position());
+
+            let segments = points
+                .windows(2)
+                .map(|points| Segment::from_points([points[0], points[1]]))
+                .collect();
+
+            // A polyline is not a polygon, but we only care about whether the
+            // point is on it.
+            let location =
+                locate_in_polygon([segments], *point, tolerance.inner());
+            if location == PointLocation::OnBoundary {
+                return Some(FacePointIntersection::PointIsOnEdge(
+                    half_edge.clone(),
+                ));
+            }
         }
+
+        unreachable!("Point is on boundary, but not on any half-edge")
     }
 }
 
@@ -4,19 +4,24 @@ pub mod face_point;
 pub mod ray_edge;
 pub mod ray_face;
 pub mod ray_segment;
+pub mod ray_solid;
 
+mod curve_curve;
 mod curve_edge;
 mod curve_face;
 mod face_face;
+mod face_face_curves;
 mod line_segment;
 mod surface_surface;
 
 use fj_math::{Point, Vector};
 
 pub use self::{
+    curve_curve::CurveCurveIntersection,
     curve_edge::CurveEdgeIntersection,
     curve_face::{CurveFaceIntersection, CurveFaceIntersectionInterval},
     face_face::FaceFaceIntersection,
+    face_face_curves::{intersect, IntersectError, IntersectionCurve},
     line_segment::LineSegmentIntersection,
     surface_surface::SurfaceSurfaceIntersection,
 };
@@ -1,6 +1,6 @@
 //! Intersection between a ray and an edge in 2D
 
-use fj_math::Segment;
+use fj_math::{Point, Scalar, Segment, Vector};
 
 use crate::{
     algorithms::intersect::{HorizontalRayToTheRight, Intersect},
@@ -19,8 +19,18 @@ impl Intersect for (&HorizontalRayToTheRight<2>, &Handle<HalfEdge>) {
 
         let line = match edge.curve().path() {
             SurfacePath::Line(line) => line,
-            SurfacePath::Circle(_) => {
-                todo!("Casting rays against circles is not supported yet")
+            SurfacePath::Circle(circle) => {
+                let arc =
+                    Arc::new(edge, circle.center(), circle.a(), circle.b());
+                return arc.intersect_ray(ray);
+            }
+            SurfacePath::Ellipse(ellipse) => {
+                let arc =
+                    Arc::new(edge, ellipse.center(), ellipse.a(), ellipse.b());
+                return arc.intersect_ray(ray);
+            }
+            SurfacePath::Nurbs(_) => {
+                todo!("Ray-edge intersection doesn't support NURBS curves yet")
             }
         };
 
@@ -33,3 +43,241 @@ impl Intersect for (&HorizontalRayToTheRight<2>, &Handle<HalfEdge>) {
         (ray, &segment).intersect()
     }
 }
+
+/// An edge that is a section of a circle or an ellipse
+///
+/// The points on the arc are `center + a * cos(t) + b * sin(t)`, for all `t`
+/// within `range`.
+struct Arc {
+    center: Point<2>,
+    a: Vector<2>,
+    b: Vector<2>,
+    range: [Scalar; 2],
+    vertices: [Point<2>; 2],
+}
+
+impl Arc {
+    fn new(
+        edge: &HalfEdge,
+        center: Point<2>,
+        a: Vector<2>,
+        b: Vector<2>,
+    ) -> Self {
+        let [back, front] = edge.vertices();
+
+        Self {
+            center,
+            a,
+            b,
+            range: [back, front].map(|vertex| vertex.position().t),
+            vertices: [back, front]
+                .map(|vertex| vertex.surface_form().position()),
+        }
+    }
+
+    fn point(&self, t: Scalar) -> Point<2> {
+        let (sin, cos) = t.sin_cos();
+        self.center + self.a * cos + self.b * sin
+    }
+
+    fn intersect_ray(
+        &self,
+        ray: &HorizontalRayToTheRight<2>,
+    ) -> Option<RaySegmentIntersection> {
+        // Points on the arc are computed using trigonometric functions, and so
+        // can't be compared exactly. Angles or distances that differ by less
+        // than this are considered equal.
+        const EPSILON: f64 = 1e-12;
+
+        let origin = ray.origin;
+        let [start, end] = self.range;
+        let [min, max] = if start <= end {
+            [start, end]
+        } else {
+            [end, start]
+        };
+
+        let mut vertex_hits = Vec::new();
+        for (i, vertex) in self.vertices.into_iter().enumerate() {
+            if vertex.v != origin.v || vertex.u < origin.u {
+                continue;
+            }
+            if vertex.u == origin.u {
+                return Some(if i == 0 {
+                    RaySegmentIntersection::RayStartsOnOnFirstVertex
+                } else {
+                    RaySegmentIntersection::RayStartsOnSecondVertex
+                });
+            }
+
+            vertex_hits.push(self.classify_vertex(i));
+        }
+
+        // Count the points where the arc crosses the line of the ray. They are
+        // the solutions of `a.v * cos(t) + b.v * sin(t) == origin.v - center.v`.
+        // The left side of that equation can be rewritten as
+        // `radius * cos(t - phase)`.
+        let mut num_crossings = 0;
+        let radius = Vector::from([self.a.v, self.b.v]).magnitude();
+        let offset = origin.v - self.center.v;
+        if offset.abs() <= radius {
+            let phase = self.b.v.atan2(self.a.v);
+            let angle = (offset / radius).acos();
+
+            // If there's only one solution, the line of the ray touches the
+            // arc without crossing it.
+            let is_tangent = angle == Scalar::ZERO;
+            let solutions = if is_tangent {
+                vec![phase]
+            } else {
+                vec![phase - angle, phase + angle]
+            };
+
+            for t in solutions {
+                // Move the angle into the first full turn from the start of
+                // the arc.
+                let turns = ((t - min) / Scalar::TAU).floor();
+                let t = t - turns * Scalar::TAU;
+
+                if t <= min + EPSILON || t >= max - EPSILON {
+                    continue;
+                }
+
+                let point = self.point(t);
+                if (point.u - origin.u).abs() < Scalar::from(EPSILON) {
+                    return Some(RaySegmentIntersection::RayStartsOnSegment);
+                }
+                if point.u > origin.u && !is_tangent {
+                    num_crossings += 1;
+                }
+            }
+        }
+
+        let crossing = match vertex_hits.as_slice() {
+            [] => num_crossings % 2 == 1,
+            // If the arc is closed, the ray passes through its vertex, if the
+            // arc continues to opposite sides of the ray from there.
+            [first, second] if self.vertices[0] == self.vertices[1] => {
+                (num_crossings + usize::from(first != second)) % 2 == 1
+            }
+            // The ray hits a vertex that is shared with a neighboring edge.
+            // Whether that counts as passing through the boundary, depends on
+            // the neighbor.
+            [first, ..] => return Some(*first),
+        };
+
+        crossing.then_some(RaySegmentIntersection::RayHitsSegment)
+    }
+
+    /// Determine whether a vertex is the upper or lower end of the arc
+    fn classify_vertex(&self, i: usize) -> RaySegmentIntersection {
+        let [start, end] = self.range;
+        let t = self.range[i];
+
+        // The direction in which the arc leaves the vertex.
+        let direction = {
+            let (sin, cos) = t.sin_cos();
+            let tangent = self.b * cos - self.a * sin;
+            let towards_other_vertex =
+                if i == 0 { end - start } else { start - end };
+
+            tangent * towards_other_vertex.sign().to_scalar()
+        };
+
+        // If the arc leaves the vertex horizontally, its curvature determines
+        // where it goes.
+        let upwards = if direction.v == Scalar::ZERO {
+            self.vertices[i].v < self.center.v
+        } else {
+            direction.v > Scalar::ZERO
+        };
+
+        if upwards {
+            RaySegmentIntersection::RayHitsLowerVertex
+        } else {
+            RaySegmentIntersection::RayHitsUpperVertex
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        algorithms::intersect::{
+            ray_segment::RaySegmentIntersection, HorizontalRayToTheRight,
+            Intersect,
+        },
+        builder::CycleBuilder,
+        insert::Insert,
+        objects::Cycle,
+        partial::HasPartial,
+        services::Services,
+    };
+
+    #[test]
+    fn ray_and_circle() {
+        let mut services = Services::new();
+
+        let surface = services.objects.surfaces.xy_plane();
+        let cycle = Cycle::partial()
+            .with_circle(surface, [0., 0.], 1., &mut services.objects)
+            .build(&mut services.objects)
+            .insert(&mut services.objects);
+        let edge = cycle.half_edges().next().unwrap();
+
+        let hit = |origin: [f64; 2]| {
+            let ray = HorizontalRayToTheRight::from(origin);
+            (&ray, edge).intersect()
+        };
+
+        assert_eq!(
+            hit([0., 0.5]),
+            Some(RaySegmentIntersection::RayHitsSegment)
+        );
+        assert_eq!(hit([-2., 0.5]), None);
+        assert_eq!(hit([2., 0.5]), None);
+        assert_eq!(hit([0., 2.]), None);
+        assert_eq!(
+            hit([0., 1.]),
+            Some(RaySegmentIntersection::RayStartsOnSegment)
+        );
+
+        // The vertex of the circle is at `[1., 0.]`.
+        assert_eq!(hit([0., 0.]), Some(RaySegmentIntersection::RayHitsSegment));
+        assert_eq!(
+            hit([1., 0.]),
+            Some(RaySegmentIntersection::RayStartsOnOnFirstVertex)
+        );
+    }
+
+    #[test]
+    fn ray_and_arc() {
+        let mut services = Services::new();
+
+        // An arc from `[1., 0.]` over `[0., 1.]` to `[-1., 0.]`, closed by a
+        // line segment.
+        let surface = services.objects.surfaces.xy_plane();
+        let cycle = Cycle::partial()
+            .with_arc_from_points(surface, [[1., 0.], [0., 1.], [-1., 0.]])
+            .close_with_line_segment()
+            .build(&mut services.objects)
+            .insert(&mut services.objects);
+        let arc = cycle.half_edges().next().unwrap();
+
+        let hit = |origin: [f64; 2]| {
+            let ray = HorizontalRayToTheRight::from(origin);
+            (&ray, arc).intersect()
+        };
+
+        assert_eq!(
+            hit([0., 0.5]),
+            Some(RaySegmentIntersection::RayHitsSegment)
+        );
+        assert_eq!(hit([-2., 0.5]), None);
+        assert_eq!(hit([0., -0.5]), None);
+        assert_eq!(
+            hit([0., 0.]),
+            Some(RaySegmentIntersection::RayHitsLowerVertex)
+        );
+    }
+}
@@ -4,7 +4,7 @@ use fj_math::{Plane, Point, Scalar};
 
 use crate::{
     algorithms::intersect::face_point::FacePointIntersection,
-    geometry::path::GlobalPath,
+    geometry::{path::GlobalPath, surface::SurfaceGeometry},
     objects::{Face, HalfEdge, Vertex},
     storage::Handle,
 };
@@ -17,15 +17,30 @@ impl Intersect for (&HorizontalRayToTheRight<3>, &Handle<Face>) {
     fn intersect(self) -> Option<Self::Intersection> {
         let (ray, face) = self;
 
-        let plane = match face.surface().geometry().u {
-            GlobalPath::Circle(_) => todo!(
-                "Casting a ray against a swept circle is not supported yet"
+        let plane = match face.surface().geometry() {
+            SurfaceGeometry::Swept {
+                u: GlobalPath::Line(line),
+                v,
+            } => Plane::from_parametric(line.origin(), line.direction(), v),
+            SurfaceGeometry::Swept {
+                u:
+                    GlobalPath::Circle(_)
+                    | GlobalPath::Ellipse(_)
+                    | GlobalPath::Helix(_)
+                    | GlobalPath::Nurbs(_),
+                ..
+            } => todo!(
+                "Casting a ray against a swept curve is not supported yet"
             ),
-            GlobalPath::Line(line) => Plane::from_parametric(
-                line.origin(),
-                line.direction(),
-                face.surface().geometry().v,
+            SurfaceGeometry::Nurbs(_) => todo!(
+                "Casting a ray against a NURBS surface is not supported yet"
             ),
+            SurfaceGeometry::Ruled { .. } => todo!(
+                "Casting a ray against a ruled surface is not supported yet"
+            ),
+            SurfaceGeometry::Cone(_) => {
+                todo!("Casting a ray against a cone is not supported yet")
+            }
         };
 
         if plane.is_parallel_to_vector(&ray.direction()) {
@@ -0,0 +1,164 @@
+//! Intersection between a ray and a solid
+
+use fj_math::{Point, Scalar, Vector};
+
+use crate::{
+    algorithms::{approx::Tolerance, point_in_solid::face_triangles},
+    objects::{Face, Solid},
+    storage::Handle,
+};
+
+use super::Intersect;
+
+/// A ray in 3D space
+///
+/// Unlike [`HorizontalRayToTheRight`], this ray can point in any direction.
+///
+/// [`HorizontalRayToTheRight`]: super::HorizontalRayToTheRight
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct Ray {
+    /// The point where the ray originates
+    pub origin: Point<3>,
+
+    /// The direction of the ray
+    ///
+    /// Doesn't need to be normalized. Distances along the ray are measured in
+    /// multiples of this vector.
+    pub direction: Vector<3>,
+}
+
+impl Ray {
+    /// Access the point at the given parameter along the ray
+    pub fn point_at(&self, t: impl Into<Scalar>) -> Point<3> {
+        self.origin + self.direction * t.into()
+    }
+}
+
+impl Intersect for (&Ray, &Solid, Tolerance) {
+    type Intersection = RaySolidIntersection;
+
+    fn intersect(self) -> Option<Self::Intersection> {
+        let (ray, solid, tolerance) = self;
+
+        // Hits are deduplicated within the tolerance, which needs to be
+        // expressed in units of the ray parameter.
+        let min_distance = tolerance.inner() / ray.direction.magnitude();
+
+        let mut hits = Vec::new();
+
+        for (face, triangles) in face_triangles(solid, tolerance) {
+            let mut face_hits = triangles
+                .iter()
+                .filter_map(|triangle| {
+                    triangle.cast_local_ray(
+                        ray.origin,
+                        ray.direction,
+                        f64::MAX,
+                        false,
+                    )
+                })
+                .collect::<Vec<_>>();
+
+            // A ray that passes through an edge shared by two triangles of the
+            // same face hits both of them. That's still only one hit on the
+            // face.
+            face_hits.sort();
+            face_hits.dedup_by(|a, b| *a - *b < min_distance);
+
+            hits.extend(face_hits.into_iter().map(|t| RaySolidHit {
+                t,
+                point: ray.point_at(t),
+                face: face.clone(),
+            }));
+        }
+
+        if hits.is_empty() {
+            return None;
+        }
+
+        hits.sort_by_key(|hit| hit.t);
+
+        Some(RaySolidIntersection { hits })
+    }
+}
+
+/// The intersection between a ray and a solid
+///
+/// See [`Intersect`] implementation for `(&Ray, &Solid, Tolerance)`.
+#[derive(Clone, Debug, Eq, PartialEq, Hash)]
+pub struct RaySolidIntersection {
+    /// The points where the ray hits the boundary of the solid
+    ///
+    /// Sorted by their distance from the origin of the ray. If the ray passes
+    /// through an edge or vertex, it hits every face that shares it, and each
+    /// of those hits is listed separately.
+    pub hits: Vec<RaySolidHit>,
+}
+
+/// A point where a ray hits the boundary of a solid
+///
+/// See [`RaySolidIntersection`].
+#[derive(Clone, Debug, Eq, PartialEq, Hash)]
+pub struct RaySolidHit {
+    /// The parameter of the hit along the ray
+    ///
+    /// See [`Ray::point_at`].
+    pub t: Scalar,
+
+    /// The point where the ray hits the face
+    pub point: Point<3>,
+
+    /// The face that the ray hits
+    pub face: Handle<Face>,
+}
+
+#[cfg(test)]
+mod tests {
+    use fj_math::Point;
+
+    use crate::{
+        algorithms::{approx::Tolerance, intersect::Intersect, sweep::Sweep},
+        objects::Sketch,
+        services::Services,
+    };
+
+    use super::Ray;
+
+    #[test]
+    fn ray_through_cube() {
+        let mut services = Services::new();
+
+        let surface = services.objects.surfaces.xy_plane();
+        let cube = Sketch::builder()
+            .with_polygon_from_points(
+                surface,
+                [[-1., -1.], [1., -1.], [1., 1.], [-1., 1.]],
+                &mut services.objects,
+            )
+            .build(&mut services.objects)
+            .sweep([0., 0., 2.], &mut services.objects);
+        let tolerance = Tolerance::from_scalar(0.001).unwrap();
+
+        let ray = Ray {
+            origin: Point::from([-3., 0.5, 0.25]),
+            direction: [1., 0., 0.].into(),
+        };
+        let intersection = (&ray, &*cube, tolerance).intersect().unwrap();
+
+        let points = intersection
+            .hits
+            .iter()
+            .map(|hit| hit.point)
+            .collect::<Vec<_>>();
+        assert_eq!(
+            points,
+            [Point::from([-1., 0.5, 0.25]), Point::from([1., 0.5, 0.25])]
+        );
+
+        let ray = Ray {
+            origin: Point::from([-3., 0.5, 2.5]),
+            direction: [1., 0., 0.].into(),
+        };
+        assert!((&ray, &*cube, tolerance).intersect().is_none());
+    }
+}
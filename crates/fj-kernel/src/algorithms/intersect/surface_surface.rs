@@ -1,7 +1,14 @@
-use fj_math::{Line, Plane, Point, Scalar};
+use std::collections::BTreeMap;
+
+use fj_math::{
+    Circle, Cone, Line, Nurbs, NurbsSurface, Plane, Point, Scalar, Vector,
+};
 
 use crate::{
-    geometry::path::{GlobalPath, SurfacePath},
+    geometry::{
+        path::{GlobalPath, SurfacePath},
+        surface::SurfaceGeometry,
+    },
     insert::Insert,
     objects::{Curve, GlobalCurve, Objects, Surface},
     services::Service,
@@ -17,80 +24,434 @@ pub struct SurfaceSurfaceIntersection {
 
 impl SurfaceSurfaceIntersection {
     /// Compute the intersection between two surfaces
+    ///
+    /// Intersections between two planes, between a plane and a NURBS surface,
+    /// and between a plane and a cone are supported. See [`plane_nurbs`] and
+    /// [`plane_cone`] for the limitations of the latter two.
     pub fn compute(
         surfaces: [Handle<Surface>; 2],
         objects: &mut Service<Objects>,
     ) -> Option<Self> {
-        // Algorithm from Real-Time Collision Detection by Christer Ericson. See
-        // section 5.4.4, Intersection of Two Planes.
+        let [a, b] = surfaces.clone().map(|surface| surface.geometry());
+
+        let paths = match (plane_from_surface(&a), plane_from_surface(&b)) {
+            (Some(a), Some(b)) => plane_plane([a, b])?,
+            (Some(plane), None) => match b {
+                SurfaceGeometry::Nurbs(nurbs) => plane_nurbs(&plane, &nurbs)?,
+                SurfaceGeometry::Cone(cone) => plane_cone(&plane, &cone)?,
+                SurfaceGeometry::Swept { .. }
+                | SurfaceGeometry::Ruled { .. } => unsupported(),
+            },
+            (None, Some(plane)) => match a {
+                SurfaceGeometry::Nurbs(nurbs) => {
+                    let [on_plane, on_nurbs] = plane_nurbs(&plane, &nurbs)?;
+                    [on_nurbs, on_plane]
+                }
+                SurfaceGeometry::Cone(cone) => {
+                    let [on_plane, on_cone] = plane_cone(&plane, &cone)?;
+                    [on_cone, on_plane]
+                }
+                SurfaceGeometry::Swept { .. }
+                | SurfaceGeometry::Ruled { .. } => unsupported(),
+            },
+            (None, None) => unsupported(),
+        };
+
+        let [surface_a, surface_b] = surfaces;
+        let [path_a, path_b] = paths;
+
+        let curves = [(surface_a, path_a), (surface_b, path_b)].map(
+            |(surface, path)| {
+                let global_form = GlobalCurve.insert(objects);
+                Curve::new(surface, path, global_form).insert(objects)
+            },
+        );
+
+        Some(Self {
+            intersection_curves: curves,
+        })
+    }
+}
+
+fn unsupported() -> ! {
+    todo!(
+        "Only plane-plane, plane-NURBS, and plane-cone intersection is \
+        currently supported."
+    )
+}
+
+fn plane_plane(planes: [Plane; 2]) -> Option<[SurfacePath; 2]> {
+    // Algorithm from Real-Time Collision Detection by Christer Ericson. See
+    // section 5.4.4, Intersection of Two Planes.
+    //
+    // Adaptations were made to get the intersection curves in local
+    // coordinates for each surface.
+
+    let [a, b] = planes;
+
+    let (a_distance, a_normal) = a.constant_normal_form();
+    let (b_distance, b_normal) = b.constant_normal_form();
+
+    let direction = a_normal.cross(&b_normal);
+
+    let denom = direction.dot(&direction);
+    if denom == Scalar::ZERO {
+        // Comparing `denom` against zero looks fishy. It's probably better
+        // to compare it against an epsilon value, but I don't know how
+        // large that epsilon should be.
         //
-        // Adaptations were made to get the intersection curves in local
-        // coordinates for each surface.
+        // I'll just leave it like that, until we had the opportunity to
+        // collect some experience with this code.
+        // - @hannobraun
+        return None;
+    }
 
-        let surfaces_and_planes = surfaces.map(|surface| {
-            let plane = plane_from_surface(&surface);
-            (surface, plane)
-        });
-        let [a, b] = surfaces_and_planes.clone().map(|(_, plane)| plane);
-
-        let (a_distance, a_normal) = a.constant_normal_form();
-        let (b_distance, b_normal) = b.constant_normal_form();
-
-        let direction = a_normal.cross(&b_normal);
-
-        let denom = direction.dot(&direction);
-        if denom == Scalar::ZERO {
-            // Comparing `denom` against zero looks fishy. It's probably better
-            // to compare it against an epsilon value, but I don't know how
-            // large that epsilon should be.
-            //
-            // I'll just leave it like that, until we had the opportunity to
-            // collect some experience with this code.
-            // - @hannobraun
+    let origin = (b_normal * a_distance - a_normal * b_distance)
+        .cross(&direction)
+        / denom;
+    let origin = Point { coords: origin };
+
+    let line = Line::from_origin_and_direction(origin, direction);
+
+    Some(planes.map(|plane| SurfacePath::Line(plane.project_line(&line))))
+}
+
+/// Intersect a plane with a NURBS surface
+///
+/// Returns the intersection curve on the plane, followed by the intersection
+/// curve on the NURBS surface. Both are polylines, represented as NURBS curves
+/// of degree one, whose points are on the exact intersection.
+///
+/// The surface is sampled on a grid, to find the intersection. Parts of the
+/// intersection that are smaller than a grid cell might be missed. If the
+/// intersection consists of multiple curves, only the one that is made up of
+/// the most points is returned.
+pub(super) fn plane_nurbs(
+    plane: &Plane,
+    nurbs: &NurbsSurface<3>,
+) -> Option<[SurfacePath; 2]> {
+    const SUBDIVISIONS_PER_SPAN: usize = 16;
+    const REFINEMENT_ITERATIONS: usize = 8;
+
+    let (distance, normal) = plane.constant_normal_form();
+    let signed_distance = |point: Point<2>| -> Scalar {
+        normal.dot(&nurbs.point_from_surface_coords(point).coords) - distance
+    };
+
+    let [params_u, params_v] = nurbs.spans().map(|spans| {
+        let mut params = vec![spans[0]];
+        for span in spans.windows(2) {
+            let [start, end] = [span[0], span[1]];
+            params.extend((1..=SUBDIVISIONS_PER_SPAN).map(|i| {
+                let f = Scalar::from(i as f64)
+                    / Scalar::from(SUBDIVISIONS_PER_SPAN as f64);
+                start + (end - start) * f
+            }));
+        }
+        params
+    });
+
+    let grid_point =
+        |[i, j]: [usize; 2]| Point::from([params_u[i], params_v[j]]);
+    let values = (0..params_u.len())
+        .map(|i| {
+            (0..params_v.len())
+                .map(|j| signed_distance(grid_point([i, j])))
+                .collect::<Vec<_>>()
+        })
+        .collect::<Vec<_>>();
+
+    // Treating zero as positive makes sure that each grid edge is crossed at
+    // most once.
+    let is_positive = |[i, j]: [usize; 2]| values[i][j] >= Scalar::ZERO;
+
+    // Find the crossings of each grid cell, using marching squares. Crossings
+    // are identified by the grid edge they are on, which is given by its two
+    // grid points.
+    let mut segments = Vec::new();
+    for i in 0..params_u.len() - 1 {
+        for j in 0..params_v.len() - 1 {
+            let corners = [[i, j], [i + 1, j], [i + 1, j + 1], [i, j + 1]];
+
+            let crossings = (0..4)
+                .map(|k| [corners[k], corners[(k + 1) % 4]])
+                .filter(|&[a, b]| is_positive(a) != is_positive(b))
+                .map(|edge| {
+                    let mut edge = edge;
+                    edge.sort();
+                    edge
+                })
+                .collect::<Vec<_>>();
+
+            match crossings.as_slice() {
+                [a, b] => segments.push([*a, *b]),
+                [a, b, c, d] => {
+                    // A saddle point. Which crossings are connected depends
+                    // on the value in the middle of the cell.
+                    let [u, v] = [
+                        (params_u[i] + params_u[i + 1]) / 2.,
+                        (params_v[j] + params_v[j + 1]) / 2.,
+                    ];
+                    let middle_is_positive =
+                        signed_distance(Point::from([u, v])) >= Scalar::ZERO;
+
+                    // If the middle is on the same side as the first corner,
+                    // the crossings cut off the two other corners.
+                    if middle_is_positive == is_positive(corners[0]) {
+                        segments.push([*a, *b]);
+                        segments.push([*c, *d]);
+                    } else {
+                        segments.push([*d, *a]);
+                        segments.push([*b, *c]);
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+
+    let chain = longest_chain(&segments)?;
+
+    let points_nurbs = chain
+        .into_iter()
+        .map(|[a, b]| {
+            // Start with the linear interpolation of the crossing, then
+            // refine it on the actual surface using Newton's method.
+            let [value_a, value_b] = [a, b].map(|[i, j]| values[i][j]);
+            let [a, b] = [a, b].map(grid_point);
+            let f = value_a / (value_a - value_b);
+            let mut point = a + (b - a) * f;
+
+            for _ in 0..REFINEMENT_ITERATIONS {
+                let value = signed_distance(point);
+                let [du, dv] = nurbs
+                    .derivatives_at(point)
+                    .map(|derivative| derivative.dot(&normal));
+
+                let gradient_squared = du * du + dv * dv;
+                if gradient_squared == Scalar::ZERO {
+                    break;
+                }
+
+                let step = value / gradient_squared;
+                point.u -= du * step;
+                point.v -= dv * step;
+            }
+
+            point
+        })
+        .collect::<Vec<_>>();
+
+    let points_plane = points_nurbs.iter().map(|&point| {
+        point_to_plane_coords(plane, nurbs.point_from_surface_coords(point))
+    });
+
+    Some([
+        SurfacePath::Nurbs(Nurbs::from_control_points(1, points_plane)),
+        SurfacePath::Nurbs(Nurbs::from_control_points(1, points_nurbs)),
+    ])
+}
+
+/// Intersect a plane with a cone
+///
+/// Returns the intersection curve on the plane, followed by the intersection
+/// curve on the cone.
+///
+/// Planes that are perpendicular to the axis intersect the cone along a circle,
+/// planes through the apex along straight lines. Those intersections are
+/// exact. If there are two lines, like with [`plane_nurbs`], only one of them
+/// is returned. Other planes intersect the cone along an ellipse, which is
+/// approximated by a polyline, whose points are on the exact intersection.
+///
+/// Returns `None`, if the intersection is a parabola or a hyperbola. Those run
+/// off to infinity, and can't be represented without knowing which part of the
+/// cone is of interest.
+pub(super) fn plane_cone(
+    plane: &Plane,
+    cone: &Cone,
+) -> Option<[SurfacePath; 2]> {
+    const NUM_POINTS: usize = 64;
+    const EPSILON: f64 = 1e-12;
+
+    // The signed distance of a point on the cone from the plane is
+    // `k + v * (w + p * cos(u) + q * sin(u))`. The sum in parentheses can be
+    // written as `w + r * cos(u - phi)`.
+    let (distance, normal) = plane.constant_normal_form();
+    let k = normal.dot(&cone.apex().coords) - distance;
+    let [p, q, w] = [cone.a(), cone.b(), cone.axis()].map(|v| normal.dot(&v));
+    let r = Vector::from([p, q]).magnitude();
+    let phi = q.atan2(p);
+
+    if k.abs() <= Scalar::from(EPSILON) * distance.abs().max(Scalar::ONE) {
+        // The plane goes through the apex. It intersects the cone along the
+        // lines from the apex, where `w + r * cos(u - phi)` is zero.
+        if w.abs() > r {
             return None;
         }
 
-        let origin = (b_normal * a_distance - a_normal * b_distance)
-            .cross(&direction)
-            / denom;
-        let origin = Point { coords: origin };
+        let u = phi + (-w / r).max(-Scalar::ONE).min(Scalar::ONE).acos();
+        let u = Scalar::from(u.into_f64().rem_euclid(Scalar::TAU.into_f64()));
 
-        let line = Line::from_origin_and_direction(origin, direction);
+        let (sin, cos) = u.sin_cos();
+        let direction = cone.axis() + cone.a() * cos + cone.b() * sin;
 
-        let curves = surfaces_and_planes.map(|(surface, plane)| {
-            let path = SurfacePath::Line(plane.project_line(&line));
-            let global_form = GlobalCurve.insert(objects);
+        return Some([
+            SurfacePath::Line(Line::from_origin_and_direction(
+                point_to_plane_coords(plane, cone.apex()),
+                vector_to_plane_coords(plane, direction),
+            )),
+            SurfacePath::Line(Line::from_origin_and_direction(
+                Point::from([u, Scalar::ZERO]),
+                Vector::from([0., 1.]),
+            )),
+        ]);
+    }
 
-            Curve::new(surface, path, global_form).insert(objects)
-        });
+    // Where `w + r * cos(u - phi)` can become zero, the intersection is open.
+    if w.abs() <= r {
+        return None;
+    }
 
-        Some(Self {
-            intersection_curves: curves,
+    if r <= w.abs() * Scalar::from(EPSILON) {
+        // The plane is perpendicular to the axis. It intersects the cone along
+        // a circle, which is a line of constant v in surface coordinates.
+        let v = -k / w;
+        let center =
+            point_to_plane_coords(plane, cone.apex() + cone.axis() * v);
+        let [a, b] = [cone.a(), cone.b()]
+            .map(|vector| vector_to_plane_coords(plane, vector * v));
+
+        // The circle might be distorted, if the axes of the plane aren't
+        // perpendicular, or of different length.
+        let is_circle = (a.magnitude() - b.magnitude()).abs()
+            <= a.magnitude() * Scalar::from(EPSILON)
+            && a.dot(&b).abs() <= a.magnitude() * Scalar::from(EPSILON);
+        if is_circle {
+            return Some([
+                SurfacePath::Circle(Circle::new(center, a, b)),
+                SurfacePath::Line(Line::from_origin_and_direction(
+                    Point::from([Scalar::ZERO, v]),
+                    Vector::from([1., 0.]),
+                )),
+            ]);
+        }
+    }
+
+    let points_cone = (0..=NUM_POINTS)
+        .map(|i| {
+            let u = Scalar::TAU * Scalar::from(i as f64)
+                / Scalar::from(NUM_POINTS as f64);
+            let v = -k / (w + r * (u - phi).cos());
+            Point::from([u, v])
         })
+        .collect::<Vec<_>>();
+    let points_plane = points_cone.iter().map(|&point| {
+        point_to_plane_coords(plane, cone.point_from_surface_coords(point))
+    });
+
+    Some([
+        SurfacePath::Nurbs(Nurbs::from_control_points(1, points_plane)),
+        SurfacePath::Nurbs(Nurbs::from_control_points(1, points_cone)),
+    ])
+}
+
+/// Connect segments into chains, returning the longest one
+///
+/// Returns `None`, if there are no segments.
+fn longest_chain<T: Copy + Ord>(segments: &[[T; 2]]) -> Option<Vec<T>> {
+    let mut neighbors: BTreeMap<T, Vec<usize>> = BTreeMap::new();
+    for (index, &[a, b]) in segments.iter().enumerate() {
+        neighbors.entry(a).or_default().push(index);
+        neighbors.entry(b).or_default().push(index);
     }
+
+    // Open chains need to be followed from one of their ends. Start there,
+    // before starting anywhere within closed chains.
+    let mut starts = neighbors
+        .iter()
+        .filter(|(_, segments)| segments.len() == 1)
+        .map(|(&point, _)| point)
+        .collect::<Vec<_>>();
+    starts.extend(segments.iter().map(|&[a, _]| a));
+
+    let mut visited = vec![false; segments.len()];
+    let mut longest: Option<Vec<T>> = None;
+
+    for start in starts {
+        let mut chain = vec![start];
+        let mut current = start;
+
+        while let Some(&index) =
+            neighbors[&current].iter().find(|&&index| !visited[index])
+        {
+            visited[index] = true;
+
+            let [a, b] = segments[index];
+            current = if a == current { b } else { a };
+            chain.push(current);
+        }
+
+        if chain.len() > longest.as_ref().map(Vec::len).unwrap_or(1) {
+            longest = Some(chain);
+        }
+    }
+
+    longest
 }
 
-fn plane_from_surface(surface: &Surface) -> Plane {
-    let (line, path) = {
-        let line = match surface.geometry().u {
-            GlobalPath::Line(line) => line,
-            _ => todo!("Only plane-plane intersection is currently supported."),
-        };
+/// Convert a point into the coordinates of a plane
+pub(super) fn point_to_plane_coords(
+    plane: &Plane,
+    point: Point<3>,
+) -> Point<2> {
+    Point {
+        coords: vector_to_plane_coords(plane, point - plane.origin()),
+    }
+}
 
-        (line, surface.geometry().v)
-    };
+/// Convert a vector into the coordinates of a plane
+///
+/// The axes of the plane don't need to be perpendicular to each other, or be
+/// of unit length.
+pub(super) fn vector_to_plane_coords(
+    plane: &Plane,
+    vector: Vector<3>,
+) -> Vector<2> {
+    let [u, v] = [plane.u(), plane.v()];
+
+    let [uu, uv, vv] = [u.dot(&u), u.dot(&v), v.dot(&v)];
+    let [wu, wv] = [vector.dot(&u), vector.dot(&v)];
+    let det = uu * vv - uv * uv;
+
+    Vector::from([(wu * vv - wv * uv) / det, (wv * uu - wu * uv) / det])
+}
 
-    Plane::from_parametric(line.origin(), line.direction(), path)
+pub(super) fn plane_from_surface(surface: &SurfaceGeometry) -> Option<Plane> {
+    match surface {
+        SurfaceGeometry::Swept {
+            u: GlobalPath::Line(line),
+            v,
+        } => Some(Plane::from_parametric(line.origin(), line.direction(), *v)),
+        _ => None,
+    }
 }
 
 #[cfg(test)]
 mod tests {
-    use fj_math::Transform;
+    use std::f64::consts::FRAC_1_SQRT_2;
+
+    use fj_math::{Cone, NurbsSurface, Point, Scalar, Transform};
     use pretty_assertions::assert_eq;
 
     use crate::{
-        algorithms::transform::TransformObject, builder::CurveBuilder,
-        insert::Insert, partial::PartialCurve, services::Services,
+        algorithms::transform::TransformObject,
+        builder::{CurveBuilder, SurfaceBuilder},
+        geometry::path::SurfacePath,
+        insert::Insert,
+        objects::{Curve, Objects, Surface},
+        partial::{PartialCurve, PartialSurface},
+        services::{Service, Services},
+        storage::Handle,
     };
 
     use super::SurfaceSurfaceIntersection;
@@ -144,4 +505,206 @@ mod tests {
             })
         );
     }
+
+    #[test]
+    fn plane_nurbs() {
+        let mut services = Services::new();
+
+        // A quarter of a cylinder around the z-axis, with a radius of two.
+        let w = FRAC_1_SQRT_2;
+        let cylinder = PartialSurface::from_nurbs(NurbsSurface::new(
+            [2, 1],
+            [
+                [[2., 0., 0.], [2., 0., 1.]],
+                [[2., 2., 0.], [2., 2., 1.]],
+                [[0., 2., 0.], [0., 2., 1.]],
+            ],
+            [[1., 1.], [w, w], [1., 1.]],
+            [vec![0., 0., 0., 1., 1., 1.], vec![0., 0., 1., 1.]],
+        ))
+        .build(&services.objects)
+        .insert(&mut services.objects);
+        let plane = services.objects.surfaces.xy_plane().transform(
+            &Transform::translation([0., 0., 0.3]),
+            &mut services.objects,
+        );
+
+        let intersection = SurfaceSurfaceIntersection::compute(
+            [plane, cylinder],
+            &mut services.objects,
+        )
+        .expect("Expected intersection");
+
+        // The intersection is a quarter circle, at the height of the plane.
+        // It's approximated by a polyline, whose points are on the circle.
+        for curve in intersection.intersection_curves {
+            let surface = curve.surface().geometry();
+            let nurbs = match curve.path() {
+                SurfacePath::Nurbs(nurbs) => nurbs,
+                path => panic!("Expected NURBS curve, got {path:?}"),
+            };
+
+            assert!(nurbs.control_points().len() > 2);
+            for &point in nurbs.control_points() {
+                let point = surface.point_from_surface_coords(point);
+
+                assert!((point.z - 0.3).abs() < Scalar::from(1e-12));
+                assert!(
+                    (point.coords.xy().magnitude() - 2.).abs()
+                        < Scalar::from(1e-12)
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn plane_cone_circle() {
+        let mut services = Services::new();
+
+        let cone = cone(&mut services.objects);
+        let plane = services.objects.surfaces.xy_plane().transform(
+            &Transform::translation([0., 0., 0.5]),
+            &mut services.objects,
+        );
+
+        let intersection = SurfaceSurfaceIntersection::compute(
+            [plane, cone],
+            &mut services.objects,
+        )
+        .expect("Expected intersection");
+
+        let [on_plane, on_cone] = intersection.intersection_curves;
+        match on_plane.path() {
+            SurfacePath::Circle(circle) => {
+                assert_eq!(circle.center(), Point::from([0., 0.]));
+                assert_eq!(circle.radius(), Scalar::from(0.5));
+            }
+            path => panic!("Expected circle, got {path:?}"),
+        }
+        match on_cone.path() {
+            SurfacePath::Line(line) => {
+                assert_eq!(line.origin(), Point::from([0., 0.5]));
+                assert_eq!(line.direction().v, Scalar::ZERO);
+            }
+            path => panic!("Expected line, got {path:?}"),
+        }
+    }
+
+    #[test]
+    fn plane_cone_through_apex() {
+        let mut services = Services::new();
+
+        let cone = cone(&mut services.objects);
+        let plane = services.objects.surfaces.xz_plane();
+
+        let intersection = SurfaceSurfaceIntersection::compute(
+            [cone, plane],
+            &mut services.objects,
+        )
+        .expect("Expected intersection");
+
+        // The intersection is a line of the cone, which lies in the plane.
+        let [on_cone, on_plane] = intersection.intersection_curves;
+        assert!(matches!(on_cone.path(), SurfacePath::Line(_)));
+        assert!(matches!(on_plane.path(), SurfacePath::Line(_)));
+        for t in [0., 0.5, 1.] {
+            let a = point_on_curve(&on_cone, t);
+            let b = point_on_curve(&on_plane, t);
+
+            assert!(a.y.abs() < Scalar::from(1e-12));
+            assert!((a - b).magnitude() < Scalar::from(1e-12));
+        }
+    }
+
+    #[test]
+    fn plane_cone_ellipse() {
+        let mut services = Services::new();
+
+        let cone = cone(&mut services.objects);
+        let plane = PartialSurface::plane_from_points([
+            [0., 0., 0.5],
+            [1., 0., 0.7],
+            [0., 1., 0.5],
+        ])
+        .build(&services.objects)
+        .insert(&mut services.objects);
+
+        let intersection = SurfaceSurfaceIntersection::compute(
+            [plane.clone(), cone.clone()],
+            &mut services.objects,
+        )
+        .expect("Expected intersection");
+
+        // The ellipse is approximated by a polyline, whose points are on both
+        // surfaces.
+        let [on_plane, on_cone] = intersection.intersection_curves;
+        let [on_plane, on_cone] = [on_plane, on_cone].map(|curve| {
+            let surface = curve.surface().geometry();
+            let nurbs = match curve.path() {
+                SurfacePath::Nurbs(nurbs) => nurbs,
+                path => panic!("Expected NURBS curve, got {path:?}"),
+            };
+
+            nurbs
+                .control_points()
+                .iter()
+                .map(|&point| surface.point_from_surface_coords(point))
+                .collect::<Vec<_>>()
+        });
+
+        assert!(on_cone.len() > 2);
+        assert_eq!(on_plane.len(), on_cone.len());
+        for (a, b) in on_plane.into_iter().zip(on_cone) {
+            assert!((a - b).magnitude() < Scalar::from(1e-12));
+
+            // The plane rises by 0.2 per unit along x, and the radius of the
+            // cone grows with the height.
+            assert!((a.z - 0.5 - a.x * 0.2).abs() < Scalar::from(1e-12));
+            assert!(
+                (a.coords.xy().magnitude() - a.z).abs() < Scalar::from(1e-12)
+            );
+        }
+    }
+
+    #[test]
+    fn plane_cone_parabola() {
+        let mut services = Services::new();
+
+        // The plane is parallel to one of the lines of the cone, which means
+        // the intersection is a parabola.
+        let cone = cone(&mut services.objects);
+        let plane = PartialSurface::plane_from_points([
+            [0., 0., 1.],
+            [1., 0., 2.],
+            [0., 1., 1.],
+        ])
+        .build(&services.objects)
+        .insert(&mut services.objects);
+
+        assert_eq!(
+            SurfaceSurfaceIntersection::compute(
+                [plane, cone],
+                &mut services.objects
+            ),
+            None,
+        );
+    }
+
+    /// A cone around the z-axis, with its apex at the origin, whose radius
+    /// grows by one per unit of height
+    fn cone(objects: &mut Service<Objects>) -> Handle<Surface> {
+        PartialSurface::from_cone(Cone::new(
+            [0., 0., 0.],
+            [1., 0., 0.],
+            [0., 1., 0.],
+            [0., 0., 1.],
+        ))
+        .build(objects)
+        .insert(objects)
+    }
+
+    fn point_on_curve(curve: &Curve, t: f64) -> Point<3> {
+        let point = curve.path().point_from_path_coords([t]);
+        curve.surface().geometry().point_from_surface_coords(point)
+    }
 }
@@ -0,0 +1,449 @@
+//! Lofting between profiles to create a solid
+//!
+//! A loft connects a sequence of closed profiles with side faces. The profiles
+//! are given as cycles, which can be defined on arbitrary (but planar)
+//! surfaces.
+
+use fj_interop::{
+    ext::SliceExt,
+    mesh::{Attributes, Color},
+};
+use fj_math::{Point, Scalar, Vector, Winding};
+
+use crate::{
+    algorithms::{
+        approx::{Approx, Tolerance},
+        polyhedron::polygon_normal,
+        reverse::Reverse,
+    },
+    builder::{CycleBuilder, SurfaceBuilder},
+    geometry::{
+        path::{GlobalPath, SurfacePath},
+        surface::SurfaceGeometry,
+    },
+    insert::Insert,
+    objects::{Cycle, Face, Objects, Shell, Solid, Surface},
+    partial::{
+        HasPartial, PartialGlobalVertex, PartialSurface, PartialSurfaceVertex,
+    },
+    services::Service,
+    storage::Handle,
+};
+
+/// Loft between the provided profiles
+///
+/// The profiles are connected in the order they are provided in. The first
+/// vertex of each profile is connected to the first vertex of the next, and so
+/// on. All profiles must consist of the same number of straight half-edges.
+///
+/// The side faces are made up of planar triangles. See [`LoftInterpolation`]
+/// for how they are arranged between the profiles. The first and last profile
+/// are used to close the solid.
+pub fn loft(
+    profiles: &[Handle<Cycle>],
+    interpolation: LoftInterpolation,
+    color: Color,
+    attributes: Attributes,
+    objects: &mut Service<Objects>,
+) -> Result<Handle<Solid>, LoftError> {
+    let (first, last) = match profiles {
+        [first, .., last] => (first, last),
+        _ => return Err(LoftError::NotEnoughProfiles),
+    };
+
+    let mut sections = profiles
+        .iter()
+        .map(profile_points)
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let num_vertices = sections[0].len();
+    for section in &sections {
+        if section.len() != num_vertices {
+            return Err(LoftError::VertexCountMismatch {
+                expected: num_vertices,
+                actual: section.len(),
+            });
+        }
+    }
+
+    let direction =
+        centroid(&sections[sections.len() - 1]) - centroid(&sections[0]);
+    if direction.magnitude() == Scalar::ZERO {
+        return Err(LoftError::CoincidentProfiles);
+    }
+
+    // Make sure that all profiles wind counter-clockwise, when looking at them
+    // against the direction of the loft. That way, the side faces built from
+    // them point outwards.
+    for section in &mut sections {
+        if polygon_normal(section).dot(&direction) < Scalar::ZERO {
+            section[1..].reverse();
+        }
+    }
+
+    let sections = match interpolation {
+        LoftInterpolation::Ruled => sections,
+        LoftInterpolation::Smooth { subdivisions } => {
+            interpolate_smooth(&sections, subdivisions)
+        }
+    };
+
+    let mut faces = Vec::new();
+    for (profile, outward) in [(first, -direction), (last, direction)] {
+        let face = Face::partial()
+            .with_exterior(profile.clone())
+            .with_color(color)
+            .with_attributes(attributes.clone())
+            .build(objects)
+            .insert(objects);
+        faces.push(cap(face, outward, objects)?);
+    }
+    faces.extend(side_faces(&sections, color, &attributes, objects));
+
+    let shell = Shell::builder().with_faces(faces).build(objects);
+    Ok(Solid::builder().with_shells([shell]).build(objects))
+}
+
+/// Build the side faces that connect consecutive sections of a loft
+///
+/// Each section is a closed polygon, and all sections have the same number of
+/// vertices. The side faces point outwards, if the sections wind
+/// counter-clockwise when looking at them against the direction of the loft.
+pub(crate) fn side_faces(
+    sections: &[Vec<Point<3>>],
+    color: Color,
+    attributes: &Attributes,
+    objects: &mut Service<Objects>,
+) -> Vec<Handle<Face>> {
+    let num_vertices = sections[0].len();
+
+    let mut faces = Vec::new();
+    for [bottom, top] in sections.array_windows_ext() {
+        for i in 0..num_vertices {
+            let j = (i + 1) % num_vertices;
+
+            let triangles =
+                [[bottom[i], bottom[j], top[j]], [bottom[i], top[j], top[i]]];
+            for points in triangles {
+                if let Some(face) = triangle(points, color, attributes, objects)
+                {
+                    faces.push(face);
+                }
+            }
+        }
+    }
+
+    faces
+}
+
+/// How the side faces of a loft are interpolated between profiles
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum LoftInterpolation {
+    /// Connect each profile to the next one with straight lines
+    Ruled,
+
+    /// Interpolate smoothly through all profiles
+    ///
+    /// The vertices of the profiles are interpolated using Catmull-Rom splines.
+    /// `subdivisions` intermediate sections are inserted between each pair of
+    /// consecutive profiles.
+    Smooth {
+        /// The number of sections inserted between each pair of profiles
+        subdivisions: usize,
+    },
+}
+
+/// An error that can occur while lofting
+#[derive(Clone, Debug, Eq, PartialEq, thiserror::Error)]
+pub enum LoftError {
+    /// Less than two profiles were provided
+    #[error("Loft requires at least two profiles")]
+    NotEnoughProfiles,
+
+    /// The profiles don't have the same number of vertices
+    #[error(
+        "Profiles of loft must have the same number of vertices (expected \
+        {expected}, found {actual})"
+    )]
+    VertexCountMismatch {
+        /// The number of vertices of the first profile
+        expected: usize,
+
+        /// The number of vertices of the mismatched profile
+        actual: usize,
+    },
+
+    /// A profile contains a curved half-edge
+    #[error("Profiles of loft must only consist of straight half-edges")]
+    CurvedProfile,
+
+    /// A profile is not defined on a plane
+    #[error("Profiles of loft must be defined on planes")]
+    NonPlanarProfile,
+
+    /// The first and last profile are in the same place
+    #[error("First and last profile of loft must not coincide")]
+    CoincidentProfiles,
+}
+
+/// Approximate a profile with a polygon
+///
+/// Returns the profile itself, if it only consists of straight half-edges.
+pub(crate) fn polygon_from_cycle(
+    profile: &Handle<Cycle>,
+    tolerance: Tolerance,
+    objects: &mut Service<Objects>,
+) -> Handle<Cycle> {
+    let is_polygon = profile.half_edges().all(|half_edge| {
+        matches!(half_edge.curve().path(), SurfacePath::Line(_))
+    });
+    if is_polygon {
+        return profile.clone();
+    }
+
+    let mut points = (&**profile)
+        .approx(tolerance)
+        .points()
+        .into_iter()
+        .map(|point| point.local_form)
+        .collect::<Vec<_>>();
+
+    // The approximation repeats the first point at the end.
+    points.pop();
+
+    Cycle::partial()
+        .with_poly_chain_from_points(profile.surface().clone(), points)
+        .close_with_line_segment()
+        .build(objects)
+        .insert(objects)
+}
+
+pub(crate) fn profile_points(
+    profile: &Handle<Cycle>,
+) -> Result<Vec<Point<3>>, LoftError> {
+    profile
+        .half_edges()
+        .map(|half_edge| match half_edge.curve().path() {
+            SurfacePath::Line(_) => {
+                Ok(half_edge.back().global_form().position())
+            }
+            SurfacePath::Circle(_)
+            | SurfacePath::Ellipse(_)
+            | SurfacePath::Nurbs(_) => Err(LoftError::CurvedProfile),
+        })
+        .collect()
+}
+
+/// Make sure a face that closes a loft points in the `outward` direction
+pub(crate) fn cap(
+    face: Handle<Face>,
+    outward: Vector<3>,
+    objects: &mut Service<Objects>,
+) -> Result<Handle<Face>, LoftError> {
+    let normal = {
+        let normal = surface_normal(face.surface())?;
+        match face.exterior().winding() {
+            Winding::Ccw => normal,
+            Winding::Cw => -normal,
+        }
+    };
+
+    if normal.dot(&outward) < Scalar::ZERO {
+        Ok(face.reverse(objects))
+    } else {
+        Ok(face)
+    }
+}
+
+fn triangle(
+    points: [Point<3>; 3],
+    color: Color,
+    attributes: &Attributes,
+    objects: &mut Service<Objects>,
+) -> Option<Handle<Face>> {
+    let [a, b, c] = points;
+    if (b - a).cross(&(c - a)).magnitude() == Scalar::ZERO {
+        // This can happen, if profiles share vertices. There's no need for a
+        // face in that case.
+        return None;
+    }
+
+    let surface = PartialSurface::plane_from_points(points)
+        .build(objects)
+        .insert(objects);
+
+    // Converting the surface coordinates back into global ones isn't exact.
+    // Triangles share their vertices with their neighbors, so use the exact
+    // positions instead.
+    let [a, b, c] = points;
+    let vertices = [([0., 0.], a), ([1., 0.], b), ([0., 1.], c)].map(
+        |(position, point)| PartialSurfaceVertex {
+            position: Some(position.into()),
+            surface: Some(surface.clone()),
+            global_form: PartialGlobalVertex {
+                position: Some(point),
+            }
+            .into(),
+        },
+    );
+
+    let face = Face::partial()
+        .with_exterior(
+            Cycle::partial()
+                .with_poly_chain(vertices)
+                .close_with_line_segment(),
+        )
+        .with_color(color)
+        .with_attributes(attributes.clone())
+        .build(objects)
+        .insert(objects);
+
+    Some(face)
+}
+
+fn surface_normal(surface: &Surface) -> Result<Vector<3>, LoftError> {
+    match surface.geometry() {
+        SurfaceGeometry::Swept {
+            u: GlobalPath::Line(line),
+            v,
+        } => Ok(line.direction().cross(&v)),
+        _ => Err(LoftError::NonPlanarProfile),
+    }
+}
+
+fn centroid(points: &[Point<3>]) -> Point<3> {
+    let sum = points
+        .iter()
+        .fold(Vector::from([0., 0., 0.]), |sum, point| sum + point.coords);
+    Point::origin() + sum / points.len() as f64
+}
+
+fn interpolate_smooth(
+    sections: &[Vec<Point<3>>],
+    subdivisions: usize,
+) -> Vec<Vec<Point<3>>> {
+    let last = sections.len() - 1;
+    let mut interpolated = Vec::new();
+
+    for i in 0..last {
+        let p0 = &sections[i.saturating_sub(1)];
+        let p1 = &sections[i];
+        let p2 = &sections[i + 1];
+        let p3 = &sections[(i + 2).min(last)];
+
+        interpolated.push(p1.clone());
+
+        for step in 1..=subdivisions {
+            let t = Scalar::from(step as f64 / (subdivisions + 1) as f64);
+
+            let section = (0..p1.len())
+                .map(|j| catmull_rom([p0[j], p1[j], p2[j], p3[j]], t))
+                .collect();
+            interpolated.push(section);
+        }
+    }
+
+    interpolated.push(sections[last].clone());
+    interpolated
+}
+
+/// Evaluate a uniform Catmull-Rom spline between `p1` and `p2`
+fn catmull_rom([p0, p1, p2, p3]: [Point<3>; 4], t: Scalar) -> Point<3> {
+    let [p0, p1, p2, p3] = [p0, p1, p2, p3].map(|point| point.coords);
+    let t2 = t * t;
+    let t3 = t2 * t;
+
+    let coords = (p1 * 2.
+        + (p2 - p0) * t
+        + (p0 * 2. - p1 * 5. + p2 * 4. - p3) * t2
+        + (-p0 + p1 * 3. - p2 * 3. + p3) * t3)
+        / 2.;
+
+    Point { coords }
+}
+
+#[cfg(test)]
+mod tests {
+    use fj_interop::mesh::{Attributes, Color};
+
+    use crate::{
+        algorithms::{
+            approx::Tolerance, transform::TransformObject,
+            triangulate::Triangulate,
+        },
+        builder::CycleBuilder,
+        insert::Insert,
+        iter::ObjectIters,
+        objects::Cycle,
+        partial::HasPartial,
+        services::Services,
+    };
+
+    use super::{loft, LoftError, LoftInterpolation};
+
+    const SQUARE: [[f64; 2]; 4] = [[-1., -1.], [1., -1.], [1., 1.], [-1., 1.]];
+
+    #[test]
+    fn loft_between_squares() -> anyhow::Result<()> {
+        let mut services = Services::new();
+
+        let bottom = services.objects.surfaces.xy_plane();
+        let top = bottom
+            .clone()
+            .translate([0., 0., 1.], &mut services.objects);
+
+        let profiles = [bottom, top].map(|surface| {
+            Cycle::partial()
+                .with_poly_chain_from_points(surface, SQUARE)
+                .close_with_line_segment()
+                .build(&mut services.objects)
+                .insert(&mut services.objects)
+        });
+
+        let ruled = loft(
+            &profiles,
+            LoftInterpolation::Ruled,
+            Color::default(),
+            Attributes::default(),
+            &mut services.objects,
+        )?;
+        let faces = ruled.face_iter().count();
+        assert_eq!(faces, 2 + 4 * 2);
+
+        let mesh = (&*ruled, Tolerance::from_scalar(0.001)?).triangulate();
+        assert_eq!(mesh.triangles().count(), 2 * 2 + 4 * 2);
+
+        let smooth = loft(
+            &profiles,
+            LoftInterpolation::Smooth { subdivisions: 2 },
+            Color::default(),
+            Attributes::default(),
+            &mut services.objects,
+        )?;
+        let faces = smooth.face_iter().count();
+        assert_eq!(faces, 2 + 3 * 4 * 2);
+
+        Ok(())
+    }
+
+    #[test]
+    fn loft_requires_two_profiles() {
+        let mut services = Services::new();
+
+        let surface = services.objects.surfaces.xy_plane();
+        let profile = Cycle::partial()
+            .with_poly_chain_from_points(surface, SQUARE)
+            .close_with_line_segment()
+            .build(&mut services.objects)
+            .insert(&mut services.objects);
+
+        let result = loft(
+            &[profile],
+            LoftInterpolation::Ruled,
+            Color::default(),
+            Attributes::default(),
+            &mut services.objects,
+        );
+        assert!(matches!(result, Err(LoftError::NotEnoughProfiles)));
+    }
+}
@@ -0,0 +1,583 @@
+//! Boolean operations on triangle meshes
+//!
+//! See [`mesh_boolean`].
+
+use std::collections::HashMap;
+
+use fj_interop::mesh::{Attributes, Color, Mesh, ObjectId, Origin};
+use fj_math::{Aabb, Point, Scalar, Triangle, Vector};
+
+use crate::validate::ValidationConfig;
+
+/// A boolean operation
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum BooleanOperation {
+    /// Everything that is in either of the meshes
+    Union,
+
+    /// Everything that is in the first mesh, but not in the second
+    Difference,
+
+    /// Everything that is in both meshes
+    Intersection,
+}
+
+/// Compute a boolean operation of two triangle meshes
+///
+/// Both meshes need to be closed, and their triangles need to face outward.
+/// The result is computed using binary space partitioning trees, which works
+/// for any pair of such meshes, regardless of the surfaces they approximate.
+/// This makes it useful as a fallback, where exact operations on the boundary
+/// representation are not available.
+///
+/// The triangles of the result keep the colors, attributes, and faces of the
+/// triangles they were cut from. Where triangles have been cut, the result has
+/// T-junctions, so neighboring triangles don't necessarily share their
+/// vertices. Vertices that are closer to each other than the minimum distance between distinct objects
+/// are merged, and triangles that collapse as a result are left out.
+pub fn mesh_boolean(
+    a: &Mesh<Point<3>>,
+    b: &Mesh<Point<3>>,
+    operation: BooleanOperation,
+) -> Mesh<Point<3>> {
+    let epsilon = {
+        let points = a.vertices().chain(b.vertices()).collect::<Vec<_>>();
+        if points.is_empty() {
+            return Mesh::new();
+        }
+
+        Aabb::<3>::from_points(points).size().magnitude() * 1e-9
+    };
+
+    let mut a = Bsp::new(polygons(a), epsilon);
+    let mut b = Bsp::new(polygons(b), epsilon);
+
+    match operation {
+        BooleanOperation::Union => {
+            a.clip_to(&b);
+            b.clip_to(&a);
+            b.invert();
+            b.clip_to(&a);
+            b.invert();
+            a.build(b.polygons());
+        }
+        BooleanOperation::Difference => {
+            a.invert();
+            a.clip_to(&b);
+            b.clip_to(&a);
+            b.invert();
+            b.clip_to(&a);
+            b.invert();
+            a.build(b.polygons());
+            a.invert();
+        }
+        BooleanOperation::Intersection => {
+            a.invert();
+            b.clip_to(&a);
+            b.invert();
+            a.clip_to(&b);
+            b.clip_to(&a);
+            a.build(b.polygons());
+            a.invert();
+        }
+    }
+
+    // Cutting the same edge from different sides results in points that
+    // differ in the last few bits.
+    let mut welder =
+        Welder::new(ValidationConfig::default().distinct_min_distance.inner());
+
+    let mut mesh = Mesh::new();
+    for polygon in a.polygons() {
+        let vertices = polygon
+            .vertices
+            .iter()
+            .map(|&vertex| welder.weld(vertex))
+            .collect::<Vec<_>>();
+
+        // Polygons are convex, so they can be triangulated as a fan.
+        for i in 1..vertices.len() - 1 {
+            let points = [vertices[0], vertices[i], vertices[i + 1]];
+            if let Ok(triangle) = Triangle::from_points(points) {
+                mesh.extend([fj_interop::mesh::Triangle {
+                    inner: triangle,
+                    normals: None,
+                    color: polygon.color,
+                    attributes: polygon.attributes.clone(),
+                    origin: Origin {
+                        face: polygon.face,
+                        ..Origin::default()
+                    },
+                }]);
+            }
+        }
+    }
+
+    mesh
+}
+
+fn polygons(mesh: &Mesh<Point<3>>) -> Vec<Polygon> {
+    mesh.triangles()
+        .filter_map(|triangle| {
+            let vertices = triangle.inner.points();
+            let plane = Plane::from_points(vertices)?;
+
+            Some(Polygon {
+                vertices: vertices.to_vec(),
+                plane,
+                color: triangle.color,
+                attributes: triangle.attributes,
+                face: triangle.origin.face,
+            })
+        })
+        .collect()
+}
+
+/// Merges points that are closer to each other than a given distance
+struct Welder {
+    distance: Scalar,
+    cells: HashMap<[i64; 3], Vec<Point<3>>>,
+}
+
+impl Welder {
+    fn new(distance: Scalar) -> Self {
+        Self {
+            distance,
+            cells: HashMap::new(),
+        }
+    }
+
+    /// Return a previous point that is close to this one, or the point itself
+    fn weld(&mut self, point: Point<3>) -> Point<3> {
+        let cell = point
+            .coords
+            .components
+            .map(|coord| (coord / self.distance).floor().into_f64() as i64);
+
+        // Close points can end up in neighboring cells.
+        for x in -1..=1 {
+            for y in -1..=1 {
+                for z in -1..=1 {
+                    let neighbor = [cell[0] + x, cell[1] + y, cell[2] + z];
+                    let existing =
+                        self.cells.get(&neighbor).and_then(|points| {
+                            points.iter().find(|&&existing| {
+                                (existing - point).magnitude() < self.distance
+                            })
+                        });
+                    if let Some(&existing) = existing {
+                        return existing;
+                    }
+                }
+            }
+        }
+
+        self.cells.entry(cell).or_default().push(point);
+        point
+    }
+}
+
+/// A binary space partitioning tree
+///
+/// The nodes are stored in a flat list, instead of a recursive structure, so
+/// operating on the tree doesn't require recursion. The trees of meshes can be
+/// very deep, as the triangles of a convex mesh end up in a single chain of
+/// nodes.
+struct Bsp {
+    nodes: Vec<Node>,
+    epsilon: Scalar,
+}
+
+impl Bsp {
+    fn new(polygons: Vec<Polygon>, epsilon: Scalar) -> Self {
+        let mut bsp = Self {
+            nodes: Vec::new(),
+            epsilon,
+        };
+        bsp.build(polygons);
+        bsp
+    }
+
+    /// Add polygons to the tree
+    fn build(&mut self, polygons: Vec<Polygon>) {
+        if polygons.is_empty() {
+            return;
+        }
+        if self.nodes.is_empty() {
+            self.nodes.push(Node::default());
+        }
+
+        let mut stack = vec![(0, polygons)];
+        while let Some((i, polygons)) = stack.pop() {
+            let plane =
+                *self.nodes[i].plane.get_or_insert_with(|| polygons[0].plane);
+
+            let mut coplanar = Vec::new();
+            let mut front = Vec::new();
+            let mut back = Vec::new();
+            for polygon in polygons {
+                plane.split(
+                    polygon,
+                    self.epsilon,
+                    &mut coplanar,
+                    &mut front,
+                    &mut back,
+                    true,
+                );
+            }
+            self.nodes[i].polygons.extend(coplanar);
+
+            if !front.is_empty() {
+                let front_node = self.child(i, Side::Front);
+                stack.push((front_node, front));
+            }
+            if !back.is_empty() {
+                let back_node = self.child(i, Side::Back);
+                stack.push((back_node, back));
+            }
+        }
+    }
+
+    /// Access the child of a node, creating it, if it doesn't exist
+    fn child(&mut self, i: usize, side: Side) -> usize {
+        let existing = match side {
+            Side::Front => self.nodes[i].front,
+            Side::Back => self.nodes[i].back,
+        };
+        if let Some(child) = existing {
+            return child;
+        }
+
+        let child = self.nodes.len();
+        self.nodes.push(Node::default());
+        match side {
+            Side::Front => self.nodes[i].front = Some(child),
+            Side::Back => self.nodes[i].back = Some(child),
+        }
+
+        child
+    }
+
+    /// Swap inside and outside of the tree
+    fn invert(&mut self) {
+        for node in &mut self.nodes {
+            for polygon in &mut node.polygons {
+                polygon.flip();
+            }
+            if let Some(plane) = &mut node.plane {
+                *plane = plane.flipped();
+            }
+            std::mem::swap(&mut node.front, &mut node.back);
+        }
+    }
+
+    /// Remove the parts of the polygons that are inside of the tree
+    fn clip_polygons(&self, polygons: Vec<Polygon>) -> Vec<Polygon> {
+        if self.nodes.is_empty() {
+            return polygons;
+        }
+
+        let mut clipped = Vec::new();
+
+        let mut stack = vec![(0, polygons)];
+        while let Some((i, polygons)) = stack.pop() {
+            let node = &self.nodes[i];
+            let Some(plane) = node.plane else {
+                clipped.extend(polygons);
+                continue;
+            };
+
+            let mut front = Vec::new();
+            let mut back = Vec::new();
+            for polygon in polygons {
+                plane.split(
+                    polygon,
+                    self.epsilon,
+                    &mut Vec::new(),
+                    &mut front,
+                    &mut back,
+                    false,
+                );
+            }
+
+            match node.front {
+                Some(front_node) => stack.push((front_node, front)),
+                None => clipped.extend(front),
+            }
+
+            // Polygons behind a leaf are inside of the tree.
+            if let Some(back_node) = node.back {
+                stack.push((back_node, back));
+            }
+        }
+
+        clipped
+    }
+
+    /// Remove the parts of the polygons in this tree that are inside another
+    fn clip_to(&mut self, other: &Self) {
+        for node in &mut self.nodes {
+            let polygons = std::mem::take(&mut node.polygons);
+            node.polygons = other.clip_polygons(polygons);
+        }
+    }
+
+    fn polygons(&self) -> Vec<Polygon> {
+        self.nodes
+            .iter()
+            .flat_map(|node| node.polygons.iter().cloned())
+            .collect()
+    }
+}
+
+#[derive(Default)]
+struct Node {
+    plane: Option<Plane>,
+    front: Option<usize>,
+    back: Option<usize>,
+    polygons: Vec<Polygon>,
+}
+
+enum Side {
+    Front,
+    Back,
+}
+
+/// A convex polygon
+#[derive(Clone)]
+struct Polygon {
+    vertices: Vec<Point<3>>,
+    plane: Plane,
+    color: Color,
+    attributes: Attributes,
+    face: Option<ObjectId>,
+}
+
+impl Polygon {
+    fn flip(&mut self) {
+        self.vertices.reverse();
+        self.plane = self.plane.flipped();
+    }
+}
+
+/// A plane, defined by its normal and its distance from the origin
+#[derive(Clone, Copy)]
+struct Plane {
+    normal: Vector<3>,
+    distance: Scalar,
+}
+
+impl Plane {
+    /// Compute the plane of a triangle
+    ///
+    /// Returns `None`, if the points don't form a triangle.
+    fn from_points([a, b, c]: [Point<3>; 3]) -> Option<Self> {
+        let normal = (b - a).cross(&(c - a));
+        if normal.magnitude() == Scalar::ZERO {
+            return None;
+        }
+        let normal = normal.normalize();
+
+        Some(Self {
+            normal,
+            distance: normal.dot(&a.coords),
+        })
+    }
+
+    fn flipped(self) -> Self {
+        Self {
+            normal: -self.normal,
+            distance: -self.distance,
+        }
+    }
+
+    /// Split a polygon by this plane
+    ///
+    /// The parts of the polygon end up in `front` or `back`. If the polygon is
+    /// coplanar with the plane, it ends up in `coplanar`, if that is enabled,
+    /// or in `front` or `back`, depending on which way it faces.
+    fn split(
+        &self,
+        polygon: Polygon,
+        epsilon: Scalar,
+        coplanar: &mut Vec<Polygon>,
+        front: &mut Vec<Polygon>,
+        back: &mut Vec<Polygon>,
+        keep_coplanar: bool,
+    ) {
+        #[derive(Clone, Copy, Eq, PartialEq)]
+        enum Location {
+            Coplanar,
+            Front,
+            Back,
+        }
+
+        let distances = polygon
+            .vertices
+            .iter()
+            .map(|vertex| self.normal.dot(&vertex.coords) - self.distance)
+            .collect::<Vec<_>>();
+        let locations = distances
+            .iter()
+            .map(|&distance| {
+                if distance < -epsilon {
+                    Location::Back
+                } else if distance > epsilon {
+                    Location::Front
+                } else {
+                    Location::Coplanar
+                }
+            })
+            .collect::<Vec<_>>();
+
+        let is_in_front = locations.iter().all(|&l| l != Location::Back);
+        let is_behind = locations.iter().all(|&l| l != Location::Front);
+
+        match (is_in_front, is_behind) {
+            (true, true) => {
+                if keep_coplanar {
+                    coplanar.push(polygon);
+                } else if self.normal.dot(&polygon.plane.normal) > Scalar::ZERO
+                {
+                    front.push(polygon);
+                } else {
+                    back.push(polygon);
+                }
+            }
+            (true, false) => front.push(polygon),
+            (false, true) => back.push(polygon),
+            (false, false) => {
+                let mut front_vertices = Vec::new();
+                let mut back_vertices = Vec::new();
+
+                let n = polygon.vertices.len();
+                for i in 0..n {
+                    let j = (i + 1) % n;
+                    let [a, b] = [polygon.vertices[i], polygon.vertices[j]];
+
+                    if locations[i] != Location::Back {
+                        front_vertices.push(a);
+                    }
+                    if locations[i] != Location::Front {
+                        back_vertices.push(a);
+                    }
+
+                    let is_spanning = matches!(
+                        (locations[i], locations[j]),
+                        (Location::Front, Location::Back)
+                            | (Location::Back, Location::Front)
+                    );
+                    if is_spanning {
+                        let t = distances[i] / (distances[i] - distances[j]);
+                        let vertex = a + (b - a) * t;
+
+                        front_vertices.push(vertex);
+                        back_vertices.push(vertex);
+                    }
+                }
+
+                if front_vertices.len() >= 3 {
+                    front.push(Polygon {
+                        vertices: front_vertices,
+                        ..polygon.clone()
+                    });
+                }
+                if back_vertices.len() >= 3 {
+                    back.push(Polygon {
+                        vertices: back_vertices,
+                        ..polygon
+                    });
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use fj_interop::mesh::{Color, Mesh};
+    use fj_math::{Point, Scalar, Triangle};
+
+    use super::{mesh_boolean, BooleanOperation};
+
+    #[test]
+    fn union_of_overlapping_cubes() {
+        let a = cube([0., 0., 0.], 2.);
+        let b = cube([1., 1., 1.], 2.);
+
+        let union = mesh_boolean(&a, &b, BooleanOperation::Union);
+        assert_volume(&union, 8. + 8. - 1.);
+    }
+
+    #[test]
+    fn difference_of_overlapping_cubes() {
+        let a = cube([0., 0., 0.], 2.);
+        let b = cube([1., 1., 1.], 2.);
+
+        let difference = mesh_boolean(&a, &b, BooleanOperation::Difference);
+        assert_volume(&difference, 8. - 1.);
+    }
+
+    #[test]
+    fn intersection_of_overlapping_cubes() {
+        let a = cube([0., 0., 0.], 2.);
+        let b = cube([1., 1., 1.], 2.);
+
+        let intersection = mesh_boolean(&a, &b, BooleanOperation::Intersection);
+        assert_volume(&intersection, 1.);
+    }
+
+    #[test]
+    fn intersection_of_disjoint_cubes() {
+        let a = cube([0., 0., 0.], 1.);
+        let b = cube([2., 0., 0.], 1.);
+
+        let intersection = mesh_boolean(&a, &b, BooleanOperation::Intersection);
+        assert_eq!(intersection.triangles().count(), 0);
+    }
+
+    fn cube(min: [f64; 3], size: f64) -> Mesh<Point<3>> {
+        let [x, y, z] = min;
+        let vertex = |i: usize| {
+            Point::from([
+                x + size * (i & 1) as f64,
+                y + size * ((i >> 1) & 1) as f64,
+                z + size * ((i >> 2) & 1) as f64,
+            ])
+        };
+
+        // The faces of the cube, wound counter-clockwise when viewed from
+        // the outside.
+        let faces = [
+            [0, 2, 3, 1],
+            [4, 5, 7, 6],
+            [0, 1, 5, 4],
+            [2, 6, 7, 3],
+            [0, 4, 6, 2],
+            [1, 3, 7, 5],
+        ];
+
+        let mut mesh = Mesh::new();
+        for [a, b, c, d] in faces {
+            for points in [[a, b, c], [a, c, d]] {
+                let triangle = Triangle::from_points(points.map(vertex))
+                    .expect("Cube faces are valid triangles");
+                mesh.push_triangle(triangle, Color::default());
+            }
+        }
+
+        mesh
+    }
+
+    fn assert_volume(mesh: &Mesh<Point<3>>, expected: f64) {
+        let volume = mesh
+            .triangles()
+            .map(|triangle| {
+                let [a, b, c] = triangle.inner.points().map(|p| p.coords);
+                a.dot(&b.cross(&c)) / 6.
+            })
+            .fold(Scalar::ZERO, |a, b| a + b);
+
+        assert!((volume.into_f64() - expected).abs() < 1e-9, "{volume}");
+    }
+}
@@ -0,0 +1,347 @@
+//! Mirroring solids
+//!
+//! Mirroring any object is available through [`TransformObject::mirror`]. This
+//! module adds the ability to join a solid with its mirror image, which is
+//! useful for modeling symmetric parts one half at a time.
+
+use fj_math::{Point, Scalar, Transform, Vector};
+
+use crate::{
+    algorithms::transform::{TransformCache, TransformObject},
+    geometry::{path::GlobalPath, surface::SurfaceGeometry},
+    iter::ObjectIters,
+    objects::{Face, HalfEdge, Objects, Shell, Solid},
+    services::Service,
+    storage::Handle,
+    validate::ValidationConfig,
+};
+
+/// Mirror a solid across a plane, and join the mirror image with the original
+///
+/// The plane is defined by a point on it, and its normal. The solid must be
+/// located on one side of the plane, but may touch it.
+///
+/// Where the solid touches the plane, it shares its vertices and edges with
+/// its mirror image. Faces that lie in the plane coincide with their mirror
+/// images. Both are removed, joining the shells of the solid and its mirror
+/// image into one. Shells that don't have any faces in the plane are kept
+/// separate from their mirror images.
+///
+/// # Implementation Note
+///
+/// Faces that become coplanar neighbors across the plane are not merged, and
+/// stay separate faces.
+///
+/// Only the vertices of the solid are checked, to make sure it doesn't cross
+/// the plane. Curved faces that bulge across the plane are not detected.
+///
+/// [`TransformObject::mirror`]: crate::algorithms::transform::TransformObject::mirror
+pub fn mirror_and_union(
+    solid: &Handle<Solid>,
+    origin: impl Into<Point<3>>,
+    normal: impl Into<Vector<3>>,
+    objects: &mut Service<Objects>,
+) -> Result<Handle<Solid>, MirrorError> {
+    let normal = normal.into();
+    if normal.magnitude() == Scalar::ZERO {
+        return Err(MirrorError::ZeroNormal);
+    }
+
+    let plane = MirrorPlane {
+        origin: origin.into(),
+        normal: normal.normalize(),
+    };
+
+    let mut is_in_front = false;
+    let mut is_behind = false;
+    for vertex in solid.global_vertex_iter() {
+        let distance = plane.distance_to(vertex.position());
+
+        is_in_front |= distance > plane.tolerance();
+        is_behind |= distance < -plane.tolerance();
+    }
+    if is_in_front && is_behind {
+        return Err(MirrorError::CrossesPlane);
+    }
+
+    // The mirror image needs to share the vertices and edges in the plane with
+    // the original. Pre-fill the cache with them, so the transform doesn't
+    // create copies.
+    let mut cache = TransformCache::default();
+    for half_edge in solid.half_edge_iter() {
+        for vertex in half_edge.vertices() {
+            let global_vertex = vertex.global_form();
+            if plane.contains(global_vertex.position()) {
+                cache.insert(global_vertex.clone(), global_vertex.clone());
+            }
+        }
+
+        if plane.contains_half_edge(half_edge) {
+            let global_edge = half_edge.global_form();
+            cache.insert(global_edge.clone(), global_edge.clone());
+            cache.insert(
+                global_edge.curve().clone(),
+                global_edge.curve().clone(),
+            );
+        }
+    }
+
+    let transform = Transform::reflection(plane.origin, plane.normal);
+
+    let mut shells = Vec::new();
+    for shell in solid.shells() {
+        let mirrored = shell
+            .clone()
+            .transform_with_cache(&transform, objects, &mut cache);
+
+        let has_coincident_faces = shell
+            .faces()
+            .into_iter()
+            .any(|face| plane.contains_face(face));
+        if !has_coincident_faces {
+            shells.push(shell.clone());
+            shells.push(mirrored);
+            continue;
+        }
+
+        let faces = shell
+            .faces()
+            .into_iter()
+            .chain(mirrored.faces())
+            .filter(|face| !plane.contains_face(face))
+            .cloned()
+            .collect::<Vec<_>>();
+
+        shells.push(Shell::builder().with_faces(faces).build(objects));
+    }
+
+    Ok(Solid::builder().with_shells(shells).build(objects))
+}
+
+/// An error that can occur while mirroring a solid
+#[derive(Clone, Debug, Eq, PartialEq, thiserror::Error)]
+pub enum MirrorError {
+    /// The normal of the mirror plane has zero length
+    #[error("Normal of mirror plane must not have zero length")]
+    ZeroNormal,
+
+    /// The solid is located on both sides of the mirror plane
+    #[error("Can't join solid with its mirror image, if it crosses the plane")]
+    CrossesPlane,
+}
+
+struct MirrorPlane {
+    origin: Point<3>,
+    normal: Vector<3>,
+}
+
+impl MirrorPlane {
+    fn tolerance(&self) -> Scalar {
+        ValidationConfig::default().identical_max_distance.inner()
+    }
+
+    fn distance_to(&self, point: Point<3>) -> Scalar {
+        (point - self.origin).dot(&self.normal)
+    }
+
+    fn contains(&self, point: Point<3>) -> bool {
+        self.distance_to(point).abs() <= self.tolerance()
+    }
+
+    fn contains_half_edge(&self, half_edge: &HalfEdge) -> bool {
+        const NUM_SAMPLES: usize = 4;
+
+        let path = half_edge.curve().path();
+        let surface = half_edge.surface().geometry();
+        let [a, b] =
+            half_edge.vertices().clone().map(|vertex| vertex.position());
+
+        // Checking the vertices is not enough, as the edge might curve away
+        // from the plane between them.
+        (0..=NUM_SAMPLES).all(|i| {
+            let t = a + (b - a) * (i as f64 / NUM_SAMPLES as f64);
+            let point = surface
+                .point_from_surface_coords(path.point_from_path_coords(t));
+
+            self.contains(point)
+        })
+    }
+
+    fn contains_face(&self, face: &Face) -> bool {
+        let is_parallel_plane = match face.surface().geometry() {
+            SurfaceGeometry::Swept {
+                u: GlobalPath::Line(line),
+                v,
+            } => {
+                let normal = line.direction().cross(&v).normalize();
+                normal.cross(&self.normal).magnitude() <= self.tolerance()
+            }
+            _ => false,
+        };
+
+        is_parallel_plane
+            && face.all_cycles().all(|cycle| {
+                cycle
+                    .half_edges()
+                    .all(|half_edge| self.contains_half_edge(half_edge))
+            })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use fj_interop::mesh::Color;
+    use fj_math::{Point, Scalar};
+
+    use crate::{
+        algorithms::{
+            approx::Tolerance, sweep::Sweep, transform::TransformObject,
+            triangulate::Triangulate,
+        },
+        builder::{CycleBuilder, FaceBuilder},
+        insert::Insert,
+        iter::ObjectIters,
+        objects::{Cycle, Face, Sketch},
+        partial::HasPartial,
+        services::Services,
+    };
+
+    use super::{mirror_and_union, MirrorError};
+
+    #[test]
+    fn mirror_keeps_faces_pointing_outward() -> anyhow::Result<()> {
+        let mut services = Services::new();
+
+        let surface = services.objects.surfaces.xy_plane();
+        let face = Face::partial()
+            .with_exterior_polygon_from_points(
+                surface,
+                [[0., 0.], [1., 0.], [1., 1.], [0., 1.]],
+            )
+            .build(&mut services.objects)
+            .insert(&mut services.objects);
+        let solid = Sketch::builder()
+            .with_faces([face])
+            .build(&mut services.objects)
+            .sweep([0., 0., 1.], &mut services.objects);
+
+        let mirrored = solid.clone().mirror(
+            [0., 0., 0.],
+            [1., 0., 0.],
+            &mut services.objects,
+        );
+
+        // The triangles of the mirrored cube must face away from its center.
+        let center = Point::from([-0.5, 0.5, 0.5]);
+        let mesh = (&*mirrored, Tolerance::from_scalar(0.01)?).triangulate();
+        for triangle in mesh.triangles() {
+            let triangle = triangle.inner;
+            let [a, ..] = triangle.points();
+
+            assert!(triangle.normal().dot(&(a - center)) > Scalar::ZERO);
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn mirror_cylinder_obliquely() -> anyhow::Result<()> {
+        let mut services = Services::new();
+
+        let surface = services.objects.surfaces.xy_plane();
+        let exterior = Cycle::partial()
+            .with_circle(surface, [3., 0.], 0.5, &mut services.objects)
+            .build(&mut services.objects)
+            .insert(&mut services.objects);
+        let face = Face::partial()
+            .with_exterior(exterior)
+            .with_color(Color::default())
+            .build(&mut services.objects)
+            .insert(&mut services.objects);
+        let solid = Sketch::builder()
+            .with_faces([face])
+            .build(&mut services.objects)
+            .sweep([0., 0., 1.], &mut services.objects);
+
+        // Mirroring across a plane that isn't aligned with the axes introduces
+        // rounding errors. Faces that share an edge must still approximate it
+        // identically, or triangulation fails.
+        let mirrored =
+            solid.mirror([0., 0., 0.], [1., 2., 0.], &mut services.objects);
+
+        let mesh = (&*mirrored, Tolerance::from_scalar(0.01)?).triangulate();
+        assert!(mesh.triangles().count() > 0);
+
+        Ok(())
+    }
+
+    #[test]
+    fn mirror_and_union_cylinder() -> anyhow::Result<()> {
+        let mut services = Services::new();
+
+        let surface = services.objects.surfaces.xy_plane();
+        let exterior = Cycle::partial()
+            .with_circle(surface, [0., 0.], 1., &mut services.objects)
+            .build(&mut services.objects)
+            .insert(&mut services.objects);
+        let face = Face::partial()
+            .with_exterior(exterior)
+            .with_color(Color::default())
+            .build(&mut services.objects)
+            .insert(&mut services.objects);
+        let solid = Sketch::builder()
+            .with_faces([face])
+            .build(&mut services.objects)
+            .sweep([0., 0., 1.], &mut services.objects);
+
+        let union = mirror_and_union(
+            &solid,
+            [0., 0., 0.],
+            [0., 0., 1.],
+            &mut services.objects,
+        )?;
+
+        // The two cylinders are joined at their bottom faces, which are
+        // removed. That leaves one shell with two caps and two side walls.
+        assert_eq!(union.shell_iter().count(), 1);
+        assert_eq!(union.face_iter().count(), 4);
+
+        // The side walls share the edge in the mirror plane.
+        let num_global_vertices = union.global_vertex_iter().count();
+        assert_eq!(
+            num_global_vertices,
+            solid.global_vertex_iter().count() * 2 - 1
+        );
+
+        let mesh = (&*union, Tolerance::from_scalar(0.01)?).triangulate();
+        assert!(mesh.triangles().count() > 0);
+
+        Ok(())
+    }
+
+    #[test]
+    fn mirror_and_union_crossing_plane() {
+        let mut services = Services::new();
+
+        let surface = services.objects.surfaces.xy_plane();
+        let face = Face::partial()
+            .with_exterior_polygon_from_points(
+                surface,
+                [[0., 0.], [1., 0.], [1., 1.], [0., 1.]],
+            )
+            .build(&mut services.objects)
+            .insert(&mut services.objects);
+        let solid = Sketch::builder()
+            .with_faces([face])
+            .build(&mut services.objects)
+            .sweep([0., 0., 1.], &mut services.objects);
+
+        let result = mirror_and_union(
+            &solid,
+            [0.5, 0., 0.],
+            [1., 0., 0.],
+            &mut services.objects,
+        );
+        assert_eq!(result, Err(MirrorError::CrossesPlane));
+    }
+}
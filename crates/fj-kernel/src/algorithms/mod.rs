@@ -3,9 +3,28 @@
 //! Algorithmic code is collected in this module, to keep other modules focused
 //! on their respective purpose.
 
+pub mod analysis;
 pub mod approx;
+pub mod bounding_volume;
+pub mod distance;
+pub mod draft;
+pub mod hull;
+pub mod imprint;
 pub mod intersect;
+pub mod loft;
+pub mod mesh_boolean;
+pub mod mirror;
+pub mod offset;
+pub mod pattern;
+pub mod point_in_face;
+pub mod point_in_solid;
+pub mod polyhedron;
+pub mod project;
+pub mod properties;
+pub mod repair;
 pub mod reverse;
+pub mod shell;
+pub mod slice;
 pub mod sweep;
 pub mod transform;
 pub mod triangulate;
@@ -0,0 +1,471 @@
+use fj_math::{signed_area, Point, Scalar, Segment, Vector};
+
+use crate::{
+    algorithms::{approx::Tolerance, reverse::Reverse},
+    builder::CycleBuilder,
+    geometry::path::SurfacePath,
+    insert::Insert,
+    objects::{Cycle, Objects},
+    partial::HasPartial,
+    services::Service,
+    storage::Handle,
+};
+
+/// Offset a closed cycle by a signed distance
+///
+/// Positive distances grow the area enclosed by the cycle, negative distances
+/// shrink it. This is independent of the winding of the cycle, which is
+/// preserved in the offset cycles.
+///
+/// Where the offset moves away from a corner, the corner is rounded with an arc
+/// around it. Where it moves into a corner, the adjacent edges are extended or
+/// trimmed to meet. Loops that are created when the offset is larger than a
+/// feature of the cycle (like a narrow slot) are trimmed away.
+///
+/// Depending on the shape of the cycle and the distance, the result might
+/// consist of any number of cycles. Shrinking a cycle by more than its
+/// inradius, for example, results in no cycle at all.
+///
+/// # Implementation Note
+///
+/// Half-edges can't be arcs yet, so the arcs at corners are approximated by
+/// line segments, according to the given tolerance.
+///
+/// Only cycles made up of line segments, or of a single full circle, are
+/// supported. The distance is
+/// measured in surface coordinates, which only matches distance in model space
+/// for surfaces with orthonormal axes (like the basic planes).
+pub fn offset_cycle(
+    cycle: &Handle<Cycle>,
+    distance: impl Into<Scalar>,
+    tolerance: impl Into<Tolerance>,
+    objects: &mut Service<Objects>,
+) -> Result<Vec<Handle<Cycle>>, CycleOffsetError> {
+    let distance = distance.into();
+    let tolerance = tolerance.into();
+
+    if let Some(offset) = offset_circle(cycle, distance, objects) {
+        return Ok(offset);
+    }
+
+    let mut points = cycle
+        .half_edges()
+        .map(|half_edge| match half_edge.curve().path() {
+            SurfacePath::Line(_) => {
+                Ok(half_edge.back().surface_form().position())
+            }
+            SurfacePath::Circle(_)
+            | SurfacePath::Ellipse(_)
+            | SurfacePath::Nurbs(_) => Err(CycleOffsetError::CurvedCycle),
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+    points.dedup();
+
+    let area = signed_area(&points);
+    if area == Scalar::ZERO {
+        return Err(CycleOffsetError::DegenerateCycle);
+    }
+
+    // The offsetting works on counter-clockwise polygons, so the outward
+    // normal of each edge is on its right.
+    let is_clockwise = area < Scalar::ZERO;
+    if is_clockwise {
+        points.reverse();
+    }
+
+    let raw = raw_offset(&points, distance, tolerance);
+
+    let min_distance =
+        distance.abs() - tolerance.inner() - Scalar::from(EPSILON);
+    let loops = split_at_self_intersections(raw)
+        .into_iter()
+        .filter(|loop_| signed_area(loop_) > Scalar::ZERO)
+        .filter(|loop_| {
+            // Loops that come closer to the original polygon than the offset
+            // distance are artifacts of the offset "overshooting".
+            let mut segments = loop_.iter().zip(loop_.iter().cycle().skip(1));
+            segments.all(|(&a, &b)| {
+                let midpoint = a + (b - a) / 2.;
+                [a, midpoint].into_iter().all(|point| {
+                    distance_to_polygon(point, &points) >= min_distance
+                })
+            })
+        })
+        .map(|mut loop_| {
+            if is_clockwise {
+                loop_.reverse();
+            }
+
+            Cycle::partial()
+                .with_poly_chain_from_points(cycle.surface().clone(), loop_)
+                .close_with_line_segment()
+                .build(objects)
+                .insert(objects)
+        })
+        .collect();
+
+    Ok(loops)
+}
+
+/// Offset a cycle that consists of a single full circle
+///
+/// Returns `None`, if the cycle is made up of anything else.
+fn offset_circle(
+    cycle: &Handle<Cycle>,
+    distance: Scalar,
+    objects: &mut Service<Objects>,
+) -> Option<Vec<Handle<Cycle>>> {
+    let mut half_edges = cycle.half_edges();
+    let (Some(half_edge), None) = (half_edges.next(), half_edges.next()) else {
+        return None;
+    };
+    let SurfacePath::Circle(circle) = half_edge.curve().path() else {
+        return None;
+    };
+
+    let radius = circle.radius() + distance;
+    if radius <= Scalar::ZERO {
+        return Some(Vec::new());
+    }
+
+    let [t_back, t_front] = half_edge
+        .vertices()
+        .each_ref()
+        .map(|vertex| vertex.position().t);
+    let is_counter_clockwise =
+        (circle.a().cross2d(&circle.b()) > Scalar::ZERO) == (t_front > t_back);
+
+    // The new circle is wound counter-clockwise, like all circles that are
+    // built this way.
+    let offset = Cycle::partial()
+        .with_circle(cycle.surface().clone(), circle.center(), radius, objects)
+        .build(objects)
+        .insert(objects);
+    let offset = if is_counter_clockwise {
+        offset
+    } else {
+        offset.reverse(objects)
+    };
+
+    Some(vec![offset])
+}
+
+/// An error that can occur while offsetting a cycle
+#[derive(Clone, Debug, Eq, PartialEq, thiserror::Error)]
+pub enum CycleOffsetError {
+    /// The cycle contains a curved half-edge
+    #[error(
+        "Offsetting is only supported for cycles made up of line segments"
+    )]
+    CurvedCycle,
+
+    /// The cycle doesn't enclose an area
+    #[error("Can't offset cycle that doesn't enclose an area")]
+    DegenerateCycle,
+}
+
+/// Offset each edge, and connect them at the corners
+///
+/// The result might intersect itself.
+fn raw_offset(
+    points: &[Point<2>],
+    distance: Scalar,
+    tolerance: Tolerance,
+) -> Vec<Point<2>> {
+    let num_points = points.len();
+    let mut raw = Vec::new();
+
+    for i in 0..num_points {
+        let prev = points[(i + num_points - 1) % num_points];
+        let point = points[i];
+        let next = points[(i + 1) % num_points];
+
+        let [prev_dir, next_dir] =
+            [point - prev, next - point].map(|edge| edge.normalize());
+        let [prev_normal, next_normal] = [prev_dir, next_dir].map(right_normal);
+
+        let turn = prev_dir.cross2d(&next_dir);
+
+        if turn.abs() < Scalar::from(EPSILON)
+            && prev_dir.dot(&next_dir) > Scalar::ZERO
+        {
+            // The edges are collinear. There's no corner here.
+            raw.push(point + next_normal * distance);
+        } else if (turn * distance).is_positive() {
+            // The offset moves away from the corner. Round it.
+            raw.extend(arc(
+                point,
+                prev_normal * distance,
+                next_normal * distance,
+                tolerance,
+            ));
+        } else {
+            let denominator = prev_normal.dot(&next_normal) + 1.;
+            if denominator < Scalar::from(EPSILON) {
+                // The edges double back on each other. Their offset lines
+                // don't meet, so just connect them.
+                raw.push(point + prev_normal * distance);
+                raw.push(point + next_normal * distance);
+            } else {
+                raw.push(
+                    point
+                        + (prev_normal + next_normal) * distance / denominator,
+                );
+            }
+        }
+    }
+
+    raw
+}
+
+/// Approximate the arc from `center + from` to `center + to`
+///
+/// The arc takes the shorter way around the center.
+fn arc(
+    center: Point<2>,
+    from: Vector<2>,
+    to: Vector<2>,
+    tolerance: Tolerance,
+) -> Vec<Point<2>> {
+    let radius = from.magnitude();
+    let angle = from.cross2d(&to).atan2(from.dot(&to));
+
+    let max_angle_per_segment = if tolerance.inner() >= radius {
+        Scalar::PI
+    } else {
+        (Scalar::ONE - tolerance.inner() / radius).acos() * 2.
+    };
+    let num_segments = (angle.abs() / max_angle_per_segment).ceil().max(1.);
+
+    let num_segments = num_segments.into_u64();
+    (0..=num_segments)
+        .map(|i| {
+            let angle =
+                angle * Scalar::from_u64(i) / Scalar::from_u64(num_segments);
+            let (sin, cos) = angle.sin_cos();
+            let direction = Vector::from([
+                from.u * cos - from.v * sin,
+                from.u * sin + from.v * cos,
+            ]);
+            center + direction
+        })
+        .collect()
+}
+
+/// Split a polygon into loops that don't intersect themselves
+fn split_at_self_intersections(
+    mut points: Vec<Point<2>>,
+) -> Vec<Vec<Point<2>>> {
+    points.dedup();
+    if points.first() == points.last() {
+        points.pop();
+    }
+
+    let num_points = points.len();
+    if num_points < 3 {
+        return Vec::new();
+    }
+
+    let segment = |i: usize| {
+        Segment::from_points([points[i], points[(i + 1) % num_points]])
+    };
+
+    for i in 0..num_points {
+        // Adjacent segments always meet at their shared point. Skip those, as
+        // well as the last segment, which is adjacent to the first.
+        for j in i + 2..num_points {
+            if i == 0 && j == num_points - 1 {
+                continue;
+            }
+
+            if let Some(intersection) =
+                intersect_segments(segment(i), segment(j))
+            {
+                let inner = [intersection]
+                    .into_iter()
+                    .chain(points[i + 1..=j].iter().copied())
+                    .collect();
+                let outer = points[..=i]
+                    .iter()
+                    .copied()
+                    .chain([intersection])
+                    .chain(points[j + 1..].iter().copied())
+                    .collect();
+
+                let mut loops = split_at_self_intersections(inner);
+                loops.extend(split_at_self_intersections(outer));
+                return loops;
+            }
+        }
+    }
+
+    vec![points]
+}
+
+fn intersect_segments(a: Segment<2>, b: Segment<2>) -> Option<Point<2>> {
+    let [a0, a1] = a.points();
+    let [b0, b1] = b.points();
+
+    let [da, db] = [a1 - a0, b1 - b0];
+    let denominator = da.cross2d(&db);
+    if denominator.abs() < Scalar::from(EPSILON) {
+        return None;
+    }
+
+    let t = (b0 - a0).cross2d(&db) / denominator;
+    let s = (b0 - a0).cross2d(&da) / denominator;
+
+    let range = Scalar::ZERO..=Scalar::ONE;
+    if range.contains(&t) && range.contains(&s) {
+        return Some(a0 + da * t);
+    }
+
+    None
+}
+
+fn distance_to_polygon(point: Point<2>, polygon: &[Point<2>]) -> Scalar {
+    polygon
+        .iter()
+        .zip(polygon.iter().cycle().skip(1))
+        .map(|(&a, &b)| {
+            let edge = b - a;
+            let t = ((point - a).dot(&edge) / edge.dot(&edge))
+                .max(0.)
+                .min(Scalar::ONE);
+            (point - (a + edge * t)).magnitude()
+        })
+        .min()
+        .unwrap_or(Scalar::ZERO)
+}
+
+fn right_normal(direction: Vector<2>) -> Vector<2> {
+    Vector::from([direction.v, -direction.u])
+}
+
+const EPSILON: f64 = 1e-9;
+
+#[cfg(test)]
+mod tests {
+    use fj_math::{signed_area, Point, Scalar};
+
+    use crate::{
+        algorithms::approx::Tolerance,
+        builder::CycleBuilder,
+        insert::Insert,
+        objects::{Cycle, Objects},
+        partial::HasPartial,
+        services::{Service, Services},
+        storage::Handle,
+    };
+
+    use super::{offset_cycle, split_at_self_intersections};
+
+    #[test]
+    fn offset_square_outward() -> anyhow::Result<()> {
+        let mut services = Services::new();
+
+        let square = polygon(
+            [[0., 0.], [2., 0.], [2., 2.], [0., 2.]],
+            &mut services.objects,
+        );
+        let offset = offset_cycle(
+            &square,
+            0.5,
+            Tolerance::from_scalar(0.001)?,
+            &mut services.objects,
+        )?;
+
+        assert_eq!(offset.len(), 1);
+
+        // The square grows by a rectangle on each side, and a quarter circle
+        // at each corner.
+        let area = signed_area(&points(&offset[0]));
+        let expected = 4. + 4. * 2. * 0.5 + std::f64::consts::PI * 0.25;
+        assert!(area < Scalar::from(expected));
+        assert!(area > Scalar::from(expected - 0.01));
+
+        Ok(())
+    }
+
+    #[test]
+    fn offset_square_inward() -> anyhow::Result<()> {
+        let mut services = Services::new();
+
+        let square = polygon(
+            [[0., 0.], [2., 0.], [2., 2.], [0., 2.]],
+            &mut services.objects,
+        );
+
+        let offset = offset_cycle(
+            &square,
+            -0.5,
+            Tolerance::from_scalar(0.001)?,
+            &mut services.objects,
+        )?;
+        assert_eq!(offset.len(), 1);
+        assert_eq!(
+            points(&offset[0]),
+            [[0.5, 0.5], [1.5, 0.5], [1.5, 1.5], [0.5, 1.5]].map(Point::from)
+        );
+
+        let collapsed = offset_cycle(
+            &square,
+            -1.5,
+            Tolerance::from_scalar(0.001)?,
+            &mut services.objects,
+        )?;
+        assert!(collapsed.is_empty());
+
+        Ok(())
+    }
+
+    #[test]
+    fn offset_trims_closed_slot() -> anyhow::Result<()> {
+        let mut services = Services::new();
+
+        // A U-shape with a narrow slot, which closes when growing the shape.
+        let u_shape = polygon(
+            [
+                [0., 0.],
+                [2.1, 0.],
+                [2.1, 2.],
+                [1.1, 2.],
+                [1.1, 1.],
+                [1., 1.],
+                [1., 2.],
+                [0., 2.],
+            ],
+            &mut services.objects,
+        );
+        let offset = offset_cycle(
+            &u_shape,
+            0.25,
+            Tolerance::from_scalar(0.001)?,
+            &mut services.objects,
+        )?;
+
+        assert_eq!(offset.len(), 1);
+        assert_eq!(split_at_self_intersections(points(&offset[0])).len(), 1);
+
+        Ok(())
+    }
+
+    fn polygon(
+        points: impl IntoIterator<Item = [f64; 2]>,
+        objects: &mut Service<Objects>,
+    ) -> Handle<Cycle> {
+        let surface = objects.surfaces.xy_plane();
+        Cycle::partial()
+            .with_poly_chain_from_points(surface, points)
+            .close_with_line_segment()
+            .build(objects)
+            .insert(objects)
+    }
+
+    fn points(cycle: &Handle<Cycle>) -> Vec<Point<2>> {
+        cycle
+            .half_edges()
+            .map(|half_edge| half_edge.back().surface_form().position())
+            .collect()
+    }
+}
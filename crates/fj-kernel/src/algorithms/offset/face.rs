@@ -0,0 +1,183 @@
+use fj_math::Scalar;
+
+use crate::{
+    algorithms::polyhedron::{build_face, Polyhedron, PolyhedronError},
+    objects::{Face, Objects, Shell, Solid},
+    services::Service,
+    storage::Handle,
+};
+
+/// Move the given faces of a solid along their normals
+///
+/// Positive distances move the faces outward, growing the solid. Negative
+/// distances move them inward. Faces adjacent to the moved ones keep their
+/// planes, but are stretched or shrunk to stay connected.
+///
+/// Faces that are not affected by the offset are carried over into the new
+/// solid unchanged.
+///
+/// # Implementation Note
+///
+/// Only solids bounded by planar faces with straight edges are supported.
+/// Vertices are identified by their position, which means faces that are
+/// supposed to touch must share exactly the same vertex positions.
+pub fn offset_faces(
+    solid: &Handle<Solid>,
+    faces: &[Handle<Face>],
+    distance: impl Into<Scalar>,
+    objects: &mut Service<Objects>,
+) -> Result<Handle<Solid>, OffsetError> {
+    let distance = distance.into();
+    let polyhedron = Polyhedron::from_solid(solid)?;
+
+    for face in faces {
+        if !polyhedron.faces.iter().any(|f| &f.face == face) {
+            return Err(OffsetError::UnknownFace);
+        }
+    }
+
+    let vertices = polyhedron.offset_vertices(|face| {
+        if faces.contains(&face.face) {
+            distance
+        } else {
+            Scalar::ZERO
+        }
+    });
+
+    let mut shells = Vec::new();
+    for shell in solid.shells() {
+        let mut shell_faces = Vec::new();
+
+        for face in shell.faces() {
+            let face = polyhedron
+                .faces
+                .iter()
+                .find(|f| &f.face == face)
+                .expect("Polyhedron was created from the same solid");
+
+            let cycles = face.map_cycles(&vertices);
+            if cycles == face.cycles {
+                shell_faces.push(face.face.clone());
+                continue;
+            }
+
+            if face.is_inverted_by(&cycles) {
+                return Err(OffsetError::FaceCollapsed(distance));
+            }
+
+            shell_faces.push(build_face(
+                face.normal,
+                &cycles,
+                face.face.color(),
+                face.face.attributes().clone(),
+                objects,
+            ));
+        }
+
+        shells.push(Shell::builder().with_faces(shell_faces).build(objects));
+    }
+
+    Ok(Solid::builder().with_shells(shells).build(objects))
+}
+
+/// An error that can occur while offsetting faces
+#[derive(Clone, Debug, Eq, PartialEq, thiserror::Error)]
+pub enum OffsetError {
+    /// The solid has a face that isn't planar, or has curved edges
+    #[error("Offsetting is only supported for solids bounded by planar faces")]
+    CurvedFace,
+
+    /// A face that should be offset is not part of the solid
+    #[error("Face to be offset is not part of the solid")]
+    UnknownFace,
+
+    /// The offset is so large, that a face collapses
+    #[error("Offsetting faces by {0} collapses an adjacent face")]
+    FaceCollapsed(Scalar),
+}
+
+impl From<PolyhedronError> for OffsetError {
+    fn from(err: PolyhedronError) -> Self {
+        match err {
+            PolyhedronError::CurvedFace => Self::CurvedFace,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use fj_math::Scalar;
+
+    use crate::{
+        algorithms::sweep::Sweep,
+        iter::ObjectIters,
+        objects::{Face, Objects, Sketch, Solid},
+        services::{Service, Services},
+        storage::Handle,
+    };
+
+    use super::{offset_faces, OffsetError};
+
+    #[test]
+    fn offset_top_face() -> anyhow::Result<()> {
+        let mut services = Services::new();
+
+        let (solid, top) = cube(&mut services.objects);
+        let offset = offset_faces(&solid, &[top], 0.5, &mut services.objects)?;
+
+        assert_eq!(offset.face_iter().count(), 6);
+
+        let max_z = offset
+            .global_vertex_iter()
+            .map(|vertex| vertex.position().z)
+            .max()
+            .expect("Expected solid to have vertices");
+        assert!((max_z - 2.5).abs() < Scalar::from(1e-9));
+
+        // The bottom face is not adjacent to the top face, and stays as it is.
+        let original = solid.shells().flat_map(|shell| shell.faces().clone());
+        assert_eq!(
+            original
+                .filter(|face| offset.find_face(face).is_some())
+                .count(),
+            1
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn offset_rejects_collapsing_faces() {
+        let mut services = Services::new();
+
+        let (solid, top) = cube(&mut services.objects);
+        let result = offset_faces(&solid, &[top], -3., &mut services.objects);
+
+        assert!(matches!(result, Err(OffsetError::FaceCollapsed(_))));
+    }
+
+    fn cube(objects: &mut Service<Objects>) -> (Handle<Solid>, Handle<Face>) {
+        let surface = objects.surfaces.xy_plane();
+        let solid = Sketch::builder()
+            .with_polygon_from_points(
+                surface,
+                [[-1., -1.], [1., -1.], [1., 1.], [-1., 1.]],
+                objects,
+            )
+            .build(objects)
+            .sweep([0., 0., 2.], objects);
+
+        let top = solid
+            .shells()
+            .flat_map(|shell| shell.faces().clone())
+            .find(|face| {
+                face.exterior().half_edges().all(|half_edge| {
+                    half_edge.back().global_form().position().z
+                        == Scalar::from(2.)
+                })
+            })
+            .expect("Expected cube to have a top face");
+
+        (solid, top)
+    }
+}
@@ -0,0 +1,16 @@
+//! Offsetting objects by a distance
+//!
+//! Moving selected faces of a solid along their normals, while the adjacent
+//! faces follow along, is commonly known as "push/pull" in direct modeling.
+//! Offsetting cycles is the 2D equivalent, useful for clearance outlines and
+//! similar profiles, and for compensating the kerf of cutting processes.
+
+mod cycle;
+mod face;
+mod profile;
+
+pub use self::{
+    cycle::{offset_cycle, CycleOffsetError},
+    face::{offset_faces, OffsetError},
+    profile::{offset_profiles, ProfileOffsetError},
+};
@@ -0,0 +1,209 @@
+use fj_math::{Point, Scalar};
+
+use crate::{
+    algorithms::{
+        approx::{Approx, Tolerance},
+        point_in_face::{polygon_segments, winding_number},
+    },
+    geometry::{path::GlobalPath, surface::SurfaceGeometry},
+    insert::Insert,
+    objects::{Cycle, Face, FaceSet, Objects},
+    services::Service,
+    storage::Handle,
+};
+
+use super::{offset_cycle, CycleOffsetError};
+
+/// Offset the profiles of flat faces by a signed distance
+///
+/// This compensates for the kerf of cutting processes, like laser or water jet
+/// cutting, that remove material along the cut. Each exterior cycle is offset
+/// outward by the distance, and each interior cycle (a hole) inward. A
+/// positive distance adds material on all sides of a face, so the cut part
+/// ends up with the intended size. A negative distance removes it, which
+/// leaves clearance instead.
+///
+/// Holes that close up disappear. If an exterior falls apart into multiple
+/// cycles, each of them becomes its own face, together with the holes that lie
+/// within it.
+///
+/// See [`offset_cycle`] for which cycles are supported.
+pub fn offset_profiles(
+    faces: &FaceSet,
+    distance: impl Into<Scalar>,
+    tolerance: impl Into<Tolerance>,
+    objects: &mut Service<Objects>,
+) -> Result<FaceSet, ProfileOffsetError> {
+    let distance = distance.into();
+    let tolerance = tolerance.into();
+
+    let mut offset = FaceSet::new();
+
+    for face in faces {
+        if !matches!(
+            face.surface().geometry(),
+            SurfaceGeometry::Swept {
+                u: GlobalPath::Line(_),
+                ..
+            }
+        ) {
+            return Err(ProfileOffsetError::CurvedFace);
+        }
+
+        let exteriors =
+            offset_cycle(face.exterior(), distance, tolerance, objects)?;
+
+        let mut interiors = Vec::new();
+        for interior in face.interiors() {
+            interiors
+                .extend(offset_cycle(interior, -distance, tolerance, objects)?);
+        }
+
+        if let [exterior] = &exteriors[..] {
+            let face = Face::new(exterior.clone(), interiors, face.color())
+                .with_attributes(face.attributes().clone());
+            offset.extend([face.insert(objects)]);
+            continue;
+        }
+
+        for exterior in exteriors {
+            let polygon = polygon(&exterior, tolerance);
+            let within = interiors
+                .iter()
+                .filter(|interior| {
+                    winding_number(polygon_segments(&polygon), start(interior))
+                        != 0
+                })
+                .cloned()
+                .collect::<Vec<_>>();
+
+            let face = Face::new(exterior, within, face.color())
+                .with_attributes(face.attributes().clone());
+            offset.extend([face.insert(objects)]);
+        }
+    }
+
+    Ok(offset)
+}
+
+/// An error that can occur while offsetting profiles
+#[derive(Clone, Debug, Eq, PartialEq, thiserror::Error)]
+pub enum ProfileOffsetError {
+    /// A face doesn't lie on a plane
+    #[error("Offsetting profiles is only supported for planar faces")]
+    CurvedFace,
+
+    /// A cycle of a face can't be offset
+    #[error(transparent)]
+    Cycle(#[from] CycleOffsetError),
+}
+
+/// Approximate a cycle as a polygon in surface coordinates
+fn polygon(cycle: &Handle<Cycle>, tolerance: Tolerance) -> Vec<Point<2>> {
+    cycle
+        .approx(tolerance)
+        .points()
+        .into_iter()
+        .map(|point| point.local_form)
+        .collect()
+}
+
+/// The position of a cycle's first vertex, in surface coordinates
+fn start(cycle: &Handle<Cycle>) -> Point<2> {
+    cycle
+        .half_edges()
+        .next()
+        .expect("Cycle has at least one half-edge")
+        .back()
+        .surface_form()
+        .position()
+}
+
+#[cfg(test)]
+mod tests {
+    use fj_math::Scalar;
+
+    use crate::{
+        algorithms::approx::Tolerance,
+        builder::FaceBuilder,
+        insert::Insert,
+        objects::{Face, FaceSet},
+        partial::HasPartial,
+        services::Services,
+    };
+
+    use super::offset_profiles;
+
+    #[test]
+    fn grow_plate_and_shrink_hole() -> anyhow::Result<()> {
+        let mut services = Services::new();
+
+        let surface = services.objects.surfaces.xy_plane();
+        let plate = Face::partial()
+            .with_exterior_polygon_from_points(
+                surface.clone(),
+                [[-2., -2.], [2., -2.], [2., 2.], [-2., 2.]],
+            )
+            .with_interior_circle(surface, [0., 0.], 1., &mut services.objects)
+            .build(&mut services.objects)
+            .insert(&mut services.objects);
+
+        let faces = [plate.clone()].into_iter().collect::<FaceSet>();
+
+        let offset = offset_profiles(
+            &faces,
+            0.1,
+            Tolerance::from_scalar(0.001)?,
+            &mut services.objects,
+        )?
+        .into_iter()
+        .collect::<Vec<_>>();
+        assert_eq!(offset.len(), 1);
+
+        let interiors = offset[0].interiors().collect::<Vec<_>>();
+        assert_eq!(interiors.len(), 1);
+
+        // The hole shrinks, and keeps its winding.
+        let half_edge = interiors[0].half_edges().next().unwrap();
+        let position = half_edge.back().surface_form().position();
+        assert!(
+            (position.coords.magnitude() - Scalar::from(0.9)).abs()
+                < Scalar::from(1e-9)
+        );
+        assert_eq!(
+            interiors[0].winding(),
+            plate.interiors().next().unwrap().winding()
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn close_hole() -> anyhow::Result<()> {
+        let mut services = Services::new();
+
+        let surface = services.objects.surfaces.xy_plane();
+        let plate = Face::partial()
+            .with_exterior_polygon_from_points(
+                surface.clone(),
+                [[-2., -2.], [2., -2.], [2., 2.], [-2., 2.]],
+            )
+            .with_interior_circle(surface, [0., 0.], 1., &mut services.objects)
+            .build(&mut services.objects)
+            .insert(&mut services.objects);
+
+        let faces = [plate].into_iter().collect::<FaceSet>();
+
+        let offset = offset_profiles(
+            &faces,
+            1.5,
+            Tolerance::from_scalar(0.001)?,
+            &mut services.objects,
+        )?;
+
+        let face = offset.into_iter().next().unwrap();
+        assert_eq!(face.interiors().count(), 0);
+
+        Ok(())
+    }
+}
@@ -0,0 +1,251 @@
+//! Replicating solids in regular patterns
+//!
+//! Patterns are useful for repeated features, like the holes of a bolt circle,
+//! or a row of ribs. Grids can be created by patterning a pattern.
+
+use std::f64::consts::TAU;
+
+use fj_math::{Point, Scalar, Transform, Vector};
+
+use crate::{
+    algorithms::transform::TransformObject,
+    objects::{Objects, Solid},
+    services::Service,
+    storage::Handle,
+};
+
+/// A regular arrangement of instances of an object
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Pattern {
+    /// Instances in a row, each offset from the previous one
+    Linear {
+        /// The offset between two consecutive instances
+        offset: Vector<3>,
+
+        /// The number of instances, including the original
+        count: usize,
+    },
+
+    /// Instances evenly distributed around an axis
+    Circular {
+        /// A point on the axis
+        center: Point<3>,
+
+        /// The direction of the axis
+        ///
+        /// Instances are placed counter-clockwise around the axis, when looking
+        /// against its direction.
+        axis: Vector<3>,
+
+        /// The number of instances, including the original
+        count: usize,
+    },
+}
+
+impl Pattern {
+    /// Compute the transforms that place each instance of the pattern
+    ///
+    /// The first transform is the identity, which leaves the original object
+    /// in place.
+    pub fn transforms(&self) -> Result<Vec<Transform>, PatternError> {
+        match *self {
+            Self::Linear { offset, count } => {
+                if count == 0 {
+                    return Err(PatternError::NoInstances);
+                }
+
+                Ok((0..count)
+                    .map(|i| Transform::translation(offset * i as f64))
+                    .collect())
+            }
+            Self::Circular {
+                center,
+                axis,
+                count,
+            } => {
+                if count == 0 {
+                    return Err(PatternError::NoInstances);
+                }
+                if axis.magnitude() == Scalar::ZERO {
+                    return Err(PatternError::ZeroAxis);
+                }
+
+                let axis = axis.normalize();
+                let angle = Scalar::from(TAU / count as f64);
+
+                Ok((0..count)
+                    .map(|i| {
+                        Transform::translation(center.coords)
+                            * Transform::rotation(axis * angle * i as f64)
+                            * Transform::translation(-center.coords)
+                    })
+                    .collect())
+            }
+        }
+    }
+}
+
+/// Replicate a solid according to a pattern
+///
+/// Returns a single solid that contains the shells of all instances. The
+/// instances must not touch or overlap, as they are not joined.
+pub fn pattern(
+    solid: &Handle<Solid>,
+    pattern: &Pattern,
+    objects: &mut Service<Objects>,
+) -> Result<Handle<Solid>, PatternError> {
+    let transforms = pattern.transforms()?;
+
+    let mut shells = solid.shells().cloned().collect::<Vec<_>>();
+    for transform in &transforms[1..] {
+        let instance = solid.clone().transform(transform, objects);
+        shells.extend(instance.shells().cloned());
+    }
+
+    Ok(Solid::builder().with_shells(shells).build(objects))
+}
+
+/// An error that can occur while creating a pattern
+#[derive(Clone, Debug, Eq, PartialEq, thiserror::Error)]
+pub enum PatternError {
+    /// The pattern has no instances
+    #[error("Pattern must have at least one instance")]
+    NoInstances,
+
+    /// The axis of a circular pattern has zero length
+    #[error("Axis of circular pattern must not have zero length")]
+    ZeroAxis,
+}
+
+#[cfg(test)]
+mod tests {
+    use fj_interop::mesh::Color;
+    use fj_math::{Point, Scalar, Vector};
+
+    use crate::{
+        algorithms::{
+            approx::Tolerance, sweep::Sweep, triangulate::Triangulate,
+        },
+        builder::{CycleBuilder, FaceBuilder},
+        insert::Insert,
+        iter::ObjectIters,
+        objects::{Cycle, Face, Sketch},
+        partial::HasPartial,
+        services::Services,
+    };
+
+    use super::{pattern, Pattern, PatternError};
+
+    #[test]
+    fn linear_pattern() -> anyhow::Result<()> {
+        let mut services = Services::new();
+
+        let surface = services.objects.surfaces.xy_plane();
+        let face = Face::partial()
+            .with_exterior_polygon_from_points(
+                surface,
+                [[0., 0.], [1., 0.], [1., 1.], [0., 1.]],
+            )
+            .build(&mut services.objects)
+            .insert(&mut services.objects);
+        let solid = Sketch::builder()
+            .with_faces([face])
+            .build(&mut services.objects)
+            .sweep([0., 0., 1.], &mut services.objects);
+
+        let solid = pattern(
+            &solid,
+            &Pattern::Linear {
+                offset: Vector::from([2., 0., 0.]),
+                count: 3,
+            },
+            &mut services.objects,
+        )?;
+
+        assert_eq!(solid.shell_iter().count(), 3);
+        assert_eq!(solid.face_iter().count(), 3 * 6);
+
+        let last = Point::from([5., 1., 1.]);
+        assert!(solid
+            .global_vertex_iter()
+            .any(|vertex| vertex.position() == last));
+
+        Ok(())
+    }
+
+    #[test]
+    fn circular_pattern() -> anyhow::Result<()> {
+        let pattern = Pattern::Circular {
+            center: Point::from([1., 0., 0.]),
+            axis: Vector::from([0., 0., 2.]),
+            count: 4,
+        };
+
+        let transforms = pattern.transforms()?;
+        assert_eq!(transforms.len(), 4);
+
+        let point = Point::from([2., 0., 1.]);
+        let instances = transforms
+            .iter()
+            .map(|transform| transform.transform_point(&point))
+            .collect::<Vec<_>>();
+
+        let expected =
+            [[2., 0., 1.], [1., 1., 1.], [0., 0., 1.], [1., -1., 1.]];
+        for (instance, expected) in instances.into_iter().zip(expected) {
+            assert!(
+                instance.distance_to(&Point::from(expected))
+                    < Scalar::from(1e-12)
+            );
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn circular_pattern_of_cylinders() -> anyhow::Result<()> {
+        let mut services = Services::new();
+
+        let surface = services.objects.surfaces.xy_plane();
+        let exterior = Cycle::partial()
+            .with_circle(surface, [3., 0.], 0.5, &mut services.objects)
+            .build(&mut services.objects)
+            .insert(&mut services.objects);
+        let face = Face::partial()
+            .with_exterior(exterior)
+            .with_color(Color::default())
+            .build(&mut services.objects)
+            .insert(&mut services.objects);
+        let solid = Sketch::builder()
+            .with_faces([face])
+            .build(&mut services.objects)
+            .sweep([0., 0., 1.], &mut services.objects);
+
+        let solid = pattern(
+            &solid,
+            &Pattern::Circular {
+                center: Point::origin(),
+                axis: Vector::from([0., 0., 1.]),
+                count: 6,
+            },
+            &mut services.objects,
+        )?;
+        assert_eq!(solid.shell_iter().count(), 6);
+
+        // The rotated instances must still approximate consistently.
+        let mesh = (&*solid, Tolerance::from_scalar(0.01)?).triangulate();
+        assert!(mesh.triangles().count() > 0);
+
+        Ok(())
+    }
+
+    #[test]
+    fn pattern_without_instances() {
+        let pattern = Pattern::Linear {
+            offset: Vector::from([1., 0., 0.]),
+            count: 0,
+        };
+
+        assert_eq!(pattern.transforms().err(), Some(PatternError::NoInstances));
+    }
+}
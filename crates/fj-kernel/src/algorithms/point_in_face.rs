@@ -0,0 +1,245 @@
+//! Locating points relative to faces
+//!
+//! See [`LocatePoint`].
+
+use fj_math::{Point, Scalar, Segment};
+
+use crate::{objects::Face, tolerance::ModelTolerance};
+
+use super::approx::{cycle::CycleApprox, face::FaceApprox, Approx};
+
+/// Locate a point, given in surface coordinates, relative to a face
+pub trait LocatePoint {
+    /// Determine whether the point is inside, outside, or on the boundary
+    ///
+    /// A point is inside, if the exterior of the face winds around it, and none
+    /// of the interiors do. Points that are closer to the boundary than the
+    /// tolerance are considered to be on the boundary.
+    fn locate_point(
+        &self,
+        point: impl Into<Point<2>>,
+        tolerance: impl Into<ModelTolerance>,
+    ) -> PointLocation;
+}
+
+impl LocatePoint for Face {
+    fn locate_point(
+        &self,
+        point: impl Into<Point<2>>,
+        tolerance: impl Into<ModelTolerance>,
+    ) -> PointLocation {
+        // Curved edges are approximated within half the tolerance. That way,
+        // any point on the actual edge is still within the tolerance of the
+        // approximation, and is considered to be on the boundary.
+        let tolerance = tolerance.into();
+        let approx_tolerance =
+            ModelTolerance::from_scalar(tolerance.inner() / Scalar::TWO)
+                .expect("Half of a valid tolerance is still valid");
+
+        self.approx(approx_tolerance).locate_point(point, tolerance)
+    }
+}
+
+impl LocatePoint for FaceApprox {
+    fn locate_point(
+        &self,
+        point: impl Into<Point<2>>,
+        tolerance: impl Into<ModelTolerance>,
+    ) -> PointLocation {
+        let cycles = [&self.exterior]
+            .into_iter()
+            .chain(&self.interiors)
+            .map(cycle_segments);
+
+        locate_in_polygon(cycles, point.into(), tolerance.into().inner())
+    }
+}
+
+/// The location of a point relative to a face or solid
+///
+/// See [`LocatePoint`] and [`point_in_solid::locate`].
+///
+/// [`point_in_solid::locate`]: super::point_in_solid::locate
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash, Ord, PartialOrd)]
+pub enum PointLocation {
+    /// The point is inside of the face or solid
+    Inside,
+
+    /// The point is on the boundary of the face or solid
+    OnBoundary,
+
+    /// The point is outside of the face or solid
+    Outside,
+}
+
+/// Compute how often a closed polygon winds around a point
+///
+/// The winding number is positive for counter-clockwise polygons, and negative
+/// for clockwise ones. It is zero, if the point is outside of the polygon.
+///
+/// The result is not meaningful for points that are on the polygon.
+pub fn winding_number(
+    polygon: impl IntoIterator<Item = Segment<2>>,
+    point: impl Into<Point<2>>,
+) -> i32 {
+    let point = point.into();
+    let mut winding_number = 0;
+
+    for segment in polygon {
+        let [a, b] = segment.points();
+
+        // Count each time the polygon crosses the horizontal line through the
+        // point to the right of it, upwards or downwards.
+        if a.v <= point.v {
+            if b.v > point.v && orient2d(a, b, point) > 0. {
+                winding_number += 1;
+            }
+        } else if b.v <= point.v && orient2d(a, b, point) < 0. {
+            winding_number -= 1;
+        }
+    }
+
+    winding_number
+}
+
+/// Compute the segments of a closed polygon, given by its points
+///
+/// The polygon is closed from its last point back to its first. Segments of
+/// length zero, like the closing one of a polygon that already repeats its
+/// first point at the end, are skipped. They don't affect the
+/// [`winding_number`].
+pub fn polygon_segments(
+    polygon: &[Point<2>],
+) -> impl Iterator<Item = Segment<2>> + '_ {
+    polygon
+        .iter()
+        .zip(polygon.iter().cycle().skip(1))
+        .filter(|(a, b)| a != b)
+        .map(|(&a, &b)| Segment::from_points([a, b]))
+}
+
+/// Locate a point relative to a polygon with holes
+///
+/// The first of the cycles is the exterior of the polygon, all others are its
+/// holes. Their winding doesn't matter.
+pub(crate) fn locate_in_polygon(
+    cycles: impl IntoIterator<Item = Vec<Segment<2>>>,
+    point: Point<2>,
+    tolerance: Scalar,
+) -> PointLocation {
+    let mut is_inside = true;
+
+    for (i, cycle) in cycles.into_iter().enumerate() {
+        if cycle
+            .iter()
+            .any(|segment| distance_to_segment(segment, point) < tolerance)
+        {
+            return PointLocation::OnBoundary;
+        }
+
+        let is_within_cycle = winding_number(cycle, point) != 0;
+        let is_exterior = i == 0;
+
+        if is_within_cycle != is_exterior {
+            is_inside = false;
+        }
+    }
+
+    if is_inside {
+        PointLocation::Inside
+    } else {
+        PointLocation::Outside
+    }
+}
+
+pub(crate) fn cycle_segments(cycle: &CycleApprox) -> Vec<Segment<2>> {
+    cycle
+        .points()
+        .windows(2)
+        .map(|points| {
+            Segment::from_points(
+                [&points[0], &points[1]].map(|point| point.local_form),
+            )
+        })
+        .collect()
+}
+
+fn distance_to_segment(segment: &Segment<2>, point: Point<2>) -> Scalar {
+    let [a, b] = segment.points();
+
+    let ab = b - a;
+    let length_squared = ab.dot(&ab);
+    if length_squared == Scalar::ZERO {
+        return point.distance_to(&a);
+    }
+
+    let t = ((point - a).dot(&ab) / length_squared)
+        .max(Scalar::ZERO)
+        .min(Scalar::ONE);
+
+    point.distance_to(&(a + ab * t))
+}
+
+/// Compute the orientation of `c` relative to the line from `a` to `b`
+///
+/// The result is positive, if `c` is to the left of the line, negative, if it
+/// is to the right, and zero, if the three points are collinear. Uses robust
+/// predicates, so the sign is always exact.
+pub(crate) fn orient2d(a: Point<2>, b: Point<2>, c: Point<2>) -> f64 {
+    let [a, b, c] = [a, b, c].map(|point| [point.u.into(), point.v.into()]);
+    robust_predicates::orient2d(&a, &b, &c)
+}
+
+#[cfg(test)]
+mod tests {
+    use fj_math::{PolyChain, Scalar};
+
+    use crate::{
+        builder::FaceBuilder, insert::Insert, objects::Face,
+        partial::HasPartial, services::Services, tolerance::ModelTolerance,
+    };
+
+    use super::{winding_number, LocatePoint, PointLocation};
+
+    #[test]
+    fn winding_number_of_polygon() {
+        let square =
+            PolyChain::from_points([[0., 0.], [2., 0.], [2., 2.], [0., 2.]])
+                .close();
+
+        assert_eq!(winding_number(square.segments(), [1., 1.]), 1);
+        assert_eq!(winding_number(square.segments(), [3., 1.]), 0);
+        assert_eq!(winding_number(square.reverse().segments(), [1., 1.]), -1);
+    }
+
+    #[test]
+    fn locate_point_in_face_with_hole() {
+        let mut services = Services::new();
+
+        let surface = services.objects.surfaces.xy_plane();
+        let face = Face::partial()
+            .with_exterior_polygon_from_points(
+                surface.clone(),
+                [[0., 0.], [4., 0.], [4., 4.], [0., 4.]],
+            )
+            .with_interior_circle(surface, [2., 2.], 1., &mut services.objects)
+            .build(&mut services.objects)
+            .insert(&mut services.objects);
+
+        let tolerance = ModelTolerance::default();
+        let locate = |point: [f64; 2]| face.locate_point(point, tolerance);
+
+        assert_eq!(locate([0.5, 0.5]), PointLocation::Inside);
+        assert_eq!(locate([2., 2.]), PointLocation::Outside);
+        assert_eq!(locate([5., 2.]), PointLocation::Outside);
+        assert_eq!(locate([4., 2.]), PointLocation::OnBoundary);
+        assert_eq!(locate([0., 0.]), PointLocation::OnBoundary);
+
+        // Points on the circle are on the boundary, even if they're not on its
+        // approximation.
+        assert_eq!(locate([3., 2.]), PointLocation::OnBoundary);
+        let (sin, cos) = Scalar::from(1.).sin_cos();
+        let on_circle = [2. + cos.into_f64(), 2. + sin.into_f64()];
+        assert_eq!(locate(on_circle), PointLocation::OnBoundary);
+    }
+}
@@ -0,0 +1,160 @@
+//! Locating points relative to solids
+//!
+//! See [`locate`].
+
+use fj_interop::{debug::DebugInfo, mesh::Mesh};
+use fj_math::{Point, Scalar, Triangle};
+
+use crate::{
+    objects::{Face, Solid},
+    storage::Handle,
+    tolerance::ModelTolerance,
+};
+
+use super::{
+    approx::{curve::CurveCache, Approx, Tolerance},
+    distance::closest_point_on_triangle,
+    point_in_face::PointLocation,
+    triangulate::Triangulate,
+};
+
+/// Locate a point relative to a solid
+///
+/// Determines whether the point is inside, outside, or on the boundary of the
+/// solid. Points that are closer to the boundary than the tolerance are
+/// considered to be on the boundary.
+///
+/// The solid is approximated with half of the tolerance, and the point is
+/// located relative to that approximation, using its generalized winding
+/// number. Unlike casting a ray, this doesn't depend on the direction of the
+/// ray, and is not affected by the ray hitting edges or vertices of the solid.
+pub fn locate(
+    point: impl Into<Point<3>>,
+    solid: &Solid,
+    tolerance: impl Into<ModelTolerance>,
+) -> PointLocation {
+    let point = point.into();
+
+    // Curved faces are approximated within half the tolerance. That way, any
+    // point on the actual boundary is still within the tolerance of the
+    // approximation, and is considered to be on the boundary.
+    let tolerance = tolerance.into();
+    let approx_tolerance =
+        Tolerance::from_scalar(tolerance.inner() / Scalar::TWO)
+            .expect("Half of a valid tolerance is still valid");
+
+    let triangles = face_triangles(solid, approx_tolerance)
+        .into_iter()
+        .flat_map(|(_, triangles)| triangles)
+        .collect::<Vec<_>>();
+
+    if triangles.iter().any(|triangle| {
+        let closest = closest_point_on_triangle(triangle, point);
+        (point - closest).magnitude() < tolerance.inner()
+    }) {
+        return PointLocation::OnBoundary;
+    }
+
+    if encloses(&triangles, point) {
+        PointLocation::Inside
+    } else {
+        PointLocation::Outside
+    }
+}
+
+/// Determine whether a closed triangle mesh encloses a point
+///
+/// The point is expected to not be on the mesh itself.
+pub(crate) fn encloses(triangles: &[Triangle<3>], point: Point<3>) -> bool {
+    let solid_angle = triangles
+        .iter()
+        .map(|triangle| solid_angle(triangle, point))
+        .fold(Scalar::ZERO, |sum, angle| sum + angle);
+    let winding_number = solid_angle / (Scalar::TAU * 2.);
+
+    // The winding number is close to an integer for closed shells. Which sign
+    // it has, depends on the orientation of the shells.
+    winding_number.abs() > Scalar::from(0.5)
+}
+
+/// Triangulate the faces of a solid, keeping track of which face each triangle
+/// belongs to
+///
+/// All faces share the same approximations of their edges, which means the
+/// triangles form a closed mesh, if the solid is closed.
+pub(crate) fn face_triangles(
+    solid: &Solid,
+    tolerance: Tolerance,
+) -> Vec<(Handle<Face>, Vec<Triangle<3>>)> {
+    let mut cache = CurveCache::new();
+
+    solid
+        .shells()
+        .flat_map(|shell| shell.faces().clone())
+        .map(|face| {
+            let mut mesh = Mesh::new();
+            face.approx_with_cache(tolerance, &mut cache)
+                .triangulate_into_mesh(&mut mesh, &mut DebugInfo::new());
+
+            let triangles = mesh
+                .triangles()
+                .map(|triangle| triangle.inner)
+                .collect::<Vec<_>>();
+
+            (face, triangles)
+        })
+        .collect()
+}
+
+/// Compute the signed solid angle that a triangle subtends at a point
+///
+/// Uses the formula by Van Oosterom and Strackee.
+fn solid_angle(triangle: &Triangle<3>, point: Point<3>) -> Scalar {
+    let [a, b, c] = triangle.points().map(|vertex| vertex - point);
+    let [la, lb, lc] = [a, b, c].map(|vector| vector.magnitude());
+
+    let numerator = a.dot(&b.cross(&c));
+    let denominator =
+        la * lb * lc + a.dot(&b) * lc + a.dot(&c) * lb + b.dot(&c) * la;
+
+    numerator.atan2(denominator) * 2.
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        algorithms::{point_in_face::PointLocation, sweep::Sweep},
+        objects::Sketch,
+        services::Services,
+        tolerance::ModelTolerance,
+    };
+
+    #[test]
+    fn locate_point_in_cube() {
+        let mut services = Services::new();
+
+        let surface = services.objects.surfaces.xy_plane();
+        let cube = Sketch::builder()
+            .with_polygon_from_points(
+                surface,
+                [[-1., -1.], [1., -1.], [1., 1.], [-1., 1.]],
+                &mut services.objects,
+            )
+            .build(&mut services.objects)
+            .sweep([0., 0., 2.], &mut services.objects);
+
+        let tolerance = ModelTolerance::from_scalar(0.001).unwrap();
+        let locate = |point: [f64; 3]| super::locate(point, &cube, tolerance);
+
+        assert_eq!(locate([0., 0., 1.]), PointLocation::Inside);
+        assert_eq!(locate([0.5, -0.9, 1.99]), PointLocation::Inside);
+        assert_eq!(locate([2., 0., 1.]), PointLocation::Outside);
+        assert_eq!(locate([0., 0., -0.5]), PointLocation::Outside);
+
+        // Faces, edges, and vertices are all on the boundary.
+        assert_eq!(locate([1., 0.5, 1.]), PointLocation::OnBoundary);
+        assert_eq!(locate([1., 1., 1.]), PointLocation::OnBoundary);
+        assert_eq!(locate([-1., -1., 2.]), PointLocation::OnBoundary);
+        assert_eq!(locate([0., 0., 2.0001]), PointLocation::OnBoundary);
+    }
+}
@@ -0,0 +1,302 @@
+//! Infrastructure for operations that move the planar faces of a solid
+//!
+//! Operations like shelling or offsetting faces move the planes that faces are
+//! defined on. Vertices then need to move to where the planes of their adjacent
+//! faces intersect, and the faces need to be rebuilt from those vertices.
+
+use std::collections::BTreeMap;
+
+use fj_interop::mesh::{Attributes, Color};
+use fj_math::{Line, Point, Scalar, Vector};
+
+use crate::{
+    builder::{CycleBuilder, SurfaceBuilder},
+    geometry::{
+        path::{GlobalPath, SurfacePath},
+        surface::SurfaceGeometry,
+    },
+    insert::Insert,
+    objects::{Cycle, Face, Objects, Solid},
+    partial::{
+        HasPartial, PartialGlobalVertex, PartialSurface, PartialSurfaceVertex,
+    },
+    services::Service,
+    storage::Handle,
+};
+
+/// A solid that is bounded by planar polygons only
+pub struct Polyhedron {
+    /// The faces of the solid
+    pub faces: Vec<PolyhedronFace>,
+}
+
+impl Polyhedron {
+    /// Extract the polygons from the faces of a solid
+    pub fn from_solid(solid: &Solid) -> Result<Self, PolyhedronError> {
+        let faces = solid
+            .shells()
+            .flat_map(|shell| shell.faces().clone())
+            .map(PolyhedronFace::from_face)
+            .collect::<Result<_, _>>()?;
+
+        Ok(Self { faces })
+    }
+
+    /// Compute the new vertex positions, after offsetting the faces
+    ///
+    /// `offset` returns the distance by which the plane of each face is moved
+    /// along its normal. Each vertex is moved to where the offset planes of its
+    /// adjacent faces meet. If those planes don't intersect in a single point
+    /// (for example, because all faces adjacent to a vertex are coplanar), the
+    /// vertex is moved by the smallest distance that puts it on all of them.
+    ///
+    /// The returned map associates the original vertex positions with the new
+    /// ones.
+    pub fn offset_vertices(
+        &self,
+        offset: impl Fn(&PolyhedronFace) -> Scalar,
+    ) -> BTreeMap<Point<3>, Point<3>> {
+        let mut planes_by_vertex = BTreeMap::new();
+        for face in &self.faces {
+            let offset = offset(face);
+
+            for &point in face.cycles.iter().flatten() {
+                planes_by_vertex
+                    .entry(point)
+                    .or_insert_with(Vec::new)
+                    .push((face.normal, offset));
+            }
+        }
+
+        planes_by_vertex
+            .into_iter()
+            .map(|(point, planes)| (point, point + displacement(&planes)))
+            .collect()
+    }
+}
+
+/// A planar face of a [`Polyhedron`]
+pub struct PolyhedronFace {
+    /// The original face
+    pub face: Handle<Face>,
+
+    /// The normal of the face, pointing away from its front side
+    pub normal: Vector<3>,
+
+    /// The polygons bounding the face, exterior first
+    pub cycles: Vec<Vec<Point<3>>>,
+}
+
+impl PolyhedronFace {
+    fn from_face(face: Handle<Face>) -> Result<Self, PolyhedronError> {
+        if !matches!(
+            face.surface().geometry(),
+            SurfaceGeometry::Swept {
+                u: GlobalPath::Line(_),
+                ..
+            }
+        ) {
+            return Err(PolyhedronError::CurvedFace);
+        }
+
+        let cycles = face
+            .all_cycles()
+            .map(cycle_points)
+            .collect::<Result<Vec<_>, _>>()?;
+        let normal = polygon_normal(&cycles[0]).normalize();
+
+        Ok(Self {
+            face,
+            normal,
+            cycles,
+        })
+    }
+
+    /// Map the polygons of the face to new vertex positions
+    pub fn map_cycles(
+        &self,
+        vertices: &BTreeMap<Point<3>, Point<3>>,
+    ) -> Vec<Vec<Point<3>>> {
+        self.cycles
+            .iter()
+            .map(|cycle| cycle.iter().map(|point| vertices[point]).collect())
+            .collect()
+    }
+
+    /// Determine whether the mapped polygons have turned inside out
+    ///
+    /// This is the case, if moving the vertices has reversed the direction of
+    /// any edge, which happens when a face collapses.
+    pub fn is_inverted_by(&self, mapped: &[Vec<Point<3>>]) -> bool {
+        self.cycles.iter().zip(mapped).any(|(original, mapped)| {
+            (0..original.len()).any(|i| {
+                let j = (i + 1) % original.len();
+                (original[j] - original[i]).dot(&(mapped[j] - mapped[i]))
+                    <= Scalar::ZERO
+            })
+        })
+    }
+}
+
+/// An error that can occur while converting a solid into a [`Polyhedron`]
+pub enum PolyhedronError {
+    /// A face is curved, or bounded by a curved edge
+    CurvedFace,
+}
+
+/// Build a planar face from its bounding polygons
+///
+/// The first polygon is the exterior of the face. It must be wound
+/// counter-clockwise, when looking at the face against `normal`. All other
+/// polygons are holes, and must be wound the other way.
+pub fn build_face(
+    normal: Vector<3>,
+    cycles: &[Vec<Point<3>>],
+    color: Color,
+    attributes: Attributes,
+    objects: &mut Service<Objects>,
+) -> Handle<Face> {
+    let (exterior, interiors) = cycles
+        .split_first()
+        .expect("Expected face to have an exterior");
+
+    let origin = exterior[0];
+    let u = (exterior[1] - origin).normalize();
+    let v = normal.normalize().cross(&u);
+
+    let surface = PartialSurface::from_axes(
+        GlobalPath::Line(Line::from_origin_and_direction(origin, u)),
+        v,
+    )
+    .build(objects)
+    .insert(objects);
+
+    // Converting the surface coordinates back into global ones isn't exact.
+    // Faces share their vertices with their neighbors, so use the exact
+    // positions instead.
+    let to_cycle = |points: &Vec<Point<3>>| {
+        let vertices = points.iter().map(|&point| {
+            let position = point - origin;
+            PartialSurfaceVertex {
+                position: Some([position.dot(&u), position.dot(&v)].into()),
+                surface: Some(surface.clone()),
+                global_form: PartialGlobalVertex {
+                    position: Some(point),
+                }
+                .into(),
+            }
+        });
+
+        Cycle::partial()
+            .with_poly_chain(vertices)
+            .close_with_line_segment()
+    };
+
+    Face::partial()
+        .with_exterior(to_cycle(exterior))
+        .with_interiors(interiors.iter().map(to_cycle))
+        .with_color(color)
+        .with_attributes(attributes)
+        .build(objects)
+        .insert(objects)
+}
+
+/// Compute the normal of a polygon, using Newell's method
+///
+/// The normal points towards the side from which the polygon appears to be
+/// wound counter-clockwise. Its magnitude is twice the area of the polygon.
+pub fn polygon_normal(points: &[Point<3>]) -> Vector<3> {
+    let mut normal = Vector::from([0., 0., 0.]);
+
+    for (i, a) in points.iter().enumerate() {
+        let b = points[(i + 1) % points.len()];
+        normal = normal
+            + Vector::from([
+                (a.y - b.y) * (a.z + b.z),
+                (a.z - b.z) * (a.x + b.x),
+                (a.x - b.x) * (a.y + b.y),
+            ]);
+    }
+
+    normal
+}
+
+fn cycle_points(
+    cycle: &Handle<Cycle>,
+) -> Result<Vec<Point<3>>, PolyhedronError> {
+    cycle
+        .half_edges()
+        .map(|half_edge| match half_edge.curve().path() {
+            SurfacePath::Line(_) => {
+                Ok(half_edge.back().global_form().position())
+            }
+            SurfacePath::Circle(_)
+            | SurfacePath::Ellipse(_)
+            | SurfacePath::Nurbs(_) => Err(PolyhedronError::CurvedFace),
+        })
+        .collect()
+}
+
+/// Compute the displacement that moves a point onto all offset planes
+///
+/// Solves the least-squares problem `n_i · d = o_i` for the displacement `d`,
+/// for all planes with unit normal `n_i` and offset `o_i`. The system is
+/// regularized slightly, so underdetermined systems result in the displacement
+/// of least magnitude.
+fn displacement(planes: &[(Vector<3>, Scalar)]) -> Vector<3> {
+    const REGULARIZATION: f64 = 1e-12;
+
+    let mut columns = [
+        Vector::from([REGULARIZATION, 0., 0.]),
+        Vector::from([0., REGULARIZATION, 0.]),
+        Vector::from([0., 0., REGULARIZATION]),
+    ];
+    let mut rhs = Vector::from([0., 0., 0.]);
+
+    for &(normal, offset) in planes {
+        for (i, column) in columns.iter_mut().enumerate() {
+            *column = *column + normal * normal.components[i];
+        }
+        rhs = rhs + normal * offset;
+    }
+
+    // The matrix is small, so solving by Cramer's rule is fine.
+    let [c0, c1, c2] = columns;
+    let determinant = c0.dot(&c1.cross(&c2));
+
+    Vector::from([
+        rhs.dot(&c1.cross(&c2)),
+        c0.dot(&rhs.cross(&c2)),
+        c0.dot(&c1.cross(&rhs)),
+    ]) / determinant
+}
+
+#[cfg(test)]
+mod tests {
+    use fj_math::{Scalar, Vector};
+
+    use super::displacement;
+
+    #[test]
+    fn displacement_at_corner() {
+        let planes = [
+            (Vector::unit_x(), Scalar::from(-1.)),
+            (Vector::unit_y(), Scalar::from(-2.)),
+            (Vector::unit_z(), Scalar::from(-3.)),
+        ];
+
+        let d = displacement(&planes);
+        assert!((d - Vector::from([-1., -2., -3.])).magnitude() < 1e-9.into());
+    }
+
+    #[test]
+    fn displacement_on_flat_region() {
+        let planes = [
+            (Vector::unit_z(), Scalar::from(2.)),
+            (Vector::unit_z(), Scalar::from(2.)),
+        ];
+
+        let d = displacement(&planes);
+        assert!((d - Vector::from([0., 0., 2.])).magnitude() < 1e-9.into());
+    }
+}
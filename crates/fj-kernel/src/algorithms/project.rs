@@ -0,0 +1,761 @@
+//! Projection of curves onto surfaces
+//!
+//! Projecting a curve onto a surface yields its representation in the
+//! coordinates of that surface. This is required for features that are wrapped
+//! around a surface, like an engraving on a cylinder, or a line that splits a
+//! face.
+
+use std::collections::BTreeMap;
+
+use fj_math::{signed_area, Point, Scalar, Vector};
+
+use crate::{
+    algorithms::{
+        approx::{Approx, Tolerance},
+        point_in_face::{polygon_segments, winding_number},
+    },
+    builder::{CycleBuilder, FaceBuilder},
+    geometry::{path::GlobalPath, surface::SurfaceGeometry},
+    insert::Insert,
+    objects::{Cycle, Face, HalfEdge, Objects, Sketch, Surface},
+    partial::HasPartial,
+    services::Service,
+    storage::Handle,
+};
+
+/// Project a point onto a surface
+///
+/// Returns the surface coordinates of the point on the surface that is closest
+/// to the provided point. Should multiple points be equally close, as is the
+/// case for a point on the axis of a cylinder, any of them might be returned.
+pub fn project_point(
+    point: impl Into<Point<3>>,
+    surface: &SurfaceGeometry,
+) -> Result<Point<2>, ProjectError> {
+    let point = point.into();
+
+    let guess = initial_guesses(point, surface)?
+        .into_iter()
+        .min_by_key(|&guess| {
+            (surface.point_from_surface_coords(guess) - point).magnitude()
+        })
+        .expect("Expected at least one initial guess");
+
+    refine(point, surface, guess)
+}
+
+/// Project a polygonal chain onto a surface
+///
+/// Each point is projected using [`project_point`]. On surfaces that wrap
+/// around, like cylinders, the coordinates of consecutive points are chosen
+/// such that the chain doesn't jump back when it crosses the seam of the
+/// surface.
+pub fn project_polyline(
+    points: impl IntoIterator<Item = impl Into<Point<3>>>,
+    surface: &SurfaceGeometry,
+) -> Result<Vec<Point<2>>, ProjectError> {
+    let period = period_u(surface);
+
+    let mut projected: Vec<Point<2>> = Vec::new();
+    for point in points {
+        let mut point = project_point(point, surface)?;
+
+        if let (Some(period), Some(previous)) = (period, projected.last()) {
+            let turns = ((point.u - previous.u) / period).round();
+            point.u -= turns * period;
+        }
+
+        projected.push(point);
+    }
+
+    Ok(projected)
+}
+
+/// Project a cycle onto a surface
+///
+/// The cycle is approximated with the provided tolerance, and the resulting
+/// polygon is projected onto the surface. Returns a new cycle on that surface.
+///
+/// The cycle must not wrap around the surface, as a cycle around a cylinder
+/// would. Such a cycle can't bound a face on its own.
+pub fn project_cycle(
+    cycle: &Cycle,
+    surface: &Handle<Surface>,
+    tolerance: impl Into<Tolerance>,
+    objects: &mut Service<Objects>,
+) -> Result<Handle<Cycle>, ProjectError> {
+    let tolerance = tolerance.into();
+
+    // The approximation is closed, meaning its last point is the same as its
+    // first one.
+    let points = cycle
+        .approx(tolerance)
+        .points()
+        .into_iter()
+        .map(|point| point.global_form);
+    let geometry = surface.geometry();
+    let mut points = project_polyline(points, &geometry)?;
+
+    if let (Some(first), Some(last)) = (points.first(), points.last()) {
+        if (last.u - first.u).abs() > Scalar::PI {
+            return Err(ProjectError::WrapsAround);
+        }
+    }
+    points.pop();
+
+    let simplified = simplify(points, &geometry, tolerance)?;
+
+    let cycle = Cycle::partial()
+        .with_poly_chain_from_points(surface.clone(), simplified)
+        .close_with_line_segment()
+        .build(objects)
+        .insert(objects);
+
+    Ok(cycle)
+}
+
+/// Project a face onto a surface
+///
+/// All cycles of the face are projected using [`project_cycle`]. This can be
+/// used to project the faces of a sketch onto a curved surface, for example.
+///
+/// Whether the front side of the new face points away from the surface or
+/// into it depends on the orientation of the original face relative to the
+/// surface.
+pub fn project_face(
+    face: &Face,
+    surface: &Handle<Surface>,
+    tolerance: impl Into<Tolerance>,
+    objects: &mut Service<Objects>,
+) -> Result<Handle<Face>, ProjectError> {
+    let tolerance = tolerance.into();
+
+    let exterior = project_cycle(face.exterior(), surface, tolerance, objects)?;
+    let interiors = face
+        .interiors()
+        .map(|cycle| project_cycle(cycle, surface, tolerance, objects))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    Ok(Face::new(exterior, interiors, face.color())
+        .with_attributes(face.attributes().clone())
+        .insert(objects))
+}
+
+/// Derive a sketch from the outline of faces, as seen from the front of a plane
+///
+/// The faces whose front side points towards the front of the plane are
+/// projected onto it. The edges that bound the region these faces cover make up
+/// the outline of the new sketch. Depending on the faces passed, that is the
+/// silhouette of a solid, or the outline of some of its faces. The new sketch
+/// can be the basis of further features, like any other.
+///
+/// The edges are approximated with the provided tolerance.
+///
+/// # Implementation Note
+///
+/// Faces are classified as a whole, by the direction of their projected
+/// exterior. This only works, if the region covered by the faces doesn't
+/// overlap itself when projected, as is the case for convex solids, or for
+/// extruded solids seen along the direction of extrusion. Curved faces that
+/// only partially face the plane aren't supported either.
+pub fn project_outline<'r>(
+    faces: impl IntoIterator<Item = &'r Handle<Face>>,
+    plane: &Handle<Surface>,
+    tolerance: impl Into<Tolerance>,
+    objects: &mut Service<Objects>,
+) -> Result<Handle<Sketch>, ProjectError> {
+    let tolerance = tolerance.into();
+
+    let geometry = plane.geometry();
+    if !matches!(
+        geometry,
+        SurfaceGeometry::Swept {
+            u: GlobalPath::Line(_),
+            ..
+        }
+    ) {
+        return Err(ProjectError::NotAPlane);
+    }
+
+    let mut front_faces = Vec::new();
+    for face in faces {
+        let exterior = face
+            .exterior()
+            .approx(tolerance)
+            .points()
+            .into_iter()
+            .map(|point| point.global_form);
+        let exterior = project_polyline(exterior, &geometry)?;
+
+        if signed_area(&exterior) > tolerance.inner() * tolerance.inner() {
+            front_faces.push(face);
+        }
+    }
+
+    // Edges that are shared by two of the faces are within the region they
+    // cover. Only the other ones bound it. Adjacent faces don't necessarily
+    // share the same global edge, so edges are identified by their vertices.
+    let half_edges = front_faces
+        .iter()
+        .flat_map(|face| face.all_cycles())
+        .flat_map(|cycle| cycle.half_edges())
+        .collect::<Vec<_>>();
+    let edge_id = |half_edge: &Handle<HalfEdge>| {
+        let mut ids = half_edge
+            .vertices()
+            .each_ref()
+            .map(|vertex| vertex.global_form().id());
+        ids.sort();
+        ids
+    };
+    let mut num_faces_by_edge = BTreeMap::new();
+    for half_edge in &half_edges {
+        *num_faces_by_edge.entry(edge_id(half_edge)).or_insert(0) += 1;
+    }
+    let mut boundary = half_edges
+        .into_iter()
+        .filter(|half_edge| num_faces_by_edge[&edge_id(half_edge)] == 1)
+        .map(Some)
+        .collect::<Vec<_>>();
+
+    // Connect the boundary edges to cycles. Each cycle is wound the same way
+    // as the edges of the faces it bounds: Exteriors counter-clockwise,
+    // interiors clockwise.
+    let mut exteriors = Vec::new();
+    let mut interiors = Vec::new();
+    while let Some(first) = boundary.iter_mut().find_map(Option::take) {
+        let start = first.back().global_form().id();
+
+        let mut points = Vec::new();
+        let mut half_edge = first;
+        loop {
+            points.extend(
+                half_edge
+                    .approx(tolerance)
+                    .points()
+                    .into_iter()
+                    .map(|point| point.global_form),
+            );
+
+            let end = half_edge.front().global_form().id();
+            if end == start {
+                break;
+            }
+
+            half_edge = boundary
+                .iter_mut()
+                .find(|half_edge| {
+                    matches!(
+                        half_edge,
+                        Some(half_edge)
+                            if half_edge.back().global_form().id() == end
+                    )
+                })
+                .and_then(Option::take)
+                .ok_or(ProjectError::Degenerate)?;
+        }
+
+        let points = project_polyline(points, &geometry)?;
+        let points = simplify(points, &geometry, tolerance)?;
+
+        if signed_area(&points) > Scalar::ZERO {
+            exteriors.push(points);
+        } else {
+            interiors.push(points);
+        }
+    }
+
+    // Each interior belongs to the smallest exterior that contains it.
+    let mut faces = exteriors
+        .iter()
+        .map(|exterior| {
+            Face::partial().with_exterior_polygon_from_points(
+                plane.clone(),
+                exterior.clone(),
+            )
+        })
+        .collect::<Vec<_>>();
+    for interior in interiors {
+        let (i, _) = exteriors
+            .iter()
+            .enumerate()
+            .filter(|(_, exterior)| {
+                winding_number(polygon_segments(exterior), interior[0]) != 0
+            })
+            .min_by_key(|(_, exterior)| signed_area(exterior))
+            .ok_or(ProjectError::Degenerate)?;
+
+        faces[i] = faces[i]
+            .clone()
+            .with_interior_polygon_from_points(plane.clone(), interior);
+    }
+
+    let faces = faces
+        .into_iter()
+        .map(|face| face.build(objects).insert(objects));
+
+    Ok(Sketch::new(faces).insert(objects))
+}
+
+/// Remove points that would form degenerate edges from a closed polygon
+///
+/// Points that are distinct on the original geometry can end up on top of each
+/// other on the surface.
+fn simplify(
+    points: Vec<Point<2>>,
+    surface: &SurfaceGeometry,
+    tolerance: Tolerance,
+) -> Result<Vec<Point<2>>, ProjectError> {
+    let mut simplified: Vec<Point<2>> = Vec::new();
+    for point in points {
+        let is_distinct = match simplified.last() {
+            Some(&previous) => {
+                let distance = surface.point_from_surface_coords(point)
+                    - surface.point_from_surface_coords(previous);
+                distance.magnitude() > tolerance.inner()
+            }
+            None => true,
+        };
+
+        if is_distinct {
+            simplified.push(point);
+        }
+    }
+    if let (Some(&first), Some(&last)) = (simplified.first(), simplified.last())
+    {
+        let distance = surface.point_from_surface_coords(last)
+            - surface.point_from_surface_coords(first);
+        if simplified.len() > 1 && distance.magnitude() <= tolerance.inner() {
+            simplified.pop();
+        }
+    }
+
+    if simplified.len() < 3 {
+        return Err(ProjectError::Degenerate);
+    }
+
+    Ok(simplified)
+}
+
+/// Compute starting points for the search for the closest point
+fn initial_guesses(
+    point: Point<3>,
+    surface: &SurfaceGeometry,
+) -> Result<Vec<Point<2>>, ProjectError> {
+    const NUM_SAMPLES: usize = 16;
+
+    let samples = |[start, end]: [Scalar; 2]| {
+        (0..NUM_SAMPLES).map(move |i| {
+            start + (end - start) * Scalar::from(i as f64 / NUM_SAMPLES as f64)
+        })
+    };
+
+    let guesses = match surface {
+        SurfaceGeometry::Swept { u, v } => {
+            // The v-coordinate of the closest point follows from the
+            // u-coordinate, so only the latter needs to be sampled.
+            let with_v = |u_coord: Scalar| {
+                let offset = point - u.point_from_path_coords([u_coord]);
+                Point::from([u_coord, offset.dot(v) / v.dot(v)])
+            };
+
+            match u {
+                GlobalPath::Line(line) => {
+                    let offset = point - line.origin();
+                    vec![with_v(
+                        offset.dot(&line.direction())
+                            / line.direction().dot(&line.direction()),
+                    )]
+                }
+                GlobalPath::Circle(_) | GlobalPath::Ellipse(_) => {
+                    samples([Scalar::ZERO, Scalar::TAU]).map(with_v).collect()
+                }
+                GlobalPath::Nurbs(nurbs) => {
+                    samples(nurbs.range()).map(with_v).collect()
+                }
+                GlobalPath::Helix(_) => {
+                    return Err(ProjectError::UnsupportedSurface);
+                }
+            }
+        }
+        SurfaceGeometry::Nurbs(nurbs) => {
+            let [range_u, range_v] = nurbs.range();
+            samples(range_u)
+                .flat_map(|u| samples(range_v).map(move |v| [u, v].into()))
+                .collect()
+        }
+        SurfaceGeometry::Ruled { a, b } => {
+            // As on a swept surface, the v-coordinate follows from the
+            // u-coordinate, by projecting onto the line between the paths.
+            let with_v = |u_coord: Scalar| {
+                let [a, b] =
+                    [a, b].map(|path| path.point_from_path_coords([u_coord]));
+                let line = b - a;
+                let v_coord = if line.magnitude() == Scalar::ZERO {
+                    Scalar::ZERO
+                } else {
+                    (point - a).dot(&line) / line.dot(&line)
+                };
+                Point::from([u_coord, v_coord])
+            };
+
+            match (a, b) {
+                (GlobalPath::Helix(_), _) | (_, GlobalPath::Helix(_)) => {
+                    return Err(ProjectError::UnsupportedSurface);
+                }
+                (GlobalPath::Nurbs(nurbs), _)
+                | (_, GlobalPath::Nurbs(nurbs)) => {
+                    samples(nurbs.range()).map(with_v).collect()
+                }
+                (GlobalPath::Line(line), GlobalPath::Line(_)) => {
+                    let offset = point - line.origin();
+                    vec![with_v(
+                        offset.dot(&line.direction())
+                            / line.direction().dot(&line.direction()),
+                    )]
+                }
+                _ => samples([Scalar::ZERO, Scalar::TAU]).map(with_v).collect(),
+            }
+        }
+        SurfaceGeometry::Cone(cone) => {
+            // The v-coordinate follows from the u-coordinate here too, by
+            // projecting onto the line from the apex.
+            samples([Scalar::ZERO, Scalar::TAU])
+                .map(|u_coord| {
+                    let (sin, cos) = u_coord.sin_cos();
+                    let line = cone.axis() + cone.a() * cos + cone.b() * sin;
+                    let v_coord =
+                        (point - cone.apex()).dot(&line) / line.dot(&line);
+                    Point::from([u_coord, v_coord])
+                })
+                .collect()
+        }
+    };
+
+    Ok(guesses)
+}
+
+/// Move the guess towards the closest point, until it doesn't get any closer
+fn refine(
+    point: Point<3>,
+    surface: &SurfaceGeometry,
+    mut guess: Point<2>,
+) -> Result<Point<2>, ProjectError> {
+    const MAX_ITERATIONS: usize = 64;
+
+    let distance = |guess: Point<2>| {
+        (point - surface.point_from_surface_coords(guess)).magnitude()
+    };
+
+    for _ in 0..MAX_ITERATIONS {
+        let residual = point - surface.point_from_surface_coords(guess);
+        let [du, dv] = surface.derivatives_at(guess);
+
+        // Solve the normal equations of the linearized problem.
+        let a = du.dot(&du);
+        let b = du.dot(&dv);
+        let c = dv.dot(&dv);
+        let det = a * c - b * b;
+        if det <= a * c * Scalar::from(1e-12) {
+            return Err(ProjectError::Degenerate);
+        }
+
+        let x = du.dot(&residual);
+        let y = dv.dot(&residual);
+        let mut step =
+            Vector::from([(c * x - b * y) / det, (a * y - b * x) / det]);
+
+        // The linearization can overshoot on curved surfaces. Shorten the
+        // step, until it actually gets us closer.
+        let current = distance(guess);
+        let mut next = clamp(surface, guess + step);
+        for _ in 0..16 {
+            if distance(next) <= current {
+                break;
+            }
+            step = step / 2.;
+            next = clamp(surface, guess + step);
+        }
+        if distance(next) > current {
+            break;
+        }
+
+        let change = (next - guess).magnitude();
+        guess = next;
+
+        if change
+            <= Scalar::from(1e-12) * (Scalar::ONE + guess.coords.magnitude())
+        {
+            break;
+        }
+    }
+
+    Ok(guess)
+}
+
+/// Keep a point within the range of the surface, if it has one
+fn clamp(surface: &SurfaceGeometry, point: Point<2>) -> Point<2> {
+    let clamp =
+        |value: Scalar, [min, max]: [Scalar; 2]| value.max(min).min(max);
+
+    match surface {
+        SurfaceGeometry::Swept {
+            u: GlobalPath::Nurbs(nurbs),
+            ..
+        }
+        | SurfaceGeometry::Ruled {
+            a: GlobalPath::Nurbs(nurbs),
+            ..
+        }
+        | SurfaceGeometry::Ruled {
+            b: GlobalPath::Nurbs(nurbs),
+            ..
+        } => [clamp(point.u, nurbs.range()), point.v].into(),
+        SurfaceGeometry::Nurbs(nurbs) => {
+            let [range_u, range_v] = nurbs.range();
+            [clamp(point.u, range_u), clamp(point.v, range_v)].into()
+        }
+        SurfaceGeometry::Swept { .. }
+        | SurfaceGeometry::Ruled { .. }
+        | SurfaceGeometry::Cone(_) => point,
+    }
+}
+
+/// The period of the surface in u-direction, if it wraps around
+fn period_u(surface: &SurfaceGeometry) -> Option<Scalar> {
+    match surface {
+        SurfaceGeometry::Swept {
+            u: GlobalPath::Circle(_) | GlobalPath::Ellipse(_),
+            ..
+        } => Some(Scalar::TAU),
+        // A ruled surface only wraps around, if both of its paths do.
+        SurfaceGeometry::Ruled {
+            a: GlobalPath::Circle(_) | GlobalPath::Ellipse(_),
+            b: GlobalPath::Circle(_) | GlobalPath::Ellipse(_),
+        } => Some(Scalar::TAU),
+        SurfaceGeometry::Cone(_) => Some(Scalar::TAU),
+        _ => None,
+    }
+}
+
+/// An error projecting onto a surface
+#[derive(Debug, thiserror::Error)]
+pub enum ProjectError {
+    /// The surface is not supported
+    #[error("Projecting onto surfaces swept from a helix is not supported")]
+    UnsupportedSurface,
+
+    /// The surface or the projection is degenerate
+    ///
+    /// This is the case, if the surface has no defined normal somewhere, or if
+    /// a cycle collapses into less than 3 points when projected.
+    #[error("Surface or projected geometry is degenerate")]
+    Degenerate,
+
+    /// A projected cycle wraps around the surface
+    #[error("Projected cycle wraps around the surface")]
+    WrapsAround,
+
+    /// The surface to derive a sketch on is not a plane
+    #[error("Sketches can only be derived on planes")]
+    NotAPlane,
+}
+
+#[cfg(test)]
+mod tests {
+    use fj_math::{Circle, Point, Scalar, Vector};
+
+    use crate::{
+        algorithms::approx::{Approx, Tolerance},
+        algorithms::sweep::Sweep,
+        builder::{CycleBuilder, FaceBuilder, SurfaceBuilder},
+        geometry::{path::GlobalPath, surface::SurfaceGeometry},
+        insert::Insert,
+        objects::{Cycle, Face, Sketch},
+        partial::{HasPartial, PartialSurface},
+        services::Services,
+    };
+
+    use super::ProjectError;
+
+    fn cylinder() -> SurfaceGeometry {
+        SurfaceGeometry::Swept {
+            u: GlobalPath::Circle(Circle::from_center_and_radius(
+                Point::origin(),
+                2.,
+            )),
+            v: Vector::from([0., 0., 1.]),
+        }
+    }
+
+    #[test]
+    fn project_point_onto_plane() -> anyhow::Result<()> {
+        let surface = SurfaceGeometry::Swept {
+            u: GlobalPath::x_axis(),
+            v: Vector::from([0., 2., 0.]),
+        };
+
+        let point = super::project_point([3., 4., 5.], &surface)?;
+        assert_eq!(point, Point::from([3., 2.]));
+
+        Ok(())
+    }
+
+    #[test]
+    fn project_point_onto_cylinder() -> anyhow::Result<()> {
+        let surface = cylinder();
+
+        let point = super::project_point([0., 5., 3.], &surface)?;
+        let expected = Point::from([Scalar::PI / 2., Scalar::from(3.)]);
+        assert!((point - expected).magnitude() < Scalar::from(1e-9));
+
+        let on_surface = surface.point_from_surface_coords(point);
+        let expected = Point::from([0., 2., 3.]);
+        assert!((on_surface - expected).magnitude() < Scalar::from(1e-9));
+
+        Ok(())
+    }
+
+    #[test]
+    fn project_polyline_across_seam() -> anyhow::Result<()> {
+        let surface = cylinder();
+
+        // Points on both sides of the seam at u = 0.
+        let points =
+            super::project_polyline([[3., -1., 0.], [3., 1., 0.]], &surface)?;
+
+        let [a, b] = [points[0], points[1]];
+        assert!((b.u - a.u).abs() < Scalar::ONE);
+
+        Ok(())
+    }
+
+    #[test]
+    fn project_face_onto_cylinder() -> anyhow::Result<()> {
+        let mut services = Services::new();
+        let tolerance = Tolerance::from_scalar(0.01)?;
+
+        // A square in the plane y = 3, in front of the cylinder.
+        let plane = PartialSurface::from_axes(
+            GlobalPath::Line(fj_math::Line::from_origin_and_direction(
+                Point::from([-0.5, 3., 0.]),
+                Vector::from([1., 0., 0.]),
+            )),
+            Vector::from([0., 0., 1.]),
+        )
+        .build(&services.objects)
+        .insert(&mut services.objects);
+        let face = Face::partial()
+            .with_exterior_polygon_from_points(
+                plane,
+                [[0., 0.], [1., 0.], [1., 1.], [0., 1.]],
+            )
+            .build(&mut services.objects)
+            .insert(&mut services.objects);
+
+        let cylinder = PartialSurface {
+            geometry: Some(cylinder()),
+        }
+        .build(&services.objects)
+        .insert(&mut services.objects);
+
+        let projected = super::project_face(
+            &face,
+            &cylinder,
+            tolerance,
+            &mut services.objects,
+        )?;
+
+        assert_eq!(projected.surface(), &cylinder);
+        for point in projected.exterior().approx(tolerance).points() {
+            let radius =
+                Vector::from([point.global_form.x, point.global_form.y])
+                    .magnitude();
+            assert!((radius - Scalar::from(2.)).abs() < Scalar::from(1e-9));
+            assert!(point.global_form.y > Scalar::ZERO);
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn project_cycle_around_cylinder() -> anyhow::Result<()> {
+        let mut services = Services::new();
+
+        let plane = PartialSurface::from_axes(
+            GlobalPath::x_axis(),
+            Vector::from([0., 1., 0.]),
+        )
+        .build(&services.objects)
+        .insert(&mut services.objects);
+        let cycle = Cycle::partial()
+            .with_poly_chain_from_points(
+                plane,
+                [[-3., -3.], [3., -3.], [3., 3.], [-3., 3.]],
+            )
+            .close_with_line_segment()
+            .build(&mut services.objects)
+            .insert(&mut services.objects);
+
+        let cylinder = PartialSurface {
+            geometry: Some(cylinder()),
+        }
+        .build(&services.objects)
+        .insert(&mut services.objects);
+
+        let result = super::project_cycle(
+            &cycle,
+            &cylinder,
+            Tolerance::from_scalar(0.01)?,
+            &mut services.objects,
+        );
+        assert!(matches!(result, Err(ProjectError::WrapsAround)));
+
+        Ok(())
+    }
+
+    #[test]
+    fn project_outline_of_cube() -> anyhow::Result<()> {
+        let mut services = Services::new();
+
+        let solid = Sketch::builder()
+            .with_polygon_from_points(
+                services.objects.surfaces.xy_plane(),
+                [[0., 0.], [1., 0.], [1., 1.], [0., 1.]],
+                &mut services.objects,
+            )
+            .build(&mut services.objects)
+            .sweep([0., 0., 1.], &mut services.objects);
+        let faces = solid
+            .shells()
+            .flat_map(|shell| shell.faces())
+            .collect::<Vec<_>>();
+
+        // A plane that faces the cube along its diagonal.
+        let plane = PartialSurface::from_axes(
+            GlobalPath::Line(fj_math::Line::from_origin_and_direction(
+                Point::origin(),
+                Vector::from([1., -1., 0.]),
+            )),
+            Vector::from([1., 1., -2.]),
+        )
+        .build(&services.objects)
+        .insert(&mut services.objects);
+
+        let sketch = super::project_outline(
+            faces,
+            &plane,
+            Tolerance::from_scalar(0.01)?,
+            &mut services.objects,
+        )?;
+
+        // Seen along its diagonal, the outline of the cube is a hexagon.
+        let faces = sketch.faces().into_iter().collect::<Vec<_>>();
+        assert_eq!(faces.len(), 1);
+        assert_eq!(faces[0].surface(), &plane);
+        assert_eq!(faces[0].exterior().half_edges().count(), 6);
+        assert_eq!(faces[0].interiors().count(), 0);
+
+        Ok(())
+    }
+}
@@ -0,0 +1,211 @@
+//! Mass properties of shapes
+//!
+//! See [`mass_properties`].
+
+use fj_interop::mesh::Mesh;
+use fj_math::{Point, Scalar, Triangle, Vector};
+
+use super::{
+    approx::{face::FaceApprox, Approx, Tolerance},
+    triangulate::Triangulate,
+};
+
+pub use fj_interop::mass_properties::MassProperties;
+
+/// Compute the mass properties of a shape
+///
+/// The shape is triangulated using the provided tolerance, and the properties
+/// are computed from the resulting triangle mesh. See [`mesh_properties`].
+pub fn mass_properties<T>(
+    shape: T,
+    tolerance: impl Into<Tolerance>,
+) -> MassProperties
+where
+    T: Approx,
+    T::Approximation: IntoIterator<Item = FaceApprox>,
+{
+    let mesh = (shape, tolerance.into()).triangulate();
+    mesh_properties(&mesh)
+}
+
+/// Compute the mass properties of a triangle mesh
+///
+/// The volume integrals are turned into sums over the triangles, using the
+/// divergence theorem. This requires the mesh to be closed, and its triangles
+/// to be oriented consistently. Whether they face inward or outward doesn't
+/// matter.
+pub fn mesh_properties(mesh: &Mesh<Point<3>>) -> MassProperties {
+    let mut volume = Scalar::ZERO;
+    let mut surface_area = Scalar::ZERO;
+    let mut first_moment = Vector::from([0., 0., 0.]);
+    let mut second_moment = [[Scalar::ZERO; 3]; 3];
+
+    for triangle in mesh.triangles() {
+        let triangle: Triangle<3> = triangle.inner;
+        let [a, b, c] = triangle.points().map(|point| point.coords);
+
+        surface_area += (b - a).cross(&(c - a)).magnitude() / Scalar::TWO;
+
+        // Every triangle forms a tetrahedron with the origin. Summing up the
+        // signed integrals over those tetrahedra yields the integrals over the
+        // volume that the mesh encloses.
+        let tetrahedron_volume = a.dot(&b.cross(&c)) / Scalar::from(6.);
+        let sum = a + b + c;
+
+        volume += tetrahedron_volume;
+        first_moment = first_moment + sum * (tetrahedron_volume / 4.);
+
+        for (i, row) in second_moment.iter_mut().enumerate() {
+            for (j, value) in row.iter_mut().enumerate() {
+                let products = a.components[i] * a.components[j]
+                    + b.components[i] * b.components[j]
+                    + c.components[i] * c.components[j]
+                    + sum.components[i] * sum.components[j];
+                *value += products * tetrahedron_volume / 20.;
+            }
+        }
+    }
+
+    // A mesh whose triangles face inward yields negative integrals.
+    if volume < Scalar::ZERO {
+        volume = -volume;
+        first_moment = -first_moment;
+        for value in second_moment.iter_mut().flatten() {
+            *value = -*value;
+        }
+    }
+
+    if volume == Scalar::ZERO {
+        return MassProperties {
+            surface_area,
+            ..MassProperties::default()
+        };
+    }
+
+    let center_of_mass = first_moment / volume;
+
+    // Move the second moment to the center of mass, then derive the inertia
+    // tensor from it.
+    for (i, row) in second_moment.iter_mut().enumerate() {
+        for (j, value) in row.iter_mut().enumerate() {
+            *value -= volume
+                * center_of_mass.components[i]
+                * center_of_mass.components[j];
+        }
+    }
+    let trace = (0..3).fold(Scalar::ZERO, |sum, i| sum + second_moment[i][i]);
+    let mut inertia = second_moment.map(|row| row.map(|value| -value));
+    for (i, row) in inertia.iter_mut().enumerate() {
+        row[i] += trace;
+    }
+
+    MassProperties {
+        volume,
+        surface_area,
+        center_of_mass: Point {
+            coords: center_of_mass,
+        },
+        inertia,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use fj_math::{Point, Scalar};
+
+    use crate::{
+        algorithms::{approx::Tolerance, sweep::Sweep},
+        builder::FaceBuilder,
+        insert::Insert,
+        objects::{Face, Sketch},
+        partial::HasPartial,
+        services::Services,
+    };
+
+    fn assert_close(a: Scalar, b: impl Into<Scalar>) {
+        let b = b.into();
+        assert!((a - b).abs() < Scalar::from(1e-9), "{a:?} != {b:?}");
+    }
+
+    #[test]
+    fn cuboid() {
+        let mut services = Services::new();
+
+        let surface = services.objects.surfaces.xy_plane();
+        let cuboid = Sketch::builder()
+            .with_polygon_from_points(
+                surface,
+                [[-1., -2.], [1., -2.], [1., 2.], [-1., 2.]],
+                &mut services.objects,
+            )
+            .build(&mut services.objects)
+            .sweep([0., 0., 6.], &mut services.objects);
+
+        let tolerance = Tolerance::from_scalar(0.001).unwrap();
+        let properties = super::mass_properties(&*cuboid, tolerance);
+
+        let [x, y, z] = [2., 4., 6.];
+        let mass = x * y * z;
+
+        assert_close(properties.volume, mass);
+        assert_close(properties.surface_area, 2. * (x * y + y * z + x * z));
+        for (actual, expected) in properties
+            .center_of_mass
+            .coords
+            .components
+            .into_iter()
+            .zip(Point::from([0., 0., 3.]).coords.components)
+        {
+            assert_close(actual, expected);
+        }
+
+        let diagonal = [
+            mass * (y * y + z * z) / 12.,
+            mass * (x * x + z * z) / 12.,
+            mass * (x * x + y * y) / 12.,
+        ];
+        for (i, row) in properties.inertia.into_iter().enumerate() {
+            for (j, value) in row.into_iter().enumerate() {
+                let expected = if i == j { diagonal[i] } else { 0. };
+                assert_close(value, expected);
+            }
+        }
+    }
+
+    #[test]
+    fn cylinder() {
+        let mut services = Services::new();
+
+        let radius = 2.;
+        let height = 3.;
+
+        let surface = services.objects.surfaces.xy_plane();
+        let disc = Face::partial()
+            .with_exterior_circle(
+                surface,
+                [0., 0.],
+                radius,
+                &mut services.objects,
+            )
+            .build(&mut services.objects)
+            .insert(&mut services.objects);
+        let cylinder = Sketch::builder()
+            .with_faces([disc])
+            .build(&mut services.objects)
+            .sweep([0., 0., height], &mut services.objects);
+
+        let tolerance = 0.001;
+        let properties = super::mass_properties(
+            &*cylinder,
+            Tolerance::from_scalar(tolerance).unwrap(),
+        );
+
+        // The approximation lies within the cylinder, so it's a bit smaller.
+        let volume = Scalar::PI * radius * radius * height;
+        let max_deviation = Scalar::TAU * radius * height * tolerance;
+        assert!(properties.volume <= volume);
+        assert!(properties.volume > volume - max_deviation);
+
+        assert_close(properties.center_of_mass.z, height / 2.);
+    }
+}
@@ -0,0 +1,377 @@
+//! Repair of triangle meshes
+//!
+//! See [`repair`].
+
+use std::collections::BTreeMap;
+
+use fj_interop::mesh::{
+    Attributes, Color, Mesh, Origin, Triangle as MeshTriangle,
+};
+use fj_math::{Point, Scalar, Vector};
+
+/// Close the gaps in a triangle mesh, where possible
+///
+/// The faces of a shape are approximated independently of each other, and a
+/// curve that bounds two faces isn't necessarily approximated the same way for
+/// both. Where that happens, a vertex of one face lies on an edge of the
+/// other, without being a vertex of that edge (a T-junction). The mesh looks
+/// closed, but isn't, and slicers show cracks there.
+///
+/// Repairing the mesh happens in two steps, both limited to edges that belong
+/// to only one triangle:
+///
+/// - Vertices that are closer to each other than `tolerance` are merged.
+///   Triangles that collapse as a result are removed.
+/// - Triangles are split at vertices that lie on one of their edges, within
+///   `tolerance`, so neighboring triangles share those vertices.
+///
+/// Afterwards, the mesh is checked for being closed and manifold, meaning
+/// every edge is shared by exactly two triangles. The returned [`MeshReport`]
+/// lists the edges for which this is not the case.
+pub fn repair(
+    mesh: &Mesh<Point<3>>,
+    tolerance: impl Into<Scalar>,
+) -> (Mesh<Point<3>>, MeshReport) {
+    let tolerance = tolerance.into();
+
+    let mut triangles = mesh
+        .triangles()
+        .map(|triangle| {
+            (
+                triangle.inner.points(),
+                triangle.normals,
+                triangle.color,
+                triangle.attributes,
+                triangle.origin,
+            )
+        })
+        .collect::<Vec<_>>();
+
+    let welded_vertices = weld(&mut triangles, tolerance);
+
+    let mut t_junctions = 0;
+    loop {
+        let edges = edges(&triangles);
+        let open_vertices = open_vertices(&edges);
+
+        let mut repaired = Vec::with_capacity(triangles.len());
+        let mut changed = false;
+        for (points, normals, color, attributes, origin) in triangles {
+            match find_t_junction(points, &edges, &open_vertices, tolerance) {
+                Some((edge, point)) => {
+                    let indices = [edge, edge + 1, edge + 2].map(|i| i % 3);
+                    let [a, b, c] = indices.map(|i| points[i]);
+
+                    // The normal at the new point is interpolated from the
+                    // normals at the ends of the split edge.
+                    let normals = normals.map(|normals| {
+                        let [na, nb, nc] = indices.map(|i| normals[i]);
+                        let t = (point - a).magnitude() / (b - a).magnitude();
+                        let n = (na * (Scalar::ONE - t) + nb * t).normalize();
+                        [[na, n, nc], [n, nb, nc]]
+                    });
+
+                    // The new point is on the split edge, but not on a vertex.
+                    // The new side within the triangle isn't on any edge.
+                    let [ab, bc, ca] = indices.map(|i| origin.edges[i]);
+                    let [va, vb, vc] = indices.map(|i| origin.vertices[i]);
+                    let origins = [
+                        Origin {
+                            edges: [ab, None, ca],
+                            vertices: [va, None, vc],
+                            ..origin
+                        },
+                        Origin {
+                            edges: [ab, bc, None],
+                            vertices: [None, vb, vc],
+                            ..origin
+                        },
+                    ];
+
+                    repaired.push((
+                        [a, point, c],
+                        normals.map(|[normals, _]| normals),
+                        color,
+                        attributes.clone(),
+                        origins[0],
+                    ));
+                    repaired.push((
+                        [point, b, c],
+                        normals.map(|[_, normals]| normals),
+                        color,
+                        attributes,
+                        origins[1],
+                    ));
+                    t_junctions += 1;
+                    changed = true;
+                }
+                None => {
+                    repaired.push((points, normals, color, attributes, origin))
+                }
+            }
+        }
+
+        triangles = repaired;
+        if !changed {
+            break;
+        }
+    }
+
+    let mut report = MeshReport {
+        welded_vertices,
+        t_junctions,
+        open_edges: Vec::new(),
+        non_manifold_edges: Vec::new(),
+    };
+    for (edge, triangles) in edges(&triangles) {
+        match triangles.len() {
+            2 => {}
+            1 => report.open_edges.push(edge),
+            _ => report.non_manifold_edges.push(edge),
+        }
+    }
+
+    let mut repaired = Mesh::new();
+    repaired.extend(triangles.into_iter().map(
+        |(points, normals, color, attributes, origin)| MeshTriangle {
+            inner: points.into(),
+            normals,
+            color,
+            attributes,
+            origin,
+        },
+    ));
+
+    (repaired, report)
+}
+
+/// The result of checking a triangle mesh, after repairing it
+///
+/// Returned by [`repair`].
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct MeshReport {
+    /// The number of vertices that were merged into a nearby one
+    pub welded_vertices: usize,
+
+    /// The number of T-junctions that were fixed by splitting a triangle
+    pub t_junctions: usize,
+
+    /// Edges that belong to only one triangle
+    ///
+    /// These are the boundaries of gaps, which couldn't be closed.
+    pub open_edges: Vec<[Point<3>; 2]>,
+
+    /// Edges that belong to more than two triangles
+    pub non_manifold_edges: Vec<[Point<3>; 2]>,
+}
+
+impl MeshReport {
+    /// Indicate whether the mesh is closed and manifold
+    pub fn is_watertight(&self) -> bool {
+        self.open_edges.is_empty() && self.non_manifold_edges.is_empty()
+    }
+}
+
+type Triangle = (
+    [Point<3>; 3],
+    Option<[Vector<3>; 3]>,
+    Color,
+    Attributes,
+    Origin,
+);
+
+/// Map each edge to the triangles it belongs to
+///
+/// Edges are undirected, so each one is keyed by its points in sorted order.
+fn edges(triangles: &[Triangle]) -> BTreeMap<[Point<3>; 2], Vec<usize>> {
+    let mut edges = BTreeMap::<_, Vec<_>>::new();
+
+    for (i, (points, ..)) in triangles.iter().enumerate() {
+        for j in 0..3 {
+            let [a, b] = [points[j], points[(j + 1) % 3]];
+            edges.entry([a.min(b), a.max(b)]).or_default().push(i);
+        }
+    }
+
+    edges
+}
+
+/// Collect the vertices of all edges that belong to only one triangle
+fn open_vertices(edges: &BTreeMap<[Point<3>; 2], Vec<usize>>) -> Vec<Point<3>> {
+    let mut vertices = edges
+        .iter()
+        .filter(|(_, triangles)| triangles.len() == 1)
+        .flat_map(|(edge, _)| *edge)
+        .collect::<Vec<_>>();
+
+    vertices.sort();
+    vertices.dedup();
+
+    vertices
+}
+
+/// Merge the vertices of open edges that are close to each other
+///
+/// Returns the number of vertices that were merged into another one.
+fn weld(triangles: &mut Vec<Triangle>, tolerance: Scalar) -> usize {
+    let vertices = open_vertices(&edges(triangles));
+
+    let mut welded = BTreeMap::new();
+    for (i, &a) in vertices.iter().enumerate() {
+        if welded.contains_key(&a) {
+            continue;
+        }
+
+        for &b in &vertices[i + 1..] {
+            if !welded.contains_key(&b) && (b - a).magnitude() <= tolerance {
+                welded.insert(b, a);
+            }
+        }
+    }
+
+    if welded.is_empty() {
+        return 0;
+    }
+
+    triangles.retain_mut(|(points, ..)| {
+        for point in points.iter_mut() {
+            if let Some(&target) = welded.get(point) {
+                *point = target;
+            }
+        }
+
+        let [a, b, c] = *points;
+        a != b && b != c && c != a
+    });
+
+    welded.len()
+}
+
+/// Find a vertex that lies on an open edge of a triangle
+///
+/// Returns the index of the edge and the vertex.
+fn find_t_junction(
+    points: [Point<3>; 3],
+    edges: &BTreeMap<[Point<3>; 2], Vec<usize>>,
+    open_vertices: &[Point<3>],
+    tolerance: Scalar,
+) -> Option<(usize, Point<3>)> {
+    (0..3).find_map(|i| {
+        let [a, b] = [points[i], points[(i + 1) % 3]];
+
+        let is_open = edges
+            .get(&[a.min(b), a.max(b)])
+            .is_some_and(|triangles| triangles.len() == 1);
+        if !is_open {
+            return None;
+        }
+
+        let direction = b - a;
+        let length = direction.magnitude();
+
+        open_vertices
+            .iter()
+            .copied()
+            .find(|&point| {
+                let t = (point - a).dot(&direction) / length;
+                if t <= tolerance || t >= length - tolerance {
+                    return false;
+                }
+
+                let closest = a + direction * (t / length);
+                (point - closest).magnitude() <= tolerance
+            })
+            .map(|point| (i, point))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use fj_interop::mesh::Mesh;
+    use fj_math::Point;
+
+    use crate::{
+        algorithms::{
+            approx::Tolerance, sweep::Sweep, triangulate::Triangulate,
+        },
+        objects::Sketch,
+        services::Services,
+    };
+
+    #[test]
+    fn repair_t_junction() {
+        let cube = cube();
+        let (_, report) = super::repair(&cube, 1e-6);
+        assert!(report.is_watertight());
+        assert_eq!(report.t_junctions, 0);
+
+        // Split one triangle at the middle of an edge. Its neighbor across
+        // that edge isn't split, which results in a T-junction.
+        let mut mesh = Mesh::new();
+        for (i, triangle) in cube.triangles().enumerate() {
+            let [a, b, c] = triangle.inner.points();
+
+            if i == 0 {
+                let middle = a + (b - a) / 2.;
+                mesh.push_triangle([a, middle, c], triangle.color);
+                mesh.push_triangle([middle, b, c], triangle.color);
+            } else {
+                mesh.push_triangle([a, b, c], triangle.color);
+            }
+        }
+
+        let (_, report) = super::repair(&mesh, 1e-6);
+        assert!(report.is_watertight());
+        assert_eq!(report.t_junctions, 1);
+    }
+
+    #[test]
+    fn report_gap() {
+        let mut mesh = Mesh::new();
+        for triangle in cube().triangles().skip(1) {
+            mesh.push_triangle(triangle.inner, triangle.color);
+        }
+
+        let (repaired, report) = super::repair(&mesh, 1e-6);
+        assert_eq!(repaired.triangles().count(), mesh.triangles().count());
+        assert_eq!(report.open_edges.len(), 3);
+        assert!(report.non_manifold_edges.is_empty());
+    }
+
+    #[test]
+    fn weld_vertices() {
+        // Two triangles that share an edge, except that one of them is off by
+        // a tiny bit.
+        let mut mesh = Mesh::new();
+        let color = Default::default();
+        mesh.push_triangle(
+            [[0., 0., 0.], [1., 0., 0.], [0., 1., 0.]].map(Point::from),
+            color,
+        );
+        mesh.push_triangle(
+            [[1., 0., 0.], [1., 1., 0.], [0., 1., 1e-9]].map(Point::from),
+            color,
+        );
+
+        let (_, report) = super::repair(&mesh, 1e-6);
+        assert_eq!(report.welded_vertices, 1);
+        assert_eq!(report.open_edges.len(), 4);
+    }
+
+    fn cube() -> Mesh<Point<3>> {
+        let mut services = Services::new();
+
+        let surface = services.objects.surfaces.xy_plane();
+        let cube = Sketch::builder()
+            .with_polygon_from_points(
+                surface,
+                [[0., 0.], [1., 0.], [1., 1.], [0., 1.]],
+                &mut services.objects,
+            )
+            .build(&mut services.objects)
+            .sweep([0., 0., 1.], &mut services.objects);
+
+        let tolerance = Tolerance::from_scalar(0.001).unwrap();
+        (&*cube, tolerance).triangulate()
+    }
+}
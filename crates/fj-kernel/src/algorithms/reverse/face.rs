@@ -20,6 +20,7 @@ impl Reverse for Handle<Face> {
             .with_exterior(exterior)
             .with_interiors(interiors)
             .with_color(self.color())
+            .with_attributes(self.attributes().clone())
             .build(objects)
             .insert(objects)
     }
@@ -0,0 +1,243 @@
+//! Hollowing out solids
+//!
+//! Shelling a solid turns it into a hollow one, with walls of a given
+//! thickness. Selected faces can be removed in the process, to create open
+//! containers.
+
+use fj_math::Scalar;
+
+use crate::{
+    objects::{Face, Objects, Shell, Solid},
+    services::Service,
+    storage::Handle,
+};
+
+use super::polyhedron::{build_face, Polyhedron, PolyhedronError};
+
+/// Hollow out a solid, leaving walls of the given thickness
+///
+/// All faces of the solid are offset inward by `thickness`, and the resulting
+/// faces bound the cavity. The faces in `open_faces` are removed instead. Their
+/// place is taken by a rim that connects the outer and inner walls, turning the
+/// solid into an open container.
+///
+/// If no faces are removed, the resulting solid consists of two shells: the
+/// original, outer one, and the one that bounds the cavity.
+///
+/// # Implementation Note
+///
+/// Only solids bounded by planar faces with straight edges are supported.
+/// Vertices are identified by their position, which means faces that are
+/// supposed to touch must share exactly the same vertex positions.
+pub fn shell(
+    solid: &Handle<Solid>,
+    thickness: impl Into<Scalar>,
+    open_faces: &[Handle<Face>],
+    objects: &mut Service<Objects>,
+) -> Result<Handle<Solid>, ShellError> {
+    let thickness = thickness.into();
+    if thickness <= Scalar::ZERO {
+        return Err(ShellError::NonPositiveThickness(thickness));
+    }
+
+    let polyhedron = Polyhedron::from_solid(solid)?;
+
+    for open_face in open_faces {
+        let face = polyhedron
+            .faces
+            .iter()
+            .find(|face| &face.face == open_face)
+            .ok_or(ShellError::UnknownFace)?;
+        if face.cycles.len() > 1 {
+            return Err(ShellError::OpenFaceWithHoles);
+        }
+    }
+
+    // Open faces stay where they are. That way, the inner vertices on their
+    // boundary stay within their plane, and the rim can be built there.
+    let inner_vertices = polyhedron.offset_vertices(|face| {
+        if open_faces.contains(&face.face) {
+            Scalar::ZERO
+        } else {
+            -thickness
+        }
+    });
+
+    let mut outer_faces = Vec::new();
+    let mut inner_faces = Vec::new();
+
+    for face in &polyhedron.faces {
+        let color = face.face.color();
+        let attributes = face.face.attributes();
+        let inner_cycles = face.map_cycles(&inner_vertices);
+
+        if open_faces.contains(&face.face) {
+            let outer = face.cycles[0].clone();
+            let inner = inner_cycles[0].iter().rev().copied().collect();
+
+            outer_faces.push(build_face(
+                face.normal,
+                &[outer, inner],
+                color,
+                attributes.clone(),
+                objects,
+            ));
+            continue;
+        }
+
+        // If the wall is thicker than the solid, the offset faces turn inside
+        // out.
+        if face.is_inverted_by(&inner_cycles) {
+            return Err(ShellError::ThicknessTooLarge(thickness));
+        }
+
+        // The inner faces face into the cavity, so their winding needs to be
+        // reversed.
+        let inner_cycles = inner_cycles
+            .into_iter()
+            .map(|cycle| cycle.into_iter().rev().collect())
+            .collect::<Vec<_>>();
+
+        outer_faces.push(face.face.clone());
+        inner_faces.push(build_face(
+            -face.normal,
+            &inner_cycles,
+            color,
+            attributes.clone(),
+            objects,
+        ));
+    }
+
+    let shells = if open_faces.is_empty() {
+        vec![
+            Shell::builder().with_faces(outer_faces).build(objects),
+            Shell::builder().with_faces(inner_faces).build(objects),
+        ]
+    } else {
+        outer_faces.extend(inner_faces);
+        vec![Shell::builder().with_faces(outer_faces).build(objects)]
+    };
+
+    Ok(Solid::builder().with_shells(shells).build(objects))
+}
+
+/// An error that can occur while shelling a solid
+#[derive(Clone, Debug, Eq, PartialEq, thiserror::Error)]
+pub enum ShellError {
+    /// The wall thickness is zero or negative
+    #[error("Wall thickness must be positive (got {0})")]
+    NonPositiveThickness(Scalar),
+
+    /// The wall thickness is too large for the solid
+    #[error("Wall thickness {0} is too large for the solid")]
+    ThicknessTooLarge(Scalar),
+
+    /// The solid has a face that isn't planar, or has curved edges
+    #[error("Shelling is only supported for solids bounded by planar faces")]
+    CurvedFace,
+
+    /// A face that should be removed is not part of the solid
+    #[error("Face to be removed is not part of the solid")]
+    UnknownFace,
+
+    /// A face that should be removed has holes
+    #[error("Removing faces with holes is not supported")]
+    OpenFaceWithHoles,
+}
+
+impl From<PolyhedronError> for ShellError {
+    fn from(err: PolyhedronError) -> Self {
+        match err {
+            PolyhedronError::CurvedFace => Self::CurvedFace,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use fj_math::Scalar;
+
+    use crate::{
+        algorithms::{
+            approx::Tolerance, sweep::Sweep, triangulate::Triangulate,
+        },
+        iter::ObjectIters,
+        objects::{Objects, Sketch, Solid},
+        services::{Service, Services},
+        storage::Handle,
+    };
+
+    use super::{shell, ShellError};
+
+    #[test]
+    fn shell_closed_box() -> anyhow::Result<()> {
+        let mut services = Services::new();
+
+        let solid = cube(&mut services.objects);
+        let hollow = shell(&solid, 0.5, &[], &mut services.objects)?;
+
+        assert_eq!(hollow.shells().count(), 2);
+        assert_eq!(hollow.face_iter().count(), 12);
+
+        // The cavity is a cube with an edge length of 1, centered within the
+        // original one.
+        assert!(hollow.global_vertex_iter().any(|vertex| {
+            let [x, y, z] = vertex.position().coords.components;
+            [x.abs(), y.abs(), (z - 1.).abs()]
+                .iter()
+                .all(|&c| (c - 0.5).abs() < Scalar::from(1e-9))
+        }));
+
+        let mesh = (&*hollow, Tolerance::from_scalar(0.001)?).triangulate();
+        assert_eq!(mesh.triangles().count(), 12 * 2);
+
+        Ok(())
+    }
+
+    #[test]
+    fn shell_open_box() -> anyhow::Result<()> {
+        let mut services = Services::new();
+
+        let solid = cube(&mut services.objects);
+        let top = solid
+            .shells()
+            .flat_map(|shell| shell.faces().clone())
+            .find(|face| {
+                face.exterior().half_edges().all(|half_edge| {
+                    half_edge.back().global_form().position().z
+                        == Scalar::from(2.)
+                })
+            })
+            .expect("Expected cube to have a top face");
+
+        let hollow = shell(&solid, 0.25, &[top], &mut services.objects)?;
+
+        // 5 outer walls, 5 inner walls, and the rim.
+        assert_eq!(hollow.shells().count(), 1);
+        assert_eq!(hollow.face_iter().count(), 11);
+
+        Ok(())
+    }
+
+    #[test]
+    fn shell_rejects_excessive_thickness() {
+        let mut services = Services::new();
+
+        let solid = cube(&mut services.objects);
+
+        let result = shell(&solid, 1.5, &[], &mut services.objects);
+        assert!(matches!(result, Err(ShellError::ThicknessTooLarge(_))));
+    }
+
+    fn cube(objects: &mut Service<Objects>) -> Handle<Solid> {
+        let surface = objects.surfaces.xy_plane();
+        Sketch::builder()
+            .with_polygon_from_points(
+                surface,
+                [[-1., -1.], [1., -1.], [1., 1.], [-1., 1.]],
+                objects,
+            )
+            .build(objects)
+            .sweep([0., 0., 2.], objects)
+    }
+}
@@ -0,0 +1,259 @@
+//! Slicing of shapes into layers
+//!
+//! See [`slice`].
+
+use std::collections::BTreeMap;
+
+use fj_interop::mesh::Mesh;
+use fj_math::{signed_area, Aabb, Point, Scalar};
+
+use super::{
+    approx::{face::FaceApprox, Approx, Tolerance},
+    triangulate::Triangulate,
+};
+
+/// A layer of a sliced shape
+#[derive(Clone, Debug, PartialEq)]
+pub struct Layer {
+    /// The height of the plane that the layer has been sliced at
+    pub z: Scalar,
+
+    /// The closed contours of the layer
+    ///
+    /// Viewed from above, outer contours are wound counter-clockwise, and the
+    /// contours of holes clockwise. The last point of a contour connects back
+    /// to the first.
+    pub contours: Vec<Vec<Point<2>>>,
+}
+
+/// Slice a shape into layers of the given height
+///
+/// The shape is triangulated using the provided tolerance, and the triangle
+/// mesh is sliced. See [`slice_mesh`].
+pub fn slice<T>(
+    shape: T,
+    layer_height: impl Into<Scalar>,
+    tolerance: impl Into<Tolerance>,
+) -> Vec<Layer>
+where
+    T: Approx,
+    T::Approximation: IntoIterator<Item = FaceApprox>,
+{
+    let mesh = (shape, tolerance.into()).triangulate();
+    slice_mesh(&mesh, layer_height)
+}
+
+/// Slice a triangle mesh into layers of the given height
+///
+/// The layers are stacked along the z-axis, starting at the bottom of the
+/// mesh. Each layer is sliced in its middle, so faces at the bottom and top of
+/// a layer, which are common for shapes that are made of layers, don't
+/// coincide with the slicing plane.
+///
+/// The mesh needs to be closed. Sections through parts of it that are not,
+/// don't result in closed contours, and are left out.
+///
+/// # Panics
+///
+/// Panics, if the layer height is not positive.
+pub fn slice_mesh(
+    mesh: &Mesh<Point<3>>,
+    layer_height: impl Into<Scalar>,
+) -> Vec<Layer> {
+    let layer_height = layer_height.into();
+    assert!(layer_height > Scalar::ZERO, "Layer height must be positive");
+
+    if mesh.triangles().next().is_none() {
+        return Vec::new();
+    }
+    let aabb = Aabb::<3>::from_points(mesh.vertices());
+
+    let num_layers =
+        ((aabb.max.z - aabb.min.z) / layer_height).ceil().into_f64() as usize;
+
+    (0..num_layers)
+        .map(|i| {
+            let z = aabb.min.z + layer_height * (i as f64 + 0.5);
+            let contours = contours(section(mesh, z), layer_height * 1e-6);
+
+            Layer { z, contours }
+        })
+        .collect()
+}
+
+/// Compute the section of a triangle mesh with a horizontal plane
+///
+/// Returns line segments, which are directed such that the inside of the mesh
+/// is to their left.
+fn section(mesh: &Mesh<Point<3>>, z: Scalar) -> Vec<[Point<2>; 2]> {
+    let mut segments = Vec::new();
+
+    for triangle in mesh.triangles() {
+        let triangle = triangle.inner;
+        let points = triangle.points();
+
+        // Points that lie exactly on the plane count as being above it. That
+        // way, every edge either crosses the plane or doesn't, and there are no
+        // special cases to deal with.
+        let above = points.map(|point| point.z >= z);
+        if above.iter().all(|&above| above) || above.iter().all(|&a| !a) {
+            continue;
+        }
+
+        let mut section = Vec::new();
+        for i in 0..3 {
+            let j = (i + 1) % 3;
+            if above[i] == above[j] {
+                continue;
+            }
+
+            // Always interpolate in the same direction, so the triangles on
+            // both sides of an edge end up with the same point.
+            let [a, b] = if above[i] { [i, j] } else { [j, i] };
+            let t = (points[a].z - z) / (points[a].z - points[b].z);
+            let point = points[a] + (points[b] - points[a]) * t;
+
+            section.push(Point::from([point.x, point.y]));
+        }
+
+        let [a, b] = section[..] else {
+            continue;
+        };
+        if a == b {
+            continue;
+        }
+
+        // The outward normal of the triangle points to the right of the
+        // segment.
+        let normal = triangle.normal();
+        let direction = b - a;
+        let normal_to_the_right =
+            direction.v * normal.x - direction.u * normal.y;
+        if normal_to_the_right >= Scalar::ZERO {
+            segments.push([a, b]);
+        } else {
+            segments.push([b, a]);
+        }
+    }
+
+    segments
+}
+
+/// Connect directed line segments to closed contours
+///
+/// Segments are connected where their end points are closer to each other
+/// than the resolution.
+fn contours(
+    segments: Vec<[Point<2>; 2]>,
+    resolution: Scalar,
+) -> Vec<Vec<Point<2>>> {
+    let key = |point: Point<2>| {
+        point
+            .coords
+            .components
+            .map(|coord| (coord / resolution).round().into_f64() as i64)
+    };
+
+    let mut segments_by_start: BTreeMap<_, Vec<_>> = BTreeMap::new();
+    for (i, [start, _]) in segments.iter().enumerate() {
+        segments_by_start.entry(key(*start)).or_default().push(i);
+    }
+
+    let mut used = vec![false; segments.len()];
+    let mut contours = Vec::new();
+    for i in 0..segments.len() {
+        if used[i] {
+            continue;
+        }
+        used[i] = true;
+
+        let [start, mut end] = segments[i];
+        let mut contour = vec![start];
+
+        let is_closed = loop {
+            if key(end) == key(start) {
+                break true;
+            }
+            contour.push(end);
+
+            let next = segments_by_start
+                .get(&key(end))
+                .and_then(|next| next.iter().copied().find(|&j| !used[j]));
+            let Some(next) = next else {
+                break false;
+            };
+            used[next] = true;
+
+            end = segments[next][1];
+        };
+
+        if is_closed && contour.len() >= 3 {
+            contours.push(contour);
+        }
+    }
+
+    // A mesh whose triangles face inward results in contours that are wound
+    // the other way around.
+    let area = contours
+        .iter()
+        .map(|contour| signed_area(contour))
+        .fold(Scalar::ZERO, |a, b| a + b);
+    if area < Scalar::ZERO {
+        for contour in &mut contours {
+            contour.reverse();
+        }
+    }
+
+    contours
+}
+
+#[cfg(test)]
+mod tests {
+    use fj_math::{signed_area, Scalar};
+
+    use crate::{
+        algorithms::{approx::Tolerance, sweep::Sweep},
+        objects::Sketch,
+        services::Services,
+    };
+
+    #[test]
+    fn slice_cuboid() {
+        let mut services = Services::new();
+
+        let surface = services.objects.surfaces.xy_plane();
+        let cuboid = Sketch::builder()
+            .with_polygon_from_points(
+                surface,
+                [[0., 0.], [4., 0.], [4., 4.], [0., 4.]],
+                &mut services.objects,
+            )
+            .build(&mut services.objects)
+            .sweep([0., 0., 1.], &mut services.objects);
+
+        let tolerance = Tolerance::from_scalar(0.001).unwrap();
+        let layers = super::slice(&*cuboid, 0.3, tolerance);
+
+        let heights = layers
+            .iter()
+            .map(|layer| layer.z.into_f64())
+            .collect::<Vec<_>>();
+        assert_eq!(heights.len(), 4);
+        for (height, expected) in heights.into_iter().zip([0.15, 0.45, 0.75]) {
+            assert!((height - expected).abs() < 1e-9);
+        }
+
+        for layer in &layers[..3] {
+            let [contour] = layer.contours.as_slice() else {
+                panic!("Expected one contour");
+            };
+
+            // The contour is wound counter-clockwise.
+            let area = signed_area(contour) - Scalar::from(16.);
+            assert!(area.abs() < Scalar::from(1e-9));
+        }
+
+        // The middle of the last layer is above the cuboid.
+        assert!(layers[3].contours.is_empty());
+    }
+}
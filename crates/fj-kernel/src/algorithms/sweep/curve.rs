@@ -1,8 +1,11 @@
-use fj_math::{Circle, Line, Vector};
+use fj_math::{Circle, Ellipse, Line, Nurbs, Vector};
 
 use crate::{
     builder::SurfaceBuilder,
-    geometry::path::{GlobalPath, SurfacePath},
+    geometry::{
+        path::{GlobalPath, SurfacePath},
+        surface::SurfaceGeometry,
+    },
     insert::Insert,
     objects::{Curve, Objects, Surface},
     partial::PartialSurface,
@@ -21,8 +24,18 @@ impl Sweep for Handle<Curve> {
         _: &mut SweepCache,
         objects: &mut Service<Objects>,
     ) -> Self::Swept {
-        match self.surface().geometry().u {
-            GlobalPath::Circle(_) => {
+        match self.surface().geometry() {
+            SurfaceGeometry::Swept {
+                u:
+                    GlobalPath::Circle(_)
+                    | GlobalPath::Ellipse(_)
+                    | GlobalPath::Helix(_)
+                    | GlobalPath::Nurbs(_),
+                ..
+            }
+            | SurfaceGeometry::Nurbs(_)
+            | SurfaceGeometry::Ruled { .. }
+            | SurfaceGeometry::Cone(_) => {
                 // Sweeping a `Curve` creates a `Surface`. The u-axis of that
                 // `Surface` is a `GlobalPath`, which we are computing below.
                 // That computation might or might not work with an arbitrary
@@ -39,7 +52,10 @@ impl Sweep for Handle<Curve> {
                     not supported yet."
                 )
             }
-            GlobalPath::Line(_) => {
+            SurfaceGeometry::Swept {
+                u: GlobalPath::Line(_),
+                ..
+            } => {
                 // We're sweeping from a curve on a flat surface, which is
                 // supported. Carry on.
             }
@@ -64,6 +80,24 @@ impl Sweep for Handle<Curve> {
 
                 GlobalPath::Circle(circle)
             }
+            SurfacePath::Ellipse(ellipse) => {
+                let center = self
+                    .surface()
+                    .geometry()
+                    .point_from_surface_coords(ellipse.center());
+                let a = self
+                    .surface()
+                    .geometry()
+                    .vector_from_surface_coords(ellipse.a());
+                let b = self
+                    .surface()
+                    .geometry()
+                    .vector_from_surface_coords(ellipse.b());
+
+                let ellipse = Ellipse::new(center, a, b);
+
+                GlobalPath::Ellipse(ellipse)
+            }
             SurfacePath::Line(line) => {
                 let origin = self
                     .surface()
@@ -78,6 +112,25 @@ impl Sweep for Handle<Curve> {
 
                 GlobalPath::Line(line)
             }
+            SurfacePath::Nurbs(nurbs) => {
+                // The surface is flat, so it maps the curve into global space
+                // without distorting it. Mapping the control points is enough.
+                let control_points =
+                    nurbs.control_points().iter().map(|&point| {
+                        self.surface()
+                            .geometry()
+                            .point_from_surface_coords(point)
+                    });
+
+                let nurbs = Nurbs::new(
+                    nurbs.degree(),
+                    control_points,
+                    nurbs.weights().iter().copied(),
+                    nurbs.knots().iter().copied(),
+                );
+
+                GlobalPath::Nurbs(nurbs)
+            }
         };
 
         PartialSurface::from_axes(u, path)
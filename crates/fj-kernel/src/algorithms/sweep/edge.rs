@@ -1,4 +1,7 @@
-use fj_interop::{ext::ArrayExt, mesh::Color};
+use fj_interop::{
+    ext::ArrayExt,
+    mesh::{Attributes, Color},
+};
 use fj_math::{Line, Scalar, Vector};
 use iter_fixed::IntoIteratorFixed;
 
@@ -17,7 +20,7 @@ use crate::{
 
 use super::{Sweep, SweepCache};
 
-impl Sweep for (Handle<HalfEdge>, Color) {
+impl Sweep for (Handle<HalfEdge>, Color, Attributes) {
     type Swept = Handle<Face>;
 
     fn sweep_with_cache(
@@ -26,7 +29,7 @@ impl Sweep for (Handle<HalfEdge>, Color) {
         cache: &mut SweepCache,
         objects: &mut Service<Objects>,
     ) -> Self::Swept {
-        let (edge, color) = self;
+        let (edge, color, attributes) = self;
         let path = path.into();
 
         let surface =
@@ -106,11 +109,12 @@ impl Sweep for (Handle<HalfEdge>, Color) {
                 });
 
             let curve = {
-                let global = bottom_edge
-                    .curve()
-                    .global_form()
-                    .clone()
-                    .translate(path, objects);
+                let bottom = bottom_edge.curve().global_form();
+                let global = cache
+                    .global_curve
+                    .entry(bottom.id())
+                    .or_insert_with(|| bottom.clone().translate(path, objects))
+                    .clone();
 
                 // Please note that creating a line here is correct, even if the
                 // global curve is a circle. Projected into the side surface, it
@@ -123,13 +127,19 @@ impl Sweep for (Handle<HalfEdge>, Color) {
                 Curve::new(surface, path, global).insert(objects)
             };
 
-            let global = GlobalEdge::new(
-                curve.global_form().clone(),
-                surface_vertices
-                    .clone()
-                    .map(|surface_vertex| surface_vertex.global_form().clone()),
-            )
-            .insert(objects);
+            let global = cache
+                .global_edge
+                .entry(bottom_edge.global_form().id())
+                .or_insert_with(|| {
+                    GlobalEdge::new(
+                        curve.global_form().clone(),
+                        surface_vertices.clone().map(|surface_vertex| {
+                            surface_vertex.global_form().clone()
+                        }),
+                    )
+                    .insert(objects)
+                })
+                .clone();
 
             let vertices = bottom_vertices
                 .each_ref_ext()
@@ -177,6 +187,7 @@ impl Sweep for (Handle<HalfEdge>, Color) {
         Face::partial()
             .with_exterior(cycle)
             .with_color(color)
+            .with_attributes(attributes)
             .build(objects)
             .insert(objects)
     }
@@ -184,7 +195,7 @@ impl Sweep for (Handle<HalfEdge>, Color) {
 
 #[cfg(test)]
 mod tests {
-    use fj_interop::mesh::Color;
+    use fj_interop::mesh::{Attributes, Color};
     use pretty_assertions::assert_eq;
 
     use crate::{
@@ -208,7 +219,7 @@ mod tests {
             .build(&mut services.objects)
             .insert(&mut services.objects);
 
-        let face = (half_edge, Color::default())
+        let face = (half_edge, Color::default(), Attributes::default())
             .sweep([0., 0., 1.], &mut services.objects);
 
         let expected_face = {
@@ -1,8 +1,11 @@
-use fj_math::{Scalar, Vector};
+use fj_math::{Scalar, Transform, Vector};
 
 use crate::{
-    algorithms::{reverse::Reverse, transform::TransformObject},
-    geometry::path::GlobalPath,
+    algorithms::{
+        reverse::Reverse,
+        transform::{TransformCache, TransformObject},
+    },
+    geometry::{path::GlobalPath, surface::SurfaceGeometry},
     objects::{Face, Objects, Shell},
     services::Service,
     storage::Handle,
@@ -24,16 +27,16 @@ impl Sweep for Handle<Face> {
         let mut faces = Vec::new();
 
         let is_negative_sweep = {
-            let u = match self.surface().geometry().u {
-                GlobalPath::Circle(_) => todo!(
+            let normal = match self.surface().geometry() {
+                SurfaceGeometry::Swept {
+                    u: GlobalPath::Line(line),
+                    v,
+                } => line.direction().cross(&v),
+                _ => todo!(
                     "Sweeping from faces defined in round surfaces is not \
                     supported"
                 ),
-                GlobalPath::Line(line) => line.direction(),
             };
-            let v = self.surface().geometry().v;
-
-            let normal = u.cross(&v);
 
             normal.dot(&path) < Scalar::ZERO
         };
@@ -47,17 +50,6 @@ impl Sweep for Handle<Face> {
         };
         faces.push(bottom_face);
 
-        let top_face = {
-            let mut face = self.clone().translate(path, objects);
-
-            if is_negative_sweep {
-                face = face.reverse(objects);
-            };
-
-            face
-        };
-        faces.push(top_face);
-
         // Generate side faces
         for cycle in self.all_cycles() {
             for half_edge in cycle.half_edges() {
@@ -67,20 +59,69 @@ impl Sweep for Handle<Face> {
                     half_edge.clone()
                 };
 
-                let face = (half_edge, self.color())
+                let face = (half_edge, self.color(), self.attributes().clone())
                     .sweep_with_cache(path, cache, objects);
 
                 faces.push(face);
             }
         }
 
+        let top_face = {
+            // The top face needs to share its global objects with the top edges
+            // of the side faces. Otherwise, their geometry would be computed
+            // separately, and might not end up exactly identical.
+            let mut transform_cache = TransformCache::default();
+            for half_edge in
+                self.all_cycles().flat_map(|cycle| cycle.half_edges())
+            {
+                let global_edge = half_edge.global_form();
+                if let Some(top) = cache.global_edge.get(&global_edge.id()) {
+                    transform_cache.insert(global_edge.clone(), top.clone());
+                }
+
+                let global_curve = global_edge.curve();
+                if let Some(top) = cache.global_curve.get(&global_curve.id()) {
+                    transform_cache.insert(global_curve.clone(), top.clone());
+                }
+
+                for global_vertex in
+                    global_edge.vertices().access_in_normalized_order()
+                {
+                    if let Some(top) =
+                        cache.global_vertex.get(&global_vertex.id())
+                    {
+                        transform_cache
+                            .insert(global_vertex.clone(), top.clone());
+                    }
+                }
+            }
+
+            let mut face = self.clone().transform_with_cache(
+                &Transform::translation(path),
+                objects,
+                &mut transform_cache,
+            );
+
+            if is_negative_sweep {
+                face = face.reverse(objects);
+            };
+
+            face
+        };
+        faces.push(top_face);
+
         Shell::builder().with_faces(faces).build(objects)
     }
 }
 
 #[cfg(test)]
 mod tests {
-    use fj_interop::{ext::SliceExt, mesh::Color};
+    use std::collections::BTreeMap;
+
+    use fj_interop::{
+        ext::SliceExt,
+        mesh::{Attributes, Color},
+    };
 
     use crate::{
         algorithms::{reverse::Reverse, transform::TransformObject},
@@ -88,7 +129,9 @@ mod tests {
         insert::Insert,
         objects::{Face, HalfEdge, Sketch},
         partial::HasPartial,
+        pretty::PrettyPrint,
         services::Services,
+        storage::ObjectId,
     };
 
     use super::Sweep;
@@ -125,8 +168,16 @@ mod tests {
             .build(&mut services.objects)
             .insert(&mut services.objects);
 
-        assert!(solid.find_face(&bottom).is_some());
-        assert!(solid.find_face(&top).is_some());
+        assert!(
+            solid.find_face(&bottom).is_some(),
+            "Bottom face not found in {}",
+            solid.pretty()
+        );
+        assert!(
+            solid.find_face(&top).is_some(),
+            "Top face not found in {}",
+            solid.pretty()
+        );
 
         let triangle = TRIANGLE.as_slice();
         let side_faces = triangle.array_windows_ext().map(|&[a, b]| {
@@ -137,12 +188,17 @@ mod tests {
                 )
                 .build(&mut services.objects)
                 .insert(&mut services.objects);
-            (half_edge, Color::default()).sweep(UP, &mut services.objects)
+            (half_edge, Color::default(), Attributes::default())
+                .sweep(UP, &mut services.objects)
         });
 
-        assert!(side_faces
-            .into_iter()
-            .all(|face| solid.find_face(&face).is_some()));
+        assert!(
+            side_faces
+                .into_iter()
+                .all(|face| solid.find_face(&face).is_some()),
+            "Side face not found in {}",
+            solid.pretty()
+        );
     }
 
     #[test]
@@ -172,8 +228,16 @@ mod tests {
             .build(&mut services.objects)
             .insert(&mut services.objects);
 
-        assert!(solid.find_face(&bottom).is_some());
-        assert!(solid.find_face(&top).is_some());
+        assert!(
+            solid.find_face(&bottom).is_some(),
+            "Bottom face not found in {}",
+            solid.pretty()
+        );
+        assert!(
+            solid.find_face(&top).is_some(),
+            "Top face not found in {}",
+            solid.pretty()
+        );
 
         let triangle = TRIANGLE.as_slice();
         let side_faces = triangle.array_windows_ext().map(|&[a, b]| {
@@ -185,11 +249,44 @@ mod tests {
                 .build(&mut services.objects)
                 .insert(&mut services.objects)
                 .reverse(&mut services.objects);
-            (half_edge, Color::default()).sweep(DOWN, &mut services.objects)
+            (half_edge, Color::default(), Attributes::default())
+                .sweep(DOWN, &mut services.objects)
         });
 
-        assert!(side_faces
-            .into_iter()
-            .all(|face| solid.find_face(&face).is_some()));
+        assert!(
+            side_faces
+                .into_iter()
+                .all(|face| solid.find_face(&face).is_some()),
+            "Side face not found in {}",
+            solid.pretty()
+        );
+    }
+
+    #[test]
+    fn side_faces_share_edges() {
+        let mut services = Services::new();
+
+        let surface = services.objects.surfaces.xy_plane();
+        let solid = Sketch::builder()
+            .with_polygon_from_points(surface, TRIANGLE, &mut services.objects)
+            .build(&mut services.objects)
+            .sweep(UP, &mut services.objects);
+
+        // Each edge of the prism is shared by the two faces that meet there,
+        // including the edges that the vertices of the triangle are swept
+        // into.
+        let mut faces_per_edge = BTreeMap::<ObjectId, usize>::new();
+        for face in solid.shells().flat_map(|shell| shell.faces()) {
+            for half_edge in
+                face.all_cycles().flat_map(|cycle| cycle.half_edges())
+            {
+                *faces_per_edge
+                    .entry(half_edge.global_form().id())
+                    .or_default() += 1;
+            }
+        }
+
+        assert_eq!(faces_per_edge.len(), 9);
+        assert!(faces_per_edge.values().all(|&count| count == 2));
     }
 }
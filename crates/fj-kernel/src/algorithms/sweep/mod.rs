@@ -3,6 +3,7 @@
 mod curve;
 mod edge;
 mod face;
+mod path;
 mod sketch;
 mod vertex;
 
@@ -10,8 +11,10 @@ use std::collections::BTreeMap;
 
 use fj_math::Vector;
 
+pub use self::path::{FrameOrientation, SweepAlongPath, SweepPath};
+
 use crate::{
-    objects::{GlobalVertex, Objects},
+    objects::{GlobalCurve, GlobalEdge, GlobalVertex, Objects},
     services::Service,
     storage::{Handle, ObjectId},
 };
@@ -47,4 +50,13 @@ pub trait Sweep: Sized {
 pub struct SweepCache {
     /// Cache for global vertices
     pub global_vertex: BTreeMap<ObjectId, Handle<GlobalVertex>>,
+
+    /// Cache for global curves at the end of the sweep
+    pub global_curve: BTreeMap<ObjectId, Handle<GlobalCurve>>,
+
+    /// Cache for global edges at the end of the sweep
+    pub global_edge: BTreeMap<ObjectId, Handle<GlobalEdge>>,
+
+    /// Cache for the global edges that global vertices are swept into
+    pub vertex_edge: BTreeMap<ObjectId, Handle<GlobalEdge>>,
 }
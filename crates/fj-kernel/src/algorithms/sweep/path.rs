@@ -0,0 +1,567 @@
+use fj_math::{Point, Scalar, Transform, Vector};
+
+use crate::{
+    algorithms::{
+        approx::{path::RangeOnPath, Approx, Tolerance},
+        loft::{
+            cap, polygon_from_cycle, profile_points, side_faces, LoftError,
+        },
+        polyhedron::polygon_normal,
+        transform::TransformObject,
+    },
+    geometry::path::GlobalPath,
+    insert::Insert,
+    objects::{Face, Objects, Shell, Sketch, Solid},
+    partial::HasPartial,
+    services::Service,
+    storage::Handle,
+};
+
+/// Sweep an object along a path that is not necessarily straight
+///
+/// The path is treated as a polygonal chain. A copy of the object is placed at
+/// every point of that chain, rotated according to the frame of the path at
+/// that point (see [`FrameOrientation`]), and those copies are connected with
+/// ruled side faces. Each face is swept into a single closed shell, without
+/// gaps or overlaps where the path changes direction.
+///
+/// The object is expected to be located at the start of the path already. See
+/// [`SweepPath::start_transform`]. Curved half-edges are approximated, using
+/// the provided tolerance.
+pub trait SweepAlongPath: Sized {
+    /// The object that is created by sweeping the implementing object
+    type Swept;
+
+    /// Sweep the object along the given path
+    fn sweep_along_path(
+        self,
+        path: &SweepPath,
+        orientation: FrameOrientation,
+        tolerance: impl Into<Tolerance>,
+        objects: &mut Service<Objects>,
+    ) -> Result<Self::Swept, LoftError>;
+}
+
+impl SweepAlongPath for Handle<Face> {
+    type Swept = Handle<Solid>;
+
+    fn sweep_along_path(
+        self,
+        path: &SweepPath,
+        orientation: FrameOrientation,
+        tolerance: impl Into<Tolerance>,
+        objects: &mut Service<Objects>,
+    ) -> Result<Self::Swept, LoftError> {
+        let tolerance = tolerance.into();
+
+        let exterior = polygon_from_cycle(self.exterior(), tolerance, objects);
+        let interiors = self
+            .interiors()
+            .map(|cycle| polygon_from_cycle(cycle, tolerance, objects))
+            .collect::<Vec<_>>();
+        let first = Face::partial()
+            .with_exterior(exterior)
+            .with_interiors(interiors)
+            .with_color(self.color())
+            .with_attributes(self.attributes().clone())
+            .build(objects)
+            .insert(objects);
+
+        let transforms = path.point_transforms(orientation);
+        let (last_transform, transforms) = transforms
+            .split_last()
+            .expect("Sweep path consists of at least two points");
+        let last = first.clone().transform(last_transform, objects);
+
+        let tangents = path
+            .points
+            .windows(2)
+            .map(|segment| segment[1] - segment[0])
+            .collect::<Vec<_>>();
+        let start = tangents[0];
+        let end = tangents[tangents.len() - 1];
+
+        let mut faces = vec![
+            cap(first.clone(), -start, objects)?,
+            cap(last.clone(), end, objects)?,
+        ];
+
+        for (i, (bottom, top)) in
+            first.all_cycles().zip(last.all_cycles()).enumerate()
+        {
+            // The first and last section need to match the caps exactly, so
+            // they are taken from those. The ones in between are transformed
+            // directly.
+            let points = profile_points(bottom)?;
+            let mut sections = vec![points.clone()];
+            for transform in &transforms[1..] {
+                sections.push(
+                    points
+                        .iter()
+                        .map(|point| transform.transform_point(point))
+                        .collect(),
+                );
+            }
+            sections.push(profile_points(top)?);
+
+            // The side faces of the exterior need to point away from the
+            // solid, those of the interiors into the holes. Both is achieved by
+            // winding the exterior counter-clockwise and the interiors
+            // clockwise, when looking at them against the path.
+            let is_exterior = i == 0;
+            let is_ccw =
+                polygon_normal(&sections[0]).dot(&start) > Scalar::ZERO;
+            if is_ccw != is_exterior {
+                for section in &mut sections {
+                    section[1..].reverse();
+                }
+            }
+
+            faces.extend(side_faces(
+                &sections,
+                self.color(),
+                self.attributes(),
+                objects,
+            ));
+        }
+
+        let shell = Shell::builder().with_faces(faces).build(objects);
+        Ok(Solid::builder().with_shells([shell]).build(objects))
+    }
+}
+
+impl SweepAlongPath for Handle<Sketch> {
+    type Swept = Handle<Solid>;
+
+    fn sweep_along_path(
+        self,
+        path: &SweepPath,
+        orientation: FrameOrientation,
+        tolerance: impl Into<Tolerance>,
+        objects: &mut Service<Objects>,
+    ) -> Result<Self::Swept, LoftError> {
+        let tolerance = tolerance.into();
+
+        let mut shells = Vec::new();
+        for face in self.faces().clone() {
+            let solid =
+                face.sweep_along_path(path, orientation, tolerance, objects)?;
+            shells.extend(solid.shells().cloned());
+        }
+
+        Ok(Solid::builder().with_shells(shells).build(objects))
+    }
+}
+
+/// A path that an object can be swept along
+///
+/// See [`SweepAlongPath`].
+#[derive(Clone, Debug)]
+pub struct SweepPath {
+    points: Vec<Point<3>>,
+}
+
+impl SweepPath {
+    /// Construct a path from the points of a polygonal chain
+    ///
+    /// # Panics
+    ///
+    /// Panics, if less than two points are provided, or if two consecutive
+    /// points are coincident.
+    pub fn from_points(
+        points: impl IntoIterator<Item = impl Into<Point<3>>>,
+    ) -> Self {
+        let points = points.into_iter().map(Into::into).collect::<Vec<_>>();
+
+        assert!(
+            points.len() >= 2,
+            "Sweep path must consist of at least two points"
+        );
+        for segment in points.windows(2) {
+            assert_ne!(
+                segment[0], segment[1],
+                "Sweep path must not contain coincident consecutive points"
+            );
+        }
+
+        Self { points }
+    }
+
+    /// Construct a path by approximating the given range of a curve
+    pub fn from_curve(
+        curve: GlobalPath,
+        range: impl Into<RangeOnPath>,
+        tolerance: impl Into<Tolerance>,
+    ) -> Self {
+        let range = range.into();
+
+        let [start, end] = range
+            .boundary
+            .map(|point| curve.point_from_path_coords(point));
+        let interior = (curve, range)
+            .approx(tolerance)
+            .into_iter()
+            .map(|(_, point)| point);
+
+        let points = [start].into_iter().chain(interior).chain([end]);
+        Self::from_points(points)
+    }
+
+    /// Access the points of the path
+    pub fn points(&self) -> &[Point<3>] {
+        &self.points
+    }
+
+    /// Compute the transform that places an object at the start of the path
+    ///
+    /// The transform moves the origin to the start of the path, and rotates the
+    /// z-axis into the direction of the first segment. The x-axis is rotated
+    /// according to `orientation`. An object that would be swept along the
+    /// z-axis, like a sketch in the xy-plane, is ready to be swept along the
+    /// path after being transformed like this.
+    ///
+    /// This is how a helical sweep is done: Place the object at the start of
+    /// a path that approximates a helix, then sweep it using
+    /// [`FrameOrientation::Frenet`]. The x-axis then points towards the axis of
+    /// the helix.
+    pub fn start_transform(&self, orientation: FrameOrientation) -> Transform {
+        let segments = self
+            .points
+            .windows(2)
+            .map(|segment| segment[1] - segment[0])
+            .collect::<Vec<_>>();
+        let frames = orientation.frames(&segments);
+
+        Transform::from_frame(self.points[0], frames[0])
+    }
+
+    /// Compute a transform for each point of the path
+    ///
+    /// Like the transforms computed for each segment, these move an object from
+    /// the start of the path to the respective point. The last point uses the
+    /// frame of the last segment.
+    fn point_transforms(
+        &self,
+        orientation: FrameOrientation,
+    ) -> Vec<Transform> {
+        let mut transforms = Vec::new();
+        let mut end = None;
+
+        for (transform, segment) in self.segment_transforms(orientation) {
+            end = Some(Transform::translation(segment) * transform);
+            transforms.push(transform);
+        }
+
+        transforms.extend(end);
+        transforms
+    }
+
+    /// Compute the transform and sweep vector for each segment of the path
+    ///
+    /// Each transform moves an object from the start of the path, to the start
+    /// of the respective segment, and rotates it according to the change in the
+    /// frame between those points.
+    fn segment_transforms(
+        &self,
+        orientation: FrameOrientation,
+    ) -> Vec<(Transform, Vector<3>)> {
+        let segments = self
+            .points
+            .windows(2)
+            .map(|segment| segment[1] - segment[0])
+            .collect::<Vec<_>>();
+        let frames = orientation.frames(&segments);
+
+        let from_frame = |origin: Point<3>, basis: [Vector<3>; 3]| {
+            Transform::from_frame(origin, basis)
+        };
+        let start = from_frame(self.points[0], frames[0]).inverse();
+
+        self.points
+            .iter()
+            .zip(frames)
+            .zip(segments)
+            .map(|((&origin, basis), segment)| {
+                (from_frame(origin, basis) * start, segment)
+            })
+            .collect()
+    }
+}
+
+/// How the frame of a path is oriented, as an object is swept along it
+///
+/// The frame defines the rotation of the swept object around the path.
+#[derive(Clone, Copy, Debug)]
+pub enum FrameOrientation {
+    /// Orient the frame according to the curvature of the path
+    ///
+    /// This is a discrete approximation of the Frenet-Serret frame. Where the
+    /// path is straight, the frame of the previous segment is carried over.
+    Frenet,
+
+    /// Keep the frame aligned with the given up vector
+    ///
+    /// Where a segment of the path is parallel to the up vector, the frame of
+    /// the previous segment is carried over.
+    FixedUp(Vector<3>),
+}
+
+impl FrameOrientation {
+    /// Compute an orthonormal frame for each segment
+    ///
+    /// The third vector of each frame is the direction of the segment.
+    fn frames(&self, segments: &[Vector<3>]) -> Vec<[Vector<3>; 3]> {
+        let tangents = segments
+            .iter()
+            .map(|segment| segment.normalize())
+            .collect::<Vec<_>>();
+
+        // The normal direction each segment would prefer, if any.
+        let preferred_normals = match self {
+            Self::Frenet => {
+                let turns = tangents
+                    .windows(2)
+                    .map(|tangents| Some(tangents[1] - tangents[0]));
+
+                // The first segment has no preference of its own. It uses the
+                // normal of the first turn in the path instead, if there is
+                // one.
+                let first = tangents
+                    .windows(2)
+                    .map(|tangents| tangents[1] - tangents[0])
+                    .find(|turn| turn.magnitude() > Scalar::from(EPSILON));
+
+                [first].into_iter().chain(turns).collect::<Vec<_>>()
+            }
+            Self::FixedUp(up) => tangents
+                .iter()
+                .map(|tangent| Some(up.cross(tangent)))
+                .collect(),
+        };
+
+        let mut frames: Vec<[Vector<3>; 3]> = Vec::new();
+        for (tangent, preferred) in tangents.into_iter().zip(preferred_normals)
+        {
+            let previous = frames.last().map(|&[normal, _, _]| normal);
+            let normal = [preferred, previous, Some(any_normal(tangent))]
+                .into_iter()
+                .flatten()
+                .map(|normal| normal - tangent * normal.dot(&tangent))
+                .find(|normal| normal.magnitude() > Scalar::from(EPSILON))
+                .expect("Expected to find normal perpendicular to tangent")
+                .normalize();
+
+            frames.push([normal, tangent.cross(&normal), tangent]);
+        }
+
+        frames
+    }
+}
+
+fn any_normal(tangent: Vector<3>) -> Vector<3> {
+    let axis = if tangent.x.abs() < tangent.y.abs() {
+        Vector::unit_x()
+    } else {
+        Vector::unit_y()
+    };
+    tangent.cross(&axis)
+}
+
+const EPSILON: f64 = 1e-9;
+
+#[cfg(test)]
+mod tests {
+    use std::f64::consts::TAU;
+
+    use fj_math::{Helix, Point, Scalar, Vector};
+
+    use crate::{
+        algorithms::{
+            approx::Tolerance, transform::TransformObject,
+            triangulate::Triangulate,
+        },
+        builder::CycleBuilder,
+        geometry::path::GlobalPath,
+        insert::Insert,
+        iter::ObjectIters,
+        objects::{Cycle, Face, Sketch},
+        partial::HasPartial,
+        services::Services,
+    };
+
+    use super::{FrameOrientation, SweepAlongPath, SweepPath};
+
+    #[test]
+    fn frames_are_orthonormal() {
+        let segments = [
+            Vector::from([0., 0., 1.]),
+            Vector::from([1., 0., 1.]),
+            Vector::from([1., 1., 0.]),
+            Vector::from([1., 1., 0.]),
+        ];
+        let orientations = [
+            FrameOrientation::Frenet,
+            FrameOrientation::FixedUp(Vector::unit_y()),
+        ];
+
+        for orientation in orientations {
+            let frames = orientation.frames(&segments);
+
+            for ([x, y, z], segment) in frames.into_iter().zip(segments) {
+                assert_eq!(z, segment.normalize());
+                assert_eq!(y, z.cross(&x));
+                assert!((x.magnitude() - 1.).abs() < Scalar::from(1e-9));
+                assert!(x.dot(&z).abs() < Scalar::from(1e-9));
+            }
+        }
+    }
+
+    #[test]
+    fn sweep_along_polyline() -> anyhow::Result<()> {
+        let mut services = Services::new();
+
+        let path =
+            SweepPath::from_points([[0., 0., 0.], [0., 0., 1.], [1., 0., 2.]]);
+
+        let surface = services.objects.surfaces.xy_plane();
+        let solid = Sketch::builder()
+            .with_polygon_from_points(
+                surface,
+                [[-0.1, -0.1], [0.1, -0.1], [0.1, 0.1], [-0.1, 0.1]],
+                &mut services.objects,
+            )
+            .build(&mut services.objects)
+            .sweep_along_path(
+                &path,
+                FrameOrientation::Frenet,
+                0.01,
+                &mut services.objects,
+            )?;
+
+        // A single shell, made up of the two caps, and two triangles for each
+        // edge of the profile, along each segment of the path.
+        assert_eq!(solid.shells().count(), 1);
+        assert_eq!(solid.face_iter().count(), 2 + 4 * 2 * 2);
+
+        // The profile is rotated by 45 degrees around the y axis for the second
+        // segment, so its corners end up near the end of the path.
+        let end = Point::from([1., 0., 2.]);
+        let distance_to_end = 0.1 * 2_f64.sqrt();
+        assert!(solid.global_vertex_iter().any(|vertex| {
+            let distance = (vertex.position() - end).magnitude();
+            (distance - distance_to_end).abs() < Scalar::from(1e-9)
+        }));
+
+        Ok(())
+    }
+
+    #[test]
+    fn sweep_face_with_hole_along_polyline() -> anyhow::Result<()> {
+        let mut services = Services::new();
+
+        let path =
+            SweepPath::from_points([[0., 0., 0.], [0., 0., 1.], [1., 0., 2.]]);
+
+        let surface = services.objects.surfaces.xy_plane();
+        let exterior = Cycle::partial()
+            .with_poly_chain_from_points(
+                surface.clone(),
+                [[-1., -1.], [1., -1.], [1., 1.], [-1., 1.]],
+            )
+            .close_with_line_segment()
+            .build(&mut services.objects)
+            .insert(&mut services.objects);
+        let interior = Cycle::partial()
+            .with_poly_chain_from_points(
+                surface,
+                [[-0.5, -0.5], [-0.5, 0.5], [0.5, 0.5], [0.5, -0.5]],
+            )
+            .close_with_line_segment()
+            .build(&mut services.objects)
+            .insert(&mut services.objects);
+        let face = Face::partial()
+            .with_exterior(exterior)
+            .with_interiors([interior])
+            .build(&mut services.objects)
+            .insert(&mut services.objects);
+
+        let solid = face.sweep_along_path(
+            &path,
+            FrameOrientation::Frenet,
+            0.01,
+            &mut services.objects,
+        )?;
+
+        assert_eq!(solid.shells().count(), 1);
+        assert_eq!(solid.face_iter().count(), 2 + 2 * 4 * 2 * 2);
+
+        // All faces point away from the solid. For a closed shell, the signed
+        // volume of its triangles is only positive, if that is the case.
+        let mesh = (&*solid, Tolerance::from_scalar(0.01)?).triangulate();
+        let volume = mesh
+            .triangles()
+            .map(|triangle| {
+                let [a, b, c] =
+                    triangle.inner.points().map(|point| point.coords);
+                a.dot(&b.cross(&c)) / 6.
+            })
+            .fold(Scalar::ZERO, |sum, volume| sum + volume);
+
+        // The cross-section has an area of 3. At the joint, the sections are
+        // perpendicular to the second segment, so they are sheared against the
+        // first, which the volume doesn't account for exactly.
+        let length = 1. + 2_f64.sqrt();
+        assert!(volume > Scalar::from(3. * length * 0.9));
+        assert!(volume < Scalar::from(3. * length * 1.1));
+
+        Ok(())
+    }
+
+    #[test]
+    fn sweep_along_helix() -> anyhow::Result<()> {
+        let mut services = Services::new();
+
+        let turns = 2.;
+        let path = SweepPath::from_curve(
+            GlobalPath::Helix(Helix::from_radius_and_pitch(1., 0.5)),
+            [[0.], [TAU * turns]],
+            0.01,
+        );
+        let orientation = FrameOrientation::Frenet;
+
+        let surface = services.objects.surfaces.xy_plane();
+        let solid = Sketch::builder()
+            .with_polygon_from_points(
+                surface,
+                [[-0.1, -0.1], [0.1, -0.1], [0.1, 0.1], [-0.1, 0.1]],
+                &mut services.objects,
+            )
+            .build(&mut services.objects)
+            .transform(
+                &path.start_transform(orientation),
+                &mut services.objects,
+            )
+            .sweep_along_path(
+                &path,
+                orientation,
+                0.01,
+                &mut services.objects,
+            )?;
+
+        assert_eq!(solid.shells().count(), 1);
+
+        // The profile stays within reach of the helix, all the way through.
+        // The tolerance accounts for the profile's corners.
+        for vertex in solid.global_vertex_iter() {
+            let position = vertex.position();
+            let distance_to_axis =
+                Vector::from([position.x, position.y]).magnitude();
+            let z = position.z;
+
+            assert!((distance_to_axis - 1.).abs() <= Scalar::from(0.15));
+            assert!(z >= Scalar::from(-0.15) && z <= Scalar::from(1.15));
+        }
+
+        Ok(())
+    }
+}
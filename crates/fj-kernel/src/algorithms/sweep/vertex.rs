@@ -1,7 +1,7 @@
 use fj_math::{Line, Point, Scalar, Vector};
 
 use crate::{
-    geometry::path::SurfacePath,
+    geometry::{path::SurfacePath, surface::SurfaceGeometry},
     insert::Insert,
     objects::{
         Curve, GlobalCurve, GlobalEdge, GlobalVertex, HalfEdge, Objects,
@@ -55,7 +55,10 @@ impl Sweep for (Handle<Vertex>, Handle<Surface>) {
         // not, we have no way of knowing the surface coordinates of the input
         // `Vertex` on the `Surface`, and we're going to need to do that further
         // down. There's no way to check for that, unfortunately.
-        assert_eq!(path, surface.geometry().v);
+        assert!(matches!(
+            surface.geometry(),
+            SurfaceGeometry::Swept { v, .. } if v == path
+        ));
 
         // With that out of the way, let's start by creating the `GlobalEdge`,
         // as that is the most straight-forward part of this operations, and
@@ -133,8 +136,6 @@ impl Sweep for Handle<GlobalVertex> {
         cache: &mut SweepCache,
         objects: &mut Service<Objects>,
     ) -> Self::Swept {
-        let curve = GlobalCurve.insert(objects);
-
         let a = self.clone();
         let b = cache
             .global_vertex
@@ -144,9 +145,18 @@ impl Sweep for Handle<GlobalVertex> {
             })
             .clone();
 
+        // A vertex is shared by the edges on either side of it, which sweep
+        // it into the same edge. The faces those edges are swept into must
+        // share that edge too.
         let vertices = [a, b];
-        let global_edge =
-            GlobalEdge::new(curve, vertices.clone()).insert(objects);
+        let global_edge = cache
+            .vertex_edge
+            .entry(self.id())
+            .or_insert_with(|| {
+                let curve = GlobalCurve.insert(objects);
+                GlobalEdge::new(curve, vertices.clone()).insert(objects)
+            })
+            .clone();
 
         // The vertices of the returned `GlobalEdge` are in normalized order,
         // which means the order can't be relied upon by the caller. Return the
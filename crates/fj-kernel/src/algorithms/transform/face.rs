@@ -1,6 +1,7 @@
 use fj_math::Transform;
 
 use crate::{
+    algorithms::reverse::Reverse,
     objects::{Face, FaceSet, Objects},
     services::Service,
 };
@@ -14,18 +15,36 @@ impl TransformObject for Face {
         objects: &mut Service<Objects>,
         cache: &mut TransformCache,
     ) -> Self {
-        // Color does not need to be transformed.
+        // Color and attributes do not need to be transformed.
         let color = self.color();
+        let attributes = self.attributes().clone();
 
         let exterior = self
             .exterior()
             .clone()
             .transform_with_cache(transform, objects, cache);
-        let interiors = self.interiors().cloned().map(|interior| {
-            interior.transform_with_cache(transform, objects, cache)
-        });
+        let interiors = self
+            .interiors()
+            .cloned()
+            .map(|interior| {
+                interior.transform_with_cache(transform, objects, cache)
+            })
+            .collect::<Vec<_>>();
 
-        Self::new(exterior, interiors, color)
+        // A reflection flips the surface over, which means the face would end
+        // up pointing the other way. Reverse it to keep its orientation.
+        if transform.inverts_orientation() {
+            let exterior = exterior.reverse(objects);
+            let interiors = interiors
+                .into_iter()
+                .map(|interior| interior.reverse(objects))
+                .collect::<Vec<_>>();
+
+            return Self::new(exterior, interiors, color)
+                .with_attributes(attributes);
+        }
+
+        Self::new(exterior, interiors, color).with_attributes(attributes)
     }
 }
 
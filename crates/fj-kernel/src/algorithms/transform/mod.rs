@@ -12,7 +12,7 @@ mod vertex;
 
 use std::collections::BTreeMap;
 
-use fj_math::{Transform, Vector};
+use fj_math::{Point, Scalar, Transform, Vector};
 use type_map::TypeMap;
 
 use crate::{
@@ -71,6 +71,32 @@ pub trait TransformObject: Sized {
     ) -> Self {
         self.transform(&Transform::rotation(axis_angle), objects)
     }
+
+    /// Scale the object uniformly, around the origin
+    ///
+    /// Convenience wrapper around [`TransformObject::transform`].
+    fn scale(
+        self,
+        factor: impl Into<Scalar>,
+        objects: &mut Service<Objects>,
+    ) -> Self {
+        self.transform(&Transform::scaling(factor), objects)
+    }
+
+    /// Mirror the object across a plane
+    ///
+    /// The plane is defined by a point on it, and its normal. Faces keep
+    /// pointing outward.
+    ///
+    /// Convenience wrapper around [`TransformObject::transform`].
+    fn mirror(
+        self,
+        origin: impl Into<Point<3>>,
+        normal: impl Into<Vector<3>>,
+        objects: &mut Service<Objects>,
+    ) -> Self {
+        self.transform(&Transform::reflection(origin, normal), objects)
+    }
 }
 
 impl<T> TransformObject for Handle<T>
@@ -114,7 +140,11 @@ impl TransformCache {
         map.get(&key.id())
     }
 
-    fn insert<T: 'static>(&mut self, key: Handle<T>, value: Handle<T>) {
+    pub(crate) fn insert<T: 'static>(
+        &mut self,
+        key: Handle<T>,
+        value: Handle<T>,
+    ) {
         let map = self
             .0
             .entry::<BTreeMap<ObjectId, Handle<T>>>()
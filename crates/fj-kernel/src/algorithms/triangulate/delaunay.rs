@@ -3,32 +3,38 @@ use std::collections::BTreeMap;
 use fj_math::{Point, Scalar, Triangle, Winding};
 use spade::HasPosition;
 
-use crate::{algorithms::approx::cycle::CycleApprox, objects::Handedness};
+use crate::{algorithms::approx::ApproxPoint, objects::Handedness};
+
+use super::TriangulationError;
 
 /// Create a Delaunay triangulation of all points
+///
+/// The edges of the cycles are constrained, while the additional points are
+/// inserted without any constraints.
+///
+/// Returns an error, if the cycles can't be triangulated. This is the case, if
+/// they contain invalid points, or if they intersect each other or themselves.
 pub fn triangulate(
-    cycles: impl IntoIterator<Item = CycleApprox>,
+    cycles: &[Vec<TriangulationPoint>],
+    additional_points: &[TriangulationPoint],
     coord_handedness: Handedness,
-) -> Vec<[TriangulationPoint; 3]> {
+) -> Result<Vec<[TriangulationPoint; 3]>, TriangulationError> {
     use spade::Triangulation as _;
 
     let mut triangulation = spade::ConstrainedDelaunayTriangulation::<_>::new();
 
     let mut points = BTreeMap::new();
 
-    for cycle_approx in cycles {
+    for cycle in cycles {
         let mut handle_prev = None;
 
-        for point in cycle_approx.points() {
+        for &point in cycle {
             let handle = match points.get(&point) {
                 Some(handle) => *handle,
                 None => {
-                    let handle = triangulation
-                        .insert(TriangulationPoint {
-                            point_surface: point.local_form,
-                            point_global: point.global_form,
-                        })
-                        .expect("Inserted invalid point into triangulation");
+                    let handle = triangulation.insert(point).map_err(|_| {
+                        TriangulationError::InvalidPoint(point.point_surface)
+                    })?;
 
                     points.insert(point, handle);
 
@@ -37,13 +43,36 @@ pub fn triangulate(
             };
 
             if let Some(handle_prev) = handle_prev {
-                triangulation.add_constraint(handle_prev, handle);
+                if handle_prev != handle {
+                    // `can_add_constraint` would be the obvious choice here,
+                    // but in the version of spade we use, it returns the
+                    // opposite of what its documentation says.
+                    let [from, to] = [handle_prev, handle]
+                        .map(|handle| triangulation.vertex(handle).position());
+                    if triangulation.intersects_constraint(from, to) {
+                        return Err(TriangulationError::IntersectingEdges(
+                            point.point_surface,
+                        ));
+                    }
+
+                    triangulation.add_constraint(handle_prev, handle);
+                }
             }
 
             handle_prev = Some(handle);
         }
     }
 
+    for &point in additional_points {
+        if points.contains_key(&point) {
+            continue;
+        }
+
+        triangulation.insert(point).map_err(|_| {
+            TriangulationError::InvalidPoint(point.point_surface)
+        })?;
+    }
+
     let mut triangles = Vec::new();
     for triangle in triangulation.inner_faces() {
         let [v0, v1, v2] = triangle.vertices().map(|vertex| *vertex.data());
@@ -52,7 +81,7 @@ pub fn triangulate(
             v1.point_surface,
             v2.point_surface,
         ])
-        .expect("invalid triangle")
+        .map_err(|_| TriangulationError::DegenerateTriangle(v0.point_surface))?
         .winding();
 
         let required_winding = match coord_handedness {
@@ -69,7 +98,7 @@ pub fn triangulate(
         triangles.push(triangle);
     }
 
-    triangles
+    Ok(triangles)
 }
 
 #[derive(Clone, Copy, Debug, Eq, PartialEq, Hash, Ord, PartialOrd)]
@@ -78,6 +107,15 @@ pub struct TriangulationPoint {
     pub point_global: Point<3>,
 }
 
+impl From<ApproxPoint<2>> for TriangulationPoint {
+    fn from(point: ApproxPoint<2>) -> Self {
+        Self {
+            point_surface: point.local_form,
+            point_global: point.global_form,
+        }
+    }
+}
+
 // Enables the use of `LocalPoint` in the triangulation.
 impl HasPosition for TriangulationPoint {
     type Scalar = Scalar;
@@ -0,0 +1,356 @@
+//! Ear-clipping triangulation
+//!
+//! A much simpler algorithm than the Delaunay triangulation, which produces
+//! triangles of lower quality. It serves as a fallback for faces that the
+//! Delaunay triangulation can't handle.
+//!
+//! The algorithm is designed to always terminate, and to produce a result for
+//! any input. For invalid input, like self-intersecting polygons, that result
+//! won't be a correct triangulation, but it will still be close enough to
+//! render something sensible.
+
+use std::cmp::Reverse;
+
+use fj_math::Point;
+
+use crate::{algorithms::point_in_face::orient2d, objects::Handedness};
+
+use super::delaunay::TriangulationPoint;
+
+/// Triangulate a polygon with holes by clipping ears
+///
+/// The first cycle is the exterior of the polygon, all others are its holes.
+/// The winding of the cycles doesn't matter.
+pub fn triangulate(
+    cycles: &[Vec<TriangulationPoint>],
+    coord_handedness: Handedness,
+) -> Vec<[TriangulationPoint; 3]> {
+    let mut cycles = cycles
+        .iter()
+        .map(|cycle| remove_duplicates(cycle))
+        .filter(|cycle| cycle.len() >= 3);
+
+    let mut polygon = match cycles.next() {
+        Some(exterior) => with_orientation(exterior, true),
+        None => return Vec::new(),
+    };
+
+    // Holes are bridged into the polygon from right to left. That way, the
+    // bridge of a hole can't cross the holes that are still left.
+    let mut holes = cycles
+        .map(|hole| with_orientation(hole, false))
+        .collect::<Vec<_>>();
+    holes.sort_by_key(|hole| Reverse(rightmost(hole)));
+
+    for (i, hole) in holes.iter().enumerate() {
+        bridge(&mut polygon, hole, &holes[i + 1..]);
+    }
+
+    clip_ears(polygon)
+        .into_iter()
+        .map(|[a, b, c]| match coord_handedness {
+            Handedness::LeftHanded => [a, c, b],
+            Handedness::RightHanded => [a, b, c],
+        })
+        .collect()
+}
+
+fn remove_duplicates(cycle: &[TriangulationPoint]) -> Vec<TriangulationPoint> {
+    let mut points: Vec<TriangulationPoint> = Vec::new();
+
+    for &point in cycle {
+        let is_duplicate = points
+            .last()
+            .map(|last| last.point_surface == point.point_surface)
+            .unwrap_or(false);
+        if !is_duplicate {
+            points.push(point);
+        }
+    }
+
+    // Cycles usually repeat their first point at the end.
+    while points.len() > 1
+        && points.first().map(|point| point.point_surface)
+            == points.last().map(|point| point.point_surface)
+    {
+        points.pop();
+    }
+
+    points
+}
+
+fn with_orientation(
+    mut cycle: Vec<TriangulationPoint>,
+    counter_clockwise: bool,
+) -> Vec<TriangulationPoint> {
+    let mut double_area = 0.;
+    for (i, a) in cycle.iter().enumerate() {
+        let b = cycle[(i + 1) % cycle.len()];
+        let [a, b] = [a, &b].map(|point| point.point_surface);
+
+        double_area += (a.u * b.v - b.u * a.v).into_f64();
+    }
+
+    if (double_area > 0.) != counter_clockwise {
+        cycle.reverse();
+    }
+
+    cycle
+}
+
+fn rightmost(cycle: &[TriangulationPoint]) -> (usize, Point<2>) {
+    cycle
+        .iter()
+        .map(|point| point.point_surface)
+        .enumerate()
+        .max_by_key(|&(_, point)| (point.u, point.v))
+        .expect("Cycle has at least 3 points")
+}
+
+/// Connect a hole to the polygon, turning both into a single polygon
+///
+/// The hole is connected from its rightmost point to a point of the polygon
+/// that is visible from there, via two coincident edges.
+fn bridge(
+    polygon: &mut Vec<TriangulationPoint>,
+    hole: &[TriangulationPoint],
+    remaining_holes: &[Vec<TriangulationPoint>],
+) {
+    let (start, point) = rightmost(hole);
+
+    let mut candidates = (0..polygon.len()).collect::<Vec<_>>();
+    candidates.sort_by_key(|&i| polygon[i].point_surface.distance_to(&point));
+
+    let is_visible = |i: usize| {
+        let n = polygon.len();
+        let candidate = polygon[i].point_surface;
+
+        if !is_in_cone(
+            [polygon[(i + n - 1) % n], polygon[i], polygon[(i + 1) % n]]
+                .map(|point| point.point_surface),
+            point,
+        ) {
+            return false;
+        }
+
+        let polygon_edges = (0..n)
+            .filter(|&j| j != i && (j + 1) % n != i)
+            .map(|j| [polygon[j], polygon[(j + 1) % n]]);
+        let hole_edges = remaining_holes.iter().flat_map(|hole| {
+            (0..hole.len()).map(|j| [hole[j], hole[(j + 1) % hole.len()]])
+        });
+
+        !polygon_edges.chain(hole_edges).any(|edge| {
+            let [a, b] = edge.map(|point| point.point_surface);
+            intersect_properly([candidate, point], [a, b])
+        })
+    };
+
+    // If nothing is visible, the input is invalid. Still, connecting to the
+    // nearest point gives us a polygon we can work with.
+    let target = candidates
+        .iter()
+        .copied()
+        .find(|&i| is_visible(i))
+        .unwrap_or(candidates[0]);
+
+    let mut bridged = Vec::with_capacity(polygon.len() + hole.len() + 2);
+    bridged.extend_from_slice(&polygon[..=target]);
+    bridged.extend_from_slice(&hole[start..]);
+    bridged.extend_from_slice(&hole[..=start]);
+    bridged.extend_from_slice(&polygon[target..]);
+
+    *polygon = bridged;
+}
+
+fn clip_ears(
+    mut polygon: Vec<TriangulationPoint>,
+) -> Vec<[TriangulationPoint; 3]> {
+    let mut triangles = Vec::new();
+
+    let mut i = 0;
+    let mut attempts = 0;
+
+    // Every iteration of this loop either advances to the next vertex, or
+    // removes one. Vertices are removed at least once per full round, so the
+    // loop is guaranteed to terminate.
+    while polygon.len() > 3 {
+        let n = polygon.len();
+        i %= n;
+
+        let is_ear = is_ear(&polygon, i);
+        let is_out_of_ears = attempts >= n;
+
+        if !is_ear && !is_out_of_ears {
+            i += 1;
+            attempts += 1;
+            continue;
+        }
+
+        // If there are no ears left, the polygon is invalid. Removing a vertex
+        // anyway keeps us going, at the cost of leaving a gap.
+        let [a, b, c] = neighborhood(&polygon, i);
+        if is_ear || orient(a, b, c) > 0. {
+            triangles.push([a, b, c]);
+        }
+
+        polygon.remove(i);
+        i = i.saturating_sub(1);
+        attempts = 0;
+    }
+
+    if let [a, b, c] = polygon[..] {
+        if orient(a, b, c) > 0. {
+            triangles.push([a, b, c]);
+        }
+    }
+
+    triangles
+}
+
+fn is_ear(polygon: &[TriangulationPoint], i: usize) -> bool {
+    let [a, b, c] = neighborhood(polygon, i);
+
+    if orient(a, b, c) <= 0. {
+        return false;
+    }
+
+    // Points that coincide with the corners of the triangle appear where holes
+    // are bridged. They don't prevent the triangle from being an ear.
+    let corners = [a, b, c].map(|point| point.point_surface);
+
+    !polygon.iter().any(|&point| {
+        !corners.contains(&point.point_surface)
+            && orient(a, b, point) >= 0.
+            && orient(b, c, point) >= 0.
+            && orient(c, a, point) >= 0.
+    })
+}
+
+fn neighborhood(
+    polygon: &[TriangulationPoint],
+    i: usize,
+) -> [TriangulationPoint; 3] {
+    let n = polygon.len();
+    [polygon[(i + n - 1) % n], polygon[i], polygon[(i + 1) % n]]
+}
+
+/// Determine whether a point is within the interior angle at a vertex
+///
+/// Expects a counter-clockwise polygon.
+fn is_in_cone([prev, vertex, next]: [Point<2>; 3], point: Point<2>) -> bool {
+    let left_of_incoming = orient2d(prev, vertex, point) > 0.;
+    let left_of_outgoing = orient2d(vertex, next, point) > 0.;
+
+    if orient2d(prev, vertex, next) >= 0. {
+        left_of_incoming && left_of_outgoing
+    } else {
+        left_of_incoming || left_of_outgoing
+    }
+}
+
+fn intersect_properly([a, b]: [Point<2>; 2], [c, d]: [Point<2>; 2]) -> bool {
+    orient2d(a, b, c) * orient2d(a, b, d) < 0.
+        && orient2d(c, d, a) * orient2d(c, d, b) < 0.
+}
+
+fn orient(
+    a: TriangulationPoint,
+    b: TriangulationPoint,
+    c: TriangulationPoint,
+) -> f64 {
+    orient2d(a.point_surface, b.point_surface, c.point_surface)
+}
+
+#[cfg(test)]
+mod tests {
+    use fj_math::{Point, Triangle};
+
+    use crate::{
+        algorithms::triangulate::delaunay::TriangulationPoint,
+        objects::Handedness,
+    };
+
+    use super::triangulate;
+
+    #[test]
+    fn square_with_hole() {
+        let exterior = cycle([[0., 0.], [4., 0.], [4., 4.], [0., 4.]]);
+        let hole = cycle([[1., 1.], [1., 3.], [3., 3.], [3., 1.]]);
+
+        let triangles = triangulate(&[exterior, hole], Handedness::RightHanded);
+
+        assert_eq!(area(&triangles), 12.);
+        for triangle in &triangles {
+            let center = triangle_center(triangle);
+            let is_in_hole = (1. ..3.).contains(&center.u.into_f64())
+                && (1. ..3.).contains(&center.v.into_f64());
+            assert!(!is_in_hole);
+        }
+    }
+
+    #[test]
+    fn concave_polygon() {
+        // A polygon shaped like the letter "U".
+        let exterior = cycle([
+            [0., 0.],
+            [3., 0.],
+            [3., 3.],
+            [2., 3.],
+            [2., 1.],
+            [1., 1.],
+            [1., 3.],
+            [0., 3.],
+        ]);
+
+        let triangles = triangulate(&[exterior], Handedness::LeftHanded);
+        assert_eq!(area(&triangles), 7.);
+    }
+
+    #[test]
+    fn self_intersecting_polygon() {
+        // The Delaunay triangulation can't handle this, but we still need to
+        // get something out of it, without looping forever.
+        let exterior = cycle([[0., 0.], [2., 2.], [2., 0.], [0., 2.]]);
+
+        let triangles = triangulate(&[exterior], Handedness::RightHanded);
+        assert!(triangles.len() <= 2);
+    }
+
+    fn cycle(
+        points: impl IntoIterator<Item = [f64; 2]>,
+    ) -> Vec<TriangulationPoint> {
+        points
+            .into_iter()
+            .map(|point| {
+                let point = Point::from(point);
+                TriangulationPoint {
+                    point_surface: point,
+                    point_global: point.to_xyz(),
+                }
+            })
+            .collect()
+    }
+
+    fn area(triangles: &[[TriangulationPoint; 3]]) -> f64 {
+        triangles
+            .iter()
+            .map(|triangle| {
+                let triangle = Triangle::<2>::from_points(
+                    triangle.map(|point| point.point_surface),
+                )
+                .expect("Triangulation produced invalid triangle");
+
+                let [a, b, c] = triangle.points();
+                ((b - a).cross2d(&(c - a)) / 2.).abs().into_f64()
+            })
+            .sum()
+    }
+
+    fn triangle_center(triangle: &[TriangulationPoint; 3]) -> Point<2> {
+        let [a, b, c] = triangle.map(|point| point.point_surface.coords);
+        Point {
+            coords: (a + b + c) / 3.,
+        }
+    }
+}
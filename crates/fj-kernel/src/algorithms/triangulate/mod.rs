@@ -1,21 +1,43 @@
 //! Shape triangulation
 
 mod delaunay;
+mod ear_clipping;
 mod polygon;
 
-use fj_interop::mesh::Mesh;
-use fj_math::Point;
+use std::collections::BTreeMap;
 
-use self::polygon::Polygon;
+use fj_interop::{
+    debug::{DebugInfo, TriangulationFailure},
+    mesh::{Mesh, ObjectId, Origin},
+};
+use fj_math::{Point, Scalar, Triangle, Vector};
+use rayon::prelude::*;
+
+use crate::objects::Handedness;
+
+use self::{delaunay::TriangulationPoint, polygon::Polygon};
 
 use super::approx::{face::FaceApprox, Approx, Tolerance};
 
 /// Triangulate a shape
 pub trait Triangulate: Sized {
     /// Triangulate the shape
+    ///
+    /// Faces that the primary triangulation algorithm fails on are
+    /// triangulated using a simpler fallback algorithm. Use
+    /// [`Triangulate::triangulate_with_debug_info`], to learn about those
+    /// failures.
     fn triangulate(self) -> Mesh<Point<3>> {
+        self.triangulate_with_debug_info(&mut DebugInfo::new())
+    }
+
+    /// Triangulate the shape, recording failures in the provided debug info
+    fn triangulate_with_debug_info(
+        self,
+        debug_info: &mut DebugInfo,
+    ) -> Mesh<Point<3>> {
         let mut mesh = Mesh::new();
-        self.triangulate_into_mesh(&mut mesh);
+        self.triangulate_into_mesh(&mut mesh, debug_info);
         mesh
     }
 
@@ -23,7 +45,11 @@ pub trait Triangulate: Sized {
     ///
     /// This is a low-level method, intended for implementation of
     /// `Triangulate`. Most callers should prefer [`Triangulate::triangulate`].
-    fn triangulate_into_mesh(self, mesh: &mut Mesh<Point<3>>);
+    fn triangulate_into_mesh(
+        self,
+        mesh: &mut Mesh<Point<3>>,
+        debug_info: &mut DebugInfo,
+    );
 }
 
 impl<T> Triangulate for (T, Tolerance)
@@ -31,56 +57,274 @@ where
     T: Approx,
     T::Approximation: IntoIterator<Item = FaceApprox>,
 {
-    fn triangulate_into_mesh(self, mesh: &mut Mesh<Point<3>>) {
+    fn triangulate_into_mesh(
+        self,
+        mesh: &mut Mesh<Point<3>>,
+        debug_info: &mut DebugInfo,
+    ) {
         let (approx, tolerance) = self;
 
-        let approx = approx.approx(tolerance);
+        let approx = approx.approx(tolerance).into_iter().collect();
 
-        for approx in approx {
-            approx.triangulate_into_mesh(mesh);
+        for (face_mesh, face_debug_info) in triangulate_faces(approx) {
+            mesh.extend(face_mesh.triangles());
+            debug_info
+                .triangulation_failures
+                .extend(face_debug_info.triangulation_failures);
         }
     }
 }
 
+/// Triangulate the approximations of multiple faces in parallel
+///
+/// Faces are independent of each other, once they have been approximated, so
+/// they are distributed across all available cores. Returns the mesh and the
+/// debug info of each face, in the order of the approximations, regardless of
+/// which face finishes first.
+pub fn triangulate_faces(
+    approx: Vec<FaceApprox>,
+) -> Vec<(Mesh<Point<3>>, DebugInfo)> {
+    approx
+        .into_par_iter()
+        .map(|approx| {
+            let mut debug_info = DebugInfo::new();
+            let mesh = approx.triangulate_with_debug_info(&mut debug_info);
+
+            (mesh, debug_info)
+        })
+        .collect()
+}
+
 impl Triangulate for FaceApprox {
-    fn triangulate_into_mesh(self, mesh: &mut Mesh<Point<3>>) {
-        let face_as_polygon = Polygon::new()
-            .with_exterior(
-                self.exterior
+    fn triangulate_into_mesh(
+        self,
+        mesh: &mut Mesh<Point<3>>,
+        debug_info: &mut DebugInfo,
+    ) {
+        let boundary = BoundaryObjects::new(&self);
+        let face = self.face.map(Into::into);
+
+        for triangle in triangulate_face(&self, debug_info) {
+            let points =
+                Triangle::from(triangle.map(|point| point.point_global));
+
+            // The normal of the surface isn't defined everywhere, like at the
+            // poles of a sphere. The flat triangle has to do there.
+            let normals = triangle.map(|point| {
+                normal_at(&self, point.point_surface)
+                    .unwrap_or_else(|| points.normal())
+            });
+
+            mesh.extend([fj_interop::mesh::Triangle {
+                inner: points,
+                normals: Some(normals),
+                color: self.color,
+                attributes: self.attributes.clone(),
+                origin: boundary.origin(face, points.points()),
+            }]);
+        }
+    }
+}
+
+/// The edges and vertices on the boundary of a face, by their position
+struct BoundaryObjects {
+    /// The edge that each segment of the boundary is part of
+    ///
+    /// Segments are undirected, so each one is keyed by its points in sorted
+    /// order.
+    edges: BTreeMap<[Point<3>; 2], ObjectId>,
+
+    /// The vertex at each point of the boundary that is on one
+    vertices: BTreeMap<Point<3>, ObjectId>,
+}
+
+impl BoundaryObjects {
+    fn new(approx: &FaceApprox) -> Self {
+        let mut edges = BTreeMap::new();
+        let mut vertices = BTreeMap::new();
+
+        for cycle in [&approx.exterior].into_iter().chain(&approx.interiors) {
+            let half_edges = &cycle.half_edges;
+
+            for (i, half_edge) in half_edges.iter().enumerate() {
+                vertices.insert(
+                    half_edge.first.global_form,
+                    half_edge.vertex.into(),
+                );
+
+                // The approximation of a half-edge leaves off its last point,
+                // which is the first point of the next one.
+                let next = &half_edges[(i + 1) % half_edges.len()];
+                let points = half_edge
                     .points()
                     .into_iter()
-                    .map(|point| point.local_form),
-            )
-            .with_interiors(self.interiors.iter().map(|interior| {
-                interior.points().into_iter().map(|point| point.local_form)
-            }));
-
-        let cycles = [self.exterior].into_iter().chain(self.interiors);
-        let mut triangles =
-            delaunay::triangulate(cycles, self.coord_handedness);
-        triangles.retain(|triangle| {
-            face_as_polygon
-                .contains_triangle(triangle.map(|point| point.point_surface))
+                    .chain([next.first.clone()])
+                    .map(|point| point.global_form)
+                    .collect::<Vec<_>>();
+
+                for segment in points.windows(2) {
+                    let [a, b] = [segment[0], segment[1]];
+                    edges.insert([a.min(b), a.max(b)], half_edge.edge.into());
+                }
+            }
+        }
+
+        Self { edges, vertices }
+    }
+
+    /// Determine the kernel objects that a triangle of the face lies on
+    fn origin(&self, face: Option<ObjectId>, points: [Point<3>; 3]) -> Origin {
+        let edges = [0, 1, 2].map(|i| {
+            let [a, b] = [points[i], points[(i + 1) % 3]];
+            self.edges.get(&[a.min(b), a.max(b)]).copied()
         });
+        let vertices = points.map(|point| self.vertices.get(&point).copied());
 
-        for triangle in triangles {
-            let points = triangle.map(|point| point.point_global);
-            mesh.push_triangle(points, self.color);
+        Origin {
+            face,
+            edges,
+            vertices,
         }
     }
 }
 
+/// Compute the normal of the front side of a face, at a point on its surface
+///
+/// Returns `None`, if the surface's derivatives at the point don't span a
+/// plane.
+fn normal_at(approx: &FaceApprox, point: Point<2>) -> Option<Vector<3>> {
+    let [du, dv] = approx.surface.derivatives_at(point);
+
+    let normal = du.cross(&dv);
+    if normal.magnitude()
+        <= du.magnitude() * dv.magnitude() * Scalar::from_f64(1e-9)
+    {
+        return None;
+    }
+
+    let normal = normal.normalize();
+    match approx.coord_handedness {
+        Handedness::RightHanded => Some(normal),
+        Handedness::LeftHanded => Some(-normal),
+    }
+}
+
+/// Triangulate the approximation of a face
+///
+/// Returns each triangle in both surface and global coordinates. A point on
+/// the seam of a closed surface, like a cylinder, has different surface
+/// coordinates depending on the triangle, which is lost in the triangle mesh.
+pub(crate) fn triangulate_face(
+    approx: &FaceApprox,
+    debug_info: &mut DebugInfo,
+) -> Vec<[TriangulationPoint; 3]> {
+    let face_as_polygon = Polygon::new()
+        .with_exterior(
+            approx
+                .exterior
+                .points()
+                .into_iter()
+                .map(|point| point.local_form),
+        )
+        .with_interiors(approx.interiors.iter().map(|interior| {
+            interior.points().into_iter().map(|point| point.local_form)
+        }));
+
+    let cycles = [&approx.exterior]
+        .into_iter()
+        .chain(&approx.interiors)
+        .map(|cycle| {
+            cycle
+                .points()
+                .into_iter()
+                .map(TriangulationPoint::from)
+                .collect::<Vec<_>>()
+        })
+        .collect::<Vec<_>>();
+
+    let surface_points = approx
+        .surface_points
+        .iter()
+        .cloned()
+        .map(TriangulationPoint::from)
+        .collect::<Vec<_>>();
+
+    let triangles = match delaunay::triangulate(
+        &cycles,
+        &surface_points,
+        approx.coord_handedness,
+    ) {
+        Ok(mut triangles) => {
+            triangles.retain(|triangle| {
+                face_as_polygon.contains_triangle(
+                    triangle.map(|point| point.point_surface),
+                )
+            });
+            triangles
+        }
+        Err(err) => {
+            // One bad face shouldn't prevent the rest of the shape from
+            // being triangulated. Record the failure, and fall back to an
+            // algorithm that can handle anything.
+            debug_info
+                .triangulation_failures
+                .push(TriangulationFailure {
+                    error: err.to_string(),
+                    exterior: cycles[0]
+                        .iter()
+                        .map(|point| point.point_global)
+                        .collect(),
+                });
+
+            // The fallback doesn't support the additional points on
+            // the surface. Curved surfaces will look coarse, but that
+            // is still better than nothing.
+            ear_clipping::triangulate(&cycles, approx.coord_handedness)
+        }
+    };
+
+    triangles
+}
+
+/// An error that can occur while triangulating a face
+#[derive(Debug, thiserror::Error)]
+pub enum TriangulationError {
+    /// A point of the face can't be triangulated
+    #[error("Invalid point in face: {0:?}")]
+    InvalidPoint(Point<2>),
+
+    /// The edges of the face intersect each other
+    #[error("Edges of face intersect each other near {0:?}")]
+    IntersectingEdges(Point<2>),
+
+    /// The triangulation produced a triangle without area
+    #[error("Triangulation produced degenerate triangle at {0:?}")]
+    DegenerateTriangle(Point<2>),
+}
+
 #[cfg(test)]
 mod tests {
-    use fj_interop::mesh::Mesh;
-    use fj_math::{Point, Scalar};
+    use std::{
+        collections::{BTreeMap, BTreeSet},
+        f64::consts::{FRAC_1_SQRT_2, FRAC_PI_2, SQRT_2},
+    };
+
+    use fj_interop::{
+        debug::DebugInfo,
+        mesh::{Attributes, Mesh},
+    };
+    use fj_math::{Circle, NurbsSurface, Point, Scalar, Vector};
 
     use crate::{
-        algorithms::approx::{Approx, Tolerance},
-        builder::FaceBuilder,
+        algorithms::{
+            approx::{Approx, Tolerance},
+            sweep::Sweep,
+        },
+        builder::{FaceBuilder, SurfaceBuilder},
+        geometry::path::GlobalPath,
         insert::Insert,
-        objects::Face,
-        partial::HasPartial,
+        objects::{Face, Sketch},
+        partial::{HasPartial, PartialSurface},
         services::Services,
         storage::Handle,
     };
@@ -210,6 +454,244 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn fallback_for_self_intersecting_face() -> anyhow::Result<()> {
+        let mut services = Services::new();
+
+        let surface = services.objects.surfaces.xy_plane();
+        let face = Face::partial()
+            .with_exterior_polygon_from_points(
+                surface,
+                [[0., 0.], [3., 3.], [3., 0.], [0., 1.]],
+            )
+            .build(&mut services.objects)
+            .insert(&mut services.objects);
+
+        let mut debug_info = DebugInfo::new();
+        let mesh = face
+            .approx(Tolerance::from_scalar(Scalar::ONE)?)
+            .triangulate_with_debug_info(&mut debug_info);
+
+        assert_eq!(debug_info.triangulation_failures.len(), 1);
+        assert!(mesh.triangles().count() > 0);
+
+        Ok(())
+    }
+
+    #[test]
+    fn nurbs_surface() -> anyhow::Result<()> {
+        let mut services = Services::new();
+
+        // A quarter of a cylinder with a radius and height of one.
+        let w = FRAC_1_SQRT_2;
+        let surface = PartialSurface::from_nurbs(NurbsSurface::new(
+            [2, 1],
+            [
+                [[1., 0., 0.], [1., 0., 1.]],
+                [[1., 1., 0.], [1., 1., 1.]],
+                [[0., 1., 0.], [0., 1., 1.]],
+            ],
+            [[1., 1.], [w, w], [1., 1.]],
+            [vec![0., 0., 0., 1., 1., 1.], vec![0., 0., 1., 1.]],
+        ))
+        .build(&services.objects)
+        .insert(&mut services.objects);
+
+        let face = Face::partial()
+            .with_exterior_polygon_from_points(
+                surface,
+                [[0., 0.], [1., 0.], [1., 1.], [0., 1.]],
+            )
+            .build(&mut services.objects)
+            .insert(&mut services.objects);
+
+        let mesh = face.approx(Tolerance::from_scalar(0.001)?).triangulate();
+
+        // Without points from within the face, the triangulation would be
+        // flat.
+        let area: f64 = mesh
+            .triangles()
+            .map(|triangle| {
+                let [a, b, c] = triangle.inner.points();
+                ((b - a).cross(&(c - a)).magnitude() / 2.).into_f64()
+            })
+            .sum();
+        assert!((area - FRAC_PI_2).abs() < 0.01);
+
+        Ok(())
+    }
+
+    #[test]
+    fn ruled_surface() -> anyhow::Result<()> {
+        let mut services = Services::new();
+
+        // A quarter of a cone, narrowing from a radius of two to one over a
+        // height of one.
+        let surface = PartialSurface::ruled(
+            GlobalPath::Circle(Circle::from_center_and_radius(
+                Point::origin(),
+                2.,
+            )),
+            GlobalPath::Circle(Circle::from_center_and_radius(
+                [0., 0., 1.],
+                1.,
+            )),
+        )
+        .build(&services.objects)
+        .insert(&mut services.objects);
+
+        let face = Face::partial()
+            .with_exterior_polygon_from_points(
+                surface,
+                [[0., 0.], [FRAC_PI_2, 0.], [FRAC_PI_2, 1.], [0., 1.]],
+            )
+            .build(&mut services.objects)
+            .insert(&mut services.objects);
+
+        let mesh = face.approx(Tolerance::from_scalar(0.001)?).triangulate();
+
+        let area: f64 = mesh
+            .triangles()
+            .map(|triangle| {
+                let [a, b, c] = triangle.inner.points();
+                ((b - a).cross(&(c - a)).magnitude() / 2.).into_f64()
+            })
+            .sum();
+        assert!((area - FRAC_PI_2 * 1.5 * SQRT_2).abs() < 0.01);
+
+        Ok(())
+    }
+
+    #[test]
+    fn surface_normals() -> anyhow::Result<()> {
+        let mut services = Services::new();
+
+        let surface = services.objects.surfaces.xy_plane();
+        let disc = Face::partial()
+            .with_exterior_circle(surface, [0., 0.], 1., &mut services.objects)
+            .build(&mut services.objects)
+            .insert(&mut services.objects);
+        let cylinder = Sketch::builder()
+            .with_faces([disc])
+            .build(&mut services.objects)
+            .sweep([0., 0., 1.], &mut services.objects);
+
+        let tolerance = Tolerance::from_scalar(0.01)?;
+        let mesh = (&*cylinder, tolerance).triangulate();
+
+        for triangle in mesh.triangles() {
+            let normals = triangle.normals.expect("Normals are known");
+            let flat = triangle.inner.normal();
+
+            for (point, normal) in
+                triangle.inner.points().into_iter().zip(normals)
+            {
+                assert!(normal.dot(&flat) > Scalar::ZERO);
+
+                // On the curved side, the normals point away from the axis,
+                // instead of being the same across the whole triangle.
+                if flat.z.abs() < Scalar::from(1e-6) {
+                    let radial = Vector::from([point.x, point.y, Scalar::ZERO]);
+                    assert!((normal - radial).magnitude() < Scalar::from(1e-6));
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn attributes() -> anyhow::Result<()> {
+        let mut services = Services::new();
+
+        let attributes = Attributes {
+            name: Some("plate".into()),
+            material: Some("PLA".into()),
+        };
+
+        let surface = services.objects.surfaces.xy_plane();
+        let face = Face::partial()
+            .with_exterior_polygon_from_points(
+                surface,
+                [[0., 0.], [1., 0.], [1., 1.], [0., 1.]],
+            )
+            .with_attributes(attributes.clone())
+            .build(&mut services.objects)
+            .insert(&mut services.objects);
+        let cuboid = Sketch::builder()
+            .with_faces([face])
+            .build(&mut services.objects)
+            .sweep([0., 0., 1.], &mut services.objects);
+
+        // The side faces are created by the sweep, and the top face by
+        // translating the original. All of them keep its attributes.
+        let tolerance = Tolerance::from_scalar(0.01)?;
+        let mesh = (&*cuboid, tolerance).triangulate();
+
+        assert_eq!(mesh.triangles().count(), 12);
+        for triangle in mesh.triangles() {
+            assert_eq!(triangle.attributes, attributes);
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn origin() -> anyhow::Result<()> {
+        let mut services = Services::new();
+
+        let surface = services.objects.surfaces.xy_plane();
+        let face = Face::partial()
+            .with_exterior_polygon_from_points(
+                surface,
+                [[0., 0.], [1., 0.], [1., 1.], [0., 1.]],
+            )
+            .build(&mut services.objects)
+            .insert(&mut services.objects);
+        let cuboid = Sketch::builder()
+            .with_faces([face])
+            .build(&mut services.objects)
+            .sweep([0., 0., 1.], &mut services.objects);
+
+        let tolerance = Tolerance::from_scalar(0.01)?;
+        let mesh = (&*cuboid, tolerance).triangulate();
+
+        let mut faces = BTreeSet::new();
+        let mut edges = BTreeMap::<_, BTreeSet<_>>::new();
+        let mut vertices = BTreeMap::<_, BTreeSet<_>>::new();
+        for triangle in mesh.triangles() {
+            let origin = triangle.origin;
+            let points = triangle.inner.points();
+            faces.insert(origin.face.expect("Face is known"));
+
+            // Each face is split into two triangles. One side of each
+            // triangle is the diagonal of the face, which isn't on an edge.
+            assert_eq!(origin.edges.iter().flatten().count(), 2);
+            for i in 0..3 {
+                let [a, b] = [points[i], points[(i + 1) % 3]];
+                if let Some(edge) = origin.edges[i] {
+                    edges.entry([a.min(b), a.max(b)]).or_default().insert(edge);
+                }
+            }
+
+            for (point, vertex) in points.into_iter().zip(origin.vertices) {
+                vertices
+                    .entry(point)
+                    .or_default()
+                    .insert(vertex.expect("Vertex is known"));
+            }
+        }
+
+        // The faces that share an edge or a vertex agree on its identity.
+        assert_eq!(faces.len(), 6);
+        assert_eq!(edges.len(), 12);
+        assert!(edges.values().all(|ids| ids.len() == 1));
+        assert_eq!(vertices.len(), 8);
+        assert!(vertices.values().all(|ids| ids.len() == 1));
+
+        Ok(())
+    }
+
     fn triangulate(face: Handle<Face>) -> anyhow::Result<Mesh<Point<3>>> {
         let tolerance = Tolerance::from_scalar(Scalar::ONE)?;
         Ok(face.approx(tolerance).triangulate())
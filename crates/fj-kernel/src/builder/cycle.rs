@@ -1,11 +1,14 @@
-use fj_math::Point;
+use fj_math::{Circle, Ellipse, Nurbs, Point, Scalar, Vector};
 
 use crate::{
-    objects::{HalfEdge, Surface, SurfaceVertex},
+    geometry::path::SurfacePath,
+    insert::Insert,
+    objects::{HalfEdge, Objects, Surface, SurfaceVertex},
     partial::{
         HasPartial, MaybePartial, PartialCurve, PartialCycle, PartialHalfEdge,
         PartialSurfaceVertex, PartialVertex,
     },
+    services::Service,
     storage::Handle,
 };
 
@@ -26,10 +29,149 @@ pub trait CycleBuilder {
         points: impl IntoIterator<Item = impl Into<Point<2>>>,
     ) -> Self;
 
+    /// Update the partial cycle with a circular arc through the provided points
+    ///
+    /// The arc starts at the first point, passes through the second, and ends
+    /// at the third. If the cycle already has half-edges, the arc is connected
+    /// to the last of them, and the first point must coincide with its end.
+    ///
+    /// # Panics
+    ///
+    /// Panics, if the points are collinear.
+    fn with_arc_from_points(
+        self,
+        surface: Handle<Surface>,
+        points: [impl Into<Point<2>>; 3],
+    ) -> Self;
+
+    /// Update the partial cycle with an arc that continues the last half-edge
+    ///
+    /// The arc starts at the end of the last half-edge, is tangent to it there,
+    /// and ends at the provided point.
+    ///
+    /// # Panics
+    ///
+    /// Panics, if the cycle has no half-edges, or if the end point lies on the
+    /// tangent of the last half-edge.
+    fn with_tangent_arc_to_point(self, end: impl Into<Point<2>>) -> Self;
+
+    /// Update the partial cycle with a full circle
+    ///
+    /// The circle is made up of a single half-edge that is wound
+    /// counter-clockwise, and is meant to be the only content of the cycle.
+    fn with_circle(
+        self,
+        surface: Handle<Surface>,
+        center: impl Into<Point<2>>,
+        radius: impl Into<Scalar>,
+        objects: &mut Service<Objects>,
+    ) -> Self;
+
+    /// Update the partial cycle with a full ellipse
+    ///
+    /// The semi-axes of the ellipse are aligned with the axes of the surface,
+    /// and have the provided radii. Like [`CycleBuilder::with_circle`], the
+    /// ellipse is made up of a single half-edge that is wound
+    /// counter-clockwise.
+    fn with_ellipse(
+        self,
+        surface: Handle<Surface>,
+        center: impl Into<Point<2>>,
+        radii: [impl Into<Scalar>; 2],
+        objects: &mut Service<Objects>,
+    ) -> Self;
+
+    /// Update the partial cycle with a NURBS curve
+    ///
+    /// The curve spans its whole range. If the cycle already has half-edges,
+    /// the curve is connected to the last of them, and the start of the curve
+    /// must coincide with its end.
+    fn with_nurbs(self, surface: Handle<Surface>, nurbs: Nurbs<2>) -> Self;
+
+    /// Update the partial cycle with a rectangle
+    ///
+    /// The sides of the rectangle are aligned with the axes of the surface,
+    /// and `size` defines their lengths along those axes. Like
+    /// [`CycleBuilder::with_circle`], the rectangle is wound counter-clockwise
+    /// and is meant to be the only content of the cycle.
+    fn with_rectangle(
+        self,
+        surface: Handle<Surface>,
+        center: impl Into<Point<2>>,
+        size: [impl Into<Scalar>; 2],
+    ) -> Self;
+
+    /// Update the partial cycle with a rectangle with rounded corners
+    ///
+    /// Like [`CycleBuilder::with_rectangle`], but each corner is replaced by a
+    /// quarter circle with the provided radius.
+    ///
+    /// # Panics
+    ///
+    /// Panics, if the radius is not positive, or if it's not smaller than half
+    /// of each side of the rectangle.
+    fn with_rounded_rectangle(
+        self,
+        surface: Handle<Surface>,
+        center: impl Into<Point<2>>,
+        size: [impl Into<Scalar>; 2],
+        radius: impl Into<Scalar>,
+    ) -> Self;
+
+    /// Update the partial cycle with a regular polygon
+    ///
+    /// The corners of the polygon are on a circle with the provided radius.
+    /// The first corner is in the direction of the surface's u-axis from the
+    /// center. The polygon is wound counter-clockwise, and is meant to be the
+    /// only content of the cycle.
+    ///
+    /// # Panics
+    ///
+    /// Panics, if the polygon has less than 3 sides.
+    fn with_regular_polygon(
+        self,
+        surface: Handle<Surface>,
+        center: impl Into<Point<2>>,
+        radius: impl Into<Scalar>,
+        num_sides: usize,
+    ) -> Self;
+
+    /// Update the partial cycle with a slot
+    ///
+    /// A slot is the shape that a circle with the provided radius covers, as
+    /// it moves from the first to the second center. It is made up of two
+    /// straight sides and two semicircles. The slot is wound
+    /// counter-clockwise, and is meant to be the only content of the cycle.
+    ///
+    /// # Panics
+    ///
+    /// Panics, if the centers coincide.
+    fn with_slot(
+        self,
+        surface: Handle<Surface>,
+        centers: [impl Into<Point<2>>; 2],
+        radius: impl Into<Scalar>,
+    ) -> Self;
+
     /// Update the partial cycle by closing it with a line segment
     ///
     /// Builds a line segment from the last and first vertex, closing the cycle.
     fn close_with_line_segment(self) -> Self;
+
+    /// Round the corners of the closed partial cycle
+    ///
+    /// Each corner between two line segments is replaced by a circular arc
+    /// with the provided radius, which is tangent to both line segments. The
+    /// line segments are shortened accordingly. Corners that involve curved
+    /// half-edges, and corners between collinear line segments, are left
+    /// as they are.
+    ///
+    /// # Panics
+    ///
+    /// Panics, if the radius is not positive, or if it's too large for the
+    /// line segments, meaning the arcs at both ends of a line segment would
+    /// overlap.
+    fn fillet_corners(self, radius: impl Into<Scalar>) -> Self;
 }
 
 impl CycleBuilder for PartialCycle {
@@ -110,6 +252,262 @@ impl CycleBuilder for PartialCycle {
         }))
     }
 
+    fn with_arc_from_points(
+        self,
+        surface: Handle<Surface>,
+        points: [impl Into<Point<2>>; 3],
+    ) -> Self {
+        let [start, through, end] = points.map(Into::into);
+        let start_vertex = self.last_vertex().unwrap_or_else(|| {
+            PartialSurfaceVertex {
+                position: Some(start),
+                surface: Some(surface.clone()),
+                ..Default::default()
+            }
+            .into()
+        });
+
+        let center = circumcenter([start, through, end])
+            .expect("Can't build arc through collinear points");
+        let is_ccw = (through - start).cross2d(&(end - through)) > Scalar::ZERO;
+
+        self.with_arc(surface, start_vertex, center, is_ccw, end)
+    }
+
+    fn with_tangent_arc_to_point(self, end: impl Into<Point<2>>) -> Self {
+        let end = end.into();
+
+        let last = self
+            .half_edges()
+            .last()
+            .expect("Need half-edge to continue tangentially from");
+        let [back, front] = last.vertices();
+        let [back_coord, front_coord] = [&back, &front].map(|vertex| {
+            vertex
+                .position()
+                .expect("Need curve position to compute tangent")
+        });
+        let path = last.curve().path().expect("Need path to compute tangent");
+        let surface = last
+            .curve()
+            .surface()
+            .expect("Need surface to extend cycle with arc");
+        let start_vertex = front.surface_form();
+        let start = start_vertex
+            .position()
+            .expect("Need surface position to extend cycle with arc");
+
+        let tangent = {
+            let tangent = path.derivative_at(front_coord);
+            if front_coord < back_coord {
+                -tangent
+            } else {
+                tangent
+            }
+        };
+
+        // The center lies on the normal of the tangent, at the same distance
+        // from the start and the end point.
+        let normal = Vector::from([-tangent.v, tangent.u]).normalize();
+        let chord = end - start;
+        let offset = chord.dot(&normal);
+        assert!(
+            offset != Scalar::ZERO,
+            "Can't build tangent arc to point on the tangent"
+        );
+        let center = start + normal * (chord.dot(&chord) / (offset * 2.));
+
+        self.with_arc(surface, start_vertex, center, offset > Scalar::ZERO, end)
+    }
+
+    fn with_circle(
+        self,
+        surface: Handle<Surface>,
+        center: impl Into<Point<2>>,
+        radius: impl Into<Scalar>,
+        objects: &mut Service<Objects>,
+    ) -> Self {
+        let circle = Circle::from_center_and_radius(center, radius);
+        self.with_closed_path(surface, SurfacePath::Circle(circle), objects)
+    }
+
+    fn with_ellipse(
+        self,
+        surface: Handle<Surface>,
+        center: impl Into<Point<2>>,
+        radii: [impl Into<Scalar>; 2],
+        objects: &mut Service<Objects>,
+    ) -> Self {
+        let ellipse = Ellipse::from_center_and_radii(center, radii);
+        self.with_closed_path(surface, SurfacePath::Ellipse(ellipse), objects)
+    }
+
+    fn with_nurbs(self, surface: Handle<Surface>, nurbs: Nurbs<2>) -> Self {
+        let [start_coord, end_coord] = nurbs.range();
+
+        let start = self.last_vertex().unwrap_or_else(|| {
+            PartialSurfaceVertex {
+                position: Some(nurbs.point_from_nurbs_coords([start_coord])),
+                surface: Some(surface.clone()),
+                ..Default::default()
+            }
+            .into()
+        });
+        let end = PartialSurfaceVertex {
+            position: Some(nurbs.point_from_nurbs_coords([end_coord])),
+            surface: Some(surface.clone()),
+            ..Default::default()
+        };
+
+        let curve = PartialCurve {
+            path: Some(SurfacePath::Nurbs(nurbs)),
+            surface: Some(surface),
+            ..Default::default()
+        };
+
+        let vertices = [(start_coord, start), (end_coord, end.into())].map(
+            |(coord, surface_form)| PartialVertex {
+                position: Some([coord].into()),
+                curve: curve.clone().into(),
+                surface_form,
+            },
+        );
+
+        self.with_half_edges([PartialHalfEdge {
+            vertices: vertices.map(Into::into),
+            ..Default::default()
+        }])
+    }
+
+    fn with_rectangle(
+        self,
+        surface: Handle<Surface>,
+        center: impl Into<Point<2>>,
+        size: [impl Into<Scalar>; 2],
+    ) -> Self {
+        let [min, max] = rectangle_bounds(center.into(), size);
+
+        self.with_poly_chain_from_points(
+            surface,
+            [
+                [min.u, min.v],
+                [max.u, min.v],
+                [max.u, max.v],
+                [min.u, max.v],
+            ],
+        )
+        .close_with_line_segment()
+    }
+
+    fn with_rounded_rectangle(
+        self,
+        surface: Handle<Surface>,
+        center: impl Into<Point<2>>,
+        size: [impl Into<Scalar>; 2],
+        radius: impl Into<Scalar>,
+    ) -> Self {
+        let [min, max] = rectangle_bounds(center.into(), size);
+        let radius = radius.into();
+
+        assert!(
+            radius > Scalar::ZERO,
+            "Radius of rounded corners must be positive"
+        );
+        assert!(
+            radius * 2. < max.u - min.u && radius * 2. < max.v - min.v,
+            "Radius of rounded corners must be smaller than half of each side"
+        );
+
+        // The centers of the corners, counter-clockwise from the bottom right,
+        // and the angle at which each corner's arc starts.
+        let corners = [
+            ([max.u - radius, min.v + radius], -Scalar::PI / 2.),
+            ([max.u - radius, max.v - radius], Scalar::ZERO),
+            ([min.u + radius, max.v - radius], Scalar::PI / 2.),
+            ([min.u + radius, min.v + radius], Scalar::PI),
+        ];
+        let point_on_corner = |center: [Scalar; 2], angle: Scalar| {
+            let (sin, cos) = angle.sin_cos();
+            Point::from(center) + Vector::from([cos, sin]) * radius
+        };
+
+        let mut cycle = self;
+
+        for (i, (center, angle)) in corners.into_iter().enumerate() {
+            let [arc_start, through, arc_end] = [0., 0.5, 1.]
+                .map(|f| point_on_corner(center, angle + Scalar::PI / 2. * f));
+
+            // The last arc ends where the first side starts. That point is
+            // computed the same way in both cases, so the cycle is closed
+            // exactly.
+            let side = if i == 0 {
+                let (center, angle) = corners[3];
+                vec![
+                    point_on_corner(center, angle + Scalar::PI / 2.),
+                    arc_start,
+                ]
+            } else {
+                vec![arc_start]
+            };
+
+            cycle = cycle
+                .with_poly_chain_from_points(surface.clone(), side)
+                .with_arc_from_points(
+                    surface.clone(),
+                    [arc_start, through, arc_end],
+                );
+        }
+
+        cycle
+    }
+
+    fn with_regular_polygon(
+        self,
+        surface: Handle<Surface>,
+        center: impl Into<Point<2>>,
+        radius: impl Into<Scalar>,
+        num_sides: usize,
+    ) -> Self {
+        assert!(num_sides >= 3, "Regular polygon needs at least 3 sides");
+
+        let center = center.into();
+        let radius = radius.into();
+
+        let points = (0..num_sides).map(|i| {
+            let angle = Scalar::TAU * i as f64 / num_sides as f64;
+            let (sin, cos) = angle.sin_cos();
+            center + Vector::from([cos, sin]) * radius
+        });
+
+        self.with_poly_chain_from_points(surface, points)
+            .close_with_line_segment()
+    }
+
+    fn with_slot(
+        self,
+        surface: Handle<Surface>,
+        centers: [impl Into<Point<2>>; 2],
+        radius: impl Into<Scalar>,
+    ) -> Self {
+        let [a, b] = centers.map(Into::into);
+        let radius = radius.into();
+
+        assert_ne!(a, b, "Centers of slot must not coincide");
+
+        let direction = (b - a).normalize() * radius;
+        let normal = Vector::from([-direction.v, direction.u]);
+
+        let start = a - normal;
+
+        self.with_poly_chain_from_points(surface.clone(), [start, b - normal])
+            .with_arc_from_points(
+                surface.clone(),
+                [b - normal, b + direction, b + normal],
+            )
+            .with_poly_chain_from_points(surface.clone(), [a + normal])
+            .with_arc_from_points(surface, [a + normal, a - direction, start])
+    }
+
     fn close_with_line_segment(self) -> Self {
         let first = self.half_edges().next();
         let last = self.half_edges().last();
@@ -134,4 +532,565 @@ impl CycleBuilder for PartialCycle {
                 .update_as_line_segment_from_points(surface, vertices),
         ))
     }
+
+    fn fillet_corners(self, radius: impl Into<Scalar>) -> Self {
+        let radius = radius.into();
+        assert!(radius > Scalar::ZERO, "Fillet radius must be positive");
+
+        let surface = match self.surface() {
+            Some(surface) => surface,
+            None => return self,
+        };
+
+        let half_edges = self.half_edges().collect::<Vec<_>>();
+        let n = half_edges.len();
+
+        let positions = half_edges
+            .iter()
+            .map(|half_edge| {
+                half_edge.vertices().map(|vertex| {
+                    vertex
+                        .surface_form()
+                        .position()
+                        .expect("Need surface position to fillet corners")
+                })
+            })
+            .collect::<Vec<_>>();
+        let is_line = half_edges
+            .iter()
+            .map(|half_edge| {
+                matches!(half_edge.curve().path(), Some(SurfacePath::Line(_)))
+            })
+            .collect::<Vec<_>>();
+
+        // The corner `i` is at the start of half-edge `i`.
+        let fillets = (0..n)
+            .map(|i| {
+                let previous = (i + n - 1) % n;
+                if n < 3 || !is_line[previous] || !is_line[i] {
+                    return None;
+                }
+
+                let [back, corner] = positions[previous];
+                let [_, front] = positions[i];
+
+                Fillet::new([back, corner, front], radius)
+            })
+            .collect::<Vec<_>>();
+
+        let mut cycle = PartialCycle::default();
+
+        for (i, half_edge) in half_edges.into_iter().enumerate() {
+            let next = (i + 1) % n;
+
+            if !is_line[i] {
+                cycle = cycle.with_half_edges([half_edge]);
+                continue;
+            }
+
+            let [start, end] = positions[i];
+            let distances = [&fillets[i], &fillets[next]].map(|fillet| {
+                fillet
+                    .as_ref()
+                    .map(|(_, distance)| *distance)
+                    .unwrap_or_default()
+            });
+            assert!(
+                distances[0] + distances[1] <= start.distance_to(&end),
+                "Fillet radius is too large for the line segments of the cycle"
+            );
+
+            let start = match &fillets[i] {
+                Some((fillet, _)) => fillet.end,
+                None => start,
+            };
+            let line_end = match &fillets[next] {
+                Some((fillet, _)) => fillet.start,
+                None => end,
+            };
+
+            // The first half-edge needs its start point. All others start at
+            // the end of the previous one.
+            let mut points = Vec::new();
+            if cycle.half_edges().next().is_none() {
+                points.push(start);
+            }
+            if start != line_end {
+                points.push(line_end);
+            }
+            cycle = cycle.with_poly_chain_from_points(surface.clone(), points);
+
+            if let Some((fillet, _)) = &fillets[next] {
+                cycle = cycle.with_arc_from_points(
+                    surface.clone(),
+                    [fillet.start, fillet.through, fillet.end],
+                );
+            }
+        }
+
+        cycle
+    }
+}
+
+/// A corner of a cycle, replaced by an arc
+struct Fillet {
+    start: Point<2>,
+    through: Point<2>,
+    end: Point<2>,
+}
+
+impl Fillet {
+    /// Compute the fillet for the corner at `corner`
+    ///
+    /// Returns the fillet, and the distance from the corner to where the arc
+    /// touches the adjacent line segments. Returns `None`, if the line
+    /// segments are collinear.
+    fn new(
+        [previous, corner, next]: [Point<2>; 3],
+        radius: Scalar,
+    ) -> Option<(Self, Scalar)> {
+        let to_previous = (previous - corner).normalize();
+        let to_next = (next - corner).normalize();
+
+        // The angle between the two line segments at the corner.
+        let angle = to_previous
+            .cross2d(&to_next)
+            .abs()
+            .atan2(to_previous.dot(&to_next));
+        if angle == Scalar::ZERO || angle == Scalar::PI {
+            return None;
+        }
+
+        let (sin, cos) = (angle / 2.).sin_cos();
+        let distance = radius * cos / sin;
+
+        let bisector = (to_previous + to_next).normalize();
+        let center = corner + bisector * (radius / sin);
+
+        let fillet = Self {
+            start: corner + to_previous * distance,
+            through: center - bisector * radius,
+            end: corner + to_next * distance,
+        };
+
+        Some((fillet, distance))
+    }
+}
+
+trait CycleBuilderExt {
+    fn last_vertex(&self) -> Option<MaybePartial<SurfaceVertex>>;
+
+    fn with_arc(
+        self,
+        surface: Handle<Surface>,
+        start: MaybePartial<SurfaceVertex>,
+        center: Point<2>,
+        is_ccw: bool,
+        end: Point<2>,
+    ) -> Self;
+
+    fn with_closed_path(
+        self,
+        surface: Handle<Surface>,
+        path: SurfacePath,
+        objects: &mut Service<Objects>,
+    ) -> Self;
+}
+
+impl CycleBuilderExt for PartialCycle {
+    fn last_vertex(&self) -> Option<MaybePartial<SurfaceVertex>> {
+        self.half_edges().last().map(|half_edge| {
+            let [_, last] = half_edge.vertices();
+            last.surface_form()
+        })
+    }
+
+    fn with_arc(
+        self,
+        surface: Handle<Surface>,
+        start: MaybePartial<SurfaceVertex>,
+        center: Point<2>,
+        is_ccw: bool,
+        end: Point<2>,
+    ) -> Self {
+        let start_position = start
+            .position()
+            .expect("Need surface position to extend cycle with arc");
+
+        // Orient the circle such that its coordinates increase along the arc.
+        let a = start_position - center;
+        let b = if is_ccw {
+            Vector::from([-a.v, a.u])
+        } else {
+            Vector::from([a.v, -a.u])
+        };
+        let circle = Circle::new(center, a, b);
+
+        let end_coord = {
+            let end = end - center;
+            let coord = end.dot(&b).atan2(end.dot(&a));
+            if coord <= Scalar::ZERO {
+                coord + Scalar::TAU
+            } else {
+                coord
+            }
+        };
+
+        let curve = PartialCurve {
+            path: Some(SurfacePath::Circle(circle)),
+            surface: Some(surface.clone()),
+            ..Default::default()
+        };
+
+        let end = PartialSurfaceVertex {
+            position: Some(end),
+            surface: Some(surface),
+            ..Default::default()
+        };
+
+        let vertices = [(Scalar::ZERO, start), (end_coord, end.into())].map(
+            |(coord, surface_form)| PartialVertex {
+                position: Some([coord].into()),
+                curve: curve.clone().into(),
+                surface_form,
+            },
+        );
+
+        self.with_half_edges([PartialHalfEdge {
+            vertices: vertices.map(Into::into),
+            ..Default::default()
+        }])
+    }
+
+    fn with_closed_path(
+        self,
+        surface: Handle<Surface>,
+        path: SurfacePath,
+        objects: &mut Service<Objects>,
+    ) -> Self {
+        let curve = PartialCurve {
+            path: Some(path.clone()),
+            surface: Some(surface.clone()),
+            ..Default::default()
+        };
+
+        // Both vertices of the half-edge are at the same position. They need
+        // to refer to the same surface vertex.
+        let surface_vertex = PartialSurfaceVertex {
+            position: Some(path.point_from_path_coords([0.])),
+            surface: Some(surface),
+            ..Default::default()
+        }
+        .build(objects)
+        .insert(objects);
+
+        let vertices = [Scalar::ZERO, Scalar::TAU].map(|coord| PartialVertex {
+            position: Some([coord].into()),
+            curve: curve.clone().into(),
+            surface_form: surface_vertex.clone().into(),
+        });
+
+        self.with_half_edges([PartialHalfEdge {
+            vertices: vertices.map(Into::into),
+            ..Default::default()
+        }])
+    }
+}
+
+/// Compute the minimum and maximum corners of an axis-aligned rectangle
+fn rectangle_bounds(
+    center: Point<2>,
+    size: [impl Into<Scalar>; 2],
+) -> [Point<2>; 2] {
+    let half_size = Vector::from(size.map(Into::into)) / 2.;
+    [center - half_size, center + half_size]
+}
+
+/// Compute the center of the circle through three points
+fn circumcenter([a, b, c]: [Point<2>; 3]) -> Option<Point<2>> {
+    let [ab, ac] = [b - a, c - a];
+
+    let denominator = ab.cross2d(&ac) * 2.;
+    if denominator == Scalar::ZERO {
+        return None;
+    }
+
+    let [ab2, ac2] = [ab.dot(&ab), ac.dot(&ac)];
+    let offset = Vector::from([
+        (ac.v * ab2 - ab.v * ac2) / denominator,
+        (ab.u * ac2 - ac.u * ab2) / denominator,
+    ]);
+
+    Some(a + offset)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::f64::consts::PI;
+
+    use fj_interop::mesh::Mesh;
+    use fj_math::{Nurbs, Point, Scalar};
+
+    use crate::{
+        algorithms::{
+            approx::{Approx, Tolerance},
+            triangulate::Triangulate,
+        },
+        builder::FaceBuilder,
+        geometry::path::SurfacePath,
+        insert::Insert,
+        objects::{Cycle, Face},
+        partial::HasPartial,
+        services::Services,
+    };
+
+    use super::CycleBuilder;
+
+    #[test]
+    fn arcs() -> anyhow::Result<()> {
+        let mut services = Services::new();
+
+        let surface = services.objects.surfaces.xy_plane();
+        let cycle = Cycle::partial()
+            .with_poly_chain_from_points(
+                surface.clone(),
+                [[-1., -1.], [1., -1.]],
+            )
+            .with_arc_from_points(
+                surface.clone(),
+                [[1., -1.], [2., 0.], [1., 1.]],
+            )
+            .with_poly_chain_from_points(surface.clone(), [[-1., 1.]])
+            .with_tangent_arc_to_point([-1., -1.])
+            .build(&mut services.objects)
+            .insert(&mut services.objects);
+
+        let circles = cycle
+            .half_edges()
+            .filter(|half_edge| {
+                matches!(half_edge.curve().path(), SurfacePath::Circle(_))
+            })
+            .count();
+        assert_eq!(circles, 2);
+
+        let face = Face::partial()
+            .with_exterior(cycle)
+            .build(&mut services.objects)
+            .insert(&mut services.objects);
+        let mesh = face.approx(Tolerance::from_scalar(0.01)?).triangulate();
+        // The arc to the right and the tangent arc to the left each add half
+        // a circle with radius 1 to the 2x2 square.
+        let area: f64 = mesh
+            .triangles()
+            .map(|triangle| {
+                let [a, b, c] = triangle.inner.points();
+                (b - a).cross(&(c - a)).magnitude().into_f64() / 2.
+            })
+            .sum();
+        assert!((area - (4. + std::f64::consts::PI)).abs() < 0.1);
+
+        Ok(())
+    }
+
+    #[test]
+    fn circle() -> anyhow::Result<()> {
+        let mut services = Services::new();
+
+        let surface = services.objects.surfaces.xy_plane();
+        let face = Face::partial()
+            .with_exterior_circle(
+                surface.clone(),
+                [0., 0.],
+                2.,
+                &mut services.objects,
+            )
+            .with_interior_circle(surface, [0., 0.], 1., &mut services.objects)
+            .build(&mut services.objects)
+            .insert(&mut services.objects);
+
+        let mesh = face.approx(Tolerance::from_scalar(0.01)?).triangulate();
+        assert!(mesh.triangles().all(|triangle| {
+            let [a, b, c] = triangle.inner.points();
+            let center = (a.coords + b.coords + c.coords) / 3.;
+            center.magnitude() > Scalar::ONE
+        }));
+
+        Ok(())
+    }
+
+    #[test]
+    fn nurbs() -> anyhow::Result<()> {
+        let mut services = Services::new();
+
+        // A parabola, `y = x²`, for `x` from `-1` to `1`, closed at the top.
+        let surface = services.objects.surfaces.xy_plane();
+        let face = Face::partial()
+            .with_exterior(
+                Cycle::partial()
+                    .with_nurbs(
+                        surface,
+                        Nurbs::from_control_points(
+                            2,
+                            [[-1., 1.], [0., -1.], [1., 1.]],
+                        ),
+                    )
+                    .close_with_line_segment(),
+            )
+            .build(&mut services.objects)
+            .insert(&mut services.objects);
+
+        let mesh = face.approx(Tolerance::from_scalar(0.001)?).triangulate();
+        let area: f64 = mesh
+            .triangles()
+            .map(|triangle| {
+                let [a, b, c] = triangle.inner.points();
+                (b - a).cross(&(c - a)).magnitude().into_f64() / 2.
+            })
+            .sum();
+        assert!((area - 4. / 3.).abs() < 0.01);
+
+        Ok(())
+    }
+
+    #[test]
+    fn ellipse() -> anyhow::Result<()> {
+        let mut services = Services::new();
+
+        let surface = services.objects.surfaces.xy_plane();
+        let face = Face::partial()
+            .with_exterior(Cycle::partial().with_ellipse(
+                surface,
+                [0., 0.],
+                [2., 1.],
+                &mut services.objects,
+            ))
+            .build(&mut services.objects)
+            .insert(&mut services.objects);
+
+        let mesh = face.approx(Tolerance::from_scalar(0.001)?).triangulate();
+        let area: f64 = mesh
+            .triangles()
+            .map(|triangle| {
+                let [a, b, c] = triangle.inner.points();
+                (b - a).cross(&(c - a)).magnitude().into_f64() / 2.
+            })
+            .sum();
+        assert!((area - 2. * std::f64::consts::PI).abs() < 0.05);
+
+        Ok(())
+    }
+
+    #[test]
+    fn rectangle_and_regular_polygon() -> anyhow::Result<()> {
+        let mut services = Services::new();
+
+        let surface = services.objects.surfaces.xy_plane();
+        let face = Face::partial()
+            .with_exterior_rectangle(surface.clone(), [1., 1.], [4., 2.])
+            .with_interior_regular_polygon(
+                surface,
+                [1., 1.],
+                0.5,
+                6,
+                &mut services.objects,
+            )
+            .build(&mut services.objects)
+            .insert(&mut services.objects);
+
+        let mesh = face.approx(Tolerance::from_scalar(0.01)?).triangulate();
+        let hexagon = 3. * 3_f64.sqrt() / 2. * 0.25;
+        assert!((area(&mesh) - (8. - hexagon)).abs() < 1e-12);
+
+        Ok(())
+    }
+
+    #[test]
+    fn rounded_rectangle_and_slot() -> anyhow::Result<()> {
+        let mut services = Services::new();
+
+        let surface = services.objects.surfaces.xy_plane();
+        let face = Face::partial()
+            .with_exterior_rounded_rectangle(
+                surface.clone(),
+                [0., 0.],
+                [4., 4.],
+                1.,
+            )
+            .with_interior_slot(
+                surface,
+                [[-1., 0.], [1., 0.]],
+                0.5,
+                &mut services.objects,
+            )
+            .build(&mut services.objects)
+            .insert(&mut services.objects);
+
+        let mesh = face.approx(Tolerance::from_scalar(0.001)?).triangulate();
+        let rounded_rectangle = 12. + PI;
+        let slot = 2. + PI * 0.25;
+        assert!((area(&mesh) - (rounded_rectangle - slot)).abs() < 0.01);
+
+        Ok(())
+    }
+
+    #[test]
+    fn fillet_corners() -> anyhow::Result<()> {
+        let mut services = Services::new();
+
+        // An L-shape, which has a concave corner besides the convex ones.
+        let surface = services.objects.surfaces.xy_plane();
+        let radius = 0.25;
+        let cycle = Cycle::partial()
+            .with_poly_chain_from_points(
+                surface,
+                [[0., 0.], [2., 0.], [2., 1.], [1., 1.], [1., 2.], [0., 2.]],
+            )
+            .close_with_line_segment()
+            .fillet_corners(radius)
+            .build(&mut services.objects)
+            .insert(&mut services.objects);
+
+        let arcs = cycle
+            .half_edges()
+            .filter(|half_edge| {
+                matches!(half_edge.curve().path(), SurfacePath::Circle(_))
+            })
+            .count();
+        assert_eq!(arcs, 6);
+
+        let face = Face::partial()
+            .with_exterior(cycle)
+            .build(&mut services.objects)
+            .insert(&mut services.objects);
+
+        // Five convex corners each remove a bit of area, the concave corner
+        // adds the same amount back.
+        let mesh = face.approx(Tolerance::from_scalar(0.0001)?).triangulate();
+        let corner = (1. - PI / 4.) * radius * radius;
+        assert!((area(&mesh) - (3. - 4. * corner)).abs() < 0.001);
+
+        Ok(())
+    }
+
+    #[test]
+    #[should_panic]
+    fn fillet_corners_radius_too_large() {
+        let surface = Services::new().objects.surfaces.xy_plane();
+        Cycle::partial()
+            .with_poly_chain_from_points(
+                surface,
+                [[0., 0.], [1., 0.], [1., 1.], [0., 1.]],
+            )
+            .close_with_line_segment()
+            .fillet_corners(0.6);
+    }
+
+    fn area(mesh: &Mesh<Point<3>>) -> f64 {
+        mesh.triangles()
+            .map(|triangle| {
+                let [a, b, c] = triangle.inner.points();
+                (b - a).cross(&(c - a)).magnitude().into_f64() / 2.
+            })
+            .sum()
+    }
 }
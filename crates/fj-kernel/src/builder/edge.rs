@@ -77,7 +77,10 @@ impl HalfEdgeBuilder for PartialHalfEdge {
         let mut curve = self.curve().into_partial();
         curve.update_as_circle_from_radius(radius);
 
-        let path = curve.path.expect("Expected path that was just created");
+        let path = curve
+            .path
+            .clone()
+            .expect("Expected path that was just created");
 
         let [a_curve, b_curve] =
             [Scalar::ZERO, Scalar::TAU].map(|coord| Point::from([coord]));
@@ -1,8 +1,11 @@
-use fj_math::Point;
+use fj_math::{Point, Scalar};
 
 use crate::{
-    objects::{Cycle, Surface},
-    partial::{HasPartial, PartialFace},
+    algorithms::reverse::Reverse,
+    insert::Insert,
+    objects::{Cycle, Objects, Surface},
+    partial::{HasPartial, PartialCycle, PartialFace},
+    services::Service,
     storage::Handle,
 };
 
@@ -23,6 +26,112 @@ pub trait FaceBuilder {
         surface: Handle<Surface>,
         points: impl IntoIterator<Item = impl Into<Point<2>>>,
     ) -> Self;
+
+    /// Update the [`PartialFace`] with an exterior circle
+    fn with_exterior_circle(
+        self,
+        surface: Handle<Surface>,
+        center: impl Into<Point<2>>,
+        radius: impl Into<Scalar>,
+        objects: &mut Service<Objects>,
+    ) -> Self;
+
+    /// Update the [`PartialFace`] with an interior circle
+    fn with_interior_circle(
+        self,
+        surface: Handle<Surface>,
+        center: impl Into<Point<2>>,
+        radius: impl Into<Scalar>,
+        objects: &mut Service<Objects>,
+    ) -> Self;
+
+    /// Update the [`PartialFace`] with an exterior rectangle
+    ///
+    /// See [`CycleBuilder::with_rectangle`].
+    fn with_exterior_rectangle(
+        self,
+        surface: Handle<Surface>,
+        center: impl Into<Point<2>>,
+        size: [impl Into<Scalar>; 2],
+    ) -> Self;
+
+    /// Update the [`PartialFace`] with an interior rectangle
+    ///
+    /// See [`CycleBuilder::with_rectangle`].
+    fn with_interior_rectangle(
+        self,
+        surface: Handle<Surface>,
+        center: impl Into<Point<2>>,
+        size: [impl Into<Scalar>; 2],
+        objects: &mut Service<Objects>,
+    ) -> Self;
+
+    /// Update the [`PartialFace`] with an exterior rounded rectangle
+    ///
+    /// See [`CycleBuilder::with_rounded_rectangle`].
+    fn with_exterior_rounded_rectangle(
+        self,
+        surface: Handle<Surface>,
+        center: impl Into<Point<2>>,
+        size: [impl Into<Scalar>; 2],
+        radius: impl Into<Scalar>,
+    ) -> Self;
+
+    /// Update the [`PartialFace`] with an interior rounded rectangle
+    ///
+    /// See [`CycleBuilder::with_rounded_rectangle`].
+    fn with_interior_rounded_rectangle(
+        self,
+        surface: Handle<Surface>,
+        center: impl Into<Point<2>>,
+        size: [impl Into<Scalar>; 2],
+        radius: impl Into<Scalar>,
+        objects: &mut Service<Objects>,
+    ) -> Self;
+
+    /// Update the [`PartialFace`] with an exterior regular polygon
+    ///
+    /// See [`CycleBuilder::with_regular_polygon`].
+    fn with_exterior_regular_polygon(
+        self,
+        surface: Handle<Surface>,
+        center: impl Into<Point<2>>,
+        radius: impl Into<Scalar>,
+        num_sides: usize,
+    ) -> Self;
+
+    /// Update the [`PartialFace`] with an interior regular polygon
+    ///
+    /// See [`CycleBuilder::with_regular_polygon`].
+    fn with_interior_regular_polygon(
+        self,
+        surface: Handle<Surface>,
+        center: impl Into<Point<2>>,
+        radius: impl Into<Scalar>,
+        num_sides: usize,
+        objects: &mut Service<Objects>,
+    ) -> Self;
+
+    /// Update the [`PartialFace`] with an exterior slot
+    ///
+    /// See [`CycleBuilder::with_slot`].
+    fn with_exterior_slot(
+        self,
+        surface: Handle<Surface>,
+        centers: [impl Into<Point<2>>; 2],
+        radius: impl Into<Scalar>,
+    ) -> Self;
+
+    /// Update the [`PartialFace`] with an interior slot
+    ///
+    /// See [`CycleBuilder::with_slot`].
+    fn with_interior_slot(
+        self,
+        surface: Handle<Surface>,
+        centers: [impl Into<Point<2>>; 2],
+        radius: impl Into<Scalar>,
+        objects: &mut Service<Objects>,
+    ) -> Self;
 }
 
 impl FaceBuilder for PartialFace {
@@ -47,4 +156,133 @@ impl FaceBuilder for PartialFace {
             .with_poly_chain_from_points(surface, points)
             .close_with_line_segment()])
     }
+
+    fn with_exterior_circle(
+        self,
+        surface: Handle<Surface>,
+        center: impl Into<Point<2>>,
+        radius: impl Into<Scalar>,
+        objects: &mut Service<Objects>,
+    ) -> Self {
+        self.with_exterior(
+            Cycle::partial().with_circle(surface, center, radius, objects),
+        )
+    }
+
+    fn with_interior_circle(
+        self,
+        surface: Handle<Surface>,
+        center: impl Into<Point<2>>,
+        radius: impl Into<Scalar>,
+        objects: &mut Service<Objects>,
+    ) -> Self {
+        let cycle =
+            Cycle::partial().with_circle(surface, center, radius, objects);
+        self.with_interiors([reversed(cycle, objects)])
+    }
+
+    fn with_exterior_rectangle(
+        self,
+        surface: Handle<Surface>,
+        center: impl Into<Point<2>>,
+        size: [impl Into<Scalar>; 2],
+    ) -> Self {
+        self.with_exterior(
+            Cycle::partial().with_rectangle(surface, center, size),
+        )
+    }
+
+    fn with_interior_rectangle(
+        self,
+        surface: Handle<Surface>,
+        center: impl Into<Point<2>>,
+        size: [impl Into<Scalar>; 2],
+        objects: &mut Service<Objects>,
+    ) -> Self {
+        let cycle = Cycle::partial().with_rectangle(surface, center, size);
+        self.with_interiors([reversed(cycle, objects)])
+    }
+
+    fn with_exterior_rounded_rectangle(
+        self,
+        surface: Handle<Surface>,
+        center: impl Into<Point<2>>,
+        size: [impl Into<Scalar>; 2],
+        radius: impl Into<Scalar>,
+    ) -> Self {
+        self.with_exterior(
+            Cycle::partial()
+                .with_rounded_rectangle(surface, center, size, radius),
+        )
+    }
+
+    fn with_interior_rounded_rectangle(
+        self,
+        surface: Handle<Surface>,
+        center: impl Into<Point<2>>,
+        size: [impl Into<Scalar>; 2],
+        radius: impl Into<Scalar>,
+        objects: &mut Service<Objects>,
+    ) -> Self {
+        let cycle = Cycle::partial()
+            .with_rounded_rectangle(surface, center, size, radius);
+        self.with_interiors([reversed(cycle, objects)])
+    }
+
+    fn with_exterior_regular_polygon(
+        self,
+        surface: Handle<Surface>,
+        center: impl Into<Point<2>>,
+        radius: impl Into<Scalar>,
+        num_sides: usize,
+    ) -> Self {
+        self.with_exterior(
+            Cycle::partial()
+                .with_regular_polygon(surface, center, radius, num_sides),
+        )
+    }
+
+    fn with_interior_regular_polygon(
+        self,
+        surface: Handle<Surface>,
+        center: impl Into<Point<2>>,
+        radius: impl Into<Scalar>,
+        num_sides: usize,
+        objects: &mut Service<Objects>,
+    ) -> Self {
+        let cycle = Cycle::partial()
+            .with_regular_polygon(surface, center, radius, num_sides);
+        self.with_interiors([reversed(cycle, objects)])
+    }
+
+    fn with_exterior_slot(
+        self,
+        surface: Handle<Surface>,
+        centers: [impl Into<Point<2>>; 2],
+        radius: impl Into<Scalar>,
+    ) -> Self {
+        self.with_exterior(Cycle::partial().with_slot(surface, centers, radius))
+    }
+
+    fn with_interior_slot(
+        self,
+        surface: Handle<Surface>,
+        centers: [impl Into<Point<2>>; 2],
+        radius: impl Into<Scalar>,
+        objects: &mut Service<Objects>,
+    ) -> Self {
+        let cycle = Cycle::partial().with_slot(surface, centers, radius);
+        self.with_interiors([reversed(cycle, objects)])
+    }
+}
+
+/// Build a cycle and reverse it
+///
+/// Interiors need to be wound clockwise, which is the opposite of how the
+/// cycle builder creates closed shapes.
+fn reversed(
+    cycle: PartialCycle,
+    objects: &mut Service<Objects>,
+) -> Handle<Cycle> {
+    cycle.build(objects).insert(objects).reverse(objects)
 }
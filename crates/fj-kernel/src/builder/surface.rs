@@ -1,7 +1,11 @@
-use fj_math::{Line, Point, Vector};
+use fj_math::{Cone, Line, NurbsSurface, Point, Scalar, Vector};
 
 use crate::{
-    geometry::{path::GlobalPath, surface::SurfaceGeometry},
+    geometry::{
+        path::{GlobalPath, SurfacePath},
+        surface::SurfaceGeometry,
+    },
+    objects::{Face, HalfEdge, Handedness, Surface},
     partial::PartialSurface,
 };
 
@@ -11,7 +15,53 @@ pub trait SurfaceBuilder {
     fn from_axes(u: GlobalPath, v: impl Into<Vector<3>>) -> Self;
 
     /// Construct a plane from 3 points
+    ///
+    /// The first point becomes the origin of the plane. The u-axis points from
+    /// there to the second point, the v-axis to the third.
     fn plane_from_points(points: [impl Into<Point<3>>; 3]) -> Self;
+
+    /// Construct a plane that is parallel to another, at the provided distance
+    ///
+    /// The new plane is moved along the normal of the other plane, and keeps
+    /// its axes. Sketches on the new plane end up where sketches on the other
+    /// plane would, if they were moved along that normal. Negative distances
+    /// move the plane in the opposite direction.
+    ///
+    /// # Panics
+    ///
+    /// Panics, if the other surface is not a plane.
+    fn plane_offset_from(plane: &Surface, distance: impl Into<Scalar>) -> Self;
+
+    /// Construct a plane through an edge, at an angle to a face
+    ///
+    /// The u-axis of the plane runs along the edge, from its back to its front
+    /// vertex. The v-axis is perpendicular to the edge. At an angle of zero,
+    /// the new plane is the plane of the face, and its v-axis points from the
+    /// edge into the face. Positive angles, in radians, rotate the plane
+    /// around the edge, towards the front side of the face.
+    ///
+    /// The half-edge is expected to be part of the face.
+    ///
+    /// # Panics
+    ///
+    /// Panics, if the face is not defined on a plane, or if the half-edge is
+    /// not a straight line.
+    fn plane_at_angle_to_face(
+        face: &Face,
+        half_edge: &HalfEdge,
+        angle: impl Into<Scalar>,
+    ) -> Self;
+
+    /// Build a surface from a NURBS surface
+    fn from_nurbs(nurbs: NurbsSurface<3>) -> Self;
+
+    /// Build a ruled surface between two paths
+    ///
+    /// See [`SurfaceGeometry::Ruled`].
+    fn ruled(a: GlobalPath, b: GlobalPath) -> Self;
+
+    /// Build a surface from a cone
+    fn from_cone(cone: Cone) -> Self;
 }
 
 impl SurfaceBuilder for PartialSurface {
@@ -19,7 +69,7 @@ impl SurfaceBuilder for PartialSurface {
         let v = v.into();
 
         Self {
-            geometry: Some(SurfaceGeometry { u, v }),
+            geometry: Some(SurfaceGeometry::Swept { u, v }),
         }
     }
 
@@ -30,7 +80,153 @@ impl SurfaceBuilder for PartialSurface {
         let v = c - a;
 
         Self {
-            geometry: Some(SurfaceGeometry { u, v }),
+            geometry: Some(SurfaceGeometry::Swept { u, v }),
+        }
+    }
+
+    fn plane_offset_from(plane: &Surface, distance: impl Into<Scalar>) -> Self {
+        let SurfaceGeometry::Swept {
+            u: GlobalPath::Line(line),
+            v,
+        } = plane.geometry()
+        else {
+            panic!("Can only offset planes");
+        };
+
+        let normal = line.direction().cross(&v).normalize();
+        let origin = line.origin() + normal * distance.into();
+
+        Self::from_axes(
+            GlobalPath::Line(Line::from_origin_and_direction(
+                origin,
+                line.direction(),
+            )),
+            v,
+        )
+    }
+
+    fn plane_at_angle_to_face(
+        face: &Face,
+        half_edge: &HalfEdge,
+        angle: impl Into<Scalar>,
+    ) -> Self {
+        let angle = angle.into();
+
+        let SurfaceGeometry::Swept {
+            u: GlobalPath::Line(line),
+            v,
+        } = face.surface().geometry()
+        else {
+            panic!("Expected face on a plane");
+        };
+        assert!(
+            matches!(half_edge.curve().path(), SurfacePath::Line(_)),
+            "Expected straight half-edge"
+        );
+
+        let [a, b] = half_edge
+            .vertices()
+            .each_ref()
+            .map(|vertex| vertex.global_form().position());
+        let direction = (b - a).normalize();
+
+        // Looking at the face against its normal, the face is to the left of
+        // all of its cycles.
+        let normal = match face.coord_handedness() {
+            Handedness::RightHanded => line.direction().cross(&v),
+            Handedness::LeftHanded => v.cross(&line.direction()),
+        }
+        .normalize();
+        let into_face = normal.cross(&direction);
+
+        let (sin, cos) = angle.sin_cos();
+        let v = into_face * cos + normal * sin;
+
+        Self::from_axes(
+            GlobalPath::Line(Line::from_origin_and_direction(a, direction)),
+            v,
+        )
+    }
+
+    fn from_nurbs(nurbs: NurbsSurface<3>) -> Self {
+        Self {
+            geometry: Some(SurfaceGeometry::Nurbs(nurbs)),
+        }
+    }
+
+    fn ruled(a: GlobalPath, b: GlobalPath) -> Self {
+        Self {
+            geometry: Some(SurfaceGeometry::Ruled { a, b }),
+        }
+    }
+
+    fn from_cone(cone: Cone) -> Self {
+        Self {
+            geometry: Some(SurfaceGeometry::Cone(cone)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use fj_math::{Point, Scalar};
+
+    use crate::{
+        builder::FaceBuilder,
+        insert::Insert,
+        objects::Face,
+        partial::{HasPartial, PartialSurface},
+        services::Services,
+    };
+
+    use super::SurfaceBuilder;
+
+    #[test]
+    fn plane_offset_from() {
+        let services = Services::new();
+
+        let xy_plane = services.objects.surfaces.xy_plane();
+        let plane = PartialSurface::plane_offset_from(&xy_plane, -2.)
+            .build(&services.objects);
+
+        assert_eq!(
+            plane.geometry().point_from_surface_coords([1., 1.]),
+            Point::from([1., 1., -2.]),
+        );
+    }
+
+    #[test]
+    fn plane_at_angle_to_face() {
+        let mut services = Services::new();
+
+        let face = Face::partial()
+            .with_exterior_polygon_from_points(
+                services.objects.surfaces.xy_plane(),
+                [[0., 0.], [1., 0.], [1., 1.], [0., 1.]],
+            )
+            .build(&mut services.objects)
+            .insert(&mut services.objects);
+        let half_edge = face
+            .exterior()
+            .half_edges()
+            .find(|half_edge| {
+                half_edge.back().global_form().position()
+                    == Point::from([0., 0., 0.])
+            })
+            .unwrap();
+
+        let tests = [
+            (Scalar::ZERO, [0.5, 1., 0.]),
+            (Scalar::PI / 2., [0.5, 0., 1.]),
+            (Scalar::PI, [0.5, -1., 0.]),
+        ];
+        for (angle, expected) in tests {
+            let plane =
+                PartialSurface::plane_at_angle_to_face(&face, half_edge, angle)
+                    .build(&services.objects);
+
+            let point = plane.geometry().point_from_surface_coords([0.5, 1.]);
+            assert!(point.distance_to(&Point::from(expected)) < 1e-9.into());
         }
     }
 }
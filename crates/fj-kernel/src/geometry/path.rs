@@ -22,16 +22,34 @@
 //! [`Surface`]: crate::objects::Surface
 //! [#1021]: https://github.com/hannobraun/Fornjot/issues/1021
 
-use fj_math::{Circle, Line, Point, Scalar, Transform, Vector};
+use fj_math::{
+    Circle, Ellipse, Helix, Line, Nurbs, Point, Scalar, Transform, Vector,
+};
 
 /// A path through surface (2D) space
-#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash, Ord, PartialOrd)]
+#[derive(
+    Clone,
+    Debug,
+    Eq,
+    PartialEq,
+    Hash,
+    Ord,
+    PartialOrd,
+    serde::Serialize,
+    serde::Deserialize,
+)]
 pub enum SurfacePath {
     /// A circle
     Circle(Circle<2>),
 
+    /// An ellipse
+    Ellipse(Ellipse<2>),
+
     /// A line
     Line(Line<2>),
+
+    /// A NURBS curve
+    Nurbs(Nurbs<2>),
 }
 
 impl SurfacePath {
@@ -57,19 +75,63 @@ impl SurfacePath {
     ) -> Point<2> {
         match self {
             Self::Circle(circle) => circle.point_from_circle_coords(point),
+            Self::Ellipse(ellipse) => ellipse.point_from_ellipse_coords(point),
             Self::Line(line) => line.point_from_line_coords(point),
+            Self::Nurbs(nurbs) => nurbs.point_from_nurbs_coords(point),
+        }
+    }
+
+    /// Compute the derivative of the path at the given point
+    ///
+    /// The point is given in path coordinates. The returned vector is tangent
+    /// to the path, pointing in the direction of increasing path coordinates.
+    pub fn derivative_at(&self, point: impl Into<Point<1>>) -> Vector<2> {
+        let point = point.into();
+
+        // The derivative of `center + a * cos(t) + b * sin(t)` is
+        // `a * cos(t + PI / 2) + b * sin(t + PI / 2)`.
+        let quarter_turn = Vector::from([Scalar::PI / 2.]);
+
+        match self {
+            Self::Circle(circle) => {
+                circle.vector_from_circle_coords(point.coords + quarter_turn)
+            }
+            Self::Ellipse(ellipse) => {
+                ellipse.vector_from_ellipse_coords(point.coords + quarter_turn)
+            }
+            Self::Line(line) => line.direction(),
+            Self::Nurbs(nurbs) => nurbs.derivative_at(point),
         }
     }
 }
 
 /// A path through global (3D) space
-#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash, Ord, PartialOrd)]
+#[derive(
+    Clone,
+    Debug,
+    Eq,
+    PartialEq,
+    Hash,
+    Ord,
+    PartialOrd,
+    serde::Serialize,
+    serde::Deserialize,
+)]
 pub enum GlobalPath {
     /// A circle
     Circle(Circle<3>),
 
+    /// An ellipse
+    Ellipse(Ellipse<3>),
+
+    /// A helix
+    Helix(Helix),
+
     /// A line
     Line(Line<3>),
+
+    /// A NURBS curve
+    Nurbs(Nurbs<3>),
 }
 
 impl GlobalPath {
@@ -116,7 +178,13 @@ impl GlobalPath {
     pub fn origin(&self) -> Point<3> {
         match self {
             Self::Circle(circle) => circle.center() + circle.a(),
+            Self::Ellipse(ellipse) => ellipse.center() + ellipse.a(),
+            Self::Helix(helix) => helix.center() + helix.a(),
             Self::Line(line) => line.origin(),
+            Self::Nurbs(nurbs) => {
+                let [start, _] = nurbs.range();
+                nurbs.point_from_nurbs_coords([start])
+            }
         }
     }
 
@@ -127,7 +195,10 @@ impl GlobalPath {
     ) -> Point<3> {
         match self {
             Self::Circle(circle) => circle.point_from_circle_coords(point),
+            Self::Ellipse(ellipse) => ellipse.point_from_ellipse_coords(point),
+            Self::Helix(helix) => helix.point_from_helix_coords(point),
             Self::Line(line) => line.point_from_line_coords(point),
+            Self::Nurbs(nurbs) => nurbs.point_from_nurbs_coords(point),
         }
     }
 
@@ -138,7 +209,38 @@ impl GlobalPath {
     ) -> Vector<3> {
         match self {
             Self::Circle(circle) => circle.vector_from_circle_coords(vector),
+            Self::Ellipse(ellipse) => {
+                ellipse.vector_from_ellipse_coords(vector)
+            }
+            Self::Helix(helix) => {
+                helix.point_from_helix_coords([vector.into().t]) - self.origin()
+            }
             Self::Line(line) => line.vector_from_line_coords(vector),
+            Self::Nurbs(nurbs) => {
+                // Like for the other paths, this is the vector from the origin
+                // of the path to the point at the given coordinate.
+                nurbs.point_from_nurbs_coords([vector.into().t]) - self.origin()
+            }
+        }
+    }
+
+    /// Compute the derivative of the path at the given point
+    ///
+    /// See [`SurfacePath::derivative_at`].
+    pub fn derivative_at(&self, point: impl Into<Point<1>>) -> Vector<3> {
+        let point = point.into();
+        let quarter_turn = Vector::from([Scalar::PI / 2.]);
+
+        match self {
+            Self::Circle(circle) => {
+                circle.vector_from_circle_coords(point.coords + quarter_turn)
+            }
+            Self::Ellipse(ellipse) => {
+                ellipse.vector_from_ellipse_coords(point.coords + quarter_turn)
+            }
+            Self::Helix(helix) => helix.derivative_at(point),
+            Self::Line(line) => line.direction(),
+            Self::Nurbs(nurbs) => nurbs.derivative_at(point),
         }
     }
 
@@ -149,7 +251,16 @@ impl GlobalPath {
             Self::Circle(curve) => {
                 Self::Circle(transform.transform_circle(&curve))
             }
+            Self::Ellipse(curve) => {
+                Self::Ellipse(transform.transform_ellipse(&curve))
+            }
+            Self::Helix(curve) => {
+                Self::Helix(transform.transform_helix(&curve))
+            }
             Self::Line(curve) => Self::Line(transform.transform_line(&curve)),
+            Self::Nurbs(curve) => {
+                Self::Nurbs(transform.transform_nurbs(&curve))
+            }
         }
     }
 }
@@ -1,17 +1,57 @@
 //! The geometry that defines a surface
 
-use fj_math::{Line, Point, Transform, Vector};
+use fj_math::{Cone, Line, NurbsSurface, Point, Scalar, Transform, Vector};
 
 use super::path::GlobalPath;
 
 /// The geometry that defines a surface
-#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash, Ord, PartialOrd)]
-pub struct SurfaceGeometry {
-    /// The u-axis of the surface
-    pub u: GlobalPath,
+#[derive(
+    Clone,
+    Debug,
+    Eq,
+    PartialEq,
+    Hash,
+    Ord,
+    PartialOrd,
+    serde::Serialize,
+    serde::Deserialize,
+)]
+pub enum SurfaceGeometry {
+    /// A surface that is swept from a path along a vector
+    Swept {
+        /// The u-axis of the surface
+        u: GlobalPath,
 
-    /// The v-axis of the surface
-    pub v: Vector<3>,
+        /// The v-axis of the surface
+        v: Vector<3>,
+    },
+
+    /// A NURBS surface
+    Nurbs(NurbsSurface<3>),
+
+    /// A surface of straight lines between two paths
+    ///
+    /// For each u-coordinate, the surface consists of the line from the point
+    /// on `a` to the point on `b` at that coordinate. The v-coordinate goes
+    /// from 0 on `a` to 1 on `b`.
+    ///
+    /// Both paths are evaluated at the same coordinate, so their
+    /// parametrizations need to line up. Between two circles with the same
+    /// axis, for example, that's a cone. Between a circle and a NURBS curve
+    /// that goes around it, it depends on how fast each of them goes.
+    Ruled {
+        /// The path at `v = 0`
+        a: GlobalPath,
+
+        /// The path at `v = 1`
+        b: GlobalPath,
+    },
+
+    /// A cone
+    ///
+    /// The u-coordinate is the angle around the axis, the v-coordinate goes
+    /// from 0 at the apex to 1 on the cone's circle. See [`Cone`].
+    Cone(Cone),
 }
 
 impl SurfaceGeometry {
@@ -21,43 +61,216 @@ impl SurfaceGeometry {
         point: impl Into<Point<2>>,
     ) -> Point<3> {
         let point = point.into();
-        self.u.point_from_path_coords([point.u])
-            + self.path_to_line().vector_from_line_coords([point.v])
+
+        match self {
+            Self::Swept { u, v } => {
+                u.point_from_path_coords([point.u])
+                    + path_to_line(u, v).vector_from_line_coords([point.v])
+            }
+            Self::Nurbs(nurbs) => nurbs.point_from_surface_coords(point),
+            Self::Ruled { a, b } => {
+                let a = a.point_from_path_coords([point.u]);
+                let b = b.point_from_path_coords([point.u]);
+                a + (b - a) * point.v
+            }
+            Self::Cone(cone) => cone.point_from_surface_coords(point),
+        }
     }
 
     /// Convert a vector in surface coordinates to model coordinates
+    ///
+    /// NURBS surfaces, ruled surfaces, and cones don't map vectors linearly.
+    /// For those, the vector is interpreted as an offset from the start of the
+    /// NURBS surface's range, or from the origin of the surface coordinates,
+    /// respectively.
     pub fn vector_from_surface_coords(
         &self,
         vector: impl Into<Vector<2>>,
     ) -> Vector<3> {
         let vector = vector.into();
-        self.u.vector_from_path_coords([vector.u])
-            + self.path_to_line().vector_from_line_coords([vector.v])
+
+        match self {
+            Self::Swept { u, v } => {
+                u.vector_from_path_coords([vector.u])
+                    + path_to_line(u, v).vector_from_line_coords([vector.v])
+            }
+            Self::Nurbs(nurbs) => {
+                let [[u_start, _], [v_start, _]] = nurbs.range();
+                let origin = Point::from([u_start, v_start]);
+
+                nurbs.point_from_surface_coords(origin + vector)
+                    - nurbs.point_from_surface_coords(origin)
+            }
+            Self::Ruled { .. } | Self::Cone(_) => {
+                self.point_from_surface_coords(Point::origin() + vector)
+                    - self.point_from_surface_coords(Point::origin())
+            }
+        }
+    }
+
+    /// Compute the normal of the surface at the given point
+    ///
+    /// The point is given in surface coordinates. The returned normal is
+    /// normalized, and points in the direction of the cross product of the
+    /// surface's derivatives in u- and v-direction.
+    pub fn normal_at(&self, point: impl Into<Point<2>>) -> Vector<3> {
+        let point = point.into();
+
+        match self {
+            Self::Swept { u, v } => {
+                u.derivative_at([point.u]).cross(v).normalize()
+            }
+            Self::Nurbs(nurbs) => nurbs.normal_at(point),
+            Self::Cone(cone) => cone.normal_at(point),
+            Self::Ruled { .. } => {
+                let [du, dv] = self.derivatives_at(point);
+                du.cross(&dv).normalize()
+            }
+        }
+    }
+
+    /// Compute the curvature of the surface at the given point
+    ///
+    /// The point is given in surface coordinates. See [`Curvature`], for how
+    /// the values are defined.
+    pub fn curvature_at(&self, point: impl Into<Point<2>>) -> Curvature {
+        let point = point.into();
+
+        // The second derivatives are computed by differentiating the first
+        // derivatives numerically. NURBS are clamped to their range, so points
+        // near its ends are moved inward, for the differences to be
+        // meaningful.
+        let h = Scalar::from(1e-5);
+        let point = match self {
+            Self::Swept {
+                u: GlobalPath::Nurbs(nurbs),
+                ..
+            } => Point::from([inset(point.u, nurbs.range(), h), point.v]),
+            Self::Swept { .. } => point,
+            Self::Ruled {
+                a: GlobalPath::Nurbs(nurbs),
+                ..
+            }
+            | Self::Ruled {
+                b: GlobalPath::Nurbs(nurbs),
+                ..
+            } => Point::from([inset(point.u, nurbs.range(), h), point.v]),
+            Self::Ruled { .. } | Self::Cone(_) => point,
+            Self::Nurbs(nurbs) => {
+                let [u, v] = nurbs.range();
+                Point::from([inset(point.u, u, h), inset(point.v, v, h)])
+            }
+        };
+
+        let [du, dv] = self.derivatives_at(point);
+        let second_derivatives = |direction: Vector<2>| {
+            let [du_a, dv_a] = self.derivatives_at(point + direction * h);
+            let [du_b, dv_b] = self.derivatives_at(point - direction * h);
+            [(du_a - du_b) / (h * 2.), (dv_a - dv_b) / (h * 2.)]
+        };
+        let [duu, duv] = second_derivatives(Vector::from([1., 0.]));
+        let [_, dvv] = second_derivatives(Vector::from([0., 1.]));
+
+        let normal = du.cross(&dv);
+        if normal.magnitude() == Scalar::ZERO {
+            // The surface is degenerate at this point.
+            return Curvature::default();
+        }
+        let normal = normal.normalize();
+
+        // Coefficients of the first and second fundamental forms
+        let [e, f, g] = [du.dot(&du), du.dot(&dv), dv.dot(&dv)];
+        let [l, m, n] = [duu, duv, dvv].map(|d| d.dot(&normal));
+        let det = e * g - f * f;
+
+        Curvature {
+            gaussian: (l * n - m * m) / det,
+            mean: -(e * n - f * m * 2. + g * l) / (det * 2.),
+        }
     }
 
-    fn path_to_line(&self) -> Line<3> {
-        Line::from_origin_and_direction(self.u.origin(), self.v)
+    /// Compute the derivatives in u- and v-direction at the given point
+    pub(crate) fn derivatives_at(&self, point: Point<2>) -> [Vector<3>; 2] {
+        match self {
+            Self::Swept { u, v } => [u.derivative_at([point.u]), *v],
+            Self::Nurbs(nurbs) => nurbs.derivatives_at(point),
+            Self::Ruled { a, b } => {
+                let [da, db] = [a, b].map(|path| path.derivative_at([point.u]));
+                let [a, b] =
+                    [a, b].map(|path| path.point_from_path_coords([point.u]));
+
+                [da + (db - da) * point.v, b - a]
+            }
+            Self::Cone(cone) => cone.derivatives_at(point),
+        }
     }
 
     /// Transform the surface geometry
     #[must_use]
     pub fn transform(self, transform: &Transform) -> Self {
-        let u = self.u.transform(transform);
-        let v = transform.transform_vector(&self.v);
-        Self { u, v }
+        match self {
+            Self::Swept { u, v } => {
+                let u = u.transform(transform);
+                let v = transform.transform_vector(&v);
+                Self::Swept { u, v }
+            }
+            Self::Nurbs(nurbs) => {
+                Self::Nurbs(transform.transform_nurbs_surface(&nurbs))
+            }
+            Self::Ruled { a, b } => Self::Ruled {
+                a: a.transform(transform),
+                b: b.transform(transform),
+            },
+            Self::Cone(cone) => Self::Cone(transform.transform_cone(&cone)),
+        }
     }
 }
 
+/// The curvature of a surface at a point
+///
+/// Returned by [`SurfaceGeometry::curvature_at`].
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, Hash, Ord, PartialOrd)]
+pub struct Curvature {
+    /// The Gaussian curvature, the product of the principal curvatures
+    ///
+    /// It is positive where the surface is curved like a sphere, negative
+    /// where it is curved like a saddle, and zero where it is straight in at
+    /// least one direction, like a plane or a cylinder.
+    pub gaussian: Scalar,
+
+    /// The mean curvature, the average of the principal curvatures
+    ///
+    /// It is positive where the surface bends away from its normal, like on
+    /// the outside of a cylinder, and negative where it bends toward it.
+    pub mean: Scalar,
+}
+
+fn path_to_line(u: &GlobalPath, v: &Vector<3>) -> Line<3> {
+    Line::from_origin_and_direction(u.origin(), *v)
+}
+
+/// Move a coordinate inward, so it's at least `margin` away from both ends of
+/// a range
+fn inset(value: Scalar, [start, end]: [Scalar; 2], margin: Scalar) -> Scalar {
+    if end - start <= margin * 2. {
+        return value;
+    }
+
+    Ord::min(value.max(start + margin), end - margin)
+}
+
 #[cfg(test)]
 mod tests {
-    use fj_math::{Line, Point, Vector};
+    use fj_math::{
+        Circle, Cone, Line, NurbsSurface, Point, Scalar, Transform, Vector,
+    };
     use pretty_assertions::assert_eq;
 
     use crate::geometry::{path::GlobalPath, surface::SurfaceGeometry};
 
     #[test]
     fn point_from_surface_coords() {
-        let surface = SurfaceGeometry {
+        let surface = SurfaceGeometry::Swept {
             u: GlobalPath::Line(Line::from_origin_and_direction(
                 Point::from([1., 1., 1.]),
                 Vector::from([0., 2., 0.]),
@@ -73,7 +286,7 @@ mod tests {
 
     #[test]
     fn vector_from_surface_coords() {
-        let surface = SurfaceGeometry {
+        let surface = SurfaceGeometry::Swept {
             u: GlobalPath::Line(Line::from_origin_and_direction(
                 Point::from([1., 0., 0.]),
                 Vector::from([0., 2., 0.]),
@@ -86,4 +299,104 @@ mod tests {
             Vector::from([0., 4., 8.]),
         );
     }
+
+    #[test]
+    fn normal_at() {
+        let plane = SurfaceGeometry::Swept {
+            u: GlobalPath::x_axis(),
+            v: Vector::from([0., 2., 0.]),
+        };
+        assert_eq!(plane.normal_at([1., 1.]), Vector::unit_z());
+
+        let nurbs = SurfaceGeometry::Nurbs(NurbsSurface::from_control_points(
+            [1, 1],
+            [[[0., 0., 0.], [0., 0., 1.]], [[1., 0., 0.], [1., 0., 1.]]],
+        ));
+        assert_eq!(nurbs.normal_at([0.5, 0.5]), -Vector::unit_y());
+    }
+
+    #[test]
+    fn curvature_at() {
+        let plane = SurfaceGeometry::Swept {
+            u: GlobalPath::x_axis(),
+            v: Vector::from([0., 2., 0.]),
+        };
+        let curvature = plane.curvature_at([1., 1.]);
+        assert_eq!(curvature.gaussian, Scalar::ZERO);
+        assert_eq!(curvature.mean, Scalar::ZERO);
+
+        let cylinder = SurfaceGeometry::Swept {
+            u: GlobalPath::Circle(Circle::from_center_and_radius(
+                Point::origin(),
+                2.,
+            )),
+            v: Vector::from([0., 0., 3.]),
+        };
+        let curvature = cylinder.curvature_at([1., 1.]);
+        assert_eq!(curvature.gaussian, Scalar::ZERO);
+        assert!((curvature.mean - 0.25).abs() < Scalar::from(1e-6));
+
+        let nurbs = SurfaceGeometry::Nurbs(NurbsSurface::from_control_points(
+            [1, 1],
+            [[[0., 0., 0.], [0., 0., 1.]], [[1., 0., 0.], [1., 0., 1.]]],
+        ));
+        let curvature = nurbs.curvature_at([0., 0.5]);
+        assert!(curvature.gaussian.abs() < Scalar::from(1e-6));
+        assert!(curvature.mean.abs() < Scalar::from(1e-6));
+    }
+
+    #[test]
+    fn ruled() {
+        // A cone, narrowing from a radius of two to one over a height of one
+        let surface = SurfaceGeometry::Ruled {
+            a: GlobalPath::Circle(Circle::from_center_and_radius(
+                Point::origin(),
+                2.,
+            )),
+            b: GlobalPath::Circle(Circle::from_center_and_radius(
+                [0., 0., 1.],
+                1.,
+            )),
+        };
+
+        assert_eq!(
+            surface.point_from_surface_coords([0., 0.5]),
+            Point::from([1.5, 0., 0.5]),
+        );
+
+        let normal = surface.normal_at([0., 0.5]);
+        let expected = Vector::from([1., 0., 1.]).normalize();
+        assert!((normal - expected).magnitude() < Scalar::from(1e-9));
+
+        let surface = surface.transform(&Transform::translation([0., 0., 1.]));
+        assert_eq!(
+            surface.point_from_surface_coords([0., 1.]),
+            Point::from([1., 0., 2.]),
+        );
+    }
+
+    #[test]
+    fn cone() {
+        let surface = SurfaceGeometry::Cone(Cone::new(
+            [0., 0., 2.],
+            [1., 0., 0.],
+            [0., -1., 0.],
+            [0., 0., -2.],
+        ));
+
+        assert_eq!(
+            surface.point_from_surface_coords([0., 0.5]),
+            Point::from([0.5, 0., 1.]),
+        );
+
+        let normal = surface.normal_at([0., 0.5]);
+        let expected = Vector::from([2., 0., 1.]).normalize();
+        assert!((normal - expected).magnitude() < Scalar::from(1e-9));
+
+        let surface = surface.transform(&Transform::translation([0., 0., 1.]));
+        assert_eq!(
+            surface.point_from_surface_coords([0., 0.]),
+            Point::from([0., 0., 3.]),
+        );
+    }
 }
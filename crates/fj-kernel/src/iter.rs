@@ -2,12 +2,15 @@
 
 use std::collections::VecDeque;
 
+use fj_math::{Point, Scalar};
+
 use crate::{
     objects::{
         Curve, Cycle, Face, GlobalCurve, GlobalVertex, HalfEdge, Shell, Sketch,
         Solid, Surface, Vertex,
     },
     storage::Handle,
+    tolerance::ModelTolerance,
 };
 
 /// Access iterators over all objects of a shape, or part of it
@@ -73,6 +76,61 @@ pub trait ObjectIters<'r> {
         iter
     }
 
+    /// Find the global vertex that is nearest to the given point
+    ///
+    /// Returns `None`, if there are no global vertices.
+    fn nearest_global_vertex(
+        &'r self,
+        point: impl Into<Point<3>>,
+    ) -> Option<&'r GlobalVertex>
+    where
+        Self: Sized,
+    {
+        let point = point.into();
+
+        self.global_vertex_iter()
+            .min_by_key(|vertex| (vertex.position() - point).magnitude())
+    }
+
+    /// Iterate over all global vertices within a radius around a point
+    fn global_vertices_within_radius(
+        &'r self,
+        point: impl Into<Point<3>>,
+        radius: impl Into<Scalar>,
+    ) -> Iter<&'r GlobalVertex>
+    where
+        Self: Sized,
+    {
+        let point = point.into();
+        let radius = radius.into();
+
+        Iter(
+            self.global_vertex_iter()
+                .filter(|vertex| {
+                    (vertex.position() - point).magnitude() <= radius
+                })
+                .collect(),
+        )
+    }
+
+    /// Iterate over all global vertices that are located at the given point
+    fn global_vertices_at(
+        &'r self,
+        point: impl Into<Point<3>>,
+        tolerance: ModelTolerance,
+    ) -> Iter<&'r GlobalVertex>
+    where
+        Self: Sized,
+    {
+        let point = point.into();
+
+        Iter(
+            self.global_vertex_iter()
+                .filter(|vertex| vertex.is_at(point, tolerance))
+                .collect(),
+        )
+    }
+
     /// Iterate over all half-edges
     fn half_edge_iter(&'r self) -> Iter<&'r Handle<HalfEdge>> {
         let mut iter = Iter::empty();
@@ -359,6 +417,8 @@ impl<T> Iterator for Iter<T> {
 
 #[cfg(test)]
 mod tests {
+    use fj_math::Point;
+
     use crate::{
         builder::{CurveBuilder, CycleBuilder, FaceBuilder, HalfEdgeBuilder},
         insert::Insert,
@@ -368,6 +428,7 @@ mod tests {
         },
         partial::{HasPartial, PartialCurve},
         services::Services,
+        tolerance::ModelTolerance,
     };
 
     use super::ObjectIters as _;
@@ -587,6 +648,47 @@ mod tests {
         assert_eq!(48, object.vertex_iter().count());
     }
 
+    #[test]
+    fn spatial_queries() {
+        let mut services = Services::new();
+
+        let object = Solid::builder()
+            .with_cube_from_edge_length(1., &mut services.objects)
+            .build(&mut services.objects);
+
+        let nearest = object
+            .nearest_global_vertex([1., 1., 1.])
+            .map(|vertex| vertex.position());
+        assert_eq!(nearest, Some(Point::from([0.5, 0.5, 0.5])));
+
+        assert_eq!(
+            4,
+            object
+                .global_vertices_within_radius([0., 0., 0.5], 0.75)
+                .count()
+        );
+        assert_eq!(
+            8,
+            object
+                .global_vertices_within_radius([0., 0., 0.], 1.)
+                .count()
+        );
+
+        let tolerance = ModelTolerance::default();
+        assert_eq!(
+            1,
+            object
+                .global_vertices_at([0.5, 0.5, 0.5 + 1e-9], tolerance)
+                .count()
+        );
+        assert_eq!(
+            0,
+            object
+                .global_vertices_at([0.5, 0.5, 0.6], tolerance)
+                .count()
+        );
+    }
+
     #[test]
     fn surface() {
         let objects = Objects::new();
@@ -100,6 +100,9 @@ pub mod insert;
 pub mod iter;
 pub mod objects;
 pub mod partial;
+pub mod pretty;
+pub mod serialize;
 pub mod services;
 pub mod storage;
+pub mod tolerance;
 pub mod validate;
@@ -29,7 +29,7 @@ impl Curve {
 
     /// Access the path that defines the curve
     pub fn path(&self) -> SurfacePath {
-        self.path
+        self.path.clone()
     }
 
     /// Access the surface that the curve is defined in
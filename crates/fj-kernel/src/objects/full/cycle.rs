@@ -1,7 +1,6 @@
 use std::slice;
 
-use fj_interop::ext::SliceExt;
-use fj_math::{Scalar, Winding};
+use fj_math::{signed_area, Scalar, Winding};
 
 use crate::{
     geometry::path::SurfacePath,
@@ -58,9 +57,9 @@ impl Cycle {
     /// two possible windings, depending on the direction you look at the
     /// surface that the cycle is defined on from.
     pub fn winding(&self) -> Winding {
-        // The cycle could be made up of one or two circles. If that is the
-        // case, the winding of the cycle is determined by the winding of the
-        // first circle.
+        // The cycle could be made up of one or two curved half-edges. If the
+        // first of them is a circle or an ellipse, the winding of the cycle is
+        // determined by its winding.
         if self.half_edges.len() < 3 {
             let first = self
                 .half_edges()
@@ -70,13 +69,16 @@ impl Cycle {
             let [a, b] = first.vertices();
             let edge_direction_positive = a.position() < b.position();
 
-            let circle = match first.curve().path() {
-                SurfacePath::Circle(circle) => circle,
-                SurfacePath::Line(_) => unreachable!(
-                    "Invalid cycle: less than 3 edges, but not all are circles"
-                ),
+            let [axis_a, axis_b] = match first.curve().path() {
+                SurfacePath::Circle(circle) => [circle.a(), circle.b()],
+                SurfacePath::Ellipse(ellipse) => [ellipse.a(), ellipse.b()],
+                SurfacePath::Line(_) | SurfacePath::Nurbs(_) => {
+                    // The winding can't be read from the parameters of the
+                    // first half-edge. Sample the whole cycle instead.
+                    return self.winding_of_samples();
+                }
             };
-            let cross_positive = circle.a().cross2d(&circle.b()) > Scalar::ZERO;
+            let cross_positive = axis_a.cross2d(&axis_b) > Scalar::ZERO;
 
             if edge_direction_positive == cross_positive {
                 return Winding::Ccw;
@@ -91,7 +93,10 @@ impl Cycle {
 
         let mut sum = Scalar::ZERO;
 
-        for [a, b] in self.half_edges.as_slice().array_windows_ext() {
+        // The polygon is closed, so the last half-edge connects back to the
+        // first one.
+        let half_edges = self.half_edges.iter().chain(self.half_edges.first());
+        for (a, b) in self.half_edges.iter().zip(half_edges.skip(1)) {
             let [a, b] = [a, b].map(|half_edge| {
                 let [vertex, _] = half_edge.vertices();
                 vertex.surface_form().position()
@@ -109,6 +114,23 @@ impl Cycle {
 
         unreachable!("Encountered invalid cycle: {self:#?}");
     }
+
+    fn winding_of_samples(&self) -> Winding {
+        const SAMPLES_PER_HALF_EDGE: usize = 16;
+
+        let points = self
+            .half_edges()
+            .flat_map(|half_edge| {
+                half_edge.surface_samples(SAMPLES_PER_HALF_EDGE)
+            })
+            .collect::<Vec<_>>();
+
+        if signed_area(&points) < Scalar::ZERO {
+            Winding::Cw
+        } else {
+            Winding::Ccw
+        }
+    }
 }
 
 /// An iterator over the half-edges of a [`Cycle`]
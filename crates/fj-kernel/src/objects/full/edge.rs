@@ -1,5 +1,9 @@
 use std::fmt;
 
+use fj_math::Point;
+
+use fj_interop::ext::ArrayExt;
+
 use crate::{
     get::Get,
     objects::{Curve, GlobalCurve, GlobalVertex, Surface, Vertex},
@@ -57,6 +61,27 @@ impl HalfEdge {
     pub fn global_form(&self) -> &Handle<GlobalEdge> {
         &self.global_form
     }
+
+    /// Sample points along the half-edge, in surface coordinates
+    ///
+    /// The samples are evenly spaced in curve coordinates. They start at the
+    /// back vertex and stop short of the front vertex, so the samples of the
+    /// half-edges of a cycle don't repeat any points.
+    pub fn surface_samples(
+        &self,
+        num_samples: usize,
+    ) -> impl Iterator<Item = Point<2>> + '_ {
+        let path = self.curve().path();
+        let [back, front] = self
+            .vertices()
+            .each_ref_ext()
+            .map(|vertex| vertex.position().t);
+
+        (0..num_samples).map(move |i| {
+            let f = i as f64 / num_samples as f64;
+            path.point_from_path_coords([back + (front - back) * f])
+        })
+    }
 }
 
 impl Get<GlobalEdge> for HalfEdge {
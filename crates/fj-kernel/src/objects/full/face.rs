@@ -1,6 +1,6 @@
 use std::collections::{btree_set, BTreeSet};
 
-use fj_interop::mesh::Color;
+use fj_interop::mesh::{Attributes, Color};
 use fj_math::Winding;
 
 use crate::{
@@ -37,6 +37,7 @@ pub struct Face {
     exterior: Handle<Cycle>,
     interiors: Vec<Handle<Cycle>>,
     color: Color,
+    attributes: Attributes,
 }
 
 impl Face {
@@ -52,9 +53,19 @@ impl Face {
             exterior,
             interiors,
             color,
+            attributes: Attributes::default(),
         }
     }
 
+    /// Attach the provided attributes to the face
+    ///
+    /// Faces that are derived from this one, by any of the algorithms in this
+    /// crate, have the same attributes.
+    pub fn with_attributes(mut self, attributes: Attributes) -> Self {
+        self.attributes = attributes;
+        self
+    }
+
     /// Access the surface of the face
     pub fn surface(&self) -> &Handle<Surface> {
         self.exterior().surface()
@@ -82,6 +93,11 @@ impl Face {
         self.color
     }
 
+    /// Access the attributes of the face
+    pub fn attributes(&self) -> &Attributes {
+        &self.attributes
+    }
+
     /// Determine handed-ness of the face's front-side coordinate system
     ///
     /// A face is defined on a surface, which has a coordinate system. Since
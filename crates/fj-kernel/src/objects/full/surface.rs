@@ -1,7 +1,7 @@
 use crate::geometry::surface::SurfaceGeometry;
 
 /// A two-dimensional shape
-#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash, Ord, PartialOrd)]
+#[derive(Clone, Debug, Eq, PartialEq, Hash, Ord, PartialOrd)]
 pub struct Surface {
     geometry: SurfaceGeometry,
 }
@@ -14,6 +14,6 @@ impl Surface {
 
     /// Access the surface's geometry
     pub fn geometry(&self) -> SurfaceGeometry {
-        self.geometry
+        self.geometry.clone()
     }
 }
@@ -4,6 +4,7 @@ use crate::{
     get::Get,
     objects::{Curve, GlobalCurve, Surface},
     storage::Handle,
+    tolerance::ModelTolerance,
 };
 
 /// A vertex
@@ -170,4 +171,13 @@ impl GlobalVertex {
     pub fn position(&self) -> Point<3> {
         self.position
     }
+
+    /// Determine whether the vertex is located at the given point
+    pub fn is_at(
+        &self,
+        point: impl Into<Point<3>>,
+        tolerance: ModelTolerance,
+    ) -> bool {
+        tolerance.coincide(self.position, point.into())
+    }
 }
@@ -162,7 +162,7 @@ impl MaybePartial<Curve> {
     pub fn path(&self) -> Option<SurfacePath> {
         match self {
             MaybePartial::Full(full) => Some(full.path()),
-            MaybePartial::Partial(partial) => partial.path,
+            MaybePartial::Partial(partial) => partial.path.clone(),
         }
     }
 
@@ -257,7 +257,7 @@ impl MaybePartial<Surface> {
     pub fn geometry(&self) -> Option<SurfaceGeometry> {
         match self {
             Self::Full(full) => Some(full.geometry()),
-            Self::Partial(partial) => partial.geometry,
+            Self::Partial(partial) => partial.geometry.clone(),
         }
     }
 }
@@ -289,6 +289,14 @@ impl MaybePartial<SurfaceVertex> {
 }
 
 impl MaybePartial<Vertex> {
+    /// Access the position
+    pub fn position(&self) -> Option<Point<1>> {
+        match self {
+            Self::Full(full) => Some(full.position()),
+            Self::Partial(partial) => partial.position,
+        }
+    }
+
     /// Access the curve
     pub fn curve(&self) -> MaybePartial<Curve> {
         match self {
@@ -1,4 +1,4 @@
-use fj_interop::mesh::Color;
+use fj_interop::mesh::{Attributes, Color};
 
 use crate::{
     objects::{Cycle, Face, Objects, Surface},
@@ -15,6 +15,7 @@ pub struct PartialFace {
     exterior: MaybePartial<Cycle>,
     interiors: Vec<MaybePartial<Cycle>>,
     color: Option<Color>,
+    attributes: Option<Attributes>,
 }
 
 impl PartialFace {
@@ -38,6 +39,11 @@ impl PartialFace {
         self.color
     }
 
+    /// Access the attributes of the [`Face`]
+    pub fn attributes(&self) -> Option<&Attributes> {
+        self.attributes.as_ref()
+    }
+
     /// Build the [`Face`] with the provided exterior
     pub fn with_exterior(
         mut self,
@@ -63,6 +69,12 @@ impl PartialFace {
         self
     }
 
+    /// Build the [`Face`] with the provided attributes
+    pub fn with_attributes(mut self, attributes: Attributes) -> Self {
+        self.attributes = Some(attributes);
+        self
+    }
+
     /// Construct a polygon from a list of points
     pub fn build(self, objects: &mut Service<Objects>) -> Face {
         let exterior = self.exterior.into_full(objects);
@@ -72,8 +84,9 @@ impl PartialFace {
             .map(|cycle| cycle.into_full(objects))
             .collect::<Vec<_>>();
         let color = self.color.unwrap_or_default();
+        let attributes = self.attributes.unwrap_or_default();
 
-        Face::new(exterior, interiors, color)
+        Face::new(exterior, interiors, color).with_attributes(attributes)
     }
 }
 
@@ -87,6 +100,7 @@ impl MergeWith for PartialFace {
                 .merge_with(Mergeable(other.interiors))
                 .0,
             color: self.color.merge_with(other.color),
+            attributes: self.attributes.merge_with(other.attributes),
         }
     }
 }
@@ -97,6 +111,7 @@ impl From<&Face> for PartialFace {
             exterior: face.exterior().clone().into(),
             interiors: face.interiors().cloned().map(Into::into).collect(),
             color: Some(face.color()),
+            attributes: Some(face.attributes().clone()),
         }
     }
 }
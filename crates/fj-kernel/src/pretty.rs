@@ -0,0 +1,491 @@
+//! Human-readable formatting of objects
+//!
+//! The [`fmt::Debug`] output of objects includes everything they reference,
+//! with all the details of every object along the way. For anything but the
+//! most trivial objects, that is too much to make sense of.
+//!
+//! [`PrettyPrint`] provides a more compact format instead: One line per object,
+//! indented to show the structure of the topology, with positions rounded to a
+//! reasonable precision. It is meant for diagnostics, like validation errors or
+//! the output of failing tests.
+//!
+//! ``` text
+//! Solid
+//!   Shell @ 0x1a0 (6 faces)
+//!     Face @ 0x2c8 (color #ff0000ff) on plane through [0, 0, 0], u [1, 0, 0], v [0, 1, 0]
+//!       exterior: Cycle @ 0x2c0 (counter-clockwise)
+//!         HalfEdge @ 0x240: line [0, 0, 0] -> [1, 0, 0]
+//!         ...
+//! ```
+
+use std::fmt::{self, Write as _};
+
+use fj_math::{Point, Scalar, Vector};
+
+use crate::{
+    geometry::{
+        path::{GlobalPath, SurfacePath},
+        surface::SurfaceGeometry,
+    },
+    objects::{
+        Curve, Cycle, Face, FaceSet, GlobalVertex, HalfEdge, Shell, Sketch,
+        Solid, Surface, SurfaceVertex, Vertex,
+    },
+    storage::Handle,
+};
+
+/// Format an object in a human-readable way
+///
+/// See [module documentation] for more information.
+///
+/// [module documentation]: self
+pub trait PrettyPrint {
+    /// Write the object to the printer
+    fn fmt_pretty(&self, printer: &mut Printer) -> fmt::Result;
+
+    /// Provide a wrapper that formats the object using [`fmt::Display`]
+    fn pretty(&self) -> Pretty<'_, Self> {
+        Pretty(self)
+    }
+}
+
+/// Formats the wrapped object using [`PrettyPrint`]
+///
+/// Returned by [`PrettyPrint::pretty`].
+pub struct Pretty<'r, T: ?Sized>(&'r T);
+
+impl<T> fmt::Display for Pretty<'_, T>
+where
+    T: PrettyPrint + ?Sized,
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let mut printer = Printer {
+            f,
+            indentation: 0,
+            at_line_start: true,
+            label: None,
+            id: None,
+        };
+        self.0.fmt_pretty(&mut printer)
+    }
+}
+
+/// Writes objects line by line, indented according to their nesting
+///
+/// Used by implementations of [`PrettyPrint`].
+pub struct Printer<'a, 'b> {
+    f: &'a mut fmt::Formatter<'b>,
+    indentation: usize,
+    at_line_start: bool,
+    label: Option<&'static str>,
+    id: Option<u64>,
+}
+
+impl Printer<'_, '_> {
+    /// Write the line that describes an object
+    ///
+    /// The line starts with the name of the object, followed by its id, if it
+    /// is printed through a [`Handle`], and the provided description.
+    pub fn object(
+        &mut self,
+        name: &str,
+        description: fmt::Arguments,
+    ) -> fmt::Result {
+        if !self.at_line_start {
+            writeln!(self.f)?;
+        }
+        self.at_line_start = false;
+
+        write!(self.f, "{:1$}", "", self.indentation * 2)?;
+        if let Some(label) = self.label.take() {
+            write!(self.f, "{label}: ")?;
+        }
+        write!(self.f, "{name}")?;
+        if let Some(id) = self.id.take() {
+            write!(self.f, " @ {id:#x}")?;
+        }
+
+        let mut description = description.to_string();
+        if !description.is_empty() {
+            if !description.starts_with(':') {
+                description.insert(0, ' ');
+            }
+            self.f.write_str(&description)?;
+        }
+
+        Ok(())
+    }
+
+    /// Write a nested object, indented one level deeper
+    pub fn child(&mut self, object: &impl PrettyPrint) -> fmt::Result {
+        self.indentation += 1;
+        let result = object.fmt_pretty(self);
+        self.indentation -= 1;
+
+        result
+    }
+
+    /// Write a nested object with a label, that describes its role
+    pub fn labeled_child(
+        &mut self,
+        label: &'static str,
+        object: &impl PrettyPrint,
+    ) -> fmt::Result {
+        self.label = Some(label);
+        self.child(object)
+    }
+}
+
+impl<T> PrettyPrint for Handle<T>
+where
+    T: PrettyPrint,
+{
+    fn fmt_pretty(&self, printer: &mut Printer) -> fmt::Result {
+        printer.id = Some(self.id().0);
+        (**self).fmt_pretty(printer)
+    }
+}
+
+/// Writes each object on its own line, starting with a new one
+impl<T, const N: usize> PrettyPrint for [T; N]
+where
+    T: PrettyPrint,
+{
+    fn fmt_pretty(&self, printer: &mut Printer) -> fmt::Result {
+        printer.at_line_start = false;
+        for object in self {
+            printer.child(object)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl PrettyPrint for Solid {
+    fn fmt_pretty(&self, printer: &mut Printer) -> fmt::Result {
+        printer.object("Solid", format_args!(""))?;
+        for shell in self.shells() {
+            printer.child(shell)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl PrettyPrint for Shell {
+    fn fmt_pretty(&self, printer: &mut Printer) -> fmt::Result {
+        let num_faces = self.faces().into_iter().count();
+        printer.object("Shell", format_args!("({num_faces} faces)"))?;
+        self.faces().fmt_pretty(printer)
+    }
+}
+
+impl PrettyPrint for Sketch {
+    fn fmt_pretty(&self, printer: &mut Printer) -> fmt::Result {
+        let num_faces = self.faces().into_iter().count();
+        printer.object("Sketch", format_args!("({num_faces} faces)"))?;
+        self.faces().fmt_pretty(printer)
+    }
+}
+
+/// Writes the faces as children of the preceding object
+impl PrettyPrint for FaceSet {
+    fn fmt_pretty(&self, printer: &mut Printer) -> fmt::Result {
+        for face in self {
+            printer.child(face)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl PrettyPrint for Face {
+    fn fmt_pretty(&self, printer: &mut Printer) -> fmt::Result {
+        let [r, g, b, a] = self.color().0;
+        let name = self
+            .attributes()
+            .name
+            .as_ref()
+            .map(|name| format!("{name:?} "))
+            .unwrap_or_default();
+        printer.object(
+            "Face",
+            format_args!(
+                "{name}(color #{r:02x}{g:02x}{b:02x}{a:02x}) on {}",
+                SurfaceDescription(self.surface())
+            ),
+        )?;
+
+        printer.labeled_child("exterior", self.exterior())?;
+        for interior in self.interiors() {
+            printer.labeled_child("interior", interior)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl PrettyPrint for Cycle {
+    fn fmt_pretty(&self, printer: &mut Printer) -> fmt::Result {
+        let winding = match self.winding() {
+            fj_math::Winding::Ccw => "counter-clockwise",
+            fj_math::Winding::Cw => "clockwise",
+        };
+        printer.object("Cycle", format_args!("({winding})"))?;
+
+        for half_edge in self.half_edges() {
+            printer.child(half_edge)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl PrettyPrint for HalfEdge {
+    fn fmt_pretty(&self, printer: &mut Printer) -> fmt::Result {
+        let kind = path_kind(&self.curve().path());
+        let [back, front] = self
+            .vertices()
+            .each_ref()
+            .map(|vertex| Rounded(vertex.global_form().position()));
+
+        printer.object("HalfEdge", format_args!(": {kind} {back} -> {front}"))
+    }
+}
+
+impl PrettyPrint for Curve {
+    fn fmt_pretty(&self, printer: &mut Printer) -> fmt::Result {
+        printer.object(
+            "Curve",
+            format_args!(
+                ": {} on {}",
+                path_kind(&self.path()),
+                SurfaceDescription(self.surface())
+            ),
+        )
+    }
+}
+
+impl PrettyPrint for Vertex {
+    fn fmt_pretty(&self, printer: &mut Printer) -> fmt::Result {
+        printer.object(
+            "Vertex",
+            format_args!(
+                "at {} on curve, {} on surface, {} globally",
+                Rounded(self.position()),
+                Rounded(self.surface_form().position()),
+                Rounded(self.global_form().position()),
+            ),
+        )
+    }
+}
+
+impl PrettyPrint for SurfaceVertex {
+    fn fmt_pretty(&self, printer: &mut Printer) -> fmt::Result {
+        printer.object(
+            "SurfaceVertex",
+            format_args!(
+                "at {} on {}, {} globally",
+                Rounded(self.position()),
+                SurfaceDescription(self.surface()),
+                Rounded(self.global_form().position()),
+            ),
+        )
+    }
+}
+
+impl PrettyPrint for GlobalVertex {
+    fn fmt_pretty(&self, printer: &mut Printer) -> fmt::Result {
+        printer.object(
+            "GlobalVertex",
+            format_args!("at {}", Rounded(self.position())),
+        )
+    }
+}
+
+impl PrettyPrint for Surface {
+    fn fmt_pretty(&self, printer: &mut Printer) -> fmt::Result {
+        printer
+            .object("Surface", format_args!(": {}", SurfaceDescription(self)))
+    }
+}
+
+fn path_kind(path: &SurfacePath) -> &'static str {
+    match path {
+        SurfacePath::Circle(_) => "circle",
+        SurfacePath::Ellipse(_) => "ellipse",
+        SurfacePath::Line(_) => "line",
+        SurfacePath::Nurbs(_) => "NURBS curve",
+    }
+}
+
+/// Describes the geometry of a surface in a few words
+struct SurfaceDescription<'r>(&'r Surface);
+
+impl fmt::Display for SurfaceDescription<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.0.geometry() {
+            SurfaceGeometry::Swept {
+                u: GlobalPath::Line(line),
+                v,
+            } => write!(
+                f,
+                "plane through {}, u {}, v {}",
+                Rounded(line.origin()),
+                Rounded(line.direction()),
+                Rounded(v)
+            ),
+            SurfaceGeometry::Swept {
+                u: GlobalPath::Circle(circle),
+                v,
+            } => write!(
+                f,
+                "cylinder around {}, radius {}, axis {}",
+                Rounded(circle.center()),
+                Rounded(circle.radius()),
+                Rounded(v)
+            ),
+            SurfaceGeometry::Swept { u, v } => {
+                let kind = match u {
+                    GlobalPath::Circle(_) | GlobalPath::Line(_) => {
+                        unreachable!("Handled above")
+                    }
+                    GlobalPath::Ellipse(_) => "ellipse",
+                    GlobalPath::Helix(_) => "helix",
+                    GlobalPath::Nurbs(_) => "NURBS curve",
+                };
+                write!(f, "{kind}, swept along {}", Rounded(v))
+            }
+            SurfaceGeometry::Nurbs(_) => write!(f, "NURBS surface"),
+            SurfaceGeometry::Ruled { a, b } => {
+                let [a, b] = [a, b].map(|path| match path {
+                    GlobalPath::Circle(_) => "circle",
+                    GlobalPath::Ellipse(_) => "ellipse",
+                    GlobalPath::Helix(_) => "helix",
+                    GlobalPath::Line(_) => "line",
+                    GlobalPath::Nurbs(_) => "NURBS curve",
+                });
+                write!(f, "ruled surface between {a} and {b}")
+            }
+            SurfaceGeometry::Cone(cone) => write!(
+                f,
+                "cone with apex {}, radius {}, axis {}",
+                Rounded(cone.apex()),
+                Rounded(cone.radius()),
+                Rounded(cone.axis())
+            ),
+        }
+    }
+}
+
+/// Formats numbers with a fixed maximum number of decimal places
+///
+/// Trailing zeros are omitted, so round numbers stay short.
+struct Rounded<T>(T);
+
+impl Rounded<()> {
+    const DECIMAL_PLACES: usize = 6;
+
+    fn write(f: &mut fmt::Formatter, value: Scalar) -> fmt::Result {
+        let mut s = format!("{:.*}", Self::DECIMAL_PLACES, value.into_f64());
+        if s.contains('.') {
+            let trimmed = s.trim_end_matches('0').trim_end_matches('.').len();
+            s.truncate(trimmed);
+        }
+        if s == "-0" {
+            s = "0".to_owned();
+        }
+
+        f.write_str(&s)
+    }
+
+    fn write_components(
+        f: &mut fmt::Formatter,
+        components: &[Scalar],
+    ) -> fmt::Result {
+        f.write_char('[')?;
+        for (i, &component) in components.iter().enumerate() {
+            if i > 0 {
+                f.write_str(", ")?;
+            }
+            Self::write(f, component)?;
+        }
+        f.write_char(']')
+    }
+}
+
+impl fmt::Display for Rounded<Scalar> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        Rounded::write(f, self.0)
+    }
+}
+
+impl<const D: usize> fmt::Display for Rounded<Point<D>> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        Rounded::write_components(f, &self.0.coords.components)
+    }
+}
+
+impl<const D: usize> fmt::Display for Rounded<Vector<D>> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        Rounded::write_components(f, &self.0.components)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        algorithms::sweep::Sweep,
+        builder::HalfEdgeBuilder,
+        objects::{HalfEdge, Sketch},
+        partial::HasPartial,
+        services::Services,
+    };
+
+    use super::PrettyPrint;
+
+    #[test]
+    fn half_edge() {
+        let mut services = Services::new();
+
+        let half_edge = HalfEdge::partial()
+            .update_as_line_segment_from_points(
+                services.objects.surfaces.xy_plane(),
+                [[0., 0.], [1. / 3., 1.]],
+            )
+            .build(&mut services.objects);
+
+        assert_eq!(
+            half_edge.pretty().to_string(),
+            "HalfEdge: line [0, 0, 0] -> [0.333333, 1, 0]"
+        );
+    }
+
+    #[test]
+    fn nested_objects() {
+        let mut services = Services::new();
+
+        let solid = Sketch::builder()
+            .with_polygon_from_points(
+                services.objects.surfaces.xy_plane(),
+                [[0., 0.], [1., 0.], [0., 1.]],
+                &mut services.objects,
+            )
+            .build(&mut services.objects)
+            .sweep([0., 0., 1.], &mut services.objects);
+
+        let output = solid.pretty().to_string();
+        let lines = output.lines().collect::<Vec<_>>();
+
+        assert!(lines[0].starts_with("Solid @ 0x"));
+        assert!(lines[1].starts_with("  Shell @ 0x"));
+        assert!(lines[1].ends_with("(5 faces)"));
+        assert!(lines[2].starts_with("    Face @ 0x"));
+        assert!(lines[3].starts_with("      exterior: Cycle @ 0x"));
+        assert!(lines[4].starts_with("        HalfEdge @ 0x"));
+
+        // A triangular prism has 2 triangles and 3 rectangles as faces. Each
+        // face takes up a line, as does its exterior cycle.
+        assert_eq!(lines.len(), 2 + 5 * 2 + 2 * 3 + 3 * 4);
+    }
+}
@@ -0,0 +1,617 @@
+//! Serialization of objects
+//!
+//! Objects refer to each other through handles, and many of them are shared
+//! between multiple other objects. A [`Snapshot`] flattens this object graph
+//! into one table per type of object, in which objects refer to each other by
+//! index. Shared objects are stored only once, so restoring a snapshot restores
+//! the sharing too.
+//!
+//! Snapshots store sets of faces, which is what shells, solids, and sketches
+//! are made of. They can be written to and read from JSON files, so a shape
+//! doesn't need to be computed again, to be used later.
+
+use std::{
+    collections::HashMap,
+    io::{Read, Write},
+};
+
+use fj_interop::mesh::{Attributes, Color};
+use fj_math::Point;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    geometry::{path::SurfacePath, surface::SurfaceGeometry},
+    insert::Insert,
+    objects::{
+        Curve, Cycle, Face, FaceSet, GlobalCurve, GlobalEdge, GlobalVertex,
+        HalfEdge, Objects, Surface, SurfaceVertex, Vertex,
+    },
+    services::Service,
+    storage::{Handle, ObjectId},
+};
+
+/// Sets of faces, and all objects they refer to, in serializable form
+///
+/// See the [module documentation] for details.
+///
+/// [module documentation]: self
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct Snapshot {
+    version: u32,
+    face_sets: Vec<(String, Vec<usize>)>,
+
+    surfaces: Vec<SurfaceGeometry>,
+    global_curves: usize,
+    global_vertices: Vec<Point<3>>,
+    curves: Vec<CurveData>,
+    surface_vertices: Vec<SurfaceVertexData>,
+    vertices: Vec<VertexData>,
+    global_edges: Vec<GlobalEdgeData>,
+    half_edges: Vec<HalfEdgeData>,
+    cycles: Vec<Vec<usize>>,
+    faces: Vec<FaceData>,
+
+    #[serde(skip)]
+    indices: Indices,
+}
+
+impl Snapshot {
+    /// The version of the format that is written
+    ///
+    /// Increment this, whenever the format changes in an incompatible way.
+    pub const VERSION: u32 = 1;
+
+    /// Create an empty snapshot
+    pub fn new() -> Self {
+        Self {
+            version: Self::VERSION,
+            ..Self::default()
+        }
+    }
+
+    /// Add a named set of faces to the snapshot
+    pub fn add_faces(&mut self, name: impl Into<String>, faces: &FaceSet) {
+        let faces = faces.into_iter().map(|face| self.face(face)).collect();
+        self.face_sets.push((name.into(), faces));
+    }
+
+    /// Restore the sets of faces, in the order they were added
+    ///
+    /// The restored objects are inserted into `objects`.
+    pub fn restore(
+        &self,
+        objects: &mut Service<Objects>,
+    ) -> Result<Vec<(String, FaceSet)>, SnapshotError> {
+        if self.version != Self::VERSION {
+            return Err(SnapshotError::UnsupportedVersion(self.version));
+        }
+
+        // Objects can only refer to objects of other types, so restoring them
+        // type by type, in the right order, is enough to make sure that all
+        // references can be resolved.
+        let default_planes = [
+            objects.surfaces.xy_plane(),
+            objects.surfaces.xz_plane(),
+            objects.surfaces.yz_plane(),
+        ];
+        let surfaces = self
+            .surfaces
+            .iter()
+            .map(|geometry| {
+                // Code compares surfaces to the default planes by identity,
+                // so those must not be duplicated.
+                default_planes
+                    .iter()
+                    .find(|plane| plane.geometry() == *geometry)
+                    .cloned()
+                    .unwrap_or_else(|| {
+                        Surface::new(geometry.clone()).insert(objects)
+                    })
+            })
+            .collect::<Vec<_>>();
+        let global_curves = (0..self.global_curves)
+            .map(|_| GlobalCurve.insert(objects))
+            .collect::<Vec<_>>();
+        let global_vertices = self
+            .global_vertices
+            .iter()
+            .map(|&position| GlobalVertex::new(position).insert(objects))
+            .collect::<Vec<_>>();
+
+        let mut curves = Vec::new();
+        for curve in &self.curves {
+            let surface = get(&surfaces, curve.surface, "surface")?;
+            let global_form =
+                get(&global_curves, curve.global_form, "global curve")?;
+
+            curves.push(
+                Curve::new(surface, curve.path.clone(), global_form)
+                    .insert(objects),
+            );
+        }
+
+        let mut surface_vertices = Vec::new();
+        for vertex in &self.surface_vertices {
+            let surface = get(&surfaces, vertex.surface, "surface")?;
+            let global_form =
+                get(&global_vertices, vertex.global_form, "global vertex")?;
+
+            surface_vertices.push(
+                SurfaceVertex::new(vertex.position, surface, global_form)
+                    .insert(objects),
+            );
+        }
+
+        let mut vertices = Vec::new();
+        for vertex in &self.vertices {
+            let curve = get(&curves, vertex.curve, "curve")?;
+            let surface_form =
+                get(&surface_vertices, vertex.surface_form, "surface vertex")?;
+
+            vertices.push(
+                Vertex::new(vertex.position, curve, surface_form)
+                    .insert(objects),
+            );
+        }
+
+        let mut global_edges = Vec::new();
+        for edge in &self.global_edges {
+            let curve = get(&global_curves, edge.curve, "global curve")?;
+            let [a, b] = edge.vertices;
+            let vertices = [
+                get(&global_vertices, a, "global vertex")?,
+                get(&global_vertices, b, "global vertex")?,
+            ];
+
+            global_edges.push(GlobalEdge::new(curve, vertices).insert(objects));
+        }
+
+        let mut half_edges = Vec::new();
+        for edge in &self.half_edges {
+            let [a, b] = edge.vertices;
+            let vertices =
+                [get(&vertices, a, "vertex")?, get(&vertices, b, "vertex")?];
+            let global_form =
+                get(&global_edges, edge.global_form, "global edge")?;
+
+            half_edges
+                .push(HalfEdge::new(vertices, global_form).insert(objects));
+        }
+
+        let mut cycles = Vec::new();
+        for (i, cycle) in self.cycles.iter().enumerate() {
+            if cycle.is_empty() {
+                return Err(SnapshotError::EmptyCycle(i));
+            }
+
+            let half_edges = cycle
+                .iter()
+                .map(|&index| get(&half_edges, index, "half-edge"))
+                .collect::<Result<Vec<_>, _>>()?;
+
+            cycles.push(Cycle::new(half_edges).insert(objects));
+        }
+
+        let mut faces = Vec::new();
+        for face in &self.faces {
+            let exterior = get(&cycles, face.exterior, "cycle")?;
+            let interiors = face
+                .interiors
+                .iter()
+                .map(|&index| get(&cycles, index, "cycle"))
+                .collect::<Result<Vec<_>, _>>()?;
+
+            let attributes = Attributes {
+                name: face.name.as_deref().map(Into::into),
+                material: face.material.as_deref().map(Into::into),
+            };
+            faces.push(
+                Face::new(exterior, interiors, Color(face.color))
+                    .with_attributes(attributes)
+                    .insert(objects),
+            );
+        }
+
+        self.face_sets
+            .iter()
+            .map(|(name, indices)| {
+                let face_set = indices
+                    .iter()
+                    .map(|&index| get(&faces, index, "face"))
+                    .collect::<Result<FaceSet, _>>()?;
+
+                Ok((name.clone(), face_set))
+            })
+            .collect()
+    }
+
+    /// Write the snapshot as JSON
+    pub fn write(&self, writer: impl Write) -> Result<(), SnapshotError> {
+        serde_json::to_writer(writer, self)?;
+        Ok(())
+    }
+
+    /// Read a snapshot that has been written using [`Snapshot::write`]
+    pub fn read(reader: impl Read) -> Result<Self, SnapshotError> {
+        let snapshot = serde_json::from_reader(reader)?;
+        Ok(snapshot)
+    }
+
+    fn face(&mut self, face: &Handle<Face>) -> usize {
+        if let Some(&index) = self.indices.faces.get(&face.id()) {
+            return index;
+        }
+
+        let face_data = FaceData {
+            exterior: self.cycle(face.exterior()),
+            interiors: face
+                .interiors()
+                .map(|cycle| self.cycle(cycle))
+                .collect(),
+            color: face.color().0,
+            name: face.attributes().name.as_deref().map(Into::into),
+            material: face.attributes().material.as_deref().map(Into::into),
+        };
+
+        push(&mut self.faces, &mut self.indices.faces, face, face_data)
+    }
+
+    fn cycle(&mut self, cycle: &Handle<Cycle>) -> usize {
+        if let Some(&index) = self.indices.cycles.get(&cycle.id()) {
+            return index;
+        }
+
+        let half_edges = cycle
+            .half_edges()
+            .map(|half_edge| self.half_edge(half_edge))
+            .collect();
+
+        push(
+            &mut self.cycles,
+            &mut self.indices.cycles,
+            cycle,
+            half_edges,
+        )
+    }
+
+    fn half_edge(&mut self, half_edge: &Handle<HalfEdge>) -> usize {
+        if let Some(&index) = self.indices.half_edges.get(&half_edge.id()) {
+            return index;
+        }
+
+        let [a, b] = half_edge.vertices();
+        let half_edge_data = HalfEdgeData {
+            vertices: [self.vertex(a), self.vertex(b)],
+            global_form: self.global_edge(half_edge.global_form()),
+        };
+
+        push(
+            &mut self.half_edges,
+            &mut self.indices.half_edges,
+            half_edge,
+            half_edge_data,
+        )
+    }
+
+    fn global_edge(&mut self, global_edge: &Handle<GlobalEdge>) -> usize {
+        if let Some(&index) = self.indices.global_edges.get(&global_edge.id()) {
+            return index;
+        }
+
+        let [a, b] = global_edge.vertices().access_in_normalized_order();
+        let global_edge_data = GlobalEdgeData {
+            curve: self.global_curve(global_edge.curve()),
+            vertices: [self.global_vertex(&a), self.global_vertex(&b)],
+        };
+
+        push(
+            &mut self.global_edges,
+            &mut self.indices.global_edges,
+            global_edge,
+            global_edge_data,
+        )
+    }
+
+    fn vertex(&mut self, vertex: &Handle<Vertex>) -> usize {
+        if let Some(&index) = self.indices.vertices.get(&vertex.id()) {
+            return index;
+        }
+
+        let vertex_data = VertexData {
+            position: vertex.position(),
+            curve: self.curve(vertex.curve()),
+            surface_form: self.surface_vertex(vertex.surface_form()),
+        };
+
+        push(
+            &mut self.vertices,
+            &mut self.indices.vertices,
+            vertex,
+            vertex_data,
+        )
+    }
+
+    fn surface_vertex(&mut self, vertex: &Handle<SurfaceVertex>) -> usize {
+        if let Some(&index) = self.indices.surface_vertices.get(&vertex.id()) {
+            return index;
+        }
+
+        let vertex_data = SurfaceVertexData {
+            position: vertex.position(),
+            surface: self.surface(vertex.surface()),
+            global_form: self.global_vertex(vertex.global_form()),
+        };
+
+        push(
+            &mut self.surface_vertices,
+            &mut self.indices.surface_vertices,
+            vertex,
+            vertex_data,
+        )
+    }
+
+    fn curve(&mut self, curve: &Handle<Curve>) -> usize {
+        if let Some(&index) = self.indices.curves.get(&curve.id()) {
+            return index;
+        }
+
+        let curve_data = CurveData {
+            path: curve.path(),
+            surface: self.surface(curve.surface()),
+            global_form: self.global_curve(curve.global_form()),
+        };
+
+        push(
+            &mut self.curves,
+            &mut self.indices.curves,
+            curve,
+            curve_data,
+        )
+    }
+
+    fn global_vertex(&mut self, vertex: &Handle<GlobalVertex>) -> usize {
+        push(
+            &mut self.global_vertices,
+            &mut self.indices.global_vertices,
+            vertex,
+            vertex.position(),
+        )
+    }
+
+    fn global_curve(&mut self, curve: &Handle<GlobalCurve>) -> usize {
+        let next = self.global_curves;
+        let index =
+            *self.indices.global_curves.entry(curve.id()).or_insert(next);
+        if index == next {
+            self.global_curves += 1;
+        }
+
+        index
+    }
+
+    fn surface(&mut self, surface: &Handle<Surface>) -> usize {
+        push(
+            &mut self.surfaces,
+            &mut self.indices.surfaces,
+            surface,
+            surface.geometry(),
+        )
+    }
+}
+
+/// Error restoring or reading a [`Snapshot`]
+#[derive(Debug, thiserror::Error)]
+pub enum SnapshotError {
+    /// The snapshot has been written in a format that is not supported
+    #[error("Unsupported snapshot version: {0}")]
+    UnsupportedVersion(u32),
+
+    /// An object refers to another object that doesn't exist
+    #[error("Reference to {kind} {index}, but there are only {len}")]
+    InvalidReference {
+        /// The type of the object that is referred to
+        kind: &'static str,
+
+        /// The index of the object that is referred to
+        index: usize,
+
+        /// The number of objects of that type
+        len: usize,
+    },
+
+    /// A cycle has no half-edges
+    #[error("Cycle {0} has no half-edges")]
+    EmptyCycle(usize),
+
+    /// Error reading or writing JSON
+    #[error("Error reading or writing snapshot")]
+    Json(#[from] serde_json::Error),
+}
+
+/// The indices of objects that have already been added to a [`Snapshot`]
+#[derive(Clone, Debug, Default)]
+struct Indices {
+    surfaces: HashMap<ObjectId, usize>,
+    global_curves: HashMap<ObjectId, usize>,
+    global_vertices: HashMap<ObjectId, usize>,
+    curves: HashMap<ObjectId, usize>,
+    surface_vertices: HashMap<ObjectId, usize>,
+    vertices: HashMap<ObjectId, usize>,
+    global_edges: HashMap<ObjectId, usize>,
+    half_edges: HashMap<ObjectId, usize>,
+    cycles: HashMap<ObjectId, usize>,
+    faces: HashMap<ObjectId, usize>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+struct CurveData {
+    path: SurfacePath,
+    surface: usize,
+    global_form: usize,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+struct SurfaceVertexData {
+    position: Point<2>,
+    surface: usize,
+    global_form: usize,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+struct VertexData {
+    position: Point<1>,
+    curve: usize,
+    surface_form: usize,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+struct GlobalEdgeData {
+    curve: usize,
+    vertices: [usize; 2],
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+struct HalfEdgeData {
+    vertices: [usize; 2],
+    global_form: usize,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+struct FaceData {
+    exterior: usize,
+    interiors: Vec<usize>,
+    color: [u8; 4],
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    name: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    material: Option<String>,
+}
+
+/// Add an object to its table, unless it has been added already
+fn push<T, D>(
+    table: &mut Vec<D>,
+    indices: &mut HashMap<ObjectId, usize>,
+    handle: &Handle<T>,
+    data: D,
+) -> usize {
+    *indices.entry(handle.id()).or_insert_with(|| {
+        table.push(data);
+        table.len() - 1
+    })
+}
+
+/// Access a restored object by index
+fn get<T>(
+    table: &[Handle<T>],
+    index: usize,
+    kind: &'static str,
+) -> Result<Handle<T>, SnapshotError> {
+    table
+        .get(index)
+        .cloned()
+        .ok_or(SnapshotError::InvalidReference {
+            kind,
+            index,
+            len: table.len(),
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use fj_interop::mesh::{Mesh, Origin, Triangle};
+    use fj_math::Point;
+
+    use crate::{
+        algorithms::{
+            approx::Tolerance, sweep::Sweep, triangulate::Triangulate,
+        },
+        objects::{FaceSet, Sketch},
+        services::Services,
+    };
+
+    use super::{Snapshot, SnapshotError};
+
+    #[test]
+    fn restore_cuboid() -> anyhow::Result<()> {
+        let mut services = Services::new();
+
+        let surface = services.objects.surfaces.xy_plane();
+        let solid = Sketch::builder()
+            .with_polygon_from_points(
+                surface,
+                [[0., 0.], [4., 0.], [4., 4.], [0., 4.]],
+                &mut services.objects,
+            )
+            .build(&mut services.objects)
+            .sweep([0., 0., 1.], &mut services.objects);
+        let faces = solid
+            .shells()
+            .flat_map(|shell| shell.faces().clone())
+            .collect::<FaceSet>();
+
+        let mut snapshot = Snapshot::new();
+        snapshot.add_faces("solid", &faces);
+
+        let mut json = Vec::new();
+        snapshot.write(&mut json)?;
+        let snapshot = Snapshot::read(json.as_slice())?;
+
+        let restored = snapshot.restore(&mut services.objects)?;
+        let [(name, restored)] = restored.as_slice() else {
+            panic!("Expected one set of faces");
+        };
+        assert_eq!(name, "solid");
+        assert_eq!(restored.into_iter().count(), (&faces).into_iter().count());
+
+        // Shared objects must still be shared, or the triangulation would run
+        // into duplicated points.
+        let tolerance = Tolerance::from_scalar(0.01)?;
+        let original = (&faces, tolerance).triangulate();
+        let restored = (restored, tolerance).triangulate();
+
+        // The restored objects are new ones, so the triangles are only expected
+        // to be the same, apart from the objects they refer to.
+        let triangles = |mesh: Mesh<Point<3>>| {
+            mesh.triangles()
+                .map(|triangle| Triangle {
+                    origin: Origin::default(),
+                    ..triangle
+                })
+                .collect::<Vec<_>>()
+        };
+        let mut original = triangles(original);
+        let mut restored = triangles(restored);
+        original.sort();
+        restored.sort();
+        assert_eq!(original, restored);
+
+        Ok(())
+    }
+
+    #[test]
+    fn reject_invalid_reference() {
+        let mut services = Services::new();
+
+        let json = r#"{
+            "version": 1,
+            "face_sets": [["faces", [0]]],
+            "surfaces": [],
+            "global_curves": 0,
+            "global_vertices": [],
+            "curves": [],
+            "surface_vertices": [],
+            "vertices": [],
+            "global_edges": [],
+            "half_edges": [],
+            "cycles": [],
+            "faces": []
+        }"#;
+        let snapshot = Snapshot::read(json.as_bytes()).unwrap();
+
+        let result = snapshot.restore(&mut services.objects);
+        assert!(matches!(
+            result,
+            Err(SnapshotError::InvalidReference { kind: "face", .. })
+        ));
+    }
+}
@@ -13,9 +13,11 @@ use parking_lot::Mutex;
 use crate::objects::Objects;
 
 pub use self::{
-    objects::ServiceObjectsExt,
+    objects::{ServiceObjectsExt, TransactionError},
     service::{Service, State},
-    validation::{Validation, ValidationEvent},
+    validation::{
+        ServiceValidationExt, Validation, ValidationCommand, ValidationEvent,
+    },
 };
 
 /// The kernel services
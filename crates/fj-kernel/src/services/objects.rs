@@ -1,6 +1,7 @@
 use crate::{
     objects::{Object, Objects, WithHandle},
     storage::Handle,
+    validate::ValidationError,
 };
 
 use super::{Service, State};
@@ -44,6 +45,18 @@ pub trait ServiceObjectsExt {
     fn insert<T>(&mut self, handle: Handle<T>, object: T)
     where
         (Handle<T>, T): Into<Object<WithHandle>>;
+
+    /// Insert objects within a transaction
+    ///
+    /// All objects that `insert` inserts are validated, once it returns. If
+    /// `insert` returns an error, or any of the objects are invalid, the
+    /// transaction is rolled back. Otherwise, it is committed.
+    ///
+    /// See [`Service::begin_transaction`] for details on what that means.
+    fn transaction<T, E>(
+        &mut self,
+        insert: impl FnOnce(&mut Self) -> Result<T, E>,
+    ) -> Result<T, TransactionError<E>>;
 }
 
 impl ServiceObjectsExt for Service<Objects> {
@@ -55,4 +68,107 @@ impl ServiceObjectsExt for Service<Objects> {
             object: (handle, object).into(),
         })
     }
+
+    fn transaction<T, E>(
+        &mut self,
+        insert: impl FnOnce(&mut Self) -> Result<T, E>,
+    ) -> Result<T, TransactionError<E>> {
+        self.begin_transaction();
+
+        let value = match insert(self) {
+            Ok(value) => value,
+            Err(err) => {
+                self.roll_back_transaction();
+                return Err(TransactionError::Operation(err));
+            }
+        };
+
+        let errors = self
+            .transaction_events()
+            .filter_map(|event| event.object.validate().err())
+            .collect::<Vec<_>>();
+        if !errors.is_empty() {
+            self.roll_back_transaction();
+            return Err(TransactionError::Validation(errors));
+        }
+
+        self.commit_transaction();
+        Ok(value)
+    }
+}
+
+/// Error returned by [`ServiceObjectsExt::transaction`]
+#[derive(Clone, Debug, thiserror::Error)]
+pub enum TransactionError<E> {
+    /// The operation within the transaction failed
+    #[error("Operation within transaction failed")]
+    Operation(#[source] E),
+
+    /// Objects inserted within the transaction are invalid
+    #[error("Transaction inserted {} invalid objects", .0.len())]
+    Validation(Vec<ValidationError>),
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        insert::Insert,
+        objects::{GlobalVertex, SurfaceVertex},
+        services::{ServiceObjectsExt, Services},
+    };
+
+    use super::TransactionError;
+
+    #[test]
+    fn transaction_commit() {
+        let mut services = Services::new();
+        let num_events = services.objects.events().count();
+
+        services
+            .objects
+            .transaction(|objects| {
+                GlobalVertex::new([0., 0., 0.]).insert(objects);
+                assert_eq!(objects.events().count(), num_events);
+
+                Ok::<_, ()>(())
+            })
+            .unwrap();
+
+        assert_eq!(services.objects.events().count(), num_events + 1);
+    }
+
+    #[test]
+    fn transaction_roll_back_on_invalid_object() {
+        let mut services = Services::new();
+        let num_events = services.objects.events().count();
+
+        let result = services.objects.transaction(|objects| {
+            let global_form = GlobalVertex::new([1., 1., 1.]).insert(objects);
+            let surface = objects.surfaces.xy_plane();
+            SurfaceVertex::new([0., 0.], surface, global_form).insert(objects);
+
+            Ok::<_, ()>(())
+        });
+
+        assert!(
+            matches!(result, Err(TransactionError::Validation(errors)) if errors.len() == 1)
+        );
+        assert_eq!(services.objects.events().count(), num_events);
+
+        // `Validation` would panic on drop, if it had seen the invalid object.
+    }
+
+    #[test]
+    fn transaction_roll_back_on_error() {
+        let mut services = Services::new();
+        let num_events = services.objects.events().count();
+
+        let result = services.objects.transaction(|objects| {
+            GlobalVertex::new([0., 0., 0.]).insert(objects);
+            Err::<(), _>("operation failed")
+        });
+
+        assert!(matches!(result, Err(TransactionError::Operation(_))));
+        assert_eq!(services.objects.events().count(), num_events);
+    }
 }
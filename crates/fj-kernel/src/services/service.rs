@@ -25,6 +25,12 @@ pub struct Service<S: State> {
     state: S,
     events: Vec<S::Event>,
     subscribers: Vec<Arc<Mutex<dyn Subscriber<S::Event>>>>,
+
+    /// Events of open transactions, that have not been published yet
+    pending: Vec<S::Event>,
+
+    /// The index into `pending` where each open transaction starts
+    transactions: Vec<usize>,
 }
 
 impl<S: State> Service<S> {
@@ -34,6 +40,8 @@ impl<S: State> Service<S> {
             state,
             events: Vec::new(),
             subscribers: Vec::new(),
+            pending: Vec::new(),
+            transactions: Vec::new(),
         }
     }
 
@@ -49,20 +57,79 @@ impl<S: State> Service<S> {
     ///
     /// The command is executed synchronously. When this method returns, the
     /// state has been updated and any events have been logged.
+    ///
+    /// If a transaction is open, the events are held back until it is
+    /// committed. See [`Service::begin_transaction`].
     pub fn execute(&mut self, command: S::Command) {
         let mut events = Vec::new();
         self.state.decide(command, &mut events);
 
         for event in &events {
             self.state.evolve(event);
+        }
 
-            for subscriber in &self.subscribers {
-                let mut subscriber = subscriber.lock();
-                subscriber.handle_event(event);
-            }
+        if self.transactions.is_empty() {
+            self.publish(events);
+        } else {
+            self.pending.extend(events);
         }
+    }
 
-        self.events.extend(events);
+    /// Begin a transaction
+    ///
+    /// Commands executed while a transaction is open still update the state
+    /// immediately, but the resulting events are neither logged nor passed to
+    /// subscribers, until the transaction is committed using
+    /// [`Service::commit_transaction`]. If it is rolled back using
+    /// [`Service::roll_back_transaction`] instead, the events are discarded.
+    ///
+    /// Transactions can be nested. The events of a nested transaction only get
+    /// published, once the outermost transaction is committed.
+    pub fn begin_transaction(&mut self) {
+        self.transactions.push(self.pending.len());
+    }
+
+    /// Commit the innermost open transaction
+    ///
+    /// # Panics
+    ///
+    /// Panics, if no transaction is open.
+    pub fn commit_transaction(&mut self) {
+        self.transactions
+            .pop()
+            .expect("Attempting to commit, but no transaction is open");
+
+        if self.transactions.is_empty() {
+            let events = std::mem::take(&mut self.pending);
+            self.publish(events);
+        }
+    }
+
+    /// Roll back the innermost open transaction
+    ///
+    /// The events of the transaction are discarded, meaning they don't end up
+    /// in the event log, and subscribers never see them. The state itself is
+    /// not reverted, as it might be append-only (which is the case for
+    /// `Objects`). Replaying the event log will re-create the state without the
+    /// changes made in the transaction, however.
+    ///
+    /// # Panics
+    ///
+    /// Panics, if no transaction is open.
+    pub fn roll_back_transaction(&mut self) {
+        let start = self
+            .transactions
+            .pop()
+            .expect("Attempting to roll back, but no transaction is open");
+        self.pending.truncate(start);
+    }
+
+    /// Access the events of the innermost open transaction
+    ///
+    /// Returns an empty iterator, if no transaction is open.
+    pub fn transaction_events(&self) -> impl Iterator<Item = &S::Event> {
+        let start = self.transactions.last().copied().unwrap_or(0);
+        self.pending[start..].iter()
     }
 
     /// Access the events
@@ -81,6 +148,17 @@ impl<S: State> Service<S> {
             state.evolve(event);
         }
     }
+
+    fn publish(&mut self, events: Vec<S::Event>) {
+        for event in &events {
+            for subscriber in &self.subscribers {
+                let mut subscriber = subscriber.lock();
+                subscriber.handle_event(event);
+            }
+        }
+
+        self.events.extend(events);
+    }
 }
 
 impl<S: State> Deref for Service<S> {
@@ -100,9 +178,12 @@ where
     }
 }
 
-impl<S: State> Subscriber<S::Command> for Service<S> {
-    fn handle_event(&mut self, event: &S::Command) {
-        self.execute(event.clone());
+impl<S: State, T> Subscriber<T> for Service<S>
+where
+    T: Clone + Into<S::Command>,
+{
+    fn handle_event(&mut self, event: &T) {
+        self.execute(event.clone().into());
     }
 }
 
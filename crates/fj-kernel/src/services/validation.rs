@@ -1,12 +1,12 @@
 use std::{collections::BTreeMap, thread};
 
 use crate::{
-    objects::{BehindHandle, Object},
+    objects::{BehindHandle, Object, WithHandle},
     storage::ObjectId,
     validate::ValidationError,
 };
 
-use super::{objects::ObjectToInsert, State};
+use super::{objects::ObjectToInsert, Service, State};
 
 /// Errors that occurred while validating the objects inserted into the stores
 #[derive(Default)]
@@ -27,7 +27,11 @@ impl Drop for Validation {
                 println!("{err}");
             }
 
-            if !thread::panicking() {
+            // Unhandled validation errors are a bug in the code that uses the
+            // kernel. Make sure they are noticed during development, but don't
+            // bring down the whole process otherwise. Callers that want to
+            // handle the errors can use `ServiceValidationExt::take_errors`.
+            if cfg!(debug_assertions) && !thread::panicking() {
                 panic!();
             }
         }
@@ -35,33 +39,126 @@ impl Drop for Validation {
 }
 
 impl State for Validation {
-    type Command = ObjectToInsert;
+    type Command = ValidationCommand;
     type Event = ValidationEvent;
 
     fn decide(&self, command: Self::Command, events: &mut Vec<Self::Event>) {
-        let err = command.object.validate().err();
-        events.push(ValidationEvent {
-            object: command.object.into(),
-            err,
-        });
+        match command {
+            ValidationCommand::ValidateObject { object } => {
+                let err = object.validate().err();
+                events.push(ValidationEvent::ValidationAttempted {
+                    object: object.into(),
+                    err,
+                });
+            }
+            ValidationCommand::ClearErrors => {
+                if !self.0.is_empty() {
+                    events.push(ValidationEvent::ErrorsCleared);
+                }
+            }
+        }
     }
 
     fn evolve(&mut self, event: &Self::Event) {
-        if let Some(err) = &event.err {
-            self.0
-                .insert(event.object.id(), (event.object.clone(), err.clone()));
+        match event {
+            ValidationEvent::ValidationAttempted { object, err } => {
+                if let Some(err) = err {
+                    self.0.insert(object.id(), (object.clone(), err.clone()));
+                }
+            }
+            ValidationEvent::ErrorsCleared => {
+                self.0.clear();
+            }
+        }
+    }
+}
+
+/// Command for `Service<Validation>`
+#[allow(clippy::large_enum_variant)]
+#[derive(Clone, Debug)]
+pub enum ValidationCommand {
+    /// Validate the provided object
+    ValidateObject {
+        /// The object to validate
+        object: Object<WithHandle>,
+    },
+
+    /// Clear all validation errors, marking them as handled
+    ///
+    /// You might prefer to use [`ServiceValidationExt::take_errors`], which
+    /// is a convenient wrapper around this command.
+    ClearErrors,
+}
+
+impl From<ObjectToInsert> for ValidationCommand {
+    fn from(event: ObjectToInsert) -> Self {
+        Self::ValidateObject {
+            object: event.object,
         }
     }
 }
 
-/// An event produced by the validation service
+/// Event produced by `Service<Validation>`
+#[allow(clippy::large_enum_variant)]
 #[derive(Clone)]
-pub struct ValidationEvent {
-    /// The object for which validation has been attempted
-    pub object: Object<BehindHandle>,
+pub enum ValidationEvent {
+    /// Validation of an object has been attempted
+    ValidationAttempted {
+        /// The object for which validation has been attempted
+        object: Object<BehindHandle>,
 
-    /// The validation error, if the validation resulted in one
+        /// The validation error, if the validation resulted in one
+        ///
+        /// If this is `None`, the object has been validated successfully.
+        err: Option<ValidationError>,
+    },
+
+    /// All validation errors have been cleared
+    ErrorsCleared,
+}
+
+/// Convenient API for `Service<Validation>`
+pub trait ServiceValidationExt {
+    /// Take all validation errors that have occurred so far
     ///
-    /// If this is `None`, the object has been validated successfully.
-    pub err: Option<ValidationError>,
+    /// The errors are considered handled afterwards. `Validation` is not going
+    /// to complain about them, when it is dropped.
+    fn take_errors(&mut self) -> Vec<ValidationError>;
+}
+
+impl ServiceValidationExt for Service<Validation> {
+    fn take_errors(&mut self) -> Vec<ValidationError> {
+        let errors = self.0.values().map(|(_, err)| err.clone()).collect();
+        self.execute(ValidationCommand::ClearErrors);
+        errors
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        insert::Insert,
+        objects::{GlobalVertex, SurfaceVertex},
+        services::{ServiceValidationExt, Services},
+    };
+
+    #[test]
+    fn take_errors() {
+        let mut services = Services::new();
+
+        let global_form =
+            GlobalVertex::new([1., 1., 1.]).insert(&mut services.objects);
+        SurfaceVertex::new(
+            [0., 0.],
+            services.objects.surfaces.xy_plane(),
+            global_form,
+        )
+        .insert(&mut services.objects);
+
+        let mut validation = services.validation.lock();
+        assert_eq!(validation.take_errors().len(), 1);
+        assert!(validation.take_errors().is_empty());
+
+        // `Validation` would panic on drop, if the errors weren't handled.
+    }
 }
@@ -168,7 +168,7 @@ unsafe impl<T> Sync for Handle<T> {}
 ///
 /// See [`Handle::id`].
 #[derive(Clone, Copy, Eq, PartialEq, Hash, Ord, PartialOrd)]
-pub struct ObjectId(u64);
+pub struct ObjectId(pub(crate) u64);
 
 impl fmt::Debug for ObjectId {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
@@ -177,6 +177,12 @@ impl fmt::Debug for ObjectId {
     }
 }
 
+impl From<ObjectId> for fj_interop::mesh::ObjectId {
+    fn from(id: ObjectId) -> Self {
+        Self(id.0)
+    }
+}
+
 /// A wrapper around [`Handle`] to define equality based on identity
 ///
 /// This is a utility type that implements [`Eq`]/[`PartialEq`] and other common
@@ -0,0 +1,141 @@
+//! Tolerance classes
+//!
+//! The kernel uses distances as tolerances for different purposes, and those
+//! tolerances differ by many orders of magnitude. Mixing them up leads to false
+//! validation errors, or to cracks in the output. To prevent that, each kind of
+//! tolerance has its own type:
+//!
+//! - [`ModelTolerance`] defines when positions in the model are considered to
+//!   be the same.
+//! - [`ValidationTolerance`] defines how far the different representations of
+//!   the same position may deviate, due to floating-point inaccuracies.
+//! - [`Tolerance`] defines how far an approximation may deviate from the exact
+//!   shape.
+//!
+//! Conversions are only provided in the direction where they are safe: Two
+//! positions that match within the validation tolerance also match within the
+//! model tolerance, and an approximation that doesn't deviate by more than the
+//! model tolerance is indistinguishable from the exact shape.
+
+use fj_math::{Point, Scalar};
+
+pub use crate::algorithms::approx::{InvalidTolerance, Tolerance};
+
+/// The tolerance that defines when positions in the model coincide
+///
+/// Positions closer to each other than this tolerance are considered to be the
+/// same. Objects at such positions must be identical, not just duplicates.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash, Ord, PartialOrd)]
+pub struct ModelTolerance(Scalar);
+
+impl ModelTolerance {
+    /// Construct a `ModelTolerance` from a [`Scalar`]
+    ///
+    /// Returns an error, if the passed scalar is not larger than zero.
+    pub fn from_scalar(
+        scalar: impl Into<Scalar>,
+    ) -> Result<Self, InvalidTolerance> {
+        let scalar = scalar.into();
+
+        if scalar <= Scalar::ZERO {
+            return Err(InvalidTolerance(scalar));
+        }
+
+        Ok(Self(scalar))
+    }
+
+    /// Return the [`Scalar`] that defines the tolerance
+    pub fn inner(&self) -> Scalar {
+        self.0
+    }
+
+    /// Determine whether two points coincide, according to this tolerance
+    pub fn coincide<const D: usize>(&self, a: Point<D>, b: Point<D>) -> bool {
+        a.distance_to(&b) < self.0
+    }
+}
+
+impl Default for ModelTolerance {
+    fn default() -> Self {
+        Self(Scalar::from_f64(5e-7)) // 0.5 µm
+    }
+}
+
+impl From<ValidationTolerance> for ModelTolerance {
+    fn from(tolerance: ValidationTolerance) -> Self {
+        Self(tolerance.0)
+    }
+}
+
+/// The tolerance for inaccuracies in the representation of positions
+///
+/// The same position might be represented in different ways, for example in
+/// global coordinates and in the coordinates of a surface. Due to the limited
+/// precision of floating-point numbers, those representations don't always
+/// match exactly. This tolerance defines how large the deviation may become.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash, Ord, PartialOrd)]
+pub struct ValidationTolerance(Scalar);
+
+impl ValidationTolerance {
+    /// Construct a `ValidationTolerance` from a [`Scalar`]
+    ///
+    /// Returns an error, if the passed scalar is not larger than zero.
+    pub fn from_scalar(
+        scalar: impl Into<Scalar>,
+    ) -> Result<Self, InvalidTolerance> {
+        let scalar = scalar.into();
+
+        if scalar <= Scalar::ZERO {
+            return Err(InvalidTolerance(scalar));
+        }
+
+        Ok(Self(scalar))
+    }
+
+    /// Return the [`Scalar`] that defines the tolerance
+    pub fn inner(&self) -> Scalar {
+        self.0
+    }
+}
+
+impl Default for ValidationTolerance {
+    fn default() -> Self {
+        // This value was chosen pretty arbitrarily. Seems small enough to catch
+        // errors. If it turns out it's too small (because it produces false
+        // positives due to floating-point accuracy issues), we can adjust it.
+        Self(Scalar::from_f64(5e-14))
+    }
+}
+
+impl From<ModelTolerance> for Tolerance {
+    fn from(tolerance: ModelTolerance) -> Self {
+        Self::from_scalar(tolerance.0)
+            .expect("Model tolerance is always larger than zero")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use fj_math::Point;
+
+    use super::{ModelTolerance, Tolerance, ValidationTolerance};
+
+    #[test]
+    fn conversions() {
+        let validation = ValidationTolerance::default();
+        let model = ModelTolerance::from(validation);
+        assert_eq!(model.inner(), validation.inner());
+
+        let approx = Tolerance::from(ModelTolerance::default());
+        assert_eq!(approx.inner(), ModelTolerance::default().inner());
+    }
+
+    #[test]
+    fn coincide() {
+        let tolerance = ModelTolerance::default();
+
+        let a = Point::from([0., 0., 0.]);
+        assert!(tolerance.coincide(a, Point::from([1e-8, 0., 0.])));
+        assert!(!tolerance.coincide(a, Point::from([1e-3, 0., 0.])));
+    }
+}
@@ -2,6 +2,7 @@ use itertools::Itertools;
 
 use crate::{
     objects::{Cycle, SurfaceVertex},
+    pretty::PrettyPrint,
     storage::Handle,
 };
 
@@ -30,8 +31,10 @@ pub enum CycleValidationError {
     /// Half-edges are not connected
     #[error(
         "`HalfEdge`s of `Cycle` are not connected\n\
-        - Front vertex of previous `HalfEdge`: {prev:#?}\n\
-        - Back vertex of next `HalfEdge`: {next:#?}"
+        - Front vertex of previous `HalfEdge`: {prev}\n\
+        - Back vertex of next `HalfEdge`: {next}",
+        prev = .prev.pretty(),
+        next = .next.pretty(),
     )]
     HalfEdgeConnection {
         /// The front vertex of the previous half-edge
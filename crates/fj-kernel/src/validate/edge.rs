@@ -8,6 +8,7 @@ use crate::{
         Curve, GlobalCurve, GlobalEdge, GlobalVertex, HalfEdge,
         VerticesInNormalizedOrder,
     },
+    pretty::PrettyPrint,
     storage::Handle,
 };
 
@@ -50,8 +51,10 @@ pub enum HalfEdgeValidationError {
     /// [`HalfEdge`] vertices are not defined on the same `Curve`
     #[error(
         "`HalfEdge` vertices are not defined on the same `Curve`\n\
-        - `Curve` of back vertex: {back_curve:#?}\n\
-        - `Curve` of front vertex: {front_curve:#?}"
+        - `Curve` of back vertex: {back_curve}\n\
+        - `Curve` of front vertex: {front_curve}",
+        back_curve = .back_curve.pretty(),
+        front_curve = .front_curve.pretty(),
     )]
     CurveMismatch {
         /// The curve of the [`HalfEdge`]'s back vertex
@@ -81,9 +84,13 @@ pub enum HalfEdgeValidationError {
         "Global forms of `HalfEdge` vertices do not match vertices of \n\
         `HalfEdge`'s global form\n\
         - `GlobalVertex` objects from `Vertex` objects: \
-            {global_vertices_from_vertices:#?}\n\
+            {global_vertices_from_vertices}\n\
         - `GlobalVertex` objects from `GlobalEdge`: \
-            {global_vertices_from_global_form:#?}"
+            {global_vertices_from_global_form}",
+        global_vertices_from_vertices =
+            .global_vertices_from_vertices.pretty(),
+        global_vertices_from_global_form =
+            .global_vertices_from_global_form.pretty(),
     )]
     GlobalVertexMismatch {
         /// The [`GlobalVertex`] from the [`HalfEdge`]'s vertices
@@ -184,7 +191,7 @@ impl HalfEdgeValidationError {
 
         let distance = (back_position - front_position).magnitude();
 
-        if distance < config.distinct_min_distance {
+        if distance < config.distinct_min_distance.inner() {
             return Err(Self::VerticesAreCoincident {
                 back_position,
                 front_position,
@@ -2,6 +2,7 @@ use fj_math::Winding;
 
 use crate::{
     objects::{Cycle, Face, Surface},
+    pretty::PrettyPrint,
     storage::Handle,
 };
 
@@ -26,9 +27,12 @@ pub enum FaceValidationError {
     /// [`Surface`] of an interior [`Cycle`] doesn't match [`Face`]'s `Surface`
     #[error(
         "`Surface` of an interior `Cycle` doesn't match `Face`'s `Surface`\n\
-        - `Surface` of the `Face`: {surface:#?}\n\
-        - Invalid interior `Cycle`: {interior:#?}\n\
-        - `Face`: {face:#?}"
+        - `Surface` of the `Face`: {surface}\n\
+        - Invalid interior `Cycle`: {interior}\n\
+        - `Face`: {face}",
+        surface = .surface.pretty(),
+        interior = .interior.pretty(),
+        face = .face.pretty(),
     )]
     SurfaceMismatch {
         /// The surface of the [`Face`]
@@ -46,7 +50,8 @@ pub enum FaceValidationError {
         "Interior of `Face` has invalid winding; must be opposite of exterior\n\
         - Winding of exterior cycle: {exterior_winding:#?}\n\
         - Winding of interior cycle: {interior_winding:#?}\n\
-        - `Face`: {face:#?}"
+        - `Face`: {face}",
+        face = .face.pretty(),
     )]
     InvalidInteriorWinding {
         /// The winding of the [`Face`]'s exterior cycle
@@ -175,4 +180,26 @@ mod tests {
         assert!(valid.validate().is_ok());
         assert!(invalid.validate().is_err());
     }
+    #[test]
+    fn face_interior_slot_away_from_origin() {
+        let mut services = Services::new();
+
+        // The winding of this slot can only be determined correctly, if the
+        // polygon formed by its vertices is closed.
+        let surface = services.objects.surfaces.xy_plane();
+        let face = Face::partial()
+            .with_exterior_polygon_from_points(
+                surface.clone(),
+                [[-2., -2.], [2., -2.], [2., 2.], [-2., 2.]],
+            )
+            .with_interior_slot(
+                surface,
+                [[0.5, -1.], [1.5, -1.]],
+                0.2,
+                &mut services.objects,
+            )
+            .build(&mut services.objects);
+
+        assert!(face.validate().is_ok());
+    }
 }
@@ -19,7 +19,7 @@ pub use self::{
 
 use std::convert::Infallible;
 
-use fj_math::Scalar;
+use crate::tolerance::{ModelTolerance, ValidationTolerance};
 
 /// Validate an object
 ///
@@ -41,13 +41,13 @@ pub trait Validate: Sized {
 }
 
 /// Configuration required for the validation process
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, Default)]
 pub struct ValidationConfig {
     /// The minimum distance between distinct objects
     ///
     /// Objects whose distance is less than the value defined in this field, are
     /// considered identical.
-    pub distinct_min_distance: Scalar,
+    pub distinct_min_distance: ModelTolerance,
 
     /// The maximum distance between identical objects
     ///
@@ -55,21 +55,7 @@ pub struct ValidationConfig {
     /// between them, due to inaccuracies of the numerical representation. If
     /// that distance is less than the one defined in this field, can not be
     /// considered identical.
-    pub identical_max_distance: Scalar,
-}
-
-impl Default for ValidationConfig {
-    fn default() -> Self {
-        Self {
-            distinct_min_distance: Scalar::from_f64(5e-7), // 0.5 µm,
-
-            // This value was chosen pretty arbitrarily. Seems small enough to
-            // catch errors. If it turns out it's too small (because it produces
-            // false positives due to floating-point accuracy issues), we can
-            // adjust it.
-            identical_max_distance: Scalar::from_f64(5e-14),
-        }
-    }
+    pub identical_max_distance: ValidationTolerance,
 }
 
 /// An error that can occur during a validation
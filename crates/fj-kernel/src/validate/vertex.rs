@@ -4,6 +4,7 @@ use fj_math::{Point, Scalar};
 
 use crate::{
     objects::{GlobalVertex, Surface, SurfaceVertex, Vertex},
+    pretty::PrettyPrint,
     storage::Handle,
 };
 
@@ -51,8 +52,10 @@ pub enum VertexValidationError {
     /// Mismatch between the surface's of the curve and surface form
     #[error(
         "Surface form of vertex must be defined on same surface as curve\n\
-        - `Surface` of curve: {curve_surface:#?}\n\
-        - `Surface` of surface form: {surface_form_surface:#?}"
+        - `Surface` of curve: {curve_surface}\n\
+        - `Surface` of surface form: {surface_form_surface}",
+        curve_surface = .curve_surface.pretty(),
+        surface_form_surface = .surface_form_surface.pretty(),
     )]
     SurfaceMismatch {
         /// The surface of the vertex' curve
@@ -65,10 +68,12 @@ pub enum VertexValidationError {
     /// Mismatch between position of the vertex and position of its surface form
     #[error(
         "`Vertex` position doesn't match position of its surface form\n\
-        - `Vertex`: {vertex:#?}\n\
-        - `SurfaceVertex`: {surface_vertex:#?}\n\
+        - `Vertex`: {vertex}\n\
+        - `SurfaceVertex`: {surface_vertex}\n\
         - `Vertex` position as surface: {curve_position_as_surface:?}\n\
-        - Distance between the positions: {distance}"
+        - Distance between the positions: {distance}",
+        vertex = .vertex.pretty(),
+        surface_vertex = .surface_vertex.pretty(),
     )]
     PositionMismatch {
         /// The vertex
@@ -112,7 +117,7 @@ impl VertexValidationError {
 
         let distance = curve_position_as_surface.distance_to(&surface_position);
 
-        if distance > config.identical_max_distance {
+        if distance > config.identical_max_distance.inner() {
             return Err(VertexValidationError::PositionMismatch {
                 vertex: vertex.clone(),
                 surface_vertex: vertex.surface_form().clone_object(),
@@ -131,10 +136,12 @@ pub enum SurfaceVertexValidationError {
     /// Mismatch between position and position of global form
     #[error(
         "`SurfaceVertex` position doesn't match position of its global form\n\
-    - `SurfaceVertex`: {surface_vertex:#?}\n\
-    - `GlobalVertex`: {global_vertex:#?}\n\
+    - `SurfaceVertex`: {surface_vertex}\n\
+    - `GlobalVertex`: {global_vertex}\n\
     - `SurfaceVertex` position as global: {surface_position_as_global:?}\n\
-    - Distance between the positions: {distance}"
+    - Distance between the positions: {distance}",
+        surface_vertex = .surface_vertex.pretty(),
+        global_vertex = .global_vertex.pretty(),
     )]
     PositionMismatch {
         /// The surface vertex
@@ -164,7 +171,7 @@ impl SurfaceVertexValidationError {
 
         let distance = surface_position_as_global.distance_to(&global_position);
 
-        if distance > config.identical_max_distance {
+        if distance > config.identical_max_distance.inner() {
             return Err(Self::PositionMismatch {
                 surface_vertex: surface_vertex.clone(),
                 global_vertex: surface_vertex.global_form().clone_object(),
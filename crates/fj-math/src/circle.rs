@@ -7,6 +7,7 @@ use crate::{Point, Scalar, Vector};
 /// The dimensionality of the circle is defined by the const generic `D`
 /// parameter.
 #[derive(Clone, Copy, Debug, Default, Eq, PartialEq, Hash, Ord, PartialOrd)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Circle<const D: usize> {
     center: Point<D>,
     a: Vector<D>,
@@ -33,9 +34,12 @@ impl<const D: usize> Circle<D> {
         let a = a.into();
         let b = b.into();
 
-        assert_eq!(
-            a.magnitude(),
-            b.magnitude(),
+        // Like for perpendicularity below, requiring the lengths to be
+        // *precisely* equal is not practical. Transforming a circle is enough
+        // to make them deviate slightly.
+        assert!(
+            (a.magnitude() - b.magnitude()).abs()
+                <= a.magnitude() * Scalar::from(1e-12),
             "`a` and `b` must be of equal length"
         );
         assert_ne!(
@@ -0,0 +1,202 @@
+use approx::AbsDiffEq;
+
+use crate::{Point, Scalar, Vector};
+
+/// A cone in 3-dimensional space
+///
+/// The cone consists of the lines from its apex through the points of a
+/// circle. In surface coordinates, `u` is the angle around the axis, like the
+/// coordinate of a [`Circle`]. `v` is 0 at the apex, and 1 on that circle.
+///
+/// [`Circle`]: crate::Circle
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, Hash, Ord, PartialOrd)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Cone {
+    apex: Point<3>,
+    a: Vector<3>,
+    b: Vector<3>,
+    axis: Vector<3>,
+}
+
+impl Cone {
+    /// Construct a cone
+    ///
+    /// `axis` points from the apex to the center of the circle. `a` and `b`
+    /// define that circle, like they do for a [`Circle`].
+    ///
+    /// # Panics
+    ///
+    /// Panics, if any of the following requirements are not met:
+    ///
+    /// - The radius (defined by the length of `a` and `b`) must not be zero.
+    /// - `a` and `b` must be of equal length.
+    /// - `a` and `b` must be perpendicular to each other.
+    /// - `axis` must not be zero, and must be perpendicular to `a` and `b`.
+    ///
+    /// [`Circle`]: crate::Circle
+    pub fn new(
+        apex: impl Into<Point<3>>,
+        a: impl Into<Vector<3>>,
+        b: impl Into<Vector<3>>,
+        axis: impl Into<Vector<3>>,
+    ) -> Self {
+        let apex = apex.into();
+        let a = a.into();
+        let b = b.into();
+        let axis = axis.into();
+
+        // Requiring the lengths to be *precisely* equal is not practical. See
+        // `Helix::new`.
+        assert!(
+            (a.magnitude() - b.magnitude()).abs()
+                <= a.magnitude() * Scalar::from(1e-12),
+            "`a` and `b` must be of equal length"
+        );
+        assert_ne!(a.magnitude(), Scalar::ZERO, "cone radius must not be zero");
+        assert!(
+            a.dot(&b) < Scalar::default_epsilon(),
+            "`a` and `b` must be perpendicular to each other"
+        );
+        assert_ne!(
+            axis.magnitude(),
+            Scalar::ZERO,
+            "cone axis must not be zero"
+        );
+        let epsilon = Scalar::from(1e-9) * a.magnitude() * axis.magnitude();
+        assert!(
+            axis.dot(&a).abs() <= epsilon && axis.dot(&b).abs() <= epsilon,
+            "`axis` must be perpendicular to `a` and `b`"
+        );
+
+        Self { apex, a, b, axis }
+    }
+
+    /// Access the apex of the cone
+    pub fn apex(&self) -> Point<3> {
+        self.apex
+    }
+
+    /// Access the vector that defines the starting point of the circle
+    ///
+    /// See [`Circle::a`].
+    ///
+    /// [`Circle::a`]: crate::Circle::a
+    pub fn a(&self) -> Vector<3> {
+        self.a
+    }
+
+    /// Access the vector that defines the direction of the circle
+    ///
+    /// See [`Circle::b`].
+    ///
+    /// [`Circle::b`]: crate::Circle::b
+    pub fn b(&self) -> Vector<3> {
+        self.b
+    }
+
+    /// Access the vector from the apex to the center of the circle
+    pub fn axis(&self) -> Vector<3> {
+        self.axis
+    }
+
+    /// Access the radius of the circle
+    pub fn radius(&self) -> Scalar {
+        self.a.magnitude()
+    }
+
+    /// Compute the angle between the axis and the lines of the cone
+    pub fn half_angle(&self) -> Scalar {
+        self.radius().atan2(self.axis.magnitude())
+    }
+
+    /// Convert a point in surface coordinates into a 3-dimensional point
+    pub fn point_from_surface_coords(
+        &self,
+        point: impl Into<Point<2>>,
+    ) -> Point<3> {
+        let point = point.into();
+        let (sin, cos) = point.u.sin_cos();
+
+        self.apex + (self.axis + self.a * cos + self.b * sin) * point.v
+    }
+
+    /// Compute the partial derivatives of the cone at the given point
+    ///
+    /// The point is given in surface coordinates. Returns the derivative in
+    /// u-direction, followed by the derivative in v-direction.
+    pub fn derivatives_at(&self, point: impl Into<Point<2>>) -> [Vector<3>; 2] {
+        let point = point.into();
+        let (sin, cos) = point.u.sin_cos();
+
+        [
+            (self.b * cos - self.a * sin) * point.v,
+            self.axis + self.a * cos + self.b * sin,
+        ]
+    }
+
+    /// Compute the normal of the cone at the given point
+    ///
+    /// The point is given in surface coordinates. For positive `v`, the normal
+    /// points away from the axis, if `a`, `b`, and `axis` form a right-handed
+    /// system. The normal is not defined at the apex.
+    pub fn normal_at(&self, point: impl Into<Point<2>>) -> Vector<3> {
+        let [du, dv] = self.derivatives_at(point);
+        du.cross(&dv).normalize()
+    }
+}
+
+impl approx::AbsDiffEq for Cone {
+    type Epsilon = <Scalar as approx::AbsDiffEq>::Epsilon;
+
+    fn default_epsilon() -> Self::Epsilon {
+        Scalar::default_epsilon()
+    }
+
+    fn abs_diff_eq(&self, other: &Self, epsilon: Self::Epsilon) -> bool {
+        self.apex.abs_diff_eq(&other.apex, epsilon)
+            && self.a.abs_diff_eq(&other.a, epsilon)
+            && self.b.abs_diff_eq(&other.b, epsilon)
+            && self.axis.abs_diff_eq(&other.axis, epsilon)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::f64::consts::{FRAC_PI_2, FRAC_PI_4};
+
+    use approx::assert_abs_diff_eq;
+
+    use crate::{Point, Scalar, Vector};
+
+    use super::Cone;
+
+    #[test]
+    fn point_from_surface_coords() {
+        let cone =
+            Cone::new([0., 0., 2.], [1., 0., 0.], [0., 1., 0.], [0., 0., -2.]);
+
+        assert_abs_diff_eq!(
+            cone.point_from_surface_coords([FRAC_PI_2, 0.5]),
+            Point::from([0., 0.5, 1.]),
+            epsilon = Scalar::from(1e-12),
+        );
+        assert_abs_diff_eq!(
+            cone.point_from_surface_coords([0., 0.]),
+            cone.apex(),
+            epsilon = Scalar::from(1e-12),
+        );
+    }
+
+    #[test]
+    fn normal_at() {
+        let cone =
+            Cone::new([0., 0., 1.], [1., 0., 0.], [0., -1., 0.], [0., 0., -1.]);
+
+        assert_abs_diff_eq!(cone.half_angle(), Scalar::from(FRAC_PI_4));
+        assert_abs_diff_eq!(
+            cone.normal_at([0., 0.5]),
+            Vector::from([1., 0., 1.]).normalize(),
+            epsilon = Scalar::from(1e-12),
+        );
+    }
+}
@@ -0,0 +1,221 @@
+use approx::AbsDiffEq;
+
+use crate::{Point, Scalar, Vector};
+
+/// An n-dimensional ellipse
+///
+/// The dimensionality of the ellipse is defined by the const generic `D`
+/// parameter.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, Hash, Ord, PartialOrd)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Ellipse<const D: usize> {
+    center: Point<D>,
+    a: Vector<D>,
+    b: Vector<D>,
+}
+
+impl<const D: usize> Ellipse<D> {
+    /// Construct an ellipse
+    ///
+    /// # Panics
+    ///
+    /// Panics, if any of the following requirements are not met:
+    ///
+    /// - Neither of the semi-axes (defined by the length of `a` and `b`) must
+    ///   be zero.
+    /// - `a` and `b` must be perpendicular to each other.
+    pub fn new(
+        center: impl Into<Point<D>>,
+        a: impl Into<Vector<D>>,
+        b: impl Into<Vector<D>>,
+    ) -> Self {
+        let center = center.into();
+        let a = a.into();
+        let b = b.into();
+
+        assert_ne!(
+            a.magnitude(),
+            Scalar::ZERO,
+            "semi-axis `a` of ellipse must not be zero"
+        );
+        assert_ne!(
+            b.magnitude(),
+            Scalar::ZERO,
+            "semi-axis `b` of ellipse must not be zero"
+        );
+        // Requiring the vectors to be *precisely* perpendicular is not
+        // practical, because of numerical inaccuracy. See `Circle::new`.
+        assert!(
+            a.dot(&b) < Scalar::default_epsilon(),
+            "`a` and `b` must be perpendicular to each other"
+        );
+
+        Self { center, a, b }
+    }
+
+    /// Construct an `Ellipse` from a center point and two radii
+    ///
+    /// The semi-axis with the first radius is aligned with the first axis of
+    /// the coordinate system, the other one with the second axis.
+    pub fn from_center_and_radii(
+        center: impl Into<Point<D>>,
+        radii: [impl Into<Scalar>; 2],
+    ) -> Self {
+        let [radius_a, radius_b] = radii.map(Into::into);
+
+        let mut a = [Scalar::ZERO; D];
+        let mut b = [Scalar::ZERO; D];
+
+        a[0] = radius_a;
+        b[1] = radius_b;
+
+        Self::new(center, a, b)
+    }
+
+    /// Access the center point of the ellipse
+    pub fn center(&self) -> Point<D> {
+        self.center
+    }
+
+    /// Access the vector that defines the starting point of the ellipse
+    ///
+    /// The point where this vector points from the ellipse center, is the zero
+    /// coordinate of the ellipse's coordinate system. The length of the vector
+    /// is the length of one of the ellipse's semi-axes.
+    ///
+    /// Please also refer to [`Self::b`].
+    pub fn a(&self) -> Vector<D> {
+        self.a
+    }
+
+    /// Access the vector that defines the plane of the ellipse
+    ///
+    /// Also defines the direction of the ellipse's coordinate system. The
+    /// length of the vector is the length of the other semi-axis, and it is
+    /// perpendicular to [`Self::a`].
+    pub fn b(&self) -> Vector<D> {
+        self.b
+    }
+
+    /// Access the length of the longer semi-axis
+    pub fn major_radius(&self) -> Scalar {
+        self.a.magnitude().max(self.b.magnitude())
+    }
+
+    /// Create a new instance that is reversed
+    #[must_use]
+    pub fn reverse(mut self) -> Self {
+        self.b = -self.b;
+        self
+    }
+
+    /// Convert a `D`-dimensional point to ellipse coordinates
+    ///
+    /// Converts the provided point into ellipse coordinates between `0.`
+    /// (inclusive) and `PI * 2.` (exclusive).
+    ///
+    /// The ellipse coordinate of a point is the parameter `t`, for which the
+    /// point is `center + a * cos(t) + b * sin(t)`. As with
+    /// [`Circle::point_to_circle_coords`], points that are not on the ellipse
+    /// are projected onto it along a line through the center, and will not
+    /// result in an error.
+    ///
+    /// [`Circle::point_to_circle_coords`]: crate::Circle::point_to_circle_coords
+    pub fn point_to_ellipse_coords(
+        &self,
+        point: impl Into<Point<D>>,
+    ) -> Point<1> {
+        let vector = point.into() - self.center;
+
+        let cos = vector.dot(&self.a) / self.a.dot(&self.a);
+        let sin = vector.dot(&self.b) / self.b.dot(&self.b);
+
+        let atan = Scalar::atan2(sin, cos);
+        let coord = if atan >= Scalar::ZERO {
+            atan
+        } else {
+            atan + Scalar::TAU
+        };
+        Point::from([coord])
+    }
+
+    /// Convert a point in ellipse coordinates into a `D`-dimensional point
+    pub fn point_from_ellipse_coords(
+        &self,
+        point: impl Into<Point<1>>,
+    ) -> Point<D> {
+        self.center + self.vector_from_ellipse_coords(point.into().coords)
+    }
+
+    /// Convert a vector in ellipse coordinates into a `D`-dimensional vector
+    pub fn vector_from_ellipse_coords(
+        &self,
+        vector: impl Into<Vector<1>>,
+    ) -> Vector<D> {
+        let angle = vector.into().t;
+        let (sin, cos) = angle.sin_cos();
+
+        self.a * cos + self.b * sin
+    }
+}
+
+impl<const D: usize> approx::AbsDiffEq for Ellipse<D> {
+    type Epsilon = <Scalar as approx::AbsDiffEq>::Epsilon;
+
+    fn default_epsilon() -> Self::Epsilon {
+        Scalar::default_epsilon()
+    }
+
+    fn abs_diff_eq(&self, other: &Self, epsilon: Self::Epsilon) -> bool {
+        self.center.abs_diff_eq(&other.center, epsilon)
+            && self.a.abs_diff_eq(&other.a, epsilon)
+            && self.b.abs_diff_eq(&other.b, epsilon)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::f64::consts::{FRAC_PI_2, PI};
+
+    use approx::assert_abs_diff_eq;
+
+    use crate::{Point, Scalar};
+
+    use super::Ellipse;
+
+    #[test]
+    fn point_to_ellipse_coords() {
+        let ellipse = Ellipse::from_center_and_radii([1., 2., 3.], [2., 1.]);
+
+        assert_eq!(
+            ellipse.point_to_ellipse_coords([3., 2., 3.]),
+            Point::from([0.]),
+        );
+        assert_eq!(
+            ellipse.point_to_ellipse_coords([1., 3., 3.]),
+            Point::from([FRAC_PI_2]),
+        );
+        assert_eq!(
+            ellipse.point_to_ellipse_coords([-1., 2., 3.]),
+            Point::from([PI]),
+        );
+        assert_eq!(
+            ellipse.point_to_ellipse_coords([1., 1., 3.]),
+            Point::from([FRAC_PI_2 * 3.]),
+        );
+    }
+
+    #[test]
+    fn point_from_ellipse_coords() {
+        let ellipse = Ellipse::from_center_and_radii([0., 0.], [2., 1.]);
+
+        for t in [0.5, 1., 2., 4.] {
+            let point = ellipse.point_from_ellipse_coords([t]);
+            assert_abs_diff_eq!(
+                ellipse.point_to_ellipse_coords(point),
+                Point::from([t]),
+                epsilon = Scalar::from(1e-12),
+            );
+        }
+    }
+}
@@ -0,0 +1,232 @@
+use approx::AbsDiffEq;
+
+use crate::{Point, Scalar, Vector};
+
+/// A helix in 3-dimensional space
+///
+/// The helix winds around an axis through its center, while advancing along
+/// that axis by its pitch with every full turn.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, Hash, Ord, PartialOrd)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Helix {
+    center: Point<3>,
+    a: Vector<3>,
+    b: Vector<3>,
+    pitch: Vector<3>,
+}
+
+impl Helix {
+    /// Construct a helix
+    ///
+    /// `a` and `b` define the circle that the helix winds around, like they do
+    /// for a [`Circle`]. `pitch` defines how far the helix advances along its
+    /// axis with each full turn.
+    ///
+    /// # Panics
+    ///
+    /// Panics, if any of the following requirements are not met:
+    ///
+    /// - The radius (defined by the length of `a` and `b`) must not be zero.
+    /// - `a` and `b` must be of equal length.
+    /// - `a` and `b` must be perpendicular to each other.
+    /// - `pitch` must be perpendicular to `a` and `b`.
+    ///
+    /// [`Circle`]: crate::Circle
+    pub fn new(
+        center: impl Into<Point<3>>,
+        a: impl Into<Vector<3>>,
+        b: impl Into<Vector<3>>,
+        pitch: impl Into<Vector<3>>,
+    ) -> Self {
+        let center = center.into();
+        let a = a.into();
+        let b = b.into();
+        let pitch = pitch.into();
+
+        // Requiring the lengths to be
+        // *precisely* equal is not practical. Transforming a helix is enough
+        // to make them deviate slightly.
+        assert!(
+            (a.magnitude() - b.magnitude()).abs()
+                <= a.magnitude() * Scalar::from(1e-12),
+            "`a` and `b` must be of equal length"
+        );
+        assert_ne!(
+            a.magnitude(),
+            Scalar::ZERO,
+            "helix radius must not be zero"
+        );
+        // Requiring the vectors to be *precisely* perpendicular is not
+        // practical, because of numerical inaccuracy. See `Circle::new`.
+        assert!(
+            a.dot(&b) < Scalar::default_epsilon(),
+            "`a` and `b` must be perpendicular to each other"
+        );
+        // The pitch is independent of the radius, so the same absolute
+        // epsilon wouldn't make sense for all helices. Scale it instead.
+        let epsilon = Scalar::from(1e-9) * a.magnitude() * pitch.magnitude();
+        assert!(
+            pitch.dot(&a).abs() <= epsilon && pitch.dot(&b).abs() <= epsilon,
+            "`pitch` must be perpendicular to `a` and `b`"
+        );
+
+        Self {
+            center,
+            a,
+            b,
+            pitch,
+        }
+    }
+
+    /// Construct a `Helix` around the z-axis, from a radius and a pitch
+    ///
+    /// The helix starts on the positive x-axis. It is right-handed for a
+    /// positive pitch, left-handed for a negative one.
+    pub fn from_radius_and_pitch(
+        radius: impl Into<Scalar>,
+        pitch: impl Into<Scalar>,
+    ) -> Self {
+        let radius = radius.into();
+        let pitch = pitch.into();
+
+        Self::new(
+            Point::origin(),
+            [radius, Scalar::ZERO, Scalar::ZERO],
+            [Scalar::ZERO, radius, Scalar::ZERO],
+            [Scalar::ZERO, Scalar::ZERO, pitch],
+        )
+    }
+
+    /// Access the center point of the helix
+    ///
+    /// This is the point on the axis of the helix, where the helix coordinate
+    /// is zero.
+    pub fn center(&self) -> Point<3> {
+        self.center
+    }
+
+    /// Access the vector that defines the starting point of the helix
+    ///
+    /// See [`Circle::a`].
+    ///
+    /// [`Circle::a`]: crate::Circle::a
+    pub fn a(&self) -> Vector<3> {
+        self.a
+    }
+
+    /// Access the vector that defines the direction of the helix's rotation
+    ///
+    /// See [`Circle::b`].
+    ///
+    /// [`Circle::b`]: crate::Circle::b
+    pub fn b(&self) -> Vector<3> {
+        self.b
+    }
+
+    /// Access the distance the helix advances along its axis per full turn
+    pub fn pitch(&self) -> Vector<3> {
+        self.pitch
+    }
+
+    /// Access the radius of the helix
+    pub fn radius(&self) -> Scalar {
+        self.a.magnitude()
+    }
+
+    /// Create a new instance that is reversed
+    #[must_use]
+    pub fn reverse(mut self) -> Self {
+        self.b = -self.b;
+        self.pitch = -self.pitch;
+        self
+    }
+
+    /// Convert a point in helix coordinates into a 3-dimensional point
+    ///
+    /// The helix coordinate of a point is the angle `t`, for which the point is
+    /// `center + a * cos(t) + b * sin(t) + pitch * t / TAU`. Unlike for a
+    /// circle, the coordinate isn't limited to a single turn.
+    pub fn point_from_helix_coords(
+        &self,
+        point: impl Into<Point<1>>,
+    ) -> Point<3> {
+        let t = point.into().t;
+        let (sin, cos) = t.sin_cos();
+
+        self.center
+            + self.a * cos
+            + self.b * sin
+            + self.pitch * (t / Scalar::TAU)
+    }
+
+    /// Compute the derivative of the helix at the given point
+    ///
+    /// The point is given in helix coordinates.
+    pub fn derivative_at(&self, point: impl Into<Point<1>>) -> Vector<3> {
+        let t = point.into().t;
+        let (sin, cos) = t.sin_cos();
+
+        self.b * cos - self.a * sin + self.pitch / Scalar::TAU
+    }
+}
+
+impl approx::AbsDiffEq for Helix {
+    type Epsilon = <Scalar as approx::AbsDiffEq>::Epsilon;
+
+    fn default_epsilon() -> Self::Epsilon {
+        Scalar::default_epsilon()
+    }
+
+    fn abs_diff_eq(&self, other: &Self, epsilon: Self::Epsilon) -> bool {
+        self.center.abs_diff_eq(&other.center, epsilon)
+            && self.a.abs_diff_eq(&other.a, epsilon)
+            && self.b.abs_diff_eq(&other.b, epsilon)
+            && self.pitch.abs_diff_eq(&other.pitch, epsilon)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::f64::consts::{FRAC_PI_2, TAU};
+
+    use approx::assert_abs_diff_eq;
+
+    use crate::{Point, Scalar, Vector};
+
+    use super::Helix;
+
+    #[test]
+    fn point_from_helix_coords() {
+        let helix = Helix::from_radius_and_pitch(2., 0.5);
+
+        assert_abs_diff_eq!(
+            helix.point_from_helix_coords([FRAC_PI_2]),
+            Point::from([0., 2., 0.125]),
+            epsilon = Scalar::from(1e-12),
+        );
+        assert_abs_diff_eq!(
+            helix.point_from_helix_coords([TAU * 3.]),
+            Point::from([2., 0., 1.5]),
+            epsilon = Scalar::from(1e-12),
+        );
+
+        let reversed = helix.reverse();
+        assert_abs_diff_eq!(
+            reversed.point_from_helix_coords([-FRAC_PI_2]),
+            helix.point_from_helix_coords([FRAC_PI_2]),
+            epsilon = Scalar::from(1e-12),
+        );
+    }
+
+    #[test]
+    fn derivative_at() {
+        let helix = Helix::from_radius_and_pitch(1., TAU);
+
+        // With a pitch of one full turn, the helix rises at 45 degrees.
+        assert_abs_diff_eq!(
+            helix.derivative_at([0.]),
+            Vector::from([0., 1., 1.]),
+            epsilon = Scalar::from(1e-12),
+        );
+    }
+}
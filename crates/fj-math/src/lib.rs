@@ -37,8 +37,13 @@
 
 mod aabb;
 mod circle;
+mod cone;
 mod coordinates;
+mod ellipse;
+mod helix;
 mod line;
+mod nurbs;
+mod nurbs_surface;
 mod plane;
 mod point;
 mod poly_chain;
@@ -51,11 +56,16 @@ mod vector;
 pub use self::{
     aabb::Aabb,
     circle::Circle,
+    cone::Cone,
     coordinates::{Uv, Xyz, T},
+    ellipse::Ellipse,
+    helix::Helix,
     line::Line,
+    nurbs::Nurbs,
+    nurbs_surface::NurbsSurface,
     plane::Plane,
     point::Point,
-    poly_chain::PolyChain,
+    poly_chain::{signed_area, PolyChain},
     scalar::{Scalar, Sign},
     segment::Segment,
     transform::Transform,
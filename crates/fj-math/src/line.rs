@@ -5,6 +5,7 @@ use crate::{Point, Scalar, Triangle, Vector};
 /// The dimensionality of the line is defined by the const generic `D`
 /// parameter.
 #[derive(Clone, Copy, Debug, Default, Eq, PartialEq, Hash, Ord, PartialOrd)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[repr(C)]
 pub struct Line<const D: usize> {
     origin: Point<D>,
@@ -0,0 +1,463 @@
+use std::ops::{Add, Mul};
+
+use crate::{Point, Scalar, Vector};
+
+/// An n-dimensional NURBS curve
+///
+/// A non-uniform rational B-spline, defined by its degree, a list of control
+/// points, a weight for each control point, and a knot vector.
+///
+/// The dimensionality of the curve is defined by the const generic `D`
+/// parameter.
+///
+/// Points on the curve are addressed by a single parameter. Only parameters
+/// within [`Nurbs::range`] are meaningful. Parameters outside of that range
+/// are clamped to it.
+#[derive(Clone, Debug, Eq, PartialEq, Hash, Ord, PartialOrd)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Nurbs<const D: usize> {
+    degree: usize,
+    control_points: Vec<Point<D>>,
+    weights: Vec<Scalar>,
+    knots: Vec<Scalar>,
+}
+
+impl<const D: usize> Nurbs<D> {
+    /// Construct a NURBS curve
+    ///
+    /// # Panics
+    ///
+    /// Panics, if any of the following requirements are not met:
+    ///
+    /// - The degree must not be zero.
+    /// - There must be more control points than the degree.
+    /// - There must be exactly one weight per control point, and all weights
+    ///   must be larger than zero.
+    /// - The number of knots must be the number of control points plus the
+    ///   degree plus one.
+    /// - The knots must be non-decreasing, and the range of the curve (see
+    ///   [`Nurbs::range`]) must not be empty.
+    pub fn new(
+        degree: usize,
+        control_points: impl IntoIterator<Item = impl Into<Point<D>>>,
+        weights: impl IntoIterator<Item = impl Into<Scalar>>,
+        knots: impl IntoIterator<Item = impl Into<Scalar>>,
+    ) -> Self {
+        let control_points = control_points
+            .into_iter()
+            .map(Into::into)
+            .collect::<Vec<_>>();
+        let weights = weights.into_iter().map(Into::into).collect::<Vec<_>>();
+        let knots = knots.into_iter().map(Into::into).collect::<Vec<_>>();
+
+        let n = control_points.len();
+
+        assert_ne!(degree, 0, "degree of NURBS curve must not be zero");
+        assert!(
+            n > degree,
+            "NURBS curve of degree {degree} needs more than {degree} control \
+            points"
+        );
+        assert_eq!(
+            weights.len(),
+            n,
+            "NURBS curve needs exactly one weight per control point"
+        );
+        assert!(
+            weights.iter().all(|&weight| weight > Scalar::ZERO),
+            "weights of NURBS curve must be larger than zero"
+        );
+        assert_eq!(
+            knots.len(),
+            n + degree + 1,
+            "NURBS curve needs `number of control points + degree + 1` knots"
+        );
+        assert!(
+            knots.windows(2).all(|knots| knots[0] <= knots[1]),
+            "knots of NURBS curve must be non-decreasing"
+        );
+        assert!(
+            knots[degree] < knots[n],
+            "range of NURBS curve must not be empty"
+        );
+
+        Self {
+            degree,
+            control_points,
+            weights,
+            knots,
+        }
+    }
+
+    /// Construct a non-rational B-spline from its control points
+    ///
+    /// All weights are set to one. The knot vector is uniform and clamped, so
+    /// the curve starts at the first control point and ends at the last one.
+    /// The range of the curve is `0..=1`.
+    pub fn from_control_points(
+        degree: usize,
+        control_points: impl IntoIterator<Item = impl Into<Point<D>>>,
+    ) -> Self {
+        let control_points = control_points
+            .into_iter()
+            .map(Into::into)
+            .collect::<Vec<_>>();
+        let n = control_points.len();
+        let knots = clamped_uniform_knots(degree, n);
+
+        Self::new(degree, control_points, vec![Scalar::ONE; n], knots)
+    }
+
+    /// Access the degree of the curve
+    pub fn degree(&self) -> usize {
+        self.degree
+    }
+
+    /// Access the control points of the curve
+    pub fn control_points(&self) -> &[Point<D>] {
+        &self.control_points
+    }
+
+    /// Access the weights of the control points
+    pub fn weights(&self) -> &[Scalar] {
+        &self.weights
+    }
+
+    /// Access the knot vector of the curve
+    pub fn knots(&self) -> &[Scalar] {
+        &self.knots
+    }
+
+    /// Access the range of parameters that define the curve
+    pub fn range(&self) -> [Scalar; 2] {
+        [
+            self.knots[self.degree],
+            self.knots[self.control_points.len()],
+        ]
+    }
+
+    /// Access the distinct knots within the range of the curve
+    ///
+    /// Between two consecutive of those knots, the curve is a single rational
+    /// polynomial. This makes them a good starting point for algorithms that
+    /// need to subdivide the curve.
+    pub fn spans(&self) -> Vec<Scalar> {
+        let [start, end] = self.range();
+
+        let mut spans: Vec<Scalar> = Vec::new();
+        for &knot in &self.knots {
+            if knot < start || knot > end {
+                continue;
+            }
+            if spans.last() != Some(&knot) {
+                spans.push(knot);
+            }
+        }
+
+        spans
+    }
+
+    /// Create a new instance that is reversed
+    ///
+    /// The range of the reversed curve is the same as that of the original.
+    #[must_use]
+    pub fn reverse(mut self) -> Self {
+        let [start, end] = self.range();
+
+        self.control_points.reverse();
+        self.weights.reverse();
+        self.knots = self
+            .knots
+            .iter()
+            .rev()
+            .map(|&knot| start + end - knot)
+            .collect();
+
+        self
+    }
+
+    /// Convert a point in curve coordinates into a `D`-dimensional point
+    pub fn point_from_nurbs_coords(
+        &self,
+        point: impl Into<Point<1>>,
+    ) -> Point<D> {
+        let t = self.clamp(point.into().t);
+
+        let control_points = self
+            .control_points
+            .iter()
+            .zip(&self.weights)
+            .map(|(&point, &weight)| Homogeneous::new(point, weight))
+            .collect::<Vec<_>>();
+
+        de_boor(self.degree, &self.knots, &control_points, t).to_point()
+    }
+
+    /// Compute the derivative of the curve at the given point
+    ///
+    /// The point is given in curve coordinates. The returned vector is tangent
+    /// to the curve, and its length is the speed at which the curve moves
+    /// through space, as the parameter changes.
+    pub fn derivative_at(&self, point: impl Into<Point<1>>) -> Vector<D> {
+        let t = self.clamp(point.into().t);
+
+        let control_points = self
+            .control_points
+            .iter()
+            .zip(&self.weights)
+            .map(|(&point, &weight)| Homogeneous::new(point, weight))
+            .collect::<Vec<_>>();
+
+        let a = de_boor(self.degree, &self.knots, &control_points, t);
+        let da =
+            de_boor_derivative(self.degree, &self.knots, &control_points, t);
+
+        a.derivative_to_cartesian(da)
+    }
+
+    fn clamp(&self, t: Scalar) -> Scalar {
+        let [start, end] = self.range();
+        Ord::min(t.max(start), end)
+    }
+}
+
+/// A control point in homogeneous coordinates
+///
+/// The coordinates are pre-multiplied with the weight.
+#[derive(Clone, Copy)]
+pub(crate) struct Homogeneous<const D: usize> {
+    coords: Vector<D>,
+    weight: Scalar,
+}
+
+impl<const D: usize> Homogeneous<D> {
+    pub(crate) fn new(point: Point<D>, weight: Scalar) -> Self {
+        Self {
+            coords: point.coords * weight,
+            weight,
+        }
+    }
+
+    fn zero() -> Self {
+        Self {
+            coords: Vector::from([Scalar::ZERO; D]),
+            weight: Scalar::ZERO,
+        }
+    }
+
+    pub(crate) fn to_point(self) -> Point<D> {
+        Point {
+            coords: self.coords / self.weight,
+        }
+    }
+
+    /// Convert a derivative in homogeneous coordinates to Cartesian ones
+    ///
+    /// Expects `self` to be the point at which the derivative was taken.
+    pub(crate) fn derivative_to_cartesian(self, derivative: Self) -> Vector<D> {
+        // This is the quotient rule.
+        let point = self.to_point().coords;
+        (derivative.coords - point * derivative.weight) / self.weight
+    }
+}
+
+impl<const D: usize> Add for Homogeneous<D> {
+    type Output = Self;
+
+    fn add(self, other: Self) -> Self {
+        Self {
+            coords: self.coords + other.coords,
+            weight: self.weight + other.weight,
+        }
+    }
+}
+
+impl<const D: usize> Mul<Scalar> for Homogeneous<D> {
+    type Output = Self;
+
+    fn mul(self, scalar: Scalar) -> Self {
+        Self {
+            coords: self.coords * scalar,
+            weight: self.weight * scalar,
+        }
+    }
+}
+
+/// Compute a clamped, uniform knot vector over the range `0..=1`
+pub(crate) fn clamped_uniform_knots(degree: usize, n: usize) -> Vec<Scalar> {
+    // Make sure we don't underflow below. The constructors that use this will
+    // panic with a proper error message.
+    let num_spans = n.saturating_sub(degree).max(1);
+
+    vec![Scalar::ZERO; degree]
+        .into_iter()
+        .chain(
+            (0..=num_spans).map(|i| {
+                Scalar::from(i as f64) / Scalar::from(num_spans as f64)
+            }),
+        )
+        .chain(vec![Scalar::ONE; degree])
+        .collect()
+}
+
+/// Evaluate a B-spline in homogeneous coordinates, using de Boor's algorithm
+pub(crate) fn de_boor<const D: usize>(
+    degree: usize,
+    knots: &[Scalar],
+    control_points: &[Homogeneous<D>],
+    t: Scalar,
+) -> Homogeneous<D> {
+    let p = degree;
+    let n = control_points.len();
+
+    // Find the knot span that contains `t`. If `t` is at the end of the range,
+    // that is the last non-empty span.
+    let mut k = p;
+    for i in p..n {
+        if knots[i] <= t && knots[i] < knots[i + 1] {
+            k = i;
+        }
+    }
+
+    let mut d = control_points[k - p..=k].to_vec();
+
+    for r in 1..=p {
+        for j in (r..=p).rev() {
+            let left = knots[j + k - p];
+            let right = knots[j + 1 + k - r];
+
+            let alpha = (t - left) / (right - left);
+            d[j] = d[j - 1] * (Scalar::ONE - alpha) + d[j] * alpha;
+        }
+    }
+
+    d[p]
+}
+
+/// Evaluate the derivative of a B-spline in homogeneous coordinates
+pub(crate) fn de_boor_derivative<const D: usize>(
+    degree: usize,
+    knots: &[Scalar],
+    control_points: &[Homogeneous<D>],
+    t: Scalar,
+) -> Homogeneous<D> {
+    let p = degree;
+
+    // The derivative of a B-spline is another B-spline, of one degree less.
+    let derivative_points = control_points
+        .windows(2)
+        .enumerate()
+        .map(|(i, points)| {
+            let span = knots[i + p + 1] - knots[i + 1];
+            if span == Scalar::ZERO {
+                return Homogeneous::zero();
+            }
+
+            (points[1] + points[0] * -Scalar::ONE)
+                * (Scalar::from(p as f64) / span)
+        })
+        .collect::<Vec<_>>();
+    let derivative_knots = &knots[1..knots.len() - 1];
+
+    de_boor(p - 1, derivative_knots, &derivative_points, t)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::f64::consts::FRAC_1_SQRT_2;
+
+    use approx::assert_abs_diff_eq;
+
+    use crate::{Point, Scalar, Vector};
+
+    use super::Nurbs;
+
+    #[test]
+    fn from_control_points() {
+        let nurbs = Nurbs::from_control_points(
+            2,
+            [[0., 0.], [1., 1.], [2., 0.], [3., 1.]],
+        );
+
+        assert_eq!(
+            nurbs.knots(),
+            [0., 0., 0., 0.5, 1., 1., 1.].map(Scalar::from)
+        );
+        assert_eq!(nurbs.range(), [Scalar::ZERO, Scalar::ONE]);
+        assert_eq!(nurbs.spans(), [0., 0.5, 1.].map(Scalar::from));
+
+        // A clamped B-spline starts and ends at its outer control points.
+        assert_eq!(nurbs.point_from_nurbs_coords([0.]), Point::from([0., 0.]));
+        assert_eq!(nurbs.point_from_nurbs_coords([1.]), Point::from([3., 1.]));
+    }
+
+    #[test]
+    fn quarter_circle() {
+        let nurbs = quarter_circle_nurbs();
+
+        for i in 0..=10 {
+            let t = i as f64 / 10.;
+
+            let point = nurbs.point_from_nurbs_coords([t]);
+            assert_abs_diff_eq!(
+                point.distance_to(&Point::origin()),
+                Scalar::ONE,
+                epsilon = Scalar::from(1e-12),
+            );
+
+            // The tangent of a circle is perpendicular to the radius.
+            let derivative = nurbs.derivative_at([t]);
+            assert_abs_diff_eq!(
+                derivative.dot(&point.coords),
+                Scalar::ZERO,
+                epsilon = Scalar::from(1e-12),
+            );
+        }
+    }
+
+    #[test]
+    fn derivative() {
+        let nurbs = quarter_circle_nurbs();
+        let h = 1e-6;
+
+        for t in [0.1, 0.5, 0.9] {
+            let a = nurbs.point_from_nurbs_coords([t - h]);
+            let b = nurbs.point_from_nurbs_coords([t + h]);
+            let expected = (b - a) / (2. * h);
+
+            assert_abs_diff_eq!(
+                nurbs.derivative_at([t]),
+                expected,
+                epsilon = Scalar::from(1e-6),
+            );
+        }
+
+        // The derivative of a B-spline of degree 1 is constant on each span.
+        let nurbs = Nurbs::from_control_points(1, [[0., 0.], [2., 0.]]);
+        assert_eq!(nurbs.derivative_at([0.5]), Vector::from([2., 0.]));
+    }
+
+    #[test]
+    fn reverse() {
+        let nurbs = quarter_circle_nurbs();
+        let reversed = nurbs.clone().reverse();
+
+        assert_eq!(reversed.range(), nurbs.range());
+        for t in [0., 0.25, 0.5, 1.] {
+            assert_abs_diff_eq!(
+                reversed.point_from_nurbs_coords([t]),
+                nurbs.point_from_nurbs_coords([1. - t]),
+                epsilon = Scalar::from(1e-12),
+            );
+        }
+    }
+
+    fn quarter_circle_nurbs() -> Nurbs<2> {
+        Nurbs::new(
+            2,
+            [[1., 0.], [1., 1.], [0., 1.]],
+            [1., FRAC_1_SQRT_2, 1.],
+            [0., 0., 0., 1., 1., 1.],
+        )
+    }
+}
@@ -0,0 +1,383 @@
+use crate::{
+    nurbs::{clamped_uniform_knots, de_boor, de_boor_derivative, Homogeneous},
+    Point, Scalar, Vector,
+};
+
+/// An n-dimensional NURBS surface
+///
+/// A tensor-product non-uniform rational B-spline surface. It is defined by a
+/// degree and a knot vector for each of its two parameter directions, `u` and
+/// `v`, as well as a grid of control points with a weight for each of them.
+///
+/// The control points are indexed by `[i][j]`, with `i` running along the
+/// u-direction and `j` along the v-direction.
+///
+/// The dimensionality of the surface is defined by the const generic `D`
+/// parameter.
+///
+/// Points on the surface are addressed by two parameters. Only parameters
+/// within [`NurbsSurface::range`] are meaningful. Parameters outside of that
+/// range are clamped to it.
+#[derive(Clone, Debug, Eq, PartialEq, Hash, Ord, PartialOrd)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct NurbsSurface<const D: usize> {
+    degrees: [usize; 2],
+    control_points: Vec<Vec<Point<D>>>,
+    weights: Vec<Vec<Scalar>>,
+    knots: [Vec<Scalar>; 2],
+}
+
+impl<const D: usize> NurbsSurface<D> {
+    /// Construct a NURBS surface
+    ///
+    /// # Panics
+    ///
+    /// Panics, if any of the following requirements are not met:
+    ///
+    /// - The control points must form a rectangular grid.
+    /// - There must be exactly one weight per control point, and all weights
+    ///   must be larger than zero.
+    /// - In each direction, the requirements for the degree, the number of
+    ///   control points, and the knots are the same as those of
+    ///   [`Nurbs::new`](crate::Nurbs::new).
+    pub fn new(
+        degrees: [usize; 2],
+        control_points: impl IntoIterator<
+            Item = impl IntoIterator<Item = impl Into<Point<D>>>,
+        >,
+        weights: impl IntoIterator<
+            Item = impl IntoIterator<Item = impl Into<Scalar>>,
+        >,
+        knots: [impl IntoIterator<Item = impl Into<Scalar>>; 2],
+    ) -> Self {
+        let control_points = control_points
+            .into_iter()
+            .map(|row| row.into_iter().map(Into::into).collect::<Vec<_>>())
+            .collect::<Vec<_>>();
+        let weights = weights
+            .into_iter()
+            .map(|row| row.into_iter().map(Into::into).collect::<Vec<_>>())
+            .collect::<Vec<_>>();
+        let knots = knots
+            .map(|knots| knots.into_iter().map(Into::into).collect::<Vec<_>>());
+
+        let n_u = control_points.len();
+        let n_v = control_points.first().map(Vec::len).unwrap_or(0);
+
+        assert!(
+            control_points.iter().all(|row| row.len() == n_v),
+            "control points of NURBS surface must form a rectangular grid"
+        );
+        assert!(
+            weights.len() == n_u && weights.iter().all(|row| row.len() == n_v),
+            "NURBS surface needs exactly one weight per control point"
+        );
+        assert!(
+            weights
+                .iter()
+                .flatten()
+                .all(|&weight| weight > Scalar::ZERO),
+            "weights of NURBS surface must be larger than zero"
+        );
+
+        for ((degree, n), knots) in degrees.iter().zip([n_u, n_v]).zip(&knots) {
+            let degree = *degree;
+
+            assert_ne!(degree, 0, "degree of NURBS surface must not be zero");
+            assert!(
+                n > degree,
+                "NURBS surface of degree {degree} needs more than {degree} \
+                control points in each direction"
+            );
+            assert_eq!(
+                knots.len(),
+                n + degree + 1,
+                "NURBS surface needs `number of control points + degree + 1` \
+                knots in each direction"
+            );
+            assert!(
+                knots.windows(2).all(|knots| knots[0] <= knots[1]),
+                "knots of NURBS surface must be non-decreasing"
+            );
+            assert!(
+                knots[degree] < knots[n],
+                "range of NURBS surface must not be empty"
+            );
+        }
+
+        Self {
+            degrees,
+            control_points,
+            weights,
+            knots,
+        }
+    }
+
+    /// Construct a non-rational B-spline surface from its control points
+    ///
+    /// All weights are set to one. The knot vectors are uniform and clamped,
+    /// so the corners of the surface are the corners of the control point
+    /// grid. The range of the surface is `0..=1` in both directions.
+    pub fn from_control_points(
+        degrees: [usize; 2],
+        control_points: impl IntoIterator<
+            Item = impl IntoIterator<Item = impl Into<Point<D>>>,
+        >,
+    ) -> Self {
+        let control_points = control_points
+            .into_iter()
+            .map(|row| row.into_iter().map(Into::into).collect::<Vec<_>>())
+            .collect::<Vec<_>>();
+
+        let n_u = control_points.len();
+        let n_v = control_points.first().map(Vec::len).unwrap_or(0);
+
+        let weights = vec![vec![Scalar::ONE; n_v]; n_u];
+        let knots = [
+            clamped_uniform_knots(degrees[0], n_u),
+            clamped_uniform_knots(degrees[1], n_v),
+        ];
+
+        Self::new(degrees, control_points, weights, knots)
+    }
+
+    /// Access the degrees of the surface, in u- and v-direction
+    pub fn degrees(&self) -> [usize; 2] {
+        self.degrees
+    }
+
+    /// Access the control points of the surface
+    pub fn control_points(&self) -> &[Vec<Point<D>>] {
+        &self.control_points
+    }
+
+    /// Access the weights of the control points
+    pub fn weights(&self) -> &[Vec<Scalar>] {
+        &self.weights
+    }
+
+    /// Access the knot vectors of the surface, in u- and v-direction
+    pub fn knots(&self) -> [&[Scalar]; 2] {
+        [&self.knots[0], &self.knots[1]]
+    }
+
+    /// Access the range of parameters that define the surface
+    ///
+    /// Returns the range in u-direction, followed by the range in v-direction.
+    pub fn range(&self) -> [[Scalar; 2]; 2] {
+        let n_u = self.control_points.len();
+        let n_v = self.control_points[0].len();
+
+        let [p, q] = self.degrees;
+        let [knots_u, knots_v] = &self.knots;
+
+        [[knots_u[p], knots_u[n_u]], [knots_v[q], knots_v[n_v]]]
+    }
+
+    /// Access the distinct knots within the range of the surface
+    ///
+    /// Returns the knots in u-direction, followed by those in v-direction. See
+    /// [`Nurbs::spans`](crate::Nurbs::spans).
+    pub fn spans(&self) -> [Vec<Scalar>; 2] {
+        let range = self.range();
+
+        [0, 1].map(|i| {
+            let [start, end] = range[i];
+
+            let mut spans: Vec<Scalar> = Vec::new();
+            for &knot in &self.knots[i] {
+                if knot < start || knot > end {
+                    continue;
+                }
+                if spans.last() != Some(&knot) {
+                    spans.push(knot);
+                }
+            }
+
+            spans
+        })
+    }
+
+    /// Convert a point in surface coordinates into a `D`-dimensional point
+    pub fn point_from_surface_coords(
+        &self,
+        point: impl Into<Point<2>>,
+    ) -> Point<D> {
+        let [u, v] = self.clamp(point.into());
+        let [p, _] = self.degrees;
+
+        let column = self.evaluate_rows(v);
+        de_boor(p, &self.knots[0], &column, u).to_point()
+    }
+
+    /// Compute the partial derivatives of the surface at the given point
+    ///
+    /// The point is given in surface coordinates. Returns the derivative in
+    /// u-direction, followed by the derivative in v-direction.
+    pub fn derivatives_at(&self, point: impl Into<Point<2>>) -> [Vector<D>; 2] {
+        let [u, v] = self.clamp(point.into());
+        let [p, q] = self.degrees;
+
+        // Reduce the surface to a curve in each direction, then take the
+        // derivative of that curve.
+        let column = self.evaluate_rows(v);
+        let a = de_boor(p, &self.knots[0], &column, u);
+        let du = de_boor_derivative(p, &self.knots[0], &column, u);
+
+        let row = self.evaluate_columns(u);
+        let b = de_boor(q, &self.knots[1], &row, v);
+        let dv = de_boor_derivative(q, &self.knots[1], &row, v);
+
+        [a.derivative_to_cartesian(du), b.derivative_to_cartesian(dv)]
+    }
+
+    /// Evaluate each row of control points (constant `i`) at `v`
+    fn evaluate_rows(&self, v: Scalar) -> Vec<Homogeneous<D>> {
+        let [_, q] = self.degrees;
+
+        self.control_points
+            .iter()
+            .zip(&self.weights)
+            .map(|(points, weights)| {
+                let points = points
+                    .iter()
+                    .zip(weights)
+                    .map(|(&point, &weight)| Homogeneous::new(point, weight))
+                    .collect::<Vec<_>>();
+                de_boor(q, &self.knots[1], &points, v)
+            })
+            .collect()
+    }
+
+    /// Evaluate each column of control points (constant `j`) at `u`
+    fn evaluate_columns(&self, u: Scalar) -> Vec<Homogeneous<D>> {
+        let [p, _] = self.degrees;
+
+        (0..self.control_points[0].len())
+            .map(|j| {
+                let points = self
+                    .control_points
+                    .iter()
+                    .zip(&self.weights)
+                    .map(|(points, weights)| {
+                        Homogeneous::new(points[j], weights[j])
+                    })
+                    .collect::<Vec<_>>();
+                de_boor(p, &self.knots[0], &points, u)
+            })
+            .collect()
+    }
+
+    fn clamp(&self, point: Point<2>) -> [Scalar; 2] {
+        let [[u_start, u_end], [v_start, v_end]] = self.range();
+        [
+            Ord::min(point.u.max(u_start), u_end),
+            Ord::min(point.v.max(v_start), v_end),
+        ]
+    }
+}
+
+impl NurbsSurface<3> {
+    /// Compute the normal of the surface at the given point
+    ///
+    /// The point is given in surface coordinates. The normal is the normalized
+    /// cross product of the derivatives in u- and v-direction.
+    pub fn normal_at(&self, point: impl Into<Point<2>>) -> Vector<3> {
+        let [du, dv] = self.derivatives_at(point);
+        du.cross(&dv).normalize()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::f64::consts::FRAC_1_SQRT_2;
+
+    use approx::assert_abs_diff_eq;
+
+    use crate::{Point, Scalar, Vector};
+
+    use super::NurbsSurface;
+
+    #[test]
+    fn bilinear() {
+        let surface = NurbsSurface::from_control_points(
+            [1, 1],
+            [[[0., 0., 0.], [0., 2., 0.]], [[2., 0., 0.], [2., 2., 1.]]],
+        );
+
+        assert_eq!(surface.range(), [[Scalar::ZERO, Scalar::ONE]; 2]);
+        assert_eq!(
+            surface.point_from_surface_coords([0.5, 0.5]),
+            Point::from([1., 1., 0.25])
+        );
+        assert_eq!(
+            surface.derivatives_at([0.5, 0.5]),
+            [Vector::from([2., 0., 0.5]), Vector::from([0., 2., 0.5])]
+        );
+    }
+
+    #[test]
+    fn cylinder() {
+        // A quarter of a cylinder around the z-axis, with a radius of one. The
+        // circular arc runs along the u-direction.
+        let w = FRAC_1_SQRT_2;
+        let surface = NurbsSurface::new(
+            [2, 1],
+            [
+                [[1., 0., 0.], [1., 0., 1.]],
+                [[1., 1., 0.], [1., 1., 1.]],
+                [[0., 1., 0.], [0., 1., 1.]],
+            ],
+            [[1., 1.], [w, w], [1., 1.]],
+            [vec![0., 0., 0., 1., 1., 1.], vec![0., 0., 1., 1.]],
+        );
+
+        for [u, v] in [[0., 0.], [0.25, 0.5], [0.5, 0.25], [1., 1.]] {
+            let point = surface.point_from_surface_coords([u, v]);
+            let radius = point.coords.xy().magnitude();
+
+            assert_abs_diff_eq!(
+                radius,
+                Scalar::ONE,
+                epsilon = Scalar::from(1e-12)
+            );
+            assert_abs_diff_eq!(point.z, Scalar::from(v));
+
+            // The normal of a cylinder points away from its axis.
+            let normal = surface.normal_at([u, v]);
+            let expected = Vector::from([point.x, point.y, Scalar::ZERO]);
+            assert_abs_diff_eq!(
+                normal.dot(&expected).abs(),
+                Scalar::ONE,
+                epsilon = Scalar::from(1e-12),
+            );
+        }
+    }
+
+    #[test]
+    fn derivatives() {
+        let surface = NurbsSurface::from_control_points(
+            [2, 2],
+            [
+                [[0., 0., 0.], [0., 1., 1.], [0., 2., 0.]],
+                [[1., 0., 1.], [1., 1., 2.], [1., 2., 1.]],
+                [[2., 0., 0.], [2., 1., 1.], [2., 2., 0.]],
+            ],
+        );
+        let h = 1e-6;
+
+        for [u, v] in [[0.2, 0.3], [0.5, 0.5], [0.9, 0.1]] {
+            let [du, dv] = surface.derivatives_at([u, v]);
+
+            let expected_du = (surface.point_from_surface_coords([u + h, v])
+                - surface.point_from_surface_coords([u - h, v]))
+                / (2. * h);
+            let expected_dv = (surface.point_from_surface_coords([u, v + h])
+                - surface.point_from_surface_coords([u, v - h]))
+                / (2. * h);
+
+            assert_abs_diff_eq!(du, expected_du, epsilon = Scalar::from(1e-6));
+            assert_abs_diff_eq!(dv, expected_dv, epsilon = Scalar::from(1e-6));
+        }
+    }
+}
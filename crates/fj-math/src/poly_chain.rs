@@ -1,4 +1,4 @@
-use crate::{Point, Segment};
+use crate::{Point, Scalar, Segment};
 
 /// A polygonal chain
 ///
@@ -82,3 +82,38 @@ where
         Self::from_points(points)
     }
 }
+
+/// Compute the signed area of a closed polygon
+///
+/// The area is positive, if the polygon is wound counter-clockwise, and
+/// negative, if it is wound clockwise. The first point must not be repeated at
+/// the end.
+pub fn signed_area(polygon: &[Point<2>]) -> Scalar {
+    let doubled = polygon
+        .iter()
+        .zip(polygon.iter().cycle().skip(1))
+        .fold(Scalar::ZERO, |area, (a, b)| {
+            area + a.coords.cross2d(&b.coords)
+        });
+
+    doubled / 2.
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Point, Scalar};
+
+    use super::signed_area;
+
+    #[test]
+    fn signed_area_of_polygon() {
+        let square = [[0., 0.], [2., 0.], [2., 2.], [0., 2.]].map(Point::from);
+        assert_eq!(signed_area(&square), Scalar::from(4.));
+
+        let mut reversed = square;
+        reversed.reverse();
+        assert_eq!(signed_area(&reversed), Scalar::from(-4.));
+
+        assert_eq!(signed_area(&[]), Scalar::ZERO);
+    }
+}
@@ -139,6 +139,11 @@ impl Scalar {
         (sin.into(), cos.into())
     }
 
+    /// Compute the tangent
+    pub fn tan(self) -> Self {
+        self.0.tan().into()
+    }
+
     /// Compute the arccosine
     pub fn acos(self) -> Self {
         self.0.acos().into()
@@ -589,6 +594,33 @@ impl approx::AbsDiffEq for Scalar {
     }
 }
 
+// Serialized as a plain number. Deserialization fails for NaN, instead of
+// panicking like `Scalar::from_f64` would.
+#[cfg(feature = "serde")]
+impl serde::Serialize for Scalar {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        serializer.serialize_f64(self.0)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for Scalar {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        let scalar = <f64 as serde::Deserialize>::deserialize(deserializer)?;
+        if scalar.is_nan() {
+            return Err(serde::de::Error::custom("Scalar must not be NaN"));
+        }
+
+        Ok(Self(scalar))
+    }
+}
+
 /// The sign of a [`Scalar`]
 ///
 /// See [`Scalar::sign`]
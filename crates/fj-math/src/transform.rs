@@ -1,8 +1,8 @@
 use std::ops;
 
-use nalgebra::Perspective3;
+use nalgebra::{Orthographic3, Perspective3};
 
-use crate::{Circle, Line, Scalar};
+use crate::{Circle, Cone, Ellipse, Helix, Line, Nurbs, NurbsSurface, Scalar};
 
 use super::{Aabb, Point, Segment, Triangle, Vector};
 
@@ -40,6 +40,67 @@ impl Transform {
         ))
     }
 
+    /// Construct a uniform scaling around the origin
+    pub fn scaling(factor: impl Into<Scalar>) -> Self {
+        let factor = factor.into().into_f64();
+
+        Self(nalgebra::Transform::from_matrix_unchecked(
+            nalgebra::Matrix4::new_scaling(factor),
+        ))
+    }
+
+    /// Construct a reflection across a plane
+    ///
+    /// The plane is defined by a point on it, and its normal. The normal does
+    /// not need to be normalized.
+    pub fn reflection(
+        origin: impl Into<Point<3>>,
+        normal: impl Into<Vector<3>>,
+    ) -> Self {
+        let origin = origin.into().coords.to_na();
+        let normal = normal.into().normalize().to_na();
+
+        let linear =
+            nalgebra::Matrix3::identity() - normal * normal.transpose() * 2.;
+        let translation = normal * origin.dot(&normal) * 2.;
+
+        let mut matrix = linear.to_homogeneous();
+        matrix.fixed_slice_mut::<3, 1>(0, 3).copy_from(&translation);
+
+        Self(nalgebra::Transform::from_matrix_unchecked(matrix))
+    }
+
+    /// Construct a transform from a coordinate frame
+    ///
+    /// The resulting transform maps the origin to `origin`, and the x, y, and z
+    /// axes to the respective vectors of `basis`. The vectors of `basis` are
+    /// expected to be orthonormal.
+    pub fn from_frame(
+        origin: impl Into<Point<3>>,
+        basis: [Vector<3>; 3],
+    ) -> Self {
+        let origin = origin.into();
+        let [x, y, z] = basis.map(|axis| axis.to_na().push(0.));
+
+        Self(nalgebra::Transform::from_matrix_unchecked(
+            nalgebra::Matrix4::from_columns(&[
+                x,
+                y,
+                z,
+                origin.coords.to_na().push(1.),
+            ]),
+        ))
+    }
+
+    /// Indicate whether the transform inverts orientation
+    ///
+    /// This is the case for transforms that include a reflection. Objects that
+    /// have an orientation, like faces, need to be reversed after such a
+    /// transform, to keep their orientation.
+    pub fn inverts_orientation(&self) -> bool {
+        self.0.matrix().fixed_slice::<3, 3>(0, 0).determinant() < 0.
+    }
+
     /// Transform the given point
     pub fn transform_point(&self, point: &Point<3>) -> Point<3> {
         Point::from(self.0.transform_point(&point.to_na()))
@@ -88,6 +149,65 @@ impl Transform {
         )
     }
 
+    /// Transform the given ellipse
+    pub fn transform_ellipse(&self, ellipse: &Ellipse<3>) -> Ellipse<3> {
+        Ellipse::new(
+            self.transform_point(&ellipse.center()),
+            self.transform_vector(&ellipse.a()),
+            self.transform_vector(&ellipse.b()),
+        )
+    }
+
+    /// Transform the given helix
+    pub fn transform_helix(&self, helix: &Helix) -> Helix {
+        Helix::new(
+            self.transform_point(&helix.center()),
+            self.transform_vector(&helix.a()),
+            self.transform_vector(&helix.b()),
+            self.transform_vector(&helix.pitch()),
+        )
+    }
+
+    /// Transform the given cone
+    pub fn transform_cone(&self, cone: &Cone) -> Cone {
+        Cone::new(
+            self.transform_point(&cone.apex()),
+            self.transform_vector(&cone.a()),
+            self.transform_vector(&cone.b()),
+            self.transform_vector(&cone.axis()),
+        )
+    }
+
+    /// Transform the given NURBS curve
+    pub fn transform_nurbs(&self, nurbs: &Nurbs<3>) -> Nurbs<3> {
+        Nurbs::new(
+            nurbs.degree(),
+            nurbs
+                .control_points()
+                .iter()
+                .map(|point| self.transform_point(point)),
+            nurbs.weights().iter().copied(),
+            nurbs.knots().iter().copied(),
+        )
+    }
+
+    /// Transform the given NURBS surface
+    pub fn transform_nurbs_surface(
+        &self,
+        surface: &NurbsSurface<3>,
+    ) -> NurbsSurface<3> {
+        NurbsSurface::new(
+            surface.degrees(),
+            surface.control_points().iter().map(|row| {
+                row.iter()
+                    .map(|point| self.transform_point(point))
+                    .collect::<Vec<_>>()
+            }),
+            surface.weights().to_vec(),
+            surface.knots().map(|knots| knots.to_vec()),
+        )
+    }
+
     /// Inverse transform
     pub fn inverse(&self) -> Transform {
         Self(self.0.inverse())
@@ -119,6 +239,29 @@ impl Transform {
         array.map(Scalar::from)
     }
 
+    /// Project transform according to an orthographic camera specification,
+    /// return data as an array.
+    ///
+    /// `width` and `height` are the size of the visible area. Used primarily
+    /// for graphics code.
+    pub fn project_orthographic_to_array(
+        &self,
+        width: f64,
+        height: f64,
+        znear: f64,
+        zfar: f64,
+    ) -> [Scalar; 16] {
+        let [x, y] = [width / 2., height / 2.];
+        let projection = Orthographic3::new(-x, x, -y, y, znear, zfar);
+
+        let mut array = [0.; 16];
+        array.copy_from_slice(
+            (projection.to_projective() * self.0).matrix().as_slice(),
+        );
+
+        array.map(Scalar::from)
+    }
+
     /// Transform the given axis-aligned bounding box
     pub fn transform_aabb(&self, aabb: &Aabb<3>) -> Aabb<3> {
         Aabb {
@@ -182,6 +325,55 @@ mod tests {
         );
     }
 
+    #[test]
+    fn from_frame() {
+        let transform = Transform::from_frame(
+            [1., 2., 3.],
+            [Vector::unit_y(), -Vector::unit_x(), Vector::unit_z()],
+        );
+
+        assert_abs_diff_eq!(
+            transform.transform_point(&Point::from([1., 0., 0.])),
+            Point::from([1., 3., 3.]),
+            epsilon = Scalar::from(1e-8),
+        );
+        assert_abs_diff_eq!(
+            transform.data(),
+            (Transform::translation([1., 2., 3.])
+                * Transform::rotation(Vector::unit_z() * (Scalar::PI / 2.)))
+            .data(),
+            epsilon = 1e-8,
+        );
+    }
+
+    #[test]
+    fn scaling() {
+        let transform = Transform::scaling(25.4);
+
+        assert_abs_diff_eq!(
+            transform.transform_point(&Point::from([1., 2., 3.])),
+            Point::from([25.4, 50.8, 76.2]),
+            epsilon = Scalar::from(1e-8),
+        );
+        assert!(!transform.inverts_orientation());
+    }
+
+    #[test]
+    fn reflection() {
+        let transform = Transform::reflection([0., 0., 1.], [0., 0., 2.]);
+
+        assert_abs_diff_eq!(
+            transform.transform_point(&Point::from([1., 2., 3.])),
+            Point::from([1., 2., -1.]),
+            epsilon = Scalar::from(1e-8),
+        );
+        assert!(transform.inverts_orientation());
+
+        let rotation =
+            Transform::rotation(Vector::unit_z() * (Scalar::PI / 2.));
+        assert!(!rotation.inverts_orientation());
+    }
+
     #[test]
     fn extract_rotation_translation() {
         let rotation =
@@ -352,6 +352,58 @@ impl<const D: usize> approx::AbsDiffEq for Vector<D> {
     }
 }
 
+// Serialized as a tuple of its components. `serde` doesn't support arrays of
+// arbitrary length, so this can't be derived.
+#[cfg(feature = "serde")]
+impl<const D: usize> serde::Serialize for Vector<D> {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        use serde::ser::SerializeTuple as _;
+
+        let mut tuple = serializer.serialize_tuple(D)?;
+        for component in &self.components {
+            tuple.serialize_element(component)?;
+        }
+        tuple.end()
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de, const D: usize> serde::Deserialize<'de> for Vector<D> {
+    fn deserialize<De>(deserializer: De) -> Result<Self, De::Error>
+    where
+        De: serde::Deserializer<'de>,
+    {
+        struct Visitor<const D: usize>;
+
+        impl<'de, const D: usize> serde::de::Visitor<'de> for Visitor<D> {
+            type Value = Vector<D>;
+
+            fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                write!(f, "a tuple of {D} components")
+            }
+
+            fn visit_seq<A>(self, mut seq: A) -> Result<Self::Value, A::Error>
+            where
+                A: serde::de::SeqAccess<'de>,
+            {
+                let mut components = [Scalar::ZERO; D];
+                for (i, component) in components.iter_mut().enumerate() {
+                    *component = seq.next_element()?.ok_or_else(|| {
+                        serde::de::Error::invalid_length(i, &self)
+                    })?;
+                }
+
+                Ok(Vector { components })
+            }
+        }
+
+        deserializer.deserialize_tuple(D, Visitor::<D>)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use crate::{Scalar, Vector};
@@ -0,0 +1,721 @@
+//! Placement of the instances of an assembly
+//!
+//! See [`solve`].
+
+use std::fmt;
+
+use fj_interop::debug::DebugInfo;
+use fj_kernel::{
+    objects::{FaceSet, Objects},
+    services::Service,
+};
+use fj_math::{Aabb, Point, Scalar, Transform, Vector};
+use tracing::warn;
+
+use crate::{instrument::instrument, Shape};
+
+impl Shape for fj::Assembly {
+    type Brep = FaceSet;
+
+    fn compute_brep(
+        &self,
+        objects: &mut Service<Objects>,
+        debug_info: &mut DebugInfo,
+    ) -> Self::Brep {
+        instrument("Assembly", debug_info, |debug_info| {
+            let mut faces = FaceSet::new();
+
+            for part in place_instances(self) {
+                faces.extend(part.shape().compute_brep(objects, debug_info));
+            }
+
+            faces
+        })
+    }
+
+    fn bounding_volume(&self) -> Aabb<3> {
+        placed_parts(self, &solve(self).placements)
+            .iter()
+            .map(|part| part.shape().bounding_volume())
+            .reduce(|a, b| a.merged(&b))
+            .unwrap_or_else(|| Aabb::<3>::from_points([Point::origin()]))
+    }
+}
+
+/// Place the instances of an assembly, and log any problems with its mates
+///
+/// Returns the parts of the assembly, each moved to where the mates place it.
+pub(crate) fn place_instances(assembly: &fj::Assembly) -> Vec<fj::Part> {
+    let solution = solve(assembly);
+
+    for diagnostic in &solution.diagnostics {
+        warn!("{diagnostic}");
+    }
+
+    placed_parts(assembly, &solution.placements)
+}
+
+fn placed_parts(
+    assembly: &fj::Assembly,
+    placements: &[Placement],
+) -> Vec<fj::Part> {
+    assembly
+        .instances()
+        .iter()
+        .zip(placements)
+        .map(|(part, placement)| {
+            let angle = placement.rotation.magnitude();
+            let axis = if angle == Scalar::ZERO {
+                // Any axis will do, and a zero-length one can't be normalized.
+                Vector::unit_z()
+            } else {
+                placement.rotation / angle
+            };
+
+            let shape = fj::Transform {
+                shape: part.shape().clone(),
+                axis: axis.components.map(Scalar::into_f64),
+                angle: fj::Angle::from_rad(angle.into_f64()),
+                offset: placement.offset.components.map(Scalar::into_f64),
+            };
+
+            let mut placed =
+                fj::Part::new(part.name(), shape).with_units(part.units());
+            if let Some(tolerance) = part.tolerance() {
+                placed = placed.with_tolerance(tolerance);
+            }
+            if let Some(max_angle) = part.max_angle() {
+                placed = placed.with_max_angle(max_angle);
+            }
+            if let Some(color) = part.color() {
+                placed = placed.with_color(color);
+            }
+            if let Some(material) = part.material() {
+                placed = placed.with_material(material);
+            }
+            placed
+        })
+        .collect()
+}
+
+/// Solve the mates of an assembly
+///
+/// The first instance stays where it is. All others are moved, until their
+/// mates are satisfied. Instances that can still move after that stay as close
+/// to where they were modeled as possible.
+///
+/// Mates that can't be satisfied don't cause an error. The solver places the
+/// instances as close to satisfying them as it can, and reports them as
+/// conflicts.
+pub fn solve(assembly: &fj::Assembly) -> Solution {
+    const MAX_ITERATIONS: usize = 200;
+
+    let num_instances = assembly.instances().len();
+    let solver = Solver {
+        mates: assembly.mates(),
+        num_instances,
+    };
+
+    let mut params = solver.initial_params();
+    let mut residuals = solver.residuals(&params);
+    let mut cost = sum_of_squares(residuals.iter().flatten());
+
+    // The damping keeps the system solvable, if the assembly is
+    // under-constrained, and is adapted to how well the linearized system
+    // predicts the actual one.
+    let mut damping = 1e-3;
+
+    for _ in 0..MAX_ITERATIONS {
+        if residuals.iter().flatten().all(|r| r.abs() <= TOLERANCE) {
+            break;
+        }
+
+        let jacobian = solver.jacobian(&params);
+        let n = params.len();
+        let mut matrix = normal_matrix(&jacobian, n);
+        let mut gradient = vec![0.; n];
+        for (row, residual) in jacobian.iter().zip(residuals.iter().flatten()) {
+            for (g, d) in gradient.iter_mut().zip(row) {
+                *g -= d * residual;
+            }
+        }
+        for (i, row) in matrix.iter_mut().enumerate() {
+            row[i] += damping;
+        }
+
+        let Some(step) = solve_linear(matrix, gradient) else {
+            damping *= 10.;
+            continue;
+        };
+
+        let candidate = params
+            .iter()
+            .zip(&step)
+            .map(|(param, step)| param + step)
+            .collect::<Vec<_>>();
+        let candidate_residuals = solver.residuals(&candidate);
+        let candidate_cost =
+            sum_of_squares(candidate_residuals.iter().flatten());
+
+        if candidate_cost < cost {
+            params = candidate;
+            residuals = candidate_residuals;
+            cost = candidate_cost;
+            damping = (damping / 10.).max(1e-12);
+        } else {
+            damping *= 10.;
+            if damping > 1e12 {
+                break;
+            }
+        }
+    }
+
+    let mut diagnostics = residuals
+        .iter()
+        .enumerate()
+        .filter(|(_, residuals)| {
+            residuals.iter().any(|residual| residual.abs() > CONFLICT)
+        })
+        .map(|(mate, _)| Diagnostic::Conflict { mate })
+        .collect::<Vec<_>>();
+
+    // Each instance (but the first) has six degrees of freedom. Those that
+    // the mates remove show up in the rank of the Jacobian. Removing the
+    // columns of an instance reduces the rank by the number of degrees of
+    // freedom that the mates remove from it.
+    let matrix = normal_matrix(&solver.jacobian(&params), params.len());
+    let rank = rank(&matrix, &(0..params.len()).collect::<Vec<_>>());
+    for (instance, part) in assembly.instances().iter().enumerate().skip(1) {
+        let columns = (0..params.len())
+            .filter(|&column| column / 6 != instance - 1)
+            .collect::<Vec<_>>();
+        let removed = rank - self::rank(&matrix, &columns);
+
+        if removed < 6 {
+            diagnostics.push(Diagnostic::Underconstrained {
+                instance: part.name().to_string(),
+                degrees_of_freedom: 6 - removed,
+            });
+        }
+    }
+
+    let placements = (0..num_instances)
+        .map(|instance| solver.placement(&params, instance))
+        .collect();
+
+    Solution {
+        placements,
+        diagnostics,
+    }
+}
+
+/// The result of solving the mates of an [`fj::Assembly`]
+///
+/// Returned by [`solve`].
+#[derive(Clone, Debug)]
+pub struct Solution {
+    /// The placement of each instance, in the order of the instances
+    pub placements: Vec<Placement>,
+
+    /// Problems with the mates of the assembly
+    pub diagnostics: Vec<Diagnostic>,
+}
+
+/// Where an instance of an [`fj::Assembly`] is placed
+///
+/// The instance is rotated around the origin first, then offset.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct Placement {
+    /// The axis of the rotation, scaled by the angle in radians
+    pub rotation: Vector<3>,
+
+    /// The offset that is applied after the rotation
+    pub offset: Vector<3>,
+}
+
+impl Placement {
+    /// Convert the placement into a transform
+    pub fn transform(&self) -> Transform {
+        Transform::translation(self.offset) * Transform::rotation(self.rotation)
+    }
+}
+
+/// A problem with the mates of an [`fj::Assembly`]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum Diagnostic {
+    /// The mate can't be satisfied together with the other mates
+    Conflict {
+        /// The index of the mate, in the order the mates were added
+        mate: usize,
+    },
+
+    /// The mates don't fully determine where an instance is placed
+    Underconstrained {
+        /// The name of the instance's part
+        instance: String,
+
+        /// The number of ways in which the instance can still move
+        ///
+        /// Each direction of translation and each axis of rotation counts as
+        /// one degree of freedom.
+        degrees_of_freedom: usize,
+    },
+}
+
+impl fmt::Display for Diagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Conflict { mate } => write!(
+                f,
+                "Assembly is over-constrained: mate {mate} conflicts with \
+                other mates"
+            ),
+            Self::Underconstrained {
+                instance,
+                degrees_of_freedom,
+            } => write!(
+                f,
+                "Assembly is under-constrained: part `{instance}` has \
+                {degrees_of_freedom} degrees of freedom left"
+            ),
+        }
+    }
+}
+
+/// The residuals below which a mate counts as satisfied
+const TOLERANCE: f64 = 1e-10;
+
+/// The residuals above which a mate counts as conflicting
+///
+/// Larger than [`TOLERANCE`], as the solver might stop slightly short of it.
+const CONFLICT: f64 = 1e-6;
+
+struct Solver<'r> {
+    mates: &'r [fj::Mate],
+    num_instances: usize,
+}
+
+impl Solver<'_> {
+    /// Compute a starting point for the solver
+    ///
+    /// Starting with all instances where they were modeled can leave the
+    /// solver stuck, for example if an instance needs to be turned upside
+    /// down. Instead, instances are placed one by one, each aligned to an
+    /// instance that has already been placed, through the first mate that
+    /// connects them.
+    fn initial_params(&self) -> Vec<f64> {
+        let mut params = vec![0.; self.num_instances.saturating_sub(1) * 6];
+
+        let mut placed = vec![false; self.num_instances];
+        if let Some(first) = placed.first_mut() {
+            *first = true;
+        }
+
+        let mut progress = true;
+        while progress {
+            progress = false;
+
+            for mate in self.mates {
+                let (instances, origins, vectors) = features(mate);
+                let [fixed, moved] = match instances.map(|i| placed[i]) {
+                    [true, false] => [0, 1],
+                    [false, true] => [1, 0],
+                    _ => continue,
+                };
+
+                let transform =
+                    self.placement(&params, instances[fixed]).transform();
+                let fixed_origin = transform.transform_point(&origins[fixed]);
+                let fixed_vector = transform.transform_vector(&vectors[fixed]);
+
+                // Planes face each other, axes point in the same direction.
+                let (target_vector, distance) = match *mate {
+                    fj::Mate::Coincident(..) => (-fixed_vector, 0.),
+                    fj::Mate::Offset(_, _, distance) => {
+                        (-fixed_vector, distance)
+                    }
+                    fj::Mate::Concentric(..) => (fixed_vector, 0.),
+                };
+                let target_origin = fixed_origin + fixed_vector * distance;
+
+                let rotation = rotation_between(vectors[moved], target_vector);
+                let offset = target_origin
+                    - Transform::rotation(rotation)
+                        .transform_point(&origins[moved]);
+
+                let start = (instances[moved] - 1) * 6;
+                for (param, value) in params[start..start + 6]
+                    .iter_mut()
+                    .zip(rotation.components.iter().chain(&offset.components))
+                {
+                    *param = value.into_f64();
+                }
+
+                placed[instances[moved]] = true;
+                progress = true;
+            }
+        }
+
+        params
+    }
+
+    /// Compute the placement of an instance from the parameters
+    fn placement(&self, params: &[f64], instance: usize) -> Placement {
+        if instance == 0 {
+            return Placement {
+                rotation: Vector::from([0., 0., 0.]),
+                offset: Vector::from([0., 0., 0.]),
+            };
+        }
+
+        let start = (instance - 1) * 6;
+        Placement {
+            rotation: Vector::from([
+                params[start],
+                params[start + 1],
+                params[start + 2],
+            ]),
+            offset: Vector::from([
+                params[start + 3],
+                params[start + 4],
+                params[start + 5],
+            ]),
+        }
+    }
+
+    /// Compute the residuals of each mate, which are zero, if it's satisfied
+    fn residuals(&self, params: &[f64]) -> Vec<Vec<f64>> {
+        self.mates
+            .iter()
+            .map(|mate| {
+                let ([a, b], [origin_a, origin_b], [vector_a, vector_b]) =
+                    features(mate);
+                let [transform_a, transform_b] = [a, b].map(|instance| {
+                    self.placement(params, instance).transform()
+                });
+
+                let origin_a = transform_a.transform_point(&origin_a);
+                let origin_b = transform_b.transform_point(&origin_b);
+                let vector_a = transform_a.transform_vector(&vector_a);
+                let vector_b = transform_b.transform_vector(&vector_b);
+
+                let residuals = match *mate {
+                    fj::Mate::Coincident(..) | fj::Mate::Offset(..) => {
+                        let distance = match *mate {
+                            fj::Mate::Offset(_, _, distance) => distance,
+                            _ => 0.,
+                        };
+
+                        let normals = vector_a + vector_b;
+                        let offset =
+                            (origin_b - origin_a).dot(&vector_a) - distance;
+
+                        let mut residuals = normals.components.to_vec();
+                        residuals.push(offset);
+                        residuals
+                    }
+                    fj::Mate::Concentric(..) => {
+                        let directions = vector_a.cross(&vector_b);
+                        let offset = (origin_b - origin_a).cross(&vector_a);
+
+                        directions
+                            .components
+                            .into_iter()
+                            .chain(offset.components)
+                            .collect()
+                    }
+                };
+
+                residuals.into_iter().map(Scalar::into_f64).collect()
+            })
+            .collect()
+    }
+
+    /// Compute the derivatives of all residuals, by all parameters
+    ///
+    /// Returns one row per residual.
+    fn jacobian(&self, params: &[f64]) -> Vec<Vec<f64>> {
+        const H: f64 = 1e-7;
+
+        let num_residuals = self.residuals(params).iter().flatten().count();
+        let mut jacobian = vec![vec![0.; params.len()]; num_residuals];
+
+        let mut params = params.to_vec();
+        for column in 0..params.len() {
+            let value = params[column];
+
+            params[column] = value + H;
+            let ahead = self.residuals(&params);
+            params[column] = value - H;
+            let behind = self.residuals(&params);
+            params[column] = value;
+
+            for (row, (ahead, behind)) in ahead
+                .iter()
+                .flatten()
+                .zip(behind.iter().flatten())
+                .enumerate()
+            {
+                jacobian[row][column] = (ahead - behind) / (2. * H);
+            }
+        }
+
+        jacobian
+    }
+}
+
+/// Access the instances and the features of a mate
+///
+/// Returns the instances, the origins, and the normalized normals or
+/// directions, in that order.
+fn features(mate: &fj::Mate) -> ([usize; 2], [Point<3>; 2], [Vector<3>; 2]) {
+    let (instances, origins, vectors) = match mate {
+        fj::Mate::Coincident(a, b) | fj::Mate::Offset(a, b, _) => (
+            [a.instance, b.instance],
+            [a.origin, b.origin],
+            [a.normal, b.normal],
+        ),
+        fj::Mate::Concentric(a, b) => (
+            [a.instance, b.instance],
+            [a.origin, b.origin],
+            [a.direction, b.direction],
+        ),
+    };
+
+    (
+        instances.map(|instance| instance.index()),
+        origins.map(Point::from),
+        vectors.map(|vector| Vector::from(vector).normalize()),
+    )
+}
+
+/// Compute the rotation that turns one direction into another
+///
+/// Returns the axis of the rotation, scaled by its angle.
+fn rotation_between(from: Vector<3>, to: Vector<3>) -> Vector<3> {
+    let [from, to] = [from, to].map(|vector| vector.normalize());
+
+    let axis = from.cross(&to);
+    let sin = axis.magnitude();
+    let cos = from.dot(&to);
+
+    if sin > Scalar::from(1e-9) {
+        return axis / sin * sin.atan2(cos);
+    }
+    if cos > Scalar::ZERO {
+        return Vector::from([0., 0., 0.]);
+    }
+
+    // The directions are opposite. Half a turn around any perpendicular axis
+    // will do.
+    let perpendicular = [Vector::unit_x(), Vector::unit_y()]
+        .map(|axis| from.cross(&axis))
+        .into_iter()
+        .max_by_key(|axis| axis.magnitude())
+        .expect("Array is not empty");
+    perpendicular.normalize() * Scalar::PI
+}
+
+fn normal_matrix(jacobian: &[Vec<f64>], n: usize) -> Vec<Vec<f64>> {
+    let mut matrix = vec![vec![0.; n]; n];
+    for row in jacobian {
+        for (i, d_i) in row.iter().enumerate() {
+            for (j, d_j) in row.iter().enumerate() {
+                matrix[i][j] += d_i * d_j;
+            }
+        }
+    }
+    matrix
+}
+
+fn sum_of_squares<'r>(values: impl IntoIterator<Item = &'r f64>) -> f64 {
+    values.into_iter().map(|value| value * value).sum()
+}
+
+/// Solve a linear system using Gaussian elimination with partial pivoting
+///
+/// Returns `None`, if the matrix is singular.
+fn solve_linear(
+    mut matrix: Vec<Vec<f64>>,
+    mut rhs: Vec<f64>,
+) -> Option<Vec<f64>> {
+    let n = rhs.len();
+
+    for column in 0..n {
+        let pivot = (column..n).max_by(|&a, &b| {
+            matrix[a][column].abs().total_cmp(&matrix[b][column].abs())
+        })?;
+        if matrix[pivot][column].abs() < 1e-300 {
+            return None;
+        }
+        matrix.swap(column, pivot);
+        rhs.swap(column, pivot);
+
+        let pivot_row = matrix[column].clone();
+        for row in column + 1..n {
+            let factor = matrix[row][column] / pivot_row[column];
+            for (value, pivot) in
+                matrix[row][column..].iter_mut().zip(&pivot_row[column..])
+            {
+                *value -= factor * pivot;
+            }
+            rhs[row] -= factor * rhs[column];
+        }
+    }
+
+    let mut solution = vec![0.; n];
+    for row in (0..n).rev() {
+        let sum = (row + 1..n)
+            .map(|k| matrix[row][k] * solution[k])
+            .sum::<f64>();
+        solution[row] = (rhs[row] - sum) / matrix[row][row];
+    }
+
+    Some(solution)
+}
+
+/// Compute the rank of the sub-matrix of a symmetric matrix
+///
+/// The sub-matrix consists of the given rows, and the columns with the same
+/// indices.
+fn rank(matrix: &[Vec<f64>], indices: &[usize]) -> usize {
+    let mut matrix = indices
+        .iter()
+        .map(|&i| indices.iter().map(|&j| matrix[i][j]).collect::<Vec<_>>())
+        .collect::<Vec<_>>();
+    let n = indices.len();
+
+    let scale = (0..n).map(|i| matrix[i][i].abs()).fold(1., f64::max);
+    let mut rank = 0;
+
+    for column in 0..n {
+        let Some(pivot) = (rank..n).max_by(|&a, &b| {
+            matrix[a][column].abs().total_cmp(&matrix[b][column].abs())
+        }) else {
+            break;
+        };
+        if matrix[pivot][column].abs() <= scale * 1e-9 {
+            continue;
+        }
+        matrix.swap(rank, pivot);
+
+        let pivot_row = matrix[rank].clone();
+        for row in &mut matrix[rank + 1..] {
+            let factor = row[column] / pivot_row[column];
+            for (value, pivot) in
+                row[column..].iter_mut().zip(&pivot_row[column..])
+            {
+                *value -= factor * pivot;
+            }
+        }
+
+        rank += 1;
+    }
+
+    rank
+}
+
+#[cfg(test)]
+mod tests {
+    use fj::syntax::*;
+    use fj_math::{Point, Scalar, Vector};
+
+    use super::Diagnostic;
+
+    #[test]
+    fn lid_on_box() {
+        let (mut assembly, base, lid) = assembly();
+
+        // The lid is modeled upside down, with the side that rests on the
+        // base at the top. It needs to be turned over.
+        assembly.add_mate(fj::Mate::Coincident(
+            plane(base, [0., 0., 1.], [0., 0., 1.]),
+            plane(lid, [0., 0., 1.], [0., 0., 1.]),
+        ));
+        assembly.add_mate(fj::Mate::Concentric(
+            axis(base, [1., 1., 0.], [0., 0., 1.]),
+            axis(lid, [1., 1., 0.], [0., 0., 1.]),
+        ));
+
+        let solution = super::solve(&assembly);
+        let transform = solution.placements[1].transform();
+
+        let center = transform.transform_point(&Point::from([1., 1., 1.]));
+        let normal = transform.transform_vector(&Vector::unit_z());
+        assert!((center - Point::from([1., 1., 1.])).magnitude() < 1e-6.into());
+        assert!((normal + Vector::unit_z()).magnitude() < 1e-6.into());
+
+        // The lid can still turn around the axis.
+        assert_eq!(
+            solution.diagnostics,
+            [Diagnostic::Underconstrained {
+                instance: "lid".into(),
+                degrees_of_freedom: 1,
+            }]
+        );
+    }
+
+    #[test]
+    fn conflicting_offsets() {
+        let (mut assembly, base, lid) = assembly();
+
+        for distance in [1., 2.] {
+            assembly.add_mate(fj::Mate::Offset(
+                plane(base, [0., 0., 1.], [0., 0., 1.]),
+                plane(lid, [0., 0., 0.], [0., 0., -1.]),
+                distance,
+            ));
+        }
+
+        let solution = super::solve(&assembly);
+        let offset = solution.placements[1].offset;
+        assert!((offset.z - 2.5).abs() < Scalar::from(1e-6));
+
+        assert!(solution
+            .diagnostics
+            .contains(&Diagnostic::Conflict { mate: 0 }));
+        assert!(solution
+            .diagnostics
+            .contains(&Diagnostic::Conflict { mate: 1 }));
+    }
+
+    fn assembly() -> (fj::Assembly, fj::InstanceId, fj::InstanceId) {
+        let cuboid = fj::Sketch::from_points(vec![
+            [0., 0.],
+            [2., 0.],
+            [2., 2.],
+            [0., 2.],
+        ])
+        .sweep([0., 0., 1.]);
+
+        let mut assembly = fj::Assembly::new();
+        let base = assembly.add_instance(fj::Part::new("base", cuboid.clone()));
+        let lid = assembly.add_instance(fj::Part::new("lid", cuboid));
+
+        (assembly, base, lid)
+    }
+
+    fn plane(
+        instance: fj::InstanceId,
+        origin: [f64; 3],
+        normal: [f64; 3],
+    ) -> fj::MatePlane {
+        fj::MatePlane {
+            instance,
+            origin,
+            normal,
+        }
+    }
+
+    fn axis(
+        instance: fj::InstanceId,
+        origin: [f64; 3],
+        direction: [f64; 3],
+    ) -> fj::MateAxis {
+        fj::MateAxis {
+            instance,
+            origin,
+            direction,
+        }
+    }
+}
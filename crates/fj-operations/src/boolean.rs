@@ -0,0 +1,83 @@
+use fj_interop::debug::DebugInfo;
+use fj_kernel::{
+    algorithms::{
+        approx::Tolerance,
+        bounding_volume::BoundingVolume,
+        mesh_boolean::{mesh_boolean, BooleanOperation},
+        polyhedron::build_face,
+        triangulate::Triangulate,
+    },
+    objects::{FaceSet, Objects},
+    services::Service,
+};
+use fj_math::{Aabb, Scalar};
+
+use crate::{instrument::instrument, Shape};
+
+impl Shape for fj::Boolean {
+    type Brep = FaceSet;
+
+    fn compute_brep(
+        &self,
+        objects: &mut Service<Objects>,
+        debug_info: &mut DebugInfo,
+    ) -> Self::Brep {
+        instrument("Boolean", debug_info, |debug_info| {
+            let a = self.a().compute_brep(objects, debug_info);
+            let b = self.b().compute_brep(objects, debug_info);
+
+            // The kernel can't compute boolean operations on the boundary
+            // representation yet, so the operation is computed on triangle
+            // meshes instead. The tolerance doesn't need to match the one the
+            // result is triangulated with later. It's derived from the size of
+            // the shapes the same way the default tolerance is.
+            let Some(aabb) = [a.aabb(), b.aabb()]
+                .into_iter()
+                .flatten()
+                .reduce(|a, b| a.merged(&b))
+            else {
+                return FaceSet::new();
+            };
+            let Ok(tolerance) = Tolerance::from_scalar(
+                aabb.size().magnitude() / Scalar::from_f64(1000.),
+            ) else {
+                return FaceSet::new();
+            };
+
+            let a = (&a, tolerance).triangulate();
+            let b = (&b, tolerance).triangulate();
+
+            let operation = match self.kind() {
+                fj::BooleanKind::Union => BooleanOperation::Union,
+                fj::BooleanKind::Difference => BooleanOperation::Difference,
+                fj::BooleanKind::Intersection => BooleanOperation::Intersection,
+            };
+            let mesh = mesh_boolean(&a, &b, operation);
+
+            mesh.triangles()
+                .map(|triangle| {
+                    build_face(
+                        triangle.inner.normal(),
+                        &[triangle.inner.points().to_vec()],
+                        triangle.color,
+                        triangle.attributes,
+                        objects,
+                    )
+                })
+                .collect()
+        })
+    }
+
+    fn bounding_volume(&self) -> Aabb<3> {
+        let a = self.a().bounding_volume();
+        let b = self.b().bounding_volume();
+
+        // The result of a difference or intersection is within the first
+        // shape. This is not the tightest bounding box for an intersection,
+        // but it's a valid one.
+        match self.kind() {
+            fj::BooleanKind::Union => a.merged(&b),
+            fj::BooleanKind::Difference | fj::BooleanKind::Intersection => a,
+        }
+    }
+}
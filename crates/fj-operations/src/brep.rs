@@ -0,0 +1,62 @@
+use fj_interop::{
+    debug::DebugInfo,
+    mesh::{Attributes, Color},
+};
+use fj_kernel::{
+    algorithms::polyhedron::{build_face, polygon_normal},
+    objects::{FaceSet, Objects},
+    services::Service,
+};
+use fj_math::{Aabb, Point};
+
+use crate::{instrument::instrument, Shape};
+
+impl Shape for fj::Brep {
+    type Brep = FaceSet;
+
+    fn compute_brep(
+        &self,
+        objects: &mut Service<Objects>,
+        debug_info: &mut DebugInfo,
+    ) -> Self::Brep {
+        instrument("Brep", debug_info, |_| {
+            let vertices = self
+                .vertices()
+                .iter()
+                .map(|&vertex| Point::from(vertex))
+                .collect::<Vec<_>>();
+
+            self.faces()
+                .iter()
+                .map(|face| {
+                    let polygons = face
+                        .polygons()
+                        .map(|polygon| {
+                            polygon
+                                .iter()
+                                .map(|&index| vertices[index])
+                                .collect::<Vec<_>>()
+                        })
+                        .collect::<Vec<_>>();
+
+                    let normal = polygon_normal(&polygons[0]);
+                    build_face(
+                        normal,
+                        &polygons,
+                        Color(face.color()),
+                        Attributes::default(),
+                        objects,
+                    )
+                })
+                .collect()
+        })
+    }
+
+    fn bounding_volume(&self) -> Aabb<3> {
+        if self.vertices().is_empty() {
+            return Aabb::default();
+        }
+
+        Aabb::<3>::from_points(self.vertices().iter().copied().map(Point::from))
+    }
+}
@@ -11,7 +11,7 @@ use fj_kernel::{
 };
 use fj_math::Aabb;
 
-use super::Shape;
+use crate::{instrument::instrument, Shape};
 
 impl Shape for fj::Difference2d {
     type Brep = Sketch;
@@ -21,76 +21,80 @@ impl Shape for fj::Difference2d {
         objects: &mut Service<Objects>,
         debug_info: &mut DebugInfo,
     ) -> Self::Brep {
-        // This method assumes that `b` is fully contained within `a`:
-        // https://github.com/hannobraun/Fornjot/issues/92
+        instrument("Difference2d", debug_info, |debug_info| {
+            // This method assumes that `b` is fully contained within `a`:
+            // https://github.com/hannobraun/Fornjot/issues/92
 
-        let mut faces = Vec::new();
+            let mut faces = Vec::new();
 
-        let mut exteriors = Vec::new();
-        let mut interiors = Vec::new();
+            let mut exteriors = Vec::new();
+            let mut interiors = Vec::new();
 
-        let [a, b] = self
-            .shapes()
-            .each_ref_ext()
-            .map(|shape| shape.compute_brep(objects, debug_info));
+            let [a, b] = self
+                .shapes()
+                .each_ref_ext()
+                .map(|shape| shape.compute_brep(objects, debug_info));
 
-        if let Some(face) = a.face_iter().next() {
-            // If there's at least one face to subtract from, we can proceed.
+            if let Some(face) = a.face_iter().next() {
+                // If there's at least one face to subtract from, we can proceed.
 
-            let surface = face.surface();
+                let surface = face.surface();
+                let attributes = face.attributes().clone();
 
-            for face in a.face_iter() {
-                assert_eq!(
-                    surface,
-                    face.surface(),
-                    "Trying to subtract faces with different surfaces.",
-                );
+                for face in a.face_iter() {
+                    assert_eq!(
+                        surface,
+                        face.surface(),
+                        "Trying to subtract faces with different surfaces.",
+                    );
 
-                exteriors.push(face.exterior().clone());
-                for cycle in face.interiors() {
-                    interiors.push(cycle.clone().reverse(objects));
+                    exteriors.push(face.exterior().clone());
+                    for cycle in face.interiors() {
+                        interiors.push(cycle.clone().reverse(objects));
+                    }
                 }
-            }
 
-            for face in b.face_iter() {
-                assert_eq!(
-                    surface,
-                    face.surface(),
-                    "Trying to subtract faces with different surfaces.",
+                for face in b.face_iter() {
+                    assert_eq!(
+                        surface,
+                        face.surface(),
+                        "Trying to subtract faces with different surfaces.",
+                    );
+
+                    interiors.push(face.exterior().clone().reverse(objects));
+                }
+
+                // Faces only support one exterior, while the code here comes from
+                // the time when a face could have multiple exteriors. This was only
+                // a special case, i.e. faces that connected to themselves, and I
+                // have my doubts that this code was ever correct in the first
+                // place.
+                //
+                // Anyway, the following should make sure that at least any problems
+                // this code causes become obvious. I don't know if this can ever
+                // trigger, but better safe than sorry.
+                let exterior = exteriors
+                    .pop()
+                    .expect("Can't construct face without an exterior");
+                assert!(
+                    exteriors.is_empty(),
+                    "Can't construct face with multiple exteriors"
                 );
 
-                interiors.push(face.exterior().clone().reverse(objects));
+                faces.push(
+                    Face::partial()
+                        .with_exterior(exterior)
+                        .with_interiors(interiors)
+                        .with_color(Color(self.color()))
+                        .with_attributes(attributes.clone())
+                        .build(objects)
+                        .insert(objects),
+                );
             }
 
-            // Faces only support one exterior, while the code here comes from
-            // the time when a face could have multiple exteriors. This was only
-            // a special case, i.e. faces that connected to themselves, and I
-            // have my doubts that this code was ever correct in the first
-            // place.
-            //
-            // Anyway, the following should make sure that at least any problems
-            // this code causes become obvious. I don't know if this can ever
-            // trigger, but better safe than sorry.
-            let exterior = exteriors
-                .pop()
-                .expect("Can't construct face without an exterior");
-            assert!(
-                exteriors.is_empty(),
-                "Can't construct face with multiple exteriors"
-            );
-
-            faces.push(
-                Face::partial()
-                    .with_exterior(exterior)
-                    .with_interiors(interiors)
-                    .with_color(Color(self.color()))
-                    .build(objects)
-                    .insert(objects),
-            );
-        }
-
-        let difference = Sketch::builder().with_faces(faces).build(objects);
-        difference.deref().clone()
+            let difference = Sketch::builder().with_faces(faces).build(objects);
+            difference.deref().clone()
+        })
     }
 
     fn bounding_volume(&self) -> Aabb<3> {
@@ -0,0 +1,542 @@
+//! Inspection of the operations that make up a shape
+//!
+//! See [`dry_run`].
+
+use std::fmt;
+
+/// Walk the operations of a shape, without evaluating them
+///
+/// Nothing is computed by the kernel. Instead, the size of each operation's
+/// result and the cost of computing it are estimated from the operation's
+/// parameters. This is fast, even for shapes that take a long time to
+/// evaluate, which makes it useful to find the operations that are responsible
+/// for that, before evaluating the shape.
+///
+/// The estimates are rough. They are meant to compare operations with each
+/// other, not to predict evaluation time.
+pub fn dry_run(shape: &fj::Shape) -> Operation {
+    match shape {
+        fj::Shape::Shape2d(shape) => dry_run_2d(shape),
+        fj::Shape::Group(group) => {
+            let children = vec![dry_run(&group.a), dry_run(&group.b)];
+            let size = sum_sizes(&children);
+
+            Operation::new("Group", Vec::new(), size, Some(0), children)
+        }
+        fj::Shape::Sweep(sweep) => {
+            let child = dry_run_2d(sweep.shape());
+            let size = child.size.map(Size::swept);
+
+            Operation::new(
+                "Sweep",
+                vec![
+                    ("path", format!("{:?}", sweep.path())),
+                    ("draft", format!("{}°", sweep.draft().deg())),
+                ],
+                size,
+                size.map(|size| size.faces),
+                vec![child],
+            )
+        }
+        fj::Shape::HelicalSweep(sweep) => {
+            let helix = sweep.helix();
+            let child = dry_run_2d(sweep.shape());
+            let size = child.size.map(Size::swept);
+
+            Operation::new(
+                "HelicalSweep",
+                vec![
+                    ("radius", helix.radius.to_string()),
+                    ("pitch", helix.pitch.to_string()),
+                    ("turns", helix.turns.to_string()),
+                ],
+                size,
+                size.map(|size| size.faces),
+                vec![child],
+            )
+        }
+        fj::Shape::Holes(holes) => {
+            let holes_of_sweep = holes.holes();
+            let child = dry_run(&fj::Shape::Sweep(holes.sweep().clone()));
+            let size = child.size.map(|size| {
+                holes_of_sweep.iter().fold(size, |size, hole| {
+                    let faces = hole_faces(hole);
+                    Size {
+                        faces: size.faces + faces,
+                        edges: size.edges + faces * 3,
+                    }
+                })
+            });
+
+            Operation::new(
+                "Holes",
+                vec![("holes", holes_of_sweep.len().to_string())],
+                size,
+                size.map(|size| size.faces),
+                vec![child],
+            )
+        }
+        fj::Shape::Part(part) => {
+            let mut parameters = vec![
+                ("name", format!("{:?}", part.name())),
+                ("units", format!("{:?}", part.units())),
+            ];
+            if let Some(tolerance) = part.tolerance() {
+                parameters.push(("tolerance", tolerance.to_string()));
+            }
+            if let Some(max_angle) = part.max_angle() {
+                parameters.push(("max_angle", format!("{}°", max_angle.deg())));
+            }
+
+            let child = dry_run(part.shape());
+            let size = child.size;
+
+            Operation::new("Part", parameters, size, Some(0), vec![child])
+        }
+        fj::Shape::Assembly(assembly) => {
+            let children = assembly
+                .instances()
+                .iter()
+                .map(|part| dry_run(&fj::Shape::Part(Box::new(part.clone()))))
+                .collect::<Vec<_>>();
+            let size = sum_sizes(&children);
+            let mates = assembly.mates().len() as u64;
+
+            Operation::new(
+                "Assembly",
+                vec![
+                    ("instances", assembly.instances().len().to_string()),
+                    ("mates", mates.to_string()),
+                ],
+                size,
+                size.map(|size| size.faces + mates),
+                children,
+            )
+        }
+        fj::Shape::Transform(transform) => {
+            let child = dry_run(&transform.shape);
+            let size = child.size;
+
+            Operation::new(
+                "Transform",
+                vec![
+                    ("axis", format!("{:?}", transform.axis)),
+                    ("angle", format!("{}°", transform.angle.deg())),
+                    ("offset", format!("{:?}", transform.offset)),
+                ],
+                size,
+                size.map(|size| size.faces),
+                vec![child],
+            )
+        }
+        fj::Shape::Mirror(mirror) => {
+            let child = dry_run(mirror.shape());
+            let copies = if mirror.is_with_original() { 2 } else { 1 };
+            let size = child.size.map(|size| size.times(copies));
+
+            Operation::new(
+                "Mirror",
+                vec![
+                    ("origin", format!("{:?}", mirror.origin())),
+                    ("normal", format!("{:?}", mirror.normal())),
+                    ("with_original", mirror.is_with_original().to_string()),
+                ],
+                size,
+                size.map(|size| size.faces),
+                vec![child],
+            )
+        }
+        fj::Shape::Pattern(pattern) => {
+            let child = dry_run(pattern.shape());
+            let size = child
+                .size
+                .map(|size| size.times(u64::from(pattern.count())));
+
+            let mut parameters = vec![("count", pattern.count().to_string())];
+            match pattern.kind() {
+                fj::PatternKind::Linear { offset } => {
+                    parameters.push(("offset", format!("{offset:?}")));
+                }
+                fj::PatternKind::Circular { center, axis } => {
+                    parameters.push(("center", format!("{center:?}")));
+                    parameters.push(("axis", format!("{axis:?}")));
+                }
+            }
+
+            Operation::new(
+                "Pattern",
+                parameters,
+                size,
+                size.map(|size| size.faces),
+                vec![child],
+            )
+        }
+        fj::Shape::Boolean(boolean) => {
+            let children = vec![dry_run(boolean.a()), dry_run(boolean.b())];
+            let size = sum_sizes(&children);
+
+            // Every face of one shape is intersected with every face of the
+            // other.
+            let cost = match (children[0].size, children[1].size) {
+                (Some(a), Some(b)) => Some(a.faces * b.faces),
+                _ => None,
+            };
+
+            Operation::new(
+                "Boolean",
+                vec![("kind", format!("{:?}", boolean.kind()))],
+                size,
+                cost,
+                children,
+            )
+        }
+        fj::Shape::Brep(brep) => {
+            let faces = brep.faces().len() as u64;
+
+            // Each edge is shared by two faces.
+            let edges = brep
+                .faces()
+                .iter()
+                .flat_map(|face| face.polygons())
+                .map(|polygon| polygon.len() as u64)
+                .sum::<u64>()
+                / 2;
+
+            Operation::new(
+                "Brep",
+                vec![
+                    ("vertices", brep.vertices().len().to_string()),
+                    ("faces", faces.to_string()),
+                ],
+                Some(Size { faces, edges }),
+                Some(faces),
+                Vec::new(),
+            )
+        }
+        fj::Shape::Import(import) => Operation::new(
+            "Import",
+            vec![("path", format!("{:?}", import.path()))],
+            None,
+            None,
+            Vec::new(),
+        ),
+    }
+}
+
+fn dry_run_2d(shape: &fj::Shape2d) -> Operation {
+    match shape {
+        fj::Shape2d::Difference(difference) => {
+            let children = difference
+                .shapes()
+                .iter()
+                .map(dry_run_2d)
+                .collect::<Vec<_>>();
+
+            // The faces of the second shape become holes in the faces of the
+            // first one.
+            let size = match (children[0].size, children[1].size) {
+                (Some(a), Some(b)) => Some(Size {
+                    faces: a.faces,
+                    edges: a.edges + b.edges,
+                }),
+                _ => None,
+            };
+
+            Operation::new(
+                "Difference2d",
+                Vec::new(),
+                size,
+                size.map(|size| size.edges),
+                children,
+            )
+        }
+        fj::Shape2d::Sketch(sketch) => {
+            let (chain, parameters, edges) = match sketch.chain() {
+                fj::Chain::Circle(circle) => {
+                    ("circle", vec![("radius", circle.radius().to_string())], 1)
+                }
+                fj::Chain::Ellipse(ellipse) => (
+                    "ellipse",
+                    vec![
+                        ("radius_x", ellipse.radius_x().to_string()),
+                        ("radius_y", ellipse.radius_y().to_string()),
+                    ],
+                    1,
+                ),
+                fj::Chain::PolyChain(poly_chain) => (
+                    "poly_chain",
+                    vec![("points", poly_chain.segments().len().to_string())],
+                    poly_chain.segments().len() as u64,
+                ),
+                fj::Chain::Rectangle(rectangle) => {
+                    let mut parameters = vec![
+                        ("width", rectangle.width().to_string()),
+                        ("height", rectangle.height().to_string()),
+                    ];
+                    let mut edges = 4;
+                    if rectangle.corner_radius() > 0. {
+                        parameters.push((
+                            "corner_radius",
+                            rectangle.corner_radius().to_string(),
+                        ));
+                        edges += 4;
+                    }
+
+                    ("rectangle", parameters, edges)
+                }
+                fj::Chain::RegularPolygon(polygon) => (
+                    "regular_polygon",
+                    vec![
+                        ("radius", polygon.radius().to_string()),
+                        ("sides", polygon.num_sides().to_string()),
+                    ],
+                    u64::from(polygon.num_sides()),
+                ),
+                fj::Chain::Slot(slot) => (
+                    "slot",
+                    vec![
+                        ("length", slot.length().to_string()),
+                        ("radius", slot.radius().to_string()),
+                    ],
+                    4,
+                ),
+            };
+
+            let mut all_parameters = vec![("chain", chain.to_owned())];
+            all_parameters.extend(parameters);
+
+            Operation::new(
+                "Sketch",
+                all_parameters,
+                Some(Size { faces: 1, edges }),
+                Some(edges),
+                Vec::new(),
+            )
+        }
+        fj::Shape2d::Import(import) => Operation::new(
+            "Import2d",
+            vec![("path", format!("{:?}", import.path()))],
+            None,
+            None,
+            Vec::new(),
+        ),
+    }
+}
+
+/// Estimate the number of faces that drilling a hole adds
+fn hole_faces(hole: &fj::Hole) -> u64 {
+    let walls = match hole.profile() {
+        fj::HoleProfile::Round { .. } => 1,
+        fj::HoleProfile::Slot { .. } => 4,
+    };
+
+    let bottom = match hole.depth() {
+        fj::HoleDepth::Through => 0,
+        fj::HoleDepth::Blind(_) => 1,
+    };
+    let head = match hole.head() {
+        fj::HoleHead::None => 0,
+        fj::HoleHead::Counterbore { .. } => walls + 1,
+        fj::HoleHead::Countersink { .. } => walls,
+    };
+
+    walls + bottom + head
+}
+
+fn sum_sizes(operations: &[Operation]) -> Option<Size> {
+    operations.iter().map(|operation| operation.size).try_fold(
+        Size::default(),
+        |sum, size| {
+            let size = size?;
+            Some(Size {
+                faces: sum.faces + size.faces,
+                edges: sum.edges + size.edges,
+            })
+        },
+    )
+}
+
+/// An operation of a shape, as reported by [`dry_run`]
+#[derive(Clone, Debug, PartialEq)]
+pub struct Operation {
+    /// The name of the operation
+    ///
+    /// Matches the name the operation is recorded under, when its evaluation
+    /// is timed.
+    pub name: &'static str,
+
+    /// The parameters of the operation, as names and formatted values
+    pub parameters: Vec<(&'static str, String)>,
+
+    /// The estimated size of the operation's result
+    ///
+    /// `None`, if the size can't be known without evaluating the operation,
+    /// like for imported shapes.
+    pub size: Option<Size>,
+
+    /// The estimated cost of the operation, not including its children
+    ///
+    /// The cost is given in arbitrary units, roughly the number of faces that
+    /// need to be computed. `None`, if it can't be known without evaluating the
+    /// operation.
+    pub cost: Option<u64>,
+
+    /// The operations that this operation is applied to
+    pub children: Vec<Operation>,
+}
+
+impl Operation {
+    fn new(
+        name: &'static str,
+        parameters: Vec<(&'static str, String)>,
+        size: Option<Size>,
+        cost: Option<u64>,
+        children: Vec<Operation>,
+    ) -> Self {
+        Self {
+            name,
+            parameters,
+            size,
+            cost,
+            children,
+        }
+    }
+
+    /// Compute the estimated cost of the operation, including its children
+    ///
+    /// Operations whose cost is unknown are not included.
+    pub fn total_cost(&self) -> u64 {
+        self.cost.unwrap_or(0)
+            + self.children.iter().map(Self::total_cost).sum::<u64>()
+    }
+
+    /// Count the operations, including this one and all of its descendants
+    pub fn num_operations(&self) -> usize {
+        1 + self
+            .children
+            .iter()
+            .map(Self::num_operations)
+            .sum::<usize>()
+    }
+
+    fn fmt_indented(
+        &self,
+        f: &mut fmt::Formatter,
+        indentation: usize,
+    ) -> fmt::Result {
+        write!(f, "{:indentation$}{}", "", self.name)?;
+
+        if !self.parameters.is_empty() {
+            let parameters = self
+                .parameters
+                .iter()
+                .map(|(name, value)| format!("{name} = {value}"))
+                .collect::<Vec<_>>();
+            write!(f, " ({})", parameters.join(", "))?;
+        }
+
+        match self.size {
+            Some(size) => {
+                write!(f, ": {} faces, {} edges", size.faces, size.edges)?
+            }
+            None => write!(f, ": unknown size")?,
+        }
+        match self.cost {
+            Some(cost) => write!(f, ", cost {cost}")?,
+            None => write!(f, ", unknown cost")?,
+        }
+        if !self.children.is_empty() {
+            write!(f, " (total {})", self.total_cost())?;
+        }
+        writeln!(f)?;
+
+        for child in &self.children {
+            child.fmt_indented(f, indentation + 2)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl fmt::Display for Operation {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        self.fmt_indented(f, 0)
+    }
+}
+
+/// The estimated size of the result of an [`Operation`]
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct Size {
+    /// The number of faces
+    pub faces: u64,
+
+    /// The number of edges
+    pub edges: u64,
+}
+
+impl Size {
+    /// The size of a sweep of a 2D shape of this size
+    ///
+    /// Each edge of the shape becomes a side face, and each face is there at
+    /// the bottom and the top.
+    fn swept(self) -> Self {
+        Self {
+            faces: self.faces * 2 + self.edges,
+            edges: self.edges * 3,
+        }
+    }
+
+    fn times(self, count: u64) -> Self {
+        Self {
+            faces: self.faces * count,
+            edges: self.edges * count,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use fj::syntax::*;
+
+    use super::{dry_run, Size};
+
+    #[test]
+    fn sweep() {
+        let shape = fj::Sketch::from_points(vec![[0., 0.], [1., 0.], [0., 1.]])
+            .sweep([0., 0., 1.]);
+        let operation = dry_run(&shape.into());
+
+        assert_eq!(operation.name, "Sweep");
+        assert_eq!(operation.children[0].name, "Sketch");
+        assert_eq!(operation.size, Some(Size { faces: 5, edges: 9 }));
+        assert_eq!(operation.total_cost(), 3 + 5);
+        assert_eq!(operation.num_operations(), 2);
+    }
+
+    #[test]
+    fn boolean_cost_grows_with_both_operands() {
+        let cube = fj::Sketch::from_rectangle(fj::Rectangle::from_size(1., 1.))
+            .sweep([0., 0., 1.]);
+        let cylinder = fj::Sketch::from_circle(fj::Circle::from_radius(0.25))
+            .sweep([0., 0., 1.]);
+        let operation = dry_run(&cube.subtract(&cylinder).into());
+
+        assert_eq!(operation.name, "Boolean");
+        assert_eq!(operation.cost, Some(6 * 3));
+        assert_eq!(operation.size.map(|size| size.faces), Some(6 + 3));
+    }
+
+    #[test]
+    fn import_has_unknown_size() {
+        let import: fj::Shape = fj::Import::from_path("model.stl").into();
+        let shape = import
+            .translate([1., 0., 0.])
+            .pattern_linear([2., 0., 0.], 3);
+        let operation = dry_run(&shape.into());
+
+        assert_eq!(operation.size, None);
+        assert_eq!(operation.cost, None);
+        assert_eq!(operation.total_cost(), 0);
+        assert_eq!(operation.num_operations(), 3);
+    }
+}
@@ -5,7 +5,7 @@ use fj_kernel::{
 };
 use fj_math::Aabb;
 
-use super::Shape;
+use crate::{instrument::instrument, Shape};
 
 impl Shape for fj::Group {
     type Brep = FaceSet;
@@ -15,15 +15,17 @@ impl Shape for fj::Group {
         objects: &mut Service<Objects>,
         debug_info: &mut DebugInfo,
     ) -> Self::Brep {
-        let mut faces = FaceSet::new();
+        instrument("Group", debug_info, |debug_info| {
+            let mut faces = FaceSet::new();
 
-        let a = self.a.compute_brep(objects, debug_info);
-        let b = self.b.compute_brep(objects, debug_info);
+            let a = self.a.compute_brep(objects, debug_info);
+            let b = self.b.compute_brep(objects, debug_info);
 
-        faces.extend(a);
-        faces.extend(b);
+            faces.extend(a);
+            faces.extend(b);
 
-        faces
+            faces
+        })
     }
 
     fn bounding_volume(&self) -> Aabb<3> {
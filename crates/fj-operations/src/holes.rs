@@ -0,0 +1,540 @@
+use std::ops::Deref;
+
+use fj_interop::{
+    debug::DebugInfo,
+    mesh::{Attributes, Color},
+};
+use fj_kernel::{
+    algorithms::{
+        reverse::Reverse,
+        sweep::{Sweep, SweepCache},
+        transform::{TransformCache, TransformObject},
+    },
+    builder::CycleBuilder,
+    geometry::{
+        path::{GlobalPath, SurfacePath},
+        surface::SurfaceGeometry,
+    },
+    insert::Insert,
+    objects::{
+        Curve, Cycle, Face, GlobalCurve, GlobalEdge, HalfEdge, Objects, Shell,
+        Solid, Surface, SurfaceVertex, Vertex,
+    },
+    partial::HasPartial,
+    services::Service,
+    storage::Handle,
+};
+use fj_math::{Aabb, Circle, Cone, Line, Point, Scalar, Transform, Vector};
+
+use crate::{instrument::instrument, Shape};
+
+impl Shape for fj::Holes {
+    type Brep = Solid;
+
+    fn compute_brep(
+        &self,
+        objects: &mut Service<Objects>,
+        debug_info: &mut DebugInfo,
+    ) -> Self::Brep {
+        instrument("Holes", debug_info, |debug_info| {
+            assert!(
+                self.sweep().draft().rad() == 0.,
+                "Drilling holes into a sweep with draft is not supported yet"
+            );
+
+            let sketch = self.sweep().shape().compute_brep(objects, debug_info);
+            let fj::SweepPath::Straight { offset } = self.sweep().path() else {
+                panic!(
+                    "Drilling holes into a sweep along a polyline is not \
+                    supported"
+                );
+            };
+            let path = Vector::from(*offset);
+
+            // The holes are positioned in the coordinates of the sketch. With
+            // more than one face, it would be unclear which one they belong to.
+            let mut faces = sketch.faces().into_iter();
+            let face = match (faces.next(), faces.next()) {
+                (Some(face), None) => face.clone(),
+                (None, _) => {
+                    return Solid::builder().build(objects).deref().clone()
+                }
+                (Some(_), Some(_)) => panic!(
+                    "Drilling holes into a sketch with multiple faces is not \
+                    supported"
+                ),
+            };
+
+            let holes = self
+                .holes()
+                .iter()
+                .map(|hole| {
+                    HoleSections::new(hole, face.surface(), path, objects)
+                })
+                .collect::<Vec<_>>();
+
+            drill(&face, path, &holes, objects).deref().clone()
+        })
+    }
+
+    fn bounding_volume(&self) -> Aabb<3> {
+        // Holes only ever remove material.
+        self.sweep().bounding_volume()
+    }
+}
+
+/// The cross-sections of a hole, from the top down
+struct HoleSections {
+    sections: Vec<(Scalar, Section)>,
+}
+
+impl HoleSections {
+    fn new(
+        hole: &fj::Hole,
+        surface: &Handle<Surface>,
+        path: Vector<3>,
+        objects: &mut Service<Objects>,
+    ) -> Self {
+        let length = path.magnitude();
+        let depth = match hole.depth() {
+            fj::HoleDepth::Through => length,
+            fj::HoleDepth::Blind(depth) => {
+                Ord::min(Scalar::from(depth), length)
+            }
+        };
+
+        let position = Point::from(hole.position());
+
+        let mut cycle = |width: f64| {
+            let radius = Scalar::from(width / 2.);
+            let cycle = match hole.profile() {
+                fj::HoleProfile::Round { .. } => Cycle::partial().with_circle(
+                    surface.clone(),
+                    position,
+                    radius,
+                    objects,
+                ),
+                fj::HoleProfile::Slot { length, .. } => {
+                    let offset = Vector::from([length / 2., 0.]);
+                    Cycle::partial().with_slot(
+                        surface.clone(),
+                        [position - offset, position + offset],
+                        radius,
+                    )
+                }
+            };
+
+            cycle.build(objects).insert(objects)
+        };
+
+        let hole_cycle = cycle(hole.width());
+        let mut sections = Vec::new();
+
+        match hole.head() {
+            fj::HoleHead::None => {}
+            fj::HoleHead::Counterbore {
+                diameter,
+                depth: counterbore_depth,
+            } => {
+                let counterbore_depth = Scalar::from(counterbore_depth);
+                assert!(
+                    counterbore_depth < depth,
+                    "Counterbore must not be deeper than the hole"
+                );
+
+                sections
+                    .push((counterbore_depth, Section::Wall(cycle(diameter))));
+            }
+            fj::HoleHead::Countersink { diameter, angle } => {
+                // The cone of the countersink ends where it meets the hole.
+                let (sin, cos) = Scalar::from(angle.rad() / 2.).sin_cos();
+                let countersink_depth =
+                    Scalar::from((diameter - hole.width()) / 2.) * cos / sin;
+                assert!(
+                    countersink_depth < depth,
+                    "Countersink must not be deeper than the hole"
+                );
+
+                sections
+                    .push((countersink_depth, Section::Cone(cycle(diameter))));
+            }
+        }
+
+        sections.push((depth, Section::Wall(hole_cycle)));
+
+        Self { sections }
+    }
+
+    /// Access the section at the given depth, if the hole reaches that deep
+    fn section_at(&self, depth: Scalar) -> Option<usize> {
+        self.sections
+            .iter()
+            .position(|&(section_depth, _)| depth < section_depth)
+    }
+}
+
+/// A section of a hole
+///
+/// Each section refers to a cycle on the sketch surface. It is wound
+/// counter-clockwise, as if it was the exterior of a face.
+#[derive(Clone)]
+enum Section {
+    /// The section has straight walls, which are swept from the cycle
+    Wall(Handle<Cycle>),
+
+    /// The section is a cone
+    ///
+    /// The cycle defines the wide end of the cone. The narrow end is the cycle
+    /// of the section below.
+    Cone(Handle<Cycle>),
+}
+
+impl Section {
+    fn cycle(&self) -> &Handle<Cycle> {
+        match self {
+            Self::Wall(cycle) | Self::Cone(cycle) => cycle,
+        }
+    }
+}
+
+/// Sweep a face along a path, while drilling holes into it
+///
+/// The sweep is split into layers wherever the cross-section of a hole changes.
+/// Each layer is translated from the one below it, exactly like the top face
+/// of a regular sweep is translated from its bottom face. This makes sure that
+/// the vertices of adjacent layers coincide exactly.
+fn drill(
+    face: &Handle<Face>,
+    path: Vector<3>,
+    holes: &[HoleSections],
+    objects: &mut Service<Objects>,
+) -> Handle<Solid> {
+    let length = path.magnitude();
+
+    let is_negative_sweep = {
+        let normal = match face.surface().geometry() {
+            SurfaceGeometry::Swept {
+                u: GlobalPath::Line(line),
+                v,
+            } => line.direction().cross(&v),
+            _ => panic!("Drilling holes into a round surface is not supported"),
+        };
+
+        normal.dot(&path) < Scalar::ZERO
+    };
+
+    // The depths at which the layers end, measured from the top, sorted from
+    // the bottom up.
+    let mut depths = holes
+        .iter()
+        .flat_map(|hole| hole.sections.iter().map(|&(depth, _)| depth))
+        .chain([length])
+        .collect::<Vec<_>>();
+    depths.sort();
+
+    // Depths that are computed differently can end up almost, but not quite,
+    // equal. Layers in between would be degenerate.
+    let epsilon = length * 1e-9;
+    depths.dedup_by(|a, b| *a - *b < epsilon);
+    depths.reverse();
+
+    let color = face.color();
+    let attributes = face.attributes();
+    let mut faces = Vec::new();
+
+    // All cycles are translated from one layer to the next, whether they are
+    // needed there or not.
+    let mut face_cycles = face.all_cycles().cloned().collect::<Vec<_>>();
+    let mut hole_cycles = holes
+        .iter()
+        .map(|hole| {
+            hole.sections
+                .iter()
+                .map(|(_, section)| section.cycle().clone())
+                .collect::<Vec<_>>()
+        })
+        .collect::<Vec<_>>();
+
+    // The narrow end of each countersink, once it has been reached
+    let mut cone_bottoms = vec![None; holes.len()];
+
+    let mut below = vec![None; holes.len()];
+
+    for (i, &bottom) in depths.iter().enumerate() {
+        let is_bottom = i == 0;
+
+        let top = depths.get(i + 1).copied().unwrap_or(Scalar::ZERO);
+        let middle = (top + bottom) / 2.;
+
+        let above = holes
+            .iter()
+            .map(|hole| hole.section_at(middle))
+            .collect::<Vec<_>>();
+
+        // At the bottom of the sweep, through holes are interiors of the bottom
+        // face. Further up, a hole can start, or get wider.
+        let mut interiors = Vec::new();
+        for (j, hole) in holes.iter().enumerate() {
+            if !is_bottom && above[j] == below[j] {
+                continue;
+            }
+
+            let Some(k) = above[j] else { continue };
+            let cycle = hole_cycles[j][k].clone();
+            let below = below[j].map(|k| hole_cycles[j][k].clone());
+
+            match (&hole.sections[k].1, below) {
+                (Section::Wall(_), None) if is_bottom => {
+                    interiors.push(cycle.reverse(objects));
+                }
+                (Section::Wall(_), below) => {
+                    let interiors = below.map(|cycle| cycle.reverse(objects));
+                    let face = Face::partial()
+                        .with_exterior(cycle)
+                        .with_interiors(interiors)
+                        .with_color(color)
+                        .with_attributes(attributes.clone())
+                        .build(objects)
+                        .insert(objects);
+
+                    faces.push(if is_negative_sweep {
+                        face.reverse(objects)
+                    } else {
+                        face
+                    });
+                }
+                (Section::Cone(_), Some(below)) => {
+                    cone_bottoms[j] = Some(below.reverse(objects));
+                }
+                (Section::Cone(_), None) => {
+                    unreachable!("Countersink is never deeper than its hole")
+                }
+            }
+        }
+
+        if is_bottom {
+            let face = cap(&face_cycles, interiors, color, attributes, objects);
+            faces.push(if is_negative_sweep {
+                face
+            } else {
+                face.reverse(objects)
+            });
+        }
+
+        // The walls of this layer, including the outside of the sweep
+        let layer = path * ((bottom - top) / length);
+        let mut cache = SweepCache::default();
+
+        let mut walls = face_cycles.clone();
+        for (j, hole) in holes.iter().enumerate() {
+            let Some(k) = above[j] else { continue };
+            if let Section::Wall(_) = hole.sections[k].1 {
+                walls.push(hole_cycles[j][k].clone().reverse(objects));
+            }
+        }
+
+        for cycle in walls {
+            for half_edge in cycle.half_edges() {
+                let half_edge = if is_negative_sweep {
+                    half_edge.clone().reverse(objects)
+                } else {
+                    half_edge.clone()
+                };
+
+                let face = (half_edge, color, attributes.clone())
+                    .sweep_with_cache(layer, &mut cache, objects);
+                faces.push(face);
+            }
+        }
+
+        // Move everything to the top of the layer. All cycles of a layer need
+        // to end up on the same surface, so they need to share a cache.
+        let transform = Transform::translation(layer);
+        let mut cache = TransformCache::default();
+
+        face_cycles = face_cycles
+            .into_iter()
+            .map(|cycle| {
+                cycle.transform_with_cache(&transform, objects, &mut cache)
+            })
+            .collect();
+        hole_cycles = hole_cycles
+            .into_iter()
+            .map(|cycles| {
+                cycles
+                    .into_iter()
+                    .map(|cycle| {
+                        cycle.transform_with_cache(
+                            &transform, objects, &mut cache,
+                        )
+                    })
+                    .collect()
+            })
+            .collect();
+
+        below = above;
+    }
+
+    // The top face, and the countersinks that lead into it
+    let mut interiors = Vec::new();
+    for (j, section) in below.into_iter().enumerate() {
+        let Some(k) = section else { continue };
+        let cycle = hole_cycles[j][k].clone().reverse(objects);
+
+        if let Some(bottom) = &cone_bottoms[j] {
+            faces.push(cone(bottom, &cycle, color, attributes, objects));
+        }
+
+        interiors.push(cycle);
+    }
+
+    let face = cap(&face_cycles, interiors, color, attributes, objects);
+    faces.push(if is_negative_sweep {
+        face.reverse(objects)
+    } else {
+        face
+    });
+
+    let shell = Shell::builder().with_faces(faces).build(objects);
+    Solid::builder().with_shells([shell]).build(objects)
+}
+
+fn cap(
+    face_cycles: &[Handle<Cycle>],
+    interiors: Vec<Handle<Cycle>>,
+    color: Color,
+    attributes: &Attributes,
+    objects: &mut Service<Objects>,
+) -> Handle<Face> {
+    let (exterior, face_interiors) =
+        face_cycles.split_first().expect("Face has an exterior");
+
+    Face::partial()
+        .with_exterior(exterior.clone())
+        .with_interiors(face_interiors.iter().cloned().chain(interiors))
+        .with_color(color)
+        .with_attributes(attributes.clone())
+        .build(objects)
+        .insert(objects)
+}
+
+/// Build the face of a countersink
+///
+/// Both cycles must consist of a single circle, wound clockwise, like the
+/// interiors of a face. The face shares its edges with them, so the mesh is
+/// watertight where it meets the adjacent faces.
+fn cone(
+    bottom: &Handle<Cycle>,
+    top: &Handle<Cycle>,
+    color: Color,
+    attributes: &Attributes,
+    objects: &mut Service<Objects>,
+) -> Handle<Face> {
+    let [bottom, top] = [bottom, top].map(|cycle| {
+        let mut half_edges = cycle.half_edges();
+        match (half_edges.next(), half_edges.next()) {
+            (Some(half_edge), None) => half_edge.clone(),
+            _ => unreachable!("Countersinks are round"),
+        }
+    });
+    let [bottom_circle, top_circle] = [&bottom, &top].map(|half_edge| {
+        let curve = half_edge.curve();
+        let SurfacePath::Circle(circle) = curve.path() else {
+            unreachable!("Countersinks are round")
+        };
+
+        let surface = curve.surface().geometry();
+        Circle::new(
+            surface.point_from_surface_coords(circle.center()),
+            surface.vector_from_surface_coords(circle.a()),
+            surface.vector_from_surface_coords(circle.b()),
+        )
+    });
+
+    // Both circles are on the sketch surface, or translations of it. Their
+    // u-coordinates are the ones of the surface.
+    let v_bottom = bottom_circle.radius() / top_circle.radius();
+    let axis = (top_circle.center() - bottom_circle.center())
+        / (Scalar::ONE - v_bottom);
+
+    // If the sweep is not perpendicular to the sketch, the countersink is an
+    // oblique cone. That can only be represented as a ruled surface.
+    let epsilon = Scalar::from(1e-9) * top_circle.radius() * axis.magnitude();
+    let (geometry, v_bottom) = if axis.dot(&top_circle.a()).abs() <= epsilon
+        && axis.dot(&top_circle.b()).abs() <= epsilon
+    {
+        let cone = Cone::new(
+            top_circle.center() - axis,
+            top_circle.a(),
+            top_circle.b(),
+            axis,
+        );
+        (SurfaceGeometry::Cone(cone), v_bottom)
+    } else {
+        let ruled = SurfaceGeometry::Ruled {
+            a: GlobalPath::Circle(bottom_circle),
+            b: GlobalPath::Circle(top_circle),
+        };
+        (ruled, Scalar::ZERO)
+    };
+    let surface = Surface::new(geometry).insert(objects);
+
+    // The face goes around the bottom, up the seam, back around the top, and
+    // down the seam again. That's counter-clockwise, in surface coordinates.
+    let [bottom_vertex, top_vertex] =
+        [&bottom, &top].map(|half_edge| half_edge.back().global_form().clone());
+    let seam = GlobalEdge::new(
+        GlobalCurve.insert(objects),
+        [bottom_vertex.clone(), top_vertex.clone()],
+    )
+    .insert(objects);
+
+    let corners = [
+        ([Scalar::ZERO, v_bottom], bottom_vertex.clone()),
+        ([Scalar::TAU, v_bottom], bottom_vertex),
+        ([Scalar::TAU, Scalar::ONE], top_vertex.clone()),
+        ([Scalar::ZERO, Scalar::ONE], top_vertex),
+    ]
+    .map(|(position, global_form)| {
+        SurfaceVertex::new(position, surface.clone(), global_form)
+            .insert(objects)
+    });
+    let edges = [
+        (bottom.global_form().clone(), [Scalar::ZERO, Scalar::TAU]),
+        (seam.clone(), [Scalar::ZERO, Scalar::ONE]),
+        (top.global_form().clone(), [Scalar::TAU, Scalar::ZERO]),
+        (seam, [Scalar::ONE, Scalar::ZERO]),
+    ];
+
+    let half_edges = edges.into_iter().enumerate().map(|(i, (edge, range))| {
+        let vertices = [&corners[i], &corners[(i + 1) % corners.len()]];
+
+        let path = SurfacePath::Line(Line::from_points_with_line_coords(
+            [0, 1].map(|j| (Point::from([range[j]]), vertices[j].position())),
+        ));
+        let curve = Curve::new(surface.clone(), path, edge.curve().clone())
+            .insert(objects);
+
+        let vertices = [0, 1].map(|j| {
+            Vertex::new([range[j]], curve.clone(), vertices[j].clone())
+                .insert(objects)
+        });
+        HalfEdge::new(vertices, edge).insert(objects)
+    });
+    let half_edges = half_edges.collect::<Vec<_>>();
+
+    let face = Face::partial()
+        .with_exterior(Cycle::new(half_edges).insert(objects))
+        .with_color(color)
+        .with_attributes(attributes.clone())
+        .build(objects)
+        .insert(objects);
+
+    // The face needs to point into the hole, towards the axis of the cone.
+    let normal = surface.geometry().normal_at([Scalar::ZERO, Scalar::ONE]);
+    if normal.dot(&top_circle.a()) > Scalar::ZERO {
+        face.reverse(objects)
+    } else {
+        face
+    }
+}
@@ -0,0 +1,261 @@
+use std::{cell::RefCell, collections::HashMap, ops::Deref, path::Path};
+
+use fj_interop::debug::DebugInfo;
+use fj_kernel::{
+    algorithms::bounding_volume::BoundingVolume,
+    objects::{FaceSet, Objects, Sketch},
+    services::{Service, ServiceValidationExt, Services},
+    storage::Handle,
+};
+use fj_math::Aabb;
+use tracing::error;
+
+use crate::{instrument::instrument, shape_processor::Error, Shape};
+
+thread_local! {
+    /// The files imported for the shape that is currently being evaluated
+    static IMPORTS: RefCell<Option<Imports>> = const { RefCell::new(None) };
+}
+
+/// The files that a shape imports, imported before the shape is evaluated
+///
+/// Importing a file can fail, which [`Shape::compute_brep`] has no way to
+/// report. The shape processor imports all files up front, to report such
+/// errors, and provides the imported objects while it evaluates the shape. That
+/// way, every file is read only once.
+#[derive(Default)]
+pub(crate) struct Imports {
+    solids: HashMap<String, FaceSet>,
+    sketches: HashMap<String, Handle<Sketch>>,
+}
+
+impl Imports {
+    /// Import all files that the shape refers to
+    pub(crate) fn load(
+        shape: &fj::Shape,
+        objects: &mut Service<Objects>,
+    ) -> Result<Self, Error> {
+        let mut imports = Self::default();
+        imports.load_shape(shape, objects)?;
+        Ok(imports)
+    }
+
+    /// Provide the imported objects to the import operations within `f`
+    pub(crate) fn provide<R>(self, f: impl FnOnce() -> R) -> R {
+        struct Reset;
+
+        impl Drop for Reset {
+            fn drop(&mut self) {
+                IMPORTS.with(|imports| imports.borrow_mut().take());
+            }
+        }
+
+        IMPORTS.with(|imports| *imports.borrow_mut() = Some(self));
+        let _reset = Reset;
+
+        f()
+    }
+
+    fn load_shape(
+        &mut self,
+        shape: &fj::Shape,
+        objects: &mut Service<Objects>,
+    ) -> Result<(), Error> {
+        match shape {
+            fj::Shape::Shape2d(shape) => self.load_shape_2d(shape, objects),
+            fj::Shape::Group(group) => {
+                self.load_shape(&group.a, objects)?;
+                self.load_shape(&group.b, objects)
+            }
+            fj::Shape::Sweep(sweep) => {
+                self.load_shape_2d(sweep.shape(), objects)
+            }
+            fj::Shape::HelicalSweep(sweep) => {
+                self.load_shape_2d(sweep.shape(), objects)
+            }
+            fj::Shape::Holes(holes) => {
+                self.load_shape_2d(holes.sweep().shape(), objects)
+            }
+            fj::Shape::Part(part) => self.load_shape(part.shape(), objects),
+            fj::Shape::Assembly(assembly) => {
+                for part in assembly.instances() {
+                    self.load_shape(part.shape(), objects)?;
+                }
+                Ok(())
+            }
+            fj::Shape::Transform(transform) => {
+                self.load_shape(&transform.shape, objects)
+            }
+            fj::Shape::Mirror(mirror) => {
+                self.load_shape(mirror.shape(), objects)
+            }
+            fj::Shape::Pattern(pattern) => {
+                self.load_shape(pattern.shape(), objects)
+            }
+            fj::Shape::Boolean(boolean) => {
+                self.load_shape(boolean.a(), objects)?;
+                self.load_shape(boolean.b(), objects)
+            }
+            fj::Shape::Brep(_) => Ok(()),
+            fj::Shape::Import(import) => {
+                if !self.solids.contains_key(import.path()) {
+                    let faces = import_solids(import.path(), objects).map_err(
+                        |source| Error::Import {
+                            path: import.path().to_string(),
+                            source,
+                        },
+                    )?;
+                    self.solids.insert(import.path().to_string(), faces);
+                }
+                Ok(())
+            }
+        }
+    }
+
+    fn load_shape_2d(
+        &mut self,
+        shape: &fj::Shape2d,
+        objects: &mut Service<Objects>,
+    ) -> Result<(), Error> {
+        match shape {
+            fj::Shape2d::Difference(difference) => {
+                for shape in difference.shapes() {
+                    self.load_shape_2d(shape, objects)?;
+                }
+                Ok(())
+            }
+            fj::Shape2d::Sketch(_) => Ok(()),
+            fj::Shape2d::Import(import) => {
+                if !self.sketches.contains_key(import.path()) {
+                    let sketch = import_sketch(import.path(), objects)
+                        .map_err(|source| Error::Import {
+                            path: import.path().to_string(),
+                            source,
+                        })?;
+                    self.sketches.insert(import.path().to_string(), sketch);
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+impl Shape for fj::Import {
+    type Brep = FaceSet;
+
+    fn compute_brep(
+        &self,
+        objects: &mut Service<Objects>,
+        debug_info: &mut DebugInfo,
+    ) -> Self::Brep {
+        instrument("Import", debug_info, |_| {
+            let provided = IMPORTS.with(|imports| {
+                imports.borrow().as_ref().and_then(|imports| {
+                    imports.solids.get(self.path()).cloned()
+                })
+            });
+
+            provided.unwrap_or_else(|| {
+                import_solids(self.path(), objects).unwrap_or_else(|err| {
+                    error!("Failed to import `{}`: {err}", self.path());
+                    FaceSet::new()
+                })
+            })
+        })
+    }
+
+    fn bounding_volume(&self) -> Aabb<3> {
+        let provided = IMPORTS.with(|imports| {
+            imports.borrow().as_ref().and_then(|imports| {
+                imports.solids.get(self.path()).map(|faces| faces.aabb())
+            })
+        });
+        if let Some(aabb) = provided {
+            return aabb.unwrap_or_default();
+        }
+
+        // The extent of the shape is only known after reading the file, so
+        // there's no way around importing it here.
+        let mut services = Services::new();
+        let faces =
+            self.compute_brep(&mut services.objects, &mut DebugInfo::new());
+
+        // Errors are reported when the shape is evaluated for real.
+        services.validation.lock().take_errors();
+
+        faces.aabb().unwrap_or_default()
+    }
+}
+
+impl Shape for fj::Import2d {
+    type Brep = Sketch;
+
+    fn compute_brep(
+        &self,
+        objects: &mut Service<Objects>,
+        debug_info: &mut DebugInfo,
+    ) -> Self::Brep {
+        instrument("Import2d", debug_info, |_| {
+            let provided = IMPORTS.with(|imports| {
+                imports.borrow().as_ref().and_then(|imports| {
+                    imports.sketches.get(self.path()).cloned()
+                })
+            });
+
+            match provided {
+                Some(sketch) => sketch.deref().clone(),
+                None => match import_sketch(self.path(), objects) {
+                    Ok(sketch) => sketch.deref().clone(),
+                    Err(err) => {
+                        error!("Failed to import `{}`: {err}", self.path());
+                        Sketch::new(FaceSet::new())
+                    }
+                },
+            }
+        })
+    }
+
+    fn bounding_volume(&self) -> Aabb<3> {
+        let provided = IMPORTS.with(|imports| {
+            imports.borrow().as_ref().and_then(|imports| {
+                imports
+                    .sketches
+                    .get(self.path())
+                    .map(|sketch| sketch.faces().aabb())
+            })
+        });
+        if let Some(aabb) = provided {
+            return aabb.unwrap_or_default();
+        }
+
+        // See the implementation for `fj::Import`.
+        let mut services = Services::new();
+        let sketch =
+            self.compute_brep(&mut services.objects, &mut DebugInfo::new());
+
+        services.validation.lock().take_errors();
+
+        sketch.faces().aabb().unwrap_or_default()
+    }
+}
+
+fn import_solids(
+    path: &str,
+    objects: &mut Service<Objects>,
+) -> Result<FaceSet, fj_import::Error> {
+    let solids = fj_import::import(Path::new(path), objects)?;
+
+    Ok(solids
+        .iter()
+        .flat_map(|solid| solid.shells())
+        .flat_map(|shell| shell.faces().clone())
+        .collect())
+}
+
+fn import_sketch(
+    path: &str,
+    objects: &mut Service<Objects>,
+) -> Result<Handle<Sketch>, fj_import::Error> {
+    let surface = objects.surfaces.xy_plane();
+    fj_import::import_sketch(Path::new(path), surface, objects)
+}
@@ -0,0 +1,36 @@
+//! Instrumentation of operation evaluation
+
+use std::{mem, time::Instant};
+
+use fj_interop::debug::{DebugInfo, OperationTiming};
+use tracing::debug_span;
+
+/// Evaluate an operation, recording how long the evaluation took
+///
+/// Wraps the evaluation in a tracing span and records an [`OperationTiming`]
+/// in the provided [`DebugInfo`]. Any operations that are evaluated as part of
+/// `evaluate` are recorded as children of this one.
+pub fn instrument<T>(
+    name: &str,
+    debug_info: &mut DebugInfo,
+    evaluate: impl FnOnce(&mut DebugInfo) -> T,
+) -> T {
+    let span = debug_span!("compute_brep", operation = name);
+    let _guard = span.enter();
+
+    let parent_timings = mem::take(&mut debug_info.operation_timings);
+
+    let start = Instant::now();
+    let result = evaluate(debug_info);
+    let duration = start.elapsed();
+
+    let children =
+        mem::replace(&mut debug_info.operation_timings, parent_timings);
+    debug_info.operation_timings.push(OperationTiming {
+        name: name.into(),
+        duration,
+        children,
+    });
+
+    result
+}
@@ -21,10 +21,23 @@
 // infrastructure is in flux anyway. Maybe the problem will take care of itself.
 #![allow(clippy::result_large_err)]
 
+pub mod assembly;
+pub mod dry_run;
+pub mod mesh_cache;
 pub mod shape_processor;
+pub mod tessellation_cache;
 
+mod boolean;
+mod brep;
 mod difference_2d;
 mod group;
+mod holes;
+mod import;
+mod instrument;
+mod part;
+mod pattern;
+#[cfg(test)]
+mod regression;
 mod sketch;
 mod sweep;
 mod transform;
@@ -62,7 +75,7 @@ impl Shape for fj::Shape {
         &self,
         objects: &mut Service<Objects>,
         debug_info: &mut DebugInfo,
-    ) -> Self::Brep {
+    ) -> FaceSet {
         match self {
             Self::Shape2d(shape) => {
                 shape.compute_brep(objects, debug_info).faces().clone()
@@ -77,7 +90,32 @@ impl Shape for fj::Shape {
                     a
                 })
                 .unwrap_or_default(),
+            Self::HelicalSweep(shape) => shape
+                .compute_brep(objects, debug_info)
+                .shells()
+                .map(|shell| shell.faces().clone())
+                .reduce(|mut a, b| {
+                    a.extend(b);
+                    a
+                })
+                .unwrap_or_default(),
+            Self::Holes(shape) => shape
+                .compute_brep(objects, debug_info)
+                .shells()
+                .map(|shell| shell.faces().clone())
+                .reduce(|mut a, b| {
+                    a.extend(b);
+                    a
+                })
+                .unwrap_or_default(),
+            Self::Part(shape) => shape.compute_brep(objects, debug_info),
+            Self::Assembly(shape) => shape.compute_brep(objects, debug_info),
             Self::Transform(shape) => shape.compute_brep(objects, debug_info),
+            Self::Mirror(shape) => shape.compute_brep(objects, debug_info),
+            Self::Pattern(shape) => shape.compute_brep(objects, debug_info),
+            Self::Boolean(shape) => shape.compute_brep(objects, debug_info),
+            Self::Brep(shape) => shape.compute_brep(objects, debug_info),
+            Self::Import(shape) => shape.compute_brep(objects, debug_info),
         }
     }
 
@@ -86,7 +124,16 @@ impl Shape for fj::Shape {
             Self::Shape2d(shape) => shape.bounding_volume(),
             Self::Group(shape) => shape.bounding_volume(),
             Self::Sweep(shape) => shape.bounding_volume(),
+            Self::HelicalSweep(shape) => shape.bounding_volume(),
+            Self::Holes(shape) => shape.bounding_volume(),
+            Self::Part(shape) => shape.bounding_volume(),
+            Self::Assembly(shape) => shape.bounding_volume(),
             Self::Transform(shape) => shape.bounding_volume(),
+            Self::Mirror(shape) => shape.bounding_volume(),
+            Self::Pattern(shape) => shape.bounding_volume(),
+            Self::Boolean(shape) => shape.bounding_volume(),
+            Self::Brep(shape) => shape.bounding_volume(),
+            Self::Import(shape) => shape.bounding_volume(),
         }
     }
 }
@@ -102,6 +149,7 @@ impl Shape for fj::Shape2d {
         match self {
             Self::Difference(shape) => shape.compute_brep(objects, debug_info),
             Self::Sketch(shape) => shape.compute_brep(objects, debug_info),
+            Self::Import(shape) => shape.compute_brep(objects, debug_info),
         }
     }
 
@@ -109,6 +157,7 @@ impl Shape for fj::Shape2d {
         match self {
             Self::Difference(shape) => shape.bounding_volume(),
             Self::Sketch(shape) => shape.bounding_volume(),
+            Self::Import(shape) => shape.bounding_volume(),
         }
     }
 }
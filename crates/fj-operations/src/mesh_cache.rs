@@ -0,0 +1,611 @@
+//! Persistent cache of processed shapes
+//!
+//! See [`MeshCache`].
+
+use std::{
+    collections::{hash_map::DefaultHasher, BTreeMap},
+    fs::{self, File},
+    hash::{Hash, Hasher},
+    io::{self, BufReader, BufWriter, Read, Write},
+    path::{Path, PathBuf},
+    time::UNIX_EPOCH,
+};
+
+use fj_interop::{
+    debug::DebugInfo,
+    mesh::{Attributes, Color, Mesh, Origin, Triangle},
+    processed_shape::{ProcessedPart, ProcessedShape},
+    units::Units,
+};
+use fj_kernel::algorithms::properties::mesh_properties;
+use fj_math::{Aabb, Point, Scalar, Vector};
+
+use crate::shape_processor::{EvaluatedShape, ShapeProcessor};
+
+/// Stores processed shapes on disk, so they can be reused by later runs
+///
+/// Entries are keyed by the definition of the shape, the settings of the
+/// [`ShapeProcessor`], and the modification times of the files that the shape
+/// imports. If a model is opened again without having changed, its shape
+/// doesn't need to be evaluated or triangulated.
+///
+/// The key is computed using the hasher of the standard library, which is not
+/// guaranteed to stay the same across Rust versions. That only means that
+/// entries aren't found again, after a toolchain update.
+///
+/// The cache keeps the most recently stored entries, up to
+/// [`MeshCache::MAX_ENTRIES`], and removes older ones.
+pub struct MeshCache {
+    dir: PathBuf,
+}
+
+impl MeshCache {
+    /// The maximum number of entries that are kept
+    pub const MAX_ENTRIES: usize = 32;
+
+    /// Create a `MeshCache` that stores its entries in the given directory
+    ///
+    /// The directory is created when the first entry is stored.
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        Self { dir: dir.into() }
+    }
+
+    /// Load the processed shape from the cache, if available
+    ///
+    /// Returns `Ok(None)`, if the cache has no entry for the shape.
+    pub fn load(
+        &self,
+        shape: &fj::Shape,
+        shape_processor: &ShapeProcessor,
+    ) -> io::Result<Option<CachedShape>> {
+        let path = self.path(shape, shape_processor);
+
+        let file = match File::open(path) {
+            Ok(file) => file,
+            Err(err) if err.kind() == io::ErrorKind::NotFound => {
+                return Ok(None);
+            }
+            Err(err) => return Err(err),
+        };
+
+        read(BufReader::new(file)).map(Some)
+    }
+
+    /// Store a processed shape in the cache
+    ///
+    /// `processed` is expected to be a triangulation of `evaluated`, which is
+    /// expected to be the result of evaluating `shape` with `shape_processor`.
+    pub fn store(
+        &self,
+        shape: &fj::Shape,
+        shape_processor: &ShapeProcessor,
+        evaluated: &EvaluatedShape,
+        processed: &ProcessedShape,
+    ) -> io::Result<()> {
+        fs::create_dir_all(&self.dir)?;
+
+        // Write to a temporary file first, so other instances of the
+        // application never see an incomplete entry.
+        let path = self.path(shape, shape_processor);
+        let tmp = path.with_extension(format!("tmp-{}", std::process::id()));
+
+        let mut file = BufWriter::new(File::create(&tmp)?);
+        write(&mut file, evaluated, processed)?;
+        file.flush()?;
+        drop(file);
+
+        fs::rename(tmp, path)?;
+
+        self.remove_old_entries()
+    }
+
+    fn path(
+        &self,
+        shape: &fj::Shape,
+        shape_processor: &ShapeProcessor,
+    ) -> PathBuf {
+        let mut hasher = DefaultHasher::new();
+
+        // The output of the shape processor changes between versions.
+        env!("CARGO_PKG_VERSION").hash(&mut hasher);
+
+        // Shapes contain floating-point numbers, which don't implement `Hash`.
+        // Their debug representation is exact though, so it can be hashed
+        // instead.
+        format!("{shape:?}").hash(&mut hasher);
+        format!(
+            "{:?} {:?}",
+            shape_processor.tolerance, shape_processor.angular_tolerance
+        )
+        .hash(&mut hasher);
+
+        let mut files = Vec::new();
+        imported_files(shape, &mut files);
+        for file in files {
+            // Files that can't be accessed result in a key that is never
+            // found. Evaluating the shape will report the actual error.
+            let modified = fs::metadata(file)
+                .and_then(|metadata| metadata.modified())
+                .ok()
+                .and_then(|modified| modified.duration_since(UNIX_EPOCH).ok());
+            modified.hash(&mut hasher);
+        }
+
+        self.dir.join(format!("{:016x}.mesh", hasher.finish()))
+    }
+
+    fn remove_old_entries(&self) -> io::Result<()> {
+        let mut entries = Vec::new();
+        for entry in fs::read_dir(&self.dir)? {
+            let entry = entry?;
+            if entry.path().extension() != Some("mesh".as_ref()) {
+                continue;
+            }
+
+            entries.push((entry.metadata()?.modified()?, entry.path()));
+        }
+
+        entries.sort();
+        let num_old = entries.len().saturating_sub(Self::MAX_ENTRIES);
+        for (_, path) in entries.into_iter().take(num_old) {
+            fs::remove_file(path)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// A processed shape that has been loaded from a [`MeshCache`]
+///
+/// The triangles of the shape don't know the objects they were triangulated
+/// from, which is what picking and highlighting rely on. Evaluating the shape
+/// and triangulating it with its own tolerance results in the same mesh, with
+/// these objects known.
+pub struct CachedShape {
+    /// The processed shape
+    ///
+    /// The debug info is empty, as none of the operations that would have
+    /// created it, have been run.
+    pub shape: ProcessedShape,
+
+    /// The number of faces of the shape
+    ///
+    /// See [`EvaluatedShape::num_faces`].
+    pub num_faces: usize,
+
+    /// The number of half-edges of the shape
+    ///
+    /// See [`EvaluatedShape::num_half_edges`].
+    pub num_half_edges: usize,
+}
+
+const MAGIC: &[u8; 8] = b"FJMESH04";
+const MAX_STRING_LEN: usize = 4096;
+
+fn write(
+    writer: &mut impl Write,
+    evaluated: &EvaluatedShape,
+    processed: &ProcessedShape,
+) -> io::Result<()> {
+    writer.write_all(MAGIC)?;
+
+    write_usize(writer, evaluated.num_faces())?;
+    write_usize(writer, evaluated.num_half_edges())?;
+
+    for point in [processed.aabb.min, processed.aabb.max] {
+        write_point(writer, point)?;
+    }
+    write_scalar(writer, processed.tolerance)?;
+
+    write_usize(writer, processed.parts.len())?;
+    for part in &processed.parts {
+        write_string(writer, &part.name)?;
+        writer.write_all(&[match part.units {
+            Units::Millimeters => 0,
+            Units::Inches => 1,
+            Units::Meters => 2,
+        }])?;
+
+        // Most triangles share their attributes with many others, so each
+        // distinct set of attributes is only stored once.
+        let mut attributes = BTreeMap::new();
+        for triangle in part.mesh.triangles() {
+            let index = attributes.len();
+            attributes.entry(triangle.attributes).or_insert(index);
+        }
+        let mut table = attributes.iter().collect::<Vec<_>>();
+        table.sort_by_key(|&(_, &index)| index);
+
+        write_usize(writer, table.len())?;
+        for (attributes, _) in table {
+            for value in [&attributes.name, &attributes.material] {
+                match value {
+                    Some(value) => {
+                        writer.write_all(&[1])?;
+                        write_string(writer, value)?;
+                    }
+                    None => writer.write_all(&[0])?,
+                }
+            }
+        }
+
+        write_usize(writer, part.mesh.triangles().count())?;
+        for triangle in part.mesh.triangles() {
+            for point in triangle.inner.points() {
+                write_point(writer, point)?;
+            }
+            writer.write_all(&triangle.color.0)?;
+
+            match triangle.normals {
+                Some(normals) => {
+                    writer.write_all(&[1])?;
+                    for normal in normals {
+                        write_vector(writer, normal)?;
+                    }
+                }
+                None => writer.write_all(&[0])?,
+            }
+
+            write_usize(writer, attributes[&triangle.attributes])?;
+        }
+    }
+
+    Ok(())
+}
+
+fn read(mut reader: impl Read) -> io::Result<CachedShape> {
+    let reader = &mut reader;
+
+    let mut magic = [0; 8];
+    reader.read_exact(&mut magic)?;
+    if &magic != MAGIC {
+        return Err(invalid_data("Not a mesh cache entry"));
+    }
+
+    let num_faces = read_usize(reader)?;
+    let num_half_edges = read_usize(reader)?;
+
+    let aabb = Aabb {
+        min: read_point(reader)?,
+        max: read_point(reader)?,
+    };
+    let tolerance = read_scalar(reader)?;
+
+    let mut mesh = Mesh::new();
+    let mut parts = Vec::new();
+    for _ in 0..read_usize(reader)? {
+        let name = read_string(reader)?;
+
+        let mut units = [0];
+        reader.read_exact(&mut units)?;
+        let units = match units {
+            [0] => Units::Millimeters,
+            [1] => Units::Inches,
+            [2] => Units::Meters,
+            _ => return Err(invalid_data("Invalid units")),
+        };
+
+        let mut attributes = Vec::new();
+        for _ in 0..read_usize(reader)? {
+            let mut values = [None, None];
+            for value in &mut values {
+                let mut is_some = [0];
+                reader.read_exact(&mut is_some)?;
+                *value = match is_some {
+                    [0] => None,
+                    [1] => Some(read_string(reader)?.into()),
+                    _ => return Err(invalid_data("Invalid attribute marker")),
+                };
+            }
+
+            let [name, material] = values;
+            attributes.push(Attributes { name, material });
+        }
+
+        let mut part_mesh = Mesh::new();
+        for _ in 0..read_usize(reader)? {
+            let points = [
+                read_point(reader)?,
+                read_point(reader)?,
+                read_point(reader)?,
+            ];
+            let mut color = [0; 4];
+            reader.read_exact(&mut color)?;
+
+            let mut has_normals = [0];
+            reader.read_exact(&mut has_normals)?;
+            let normals = match has_normals {
+                [0] => None,
+                [1] => Some([
+                    read_vector(reader)?,
+                    read_vector(reader)?,
+                    read_vector(reader)?,
+                ]),
+                _ => return Err(invalid_data("Invalid normals marker")),
+            };
+
+            let attributes = attributes
+                .get(read_usize(reader)?)
+                .ok_or_else(|| invalid_data("Invalid attributes index"))?
+                .clone();
+
+            let triangle = Triangle {
+                inner: points.into(),
+                normals,
+                color: Color(color),
+                attributes,
+                // The objects that the mesh was triangulated from don't exist
+                // anymore.
+                origin: Origin::default(),
+            };
+            part_mesh.extend([triangle.clone()]);
+            mesh.extend([triangle]);
+        }
+
+        parts.push(ProcessedPart {
+            name,
+            mesh: part_mesh,
+            units,
+        });
+    }
+
+    let shape = ProcessedShape {
+        aabb,
+        tolerance,
+        mass_properties: mesh_properties(&mesh),
+        mesh,
+        parts,
+        debug_info: DebugInfo::new(),
+    };
+
+    Ok(CachedShape {
+        shape,
+        num_faces,
+        num_half_edges,
+    })
+}
+
+fn write_usize(writer: &mut impl Write, value: usize) -> io::Result<()> {
+    writer.write_all(&(value as u64).to_le_bytes())
+}
+
+fn write_scalar(writer: &mut impl Write, value: Scalar) -> io::Result<()> {
+    writer.write_all(&value.into_f64().to_le_bytes())
+}
+
+fn write_point(writer: &mut impl Write, point: Point<3>) -> io::Result<()> {
+    write_vector(writer, point.coords)
+}
+
+fn write_vector(writer: &mut impl Write, vector: Vector<3>) -> io::Result<()> {
+    for component in vector.components {
+        write_scalar(writer, component)?;
+    }
+    Ok(())
+}
+
+fn write_string(writer: &mut impl Write, value: &str) -> io::Result<()> {
+    write_usize(writer, value.len())?;
+    writer.write_all(value.as_bytes())
+}
+
+fn read_usize(reader: &mut impl Read) -> io::Result<usize> {
+    let mut bytes = [0; 8];
+    reader.read_exact(&mut bytes)?;
+
+    usize::try_from(u64::from_le_bytes(bytes))
+        .map_err(|_| invalid_data("Length is too large"))
+}
+
+fn read_string(reader: &mut impl Read) -> io::Result<String> {
+    // Guard against allocating absurd amounts of memory, if the entry is
+    // corrupted.
+    let len = read_usize(reader)?;
+    if len > MAX_STRING_LEN {
+        return Err(invalid_data("String is too long"));
+    }
+
+    let mut bytes = vec![0; len];
+    reader.read_exact(&mut bytes)?;
+    String::from_utf8(bytes)
+        .map_err(|_| invalid_data("String is not valid UTF-8"))
+}
+
+fn read_scalar(reader: &mut impl Read) -> io::Result<Scalar> {
+    let mut bytes = [0; 8];
+    reader.read_exact(&mut bytes)?;
+
+    let value = f64::from_le_bytes(bytes);
+    if value.is_nan() {
+        return Err(invalid_data("Number is NaN"));
+    }
+
+    Ok(Scalar::from_f64(value))
+}
+
+fn read_point(reader: &mut impl Read) -> io::Result<Point<3>> {
+    Ok(Point::from([
+        read_scalar(reader)?,
+        read_scalar(reader)?,
+        read_scalar(reader)?,
+    ]))
+}
+
+fn read_vector(reader: &mut impl Read) -> io::Result<Vector<3>> {
+    Ok(read_point(reader)?.coords)
+}
+
+fn invalid_data(message: &str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message)
+}
+
+/// Collect the paths of all files that a shape imports
+fn imported_files<'r>(shape: &'r fj::Shape, files: &mut Vec<&'r Path>) {
+    fn shape_2d<'r>(shape: &'r fj::Shape2d, files: &mut Vec<&'r Path>) {
+        match shape {
+            fj::Shape2d::Difference(difference) => {
+                for shape in difference.shapes() {
+                    shape_2d(shape, files);
+                }
+            }
+            fj::Shape2d::Sketch(_) => {}
+            fj::Shape2d::Import(import) => files.push(Path::new(import.path())),
+        }
+    }
+
+    match shape {
+        fj::Shape::Group(group) => {
+            imported_files(&group.a, files);
+            imported_files(&group.b, files);
+        }
+        fj::Shape::Shape2d(shape) => shape_2d(shape, files),
+        fj::Shape::Sweep(sweep) => shape_2d(sweep.shape(), files),
+        fj::Shape::HelicalSweep(sweep) => shape_2d(sweep.shape(), files),
+        fj::Shape::Holes(holes) => shape_2d(holes.sweep().shape(), files),
+        fj::Shape::Part(part) => imported_files(part.shape(), files),
+        fj::Shape::Assembly(assembly) => {
+            for part in assembly.instances() {
+                imported_files(part.shape(), files);
+            }
+        }
+        fj::Shape::Transform(transform) => {
+            imported_files(&transform.shape, files);
+        }
+        fj::Shape::Mirror(mirror) => imported_files(mirror.shape(), files),
+        fj::Shape::Pattern(pattern) => imported_files(pattern.shape(), files),
+        fj::Shape::Boolean(boolean) => {
+            imported_files(boolean.a(), files);
+            imported_files(boolean.b(), files);
+        }
+        fj::Shape::Import(import) => files.push(Path::new(import.path())),
+        fj::Shape::Brep(_) => {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use fj::syntax::*;
+    use fj_interop::{
+        mesh::{Origin, Triangle},
+        units::Units,
+    };
+    use fj_kernel::algorithms::approx::Tolerance;
+
+    use crate::{
+        shape_processor::ShapeProcessor, tessellation_cache::TessellationCache,
+    };
+
+    use super::MeshCache;
+
+    #[test]
+    fn load_stored_shape() {
+        let dir = std::env::temp_dir()
+            .join(format!("fj-mesh-cache-test-{}", std::process::id()));
+        let cache = MeshCache::new(&dir);
+
+        let processor = ShapeProcessor {
+            tolerance: Some(Tolerance::from_scalar(0.01).unwrap()),
+            angular_tolerance: None,
+        };
+        let cylinder: fj::Shape = fj::Part::new(
+            "cylinder",
+            fj::Sketch::from_circle(fj::Circle::from_radius(1.))
+                .with_material("PLA")
+                .sweep([0., 0., 2.]),
+        )
+        .with_units(fj::Units::Inches)
+        .into();
+        let cuboid: fj::Shape =
+            fj::Sketch::from_points(vec![[0., 0.], [1., 0.], [1., 1.]])
+                .sweep([0., 0., 1.])
+                .into();
+
+        assert!(cache.load(&cylinder, &processor).unwrap().is_none());
+
+        let evaluated = processor.evaluate(&cylinder).unwrap();
+        let processed = evaluated.triangulate(evaluated.tolerance());
+        cache
+            .store(&cylinder, &processor, &evaluated, &processed)
+            .unwrap();
+
+        let cached = cache.load(&cylinder, &processor).unwrap().unwrap();
+        assert_eq!(cached.num_faces, evaluated.num_faces());
+        assert_eq!(cached.num_half_edges, evaluated.num_half_edges());
+        assert_eq!(cached.shape.aabb, processed.aabb);
+        assert_eq!(cached.shape.tolerance, processed.tolerance);
+        assert_eq!(cached.shape.mass_properties, processed.mass_properties);
+        // The objects that the triangles were created from aren't stored.
+        assert!(cached.shape.mesh.triangles().eq(processed
+            .mesh
+            .triangles()
+            .map(|triangle| Triangle {
+                origin: Origin::default(),
+                ..triangle
+            })));
+        assert_eq!(cached.shape.parts[0].units, Units::Inches);
+
+        // Neither a different shape, nor different settings, find the entry.
+        assert!(cache.load(&cuboid, &processor).unwrap().is_none());
+        let processor = ShapeProcessor {
+            tolerance: None,
+            ..processor
+        };
+        assert!(cache.load(&cylinder, &processor).unwrap().is_none());
+
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn cached_shape_is_pickable_after_evaluation() {
+        let dir = std::env::temp_dir()
+            .join(format!("fj-mesh-cache-pick-test-{}", std::process::id()));
+        let cache = MeshCache::new(&dir);
+
+        let processor = ShapeProcessor {
+            tolerance: None,
+            angular_tolerance: None,
+        };
+        let shape: fj::Shape =
+            fj::Sketch::from_circle(fj::Circle::from_radius(1.))
+                .sweep([0., 0., 1.])
+                .into();
+
+        let evaluated = processor.evaluate(&shape).unwrap();
+        let processed = evaluated.triangulate(evaluated.tolerance());
+        cache
+            .store(&shape, &processor, &evaluated, &processed)
+            .unwrap();
+        let cached = cache.load(&shape, &processor).unwrap().unwrap();
+        assert!(cached
+            .shape
+            .mesh
+            .triangles()
+            .all(|triangle| triangle.origin.face.is_none()));
+
+        // This is what happens, once a shape that was loaded from the cache is
+        // picked for the first time.
+        let mut tessellation_cache = TessellationCache::new();
+        let evaluated = processor.evaluate(&shape).unwrap();
+        let processed = evaluated.triangulate_with_cache(
+            evaluated.tolerance(),
+            &mut tessellation_cache,
+        );
+
+        assert_eq!(processed.aabb, cached.shape.aabb);
+        assert_eq!(
+            processed.mesh.triangles().count(),
+            cached.shape.mesh.triangles().count()
+        );
+        assert!(processed
+            .mesh
+            .triangles()
+            .all(|triangle| triangle.origin.face.is_some()));
+        assert!(processed.mesh.triangles().any(|triangle| triangle
+            .origin
+            .edges
+            .iter()
+            .any(Option::is_some)));
+
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+}
@@ -0,0 +1,49 @@
+use fj_interop::{debug::DebugInfo, mesh::Color};
+use fj_kernel::{
+    insert::Insert,
+    objects::{Face, FaceSet, Objects},
+    services::Service,
+};
+use fj_math::Aabb;
+
+use crate::Shape;
+
+impl Shape for fj::Part {
+    type Brep = FaceSet;
+
+    fn compute_brep(
+        &self,
+        objects: &mut Service<Objects>,
+        debug_info: &mut DebugInfo,
+    ) -> Self::Brep {
+        let faces = self.shape().compute_brep(objects, debug_info);
+
+        if self.color().is_none() && self.material().is_none() {
+            return faces;
+        }
+
+        faces
+            .into_iter()
+            .map(|face| {
+                let color = self.color().map_or(face.color(), Color);
+
+                let mut attributes = face.attributes().clone();
+                if attributes.material.is_none() {
+                    attributes.material = self.material().map(Into::into);
+                }
+
+                Face::new(
+                    face.exterior().clone(),
+                    face.interiors().cloned(),
+                    color,
+                )
+                .with_attributes(attributes)
+                .insert(objects)
+            })
+            .collect()
+    }
+
+    fn bounding_volume(&self) -> Aabb<3> {
+        self.shape().bounding_volume()
+    }
+}
@@ -0,0 +1,64 @@
+use fj_interop::debug::DebugInfo;
+use fj_kernel::{
+    algorithms::pattern::{pattern, Pattern},
+    objects::{FaceSet, Objects, Shell, Solid},
+    services::Service,
+};
+use fj_math::{Aabb, Point, Vector};
+
+use crate::{instrument::instrument, Shape};
+
+impl Shape for fj::Pattern {
+    type Brep = FaceSet;
+
+    fn compute_brep(
+        &self,
+        objects: &mut Service<Objects>,
+        debug_info: &mut DebugInfo,
+    ) -> Self::Brep {
+        instrument("Pattern", debug_info, |debug_info| {
+            let faces = self.shape().compute_brep(objects, debug_info);
+
+            let shell = Shell::builder().with_faces(faces).build(objects);
+            let solid = Solid::builder().with_shells([shell]).build(objects);
+
+            let solid = pattern(&solid, &make_pattern(self), objects)
+                .expect("Failed to create pattern");
+
+            let mut faces = FaceSet::new();
+            for shell in solid.shells() {
+                faces.extend(shell.faces().clone());
+            }
+            faces
+        })
+    }
+
+    fn bounding_volume(&self) -> Aabb<3> {
+        let aabb = self.shape().bounding_volume();
+
+        let transforms = make_pattern(self)
+            .transforms()
+            .expect("Failed to create pattern");
+
+        Aabb::<3>::from_points(transforms.iter().flat_map(|transform| {
+            aabb.vertices()
+                .map(|vertex| transform.transform_point(&vertex))
+        }))
+    }
+}
+
+fn make_pattern(pattern: &fj::Pattern) -> Pattern {
+    let count = pattern.count() as usize;
+
+    match *pattern.kind() {
+        fj::PatternKind::Linear { offset } => Pattern::Linear {
+            offset: Vector::from(offset),
+            count,
+        },
+        fj::PatternKind::Circular { center, axis } => Pattern::Circular {
+            center: Point::from(center),
+            axis: Vector::from(axis),
+            count,
+        },
+    }
+}
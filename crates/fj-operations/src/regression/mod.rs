@@ -0,0 +1,242 @@
+//! Regression tests that evaluate a corpus of representative models
+//!
+//! Unit tests cover individual algorithms, but can't catch regressions that
+//! only show up when those algorithms interact. The tests in this module
+//! evaluate complete models, and compare properties of the results against
+//! golden values that are stored in `golden.txt`.
+//!
+//! If a change is expected to affect the results, the golden values can be
+//! updated by running the tests with the `FJ_UPDATE_GOLDEN` environment
+//! variable set. Review the diff of `golden.txt` before committing it!
+//!
+//! The mesh hash is very sensitive, and changes with any change to the
+//! triangulation, as well as with floating-point differences between
+//! platforms. The other properties are more robust, and any change to them
+//! should be taken seriously.
+
+use std::{collections::HashMap, env, f64::consts::PI, fmt::Write, fs};
+
+use fj::{syntax::*, Angle};
+use fj_interop::{mesh::Mesh, processed_shape::ProcessedShape};
+use fj_kernel::algorithms::{approx::Tolerance, properties::mesh_properties};
+use fj_math::Point;
+
+use crate::shape_processor::ShapeProcessor;
+
+const GOLDEN: &str =
+    concat!(env!("CARGO_MANIFEST_DIR"), "/src/regression/golden.txt");
+
+/// The models that make up the corpus
+///
+/// Keep this focused on shapes that exercise different combinations of
+/// algorithms.
+fn corpus() -> Vec<(&'static str, fj::Shape)> {
+    let cuboid = fj::Sketch::from_rectangle(fj::Rectangle::from_size(2., 3.))
+        .sweep([0., 0., 1.]);
+
+    vec![
+        ("cuboid", cuboid.clone().into()),
+        ("star", star().into()),
+        ("spacer", spacer().into()),
+        (
+            "drafted",
+            fj::Sketch::from_rectangle(fj::Rectangle::from_size(4., 4.))
+                .sweep([0., 0., 1.])
+                .with_draft(Angle::from_deg(10.))
+                .into(),
+        ),
+        (
+            "holes",
+            fj::Sketch::from_rectangle(fj::Rectangle::from_size(4., 4.))
+                .sweep([0., 0., 1.])
+                .hole(fj::Hole::through([-1., -1.], 0.5))
+                .hole(fj::Hole::blind([1., 1.], 0.5, 0.5))
+                .into(),
+        ),
+        (
+            "countersink",
+            fj::Sketch::from_rectangle(fj::Rectangle::from_size(4., 4.))
+                .sweep([0., 0., 1.])
+                .hole(
+                    fj::Hole::through([0., 0.], 0.5)
+                        .with_countersink(1., Angle::from_deg(90.)),
+                )
+                .into(),
+        ),
+        (
+            "transform",
+            cuboid
+                .rotate([1., 1., 1.], Angle::from_deg(45.))
+                .translate([3., 2., 1.])
+                .into(),
+        ),
+        (
+            "mirror",
+            star()
+                .translate([3., 0., 0.])
+                .mirror([0., 0., 0.], [1., 0., 0.])
+                .with_original()
+                .into(),
+        ),
+        ("pattern", cuboid.pattern_linear([3., 0., 0.], 3).into()),
+        (
+            "group",
+            cuboid.group(&spacer().translate([4., 0., 0.])).into(),
+        ),
+        (
+            "helix",
+            fj::Sketch::from_rectangle(fj::Rectangle::from_size(0.2, 0.2))
+                .sweep_along_helix(fj::Helix {
+                    radius: 1.,
+                    pitch: 0.5,
+                    turns: 1.5,
+                })
+                .into(),
+        ),
+        (
+            "bent",
+            fj::Sketch::from_rectangle(fj::Rectangle::from_size(1., 1.))
+                .difference(&fj::Sketch::from_circle(fj::Circle::from_radius(
+                    0.25,
+                )))
+                .sweep_along_path(
+                    vec![[0., 0., 0.], [0., 0., 2.], [2., 0., 4.]],
+                    fj::FrameOrientation::FixedUp { up: [0., 1., 0.] },
+                )
+                .into(),
+        ),
+        (
+            "boolean",
+            cuboid.subtract(&cuboid.translate([1., 1., 0.5])).into(),
+        ),
+    ]
+}
+
+fn star() -> fj::Sweep {
+    let num_vertices = 10;
+
+    let mut outer = Vec::new();
+    let mut inner = Vec::new();
+    for i in 0..num_vertices {
+        let angle = 2. * PI / num_vertices as f64 * i as f64;
+        let radius = if i % 2 == 0 { 1. } else { 2. };
+
+        let (sin, cos) = angle.sin_cos();
+        outer.push([cos * radius, sin * radius]);
+        inner.push([cos * radius / 2., sin * radius / 2.]);
+    }
+
+    fj::Sketch::from_points(outer)
+        .difference(&fj::Sketch::from_points(inner))
+        .sweep([0., 0., 1.])
+}
+
+fn spacer() -> fj::Sweep {
+    fj::Sketch::from_circle(fj::Circle::from_radius(1.))
+        .difference(&fj::Sketch::from_circle(fj::Circle::from_radius(0.5)))
+        .sweep([0., 0., 1.])
+}
+
+/// Summarize the properties of a processed shape that are compared
+fn summarize(shape: &ProcessedShape) -> String {
+    let volume = mesh_properties(&shape.mesh).volume;
+    let [min, max] = [shape.aabb.min, shape.aabb.max].map(|point| {
+        let [x, y, z] = point.coords.components.map(|c| c.into_f64());
+        format!("[{x:.6},{y:.6},{z:.6}]")
+    });
+
+    format!(
+        "volume={:.6} aabb={min}..{max} watertight={} triangles={} \
+        hash={:016x}",
+        volume.into_f64(),
+        is_watertight(&shape.mesh),
+        shape.mesh.triangles().count(),
+        mesh_hash(&shape.mesh),
+    )
+}
+
+/// Determine whether a mesh is closed and consistently oriented
+///
+/// That is the case, if every edge is shared by exactly two triangles, which
+/// traverse it in opposite directions.
+fn is_watertight(mesh: &Mesh<Point<3>>) -> bool {
+    let mut edges = HashMap::new();
+
+    for triangle in mesh.triangles() {
+        let [a, b, c] = triangle.inner.points();
+        for edge in [[a, b], [b, c], [c, a]] {
+            *edges.entry(edge).or_insert(0) += 1;
+        }
+    }
+
+    edges.iter().all(|(&[a, b], &count)| {
+        count == 1 && edges.get(&[b, a]).copied() == Some(1)
+    })
+}
+
+/// Compute a hash of the mesh that is stable across platforms and releases
+///
+/// Uses FNV-1a, as the hashers in the standard library don't guarantee
+/// stable output.
+fn mesh_hash(mesh: &Mesh<Point<3>>) -> u64 {
+    let mut hash: u64 = 0xcbf29ce484222325;
+
+    for triangle in mesh.triangles() {
+        for point in triangle.inner.points() {
+            for coord in point.coords.components {
+                // Round to get rid of differences in the last few bits, which
+                // would make the hash needlessly fragile.
+                let value = (coord.into_f64() * 1e9).round() as i64;
+
+                for byte in value.to_le_bytes() {
+                    hash ^= u64::from(byte);
+                    hash = hash.wrapping_mul(0x100000001b3);
+                }
+            }
+        }
+    }
+
+    hash
+}
+
+#[test]
+fn corpus_matches_golden_values() {
+    let processor = ShapeProcessor {
+        tolerance: Some(Tolerance::from_scalar(0.01).unwrap()),
+        angular_tolerance: None,
+    };
+
+    let mut actual = String::new();
+    for (name, shape) in corpus() {
+        let processed = processor
+            .process(&shape)
+            .unwrap_or_else(|err| panic!("Failed to process `{name}`: {err}"));
+
+        writeln!(actual, "{name} {}", summarize(&processed)).unwrap();
+    }
+
+    if env::var_os("FJ_UPDATE_GOLDEN").is_some() {
+        fs::write(GOLDEN, &actual).unwrap();
+        return;
+    }
+
+    let golden = fs::read_to_string(GOLDEN).unwrap_or_default();
+
+    let mut mismatches = String::new();
+    for (expected, actual) in golden.lines().zip(actual.lines()) {
+        if expected != actual {
+            writeln!(mismatches, "- {expected}\n+ {actual}").unwrap();
+        }
+    }
+    if golden.lines().count() != actual.lines().count() {
+        writeln!(mismatches, "Number of models doesn't match golden values")
+            .unwrap();
+    }
+
+    assert!(
+        mismatches.is_empty(),
+        "Regression corpus doesn't match golden values. Run with \
+        `FJ_UPDATE_GOLDEN=1` to update them, if the change is expected.\n\
+        {mismatches}"
+    );
+}
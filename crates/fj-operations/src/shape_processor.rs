@@ -1,60 +1,541 @@
 //! API for processing shapes
 
-use fj_interop::{debug::DebugInfo, processed_shape::ProcessedShape};
+use std::{
+    borrow::Cow,
+    fs::File,
+    io::{self, BufWriter, Write as _},
+    path::Path,
+};
+
+use fj_interop::{
+    debug::{DebugInfo, OperationTiming},
+    ext::ArrayExt,
+    mesh::Mesh,
+    processed_shape::{ProcessedPart, ProcessedShape},
+    units::Units,
+};
 use fj_kernel::{
     algorithms::{
+        analysis,
         approx::{InvalidTolerance, Tolerance},
+        bounding_volume::BoundingVolume,
+        properties::mesh_properties,
+        repair::repair,
         triangulate::Triangulate,
     },
-    services::Services,
+    geometry::surface::Curvature,
+    objects::{Face, FaceSet},
+    serialize::{Snapshot, SnapshotError},
+    services::{ServiceValidationExt, Services},
+    storage::Handle,
     validate::ValidationError,
 };
-use fj_math::Scalar;
+use fj_math::{Aabb, Point, Scalar};
+use tracing::{debug, info_span, warn};
 
-use crate::Shape as _;
+use crate::{
+    assembly, import::Imports, instrument::instrument,
+    tessellation_cache::TessellationCache, Shape as _,
+};
 
 /// Processes an [`fj::Shape`] into a [`ProcessedShape`]
 pub struct ShapeProcessor {
     /// The tolerance value used for creating the triangle mesh
+    ///
+    /// If this is `None`, a tolerance is derived from the size of the model.
     pub tolerance: Option<Tolerance>,
+
+    /// The maximum angle between adjacent segments of approximated curves
+    ///
+    /// A tolerance by itself approximates small circles with very few
+    /// segments. If this is set, strongly curved geometry is refined until it
+    /// stays within this angle, while flat and gently curved geometry is
+    /// approximated according to the tolerance alone.
+    pub angular_tolerance: Option<fj::Angle>,
 }
 
 impl ShapeProcessor {
     /// Process an [`fj::Shape`] into [`ProcessedShape`]
+    ///
+    /// The time spent evaluating each operation is recorded in the returned
+    /// shape's [`DebugInfo`], and logged at the debug level.
     pub fn process(&self, shape: &fj::Shape) -> Result<ProcessedShape, Error> {
-        let aabb = shape.bounding_volume();
+        let span = info_span!("process");
+        let _guard = span.enter();
 
-        let tolerance = match self.tolerance {
-            None => {
-                // Compute a reasonable default for the tolerance value. To do
-                // this, we just look at the smallest non-zero extent of the
-                // bounding box and divide that by some value.
-                let mut min_extent = Scalar::MAX;
-                for extent in aabb.size().components {
-                    if extent > Scalar::ZERO && extent < min_extent {
-                        min_extent = extent;
+        let evaluated = self.evaluate(shape)?;
+        Ok(evaluated.triangulate(evaluated.tolerance()))
+    }
+
+    /// Compute the boundary representation of an [`fj::Shape`]
+    ///
+    /// The result can be triangulated using [`EvaluatedShape::triangulate`],
+    /// as often as required, without evaluating the shape again.
+    pub fn evaluate(&self, shape: &fj::Shape) -> Result<EvaluatedShape, Error> {
+        let span = info_span!("evaluate");
+        let _guard = span.enter();
+
+        // The boundary representation doesn't depend on the tolerance, so it
+        // can be computed first. Its bounding box is more accurate than the
+        // one of the shape definition, which makes it a better basis for the
+        // default tolerance.
+        let mut services = Services::new();
+        let mut debug_info = DebugInfo::new();
+        let imports = Imports::load(shape, &mut services.objects)?;
+        let (parts, aabb) = imports.provide(|| {
+            let parts = parts(shape)
+                .iter()
+                .enumerate()
+                .map(|(i, shape)| {
+                    let (part, shape) = match &**shape {
+                        fj::Shape::Part(part) => (Some(&**part), part.shape()),
+                        shape => (None, shape),
+                    };
+
+                    let name = part.map_or_else(
+                        || format!("part-{}", i + 1),
+                        |part| part.name().to_string(),
+                    );
+                    let faces = match part {
+                        Some(part) => part.compute_brep(
+                            &mut services.objects,
+                            &mut debug_info,
+                        ),
+                        None => shape.compute_brep(
+                            &mut services.objects,
+                            &mut debug_info,
+                        ),
+                    };
+
+                    // Validate the overrides right away, so triangulating the
+                    // part can't fail later.
+                    let tolerance = part
+                        .and_then(fj::Part::tolerance)
+                        .map(Tolerance::from_scalar)
+                        .transpose()?;
+                    let max_angle = part
+                        .and_then(fj::Part::max_angle)
+                        .map(|angle| Scalar::from_f64(angle.rad()));
+                    if let Some(max_angle) = max_angle {
+                        Tolerance::from(Scalar::ONE)
+                            .with_max_angle(max_angle)?;
                     }
-                }
 
-                let tolerance = min_extent / Scalar::from_f64(1000.);
-                Tolerance::from_scalar(tolerance)?
-            }
+                    let units =
+                        part.map_or(fj::Units::default(), fj::Part::units);
+
+                    Ok(EvaluatedPart {
+                        name,
+                        faces,
+                        units: match units {
+                            fj::Units::Millimeters => Units::Millimeters,
+                            fj::Units::Inches => Units::Inches,
+                            fj::Units::Meters => Units::Meters,
+                        },
+                        tolerance,
+                        max_angle,
+                        solid: !matches!(shape, fj::Shape::Shape2d(_)),
+                    })
+                })
+                .collect::<Result<Vec<_>, Error>>()?;
+
+            let aabb = parts
+                .iter()
+                .filter_map(|part| part.faces.aabb())
+                .reduce(|a, b| a.merged(&b))
+                .unwrap_or_else(|| shape.bounding_volume());
+
+            Ok::<_, Error>((parts, aabb))
+        })?;
+
+        let validation_errors = services.validation.lock().take_errors();
+        if let Some(err) = validation_errors.into_iter().next() {
+            return Err(err.into());
+        }
+
+        let tolerance = match self.tolerance {
+            None => default_tolerance(&aabb),
             Some(user_defined_tolerance) => user_defined_tolerance,
         };
 
-        let mut services = Services::new();
-        let mut debug_info = DebugInfo::new();
-        let shape = shape.compute_brep(&mut services.objects, &mut debug_info);
-        let mesh = (&shape, tolerance).triangulate();
+        // Circles can ask for a finer segmentation than the tolerance would
+        // provide. The approximation limits the angle between segments
+        // locally, where the geometry is curved, so the finest segmentation
+        // that was asked for doesn't affect flat geometry.
+        let max_angle = [
+            self.angular_tolerance
+                .map(|angle| Scalar::from_f64(angle.rad())),
+            segmentation_max_angle(shape),
+        ]
+        .into_iter()
+        .flatten()
+        .filter(|&angle| angle > Scalar::ZERO)
+        .min();
+        let tolerance = match max_angle {
+            Some(max_angle) => tolerance.with_max_angle(max_angle)?,
+            None => tolerance,
+        };
 
-        Ok(ProcessedShape {
+        Ok(EvaluatedShape {
             aabb,
-            mesh,
+            tolerance,
+            parts,
             debug_info,
         })
     }
 }
 
+/// An [`fj::Shape`], whose boundary representation has been computed
+///
+/// Returned by [`ShapeProcessor::evaluate`].
+pub struct EvaluatedShape {
+    aabb: Aabb<3>,
+    tolerance: Tolerance,
+    parts: Vec<EvaluatedPart>,
+    debug_info: DebugInfo,
+}
+
+impl EvaluatedShape {
+    /// Access the tolerance that the shape processor chose for the shape
+    pub fn tolerance(&self) -> Tolerance {
+        self.tolerance
+    }
+
+    /// Count the faces of the shape
+    pub fn num_faces(&self) -> usize {
+        self.faces().count()
+    }
+
+    /// Count the half-edges of the shape
+    pub fn num_half_edges(&self) -> usize {
+        self.faces()
+            .flat_map(|face| face.all_cycles())
+            .map(|cycle| cycle.half_edges().count())
+            .sum()
+    }
+
+    /// Save the boundary representation of the shape to a file
+    ///
+    /// The file can be imported using [`fj::Import`], which restores the
+    /// shape without computing it again. Each part of the shape becomes a
+    /// solid. The file extension should be `.fjshape`, for it to be
+    /// recognized.
+    pub fn save(&self, path: &Path) -> Result<(), Error> {
+        let mut snapshot = Snapshot::new();
+        for part in &self.parts {
+            snapshot.add_faces(part.name.as_str(), &part.faces);
+        }
+
+        let mut file = BufWriter::new(File::create(path)?);
+        snapshot.write(&mut file)?;
+        file.flush()?;
+
+        Ok(())
+    }
+
+    /// Access the names, boundary representations, and units of the shape's
+    /// parts
+    pub fn parts(&self) -> impl Iterator<Item = (&str, &FaceSet, Units)> {
+        self.parts
+            .iter()
+            .map(|part| (part.name.as_str(), &part.faces, part.units))
+    }
+
+    /// Compute the curvature at each triangle of a triangulation of the shape
+    ///
+    /// Expects the mesh to have been triangulated from this shape with the
+    /// given tolerance, to use the exact curvature of the shape's surfaces.
+    /// Triangles that don't match the triangulation get an estimate instead.
+    /// See [`analysis::curvature`].
+    pub fn curvature(
+        &self,
+        mesh: &Mesh<Point<3>>,
+        tolerance: Tolerance,
+    ) -> Vec<Curvature> {
+        analysis::curvature(
+            mesh,
+            self.parts
+                .iter()
+                .map(|part| (&part.faces, part.tolerance(tolerance))),
+        )
+    }
+
+    fn faces(&self) -> impl Iterator<Item = &Handle<Face>> {
+        self.parts.iter().flat_map(|part| &part.faces)
+    }
+
+    /// Triangulate the shape with the given tolerance
+    ///
+    /// Parts that override the tolerance are triangulated with their own.
+    pub fn triangulate(&self, tolerance: Tolerance) -> ProcessedShape {
+        self.triangulate_faces(tolerance, |faces, tolerance, debug_info| {
+            (faces, tolerance).triangulate_with_debug_info(debug_info)
+        })
+    }
+
+    /// Triangulate the shape, reusing triangulations from previous runs
+    ///
+    /// Faces that haven't changed since the shape was last triangulated with
+    /// the same cache, apart from being moved or rotated, don't need to be
+    /// triangulated again. See [`TessellationCache`] for details.
+    pub fn triangulate_with_cache(
+        &self,
+        tolerance: Tolerance,
+        cache: &mut TessellationCache,
+    ) -> ProcessedShape {
+        let shape = self.triangulate_faces(
+            tolerance,
+            |faces, tolerance, debug_info| {
+                cache.triangulate(faces, tolerance, debug_info)
+            },
+        );
+        cache.finish_run();
+
+        shape
+    }
+
+    fn triangulate_faces(
+        &self,
+        tolerance: Tolerance,
+        mut triangulate: impl FnMut(
+            &FaceSet,
+            Tolerance,
+            &mut DebugInfo,
+        ) -> Mesh<Point<3>>,
+    ) -> ProcessedShape {
+        let span = info_span!("triangulate");
+        let _guard = span.enter();
+
+        let mut debug_info = self.debug_info.clone();
+
+        let (mesh, parts) =
+            instrument("Triangulate", &mut debug_info, |debug_info| {
+                let mut mesh = Mesh::new();
+
+                let parts = self
+                    .parts
+                    .iter()
+                    .map(|part| {
+                        let part_tolerance = part.tolerance(tolerance);
+                        let mut part_mesh = triangulate(
+                            &part.faces,
+                            part_tolerance,
+                            debug_info,
+                        );
+
+                        if part.solid {
+                            let (repaired, report) =
+                                repair(&part_mesh, part_tolerance.inner());
+
+                            if report.welded_vertices + report.t_junctions > 0 {
+                                debug!(
+                                    "Repaired mesh of part `{}`: welded {} \
+                                    vertices, fixed {} T-junctions",
+                                    part.name,
+                                    report.welded_vertices,
+                                    report.t_junctions,
+                                );
+                            }
+                            if !report.is_watertight() {
+                                warn!(
+                                    "Mesh of part `{}` is not watertight: {} \
+                                    open edges, {} non-manifold edges",
+                                    part.name,
+                                    report.open_edges.len(),
+                                    report.non_manifold_edges.len(),
+                                );
+                            }
+
+                            part_mesh = repaired;
+                        }
+
+                        mesh.extend(part_mesh.triangles());
+
+                        ProcessedPart {
+                            name: part.name.clone(),
+                            mesh: part_mesh,
+                            units: part.units,
+                        }
+                    })
+                    .collect();
+
+                (mesh, parts)
+            });
+
+        for failure in &debug_info.triangulation_failures {
+            warn!(
+                "Failed to triangulate face, using fallback: {}",
+                failure.error
+            );
+        }
+
+        for timing in &debug_info.operation_timings {
+            log_timing(timing, 0);
+        }
+
+        ProcessedShape {
+            aabb: self.aabb,
+            tolerance: tolerance.inner(),
+            mass_properties: mesh_properties(&mesh),
+            mesh,
+            parts,
+            debug_info,
+        }
+    }
+}
+
+/// The default tolerance for shapes that don't have a size
+///
+/// An empty shape, like a B-rep without faces, has a bounding box of size zero.
+const FALLBACK_TOLERANCE: f64 = 0.001;
+
+/// Compute a reasonable default tolerance for a shape with the given bounds
+///
+/// The tolerance scales with the diagonal of the bounding box, so small and
+/// large models are approximated with a comparable number of triangles.
+fn default_tolerance(aabb: &Aabb<3>) -> Tolerance {
+    let diagonal = aabb.size().magnitude();
+    let tolerance = if diagonal > Scalar::ZERO {
+        diagonal / Scalar::from_f64(1000.)
+    } else {
+        Scalar::from_f64(FALLBACK_TOLERANCE)
+    };
+
+    Tolerance::from_scalar(tolerance).expect("Tolerance is positive")
+}
+
+/// A part of an [`EvaluatedShape`]
+struct EvaluatedPart {
+    name: String,
+    faces: FaceSet,
+    units: Units,
+
+    /// The tolerance that the part overrides, if any
+    tolerance: Option<Tolerance>,
+
+    /// The maximum angle between segments that the part overrides, if any
+    max_angle: Option<Scalar>,
+
+    /// Whether the part is expected to enclose a volume
+    ///
+    /// Only the meshes of solid parts are repaired and checked for gaps.
+    solid: bool,
+}
+
+impl EvaluatedPart {
+    /// Apply the overrides of the part to the tolerance of the shape
+    fn tolerance(&self, tolerance: Tolerance) -> Tolerance {
+        let max_angle = self.max_angle.or(tolerance.max_angle());
+        let tolerance = self.tolerance.unwrap_or(tolerance);
+
+        match max_angle {
+            Some(max_angle) => tolerance
+                .with_max_angle(max_angle)
+                .expect("Maximum angle has been validated"),
+            None => tolerance,
+        }
+    }
+}
+
+/// Split a shape into the parts that make it up
+///
+/// Every shape within a group is considered a separate part, unless it is
+/// within a named part already. Named parts are returned as
+/// [`fj::Shape::Part`]. The instances of an assembly are named parts, moved to
+/// where its mates place them.
+fn parts(shape: &fj::Shape) -> Vec<Cow<'_, fj::Shape>> {
+    match shape {
+        fj::Shape::Group(group) => {
+            let mut a = parts(&group.a);
+            a.extend(parts(&group.b));
+            a
+        }
+        fj::Shape::Assembly(assembly) => assembly::place_instances(assembly)
+            .into_iter()
+            .map(|part| Cow::Owned(part.into()))
+            .collect(),
+        shape => vec![Cow::Borrowed(shape)],
+    }
+}
+
+/// Compute the maximum angle required by the segmentation hints within a shape
+///
+/// Returns `None`, if the shape has no circles that provide such a hint.
+fn segmentation_max_angle(shape: &fj::Shape) -> Option<Scalar> {
+    fn shape_2d(shape: &fj::Shape2d) -> Option<Scalar> {
+        match shape {
+            fj::Shape2d::Difference(difference) => {
+                let [a, b] = difference.shapes().each_ref_ext().map(shape_2d);
+                min(a, b)
+            }
+            fj::Shape2d::Sketch(sketch) => match sketch.chain() {
+                fj::Chain::Circle(circle) => match circle.segmentation() {
+                    fj::Segmentation::MaxAngle(angle) => {
+                        Some(Scalar::from_f64(angle.rad()))
+                    }
+                    fj::Segmentation::Tolerance | fj::Segmentation::Exact => {
+                        None
+                    }
+                },
+                fj::Chain::Ellipse(_)
+                | fj::Chain::PolyChain(_)
+                | fj::Chain::Rectangle(_)
+                | fj::Chain::RegularPolygon(_)
+                | fj::Chain::Slot(_) => None,
+            },
+            fj::Shape2d::Import(_) => None,
+        }
+    }
+
+    fn min(a: Option<Scalar>, b: Option<Scalar>) -> Option<Scalar> {
+        match (a, b) {
+            (Some(a), Some(b)) => Some(a.min(b)),
+            (a, b) => a.or(b),
+        }
+    }
+
+    match shape {
+        fj::Shape::Group(group) => min(
+            segmentation_max_angle(&group.a),
+            segmentation_max_angle(&group.b),
+        ),
+        fj::Shape::Shape2d(shape) => shape_2d(shape),
+        fj::Shape::Sweep(sweep) => shape_2d(sweep.shape()),
+        fj::Shape::HelicalSweep(sweep) => shape_2d(sweep.shape()),
+        fj::Shape::Holes(holes) => shape_2d(holes.sweep().shape()),
+        fj::Shape::Part(part) => segmentation_max_angle(part.shape()),
+        fj::Shape::Assembly(assembly) => assembly
+            .instances()
+            .iter()
+            .map(|part| segmentation_max_angle(part.shape()))
+            .fold(None, min),
+        fj::Shape::Transform(transform) => {
+            segmentation_max_angle(&transform.shape)
+        }
+        fj::Shape::Mirror(mirror) => segmentation_max_angle(mirror.shape()),
+        fj::Shape::Pattern(pattern) => segmentation_max_angle(pattern.shape()),
+        // The result of a boolean operation consists of flat triangles only.
+        fj::Shape::Brep(_) | fj::Shape::Import(_) | fj::Shape::Boolean(_) => {
+            None
+        }
+    }
+}
+
+fn log_timing(timing: &OperationTiming, depth: usize) {
+    debug!(
+        "{:indent$}{}: {:?} (self: {:?})",
+        "",
+        timing.name,
+        timing.duration,
+        timing.self_duration(),
+        indent = depth * 2,
+    );
+
+    for child in &timing.children {
+        log_timing(child, depth + 1);
+    }
+}
+
 /// A shape processing error
 #[allow(clippy::large_enum_variant)]
 #[derive(Debug, thiserror::Error)]
@@ -66,4 +547,122 @@ pub enum Error {
     /// Model has zero size
     #[error("Model has zero size")]
     Extent(#[from] InvalidTolerance),
+
+    /// Error saving the shape
+    #[error("Error saving the shape")]
+    Snapshot(#[from] SnapshotError),
+
+    /// I/O error saving the shape
+    #[error("I/O error saving the shape")]
+    Io(#[from] io::Error),
+
+    /// Error importing a file that the shape refers to
+    #[error("Error importing `{path}`")]
+    Import {
+        /// The path of the file, as the shape refers to it
+        path: String,
+
+        /// The error that occurred while importing the file
+        #[source]
+        source: fj_import::Error,
+    },
+}
+
+#[cfg(test)]
+mod tests {
+    use fj::syntax::*;
+    use fj_math::{Aabb, Scalar};
+
+    use super::{default_tolerance, Error, ShapeProcessor, FALLBACK_TOLERANCE};
+
+    #[test]
+    fn default_tolerance_scales_with_diagonal() {
+        let aabb = Aabb::<3>::from_points([[0., 0., 0.], [3., 4., 0.]]);
+        assert_eq!(default_tolerance(&aabb).inner(), Scalar::from(0.005));
+
+        let aabb = Aabb::<3>::from_points([[0., 0., 0.], [300., 400., 0.]]);
+        assert_eq!(default_tolerance(&aabb).inner(), Scalar::from(0.5));
+    }
+
+    #[test]
+    fn default_tolerance_of_empty_shape() {
+        let aabb = Aabb::<3>::default();
+        assert_eq!(
+            default_tolerance(&aabb).inner(),
+            Scalar::from(FALLBACK_TOLERANCE)
+        );
+
+        let processor = ShapeProcessor {
+            tolerance: None,
+            angular_tolerance: None,
+        };
+        let shape = fj::Brep::from_vertices(Vec::new()).into();
+        let processed = processor.process(&shape).unwrap();
+
+        assert_eq!(processed.tolerance, Scalar::from(FALLBACK_TOLERANCE));
+    }
+
+    #[test]
+    fn failed_imports_are_reported_as_errors() {
+        let processor = ShapeProcessor {
+            tolerance: None,
+            angular_tolerance: None,
+        };
+
+        let import: fj::Shape =
+            fj::Import::from_path("does-not-exist.step").into();
+        let sketch: fj::Shape =
+            fj::Import2d::from_path("does-not-exist.dxf").into();
+        let sweep: fj::Shape = fj::Sweep::from_path(
+            fj::Import2d::from_path("model.stl").into(),
+            [0., 0., 1.],
+        )
+        .into();
+
+        for (shape, path) in [
+            (import, "does-not-exist.step"),
+            (sketch, "does-not-exist.dxf"),
+            (sweep, "model.stl"),
+        ] {
+            match processor.evaluate(&shape) {
+                Err(Error::Import { path: failed, .. }) => {
+                    assert_eq!(failed, path);
+                }
+                Err(err) => panic!("Unexpected error: {err}"),
+                Ok(_) => panic!("Expected importing `{path}` to fail"),
+            }
+        }
+    }
+
+    #[test]
+    fn import_saved_shape() {
+        let path = std::env::temp_dir().join(format!(
+            "fj-shape-processor-test-{}.fjshape",
+            std::process::id()
+        ));
+
+        let processor = ShapeProcessor {
+            tolerance: None,
+            angular_tolerance: None,
+        };
+        let cuboid: fj::Shape = fj::Sketch::from_points(vec![
+            [0., 0.],
+            [2., 0.],
+            [2., 1.],
+            [0., 1.],
+        ])
+        .sweep([0., 0., 1.])
+        .into();
+
+        let evaluated = processor.evaluate(&cuboid).unwrap();
+        evaluated.save(&path).unwrap();
+
+        let import: fj::Shape = fj::Import::from_path(&path).into();
+        let imported = processor.evaluate(&import).unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        assert_eq!(imported.num_faces(), evaluated.num_faces());
+        assert_eq!(imported.num_half_edges(), evaluated.num_half_edges());
+        assert_eq!(imported.aabb, evaluated.aabb);
+    }
 }
@@ -1,16 +1,19 @@
 use std::ops::Deref;
 
-use fj_interop::{debug::DebugInfo, mesh::Color};
+use fj_interop::{
+    debug::DebugInfo,
+    mesh::{Attributes, Color},
+};
 use fj_kernel::{
-    builder::{FaceBuilder, HalfEdgeBuilder},
+    builder::{CycleBuilder, FaceBuilder},
     insert::Insert,
-    objects::{Cycle, Face, HalfEdge, Objects, Sketch},
-    partial::{HasPartial, Replace},
+    objects::{Cycle, Face, Objects, Sketch},
+    partial::HasPartial,
     services::Service,
 };
 use fj_math::{Aabb, Point};
 
-use super::Shape;
+use crate::{instrument::instrument, Shape};
 
 impl Shape for fj::Sketch {
     type Brep = Sketch;
@@ -18,48 +21,97 @@ impl Shape for fj::Sketch {
     fn compute_brep(
         &self,
         objects: &mut Service<Objects>,
-        _: &mut DebugInfo,
+        debug_info: &mut DebugInfo,
     ) -> Self::Brep {
-        let surface = objects.surfaces.xy_plane();
+        instrument("Sketch", debug_info, |_| {
+            let surface = objects.surfaces.xy_plane();
 
-        let face = match self.chain() {
-            fj::Chain::Circle(circle) => {
-                // Circles have just a single round edge with no vertices. So
-                // none need to be added here.
+            let face = match self.chain() {
+                fj::Chain::Circle(circle) => Face::partial()
+                    .with_exterior_circle(
+                        surface,
+                        [0., 0.],
+                        circle.radius(),
+                        objects,
+                    )
+                    .with_color(Color(self.color()))
+                    .with_attributes(attributes(self.attributes()))
+                    .build(objects)
+                    .insert(objects),
+                fj::Chain::Ellipse(ellipse) => Face::partial()
+                    .with_exterior(Cycle::partial().with_ellipse(
+                        surface,
+                        [0., 0.],
+                        [ellipse.radius_x(), ellipse.radius_y()],
+                        objects,
+                    ))
+                    .with_color(Color(self.color()))
+                    .with_attributes(attributes(self.attributes()))
+                    .build(objects)
+                    .insert(objects),
+                fj::Chain::PolyChain(poly_chain) => {
+                    let points = poly_chain
+                        .segments()
+                        .iter()
+                        .map(|&fj::SketchSegment::LineTo { point }| point)
+                        .map(Point::from);
 
-                let half_edge = {
-                    let mut half_edge = HalfEdge::partial();
-                    half_edge.replace(surface);
-                    half_edge
-                        .update_as_circle_from_radius(circle.radius(), objects)
+                    Face::partial()
+                        .with_exterior_polygon_from_points(surface, points)
+                        .with_color(Color(self.color()))
+                        .with_attributes(attributes(self.attributes()))
                         .build(objects)
                         .insert(objects)
-                };
-                let cycle = Cycle::new([half_edge]).insert(objects);
+                }
+                fj::Chain::Rectangle(rectangle) => {
+                    let center = [0., 0.];
+                    let size = [rectangle.width(), rectangle.height()];
+                    let radius = rectangle.corner_radius();
 
-                Face::partial()
-                    .with_exterior(cycle)
-                    .with_color(Color(self.color()))
-                    .build(objects)
-                    .insert(objects)
-            }
-            fj::Chain::PolyChain(poly_chain) => {
-                let points = poly_chain
-                    .to_segments()
-                    .into_iter()
-                    .map(|fj::SketchSegment::LineTo { point }| point)
-                    .map(Point::from);
+                    let face = if radius > 0. {
+                        Face::partial().with_exterior_rounded_rectangle(
+                            surface, center, size, radius,
+                        )
+                    } else {
+                        Face::partial()
+                            .with_exterior_rectangle(surface, center, size)
+                    };
 
-                Face::partial()
-                    .with_exterior_polygon_from_points(surface, points)
+                    face.with_color(Color(self.color()))
+                        .with_attributes(attributes(self.attributes()))
+                        .build(objects)
+                        .insert(objects)
+                }
+                fj::Chain::RegularPolygon(polygon) => Face::partial()
+                    .with_exterior_regular_polygon(
+                        surface,
+                        [0., 0.],
+                        polygon.radius(),
+                        polygon.num_sides() as usize,
+                    )
                     .with_color(Color(self.color()))
+                    .with_attributes(attributes(self.attributes()))
                     .build(objects)
-                    .insert(objects)
-            }
-        };
+                    .insert(objects),
+                fj::Chain::Slot(slot) => {
+                    let half_length = slot.length() / 2.;
 
-        let sketch = Sketch::builder().with_faces([face]).build(objects);
-        sketch.deref().clone()
+                    Face::partial()
+                        .with_exterior_slot(
+                            surface,
+                            [[-half_length, 0.], [half_length, 0.]],
+                            slot.radius(),
+                        )
+                        .with_color(Color(self.color()))
+                        .with_attributes(attributes(self.attributes()))
+                        .build(objects)
+                        .insert(objects)
+                }
+            };
+
+            let sketch = Sketch::builder().with_faces([face]).build(objects);
+            sketch.deref().clone()
+        })
     }
 
     fn bounding_volume(&self) -> Aabb<3> {
@@ -68,14 +120,50 @@ impl Shape for fj::Sketch {
                 min: Point::from([-circle.radius(), -circle.radius(), 0.0]),
                 max: Point::from([circle.radius(), circle.radius(), 0.0]),
             },
+            fj::Chain::Ellipse(ellipse) => Aabb {
+                min: Point::from([
+                    -ellipse.radius_x(),
+                    -ellipse.radius_y(),
+                    0.0,
+                ]),
+                max: Point::from([ellipse.radius_x(), ellipse.radius_y(), 0.0]),
+            },
             fj::Chain::PolyChain(poly_chain) => Aabb::<3>::from_points(
                 poly_chain
-                    .to_segments()
-                    .into_iter()
-                    .map(|fj::SketchSegment::LineTo { point }| point)
+                    .segments()
+                    .iter()
+                    .map(|&fj::SketchSegment::LineTo { point }| point)
                     .map(Point::from)
                     .map(Point::to_xyz),
             ),
+            fj::Chain::Rectangle(rectangle) => {
+                let [x, y] = [rectangle.width(), rectangle.height()]
+                    .map(|size| size / 2.);
+                Aabb {
+                    min: Point::from([-x, -y, 0.0]),
+                    max: Point::from([x, y, 0.0]),
+                }
+            }
+            fj::Chain::RegularPolygon(polygon) => Aabb {
+                min: Point::from([-polygon.radius(), -polygon.radius(), 0.0]),
+                max: Point::from([polygon.radius(), polygon.radius(), 0.0]),
+            },
+            fj::Chain::Slot(slot) => {
+                let x = slot.length() / 2. + slot.radius();
+                let y = slot.radius();
+                Aabb {
+                    min: Point::from([-x, -y, 0.0]),
+                    max: Point::from([x, y, 0.0]),
+                }
+            }
         }
     }
 }
+
+/// Convert the attributes of a sketch into those of its faces
+fn attributes(attributes: &fj::Attributes) -> Attributes {
+    Attributes {
+        name: attributes.name().map(Into::into),
+        material: attributes.material().map(Into::into),
+    }
+}
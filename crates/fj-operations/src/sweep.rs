@@ -1,15 +1,24 @@
-use std::ops::Deref;
+use std::{
+    f64::consts::{PI, TAU},
+    ops::Deref,
+};
 
 use fj_interop::debug::DebugInfo;
 use fj_kernel::{
-    algorithms::sweep::Sweep,
+    algorithms::{
+        approx::Tolerance,
+        draft::sweep_with_draft,
+        sweep::{FrameOrientation, Sweep, SweepAlongPath, SweepPath},
+        transform::TransformObject,
+    },
+    geometry::path::GlobalPath,
     insert::Insert,
     objects::{Objects, Solid},
     services::Service,
 };
-use fj_math::{Aabb, Vector};
+use fj_math::{Aabb, Helix, Point, Scalar, Vector};
 
-use super::Shape;
+use crate::{instrument::instrument, Shape};
 
 impl Shape for fj::Sweep {
     type Brep = Solid;
@@ -19,23 +28,196 @@ impl Shape for fj::Sweep {
         objects: &mut Service<Objects>,
         debug_info: &mut DebugInfo,
     ) -> Self::Brep {
-        let sketch = self.shape().compute_brep(objects, debug_info);
-        let sketch = sketch.insert(objects);
+        instrument("Sweep", debug_info, |debug_info| {
+            let sketch = self.shape().compute_brep(objects, debug_info);
+            let sketch = sketch.insert(objects);
+
+            let path = match self.path() {
+                fj::SweepPath::Straight { offset } => Vector::from(*offset),
+                fj::SweepPath::Polyline(polyline) => {
+                    let path = SweepPath::from_points(
+                        polyline.points().iter().copied(),
+                    );
+                    let orientation = match polyline.orientation() {
+                        fj::FrameOrientation::Frenet => {
+                            FrameOrientation::Frenet
+                        }
+                        fj::FrameOrientation::FixedUp { up } => {
+                            FrameOrientation::FixedUp(Vector::from(up))
+                        }
+                    };
+
+                    // Like for the helical sweep, curves need to be
+                    // approximated, and the tolerance of the shape processor
+                    // isn't available here.
+                    let tolerance =
+                        Tolerance::from_scalar(reach(self.shape()) / 100.)
+                            .expect("Can't sweep an empty shape along a path");
+
+                    let solid = sketch
+                        .transform(&path.start_transform(orientation), objects)
+                        .sweep_along_path(
+                            &path,
+                            orientation,
+                            tolerance,
+                            objects,
+                        )
+                        .expect("Failed to sweep shape along path");
+                    return solid.deref().clone();
+                }
+            };
+
+            let draft = signed_draft(self);
+            if draft == 0. {
+                let solid = sketch.sweep(path, objects);
+                return solid.deref().clone();
+            }
 
-        let path = Vector::from(self.path());
+            // Like for the helical sweep, curves need to be approximated, and
+            // the tolerance of the shape processor isn't available here.
+            let tolerance = Tolerance::from_scalar(reach(self.shape()) / 100.)
+                .expect("Can't sweep an empty shape with draft");
 
-        let solid = sketch.sweep(path, objects);
-        solid.deref().clone()
+            let mut shells = Vec::new();
+            for face in sketch.faces() {
+                let solid =
+                    sweep_with_draft(face, path, draft, tolerance, objects)
+                        .expect("Failed to sweep shape with draft");
+                shells.extend(solid.shells().cloned());
+            }
+
+            Solid::builder()
+                .with_shells(shells)
+                .build(objects)
+                .deref()
+                .clone()
+        })
     }
 
     fn bounding_volume(&self) -> Aabb<3> {
-        self.shape()
-            .bounding_volume()
-            .merged(&Aabb::<3>::from_points(
-                self.shape()
-                    .bounding_volume()
-                    .vertices()
-                    .map(|v| v + self.path()),
-            ))
+        let path = match self.path() {
+            fj::SweepPath::Straight { offset } => *offset,
+            fj::SweepPath::Polyline(polyline) => {
+                // The shape can end up in any orientation around the path.
+                // Anything within its reach needs to be covered.
+                let reach = reach(self.shape());
+                let path = Aabb::<3>::from_points(
+                    polyline.points().iter().copied().map(Point::from),
+                );
+                let reach = Vector::from([reach, reach, reach]);
+
+                return Aabb {
+                    min: path.min - reach,
+                    max: path.max + reach,
+                };
+            }
+        };
+
+        let start = self.shape().bounding_volume();
+        let mut end =
+            Aabb::<3>::from_points(start.vertices().map(|v| v + path));
+
+        // A negative draft angle grows the shape towards the end of the sweep.
+        let draft = signed_draft(self);
+        if draft < 0. {
+            let growth = Vector::from(path).magnitude() * -draft.tan();
+            let growth = Vector::from([growth, growth, Scalar::ZERO]);
+
+            end.min = end.min - growth;
+            end.max = end.max + growth;
+        }
+
+        start.merged(&end)
     }
 }
+
+/// Access the draft angle of the sweep as a signed angle in radians
+fn signed_draft(sweep: &fj::Sweep) -> f64 {
+    let draft = sweep.draft().rad();
+    if draft > PI {
+        draft - TAU
+    } else {
+        draft
+    }
+}
+
+impl Shape for fj::HelicalSweep {
+    type Brep = Solid;
+
+    fn compute_brep(
+        &self,
+        objects: &mut Service<Objects>,
+        debug_info: &mut DebugInfo,
+    ) -> Self::Brep {
+        instrument("HelicalSweep", debug_info, |debug_info| {
+            let sketch = self.shape().compute_brep(objects, debug_info);
+            let sketch = sketch.insert(objects);
+
+            let fj::Helix {
+                radius,
+                pitch,
+                turns,
+            } = self.helix();
+
+            // The shape is lofted along an approximation of the helix, and
+            // curves within the shape are approximated too. The tolerance of
+            // the shape processor isn't available here, so derive one from
+            // the size of both.
+            let size = match reach(self.shape()) {
+                reach if reach > Scalar::ZERO => {
+                    Ord::min(reach, Scalar::from(radius))
+                }
+                _ => Scalar::from(radius),
+            };
+            let tolerance = Tolerance::from_scalar(size / 100.)
+                .expect("Helix radius must be positive");
+            let path = SweepPath::from_curve(
+                GlobalPath::Helix(Helix::from_radius_and_pitch(radius, pitch)),
+                [[0.], [TAU * turns]],
+                tolerance,
+            );
+
+            let orientation = FrameOrientation::Frenet;
+
+            let solid = sketch
+                .transform(&path.start_transform(orientation), objects)
+                .sweep_along_path(&path, orientation, tolerance, objects)
+                .expect("Failed to sweep shape along helix");
+            solid.deref().clone()
+        })
+    }
+
+    fn bounding_volume(&self) -> Aabb<3> {
+        let fj::Helix {
+            radius,
+            pitch,
+            turns,
+        } = self.helix();
+
+        // The shape can end up in any orientation around the helix. Anything
+        // within its reach needs to be covered.
+        let reach = reach(self.shape());
+
+        let radius = Scalar::from(radius) + reach;
+        let rise = Scalar::from(pitch * turns);
+
+        let bottom = Ord::min(rise, Scalar::ZERO) - reach;
+        let top = Ord::max(rise, Scalar::ZERO) + reach;
+
+        Aabb {
+            min: Point::from([-radius, -radius, bottom]),
+            max: Point::from([radius, radius, top]),
+        }
+    }
+}
+
+/// Compute how far a shape reaches from its origin
+fn reach(shape: &fj::Shape2d) -> Scalar {
+    shape
+        .bounding_volume()
+        .vertices()
+        .into_iter()
+        .map(|vertex| vertex.coords.magnitude())
+        .max()
+        .unwrap_or(Scalar::ZERO)
+}
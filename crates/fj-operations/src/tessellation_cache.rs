@@ -0,0 +1,431 @@
+//! Reuse of face triangulations across shape processor runs
+//!
+//! See [`TessellationCache`].
+
+use std::collections::HashMap;
+
+use fj_interop::{
+    debug::DebugInfo,
+    mesh::{Attributes, Color, Mesh, ObjectId, Origin, Triangle},
+};
+use fj_kernel::{
+    algorithms::{
+        approx::{curve::CurveCache, Approx, Tolerance},
+        triangulate::triangulate_faces,
+    },
+    geometry::path::SurfacePath,
+    objects::{Face, FaceSet, Handedness},
+    storage::Handle,
+};
+use fj_math::{Point, Transform};
+
+/// Caches the triangulations of faces
+///
+/// Every run of the shape processor creates new objects, so faces can't be
+/// recognized by their handles. Instead, a face is identified by its geometry,
+/// relative to a coordinate frame that is derived from its surface. That way, a
+/// face that was only moved or rotated since the last run, or a face that
+/// appears multiple times within a shape, is recognized as the same face. Its
+/// cached triangulation just needs to be transformed into place.
+///
+/// Triangulations that aren't used during a run are dropped after it, so the
+/// cache doesn't grow while a model is edited.
+///
+/// The triangles of a cached triangulation refer to the objects of the face it
+/// was created from. Where it's reused, they're mapped to the corresponding
+/// objects of the face it's reused for.
+///
+/// Faces are triangulated one by one, instead of as part of the whole shape.
+/// The points on the boundary of adjacent faces might end up slightly
+/// different from each other, which is irrelevant for display, but might not
+/// be acceptable for other uses. Use [`EvaluatedShape::triangulate`] for those.
+///
+/// [`EvaluatedShape::triangulate`]: crate::shape_processor::EvaluatedShape::triangulate
+#[derive(Default)]
+pub struct TessellationCache {
+    previous: HashMap<FaceKey, CachedFace>,
+    current: HashMap<FaceKey, CachedFace>,
+}
+
+impl TessellationCache {
+    /// Create an empty `TessellationCache`
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Triangulate a set of faces, reusing cached triangulations where possible
+    ///
+    /// Faces that need to be triangulated are triangulated in parallel.
+    pub(crate) fn triangulate(
+        &mut self,
+        faces: &FaceSet,
+        tolerance: Tolerance,
+        debug_info: &mut DebugInfo,
+    ) -> Mesh<Point<3>> {
+        // The faces that need to be triangulated share the approximations of
+        // their common edges, as they would without the cache. That's why
+        // approximating them happens up front, one after the other.
+        let mut curve_cache = CurveCache::new();
+
+        let mut jobs = Vec::new();
+        let mut approximations = Vec::new();
+        let mut pending = HashMap::new();
+
+        for face in faces {
+            let Some((key, frame)) = FaceKey::new(face, tolerance) else {
+                jobs.push(Job::Uncached {
+                    approx: approximations.len(),
+                });
+                approximations
+                    .push(face.approx_with_cache(tolerance, &mut curve_cache));
+                continue;
+            };
+
+            let objects = face_objects(face);
+
+            if !self.current.contains_key(&key) && !pending.contains_key(&key) {
+                if let Some(cached) = self.previous.remove(&key) {
+                    self.current.insert(key.clone(), cached);
+                } else {
+                    pending.insert(
+                        key.clone(),
+                        (approximations.len(), frame, objects.clone()),
+                    );
+                    approximations.push(
+                        face.approx_with_cache(tolerance, &mut curve_cache),
+                    );
+                }
+            }
+
+            jobs.push(Job::Cached {
+                key,
+                frame,
+                objects,
+            });
+        }
+
+        let triangulations = triangulate_faces(approximations);
+
+        // The fallback triangulation is a last resort. Faces that required it
+        // are not cached, so they are triangulated again next time, and the
+        // failure gets reported again.
+        let mut failed = HashMap::new();
+        for (key, (approx, frame, objects)) in pending {
+            let (face_mesh, face_debug_info) = &triangulations[approx];
+
+            if face_debug_info.triangulation_failures.is_empty() {
+                let mut mesh = Mesh::new();
+                push_triangles(&mut mesh, face_mesh, &frame.inverse(), None);
+                self.current.insert(key, CachedFace { mesh, objects });
+            } else {
+                failed.insert(key, (approx, frame, objects));
+            }
+        }
+
+        let mut mesh = Mesh::new();
+        for job in jobs {
+            match job {
+                Job::Uncached { approx } => {
+                    let (face_mesh, _) = &triangulations[approx];
+                    push_triangles(
+                        &mut mesh,
+                        face_mesh,
+                        &Transform::identity(),
+                        None,
+                    );
+                }
+                Job::Cached {
+                    key,
+                    frame,
+                    objects,
+                } => {
+                    if let Some(cached) = self.current.get(&key) {
+                        let objects = map_objects(&cached.objects, &objects);
+                        push_triangles(
+                            &mut mesh,
+                            &cached.mesh,
+                            &frame,
+                            Some(&objects),
+                        );
+                    } else if let Some((approx, first_frame, first_objects)) =
+                        failed.get(&key)
+                    {
+                        // Same as the face that was triangulated, just in
+                        // another place.
+                        let (face_mesh, _) = &triangulations[*approx];
+                        let objects = map_objects(first_objects, &objects);
+                        push_triangles(
+                            &mut mesh,
+                            face_mesh,
+                            &(frame * first_frame.inverse()),
+                            Some(&objects),
+                        );
+                    }
+                }
+            }
+        }
+
+        for (_, face_debug_info) in triangulations {
+            debug_info
+                .triangulation_failures
+                .extend(face_debug_info.triangulation_failures);
+        }
+
+        mesh
+    }
+
+    /// Drop all triangulations that weren't used since the last call
+    pub(crate) fn finish_run(&mut self) {
+        self.previous = std::mem::take(&mut self.current);
+    }
+}
+
+/// How the triangulation of a face is determined
+#[allow(clippy::large_enum_variant)]
+enum Job {
+    /// The face can't be cached, and its approximation is triangulated
+    Uncached {
+        /// The index of the face's approximation
+        approx: usize,
+    },
+
+    /// The face's triangulation is taken from the cache
+    ///
+    /// If it wasn't cached yet, it's been added during this run.
+    Cached {
+        /// The key of the face
+        key: FaceKey,
+
+        /// The frame that positions the face
+        frame: Transform,
+
+        /// The objects of the face, as returned by [`face_objects`]
+        objects: Vec<ObjectId>,
+    },
+}
+
+/// A cached triangulation of a face
+struct CachedFace {
+    /// The triangles, relative to the frame of the face
+    mesh: Mesh<Point<3>>,
+
+    /// The objects of the face, as returned by [`face_objects`]
+    objects: Vec<ObjectId>,
+}
+
+/// Identifies a face by its geometry
+///
+/// Positions are expressed relative to the coordinate frame of the face's
+/// surface, and rounded to a fraction of the tolerance. Moving or rotating a
+/// face doesn't change its key.
+#[derive(Clone, Eq, PartialEq, Hash)]
+struct FaceKey {
+    tolerance: Tolerance,
+    color: Color,
+    attributes: Attributes,
+    coord_handedness: Handedness,
+    cycles: Vec<Vec<HalfEdgeKey>>,
+    positions: Vec<[i64; 3]>,
+}
+
+/// The curve of a half-edge, and the positions of its vertices on the curve
+/// and the surface
+type HalfEdgeKey = (SurfacePath, [Point<1>; 2], [Point<2>; 2]);
+
+impl FaceKey {
+    /// Compute the key of a face, and the frame that positions the face
+    ///
+    /// Returns `None`, if no frame can be derived from the face's surface.
+    fn new(face: &Face, tolerance: Tolerance) -> Option<(Self, Transform)> {
+        // Positions that are closer to each other than this are considered
+        // the same.
+        let resolution = tolerance.inner() / 1000.;
+
+        let surface = face.surface().geometry();
+
+        let [origin, u, v] = [[0., 0.], [1., 0.], [0., 1.]]
+            .map(|point| surface.point_from_surface_coords(point));
+
+        let x = u - origin;
+        let y = v - origin;
+        let y = y - x * (x.dot(&y) / x.dot(&x));
+        if x.magnitude() < tolerance.inner()
+            || y.magnitude() < tolerance.inner()
+        {
+            return None;
+        }
+        let [x, y] = [x, y].map(|axis| axis.normalize());
+        let frame = Transform::from_frame(origin, [x, y, x.cross(&y)]);
+
+        // The shape of the surface is described by a few points on it. The
+        // boundary of the face, by the positions of its vertices.
+        let surface_points = [0., 0.5, 1.].into_iter().flat_map(|u| {
+            [0., 0.5, 1.].map(|v| surface.point_from_surface_coords([u, v]))
+        });
+        let vertex_points = face
+            .all_cycles()
+            .flat_map(|cycle| cycle.half_edges())
+            .map(|half_edge| half_edge.back().global_form().position());
+        let positions =
+            surface_points
+                .chain(vertex_points)
+                .map(|point| {
+                    frame.inverse_transform_point(&point).coords.components.map(
+                        |coord| (coord / resolution).round().into_f64() as i64,
+                    )
+                })
+                .collect();
+
+        let cycles =
+            face.all_cycles()
+                .map(|cycle| {
+                    cycle
+                        .half_edges()
+                        .map(|half_edge| {
+                            (
+                                half_edge.curve().path(),
+                                half_edge
+                                    .vertices()
+                                    .each_ref()
+                                    .map(|vertex| vertex.position()),
+                                half_edge.vertices().each_ref().map(|vertex| {
+                                    vertex.surface_form().position()
+                                }),
+                            )
+                        })
+                        .collect()
+                })
+                .collect();
+
+        let key = Self {
+            tolerance,
+            color: face.color(),
+            attributes: face.attributes().clone(),
+            coord_handedness: face.coord_handedness(),
+            cycles,
+            positions,
+        };
+
+        Some((key, frame))
+    }
+}
+
+/// List the objects of a face that its triangles can refer to
+///
+/// Faces with the same key have the same structure, so their objects
+/// correspond to each other by their position in the list.
+fn face_objects(face: &Handle<Face>) -> Vec<ObjectId> {
+    let mut objects = vec![face.id().into()];
+
+    for half_edge in face.all_cycles().flat_map(|cycle| cycle.half_edges()) {
+        let [vertex, _] = half_edge.vertices();
+        objects.push(half_edge.global_form().id().into());
+        objects.push(vertex.global_form().id().into());
+    }
+
+    objects
+}
+
+/// Map the objects of one face to the corresponding objects of another
+fn map_objects(
+    from: &[ObjectId],
+    to: &[ObjectId],
+) -> HashMap<ObjectId, ObjectId> {
+    from.iter().copied().zip(to.iter().copied()).collect()
+}
+
+/// Add transformed triangles to a mesh
+///
+/// If `objects` is passed, the objects that the triangles refer to are mapped
+/// accordingly.
+fn push_triangles(
+    mesh: &mut Mesh<Point<3>>,
+    triangles: &Mesh<Point<3>>,
+    transform: &Transform,
+    objects: Option<&HashMap<ObjectId, ObjectId>>,
+) {
+    let map = |id: Option<ObjectId>| match objects {
+        Some(objects) => id.and_then(|id| objects.get(&id).copied()),
+        None => id,
+    };
+
+    // The frames of faces are rigid transforms, which leave the normals
+    // normalized.
+    mesh.extend(triangles.triangles().map(|triangle| Triangle {
+        inner: transform.transform_triangle(&triangle.inner),
+        normals: triangle.normals.map(|normals| {
+            normals.map(|normal| transform.transform_vector(&normal))
+        }),
+        color: triangle.color,
+        attributes: triangle.attributes,
+        origin: Origin {
+            face: map(triangle.origin.face),
+            edges: triangle.origin.edges.map(map),
+            vertices: triangle.origin.vertices.map(map),
+        },
+    }));
+}
+
+#[cfg(test)]
+mod tests {
+    use fj::syntax::*;
+    use fj_interop::debug::DebugInfo;
+    use fj_kernel::algorithms::{approx::Tolerance, triangulate::Triangulate};
+
+    use crate::shape_processor::ShapeProcessor;
+
+    use super::TessellationCache;
+
+    #[test]
+    fn reuse_triangulation_of_moved_shape() {
+        let processor = ShapeProcessor {
+            tolerance: Some(Tolerance::from_scalar(0.01).unwrap()),
+            angular_tolerance: None,
+        };
+        let tolerance = processor.tolerance.unwrap();
+
+        let cylinder: fj::Shape =
+            fj::Sketch::from_circle(fj::Circle::from_radius(1.))
+                .sweep([0., 0., 2.])
+                .into();
+        let moved = fj::Shape::from(fj::Transform {
+            shape: cylinder.clone(),
+            axis: [1., 1., 0.],
+            angle: fj::Angle::from_deg(30.),
+            offset: [3., -2., 1.],
+        });
+
+        let mut cache = TessellationCache::new();
+
+        for shape in [cylinder, moved] {
+            let evaluated = processor.evaluate(&shape).unwrap();
+            let (_, faces, _) = evaluated.parts().next().unwrap();
+
+            let mesh =
+                cache.triangulate(faces, tolerance, &mut DebugInfo::new());
+
+            // All triangulations of the moved shape are reused. They refer to
+            // the objects of the moved shape, not the ones they were cached
+            // for.
+            assert!(cache.previous.is_empty());
+            assert_eq!(cache.current.len(), faces.into_iter().count());
+            cache.finish_run();
+
+            let expected = (faces, tolerance).triangulate();
+            assert_eq!(mesh.triangles().count(), expected.triangles().count());
+            for triangle in mesh.triangles() {
+                let is_expected = expected.triangles().any(|expected| {
+                    triangle.color == expected.color
+                        && triangle.origin == expected.origin
+                        && triangle
+                            .inner
+                            .points()
+                            .into_iter()
+                            .zip(expected.inner.points())
+                            .all(|(a, b)| a.distance_to(&b) < 1e-9.into())
+                });
+                assert!(is_expected);
+            }
+        }
+    }
+}
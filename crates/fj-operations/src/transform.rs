@@ -1,12 +1,12 @@
 use fj_interop::debug::DebugInfo;
 use fj_kernel::{
-    algorithms::transform::TransformObject,
-    objects::{FaceSet, Objects},
+    algorithms::{mirror::mirror_and_union, transform::TransformObject},
+    objects::{FaceSet, Objects, Shell, Solid},
     services::Service,
 };
 use fj_math::{Aabb, Transform, Vector};
 
-use super::Shape;
+use crate::{instrument::instrument, Shape};
 
 impl Shape for fj::Transform {
     type Brep = FaceSet;
@@ -16,9 +16,11 @@ impl Shape for fj::Transform {
         objects: &mut Service<Objects>,
         debug_info: &mut DebugInfo,
     ) -> Self::Brep {
-        self.shape
-            .compute_brep(objects, debug_info)
-            .transform(&make_transform(self), objects)
+        instrument("Transform", debug_info, |debug_info| {
+            self.shape
+                .compute_brep(objects, debug_info)
+                .transform(&make_transform(self), objects)
+        })
     }
 
     fn bounding_volume(&self) -> Aabb<3> {
@@ -31,3 +33,53 @@ fn make_transform(transform: &fj::Transform) -> Transform {
     Transform::translation(transform.offset)
         * Transform::rotation(axis * transform.angle.rad())
 }
+
+impl Shape for fj::Mirror {
+    type Brep = FaceSet;
+
+    fn compute_brep(
+        &self,
+        objects: &mut Service<Objects>,
+        debug_info: &mut DebugInfo,
+    ) -> Self::Brep {
+        instrument("Mirror", debug_info, |debug_info| {
+            let faces = self.shape().compute_brep(objects, debug_info);
+
+            if !self.is_with_original() {
+                return faces.transform(&make_reflection(self), objects);
+            }
+
+            let shell = Shell::builder().with_faces(faces).build(objects);
+            let solid = Solid::builder().with_shells([shell]).build(objects);
+
+            let solid =
+                mirror_and_union(&solid, self.origin(), self.normal(), objects)
+                    .expect("Failed to join shape with its mirror image");
+
+            let mut faces = FaceSet::new();
+            for shell in solid.shells() {
+                faces.extend(shell.faces().clone());
+            }
+            faces
+        })
+    }
+
+    fn bounding_volume(&self) -> Aabb<3> {
+        let aabb = self.shape().bounding_volume();
+
+        let mirrored = Aabb::<3>::from_points(
+            aabb.vertices()
+                .map(|vertex| make_reflection(self).transform_point(&vertex)),
+        );
+
+        if self.is_with_original() {
+            aabb.merged(&mirrored)
+        } else {
+            mirrored
+        }
+    }
+}
+
+fn make_reflection(mirror: &fj::Mirror) -> Transform {
+    Transform::reflection(mirror.origin(), mirror.normal())
+}
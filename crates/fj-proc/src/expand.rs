@@ -128,8 +128,16 @@ impl ToTokens for ExtractedArgument {
             ident,
             ty,
             default_value,
+            seeded,
         } = self;
 
+        if *seeded {
+            tokens.extend(quote! {
+                let #ident: #ty = fj::Rng::from_seed(ctx.seed());
+            });
+            return;
+        }
+
         let name = ident.to_string();
         let t = match default_value {
             Some(default) => quote! {
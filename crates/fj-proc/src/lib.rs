@@ -72,6 +72,18 @@ use syn::{parse_macro_input, FnArg, ItemFn};
 /// }
 /// ```
 ///
+/// Models that are generated randomly can take an `fj::Rng` argument. It is
+/// seeded by the host, so the model produces the same shape every time, unless
+/// the user chooses a different seed.
+///
+/// ```rust
+/// #[fj::model]
+/// pub fn model(mut rng: fj::Rng) -> fj::Shape {
+///     let radius = rng.range(1. ..2.);
+///     fj::Sketch::from_circle(fj::Circle::from_radius(radius)).into()
+/// }
+/// ```
+///
 /// For more complex situations, model functions are allowed to return any
 /// error type that converts into a model error.
 ///
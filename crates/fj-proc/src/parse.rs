@@ -45,6 +45,11 @@ pub(crate) struct ExtractedArgument {
     pub(crate) ident: Ident,
     pub(crate) ty: Type,
     pub(crate) default_value: Option<Expr>,
+
+    /// Whether the argument is a random number generator
+    ///
+    /// Those are seeded by the host, instead of being passed as arguments.
+    pub(crate) seeded: bool,
 }
 
 #[derive(Debug)]
@@ -80,6 +85,7 @@ fn parse_model(item: &ItemFn) -> syn::Result<Model> {
         name: geometry_function.to_string(),
         arguments: args
             .iter()
+            .filter(|a| !a.is_rng())
             .map(|a| ArgumentMetadata {
                 name: a.ident.to_string(),
                 default_value: a.default(),
@@ -95,6 +101,7 @@ fn parse_model(item: &ItemFn) -> syn::Result<Model> {
                 ident: a.ident.clone(),
                 default_value: a.default(),
                 ty: a.ty.clone(),
+                seeded: a.is_rng(),
             })
             .collect(),
         constraints: args.iter().flat_map(argument_constraints).collect(),
@@ -149,6 +156,9 @@ fn argument_constraints(arg: &Argument) -> Vec<Constraint> {
 ///            |                    |
 ///          attr                 ident
 /// ```
+///
+/// Whether the parameter is mutable only matters to the model function, so
+/// it's not recorded.
 #[derive(Debug, Clone)]
 struct Argument {
     attr: Option<HelperAttribute>,
@@ -163,6 +173,13 @@ impl Argument {
             .and_then(|attr| attr.get_default())
             .map(|param| param.val)
     }
+
+    fn is_rng(&self) -> bool {
+        match &self.ty {
+            Type::Path(p) => p.path.segments.last().unwrap().ident == "Rng",
+            _ => false,
+        }
+    }
 }
 
 impl Parse for Argument {
@@ -171,6 +188,7 @@ impl Parse for Argument {
         if input.peek(syn::token::Pound) {
             attr = Some(input.parse()?);
         }
+        let _: Option<syn::token::Mut> = input.parse()?;
         let ident: Ident = input.parse()?;
 
         let _: syn::token::Colon = input.parse()?;
@@ -386,4 +404,37 @@ mod tests {
 
         assert!(init.model.geometry.fallible);
     }
+
+    #[test]
+    fn parse_function_with_rng() {
+        let tokens = quote! {
+            pub fn scattered(count: u32, mut rng: fj::Rng) -> fj::Shape {
+                todo!()
+            }
+        };
+        let function: ItemFn = syn::parse2(tokens).unwrap();
+
+        let Initializer {
+            model: Model { metadata, geometry },
+        } = parse(&function).unwrap();
+
+        // The generator is seeded by the host. It's not an argument that can
+        // be passed to the model.
+        let names: Vec<_> = metadata
+            .arguments
+            .iter()
+            .map(|arg| arg.name.clone())
+            .collect();
+        assert_eq!(names, ["count"]);
+
+        let seeded: Vec<_> = geometry
+            .arguments
+            .iter()
+            .map(|arg| (arg.ident.to_string(), arg.seeded))
+            .collect();
+        assert_eq!(
+            seeded,
+            [("count".to_string(), false), ("rng".to_string(), true)]
+        );
+    }
 }
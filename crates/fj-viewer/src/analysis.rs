@@ -0,0 +1,113 @@
+//! Visualization of analysis results
+
+use fj_interop::{
+    analysis::TriangleValues,
+    mesh::{Mesh, Triangle},
+    processed_shape::ProcessedShape,
+};
+use fj_math::{Scalar, Vector};
+
+/// An analysis, whose results are shown by coloring the model
+///
+/// The viewer doesn't run the analysis itself. It just lets the user pick one,
+/// via [`GuiOutput::analysis`], and shows the results that are passed to
+/// [`Viewer::handle_analysis_update`].
+///
+/// [`GuiOutput::analysis`]: crate::GuiOutput::analysis
+/// [`Viewer::handle_analysis_update`]: crate::Viewer::handle_analysis_update
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum Analysis {
+    /// Don't analyze the model
+    #[default]
+    None,
+
+    /// Show the draft angles relative to a pull direction
+    Draft {
+        /// The direction in which the model is pulled out of the mold
+        pull_direction: Vector<3>,
+    },
+
+    /// Show the wall thickness
+    WallThickness,
+
+    /// Show which triangles are problematic for 3D printing
+    Printability {
+        /// The direction in which the layers are stacked
+        build_direction: Vector<3>,
+
+        /// The steepest overhang that prints without support, in radians
+        ///
+        /// The angle is measured from a vertical wall.
+        max_overhang: Scalar,
+
+        /// The thinnest wall that can be printed
+        min_thickness: Scalar,
+    },
+
+    /// Show the curvature of the surfaces
+    Curvature {
+        /// Which curvature to show
+        kind: CurvatureKind,
+    },
+}
+
+/// The kind of curvature shown by [`Analysis::Curvature`]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum CurvatureKind {
+    /// The Gaussian curvature
+    ///
+    /// Highlights doubly curved surfaces, like spheres and saddles.
+    Gaussian,
+
+    /// The mean curvature
+    ///
+    /// Distinguishes convex from concave surfaces, which makes it well suited
+    /// for spotting creases and uneven blends.
+    Mean,
+}
+
+/// Color the triangles of a shape according to the results of an analysis
+///
+/// The values are expected to be in the order of the triangles in the shape's
+/// mesh, which is the order of its parts and their triangles.
+pub(crate) fn color_shape(
+    shape: &ProcessedShape,
+    values: &TriangleValues,
+) -> ProcessedShape {
+    let colors = values.colors().collect::<Vec<_>>();
+
+    let mut mesh = Mesh::new();
+    mesh.extend(
+        shape
+            .mesh
+            .triangles()
+            .zip(&colors)
+            .map(|(triangle, &color)| Triangle { color, ..triangle }),
+    );
+
+    let mut colors = colors.into_iter();
+    let parts = shape
+        .parts
+        .iter()
+        .map(|part| {
+            let mut part = part.clone();
+
+            let mut mesh = Mesh::new();
+            mesh.extend(
+                part.mesh
+                    .triangles()
+                    .zip(&mut colors)
+                    .map(|(triangle, color)| Triangle { color, ..triangle }),
+            );
+            part.mesh = mesh;
+
+            part
+        })
+        .collect();
+
+    ProcessedShape {
+        mesh,
+        parts,
+        ..shape.clone()
+    }
+}
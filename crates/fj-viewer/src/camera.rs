@@ -1,10 +1,10 @@
 //! Viewer camera module
-use std::f64::consts::FRAC_PI_2;
+use std::f64::consts::{FRAC_PI_2, FRAC_PI_4};
 
-use fj_interop::{mesh::Mesh, processed_shape::ProcessedShape};
+use fj_interop::mesh::Mesh;
 use fj_math::{Aabb, Point, Scalar, Transform, Vector};
 
-use crate::screen::NormalizedScreenPosition;
+use crate::{picking::cast_ray, screen::NormalizedScreenPosition};
 
 /// The camera abstraction
 ///
@@ -20,6 +20,25 @@ pub struct Camera {
     /// The distance to the far plane
     far_plane: f64,
 
+    /// The distance from the camera to the center of the model
+    ///
+    /// The orthographic projection shows the same area that the perspective
+    /// one shows at this distance. Moving the camera towards the model changes
+    /// it, so zooming works the same way for both projections.
+    focal_distance: f64,
+
+    /// How the model is projected onto the screen
+    pub projection: Projection,
+
+    /// How mouse and keyboard input move the camera
+    pub navigation: Navigation,
+
+    /// The speed of the camera in fly mode
+    ///
+    /// Given as the fraction of the model's size, that the camera moves per
+    /// second.
+    pub fly_speed: f64,
+
     /// The rotational part of the transform
     pub rotation: Transform,
 
@@ -33,11 +52,17 @@ impl Camera {
 
     const INITIAL_FIELD_OF_VIEW_IN_X: f64 = FRAC_PI_2; // 90 degrees
 
+    const DEFAULT_FLY_SPEED: f64 = 0.25;
+
     /// Returns a new camera aligned for viewing a bounding box
     pub fn new() -> Self {
         Self {
             near_plane: Self::DEFAULT_NEAR_PLANE,
             far_plane: Self::DEFAULT_FAR_PLANE,
+            focal_distance: 1.,
+            projection: Projection::default(),
+            navigation: Navigation::default(),
+            fly_speed: Self::DEFAULT_FLY_SPEED,
 
             rotation: Transform::identity(),
             translation: Transform::identity(),
@@ -59,33 +84,118 @@ impl Camera {
         Self::INITIAL_FIELD_OF_VIEW_IN_X
     }
 
+    /// Returns the distance from the camera to the center of the model
+    ///
+    /// This sets the scale of the orthographic projection.
+    pub fn focal_distance(&self) -> f64 {
+        self.focal_distance
+    }
+
+    /// Returns the width of the area that the orthographic projection shows
+    pub fn orthographic_width(&self) -> f64 {
+        self.half_width_at(self.focal_distance) * 2.
+    }
+
+    /// Switch between the perspective and orthographic projections
+    pub fn toggle_projection(&mut self) {
+        self.projection = match self.projection {
+            Projection::Perspective => Projection::Orthographic,
+            Projection::Orthographic => Projection::Perspective,
+        };
+    }
+
+    /// Switch between orbiting around the model and flying through it
+    pub fn toggle_navigation(&mut self) {
+        self.navigation = match self.navigation {
+            Navigation::Orbit => Navigation::Fly,
+            Navigation::Fly => Navigation::Orbit,
+        };
+    }
+
     /// Returns the position of the camera in world space.
     pub fn position(&self) -> Point<3> {
         self.camera_to_model()
             .inverse_transform_point(&Point::<3>::origin())
     }
 
+    /// Compute the width of the visible area, at the depth of a point
+    ///
+    /// Returns `None`, if the point is not in front of the camera.
+    pub fn visible_width_at(&self, point: &Point<3>) -> Option<Scalar> {
+        // The camera looks along the negative z-axis of camera space.
+        let depth = -self.camera_to_model().transform_point(point).z;
+        if depth <= Scalar::ZERO {
+            return None;
+        }
+
+        Some(Scalar::from(self.half_width_at(depth.into_f64()) * 2.))
+    }
+
     /// Transform a normalized cursor position on the near plane to model space.
     pub fn cursor_to_model_space(
         &self,
         cursor: NormalizedScreenPosition,
     ) -> Point<3> {
         // Cursor position in camera space.
-        let f = (self.field_of_view_in_x() / 2.).tan() * self.near_plane();
+        let f = self.half_width_at(self.near_plane());
         let cursor = Point::origin()
             + Vector::from([cursor.x * f, cursor.y * f, -self.near_plane()]);
 
         self.camera_to_model().inverse_transform_point(&cursor)
     }
 
+    /// Compute the ray in model space, that passes through the cursor
+    ///
+    /// Returns the origin of the ray, on the near plane, and its normalized
+    /// direction. With an orthographic projection, all rays are parallel.
+    pub fn cursor_ray(
+        &self,
+        cursor: NormalizedScreenPosition,
+    ) -> (Point<3>, Vector<3>) {
+        let origin = self.cursor_to_model_space(cursor);
+
+        let direction = match self.projection {
+            Projection::Perspective => origin - self.position(),
+            Projection::Orthographic => self
+                .camera_to_model()
+                .inverse()
+                .transform_vector(&Vector::from([0., 0., -1.])),
+        };
+
+        (origin, direction.normalize())
+    }
+
+    /// Project a point in model space onto the screen
+    ///
+    /// This is the inverse of [`Camera::cursor_to_model_space`]. Returns
+    /// `None`, if the point is not in front of the near plane.
+    pub fn project(
+        &self,
+        point: &Point<3>,
+    ) -> Option<NormalizedScreenPosition> {
+        let point = self.camera_to_model().transform_point(point);
+
+        let depth = -point.z.into_f64();
+        if depth <= self.near_plane() {
+            return None;
+        }
+
+        let f = self.half_width_at(depth);
+        Some(NormalizedScreenPosition {
+            x: point.x.into_f64() / f,
+            y: point.y.into_f64() / f,
+        })
+    }
+
     /// Compute the point on the model, that the cursor currently points to.
     pub fn focus_point(
         &self,
         cursor: Option<NormalizedScreenPosition>,
-        shape: &ProcessedShape,
+        mesh: &Mesh<Point<3>>,
+        aabb: &Aabb<3>,
     ) -> FocusPoint {
-        self.calculate_focus_point(cursor, &shape.mesh)
-            .unwrap_or_else(|| FocusPoint(shape.aabb.center()))
+        self.calculate_focus_point(cursor, mesh)
+            .unwrap_or_else(|| FocusPoint(aabb.center()))
     }
 
     fn calculate_focus_point(
@@ -93,27 +203,20 @@ impl Camera {
         cursor: Option<NormalizedScreenPosition>,
         mesh: &Mesh<Point<3>>,
     ) -> Option<FocusPoint> {
-        // Transform camera and cursor positions to model space.
-        let origin = self.position();
-        let cursor = self.cursor_to_model_space(cursor?);
-        let dir = (cursor - origin).normalize();
-
-        let mut min_t = None;
-
-        for triangle in mesh.triangles() {
-            let t =
-                triangle
-                    .inner
-                    .cast_local_ray(origin, dir, f64::INFINITY, true);
-
-            if let Some(t) = t {
-                if t <= min_t.unwrap_or(t) {
-                    min_t = Some(t);
-                }
-            }
-        }
+        let (origin, dir) = self.cursor_ray(cursor?);
+        let t = cast_ray(mesh, origin, dir)?;
+
+        Some(FocusPoint(origin + dir * t))
+    }
+
+    /// Compute half the visible width, at the given depth
+    fn half_width_at(&self, depth: f64) -> f64 {
+        let depth = match self.projection {
+            Projection::Perspective => depth,
+            Projection::Orthographic => self.focal_distance,
+        };
 
-        Some(FocusPoint(origin + dir * min_t?))
+        (self.field_of_view_in_x() / 2.).tan() * depth
     }
 
     /// Access the transform from camera to model space.
@@ -176,11 +279,70 @@ impl Camera {
         self.translation = translation;
     }
 
+    /// Look at a bounding box from one of the standard directions
+    ///
+    /// The camera is moved, so the bounding box fills most of a screen with the
+    /// given aspect ratio (width divided by height).
+    pub fn look_at(
+        &mut self,
+        view: CameraView,
+        aabb: &Aabb<3>,
+        aspect_ratio: f64,
+    ) {
+        self.rotation = view.rotation();
+
+        // Whatever the orientation of the bounding box, it fits into its
+        // bounding sphere. The narrower of the two fields of view determines
+        // how far the camera needs to be from it.
+        let radius = (aabb.size().magnitude() / 2.).into_f64();
+        let tan_x = (Self::INITIAL_FIELD_OF_VIEW_IN_X / 2.).tan();
+        let tan_y = tan_x / aspect_ratio;
+        let half_field_of_view = tan_x.min(tan_y).atan();
+        let distance = if radius > 0. {
+            radius / half_field_of_view.sin() * 1.1
+        } else {
+            1.
+        };
+
+        let center = self.rotation.transform_point(&aabb.center());
+        self.translation = Transform::translation([
+            -center.x,
+            -center.y,
+            -center.z - Scalar::from(distance),
+        ]);
+
+        self.update_planes(aabb);
+    }
+
     /// Update the max and minimum rendering distance for this camera.
     pub fn update_planes(&mut self, aabb: &Aabb<3>) {
         let view_transform = self.camera_to_model();
         let view_direction = Vector::from([0., 0., -1.]);
 
+        let depth = |point: &Point<3>| {
+            -view_transform.transform_point(point).z.into_f64()
+        };
+        self.focal_distance =
+            depth(&aabb.center()).max(Self::DEFAULT_NEAR_PLANE);
+
+        if self.projection == Projection::Orthographic {
+            // An orthographic projection doesn't distort the model, no matter
+            // how close it is. The planes only need to enclose it, even where
+            // it is behind the camera.
+            let depths = aabb.vertices().map(|vertex| depth(&vertex));
+            let [min, max] = [f64::min, f64::max]
+                .map(|f| depths.into_iter().reduce(f).unwrap_or_default());
+            let margin = (max - min) * 0.1 + Self::DEFAULT_NEAR_PLANE;
+            let [min, max] = [min - margin, max + margin];
+
+            // The projection maps the depth between the planes to -1..1, but
+            // only 0..1 ends up on the screen. The near plane is moved out of
+            // the way, so the model lies within the second half.
+            self.near_plane = min - (max - min);
+            self.far_plane = max;
+            return;
+        }
+
         let mut dist_min = f64::INFINITY;
         let mut dist_max = f64::NEG_INFINITY;
 
@@ -205,7 +367,11 @@ impl Camera {
             }
         }
 
-        self.near_plane = if dist_min > 0. {
+        self.near_plane = if self.navigation == Navigation::Fly {
+            // Inside of the model, there's geometry right in front of the
+            // camera, no matter how far away the rest of it is.
+            (dist_max * 0.001).max(Self::DEFAULT_NEAR_PLANE)
+        } else if dist_min > 0. {
             // Setting `self.near_plane` to `dist_min` should theoretically
             // work, but results in the front of the model being clipped. I
             // wasn't able to figure out why, and for the time being, this
@@ -228,6 +394,78 @@ impl Default for Camera {
     }
 }
 
+/// How the camera projects the model onto the screen
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum Projection {
+    /// Parts of the model that are further away appear smaller
+    #[default]
+    Perspective,
+
+    /// The model appears at the same scale, no matter how far away it is
+    ///
+    /// Parallel lines stay parallel, which makes it easier to check the
+    /// alignment of features.
+    Orthographic,
+}
+
+/// A standard direction to look at the model from
+///
+/// See [`Camera::look_at`].
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum CameraView {
+    /// Look at the model diagonally, from the front, right, and above
+    #[default]
+    Iso,
+
+    /// Look at the model from the front, along the y-axis
+    Front,
+
+    /// Look down on the model, against the z-axis
+    Top,
+
+    /// Look at the model from the right side, against the x-axis
+    Side,
+}
+
+impl CameraView {
+    /// The rotation of the camera, for looking from this direction
+    fn rotation(&self) -> Transform {
+        // The camera looks against the z-axis of camera space, with the y-axis
+        // pointing up. Tilting it by a quarter turn around the x-axis makes it
+        // look along the y-axis, with the z-axis pointing up.
+        let tilt = |angle: f64| Transform::rotation(Vector::unit_x() * angle);
+        let turn = |angle: f64| Transform::rotation(Vector::unit_z() * angle);
+
+        match self {
+            Self::Iso => {
+                // Tilted down just enough to look along the diagonal of a cube
+                let elevation = (1. / 2f64.sqrt()).atan();
+                tilt(-(FRAC_PI_2 - elevation)) * turn(-FRAC_PI_4)
+            }
+            Self::Front => tilt(-FRAC_PI_2),
+            Self::Top => Transform::identity(),
+            Self::Side => tilt(-FRAC_PI_2) * turn(-FRAC_PI_2),
+        }
+    }
+}
+
+/// How mouse and keyboard input move the camera
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum Navigation {
+    /// The camera orbits around the model
+    ///
+    /// Dragging the mouse rotates the model around the point under the cursor.
+    #[default]
+    Orbit,
+
+    /// The camera flies through the model, like in a first-person game
+    ///
+    /// Dragging the mouse turns the camera in place, while the keyboard moves
+    /// it. The camera passes right through the model, which makes it possible
+    /// to look at enclosures and buildings from the inside.
+    Fly,
+}
+
 /// The point around which camera movement happens.
 ///
 /// This will be the point on the model that the cursor is currently pointing at if such a point exists,
@@ -0,0 +1,108 @@
+//! Extension point for embedders of the viewer
+//!
+//! See [`Extension`].
+
+use fj_interop::processed_shape::ProcessedShape;
+
+use crate::{camera::Camera, screen::ScreenSize};
+
+/// An extension of the viewer
+///
+/// Applications that embed the viewer can implement this trait, to add their
+/// own panels to the GUI and their own graphics to the scene, without having to
+/// fork the viewer. Register extensions using [`Viewer::add_extension`].
+///
+/// All methods do nothing by default, so only the ones that are needed have to
+/// be implemented.
+///
+/// [`Viewer::add_extension`]: crate::Viewer::add_extension
+pub trait Extension {
+    /// Show custom GUI elements
+    ///
+    /// Called once per frame, after the built-in panels have been added to
+    /// `context`. Side and top/bottom panels, windows and areas can all be
+    /// used, but not a central panel.
+    fn show_gui(
+        &mut self,
+        context: &egui::Context,
+        shape: Option<&ProcessedShape>,
+    ) {
+        let _ = (context, shape);
+    }
+
+    /// Draw custom graphics
+    ///
+    /// Called once per frame, after the model has been drawn and before the
+    /// GUI is drawn on top. See [`RenderContext`] for what's available.
+    fn render(&mut self, context: &mut RenderContext) {
+        let _ = context;
+    }
+
+    /// Handle the graphics device having been replaced
+    ///
+    /// Happens, if the previous device has been lost. Resources that were
+    /// created with it, like buffers and pipelines, can't be used anymore and
+    /// need to be created again with [`RenderContext::device`].
+    fn handle_device_change(&mut self) {}
+}
+
+/// Everything an [`Extension`] needs to draw into a frame
+pub struct RenderContext<'r> {
+    /// The graphics device
+    pub device: &'r wgpu::Device,
+
+    /// The queue of the graphics device
+    pub queue: &'r wgpu::Queue,
+
+    /// The encoder of the frame
+    ///
+    /// Commands recorded here are submitted after the GUI has been drawn.
+    pub encoder: &'r mut wgpu::CommandEncoder,
+
+    /// The multisampled frame buffer, which the model has been drawn into
+    ///
+    /// Drawing into this, with [`RenderContext::resolve_target`] as the
+    /// resolve target and [`RenderContext::depth_view`] as the depth
+    /// attachment, allows custom graphics to be hidden behind the model. Load
+    /// both, don't clear them.
+    ///
+    /// Without multisampling, this is the same as [`RenderContext::color_view`].
+    pub frame_buffer: &'r wgpu::TextureView,
+
+    /// The resolve target for drawing into [`RenderContext::frame_buffer`]
+    ///
+    /// This is [`RenderContext::color_view`], or `None` without multisampling.
+    pub resolve_target: Option<&'r wgpu::TextureView>,
+
+    /// The texture that ends up on the screen
+    ///
+    /// This is not multisampled. Graphics that are always drawn on top of the
+    /// model can go here directly.
+    pub color_view: &'r wgpu::TextureView,
+
+    /// The depth buffer of the frame buffer
+    pub depth_view: &'r wgpu::TextureView,
+
+    /// The format of the frame buffer and the texture on the screen
+    pub color_format: wgpu::TextureFormat,
+
+    /// The format of the depth buffer
+    pub depth_format: wgpu::TextureFormat,
+
+    /// The number of samples of the frame buffer and the depth buffer
+    pub sample_count: u32,
+
+    /// The size of the frame, in pixels
+    pub size: ScreenSize,
+
+    /// The transform from model space to clip space
+    ///
+    /// A column-major matrix, that is the same one used to draw the model.
+    pub transform: [f32; 16],
+
+    /// The camera
+    pub camera: &'r Camera,
+
+    /// The shape that is being shown, if any
+    pub shape: Option<&'r ProcessedShape>,
+}
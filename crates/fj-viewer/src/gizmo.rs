@@ -0,0 +1,412 @@
+//! A gizmo for translating and rotating the selected part
+//!
+//! The gizmo doesn't change the model. It only moves the part in the viewer,
+//! and reports the transform in a form that can be pasted into the model's
+//! code.
+
+use std::f64::consts::PI;
+
+use fj_math::{Point, Scalar, Transform, Vector};
+
+use crate::{Camera, NormalizedScreenPosition};
+
+/// Translates and rotates the selected part in the viewer
+#[derive(Clone, Debug)]
+pub struct Gizmo {
+    part: Option<String>,
+    offset: Vector<3>,
+    rotation: Transform,
+    drag: Option<Drag>,
+}
+
+impl Gizmo {
+    /// The size of the gizmo, relative to the visible width of the screen
+    const SIZE: f64 = 0.1;
+
+    /// How close the cursor needs to be to a handle to grab it
+    ///
+    /// The distance is given in normalized screen coordinates.
+    const GRAB_DISTANCE: f64 = 0.02;
+
+    /// Access the name of the part that the gizmo is attached to
+    pub fn part(&self) -> Option<&str> {
+        self.part.as_deref()
+    }
+
+    /// Attach the gizmo to the part with the given name
+    ///
+    /// Resets the gizmo, if it was attached to another part before.
+    pub fn attach(&mut self, part: Option<&str>) {
+        if self.part.as_deref() != part {
+            self.reset();
+            self.part = part.map(ToString::to_string);
+        }
+    }
+
+    /// Undo all translations and rotations
+    pub fn reset(&mut self) {
+        self.offset = Vector::from([0., 0., 0.]);
+        self.rotation = Transform::identity();
+        self.drag = None;
+    }
+
+    /// Indicate whether the part has been translated or rotated
+    pub fn is_identity(&self) -> bool {
+        self.offset.magnitude() == Scalar::ZERO
+            && axis_angle(&self.rotation).is_none()
+    }
+
+    /// Access the offset that the part has been translated by
+    pub fn offset(&self) -> Vector<3> {
+        self.offset
+    }
+
+    /// Access the axis and angle that the part has been rotated by
+    ///
+    /// The angle is given in radians. Returns `None`, if the part hasn't been
+    /// rotated.
+    pub fn rotation(&self) -> Option<(Vector<3>, Scalar)> {
+        axis_angle(&self.rotation)
+    }
+
+    /// Compute the transform that is applied to the part
+    ///
+    /// The part is rotated about its center, which is then moved by the
+    /// offset.
+    pub fn transform(&self, center: &Point<3>) -> Transform {
+        Transform::translation(center.coords + self.offset)
+            * self.rotation
+            * Transform::translation(-center.coords)
+    }
+
+    /// Express the transform as code that can be appended to the part
+    ///
+    /// Returns `None`, if the part hasn't been translated or rotated.
+    pub fn to_code(&self, center: &Point<3>) -> Option<String> {
+        let mut code = Vec::new();
+
+        // The transforms in the model rotate about the origin, not about the
+        // center of the part. The translation makes up for the difference.
+        if let Some((axis, angle)) = self.rotation() {
+            let [x, y, z] = axis.components.map(Scalar::into_f64);
+            code.push(format!(
+                ".rotate([{x:.3}, {y:.3}, {z:.3}], fj::Angle::from_deg({:.2}))",
+                angle.into_f64().to_degrees()
+            ));
+        }
+
+        let translation =
+            self.transform(center).transform_point(&Point::origin());
+        if translation.coords.magnitude() > Scalar::from(1e-9) {
+            let [x, y, z] = translation.coords.components.map(Scalar::into_f64);
+            code.push(format!(".translate([{x:.3}, {y:.3}, {z:.3}])"));
+        }
+
+        if code.is_empty() {
+            return None;
+        }
+
+        Some(code.join("\n"))
+    }
+
+    /// Compute the handles of the gizmo
+    ///
+    /// Returns `None`, if the gizmo is not in front of the camera. Each handle
+    /// is a polyline in model space.
+    pub(crate) fn handles(
+        &self,
+        center: &Point<3>,
+        camera: &Camera,
+    ) -> Option<Vec<(GizmoHandle, Vec<Point<3>>)>> {
+        const RING_SEGMENTS: usize = 64;
+
+        let position = *center + self.offset;
+        let size = camera.visible_width_at(&position)? * Self::SIZE;
+
+        let mut handles = Vec::new();
+        for axis in 0..3 {
+            let [direction, u, v] = axes(axis);
+
+            handles.push((
+                GizmoHandle::Translate(axis),
+                vec![position, position + direction * size],
+            ));
+
+            let ring = (0..=RING_SEGMENTS)
+                .map(|i| {
+                    let angle = Scalar::TAU * i as f64 / RING_SEGMENTS as f64;
+                    let (sin, cos) = angle.sin_cos();
+                    position + (u * cos + v * sin) * size * 0.8
+                })
+                .collect();
+            handles.push((GizmoHandle::Rotate(axis), ring));
+        }
+
+        Some(handles)
+    }
+
+    /// Indicate which handle is being dragged, if any
+    pub(crate) fn dragged_handle(&self) -> Option<GizmoHandle> {
+        self.drag.map(|drag| drag.handle)
+    }
+
+    /// Grab the handle under the cursor, if there is one
+    ///
+    /// Returns whether a handle has been grabbed.
+    pub(crate) fn grab(
+        &mut self,
+        center: &Point<3>,
+        camera: &Camera,
+        cursor: NormalizedScreenPosition,
+    ) -> bool {
+        let Some(handles) = self.handles(center, camera) else {
+            return false;
+        };
+
+        let closest = handles
+            .into_iter()
+            .filter_map(|(handle, points)| {
+                let points = points
+                    .iter()
+                    .map(|point| camera.project(point))
+                    .collect::<Option<Vec<_>>>()?;
+                let distance = points
+                    .windows(2)
+                    .map(|segment| {
+                        distance_to_segment(cursor, [segment[0], segment[1]])
+                    })
+                    .fold(f64::INFINITY, f64::min);
+
+                Some((handle, distance))
+            })
+            .filter(|&(_, distance)| distance <= Self::GRAB_DISTANCE)
+            .min_by(|(_, a), (_, b)| a.total_cmp(b));
+        let Some((handle, _)) = closest else {
+            return false;
+        };
+
+        let origin = *center + self.offset;
+        let mut drag = Drag {
+            handle,
+            origin,
+            offset: self.offset,
+            rotation: self.rotation,
+            start: Scalar::ZERO,
+        };
+        let Some(start) = drag.parameter(camera, cursor) else {
+            return false;
+        };
+        drag.start = start;
+
+        self.drag = Some(drag);
+        true
+    }
+
+    /// Move the grabbed handle to the cursor
+    ///
+    /// Does nothing, if no handle is grabbed.
+    pub(crate) fn drag(
+        &mut self,
+        camera: &Camera,
+        cursor: NormalizedScreenPosition,
+    ) {
+        let Some(drag) = self.drag else {
+            return;
+        };
+        let Some(parameter) = drag.parameter(camera, cursor) else {
+            return;
+        };
+        let delta = parameter - drag.start;
+
+        match drag.handle {
+            GizmoHandle::Translate(axis) => {
+                let [direction, _, _] = axes(axis);
+                self.offset = drag.offset + direction * delta;
+            }
+            GizmoHandle::Rotate(axis) => {
+                let [direction, _, _] = axes(axis);
+                self.rotation =
+                    Transform::rotation(direction * delta) * drag.rotation;
+            }
+        }
+    }
+
+    /// Release the grabbed handle
+    ///
+    /// Returns whether a handle was grabbed.
+    pub(crate) fn release(&mut self) -> bool {
+        self.drag.take().is_some()
+    }
+}
+
+impl Default for Gizmo {
+    fn default() -> Self {
+        Self {
+            part: None,
+            offset: Vector::from([0., 0., 0.]),
+            rotation: Transform::identity(),
+            drag: None,
+        }
+    }
+}
+
+/// A handle of the [`Gizmo`]
+///
+/// Each handle refers to one of the axes of model space, by index.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub(crate) enum GizmoHandle {
+    /// Translates along the axis
+    Translate(usize),
+
+    /// Rotates about the axis
+    Rotate(usize),
+}
+
+impl GizmoHandle {
+    /// Access the index of the axis that the handle refers to
+    pub(crate) fn axis(&self) -> usize {
+        match self {
+            Self::Translate(axis) | Self::Rotate(axis) => *axis,
+        }
+    }
+}
+
+/// A handle of the gizmo being dragged
+#[derive(Clone, Copy, Debug)]
+struct Drag {
+    handle: GizmoHandle,
+
+    /// The position of the gizmo when the handle was grabbed
+    origin: Point<3>,
+
+    /// The offset when the handle was grabbed
+    offset: Vector<3>,
+
+    /// The rotation when the handle was grabbed
+    rotation: Transform,
+
+    /// The parameter of the cursor when the handle was grabbed
+    start: Scalar,
+}
+
+impl Drag {
+    /// Compute the parameter of the cursor along the handle
+    ///
+    /// For translation handles, that's the distance along the axis. For
+    /// rotation handles, it's the angle about it. Returns `None`, if the
+    /// cursor ray is parallel to the axis, or the plane of rotation
+    /// respectively.
+    fn parameter(
+        &self,
+        camera: &Camera,
+        cursor: NormalizedScreenPosition,
+    ) -> Option<Scalar> {
+        let (ray_origin, ray_direction) = camera.cursor_ray(cursor);
+        let to_origin = self.origin - ray_origin;
+
+        match self.handle {
+            GizmoHandle::Translate(axis) => {
+                let [direction, _, _] = axes(axis);
+
+                // Find the point on the axis that is closest to the ray.
+                let b = direction.dot(&ray_direction);
+                let denominator = Scalar::ONE - b * b;
+                if denominator.abs() < Scalar::from(1e-6) {
+                    return None;
+                }
+
+                Some(
+                    (b * ray_direction.dot(&to_origin)
+                        - direction.dot(&to_origin))
+                        / denominator,
+                )
+            }
+            GizmoHandle::Rotate(axis) => {
+                let [normal, u, v] = axes(axis);
+
+                // Intersect the ray with the plane of rotation.
+                let denominator = normal.dot(&ray_direction);
+                if denominator.abs() < Scalar::from(1e-6) {
+                    return None;
+                }
+                let t = normal.dot(&to_origin) / denominator;
+                let point = ray_origin + ray_direction * t;
+
+                let direction = point - self.origin;
+                Some(direction.dot(&v).atan2(direction.dot(&u)))
+            }
+        }
+    }
+}
+
+/// Access an axis of model space, and the two axes perpendicular to it
+///
+/// The three axes form a right-handed coordinate system.
+fn axes(axis: usize) -> [Vector<3>; 3] {
+    let unit = [Vector::unit_x(), Vector::unit_y(), Vector::unit_z()];
+    [unit[axis], unit[(axis + 1) % 3], unit[(axis + 2) % 3]]
+}
+
+/// Extract axis and angle from a rotation
+///
+/// Returns `None`, if the rotation is the identity.
+fn axis_angle(rotation: &Transform) -> Option<(Vector<3>, Scalar)> {
+    // The matrix is stored in column-major order.
+    let data = rotation.data();
+    let m = |row: usize, column: usize| data[column * 4 + row];
+
+    let trace = m(0, 0) + m(1, 1) + m(2, 2);
+    let angle = ((trace - 1.) / 2.).clamp(-1., 1.).acos();
+    if angle < 1e-9 {
+        return None;
+    }
+
+    let axis = if PI - angle < 1e-6 {
+        // The matrix is almost symmetric, so the axis can't be taken from
+        // the difference between its off-diagonal elements. Take it from the
+        // column with the largest diagonal element instead.
+        let i = (0..3)
+            .max_by(|&a, &b| m(a, a).total_cmp(&m(b, b)))
+            .unwrap_or(0);
+        let mut axis = [0.; 3];
+        for (j, component) in axis.iter_mut().enumerate() {
+            *component = m(j, i) + if i == j { 1. } else { 0. };
+        }
+        Vector::from(axis)
+    } else {
+        Vector::from([m(2, 1) - m(1, 2), m(0, 2) - m(2, 0), m(1, 0) - m(0, 1)])
+    };
+
+    Some((axis.normalize(), Scalar::from(angle)))
+}
+
+/// Compute the distance from a point on the screen to a line segment
+fn distance_to_segment(
+    point: NormalizedScreenPosition,
+    [a, b]: [NormalizedScreenPosition; 2],
+) -> f64 {
+    let [dx, dy] = [b.x - a.x, b.y - a.y];
+    let length_squared = dx * dx + dy * dy;
+
+    let t = if length_squared > 0. {
+        (((point.x - a.x) * dx + (point.y - a.y) * dy) / length_squared)
+            .clamp(0., 1.)
+    } else {
+        0.
+    };
+
+    let [x, y] = [a.x + dx * t - point.x, a.y + dy * t - point.y];
+    (x * x + y * y).sqrt()
+}
+
+/// The gizmo, as it's shown in the GUI
+pub(crate) struct GizmoView<'r> {
+    /// The gizmo
+    pub gizmo: &'r mut Gizmo,
+
+    /// The center of the part that the gizmo is attached to
+    pub center: Point<3>,
+
+    /// The handles of the gizmo, projected onto the screen
+    pub handles: Vec<(GizmoHandle, Vec<NormalizedScreenPosition>)>,
+}
@@ -1,3 +1,5 @@
+use fj_math::{Scalar, Vector};
+
 /// High level configuration for rendering the active model
 #[derive(Debug)]
 pub struct DrawConfig {
@@ -9,6 +11,27 @@ pub struct DrawConfig {
 
     /// Toggle for displaying model debug information
     pub draw_debug: bool,
+
+    /// Toggle for displaying model debug information on top of the model
+    ///
+    /// If this is disabled, debug information is hidden by the model, where
+    /// the model is in front of it.
+    pub debug_on_top: bool,
+
+    /// Toggle for displaying a scale bar on top of the model
+    pub draw_scale_bar: bool,
+
+    /// Toggle for displaying the gizmo for moving the selected part
+    pub draw_gizmo: bool,
+
+    /// The plane that cuts the model open, if any
+    pub clipping_plane: Option<ClippingPlane>,
+
+    /// How the model is drawn for each eye, if it is viewed stereoscopically
+    pub stereo: Option<Stereo>,
+
+    /// How the model is lit
+    pub lighting: Lighting,
 }
 
 impl Default for DrawConfig {
@@ -17,6 +40,166 @@ impl Default for DrawConfig {
             draw_model: true,
             draw_mesh: false,
             draw_debug: false,
+            debug_on_top: false,
+            draw_scale_bar: true,
+            draw_gizmo: false,
+            clipping_plane: None,
+            stereo: None,
+            lighting: Lighting::default(),
+        }
+    }
+}
+
+/// A plane that cuts the model open, to show what's inside
+///
+/// Only the drawing of the model is affected. The model itself is left as it
+/// is.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct ClippingPlane {
+    /// The normal of the plane
+    ///
+    /// The part of the model that the normal points towards is cut away.
+    pub normal: Vector<3>,
+
+    /// The distance of the plane from the origin, along the normal
+    pub distance: Scalar,
+
+    /// The color of the surfaces where the model is cut, if they are capped
+    ///
+    /// Without capping, the model looks hollow where it is cut. Capping
+    /// relies on the model being closed.
+    pub cap_color: Option<[u8; 4]>,
+}
+
+impl ClippingPlane {
+    /// The color that cut surfaces are capped with by default
+    pub const DEFAULT_CAP_COLOR: [u8; 4] = [200, 60, 60, 255];
+}
+
+/// The lights that shade the model
+///
+/// The lights move with the camera, so the model is lit the same way, from
+/// wherever it is viewed.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Lighting {
+    /// How the color of the model's surface is computed
+    pub shading: Shading,
+
+    /// The horizontal angle of the key light, in degrees
+    ///
+    /// 0 is straight from the camera, positive angles are to the right of it.
+    pub key_light_azimuth: f64,
+
+    /// The vertical angle of the key light, in degrees
+    ///
+    /// 0 is straight from the camera, positive angles are above it.
+    pub key_light_elevation: f64,
+
+    /// The brightness of the key light, from 0 to 1
+    pub key_light_intensity: f64,
+
+    /// The brightness of the light that comes from all directions, from 0 to 1
+    ///
+    /// This keeps surfaces that face away from the key light from being black.
+    pub ambient_intensity: f64,
+}
+
+impl Lighting {
+    /// Compute the direction towards the key light, in camera space
+    pub(crate) fn key_light_direction(&self) -> Vector<3> {
+        let azimuth = self.key_light_azimuth.to_radians();
+        let elevation = self.key_light_elevation.to_radians();
+
+        // The camera looks along the negative z-axis of camera space.
+        Vector::from([
+            azimuth.sin() * elevation.cos(),
+            elevation.sin(),
+            azimuth.cos() * elevation.cos(),
+        ])
+    }
+}
+
+impl Default for Lighting {
+    fn default() -> Self {
+        // Light from the top left, as is customary. Light coming from a
+        // different direction than the camera's gives surfaces that face in
+        // different directions different shades.
+        Self {
+            shading: Shading::default(),
+            key_light_azimuth: -30.,
+            key_light_elevation: 30.,
+            key_light_intensity: 0.75,
+            ambient_intensity: 0.25,
         }
     }
 }
+
+/// How the color of the model's surface is computed
+///
+/// See [`Lighting`].
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum Shading {
+    /// Shade the surface using the key light and the ambient light
+    #[default]
+    Lit,
+
+    /// Shade the surface like a glossy sphere of clay, ignoring the lights
+    ///
+    /// The shade only depends on the direction of the surface, relative to
+    /// the camera, like with a material capture (matcap). Surfaces that face
+    /// the camera are bright, those seen at a grazing angle are dark. That
+    /// makes concave details easy to read, from any direction.
+    Matcap,
+}
+
+/// Drawing of the model for each eye, to view it in 3D
+///
+/// The model is drawn twice, from two cameras left and right of the actual
+/// one. Both look at the model's center, where the two images line up.
+///
+/// Only the model and its debug information are drawn for each eye. Everything
+/// else, like the GUI, is drawn once, as without stereo.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Stereo {
+    /// How the images for the two eyes are combined
+    pub mode: StereoMode,
+
+    /// The distance between the eyes
+    ///
+    /// Given as a fraction of the distance from the camera to the model's
+    /// center, so the depth effect stays the same when zooming.
+    pub eye_separation: f64,
+}
+
+impl Stereo {
+    /// The default distance between the eyes
+    ///
+    /// That's about the distance between human eyes, when viewing an object
+    /// from 2 meters away.
+    pub const DEFAULT_EYE_SEPARATION: f64 = 0.03;
+
+    /// Compute the offset of each eye, along the x-axis of camera space
+    ///
+    /// Returns the offsets of the left and right eye, in that order.
+    pub(crate) fn eye_offsets(&self, focal_distance: f64) -> [f64; 2] {
+        let offset = self.eye_separation * focal_distance / 2.;
+        [-offset, offset]
+    }
+}
+
+/// How the images for the two eyes are combined
+///
+/// See [`Stereo`].
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum StereoMode {
+    /// The image for the left eye on the left half, the right one on the right
+    ///
+    /// For stereoscopic displays, or for viewing with parallel eyes.
+    #[default]
+    SideBySide,
+
+    /// The image for the left eye in red, the right one in cyan
+    ///
+    /// For viewing with red-cyan glasses.
+    Anaglyph,
+}
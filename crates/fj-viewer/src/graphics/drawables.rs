@@ -7,6 +7,7 @@ pub struct Drawables<'r> {
     pub model: Drawable<'r>,
     pub mesh: Drawable<'r>,
     pub lines: Drawable<'r>,
+    pub lines_on_top: Drawable<'r>,
 }
 
 impl<'r> Drawables<'r> {
@@ -14,8 +15,15 @@ impl<'r> Drawables<'r> {
         let model = Drawable::new(&geometries.mesh, &pipelines.model);
         let mesh = Drawable::new(&geometries.mesh, &pipelines.mesh);
         let lines = Drawable::new(&geometries.lines, &pipelines.lines);
+        let lines_on_top =
+            Drawable::new(&geometries.lines, &pipelines.lines_on_top);
 
-        Self { model, mesh, lines }
+        Self {
+            model,
+            mesh,
+            lines,
+            lines_on_top,
+        }
     }
 }
 
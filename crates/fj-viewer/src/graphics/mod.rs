@@ -5,15 +5,23 @@ mod drawables;
 mod geometries;
 mod pipelines;
 mod renderer;
+mod screenshot;
 mod shaders;
 mod transform;
 mod uniforms;
 mod vertices;
 
 pub use self::{
-    draw_config::DrawConfig,
-    renderer::{DrawError, Renderer, RendererInitError},
+    draw_config::{
+        ClippingPlane, DrawConfig, Lighting, Shading, Stereo, StereoMode,
+    },
+    renderer::{DrawError, OffscreenRenderer, Renderer, RendererInitError},
+    screenshot::{Screenshot, ScreenshotError},
 };
 
 const DEPTH_FORMAT: wgpu::TextureFormat = wgpu::TextureFormat::Depth32Float;
-const SAMPLE_COUNT: u32 = 4;
+
+/// The number of samples per pixel that the model is drawn with, by default
+///
+/// See [`Renderer::new`].
+pub const DEFAULT_SAMPLE_COUNT: u32 = 4;
@@ -3,7 +3,7 @@ use std::mem::size_of;
 use super::{
     shaders::{Shader, Shaders},
     vertices::Vertex,
-    DEPTH_FORMAT, SAMPLE_COUNT,
+    DEPTH_FORMAT,
 };
 
 #[derive(Debug)]
@@ -11,6 +11,7 @@ pub struct Pipelines {
     pub model: Pipeline,
     pub mesh: Pipeline,
     pub lines: Pipeline,
+    pub lines_on_top: Pipeline,
 }
 
 impl Pipelines {
@@ -18,7 +19,20 @@ impl Pipelines {
         device: &wgpu::Device,
         bind_group_layout: &wgpu::BindGroupLayout,
         color_format: wgpu::TextureFormat,
+        sample_count: u32,
+        features: wgpu::Features,
+        write_mask: wgpu::ColorWrites,
     ) -> Self {
+        // Devices that can't draw lines, like some software renderers, can't
+        // even create pipelines that do. Those pipelines are never used on
+        // such devices, but still need to exist.
+        let line_mode = if features.contains(wgpu::Features::POLYGON_MODE_LINE)
+        {
+            wgpu::PolygonMode::Line
+        } else {
+            wgpu::PolygonMode::Fill
+        };
+
         let pipeline_layout =
             device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
                 label: None,
@@ -35,23 +49,43 @@ impl Pipelines {
                 shaders.model(),
                 wgpu::PrimitiveTopology::TriangleList,
                 wgpu::PolygonMode::Fill,
+                DepthTest::Enabled,
                 color_format,
+                sample_count,
+                write_mask,
             ),
             mesh: Pipeline::new(
                 device,
                 &pipeline_layout,
                 shaders.mesh(),
                 wgpu::PrimitiveTopology::TriangleList,
-                wgpu::PolygonMode::Line,
+                line_mode,
+                DepthTest::Enabled,
                 color_format,
+                sample_count,
+                write_mask,
             ),
             lines: Pipeline::new(
                 device,
                 &pipeline_layout,
                 shaders.lines(),
                 wgpu::PrimitiveTopology::LineList,
-                wgpu::PolygonMode::Line,
+                line_mode,
+                DepthTest::Enabled,
+                color_format,
+                sample_count,
+                write_mask,
+            ),
+            lines_on_top: Pipeline::new(
+                device,
+                &pipeline_layout,
+                shaders.lines(),
+                wgpu::PrimitiveTopology::LineList,
+                line_mode,
+                DepthTest::Disabled,
                 color_format,
+                sample_count,
+                write_mask,
             ),
         }
     }
@@ -61,21 +95,33 @@ impl Pipelines {
 pub struct Pipeline(pub wgpu::RenderPipeline);
 
 impl Pipeline {
+    #[allow(clippy::too_many_arguments)]
     fn new(
         device: &wgpu::Device,
         pipeline_layout: &wgpu::PipelineLayout,
         shader: Shader,
         topology: wgpu::PrimitiveTopology,
         polygon_mode: wgpu::PolygonMode,
+        depth_test: DepthTest,
         color_format: wgpu::TextureFormat,
+        sample_count: u32,
+        write_mask: wgpu::ColorWrites,
     ) -> Self {
+        // Geometry that isn't depth-tested is drawn on top of everything
+        // drawn before it. It must not write to the depth buffer either, or
+        // it would hide anything drawn after it.
+        let (depth_write_enabled, depth_compare) = match depth_test {
+            DepthTest::Enabled => (true, wgpu::CompareFunction::LessEqual),
+            DepthTest::Disabled => (false, wgpu::CompareFunction::Always),
+        };
+
         let pipeline =
             device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
                 label: None,
                 layout: Some(pipeline_layout),
                 vertex: wgpu::VertexState {
                     module: shader.module,
-                    entry_point: "vertex",
+                    entry_point: shader.vertex_entry,
                     buffers: &[wgpu::VertexBufferLayout {
                         array_stride: size_of::<Vertex>() as u64,
                         step_mode: wgpu::VertexStepMode::Vertex,
@@ -97,8 +143,8 @@ impl Pipeline {
                 },
                 depth_stencil: Some(wgpu::DepthStencilState {
                     format: DEPTH_FORMAT,
-                    depth_write_enabled: true,
-                    depth_compare: wgpu::CompareFunction::LessEqual,
+                    depth_write_enabled,
+                    depth_compare,
                     stencil: wgpu::StencilState {
                         front: wgpu::StencilFaceState::IGNORE,
                         back: wgpu::StencilFaceState::IGNORE,
@@ -108,9 +154,10 @@ impl Pipeline {
                     bias: wgpu::DepthBiasState::default(),
                 }),
                 multisample: wgpu::MultisampleState {
-                    count: SAMPLE_COUNT,
+                    count: sample_count,
                     mask: !0,
-                    alpha_to_coverage_enabled: true,
+                    // Without multisampling, there's no coverage to speak of.
+                    alpha_to_coverage_enabled: sample_count > 1,
                 },
                 fragment: Some(wgpu::FragmentState {
                     module: shader.module,
@@ -120,7 +167,7 @@ impl Pipeline {
                         blend: Some(
                             wgpu::BlendState::PREMULTIPLIED_ALPHA_BLENDING,
                         ),
-                        write_mask: wgpu::ColorWrites::ALL,
+                        write_mask,
                     })],
                 }),
                 multiview: None,
@@ -129,3 +176,9 @@ impl Pipeline {
         Self(pipeline)
     }
 }
+
+#[derive(Clone, Copy, Debug)]
+enum DepthTest {
+    Enabled,
+    Disabled,
+}
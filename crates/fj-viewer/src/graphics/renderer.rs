@@ -1,54 +1,345 @@
-use std::{io, mem::size_of};
+use std::{
+    io,
+    mem::size_of,
+    num::NonZeroU32,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+};
 
+use fj_interop::processed_shape::ProcessedShape;
+use fj_math::Scalar;
 use thiserror::Error;
-use tracing::debug;
+use tracing::{debug, error, warn};
 use wgpu::util::DeviceExt as _;
 use wgpu_glyph::ab_glyph::InvalidFont;
 
 use crate::{
     camera::Camera,
+    extension::{Extension, RenderContext},
     gui::Gui,
     screen::{Screen, ScreenSize},
 };
 
 use super::{
-    draw_config::DrawConfig, drawables::Drawables, geometries::Geometries,
-    pipelines::Pipelines, transform::Transform, uniforms::Uniforms,
-    vertices::Vertices, DEPTH_FORMAT, SAMPLE_COUNT,
+    draw_config::{DrawConfig, Shading, StereoMode},
+    drawables::Drawables,
+    geometries::Geometries,
+    pipelines::Pipelines,
+    screenshot::Screenshot,
+    transform::Transform,
+    uniforms::Uniforms,
+    vertices::Vertices,
+    ScreenshotError, DEPTH_FORMAT,
 };
 
 /// Graphics rendering state and target abstraction
+///
+/// The graphics device can get lost at any time, for example if the driver is
+/// reset, or the system switches between GPUs. `wgpu` reports that through
+/// uncaptured device errors, or through errors when acquiring the next frame.
+/// The renderer stops drawing on those, until [`Renderer::recover`] has created
+/// a new device.
 #[derive(Debug)]
 pub struct Renderer {
+    instance: wgpu::Instance,
     surface: wgpu::Surface,
+    sample_count: u32,
+    device_lost: Arc<AtomicBool>,
+    gpu: Gpu,
+}
+
+impl Renderer {
+    /// Returns a new `Renderer`.
+    ///
+    /// The model is drawn with `sample_count` samples per pixel, to smooth its
+    /// edges. 1, 4, and 8 samples can be requested. If the graphics device
+    /// doesn't support that many, the highest number it does support is used
+    /// instead. See [`DEFAULT_SAMPLE_COUNT`].
+    ///
+    /// [`DEFAULT_SAMPLE_COUNT`]: super::DEFAULT_SAMPLE_COUNT
+    pub async fn new(
+        screen: &impl Screen,
+        sample_count: u32,
+    ) -> Result<Self, RendererInitError> {
+        let instance = wgpu::Instance::new(wgpu::Backends::PRIMARY);
+
+        // This is sound, as `window` is an object to create a surface upon.
+        let surface = unsafe { instance.create_surface(screen.window()) };
+
+        let device_lost = Arc::new(AtomicBool::new(false));
+        let gpu = Gpu::new(
+            &instance,
+            Some(&surface),
+            screen.size(),
+            sample_count,
+            &device_lost,
+        )
+        .await?;
+
+        Ok(Self {
+            instance,
+            surface,
+            sample_count,
+            device_lost,
+            gpu,
+        })
+    }
+
+    /// Indicate whether the graphics device has been lost
+    ///
+    /// Nothing is drawn while that is the case. Call [`Renderer::recover`] to
+    /// create a new device.
+    pub fn is_device_lost(&self) -> bool {
+        self.device_lost.load(Ordering::Relaxed)
+    }
+
+    /// Create a new graphics device, to replace the one that has been lost
+    ///
+    /// The geometry needs to be uploaded again, using
+    /// [`Renderer::update_geometry`], and the GUI needs to be re-initialized,
+    /// using [`Renderer::reinit_gui`].
+    pub async fn recover(&mut self) -> Result<(), RendererInitError> {
+        let size = ScreenSize {
+            width: self.gpu.surface_config.width,
+            height: self.gpu.surface_config.height,
+        };
+
+        // The old device reports errors to the same flag, so it needs a new
+        // one.
+        let device_lost = Arc::new(AtomicBool::new(false));
+        self.gpu = Gpu::new(
+            &self.instance,
+            Some(&self.surface),
+            size,
+            self.sample_count,
+            &device_lost,
+        )
+        .await?;
+        self.device_lost = device_lost;
+
+        Ok(())
+    }
+
+    pub(crate) fn init_gui(&self) -> Gui {
+        Gui::new(&self.gpu.device, self.gpu.surface_config.format)
+    }
+
+    /// Prepare the GUI for drawing with a new graphics device
+    pub(crate) fn reinit_gui(&self, gui: &mut Gui) {
+        gui.reinit(&self.gpu.device, self.gpu.surface_config.format);
+    }
+
+    /// Updates the geometry of the model being rendered.
+    pub fn update_geometry(&mut self, mesh: Vertices, lines: Vertices) {
+        let gpu = &mut self.gpu;
+        guard(&self.device_lost, || {
+            gpu.geometries = Geometries::new(&gpu.device, &mesh, &lines);
+        });
+    }
+
+    /// Resizes the render surface.
+    ///
+    /// # Arguments
+    /// - `size`: The target size for the render surface.
+    pub fn handle_resize(&mut self, size: ScreenSize) {
+        let gpu = &mut self.gpu;
+        gpu.surface_config.width = size.width;
+        gpu.surface_config.height = size.height;
+
+        let surface = &self.surface;
+        guard(&self.device_lost, || {
+            surface.configure(&gpu.device, &gpu.surface_config);
+
+            gpu.frame_buffer = Gpu::create_frame_buffer(
+                &gpu.device,
+                &gpu.surface_config,
+                gpu.sample_count,
+            );
+            gpu.depth_view = Gpu::create_depth_buffer(
+                &gpu.device,
+                &gpu.surface_config,
+                gpu.sample_count,
+            );
+        });
+    }
+
+    /// Draws the renderer, camera, and config state to the window.
+    ///
+    /// The extensions draw their own graphics, after the model.
+    pub fn draw(
+        &mut self,
+        camera: &Camera,
+        config: &DrawConfig,
+        scale_factor: f32,
+        gui: &mut Gui,
+        extensions: &mut [Box<dyn Extension>],
+        shape: Option<&ProcessedShape>,
+    ) -> Result<(), DrawError> {
+        let gpu = &self.gpu;
+        let surface = &self.surface;
+
+        let result = guard(&self.device_lost, || {
+            gpu.draw(
+                surface,
+                camera,
+                config,
+                scale_factor,
+                gui,
+                extensions,
+                shape,
+            )
+        })
+        .unwrap_or(Err(DrawError::DeviceLost));
+
+        if let Err(DrawError::DeviceLost) = result {
+            self.device_lost.store(true, Ordering::Relaxed);
+        }
+
+        result
+    }
+
+    /// Draw what [`Renderer::draw`] draws, except the GUI, into an image
+    ///
+    /// The image has the size of the screen.
+    pub fn screenshot(
+        &mut self,
+        camera: &Camera,
+        config: &DrawConfig,
+        extensions: &mut [Box<dyn Extension>],
+        shape: Option<&ProcessedShape>,
+    ) -> Result<Screenshot, ScreenshotError> {
+        let gpu = &self.gpu;
+        guard(&self.device_lost, || {
+            gpu.capture(camera, config, extensions, shape)
+        })
+        .unwrap_or(Err(ScreenshotError::DeviceLost))
+    }
+
+    /// Access the number of samples per pixel that the model is drawn with
+    ///
+    /// This can be lower than the number passed to [`Renderer::new`], if the
+    /// graphics device doesn't support that many.
+    pub fn sample_count(&self) -> u32 {
+        self.gpu.sample_count
+    }
+
+    /// Returns true if the renderer's adapter can draw lines
+    pub fn is_line_drawing_available(&self) -> bool {
+        self.gpu
+            .features
+            .contains(wgpu::Features::POLYGON_MODE_LINE)
+    }
+}
+
+/// Renders images of a shape, without a window
+///
+/// Useful for rendering previews of models, where no screen is available.
+#[derive(Debug)]
+pub struct OffscreenRenderer {
+    device_lost: Arc<AtomicBool>,
+    gpu: Gpu,
+}
+
+impl OffscreenRenderer {
+    /// Create a renderer for images of the given size
+    ///
+    /// See [`Renderer::new`] for what `sample_count` means.
+    pub async fn new(
+        size: ScreenSize,
+        sample_count: u32,
+    ) -> Result<Self, RendererInitError> {
+        // Without a window, any backend will do. Machines without a GPU, like
+        // CI servers, often only provide a software implementation of OpenGL.
+        let instance = wgpu::Instance::new(wgpu::Backends::all());
+
+        let device_lost = Arc::new(AtomicBool::new(false));
+        let gpu =
+            Gpu::new(&instance, None, size, sample_count, &device_lost).await?;
+
+        Ok(Self { device_lost, gpu })
+    }
+
+    /// Render an image of a shape
+    pub fn render(
+        &mut self,
+        shape: &ProcessedShape,
+        camera: &Camera,
+        config: &DrawConfig,
+    ) -> Result<Screenshot, ScreenshotError> {
+        let gpu = &mut self.gpu;
+        guard(&self.device_lost, || {
+            gpu.geometries = Geometries::new(
+                &gpu.device,
+                &(&shape.mesh).into(),
+                &(&shape.debug_info).into(),
+            );
+            gpu.capture(camera, config, &mut [], Some(shape))
+        })
+        .unwrap_or(Err(ScreenshotError::DeviceLost))
+    }
+
+    /// Render an image of a shape, as it is first shown in the viewer
+    pub fn render_shape(
+        &mut self,
+        shape: &ProcessedShape,
+    ) -> Result<Screenshot, ScreenshotError> {
+        let mut camera = Camera::new();
+        camera.init_planes(&shape.aabb);
+        camera.update_planes(&shape.aabb);
+
+        self.render(shape, &camera, &DrawConfig::default())
+    }
+}
+
+/// The state of the renderer that depends on the graphics device
+#[derive(Debug)]
+struct Gpu {
     features: wgpu::Features,
     device: wgpu::Device,
     queue: wgpu::Queue,
 
     surface_config: wgpu::SurfaceConfiguration,
-    frame_buffer: wgpu::TextureView,
+    sample_count: u32,
+
+    /// The multisampled frame buffer, unless there's only one sample
+    frame_buffer: Option<wgpu::TextureView>,
     depth_view: wgpu::TextureView,
 
-    uniform_buffer: wgpu::Buffer,
-    bind_group: wgpu::BindGroup,
+    /// The uniforms, and their bind group, for each eye
+    ///
+    /// Without stereo, only the first one is used.
+    uniforms: [(wgpu::Buffer, wgpu::BindGroup); 2],
 
     geometries: Geometries,
     pipelines: Pipelines,
-}
-
-impl Renderer {
-    /// Returns a new `Renderer`.
-    pub async fn new(screen: &impl Screen) -> Result<Self, RendererInitError> {
-        let instance = wgpu::Instance::new(wgpu::Backends::PRIMARY);
 
-        // This is sound, as `window` is an object to create a surface upon.
-        let surface = unsafe { instance.create_surface(screen.window()) };
+    /// The pipelines for the left and right eye, in anaglyph stereo
+    ///
+    /// They only draw into the red, and the green and blue channels,
+    /// respectively.
+    anaglyph_pipelines: [Pipelines; 2],
+}
 
+impl Gpu {
+    /// Request a graphics device, and create everything that depends on it
+    ///
+    /// Errors of the device are logged, and reported to `device_lost`.
+    ///
+    /// Without a surface, the device can only draw into images.
+    async fn new(
+        instance: &wgpu::Instance,
+        surface: Option<&wgpu::Surface>,
+        size: ScreenSize,
+        sample_count: u32,
+        device_lost: &Arc<AtomicBool>,
+    ) -> Result<Self, RendererInitError> {
         let adapter = instance
             .request_adapter(&wgpu::RequestAdapterOptions {
                 power_preference: wgpu::PowerPreference::HighPerformance,
                 force_fallback_adapter: false,
-                compatible_surface: Some(&surface),
+                compatible_surface: surface,
             })
             .await
             .ok_or(RendererInitError::RequestAdapter)?;
@@ -91,13 +382,24 @@ impl Renderer {
             )
             .await?;
 
-        let color_format = surface
-            .get_supported_formats(&adapter)
-            .get(0)
-            .copied()
-            .expect("Error determining preferred color format");
+        device.on_uncaptured_error({
+            let device_lost = device_lost.clone();
+            move |err| {
+                error!("Graphics device error: {err}");
+                device_lost.store(true, Ordering::Relaxed);
+            }
+        });
 
-        let ScreenSize { width, height } = screen.size();
+        let color_format = match surface {
+            Some(surface) => surface
+                .get_supported_formats(&adapter)
+                .get(0)
+                .copied()
+                .expect("Error determining preferred color format"),
+            None => wgpu::TextureFormat::Rgba8UnormSrgb,
+        };
+
+        let ScreenSize { width, height } = size;
         let surface_config = wgpu::SurfaceConfiguration {
             usage: wgpu::TextureUsages::RENDER_ATTACHMENT,
             format: color_format,
@@ -105,18 +407,17 @@ impl Renderer {
             height,
             present_mode: wgpu::PresentMode::AutoVsync,
         };
-        surface.configure(&device, &surface_config);
+        if let Some(surface) = surface {
+            surface.configure(&device, &surface_config);
+        }
 
-        let frame_buffer = Self::create_frame_buffer(&device, &surface_config);
-        let depth_view = Self::create_depth_buffer(&device, &surface_config);
+        let sample_count =
+            supported_sample_count(&adapter, color_format, sample_count);
+        let frame_buffer =
+            Self::create_frame_buffer(&device, &surface_config, sample_count);
+        let depth_view =
+            Self::create_depth_buffer(&device, &surface_config, sample_count);
 
-        let uniform_buffer =
-            device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
-                label: None,
-                contents: bytemuck::cast_slice(&[Uniforms::default()]),
-                usage: wgpu::BufferUsages::UNIFORM
-                    | wgpu::BufferUsages::COPY_DST,
-            });
         let bind_group_layout =
             device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
                 entries: &[wgpu::BindGroupLayoutEntry {
@@ -135,89 +436,81 @@ impl Renderer {
                 }],
                 label: None,
             });
-        let bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
-            layout: &bind_group_layout,
-            entries: &[wgpu::BindGroupEntry {
-                binding: 0,
-                resource: wgpu::BindingResource::Buffer(wgpu::BufferBinding {
-                    buffer: &uniform_buffer,
-                    offset: 0,
-                    size: None,
-                }),
-            }],
-            label: None,
+        let uniforms = [(); 2].map(|()| {
+            let buffer =
+                device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+                    label: None,
+                    contents: bytemuck::cast_slice(&[Uniforms::default()]),
+                    usage: wgpu::BufferUsages::UNIFORM
+                        | wgpu::BufferUsages::COPY_DST,
+                });
+            let bind_group =
+                device.create_bind_group(&wgpu::BindGroupDescriptor {
+                    layout: &bind_group_layout,
+                    entries: &[wgpu::BindGroupEntry {
+                        binding: 0,
+                        resource: wgpu::BindingResource::Buffer(
+                            wgpu::BufferBinding {
+                                buffer: &buffer,
+                                offset: 0,
+                                size: None,
+                            },
+                        ),
+                    }],
+                    label: None,
+                });
+
+            (buffer, bind_group)
         });
 
         let geometries =
             Geometries::new(&device, &Vertices::empty(), &Vertices::empty());
-        let pipelines =
-            Pipelines::new(&device, &bind_group_layout, color_format);
+        let [pipelines, left_eye_pipelines, right_eye_pipelines] = [
+            wgpu::ColorWrites::ALL,
+            wgpu::ColorWrites::RED,
+            wgpu::ColorWrites::GREEN | wgpu::ColorWrites::BLUE,
+        ]
+        .map(|write_mask| {
+            Pipelines::new(
+                &device,
+                &bind_group_layout,
+                color_format,
+                sample_count,
+                features,
+                write_mask,
+            )
+        });
 
         Ok(Self {
-            surface,
             features,
             device,
             queue,
 
             surface_config,
+            sample_count,
             frame_buffer,
             depth_view,
 
-            uniform_buffer,
-            bind_group,
+            uniforms,
 
             geometries,
             pipelines,
+            anaglyph_pipelines: [left_eye_pipelines, right_eye_pipelines],
         })
     }
 
-    pub(crate) fn init_gui(&self) -> Gui {
-        Gui::new(&self.device, self.surface_config.format)
-    }
-
-    /// Updates the geometry of the model being rendered.
-    pub fn update_geometry(&mut self, mesh: Vertices, lines: Vertices) {
-        self.geometries = Geometries::new(&self.device, &mesh, &lines);
-    }
-
-    /// Resizes the render surface.
-    ///
-    /// # Arguments
-    /// - `size`: The target size for the render surface.
-    pub fn handle_resize(&mut self, size: ScreenSize) {
-        self.surface_config.width = size.width;
-        self.surface_config.height = size.height;
-
-        self.surface.configure(&self.device, &self.surface_config);
-
-        self.frame_buffer =
-            Self::create_frame_buffer(&self.device, &self.surface_config);
-        self.depth_view =
-            Self::create_depth_buffer(&self.device, &self.surface_config);
-    }
-
-    /// Draws the renderer, camera, and config state to the window.
-    pub fn draw(
-        &mut self,
+    #[allow(clippy::too_many_arguments)]
+    fn draw(
+        &self,
+        surface: &wgpu::Surface,
         camera: &Camera,
         config: &DrawConfig,
         scale_factor: f32,
         gui: &mut Gui,
+        extensions: &mut [Box<dyn Extension>],
+        shape: Option<&ProcessedShape>,
     ) -> Result<(), DrawError> {
-        let aspect_ratio = self.surface_config.width as f64
-            / self.surface_config.height as f64;
-        let uniforms = Uniforms {
-            transform: Transform::for_vertices(camera, aspect_ratio),
-            transform_normals: Transform::for_normals(camera),
-        };
-
-        self.queue.write_buffer(
-            &self.uniform_buffer,
-            0,
-            bytemuck::cast_slice(&[uniforms]),
-        );
-
-        let surface_texture = match self.surface.get_current_texture() {
+        let surface_texture = match surface.get_current_texture() {
             Ok(surface_texture) => surface_texture,
             Err(wgpu::SurfaceError::Timeout) => {
                 // I'm seeing this all the time now (as in, multiple times per
@@ -229,7 +522,15 @@ impl Renderer {
                 // - https://github.com/gfx-rs/wgpu/issues/1565
                 return Ok(());
             }
-            result => result?,
+            Err(wgpu::SurfaceError::Lost | wgpu::SurfaceError::Outdated) => {
+                // This happens when the window is resized or moved between
+                // displays, for example. Skipping a frame is fine.
+                surface.configure(&self.device, &self.surface_config);
+                return Ok(());
+            }
+            Err(wgpu::SurfaceError::OutOfMemory) => {
+                return Err(DrawError::DeviceLost);
+            }
         };
         let color_view = surface_texture
             .texture
@@ -239,51 +540,14 @@ impl Renderer {
             &wgpu::CommandEncoderDescriptor { label: None },
         );
 
-        // Need this block here, as a render pass only takes effect once it's
-        // dropped.
-        {
-            let mut render_pass =
-                encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
-                    label: None,
-                    color_attachments: &[Some(
-                        wgpu::RenderPassColorAttachment {
-                            view: &self.frame_buffer,
-                            resolve_target: Some(&color_view),
-                            ops: wgpu::Operations {
-                                load: wgpu::LoadOp::Clear(wgpu::Color::WHITE),
-                                // Not necessary, due to MSAA being enabled.
-                                store: false,
-                            },
-                        },
-                    )],
-                    depth_stencil_attachment: Some(
-                        wgpu::RenderPassDepthStencilAttachment {
-                            view: &self.depth_view,
-                            depth_ops: Some(wgpu::Operations {
-                                load: wgpu::LoadOp::Clear(1.0),
-                                store: true,
-                            }),
-                            stencil_ops: None,
-                        },
-                    ),
-                });
-            render_pass.set_bind_group(0, &self.bind_group, &[]);
-
-            let drawables = Drawables::new(&self.geometries, &self.pipelines);
-
-            if config.draw_model {
-                drawables.model.draw(&mut render_pass);
-            }
-
-            if self.is_line_drawing_available() {
-                if config.draw_mesh {
-                    drawables.mesh.draw(&mut render_pass);
-                }
-                if config.draw_debug {
-                    drawables.lines.draw(&mut render_pass);
-                }
-            }
-        };
+        self.draw_scene(
+            &mut encoder,
+            &color_view,
+            camera,
+            config,
+            extensions,
+            shape,
+        );
 
         gui.draw(
             &self.device,
@@ -309,10 +573,321 @@ impl Renderer {
         Ok(())
     }
 
+    /// Draw the model and the extensions into an image, and read it back
+    fn capture(
+        &self,
+        camera: &Camera,
+        config: &DrawConfig,
+        extensions: &mut [Box<dyn Extension>],
+        shape: Option<&ProcessedShape>,
+    ) -> Result<Screenshot, ScreenshotError> {
+        let wgpu::SurfaceConfiguration {
+            width,
+            height,
+            format,
+            ..
+        } = self.surface_config;
+
+        let size = wgpu::Extent3d {
+            width,
+            height,
+            depth_or_array_layers: 1,
+        };
+        let texture = self.device.create_texture(&wgpu::TextureDescriptor {
+            label: None,
+            size,
+            mip_level_count: 1,
+            sample_count: 1,
+            dimension: wgpu::TextureDimension::D2,
+            format,
+            usage: wgpu::TextureUsages::RENDER_ATTACHMENT
+                | wgpu::TextureUsages::COPY_SRC,
+        });
+        let color_view =
+            texture.create_view(&wgpu::TextureViewDescriptor::default());
+
+        // Rows of the copied image need to be aligned.
+        let bytes_per_row =
+            (width * 4).next_multiple_of(wgpu::COPY_BYTES_PER_ROW_ALIGNMENT);
+        let buffer = self.device.create_buffer(&wgpu::BufferDescriptor {
+            label: None,
+            size: u64::from(bytes_per_row) * u64::from(height),
+            usage: wgpu::BufferUsages::COPY_DST | wgpu::BufferUsages::MAP_READ,
+            mapped_at_creation: false,
+        });
+
+        let mut encoder = self.device.create_command_encoder(
+            &wgpu::CommandEncoderDescriptor { label: None },
+        );
+        self.draw_scene(
+            &mut encoder,
+            &color_view,
+            camera,
+            config,
+            extensions,
+            shape,
+        );
+        encoder.copy_texture_to_buffer(
+            texture.as_image_copy(),
+            wgpu::ImageCopyBuffer {
+                buffer: &buffer,
+                layout: wgpu::ImageDataLayout {
+                    offset: 0,
+                    bytes_per_row: NonZeroU32::new(bytes_per_row),
+                    rows_per_image: None,
+                },
+            },
+            size,
+        );
+        self.queue.submit(Some(encoder.finish()));
+
+        let slice = buffer.slice(..);
+        let (sender, receiver) = crossbeam_channel::bounded(1);
+        slice.map_async(wgpu::MapMode::Read, move |result| {
+            // The receiver only goes away, if the device is lost.
+            let _ = sender.send(result);
+        });
+        self.device.poll(wgpu::Maintain::Wait);
+        receiver.recv().map_err(|_| ScreenshotError::DeviceLost)??;
+
+        let is_bgra = matches!(
+            format,
+            wgpu::TextureFormat::Bgra8Unorm
+                | wgpu::TextureFormat::Bgra8UnormSrgb
+        );
+
+        let mut pixels = Vec::with_capacity((width * height * 4) as usize);
+        for row in slice
+            .get_mapped_range()
+            .chunks(bytes_per_row as usize)
+            .take(height as usize)
+        {
+            for pixel in row[..(width * 4) as usize].chunks(4) {
+                let [r, g, b, a] = [pixel[0], pixel[1], pixel[2], pixel[3]];
+                if is_bgra {
+                    pixels.extend([b, g, r, a]);
+                } else {
+                    pixels.extend([r, g, b, a]);
+                }
+            }
+        }
+        buffer.unmap();
+
+        Ok(Screenshot {
+            width,
+            height,
+            pixels,
+        })
+    }
+
+    /// Draw the model and the extensions into the given color view
+    fn draw_scene(
+        &self,
+        encoder: &mut wgpu::CommandEncoder,
+        color_view: &wgpu::TextureView,
+        camera: &Camera,
+        config: &DrawConfig,
+        extensions: &mut [Box<dyn Extension>],
+        shape: Option<&ProcessedShape>,
+    ) {
+        let (clipping_plane, cap_color) = match config.clipping_plane {
+            Some(plane) => {
+                let [x, y, z] = plane.normal.components.map(Scalar::into_f32);
+                let cap_color = plane.cap_color.map_or([0.; 4], |color| {
+                    let [r, g, b, a] = color.map(|v| f32::from(v) / 255.);
+                    [r * a, g * a, b * a, a]
+                });
+
+                ([x, y, z, plane.distance.into_f32()], cap_color)
+            }
+            None => ([0.; 4], [0.; 4]),
+        };
+
+        let light_direction = {
+            let [x, y, z] = config
+                .lighting
+                .key_light_direction()
+                .components
+                .map(Scalar::into_f32);
+            [x, y, z, 0.]
+        };
+        let lighting = [
+            config.lighting.key_light_intensity as f32,
+            config.lighting.ambient_intensity as f32,
+            match config.lighting.shading {
+                Shading::Lit => 0.,
+                Shading::Matcap => 1.,
+            },
+            0.,
+        ];
+
+        // Without stereo, the model is drawn once, from the camera. With it,
+        // once for each eye, each time into its own viewport, or with its own
+        // pipelines.
+        let [width, height] =
+            [self.surface_config.width, self.surface_config.height]
+                .map(|size| size as f32);
+        let full_viewport = [0., 0., width, height];
+        let views = match config.stereo {
+            None => vec![(0., full_viewport, &self.pipelines)],
+            Some(stereo) => {
+                let [left, right] = stereo.eye_offsets(camera.focal_distance());
+
+                match stereo.mode {
+                    StereoMode::SideBySide => {
+                        let half = width / 2.;
+                        vec![
+                            (left, [0., 0., half, height], &self.pipelines),
+                            (right, [half, 0., half, height], &self.pipelines),
+                        ]
+                    }
+                    StereoMode::Anaglyph => {
+                        let [left_pipelines, right_pipelines] =
+                            &self.anaglyph_pipelines;
+                        vec![
+                            (left, full_viewport, left_pipelines),
+                            (right, full_viewport, right_pipelines),
+                        ]
+                    }
+                }
+            }
+        };
+
+        // Without multisampling, the model is drawn into the color view
+        // directly.
+        let (view, resolve_target) = match &self.frame_buffer {
+            Some(frame_buffer) => (frame_buffer, Some(color_view)),
+            None => (color_view, None),
+        };
+
+        for (i, (eye_offset, viewport, pipelines)) in views.iter().enumerate() {
+            let [x, y, viewport_width, viewport_height] = *viewport;
+            let is_last = i + 1 == views.len();
+
+            let uniforms = Uniforms {
+                transform: Transform::for_eye(
+                    camera,
+                    f64::from(viewport_width / viewport_height),
+                    *eye_offset,
+                ),
+                transform_normals: Transform::for_normals(camera),
+                clipping_plane,
+                cap_color,
+                light_direction,
+                lighting,
+            };
+            let (uniform_buffer, bind_group) = &self.uniforms[i];
+            self.queue.write_buffer(
+                uniform_buffer,
+                0,
+                bytemuck::cast_slice(&[uniforms]),
+            );
+
+            // Need this block here, as a render pass only takes effect once
+            // it's dropped.
+            {
+                let mut render_pass =
+                    encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+                        label: None,
+                        color_attachments: &[Some(
+                            wgpu::RenderPassColorAttachment {
+                                view,
+                                resolve_target: resolve_target
+                                    .filter(|_| is_last),
+                                ops: wgpu::Operations {
+                                    // The second eye is drawn over the first.
+                                    load: if i == 0 {
+                                        wgpu::LoadOp::Clear(wgpu::Color::WHITE)
+                                    } else {
+                                        wgpu::LoadOp::Load
+                                    },
+                                    // Not necessary, if MSAA is enabled,
+                                    // unless something draws into the frame
+                                    // buffer afterwards.
+                                    store: resolve_target.is_none()
+                                        || !is_last
+                                        || !extensions.is_empty(),
+                                },
+                            },
+                        )],
+                        depth_stencil_attachment: Some(
+                            wgpu::RenderPassDepthStencilAttachment {
+                                view: &self.depth_view,
+                                depth_ops: Some(wgpu::Operations {
+                                    load: wgpu::LoadOp::Clear(1.0),
+                                    store: true,
+                                }),
+                                stencil_ops: None,
+                            },
+                        ),
+                    });
+                render_pass.set_viewport(
+                    x,
+                    y,
+                    viewport_width,
+                    viewport_height,
+                    0.,
+                    1.,
+                );
+                render_pass.set_bind_group(0, bind_group, &[]);
+
+                let drawables = Drawables::new(&self.geometries, pipelines);
+
+                if config.draw_model {
+                    drawables.model.draw(&mut render_pass);
+                }
+
+                if self.features.contains(wgpu::Features::POLYGON_MODE_LINE) {
+                    if config.draw_mesh {
+                        drawables.mesh.draw(&mut render_pass);
+                    }
+                    if config.draw_debug {
+                        if config.debug_on_top {
+                            drawables.lines_on_top.draw(&mut render_pass);
+                        } else {
+                            drawables.lines.draw(&mut render_pass);
+                        }
+                    }
+                }
+            }
+        }
+
+        // Extensions draw as without stereo.
+        let aspect_ratio = f64::from(width / height);
+        let transform = Transform::for_vertices(camera, aspect_ratio);
+
+        for extension in extensions {
+            extension.render(&mut RenderContext {
+                device: &self.device,
+                queue: &self.queue,
+                encoder,
+                frame_buffer: view,
+                resolve_target,
+                color_view,
+                depth_view: &self.depth_view,
+                color_format: self.surface_config.format,
+                depth_format: DEPTH_FORMAT,
+                sample_count: self.sample_count,
+                size: ScreenSize {
+                    width: self.surface_config.width,
+                    height: self.surface_config.height,
+                },
+                transform: transform.0,
+                camera,
+                shape,
+            });
+        }
+    }
+
     fn create_frame_buffer(
         device: &wgpu::Device,
         surface_config: &wgpu::SurfaceConfiguration,
-    ) -> wgpu::TextureView {
+        sample_count: u32,
+    ) -> Option<wgpu::TextureView> {
+        if sample_count == 1 {
+            return None;
+        }
+
         let texture = device.create_texture(&wgpu::TextureDescriptor {
             label: None,
             size: wgpu::Extent3d {
@@ -321,17 +896,18 @@ impl Renderer {
                 depth_or_array_layers: 1,
             },
             mip_level_count: 1,
-            sample_count: SAMPLE_COUNT,
+            sample_count,
             dimension: wgpu::TextureDimension::D2,
             format: surface_config.format,
             usage: wgpu::TextureUsages::RENDER_ATTACHMENT,
         });
-        texture.create_view(&wgpu::TextureViewDescriptor::default())
+        Some(texture.create_view(&wgpu::TextureViewDescriptor::default()))
     }
 
     fn create_depth_buffer(
         device: &wgpu::Device,
         surface_config: &wgpu::SurfaceConfiguration,
+        sample_count: u32,
     ) -> wgpu::TextureView {
         let texture = device.create_texture(&wgpu::TextureDescriptor {
             label: None,
@@ -341,7 +917,7 @@ impl Renderer {
                 depth_or_array_layers: 1,
             },
             mip_level_count: 1,
-            sample_count: SAMPLE_COUNT,
+            sample_count,
             dimension: wgpu::TextureDimension::D2,
             format: DEPTH_FORMAT,
             usage: wgpu::TextureUsages::RENDER_ATTACHMENT,
@@ -349,11 +925,58 @@ impl Renderer {
 
         texture.create_view(&wgpu::TextureViewDescriptor::default())
     }
+}
 
-    /// Returns true if the renderer's adapter can draw lines
-    pub fn is_line_drawing_available(&self) -> bool {
-        self.features.contains(wgpu::Features::POLYGON_MODE_LINE)
+/// Find the highest number of samples per pixel, up to the requested one, that
+/// the adapter supports
+fn supported_sample_count(
+    adapter: &wgpu::Adapter,
+    color_format: wgpu::TextureFormat,
+    requested: u32,
+) -> u32 {
+    let supports_multisampling = adapter
+        .get_texture_format_features(color_format)
+        .flags
+        .contains(
+            wgpu::TextureFormatFeatureFlags::MULTISAMPLE
+                | wgpu::TextureFormatFeatureFlags::MULTISAMPLE_RESOLVE,
+        )
+        && adapter
+            .get_texture_format_features(DEPTH_FORMAT)
+            .flags
+            .contains(wgpu::TextureFormatFeatureFlags::MULTISAMPLE);
+
+    // Whatever the adapter, the version of `wgpu` we use only supports 4
+    // samples, besides 1.
+    let sample_count = if requested >= 4 && supports_multisampling {
+        4
+    } else {
+        1
+    };
+
+    if sample_count != requested {
+        warn!(
+            "Drawing with {requested} samples per pixel is not supported. \
+            Using {sample_count}."
+        );
+    }
+
+    sample_count
+}
+
+/// Run operations on the graphics device, unless it has been lost
+///
+/// The device is considered lost, once it has reported an uncaptured error, or
+/// once acquiring a frame from the surface has failed in a way that can't be
+/// recovered from by reconfiguring it.
+///
+/// Returns `None`, if the device has been lost.
+fn guard<T>(device_lost: &AtomicBool, f: impl FnOnce() -> T) -> Option<T> {
+    if device_lost.load(Ordering::Relaxed) {
+        return None;
     }
+
+    Some(f())
 }
 
 /// Error describing the set of render surface initialization errors
@@ -394,4 +1017,10 @@ pub enum DrawError {
     /// Text rasterisation error.
     #[error("Error drawing text: {0}")]
     Text(String),
+
+    /// The graphics device has been lost
+    ///
+    /// See [`Renderer::recover`].
+    #[error("Graphics device lost")]
+    DeviceLost,
 }
@@ -0,0 +1,65 @@
+use std::{fs::File, io, io::BufWriter, path::Path};
+
+use chrono::Local;
+use thiserror::Error;
+
+/// An image of what the renderer draws
+///
+/// See [`Renderer::screenshot`] and [`OffscreenRenderer`].
+///
+/// [`Renderer::screenshot`]: super::Renderer::screenshot
+/// [`OffscreenRenderer`]: super::OffscreenRenderer
+#[derive(Clone, Debug)]
+pub struct Screenshot {
+    /// The width of the image, in pixels
+    pub width: u32,
+
+    /// The height of the image, in pixels
+    pub height: u32,
+
+    /// The pixels, row by row from the top, as 8-bit RGBA
+    pub pixels: Vec<u8>,
+}
+
+impl Screenshot {
+    /// Compute a file name for a screenshot that is taken now
+    ///
+    /// The name includes the date and time, so screenshots don't overwrite
+    /// each other.
+    pub fn file_name() -> String {
+        Local::now()
+            .format("screenshot-%Y-%m-%d-%H%M%S.png")
+            .to_string()
+    }
+
+    /// Write the screenshot to a PNG file
+    pub fn save(&self, path: &Path) -> Result<(), ScreenshotError> {
+        let file = BufWriter::new(File::create(path)?);
+        let mut encoder = png::Encoder::new(file, self.width, self.height);
+        encoder.set_color(png::ColorType::Rgba);
+        encoder.set_depth(png::BitDepth::Eight);
+        encoder.write_header()?.write_image_data(&self.pixels)?;
+
+        Ok(())
+    }
+}
+
+/// Error taking or saving a [`Screenshot`]
+#[derive(Error, Debug)]
+pub enum ScreenshotError {
+    /// The graphics device has been lost
+    #[error("Graphics device lost")]
+    DeviceLost,
+
+    /// The image could not be read back from the graphics device
+    #[error("Error reading image from graphics device: {0}")]
+    Read(#[from] wgpu::BufferAsyncError),
+
+    /// I/O error while saving the image
+    #[error("I/O error: {0}")]
+    Io(#[from] io::Error),
+
+    /// Error encoding the image
+    #[error("Error encoding PNG: {0}")]
+    Png(#[from] png::EncodingError),
+}
@@ -1,6 +1,10 @@
 struct Uniforms {
     transform: mat4x4<f32>,
     transform_normals: mat4x4<f32>,
+    clipping_plane: vec4<f32>,
+    cap_color: vec4<f32>,
+    light_direction: vec4<f32>,
+    lighting: vec4<f32>,
 };
 
 @group(0) @binding(0)
@@ -16,42 +20,109 @@ struct VertexOutput {
     @builtin(position) position: vec4<f32>,
     @location(0) normal: vec3<f32>,
     @location(1) color: vec4<f32>,
+    @location(2) model_position: vec3<f32>,
 };
 
 struct FragmentOutput {
     @location(0) color: vec4<f32>,
 }
 
-@vertex
-fn vertex(in: VertexInput) -> VertexOutput {
+fn transform_vertex(in: VertexInput) -> VertexOutput {
     var out: VertexOutput;
     out.normal = (uniforms.transform_normals * vec4<f32>(in.normal, 0.0)).xyz;
     out.position = uniforms.transform * vec4<f32>(in.position, 1.0);
+    out.model_position = in.position;
     // We use premultiplied alpha blending.
     out.color = vec4<f32>(in.color.rgb * in.color.a, in.color.a);
 
     return out;
 }
 
-let pi: f32 = 3.14159265359;
+@vertex
+fn vertex(in: VertexInput) -> VertexOutput {
+    return transform_vertex(in);
+}
 
-@fragment
-fn frag_model(in: VertexOutput) -> FragmentOutput {
-    let light = vec3<f32>(0.0, 0.0, -1.0);
+// Debug lines often lie exactly on the surface of the model. Move them towards
+// the camera a tiny bit, so they are consistently drawn in front of that
+// surface, instead of fighting with it over the depth buffer.
+let lines_depth_offset: f32 = 0.0001;
+
+@vertex
+fn vertex_lines(in: VertexInput) -> VertexOutput {
+    var out = transform_vertex(in);
+    out.position.z = out.position.z - lines_depth_offset * out.position.w;
+
+    return out;
+}
+
+// Everything in front of the clipping plane is cut away. The normal of a
+// disabled plane is zero, so nothing is in front of it.
+fn is_clipped(position: vec3<f32>) -> bool {
+    let plane = uniforms.clipping_plane;
+    return dot(plane.xyz, position) > plane.w;
+}
 
-    let angle = acos(dot(light, -in.normal));
-    let f_angle = angle / (pi * 0.75);
+fn lit(color: vec4<f32>, normal: vec3<f32>) -> vec3<f32> {
+    let key_intensity = uniforms.lighting.x;
+    let ambient_intensity = uniforms.lighting.y;
 
-    let f_normal = max(1.0 - f_angle, 0.0);
+    let key = max(dot(normal, uniforms.light_direction.xyz), 0.0);
+
+    return color.rgb * (ambient_intensity + key_intensity * key);
+}
+
+// The shade only depends on the normal in camera space, like a sphere of clay
+// lit from the top left would be, and seen from the front.
+fn matcap(color: vec4<f32>, normal: vec3<f32>) -> vec3<f32> {
+    let key = max(dot(normal, normalize(vec3<f32>(-0.4, 0.6, 0.7))), 0.0);
+    let facing = max(normal.z, 0.0);
+    let highlight = pow(key, 24.0);
+
+    let diffuse = color.rgb * (0.15 + 0.5 * key + 0.35 * facing);
+
+    // The color is premultiplied, so white is the alpha in every channel.
+    return mix(diffuse, vec3<f32>(color.a), 0.3 * highlight);
+}
+
+@fragment
+fn frag_model(
+    in: VertexOutput,
+    @builtin(front_facing) front_facing: bool,
+) -> FragmentOutput {
+    if (is_clipped(in.model_position)) {
+        discard;
+    }
 
     var out: FragmentOutput;
-    out.color = vec4<f32>(in.color.rgb * f_normal, in.color.a);
+
+    // Looking through the cut, the back faces of the model's far side are
+    // visible. Drawing them in a flat color makes the model look solid.
+    if (!front_facing && uniforms.cap_color.a > 0.0) {
+        out.color = uniforms.cap_color;
+        return out;
+    }
+
+    let normal = normalize(in.normal);
+
+    var color: vec3<f32>;
+    if (uniforms.lighting.z > 0.5) {
+        color = matcap(in.color, normal);
+    } else {
+        color = lit(in.color, normal);
+    }
+
+    out.color = vec4<f32>(color, in.color.a);
 
     return out;
 }
 
 @fragment
 fn frag_mesh(in: VertexOutput) -> FragmentOutput {
+    if (is_clipped(in.model_position)) {
+        discard;
+    }
+
     var out: FragmentOutput;
     out.color = vec4<f32>(1.0 - in.color.rgb, in.color.a);
     return out;
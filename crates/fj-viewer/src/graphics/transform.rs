@@ -1,6 +1,7 @@
 use bytemuck::{Pod, Zeroable};
+use fj_math::Scalar;
 
-use crate::camera::Camera;
+use crate::camera::{Camera, Projection};
 
 #[derive(Clone, Copy, Pod, Zeroable)]
 #[repr(transparent)]
@@ -15,15 +16,58 @@ impl Transform {
     ///
     /// The returned transform is used for transforming vertices on the GPU.
     pub fn for_vertices(camera: &Camera, aspect_ratio: f64) -> Self {
-        let field_of_view_in_y = 2.
-            * ((camera.field_of_view_in_x() / 2.).tan() / aspect_ratio).atan();
-
-        let transform = camera.camera_to_model().project_to_array(
-            aspect_ratio,
-            field_of_view_in_y,
-            camera.near_plane(),
-            camera.far_plane(),
-        );
+        Self::for_eye(camera, aspect_ratio, 0.)
+    }
+
+    /// Compute transform used for vertices, as seen by one eye
+    ///
+    /// The eye is offset from the camera along the x-axis of camera space.
+    /// Instead of turning it towards the model's center, its view is shifted
+    /// sideways, so that both eyes' views line up at the model's center
+    /// without distorting them.
+    pub fn for_eye(
+        camera: &Camera,
+        aspect_ratio: f64,
+        eye_offset: f64,
+    ) -> Self {
+        let camera_to_eye =
+            fj_math::Transform::translation([-eye_offset, 0., 0.])
+                * camera.camera_to_model();
+
+        let mut transform = match camera.projection {
+            Projection::Perspective => {
+                let field_of_view_in_y = 2.
+                    * ((camera.field_of_view_in_x() / 2.).tan() / aspect_ratio)
+                        .atan();
+
+                camera_to_eye.project_to_array(
+                    aspect_ratio,
+                    field_of_view_in_y,
+                    camera.near_plane(),
+                    camera.far_plane(),
+                )
+            }
+            Projection::Orthographic => {
+                let width = camera.orthographic_width();
+
+                camera_to_eye.project_orthographic_to_array(
+                    width,
+                    width / aspect_ratio,
+                    camera.near_plane(),
+                    camera.far_plane(),
+                )
+            }
+        };
+
+        // Shift the view by adding a multiple of `w` to `x` in clip space,
+        // which moves everything by the same amount, after the perspective
+        // division. The matrix is column-major.
+        let half_width =
+            (camera.field_of_view_in_x() / 2.).tan() * camera.focal_distance();
+        let shift = Scalar::from(eye_offset / half_width);
+        for column in transform.chunks_mut(4) {
+            column[0] += shift * column[3];
+        }
 
         Self(transform.map(|scalar| scalar.into_f32()))
     }
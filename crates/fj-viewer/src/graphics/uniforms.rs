@@ -7,6 +7,28 @@ use super::transform::Transform;
 pub struct Uniforms {
     pub transform: Transform,
     pub transform_normals: Transform,
+
+    /// The normal and distance of the clipping plane
+    ///
+    /// A zero normal doesn't clip anything.
+    pub clipping_plane: [f32; 4],
+
+    /// The premultiplied color of the caps, fully transparent for no caps
+    pub cap_color: [f32; 4],
+
+    /// The direction towards the key light, in camera space
+    ///
+    /// The last component is unused.
+    pub light_direction: [f32; 4],
+
+    /// The intensity of the key light and the ambient light, and the shading
+    ///
+    /// The shading is 0 for [`Shading::Lit`], 1 for [`Shading::Matcap`]. The
+    /// last component is unused.
+    ///
+    /// [`Shading::Lit`]: super::draw_config::Shading::Lit
+    /// [`Shading::Matcap`]: super::draw_config::Shading::Matcap
+    pub lighting: [f32; 4],
 }
 
 impl Default for Uniforms {
@@ -14,6 +36,10 @@ impl Default for Uniforms {
         Self {
             transform: Transform::identity(),
             transform_normals: Transform::identity(),
+            clipping_plane: [0.; 4],
+            cap_color: [0.; 4],
+            light_direction: [0., 0., 1., 0.],
+            lighting: [1., 0., 0., 0.],
         }
     }
 }
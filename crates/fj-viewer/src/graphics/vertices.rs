@@ -77,14 +77,16 @@ impl From<&Mesh<fj_math::Point<3>>> for Vertices {
         let mut m = Mesh::new();
 
         for triangle in mesh.triangles() {
-            let [a, b, c] = triangle.inner.points();
-
-            let normal = (b - a).cross(&(c - a)).normalize();
+            // Triangles that approximate a curved surface share the normals
+            // of that surface at their points, which makes them look smooth.
+            let normals = triangle.vertex_normals();
             let color = triangle.color;
 
-            m.push_vertex((a, normal, color));
-            m.push_vertex((b, normal, color));
-            m.push_vertex((c, normal, color));
+            for (point, normal) in
+                triangle.inner.points().into_iter().zip(normals)
+            {
+                m.push_vertex((point, normal, color));
+            }
         }
 
         let vertices = m
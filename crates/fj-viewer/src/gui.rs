@@ -14,7 +14,11 @@
 //!
 //! <https://github.com/gfx-rs/wgpu/issues/1492>
 
-use std::path::PathBuf;
+use std::{
+    collections::{BTreeMap, HashMap},
+    path::PathBuf,
+    time::Duration,
+};
 
 #[cfg(not(target_arch = "wasm32"))]
 use std::env::current_dir;
@@ -22,9 +26,26 @@ use std::env::current_dir;
 #[cfg(not(target_arch = "wasm32"))]
 use rfd::FileDialog;
 
-use fj_math::{Aabb, Scalar};
+use fj_interop::{
+    analysis::Interference, debug::OperationTiming,
+    mass_properties::MassProperties, processed_shape::ProcessedPart,
+};
+use fj_math::{Aabb, Point, Scalar, Vector};
 
-use crate::{graphics::DrawConfig, StatusReport};
+use crate::{
+    camera::{Camera, Navigation, Projection},
+    gizmo::{GizmoHandle, GizmoView},
+    graphics::{
+        ClippingPlane, DrawConfig, Lighting, Screenshot, Shading, Stereo,
+        StereoMode,
+    },
+    measurement::{Measured, Measurement},
+    overlay::{Overlay, OverlayShape},
+    picking::Pick,
+    selection::Selection,
+    Analysis, CurvatureKind, EvaluationHistory, EvaluationStatistics,
+    NormalizedScreenPosition, PartVisibility, Reference, StatusReport,
+};
 
 /// The GUI
 pub struct Gui {
@@ -79,20 +100,48 @@ impl Gui {
         }
     }
 
+    /// Prepare the GUI for drawing with a new graphics device
+    ///
+    /// The textures of the old device are gone. `egui` only uploads textures
+    /// once, so a fresh context is needed to get them uploaded again. The
+    /// options are kept.
+    pub(crate) fn reinit(
+        &mut self,
+        device: &wgpu::Device,
+        texture_format: wgpu::TextureFormat,
+    ) {
+        let options = std::mem::take(&mut self.options);
+        *self = Self::new(device, texture_format);
+        self.options = options;
+    }
+
     /// Access the egui context
     pub fn context(&self) -> &egui::Context {
         &self.context
     }
 
+    #[allow(clippy::too_many_arguments)]
     pub(crate) fn update(
         &mut self,
         pixels_per_point: f32,
         egui_input: egui::RawInput,
         config: &mut DrawConfig,
+        camera: &mut Camera,
         aabb: &Aabb<3>,
+        visible_width: Option<Scalar>,
+        tolerance: Option<Scalar>,
+        mass_properties: Option<&MassProperties>,
+        parts: &[ProcessedPart],
+        part_visibility: &mut PartVisibility,
+        references: &mut [Reference],
+        mut gizmo: Option<GizmoView>,
+        measurement: &mut Measurement,
+        selection: &Selection,
+        overlay: &Overlay,
+        operation_timings: &[OperationTiming],
         line_drawing_available: bool,
         state: GuiState,
-    ) -> Option<PathBuf> {
+    ) -> GuiOutput {
         self.context.set_pixels_per_point(pixels_per_point);
         self.context.begin_frame(egui_input);
 
@@ -101,6 +150,15 @@ impl Gui {
             format!("Model bounding box size:\n{x:0.1} {y:0.1} {z:0.1}")
         };
 
+        let mut output = GuiOutput::default();
+
+        // The gizmo and the overlay are painted before the panel, so they
+        // end up below it.
+        if let Some(gizmo) = &gizmo {
+            show_gizmo(&self.context, gizmo);
+        }
+        show_overlay(&self.context, overlay);
+
         egui::SidePanel::left("fj-left-panel").show(&self.context, |ui| {
             ui.add_space(16.0);
 
@@ -117,12 +175,200 @@ impl Gui {
                     .on_disabled_hover_text(
                         "Rendering device does not have line rendering feature support"
                     );
+                ui.add_enabled(line_drawing_available && config.draw_debug, egui::Checkbox::new(&mut config.debug_on_top, "Render debug on top"))
+                    .on_hover_text_at_pointer("Toggle with 4")
+                    .on_disabled_hover_text(
+                        "Only available when rendering debug information"
+                    );
+                ui.checkbox(&mut config.draw_scale_bar, "Render scale bar")
+                    .on_hover_text_at_pointer("Toggle with 5");
+                ui.checkbox(&mut config.draw_gizmo, "Render gizmo for selected part")
+                    .on_hover_text_at_pointer("Toggle with 6");
+                ui.horizontal(|ui| {
+                    ui.radio_value(&mut camera.projection, Projection::Perspective, "Perspective");
+                    ui.radio_value(&mut camera.projection, Projection::Orthographic, "Orthographic");
+                })
+                .response
+                .on_hover_text_at_pointer("Toggle with 7");
+                ui.horizontal(|ui| {
+                    ui.radio_value(&mut camera.navigation, Navigation::Orbit, "Orbit");
+                    ui.radio_value(&mut camera.navigation, Navigation::Fly, "Fly");
+                })
+                .response
+                .on_hover_text_at_pointer(
+                    "Toggle with 8. Fly with W, A, S, D, and Q, E for down and up",
+                );
+                if camera.navigation == Navigation::Fly {
+                    ui.add(
+                        egui::Slider::new(&mut camera.fly_speed, 0.01..=2.0)
+                            .logarithmic(true)
+                            .text("Fly speed"),
+                    )
+                    .on_hover_text_at_pointer(
+                        "Fraction of the model's size to move per second",
+                    );
+                }
                 ui.add_space(16.0);
                 ui.strong(bounding_box_size);
             });
 
             ui.add_space(16.0);
 
+            ui.group(|ui| {
+                show_lighting(ui, &mut config.lighting);
+            });
+
+            ui.add_space(16.0);
+
+            ui.group(|ui| {
+                show_stereo(ui, &mut config.stereo);
+            });
+
+            ui.add_space(16.0);
+
+            if let Some(mass_properties) = mass_properties {
+                ui.group(|ui| {
+                    show_mass_properties(ui, mass_properties);
+                });
+
+                ui.add_space(16.0);
+
+                ui.group(|ui| {
+                    show_clipping_plane(ui, &mut config.clipping_plane, aabb);
+                });
+
+                ui.add_space(16.0);
+            }
+
+            // A shape that consists of a single part doesn't need controls
+            // for showing and hiding it.
+            if parts.len() > 1 {
+                ui.group(|ui| {
+                    show_parts(ui, parts, part_visibility);
+                });
+
+                ui.add_space(16.0);
+            }
+
+            if !parts.is_empty() {
+                if ui
+                    .button("Export bill of materials")
+                    .on_hover_text_at_pointer(
+                        "Save the parts and parameters as CSV or JSON",
+                    )
+                    .clicked()
+                {
+                    output.bill_of_materials_path = show_save_bom_dialog();
+                }
+
+                if ui
+                    .button("Save screenshot")
+                    .on_hover_text_at_pointer(
+                        "Save the model as it is shown, without the GUI, as a \
+                        PNG image (F12)",
+                    )
+                    .clicked()
+                {
+                    output.screenshot_path = show_save_screenshot_dialog();
+                }
+
+                ui.add_space(16.0);
+            }
+
+            if let Some(gizmo) = &mut gizmo {
+                ui.group(|ui| {
+                    show_gizmo_transform(ui, gizmo);
+                });
+
+                ui.add_space(16.0);
+            }
+
+            if !parts.is_empty() {
+                ui.group(|ui| {
+                    show_measurement(ui, measurement);
+                });
+
+                ui.add_space(16.0);
+
+                ui.group(|ui| {
+                    show_selection(ui, selection);
+                });
+
+                ui.add_space(16.0);
+            }
+
+            if !references.is_empty() {
+                ui.group(|ui| {
+                    show_references(ui, references);
+                });
+
+                ui.add_space(16.0);
+            }
+
+            if state.model_available {
+                ui.group(|ui| {
+                    show_parameters(
+                        ui,
+                        &mut self.options,
+                        state.parameters,
+                        &mut output,
+                    );
+                });
+
+                ui.add_space(16.0);
+            }
+
+            if let Some(tolerance) = tolerance {
+                ui.group(|ui| {
+                    show_tolerance(
+                        ui,
+                        &mut self.options,
+                        tolerance,
+                        aabb,
+                        &mut output,
+                    );
+                });
+
+                ui.add_space(16.0);
+            }
+
+            // Analyses need a shape to analyze.
+            if mass_properties.is_some() {
+                ui.group(|ui| {
+                    show_analysis(ui, &mut self.options, &mut output);
+                });
+
+                ui.add_space(16.0);
+            }
+
+            if !operation_timings.is_empty() {
+                ui.group(|ui| {
+                    ui.strong("Operation timings");
+                    for (i, timing) in operation_timings.iter().enumerate() {
+                        show_operation_timing(ui, timing, &i.to_string());
+                    }
+                });
+
+                ui.add_space(16.0);
+            }
+
+            if !state.recent_models.is_empty() {
+                ui.group(|ui| {
+                    ui.strong("Recent models");
+                    show_recent_models(ui, state.recent_models, &mut output);
+                });
+
+                ui.add_space(16.0);
+            }
+
+            if !state.evaluation_history.is_empty() {
+                ui.group(|ui| {
+                    show_evaluation_history(ui, state.evaluation_history);
+                });
+
+                ui.add_space(16.0);
+            }
+
             {
                 ui.group(|ui| {
                     ui.checkbox(
@@ -254,7 +500,11 @@ impl Gui {
             })
         });
 
-        let mut new_model_path = None;
+        if config.draw_scale_bar {
+            if let Some(visible_width) = visible_width {
+                show_scale_bar(&self.context, visible_width);
+            }
+        }
 
         if !state.model_available {
             egui::Area::new("ask-model")
@@ -269,13 +519,38 @@ impl Gui {
                             .button(egui::RichText::new("Pick a model"))
                             .clicked()
                         {
-                            new_model_path = show_file_dialog();
+                            output.new_model_path = show_file_dialog();
+                        }
+
+                        if !state.recent_models.is_empty() {
+                            ui.add_space(8.0);
+                            show_recent_models(
+                                ui,
+                                state.recent_models,
+                                &mut output,
+                            );
+                        }
+
+                        if !state.sample_models.is_empty() {
+                            ui.add_space(8.0);
+                            ui.label(
+                                egui::RichText::new(
+                                    "New to Fornjot? Start with an example:",
+                                )
+                                .color(egui::Color32::BLACK)
+                                .background_color(egui::Color32::WHITE),
+                            );
+                            for &name in state.sample_models {
+                                if ui.button(name).clicked() {
+                                    output.sample_model = Some(name.to_owned());
+                                }
+                            }
                         }
                     })
                 });
         }
 
-        new_model_path
+        output
     }
 
     pub(crate) fn draw(
@@ -314,6 +589,938 @@ impl Gui {
     }
 }
 
+fn show_operation_timing(
+    ui: &mut egui::Ui,
+    timing: &OperationTiming,
+    id: &str,
+) {
+    let text = format!(
+        "{}: {:.1?} (self: {:.1?})",
+        timing.name,
+        timing.duration,
+        timing.self_duration()
+    );
+
+    if timing.children.is_empty() {
+        ui.label(text);
+        return;
+    }
+
+    egui::CollapsingHeader::new(text)
+        .id_source(format!("fj-operation-timing-{id}"))
+        .default_open(true)
+        .show(ui, |ui| {
+            for (i, child) in timing.children.iter().enumerate() {
+                show_operation_timing(ui, child, &format!("{id}-{i}"));
+            }
+        });
+}
+
+fn show_parts(
+    ui: &mut egui::Ui,
+    parts: &[ProcessedPart],
+    part_visibility: &mut PartVisibility,
+) {
+    ui.strong("Parts");
+
+    for part in parts {
+        ui.horizontal(|ui| {
+            let mut visible = part_visibility.is_visible(&part.name);
+            if ui.checkbox(&mut visible, "").changed() {
+                part_visibility.set_visible(&part.name, visible);
+            }
+
+            let is_selected =
+                part_visibility.selected() == Some(part.name.as_str());
+            if ui.selectable_label(is_selected, &part.name).clicked() {
+                part_visibility.select(if is_selected {
+                    None
+                } else {
+                    Some(&part.name)
+                });
+            }
+        });
+    }
+
+    ui.horizontal(|ui| {
+        if ui
+            .add_enabled(
+                part_visibility.selected().is_some(),
+                egui::Button::new("Isolate selection"),
+            )
+            .on_hover_text_at_pointer("Toggle with I")
+            .on_disabled_hover_text("Select a part to isolate it")
+            .clicked()
+        {
+            part_visibility.toggle_isolation(parts);
+        }
+
+        if ui.button("Show all parts").clicked() {
+            part_visibility.show_all();
+        }
+    });
+}
+
+fn show_gizmo_transform(ui: &mut egui::Ui, view: &mut GizmoView) {
+    ui.strong(format!(
+        "Transform of {}",
+        view.gizmo.part().unwrap_or_default()
+    ));
+
+    let Some(code) = view.gizmo.to_code(&view.center) else {
+        ui.label("Drag the gizmo to move the part.");
+        return;
+    };
+
+    let [x, y, z] = view.gizmo.offset().components.map(Scalar::into_f64);
+    ui.label(format!("Offset: {x:.3} {y:.3} {z:.3}"));
+    if let Some((axis, angle)) = view.gizmo.rotation() {
+        let [x, y, z] = axis.components.map(Scalar::into_f64);
+        ui.label(format!(
+            "Rotation: {:.2}° about {x:.3} {y:.3} {z:.3}",
+            angle.into_f64().to_degrees()
+        ));
+    }
+
+    ui.add_space(8.0);
+    ui.label("Append to the part in the model's code:");
+    ui.label(egui::RichText::new(&code).monospace());
+
+    ui.horizontal(|ui| {
+        if ui.button("Copy code").clicked() {
+            ui.output().copied_text = code;
+        }
+        if ui
+            .button("Reset")
+            .on_hover_text_at_pointer("Move the part back to where it was")
+            .clicked()
+        {
+            view.gizmo.reset();
+        }
+    });
+}
+
+fn show_measurement(ui: &mut egui::Ui, measurement: &mut Measurement) {
+    ui.checkbox(&mut measurement.active, "Measure")
+        .on_hover_text_at_pointer("Toggle with M");
+    if !measurement.active {
+        return;
+    }
+
+    match &measurement.measured {
+        Measured::Nothing => {
+            ui.label("Click two points to measure the distance between them, or an edge to measure its length.");
+        }
+        Measured::Point(point) => {
+            let [x, y, z] = point.coords.components.map(Scalar::into_f64);
+            ui.label(format!("From: {x:.3} {y:.3} {z:.3}"));
+            ui.label("Click a second point.");
+        }
+        Measured::Distance([a, b]) => {
+            let [a, b] = [*a, *b];
+            let [x, y, z] = (b - a).components.map(Scalar::into_f64);
+            let distance = (b - a).magnitude().into_f64();
+            ui.label(format!("Distance: {distance:.3}"));
+            ui.label(format!("Along axes: {x:.3} {y:.3} {z:.3}"));
+        }
+        Measured::Edge(_) => {
+            let length = measurement.measured.length().unwrap_or_default();
+            ui.label(format!("Edge length: {:.3}", length.into_f64()));
+        }
+    }
+
+    if measurement.measured != Measured::Nothing && ui.button("Clear").clicked()
+    {
+        measurement.clear();
+    }
+}
+
+fn show_selection(ui: &mut egui::Ui, selection: &Selection) {
+    ui.strong("Selection");
+
+    let Some(selected) = &selection.selected else {
+        ui.label("Click a face, edge, or vertex to select it.");
+        return;
+    };
+
+    let format_point = |point: &Point<3>| {
+        let [x, y, z] = point.coords.components.map(Scalar::into_f64);
+        format!("{x:.3} {y:.3} {z:.3}")
+    };
+
+    let object = match selected {
+        Pick::Point {
+            point,
+            face,
+            attributes,
+        } => {
+            match face {
+                Some(_) => ui.label("Face"),
+                None => ui.label(format!("Point: {}", format_point(point))),
+            };
+            if let Some(name) = &attributes.name {
+                ui.label(format!("Name: {name}"));
+            }
+            if let Some(material) = &attributes.material {
+                ui.label(format!("Material: {material}"));
+            }
+            face
+        }
+        Pick::Edge { points, edge } => {
+            let length = Measured::Edge(points.clone()).length();
+            ui.label(format!(
+                "Edge, length: {:.3}",
+                length.unwrap_or_default().into_f64()
+            ));
+            edge
+        }
+        Pick::Vertex { point, vertex } => {
+            ui.label(format!("Vertex: {}", format_point(point)));
+            vertex
+        }
+    };
+
+    // Meshes that weren't triangulated in this session, like those of cached
+    // models or reference bodies, don't know their objects.
+    match object {
+        Some(object) => ui.label(format!("Kernel {object:?}")),
+        None => ui.label("Kernel object unknown"),
+    };
+}
+
+fn show_references(ui: &mut egui::Ui, references: &mut [Reference]) {
+    ui.strong("Reference bodies");
+
+    for reference in references {
+        ui.checkbox(&mut reference.visible, &reference.name);
+
+        ui.indent(&reference.name, |ui| {
+            for (part, interference) in &reference.interference {
+                let (text, color) = match interference {
+                    Interference::Clear => ("clear", egui::Color32::GREEN),
+                    Interference::Close => ("close", egui::Color32::YELLOW),
+                    Interference::Interfering => {
+                        ("interfering", egui::Color32::RED)
+                    }
+                };
+
+                ui.horizontal(|ui| {
+                    ui.label(format!("{part}:"));
+                    ui.colored_label(color, text);
+                })
+                .response
+                .on_hover_text_at_pointer(
+                    "\"Close\" means that the bounding boxes overlap, but the \
+                        meshes don't",
+                );
+            }
+        });
+    }
+}
+
+fn show_analysis(
+    ui: &mut egui::Ui,
+    options: &mut Options,
+    output: &mut GuiOutput,
+) {
+    let pull_directions = axis_directions();
+
+    let previous = options.analysis;
+
+    ui.strong("Analysis");
+    ui.radio_value(&mut options.analysis, Analysis::None, "None");
+    let is_draft = matches!(options.analysis, Analysis::Draft { .. });
+    if ui.radio(is_draft, "Draft angle").clicked() && !is_draft {
+        options.analysis = Analysis::Draft {
+            pull_direction: pull_directions[4].1,
+        };
+    }
+    ui.radio_value(
+        &mut options.analysis,
+        Analysis::WallThickness,
+        "Wall thickness",
+    );
+    let is_printability =
+        matches!(options.analysis, Analysis::Printability { .. });
+    if ui.radio(is_printability, "Printability").clicked() && !is_printability {
+        // Typical values for printing with a 0.4 mm nozzle
+        options.analysis = Analysis::Printability {
+            build_direction: pull_directions[4].1,
+            max_overhang: Scalar::from(45_f64.to_radians()),
+            min_thickness: Scalar::from(0.8),
+        };
+    }
+    let is_curvature = matches!(options.analysis, Analysis::Curvature { .. });
+    if ui.radio(is_curvature, "Curvature").clicked() && !is_curvature {
+        options.analysis = Analysis::Curvature {
+            kind: CurvatureKind::Mean,
+        };
+    }
+
+    match &mut options.analysis {
+        Analysis::None => {}
+        Analysis::Draft { pull_direction } => {
+            let selected = pull_directions
+                .iter()
+                .find(|(_, direction)| direction == pull_direction)
+                .map_or("", |(name, _)| name);
+
+            egui::ComboBox::from_label("Pull direction")
+                .selected_text(selected)
+                .show_ui(ui, |ui| {
+                    for (name, direction) in pull_directions {
+                        ui.selectable_value(pull_direction, direction, name);
+                    }
+                });
+            ui.label("Green: drafted\nYellow: no draft\nRed: undercut");
+        }
+        Analysis::WallThickness => {
+            ui.label("Red: thinnest\nGreen: thickest");
+        }
+        Analysis::Printability {
+            build_direction,
+            max_overhang,
+            min_thickness,
+        } => {
+            let selected = pull_directions
+                .iter()
+                .find(|(_, direction)| direction == build_direction)
+                .map_or("", |(name, _)| name);
+
+            egui::ComboBox::from_label("Build direction")
+                .selected_text(selected)
+                .show_ui(ui, |ui| {
+                    for (name, direction) in pull_directions {
+                        ui.selectable_value(build_direction, direction, name);
+                    }
+                });
+
+            // Converting back and forth isn't exact, so only write back
+            // values that the user changed. Otherwise the analysis would run
+            // again on every frame.
+            let mut degrees = max_overhang.into_f64().to_degrees();
+            let slider = egui::Slider::new(&mut degrees, 0.0..=90.0)
+                .text("Max. overhang")
+                .suffix("°");
+            if ui.add(slider).changed() {
+                *max_overhang = Scalar::from(degrees.to_radians());
+            }
+
+            let mut thickness = min_thickness.into_f64();
+            let drag_value = egui::DragValue::new(&mut thickness)
+                .prefix("Min. thickness: ")
+                .clamp_range(0.0..=f64::MAX)
+                .speed(0.01);
+            if ui.add(drag_value).changed() {
+                *min_thickness = Scalar::from(thickness);
+            }
+
+            ui.label("Green: printable\nYellow: too thin\nRed: overhang");
+        }
+        Analysis::Curvature { kind } => {
+            ui.horizontal(|ui| {
+                ui.radio_value(kind, CurvatureKind::Mean, "Mean");
+                ui.radio_value(kind, CurvatureKind::Gaussian, "Gaussian");
+            });
+
+            match kind {
+                CurvatureKind::Mean => {
+                    ui.label("Green: convex\nYellow: flat\nRed: concave");
+                }
+                CurvatureKind::Gaussian => {
+                    ui.label(
+                        "Green: curved like a sphere\nYellow: flat or \
+                        cylindrical\nRed: curved like a saddle",
+                    );
+                }
+            }
+        }
+    }
+
+    if options.analysis != previous {
+        output.analysis = Some(options.analysis);
+    }
+}
+
+fn show_clipping_plane(
+    ui: &mut egui::Ui,
+    clipping_plane: &mut Option<ClippingPlane>,
+    aabb: &Aabb<3>,
+) {
+    let directions = axis_directions();
+
+    // The plane can be moved anywhere from one end of the model to the other.
+    let range = |normal: &Vector<3>| {
+        let distances = aabb.vertices().map(|vertex| vertex.coords.dot(normal));
+        let min = distances.into_iter().reduce(Scalar::min);
+        let max = distances.into_iter().reduce(Scalar::max);
+        [min, max].map(|distance| distance.unwrap_or_default().into_f64())
+    };
+
+    ui.strong("Section");
+
+    let mut enabled = clipping_plane.is_some();
+    if ui.checkbox(&mut enabled, "Cut model open").changed() {
+        *clipping_plane = enabled.then(|| {
+            let normal = directions[4].1;
+            ClippingPlane {
+                normal,
+                distance: aabb.center().coords.dot(&normal),
+                cap_color: Some(ClippingPlane::DEFAULT_CAP_COLOR),
+            }
+        });
+    }
+
+    let Some(plane) = clipping_plane else {
+        return;
+    };
+
+    let previous_normal = plane.normal;
+    let selected = directions
+        .iter()
+        .find(|(_, direction)| *direction == plane.normal)
+        .map_or("", |(name, _)| name);
+    egui::ComboBox::from_label("Cut away")
+        .selected_text(selected)
+        .show_ui(ui, |ui| {
+            for (name, direction) in directions {
+                ui.selectable_value(&mut plane.normal, direction, name);
+            }
+        });
+    if plane.normal != previous_normal {
+        plane.distance = aabb.center().coords.dot(&plane.normal);
+    }
+
+    let [min, max] = range(&plane.normal);
+    let mut distance = plane.distance.into_f64();
+    let slider = egui::Slider::new(&mut distance, min..=max).text("Position");
+    if ui.add(slider).changed() {
+        plane.distance = Scalar::from(distance);
+    }
+
+    let mut capped = plane.cap_color.is_some();
+    ui.horizontal(|ui| {
+        if ui
+            .checkbox(&mut capped, "Cap cut surfaces")
+            .on_hover_text_at_pointer(
+                "Show the model as solid where it is cut. Requires a closed \
+                model.",
+            )
+            .changed()
+        {
+            plane.cap_color =
+                capped.then_some(ClippingPlane::DEFAULT_CAP_COLOR);
+        }
+        if let Some(cap_color) = &mut plane.cap_color {
+            ui.color_edit_button_srgba_unmultiplied(cap_color);
+        }
+    });
+}
+
+fn show_lighting(ui: &mut egui::Ui, lighting: &mut Lighting) {
+    ui.strong("Lighting");
+
+    ui.horizontal(|ui| {
+        ui.radio_value(&mut lighting.shading, Shading::Lit, "Lights");
+        ui.radio_value(&mut lighting.shading, Shading::Matcap, "Matcap")
+            .on_hover_text_at_pointer(
+                "Shade surfaces by their direction relative to the camera, \
+                which makes concave details easier to read",
+            );
+    });
+
+    if lighting.shading != Shading::Lit {
+        return;
+    }
+
+    ui.add(
+        egui::Slider::new(&mut lighting.key_light_azimuth, -90.0..=90.0)
+            .suffix("°")
+            .text("Light from left/right"),
+    );
+    ui.add(
+        egui::Slider::new(&mut lighting.key_light_elevation, -90.0..=90.0)
+            .suffix("°")
+            .text("Light from below/above"),
+    );
+    ui.add(
+        egui::Slider::new(&mut lighting.key_light_intensity, 0.0..=1.0)
+            .text("Key light"),
+    );
+    ui.add(
+        egui::Slider::new(&mut lighting.ambient_intensity, 0.0..=1.0)
+            .text("Ambient light"),
+    );
+    if ui.button("Reset").clicked() {
+        *lighting = Lighting::default();
+    }
+}
+
+fn show_stereo(ui: &mut egui::Ui, stereo: &mut Option<Stereo>) {
+    let mut enabled = stereo.is_some();
+    if ui
+        .checkbox(&mut enabled, "Stereoscopic view")
+        .on_hover_text_at_pointer(
+            "Draw the model for each eye, to view it in 3D. Measuring and \
+            selecting still work as they would without it.",
+        )
+        .changed()
+    {
+        *stereo = enabled.then(|| Stereo {
+            mode: StereoMode::default(),
+            eye_separation: Stereo::DEFAULT_EYE_SEPARATION,
+        });
+    }
+
+    let Some(stereo) = stereo else {
+        return;
+    };
+
+    ui.horizontal(|ui| {
+        ui.radio_value(
+            &mut stereo.mode,
+            StereoMode::SideBySide,
+            "Side by side",
+        );
+        ui.radio_value(&mut stereo.mode, StereoMode::Anaglyph, "Red-cyan");
+    });
+    ui.add(
+        egui::Slider::new(&mut stereo.eye_separation, 0.0..=0.2)
+            .text("Eye separation"),
+    )
+    .on_hover_text_at_pointer(
+        "Fraction of the distance to the model. More separation means more \
+        depth.",
+    );
+}
+
+/// The directions along the coordinate axes, and their names
+fn axis_directions() -> [(&'static str, Vector<3>); 6] {
+    [
+        ("+X", [1., 0., 0.]),
+        ("-X", [-1., 0., 0.]),
+        ("+Y", [0., 1., 0.]),
+        ("-Y", [0., -1., 0.]),
+        ("+Z", [0., 0., 1.]),
+        ("-Z", [0., 0., -1.]),
+    ]
+    .map(|(name, direction)| (name, Vector::from(direction)))
+}
+
+fn show_recent_models(
+    ui: &mut egui::Ui,
+    recent_models: &[PathBuf],
+    output: &mut GuiOutput,
+) {
+    for path in recent_models {
+        // The name of the model's directory is usually enough to recognize it.
+        // The full path is available on hover.
+        let name = path.file_name().unwrap_or(path.as_os_str());
+
+        if ui
+            .button(name.to_string_lossy())
+            .on_hover_text_at_pointer(path.display().to_string())
+            .clicked()
+        {
+            output.new_model_path = Some(path.clone());
+        }
+    }
+}
+
+fn show_mass_properties(ui: &mut egui::Ui, properties: &MassProperties) {
+    ui.strong("Mass properties");
+    ui.label(format!(
+        "Surface area: {:.3}",
+        properties.surface_area.into_f64()
+    ));
+
+    // Shapes that don't enclose a volume, like sketches, have no meaningful
+    // center of mass or inertia.
+    if properties.volume == Scalar::ZERO {
+        return;
+    }
+
+    ui.label(format!("Volume: {:.3}", properties.volume.into_f64()));
+
+    let [x, y, z] = properties
+        .center_of_mass
+        .coords
+        .components
+        .map(Scalar::into_f64);
+    ui.label(format!("Center of mass:\n{x:0.3} {y:0.3} {z:0.3}"));
+
+    let [ixx, iyy, izz] =
+        [0, 1, 2].map(|i| properties.inertia[i][i].into_f64());
+    ui.label(format!(
+        "Moments of inertia:\n{ixx:0.3} {iyy:0.3} {izz:0.3}"
+    ))
+    .on_hover_text_at_pointer(
+        "Around the axes through the center of mass, assuming a density \
+            of one",
+    );
+}
+
+fn show_tolerance(
+    ui: &mut egui::Ui,
+    options: &mut Options,
+    tolerance: Scalar,
+    aabb: &Aabb<3>,
+    output: &mut GuiOutput,
+) {
+    let tolerance = tolerance.into_f64();
+
+    // Follow the tolerance of the current shape, unless the user is in the
+    // middle of choosing a different one.
+    if options.shape_tolerance != Some(tolerance) {
+        options.shape_tolerance = Some(tolerance);
+        options.tolerance = tolerance;
+    }
+
+    // The tolerance can sensibly range from very fine, to coarse enough to
+    // reduce the whole model to a few triangles.
+    let max = aabb.size().magnitude().into_f64().max(tolerance);
+    let min = (max / 1e5).min(tolerance);
+
+    ui.strong("Triangulation tolerance");
+    ui.label(format!("Current: {tolerance:.2e}"));
+    ui.add(
+        egui::Slider::new(&mut options.tolerance, min..=max)
+            .logarithmic(true)
+            .custom_formatter(|value, _| format!("{value:.2e}")),
+    );
+
+    let changed = options.tolerance != tolerance;
+    if ui
+        .add_enabled(changed, egui::Button::new("Re-tessellate"))
+        .on_hover_text_at_pointer(
+            "Triangulate the model again, using the selected tolerance",
+        )
+        .clicked()
+    {
+        output.tolerance = Some(Scalar::from_f64(options.tolerance));
+    }
+}
+
+fn show_parameters(
+    ui: &mut egui::Ui,
+    options: &mut Options,
+    parameters: &HashMap<String, String>,
+    output: &mut GuiOutput,
+) {
+    // Follow the parameters of the current model, unless the user is in the
+    // middle of editing them.
+    let parameters = parameters
+        .iter()
+        .map(|(key, value)| (key.clone(), value.clone()))
+        .collect::<BTreeMap<_, _>>();
+    if options.model_parameters != parameters {
+        options.model_parameters = parameters.clone();
+        options.parameters = parameters.clone();
+    }
+
+    ui.strong("Parameters").on_hover_text_at_pointer(
+        "Values that start with `=` are computed, and can refer to other \
+            parameters, like `=25.4*3` or `=length/2`",
+    );
+
+    let mut removed = None;
+    egui::Grid::new("fj-parameters").show(ui, |ui| {
+        for (key, value) in &mut options.parameters {
+            ui.label(key);
+            ui.text_edit_singleline(value);
+            if ui
+                .small_button("✖")
+                .on_hover_text_at_pointer("Remove the parameter")
+                .clicked()
+            {
+                removed = Some(key.clone());
+            }
+            ui.end_row();
+        }
+    });
+    if let Some(key) = removed {
+        options.parameters.remove(&key);
+    }
+
+    ui.horizontal(|ui| {
+        ui.text_edit_singleline(&mut options.new_parameter)
+            .on_hover_text_at_pointer("Name of a parameter to add");
+
+        let name = options.new_parameter.trim();
+        let addable =
+            !name.is_empty() && !options.parameters.contains_key(name);
+        if ui.add_enabled(addable, egui::Button::new("Add")).clicked() {
+            options.parameters.insert(name.to_owned(), String::new());
+            options.new_parameter.clear();
+        }
+    });
+
+    let changed = options.parameters != parameters;
+    if ui
+        .add_enabled(changed, egui::Button::new("Apply"))
+        .on_hover_text_at_pointer(
+            "Evaluate the model again, using the edited parameters",
+        )
+        .clicked()
+    {
+        output.parameters = Some(
+            options
+                .parameters
+                .iter()
+                .map(|(key, value)| (key.clone(), value.clone()))
+                .collect(),
+        );
+    }
+}
+
+fn show_evaluation_history(ui: &mut egui::Ui, history: &EvaluationHistory) {
+    ui.strong("Evaluation history");
+
+    if let Some(latest) = history.latest() {
+        let previous = history.previous();
+
+        ui.label(format!(
+            "Evaluation: {:.1?}{}",
+            latest.evaluation_time,
+            duration_trend(
+                latest.evaluation_time,
+                previous.map(|s| s.evaluation_time)
+            ),
+        ));
+        ui.label(format!(
+            "Processing: {:.1?}{}",
+            latest.processing_time,
+            duration_trend(
+                latest.processing_time,
+                previous.map(|s| s.processing_time)
+            ),
+        ));
+        ui.label(format!(
+            "Faces: {}{}",
+            latest.num_faces,
+            count_trend(latest.num_faces, previous.map(|s| s.num_faces)),
+        ));
+        ui.label(format!(
+            "Half-edges: {}{}",
+            latest.num_half_edges,
+            count_trend(
+                latest.num_half_edges,
+                previous.map(|s| s.num_half_edges)
+            ),
+        ));
+        ui.label(format!(
+            "Triangles: {}{}",
+            latest.num_triangles,
+            count_trend(
+                latest.num_triangles,
+                previous.map(|s| s.num_triangles)
+            ),
+        ));
+    }
+
+    let line = |name: &str, get: fn(&EvaluationStatistics) -> Duration| {
+        let points = history
+            .iter()
+            .enumerate()
+            .map(|(i, s)| [i as f64, get(s).as_secs_f64() * 1000.])
+            .collect::<egui::plot::PlotPoints>();
+        egui::plot::Line::new(points).name(name)
+    };
+
+    egui::plot::Plot::new("fj-evaluation-history")
+        .height(120.)
+        .include_y(0.)
+        .allow_drag(false)
+        .allow_zoom(false)
+        .allow_scroll(false)
+        .allow_boxed_zoom(false)
+        .show_x(false)
+        .legend(egui::plot::Legend::default())
+        .show(ui, |plot_ui| {
+            plot_ui.line(line("Evaluation (ms)", |s| s.evaluation_time));
+            plot_ui.line(line("Processing (ms)", |s| s.processing_time));
+        });
+}
+
+/// Format the relative change from the previous duration, if there is one
+fn duration_trend(latest: Duration, previous: Option<Duration>) -> String {
+    match previous {
+        Some(previous) if !previous.is_zero() => {
+            let change = latest.as_secs_f64() / previous.as_secs_f64() - 1.;
+            format!(" ({:+.1}%)", change * 100.)
+        }
+        _ => String::new(),
+    }
+}
+
+/// Format the absolute change from the previous count, if there is one
+fn count_trend(latest: usize, previous: Option<usize>) -> String {
+    match previous {
+        Some(previous) => format!(" ({:+})", latest as i64 - previous as i64),
+        None => String::new(),
+    }
+}
+
+/// Show a scale bar in the bottom-right corner of the screen
+///
+/// The width of the visible area is measured at the depth that the scale bar
+/// refers to. The length of the bar is picked, such that it is a round number
+/// that fits into a fixed amount of space on the screen.
+fn show_scale_bar(context: &egui::Context, visible_width: Scalar) {
+    const MAX_WIDTH: f64 = 150.;
+
+    let screen_width = f64::from(context.input().screen_rect().width());
+    if screen_width <= 0. {
+        return;
+    }
+    let units_per_point = visible_width.into_f64() / screen_width;
+
+    let (length, decimals) = scale_bar_length(units_per_point * MAX_WIDTH);
+    let width = (length / units_per_point) as f32;
+
+    egui::Area::new("fj-scale-bar")
+        .anchor(egui::Align2::RIGHT_BOTTOM, [-16., -16.])
+        .interactable(false)
+        .show(context, |ui| {
+            ui.label(
+                egui::RichText::new(format!("{length:.decimals$} mm"))
+                    .monospace()
+                    .color(egui::Color32::BLACK)
+                    .background_color(egui::Color32::WHITE),
+            );
+
+            let (rect, _) = ui.allocate_exact_size(
+                egui::vec2(width, 8.),
+                egui::Sense::hover(),
+            );
+            let stroke = egui::Stroke::new(2., egui::Color32::BLACK);
+            ui.painter().line_segment(
+                [rect.left_bottom(), rect.right_bottom()],
+                stroke,
+            );
+            ui.painter()
+                .line_segment([rect.left_top(), rect.left_bottom()], stroke);
+            ui.painter()
+                .line_segment([rect.right_top(), rect.right_bottom()], stroke);
+        });
+}
+
+fn show_gizmo(context: &egui::Context, view: &GizmoView) {
+    const COLORS: [egui::Color32; 3] = [
+        egui::Color32::from_rgb(220, 50, 50),
+        egui::Color32::from_rgb(50, 180, 50),
+        egui::Color32::from_rgb(50, 90, 220),
+    ];
+
+    let screen_rect = context.input().screen_rect();
+    let to_screen = |position| to_screen(screen_rect, position);
+
+    let painter = context.layer_painter(egui::LayerId::background());
+    let dragged = view.gizmo.dragged_handle();
+
+    for (handle, points) in &view.handles {
+        let color = if dragged == Some(*handle) {
+            egui::Color32::YELLOW
+        } else {
+            COLORS[handle.axis()]
+        };
+        let points = points.iter().map(to_screen).collect::<Vec<_>>();
+
+        match handle {
+            GizmoHandle::Translate(_) => {
+                painter.add(egui::Shape::line(
+                    points.clone(),
+                    egui::Stroke::new(3., color),
+                ));
+                if let Some(tip) = points.last() {
+                    painter.circle_filled(*tip, 5., color);
+                }
+            }
+            GizmoHandle::Rotate(_) => {
+                painter.add(egui::Shape::line(
+                    points,
+                    egui::Stroke::new(2., color),
+                ));
+            }
+        }
+    }
+}
+
+fn show_overlay(context: &egui::Context, overlay: &Overlay) {
+    let screen_rect = context.input().screen_rect();
+    let to_screen = |position| to_screen(screen_rect, position);
+    let color = |[r, g, b, a]: [u8; 4]| {
+        egui::Color32::from_rgba_unmultiplied(r, g, b, a)
+    };
+
+    let painter = context.layer_painter(egui::LayerId::background());
+
+    for shape in &overlay.shapes {
+        match shape {
+            OverlayShape::Line { points, color: c } => {
+                painter.add(egui::Shape::line(
+                    points.iter().map(to_screen).collect(),
+                    egui::Stroke::new(2., color(*c)),
+                ));
+            }
+            OverlayShape::Point { position, color: c } => {
+                painter.circle_filled(to_screen(position), 4., color(*c));
+            }
+            OverlayShape::Label { position, text } => {
+                let galley = painter.layout_no_wrap(
+                    text.clone(),
+                    egui::FontId::monospace(14.),
+                    egui::Color32::BLACK,
+                );
+                let rect = egui::Align2::CENTER_BOTTOM.anchor_rect(
+                    egui::Rect::from_min_size(
+                        to_screen(position) - egui::vec2(0., 6.),
+                        galley.size(),
+                    ),
+                );
+                painter.rect_filled(rect.expand(2.), 2., egui::Color32::WHITE);
+                painter.galley(rect.min, galley);
+            }
+        }
+    }
+}
+
+/// Convert a normalized screen position into a position on the screen
+///
+/// This is the inverse of how the cursor position is normalized.
+fn to_screen(
+    screen_rect: egui::Rect,
+    position: &NormalizedScreenPosition,
+) -> egui::Pos2 {
+    let aspect_ratio = screen_rect.width() / screen_rect.height();
+
+    egui::pos2(
+        screen_rect.left()
+            + (position.x as f32 + 1.) / 2. * screen_rect.width(),
+        screen_rect.top()
+            + (1. - position.y as f32 * aspect_ratio) / 2.
+                * screen_rect.height(),
+    )
+}
+
+/// Pick the length of a scale bar that doesn't exceed the given maximum
+///
+/// The length is one, two, or five times a power of ten. Returns the length
+/// and the number of decimals that are needed to display it.
+fn scale_bar_length(max: f64) -> (f64, usize) {
+    let exponent = max.log10().floor();
+    let magnitude = 10_f64.powf(exponent);
+
+    let length = [5., 2., 1.]
+        .into_iter()
+        .map(|factor| factor * magnitude)
+        .find(|&length| length <= max)
+        .unwrap_or(magnitude);
+    let decimals = (-exponent).max(0.) as usize;
+
+    (length, decimals)
+}
+
 fn show_file_dialog() -> Option<PathBuf> {
     #[cfg(not(target_arch = "wasm32"))]
     return FileDialog::new()
@@ -324,6 +1531,31 @@ fn show_file_dialog() -> Option<PathBuf> {
     todo!("Picking folders does not work on wasm32")
 }
 
+fn show_save_bom_dialog() -> Option<PathBuf> {
+    #[cfg(not(target_arch = "wasm32"))]
+    return FileDialog::new()
+        .set_directory(current_dir().unwrap_or_else(|_| PathBuf::from("/")))
+        .set_file_name("bom.csv")
+        .add_filter("CSV", &["csv"])
+        .add_filter("JSON", &["json"])
+        .save_file();
+
+    #[cfg(target_arch = "wasm32")]
+    todo!("Saving files does not work on wasm32")
+}
+
+fn show_save_screenshot_dialog() -> Option<PathBuf> {
+    #[cfg(not(target_arch = "wasm32"))]
+    return FileDialog::new()
+        .set_directory(current_dir().unwrap_or_else(|_| PathBuf::from("/")))
+        .set_file_name(&Screenshot::file_name())
+        .add_filter("PNG", &["png"])
+        .save_file();
+
+    #[cfg(target_arch = "wasm32")]
+    todo!("Saving files does not work on wasm32")
+}
+
 impl std::fmt::Debug for Gui {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         f.write_str("Gui {}")
@@ -337,6 +1569,12 @@ pub struct Options {
     pub show_debug_text_example: bool,
     pub show_settings_ui: bool,
     pub show_inspection_ui: bool,
+    pub tolerance: f64,
+    pub shape_tolerance: Option<f64>,
+    pub analysis: Analysis,
+    pub parameters: BTreeMap<String, String>,
+    pub model_parameters: BTreeMap<String, String>,
+    pub new_parameter: String,
 }
 
 /// The current status of the GUI
@@ -346,4 +1584,41 @@ pub struct GuiState<'a> {
 
     /// Indicates whether a model is currently available
     pub model_available: bool,
+
+    /// The parameters that the current model is evaluated with
+    pub parameters: &'a HashMap<String, String>,
+
+    /// The statistics of the most recent evaluations of the model
+    pub evaluation_history: &'a EvaluationHistory,
+
+    /// The paths of the models that were opened recently, latest first
+    pub recent_models: &'a [PathBuf],
+
+    /// The names of the sample models that can be opened
+    pub sample_models: &'a [&'a str],
+}
+
+/// The actions requested by the user through the GUI
+#[derive(Debug, Default)]
+pub struct GuiOutput {
+    /// The path of a model that the user picked
+    pub new_model_path: Option<PathBuf>,
+
+    /// The name of a sample model that the user picked
+    pub sample_model: Option<String>,
+
+    /// The parameters that the user wants the model to be evaluated with
+    pub parameters: Option<HashMap<String, String>>,
+
+    /// The tolerance that the user wants the model to be triangulated with
+    pub tolerance: Option<Scalar>,
+
+    /// The analysis that the user wants to see, if it changed
+    pub analysis: Option<Analysis>,
+
+    /// The path that the user wants to save a bill of materials to
+    pub bill_of_materials_path: Option<PathBuf>,
+
+    /// The path that the user wants to save a screenshot to
+    pub screenshot_path: Option<PathBuf>,
 }
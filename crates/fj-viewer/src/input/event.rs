@@ -1,3 +1,5 @@
+use fj_math::Vector;
+
 use crate::screen::NormalizedScreenPosition;
 
 /// An input event
@@ -20,4 +22,10 @@ pub enum InputEvent {
 
     /// Move the view forwards and backwards
     Zoom(f64),
+
+    /// Move the camera through the model, in fly mode
+    ///
+    /// The vector is the displacement of the camera, in camera space: x
+    /// points to the right, y up, and negative z forward.
+    Fly(Vector<3>),
 }
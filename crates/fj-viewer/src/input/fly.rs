@@ -0,0 +1,13 @@
+use fj_math::{Transform, Vector};
+
+use crate::camera::Camera;
+
+pub struct Fly;
+
+impl Fly {
+    pub fn apply(&mut self, displacement: Vector<3>, camera: &mut Camera) {
+        // The model moves, not the camera, so it moves the other way.
+        camera.translation =
+            camera.translation * Transform::translation(-displacement);
+    }
+}
@@ -1,10 +1,13 @@
-use super::{movement::Movement, rotation::Rotation, zoom::Zoom, InputEvent};
+use super::{
+    fly::Fly, movement::Movement, rotation::Rotation, zoom::Zoom, InputEvent,
+};
 use crate::camera::{Camera, FocusPoint};
 
 /// Input handling abstraction
 ///
 /// Takes user input and applies them to application state.
 pub struct InputHandler {
+    fly: Fly,
     movement: Movement,
     rotation: Rotation,
     zoom: Zoom,
@@ -28,6 +31,9 @@ impl InputHandler {
             InputEvent::Zoom(zoom_delta) => {
                 self.zoom.apply(zoom_delta, focus_point, camera)
             }
+            InputEvent::Fly(displacement) => {
+                self.fly.apply(displacement, camera)
+            }
         }
     }
 }
@@ -35,6 +41,7 @@ impl InputHandler {
 impl Default for InputHandler {
     fn default() -> Self {
         Self {
+            fly: Fly,
             movement: Movement,
             rotation: Rotation,
             zoom: Zoom,
@@ -1,6 +1,7 @@
 //! User input parsing and propagation.
 
 mod event;
+mod fly;
 mod handler;
 mod movement;
 mod rotation;
@@ -1,6 +1,6 @@
 use fj_math::{Transform, Vector};
 
-use crate::camera::{Camera, FocusPoint};
+use crate::camera::{Camera, FocusPoint, Navigation};
 
 pub struct Rotation;
 
@@ -12,7 +12,13 @@ impl Rotation {
         focus_point: FocusPoint,
         camera: &mut Camera,
     ) {
-        let rotate_around = Transform::translation(focus_point.0.coords);
+        // Flying, the camera turns in place, and in the direction that the
+        // mouse moves, as if looking around.
+        let (rotate_around, angle_x, angle_y) = match camera.navigation {
+            Navigation::Orbit => (focus_point.0, angle_x, angle_y),
+            Navigation::Fly => (camera.position(), -angle_x, -angle_y),
+        };
+        let rotate_around = Transform::translation(rotate_around.coords);
 
         // the model rotates not the camera, so invert the transform
         let camera_rotation = camera.rotation.inverse();
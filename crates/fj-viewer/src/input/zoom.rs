@@ -1,6 +1,6 @@
-use fj_math::{Transform, Vector};
+use fj_math::{Scalar, Transform, Vector};
 
-use crate::camera::{Camera, FocusPoint};
+use crate::camera::{Camera, FocusPoint, Projection};
 
 pub struct Zoom;
 
@@ -13,7 +13,26 @@ impl Zoom {
     ) {
         let distance = (focus_point.0 - camera.position()).magnitude();
         let displacement = zoom_delta * distance.into_f64();
+
+        // The scale of an orthographic projection depends on the distance to
+        // the model, but where the model is on the screen doesn't. Without
+        // compensating for that, it would scale around the center of the
+        // screen, instead of the point that the cursor is on.
+        let offset = match camera.projection {
+            Projection::Perspective => Vector::from([0., 0., 0.]),
+            Projection::Orthographic => {
+                let focus_point =
+                    camera.camera_to_model().transform_point(&focus_point.0);
+                let scale = displacement / camera.focal_distance();
+
+                Vector::from([focus_point.x, focus_point.y, Scalar::ZERO])
+                    * -scale
+            }
+        };
+
         camera.translation = camera.translation
-            * Transform::translation(Vector::from([0.0, 0.0, displacement]));
+            * Transform::translation(
+                offset + Vector::from([0.0, 0.0, displacement]),
+            );
     }
 }
@@ -14,20 +14,48 @@
 
 #![warn(missing_docs)]
 
+mod analysis;
 mod camera;
+mod extension;
+mod gizmo;
 mod graphics;
 mod gui;
 mod input;
+mod measurement;
+mod overlay;
+mod parts;
+mod picking;
+mod references;
 mod screen;
+mod selection;
+mod statistics;
 mod status_report;
 mod viewer;
 
 pub use self::{
-    camera::Camera,
-    graphics::{DrawConfig, Renderer, RendererInitError},
-    gui::{Gui, GuiState},
+    analysis::{Analysis, CurvatureKind},
+    camera::{Camera, CameraView, Navigation, Projection},
+    extension::{Extension, RenderContext},
+    gizmo::Gizmo,
+    graphics::{
+        ClippingPlane, DrawConfig, Lighting, OffscreenRenderer, Renderer,
+        RendererInitError, Screenshot, ScreenshotError, Shading, Stereo,
+        StereoMode, DEFAULT_SAMPLE_COUNT,
+    },
+    gui::{Gui, GuiOutput, GuiState},
     input::{InputEvent, InputHandler},
+    measurement::{Measured, Measurement},
+    overlay::Overlay,
+    parts::PartVisibility,
+    picking::{Pick, Picker},
+    references::Reference,
     screen::{NormalizedScreenPosition, Screen, ScreenSize},
+    selection::Selection,
+    statistics::{EvaluationHistory, EvaluationStatistics},
     status_report::StatusReport,
     viewer::Viewer,
 };
+
+// Extensions need to use the same versions of these as the viewer.
+pub use egui;
+pub use wgpu;
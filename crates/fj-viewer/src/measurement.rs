@@ -0,0 +1,107 @@
+//! Measuring distances on the model
+//!
+//! See [`Measurement`].
+
+use fj_math::{Point, Scalar};
+
+use crate::{camera::Camera, overlay::Overlay, picking::Pick};
+
+/// Measures the distance between points, and the length of edges
+///
+/// Clicking two points, on the surface of the model or on its vertices,
+/// measures the distance between them. Clicking an edge measures its length.
+#[derive(Debug, Default)]
+pub struct Measurement {
+    /// Whether clicks on the model are measured
+    pub active: bool,
+
+    /// What has been measured so far
+    pub measured: Measured,
+}
+
+impl Measurement {
+    const COLOR: [u8; 4] = [230, 120, 0, 255];
+
+    /// Measure what has been picked
+    pub fn add(&mut self, pick: Pick) {
+        self.measured = match (&self.measured, pick) {
+            (_, Pick::Edge { points, .. }) => Measured::Edge(points),
+            (
+                Measured::Point(a),
+                Pick::Point { point: b, .. } | Pick::Vertex { point: b, .. },
+            ) => Measured::Distance([*a, b]),
+            (_, Pick::Point { point, .. } | Pick::Vertex { point, .. }) => {
+                Measured::Point(point)
+            }
+        };
+    }
+
+    /// Forget what has been measured
+    pub fn clear(&mut self) {
+        self.measured = Measured::Nothing;
+    }
+
+    /// Draw what has been measured
+    pub fn draw(&self, overlay: &mut Overlay, camera: &Camera) {
+        match &self.measured {
+            Measured::Nothing => {}
+            Measured::Point(point) => {
+                overlay.point(point, Self::COLOR, camera);
+            }
+            &Measured::Distance([a, b]) => {
+                overlay.line(&[a, b], Self::COLOR, camera);
+                overlay.point(&a, Self::COLOR, camera);
+                overlay.point(&b, Self::COLOR, camera);
+
+                let middle = a + (b - a) / 2.;
+                let distance = (b - a).magnitude().into_f64();
+                overlay.label(&middle, format!("{distance:.3}"), camera);
+            }
+            Measured::Edge(points) => {
+                overlay.line(points, Self::COLOR, camera);
+
+                if let Some(middle) = points.get(points.len() / 2) {
+                    let length = self.measured.length().unwrap_or_default();
+                    overlay.label(
+                        middle,
+                        format!("{:.3}", length.into_f64()),
+                        camera,
+                    );
+                }
+            }
+        }
+    }
+}
+
+/// What has been measured
+#[derive(Clone, Debug, Default, PartialEq)]
+pub enum Measured {
+    /// Nothing has been measured
+    #[default]
+    Nothing,
+
+    /// The first of two points has been picked
+    Point(Point<3>),
+
+    /// The distance between two points
+    Distance([Point<3>; 2]),
+
+    /// The length of an edge, given by the points along it
+    Edge(Vec<Point<3>>),
+}
+
+impl Measured {
+    /// Compute the measured length, if something has been measured
+    pub fn length(&self) -> Option<Scalar> {
+        match self {
+            Self::Nothing | Self::Point(_) => None,
+            &Self::Distance([a, b]) => Some((b - a).magnitude()),
+            Self::Edge(points) => Some(
+                points
+                    .windows(2)
+                    .map(|segment| (segment[1] - segment[0]).magnitude())
+                    .fold(Scalar::ZERO, |sum, length| sum + length),
+            ),
+        }
+    }
+}
@@ -0,0 +1,78 @@
+//! Graphics that are drawn on top of the model
+//!
+//! See [`Overlay`].
+
+use fj_math::Point;
+
+use crate::{camera::Camera, screen::NormalizedScreenPosition};
+
+/// Graphics that are drawn on top of the model, in screen space
+///
+/// Shapes are given in model space, and projected onto the screen when they're
+/// added. They are drawn by the GUI, so they stay crisp and readable, no matter
+/// how far away the model is.
+#[derive(Debug, Default)]
+pub struct Overlay {
+    pub(crate) shapes: Vec<OverlayShape>,
+}
+
+impl Overlay {
+    /// Create an empty overlay
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a line through the given points
+    ///
+    /// Points that are behind the camera are skipped.
+    pub fn line(
+        &mut self,
+        points: &[Point<3>],
+        color: [u8; 4],
+        camera: &Camera,
+    ) {
+        let points = points
+            .iter()
+            .filter_map(|point| camera.project(point))
+            .collect();
+        self.shapes.push(OverlayShape::Line { points, color });
+    }
+
+    /// Add a point
+    pub fn point(&mut self, point: &Point<3>, color: [u8; 4], camera: &Camera) {
+        if let Some(position) = camera.project(point) {
+            self.shapes.push(OverlayShape::Point { position, color });
+        }
+    }
+
+    /// Add a text label
+    pub fn label(
+        &mut self,
+        point: &Point<3>,
+        text: impl Into<String>,
+        camera: &Camera,
+    ) {
+        if let Some(position) = camera.project(point) {
+            self.shapes.push(OverlayShape::Label {
+                position,
+                text: text.into(),
+            });
+        }
+    }
+}
+
+#[derive(Debug)]
+pub(crate) enum OverlayShape {
+    Line {
+        points: Vec<NormalizedScreenPosition>,
+        color: [u8; 4],
+    },
+    Point {
+        position: NormalizedScreenPosition,
+        color: [u8; 4],
+    },
+    Label {
+        position: NormalizedScreenPosition,
+        text: String,
+    },
+}
@@ -0,0 +1,104 @@
+//! Visibility of the parts of a shape
+
+use std::{borrow::Cow, collections::BTreeSet};
+
+use fj_interop::{
+    mesh::Mesh,
+    processed_shape::{ProcessedPart, ProcessedShape},
+};
+use fj_math::Point;
+
+/// Tracks which parts of a shape are visible, and which one is selected
+///
+/// Parts are identified by name, so their visibility is kept when the model is
+/// reloaded. Parts that share a name are shown and hidden together.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct PartVisibility {
+    hidden: BTreeSet<String>,
+    selected: Option<String>,
+}
+
+impl PartVisibility {
+    /// Indicate whether the part with the given name is visible
+    pub fn is_visible(&self, name: &str) -> bool {
+        !self.hidden.contains(name)
+    }
+
+    /// Show or hide the part with the given name
+    pub fn set_visible(&mut self, name: &str, visible: bool) {
+        if visible {
+            self.hidden.remove(name);
+        } else {
+            self.hidden.insert(name.to_string());
+        }
+    }
+
+    /// Access the name of the selected part
+    pub fn selected(&self) -> Option<&str> {
+        self.selected.as_deref()
+    }
+
+    /// Select the part with the given name, or clear the selection
+    pub fn select(&mut self, name: Option<&str>) {
+        self.selected = name.map(ToString::to_string);
+    }
+
+    /// Show all parts
+    pub fn show_all(&mut self) {
+        self.hidden.clear();
+    }
+
+    /// Indicate whether only the selected part is visible
+    pub fn is_isolated(&self, parts: &[ProcessedPart]) -> bool {
+        match &self.selected {
+            Some(selected) => parts.iter().all(|part| {
+                self.is_visible(&part.name) == (&part.name == selected)
+            }),
+            None => false,
+        }
+    }
+
+    /// Hide all parts except the selected one, or show all of them again
+    ///
+    /// Does nothing, if no part is selected.
+    pub fn toggle_isolation(&mut self, parts: &[ProcessedPart]) {
+        let selected = match &self.selected {
+            Some(selected) => selected.clone(),
+            None => return,
+        };
+
+        if self.is_isolated(parts) {
+            self.show_all();
+            return;
+        }
+
+        self.hidden = parts
+            .iter()
+            .map(|part| part.name.clone())
+            .filter(|name| name != &selected)
+            .collect();
+    }
+
+    /// Compute the mesh that consists of the visible parts of the shape
+    pub fn visible_mesh<'r>(
+        &self,
+        shape: &'r ProcessedShape,
+    ) -> Cow<'r, Mesh<Point<3>>> {
+        let all_visible =
+            shape.parts.iter().all(|part| self.is_visible(&part.name));
+        if all_visible {
+            return Cow::Borrowed(&shape.mesh);
+        }
+
+        let mut mesh = Mesh::new();
+        for part in &shape.parts {
+            if !self.is_visible(&part.name) {
+                continue;
+            }
+
+            mesh.extend(part.mesh.triangles());
+        }
+
+        Cow::Owned(mesh)
+    }
+}
@@ -0,0 +1,361 @@
+//! Picking of points, vertices, and edges of the model
+//!
+//! See [`Picker`].
+
+use std::collections::BTreeMap;
+
+use fj_interop::mesh::{Attributes, Mesh, ObjectId, Triangle};
+use fj_math::{Point, Scalar, Vector};
+
+use crate::{camera::Camera, screen::NormalizedScreenPosition};
+
+/// Finds what part of the model is under the cursor
+///
+/// The viewer only has the triangle mesh of the model, not its edges and
+/// vertices. Triangles that were triangulated from the faces of a shape know
+/// which edges and vertices they lie on. For all others, the edges and
+/// vertices are reconstructed from the mesh: Edges between triangles that meet
+/// at a sharp angle, and edges that only belong to a single triangle, are
+/// considered to be edges of the model. Where these don't continue smoothly,
+/// there's a vertex of the model.
+#[derive(Debug)]
+pub struct Picker {
+    /// The mesh of the model
+    mesh: Mesh<Point<3>>,
+
+    /// The segments of the model's edges
+    segments: Vec<[Point<3>; 2]>,
+
+    /// The index of the edge that each segment belongs to
+    edge_of_segment: Vec<usize>,
+
+    /// The segments of each edge, in order, and the edge they lie on
+    edges: Vec<(Vec<Point<3>>, Option<ObjectId>)>,
+
+    /// The vertices of the model, and the vertex objects they are, if known
+    vertices: Vec<(Point<3>, Option<ObjectId>)>,
+}
+
+impl Picker {
+    /// The screen distance within which the cursor snaps to vertices and edges
+    const SNAP_DISTANCE: f64 = 0.02;
+
+    /// The angle between triangles above which their shared edge is sharp
+    const SHARP_ANGLE_IN_DEGREES: f64 = 30.;
+
+    /// Reconstruct the edges and vertices of the model from a mesh
+    pub fn new(mesh: Mesh<Point<3>>) -> Self {
+        let mut edge_of_side = BTreeMap::new();
+        let mut vertex_objects = BTreeMap::new();
+        let mut normals_of_edge = BTreeMap::<_, Vec<Vector<3>>>::new();
+        for triangle in mesh.triangles() {
+            let points = triangle.inner.points();
+            let normal = triangle.inner.normal();
+            let origin = triangle.origin;
+
+            for i in 0..3 {
+                let [a, b] = [points[i], points[(i + 1) % 3]];
+                let side = [a.min(b), a.max(b)];
+
+                if origin.face.is_none() {
+                    normals_of_edge.entry(side).or_default().push(normal);
+                } else if let Some(edge) = origin.edges[i] {
+                    edge_of_side.insert(side, edge);
+                }
+            }
+
+            for (point, vertex) in points.into_iter().zip(origin.vertices) {
+                if let Some(vertex) = vertex {
+                    vertex_objects.insert(point, vertex);
+                }
+            }
+        }
+
+        let min_cos =
+            Scalar::from(Self::SHARP_ANGLE_IN_DEGREES.to_radians().cos());
+        let sharp_sides = normals_of_edge
+            .into_iter()
+            .filter(|(_, normals)| match normals.as_slice() {
+                [a, b] => a.dot(b) < min_cos,
+                _ => true,
+            })
+            .map(|(side, _)| (side, None));
+        let (segments, edge_objects): (Vec<_>, Vec<_>) = edge_of_side
+            .into_iter()
+            .map(|(side, edge)| (side, Some(edge)))
+            .chain(sharp_sides)
+            .unzip();
+
+        let mut segments_at_point = BTreeMap::<_, Vec<usize>>::new();
+        for (i, segment) in segments.iter().enumerate() {
+            for point in segment {
+                segments_at_point.entry(*point).or_default().push(i);
+            }
+        }
+
+        // Edges continue through points that connect exactly two segments of
+        // the same edge. Reconstructed edges do, unless they change direction
+        // sharply there.
+        let is_vertex = |point: &Point<3>| {
+            if vertex_objects.contains_key(point) {
+                return true;
+            }
+
+            match segments_at_point[point].as_slice() {
+                &[a, b] if edge_objects[a] == edge_objects[b] => {
+                    let direction = |i: usize| {
+                        let [start, end] = segments[i];
+                        let direction = if start == *point {
+                            end - start
+                        } else {
+                            start - end
+                        };
+                        direction.normalize()
+                    };
+                    edge_objects[a].is_none()
+                        && direction(a).dot(&-direction(b)) < min_cos
+                }
+                _ => true,
+            }
+        };
+        let vertices = segments_at_point
+            .keys()
+            .filter(|point| is_vertex(point))
+            .map(|point| (*point, vertex_objects.get(point).copied()))
+            .collect::<Vec<_>>();
+
+        let mut edge_of_segment = vec![usize::MAX; segments.len()];
+        let mut edges = Vec::new();
+        for start in 0..segments.len() {
+            if edge_of_segment[start] != usize::MAX {
+                continue;
+            }
+
+            let edge = edges.len();
+            edge_of_segment[start] = edge;
+
+            // Follow the edge in both directions from the first segment, until
+            // it ends at a vertex, or closes on itself.
+            let mut points = segments[start].to_vec();
+            for end in [1, 0] {
+                let mut segment = start;
+                let mut point = segments[start][end];
+                while !is_vertex(&point) {
+                    let Some(&next) = segments_at_point[&point]
+                        .iter()
+                        .find(|&&next| next != segment)
+                    else {
+                        break;
+                    };
+                    if edge_of_segment[next] != usize::MAX {
+                        break;
+                    }
+
+                    edge_of_segment[next] = edge;
+                    segment = next;
+                    point = if segments[next][0] == point {
+                        segments[next][1]
+                    } else {
+                        segments[next][0]
+                    };
+
+                    if end == 1 {
+                        points.push(point);
+                    } else {
+                        points.insert(0, point);
+                    }
+                }
+            }
+
+            edges.push((points, edge_objects[start]));
+        }
+
+        Self {
+            mesh,
+            segments,
+            edge_of_segment,
+            edges,
+            vertices,
+        }
+    }
+
+    /// Find what part of the model is under the cursor
+    ///
+    /// Vertices take precedence over edges, which take precedence over the
+    /// surface of the model. Vertices and edges that are hidden behind the
+    /// model can't be picked.
+    pub fn pick(
+        &self,
+        camera: &Camera,
+        cursor: NormalizedScreenPosition,
+    ) -> Option<Pick> {
+        let (origin, direction) = camera.cursor_ray(cursor);
+        let hit = cast_ray_onto_triangle(&self.mesh, origin, direction)
+            .map(|(t, triangle)| (origin + direction * t, triangle));
+
+        let to_camera = camera.camera_to_model();
+        let depth = |point: &Point<3>| -to_camera.transform_point(point).z;
+
+        // Where the model is hit, anything a bit further away than that is
+        // hidden behind it.
+        let max_depth = hit.as_ref().map(|(hit, _)| {
+            let depth = depth(hit);
+            depth + depth.abs() * 0.01
+        });
+        let is_visible = |point: &Point<3>| match max_depth {
+            Some(max_depth) => depth(point) <= max_depth,
+            None => true,
+        };
+
+        let screen_distance =
+            |a: NormalizedScreenPosition, b: NormalizedScreenPosition| {
+                ((a.x - b.x).powi(2) + (a.y - b.y).powi(2)).sqrt()
+            };
+
+        let vertex = self
+            .vertices
+            .iter()
+            .filter(|(point, _)| is_visible(point))
+            .filter_map(|vertex| {
+                let distance =
+                    screen_distance(camera.project(&vertex.0)?, cursor);
+                Some((vertex, distance))
+            })
+            .filter(|(_, distance)| *distance <= Self::SNAP_DISTANCE)
+            .min_by(|(_, a), (_, b)| a.total_cmp(b));
+        if let Some((&(point, vertex), _)) = vertex {
+            return Some(Pick::Vertex { point, vertex });
+        }
+
+        let edge = self
+            .segments
+            .iter()
+            .enumerate()
+            .filter_map(|(i, &[a, b])| {
+                let [pa, pb] = [camera.project(&a)?, camera.project(&b)?];
+
+                // Find the point on the segment that's closest to the cursor,
+                // on the screen.
+                let ab = [pb.x - pa.x, pb.y - pa.y];
+                let length_squared = ab[0].powi(2) + ab[1].powi(2);
+                let t = if length_squared > 0. {
+                    let ac = [cursor.x - pa.x, cursor.y - pa.y];
+                    ((ac[0] * ab[0] + ac[1] * ab[1]) / length_squared)
+                        .clamp(0., 1.)
+                } else {
+                    0.
+                };
+                let closest = NormalizedScreenPosition {
+                    x: pa.x + ab[0] * t,
+                    y: pa.y + ab[1] * t,
+                };
+
+                // Interpolating linearly isn't exact with a perspective
+                // projection, but close enough to check visibility.
+                let point = a + (b - a) * t;
+                is_visible(&point)
+                    .then(|| (i, screen_distance(closest, cursor)))
+            })
+            .filter(|(_, distance)| *distance <= Self::SNAP_DISTANCE)
+            .min_by(|(_, a), (_, b)| a.total_cmp(b));
+        if let Some((segment, _)) = edge {
+            let (points, edge) = &self.edges[self.edge_of_segment[segment]];
+            return Some(Pick::Edge {
+                points: points.clone(),
+                edge: *edge,
+            });
+        }
+
+        hit.map(|(point, triangle)| Pick::Point {
+            point,
+            face: triangle.origin.face,
+            attributes: triangle.attributes,
+        })
+    }
+
+    /// Access the mesh that is picked from
+    pub fn mesh(&self) -> &Mesh<Point<3>> {
+        &self.mesh
+    }
+}
+
+/// Something on the model that has been picked
+///
+/// The kernel objects that have been picked are only known, if the picked
+/// triangles know their origin. See [`Origin`].
+///
+/// [`Origin`]: fj_interop::mesh::Origin
+#[derive(Clone, Debug, PartialEq)]
+pub enum Pick {
+    /// A point on the surface of the model
+    Point {
+        /// The picked point
+        point: Point<3>,
+
+        /// The face that the point is on
+        face: Option<ObjectId>,
+
+        /// The attributes of that face
+        attributes: Attributes,
+    },
+
+    /// A vertex of the model
+    Vertex {
+        /// The position of the vertex
+        point: Point<3>,
+
+        /// The picked vertex
+        vertex: Option<ObjectId>,
+    },
+
+    /// An edge of the model
+    Edge {
+        /// The points along the edge
+        points: Vec<Point<3>>,
+
+        /// The picked edge
+        edge: Option<ObjectId>,
+    },
+}
+
+/// Cast a ray onto a mesh
+///
+/// Returns the distance along the ray to the closest triangle that it hits,
+/// in multiples of `direction`.
+pub fn cast_ray(
+    mesh: &Mesh<Point<3>>,
+    origin: Point<3>,
+    direction: Vector<3>,
+) -> Option<Scalar> {
+    cast_ray_onto_triangle(mesh, origin, direction).map(|(t, _)| t)
+}
+
+/// Cast a ray onto a mesh, returning the closest triangle that it hits
+fn cast_ray_onto_triangle(
+    mesh: &Mesh<Point<3>>,
+    origin: Point<3>,
+    direction: Vector<3>,
+) -> Option<(Scalar, Triangle)> {
+    let mut closest: Option<(Scalar, Triangle)> = None;
+
+    for triangle in mesh.triangles() {
+        let t = triangle.inner.cast_local_ray(
+            origin,
+            direction,
+            f64::INFINITY,
+            true,
+        );
+
+        if let Some(t) = t {
+            let is_closer = match &closest {
+                Some((min_t, _)) => t <= *min_t,
+                None => true,
+            };
+            if is_closer {
+                closest = Some((t, triangle));
+            }
+        }
+    }
+
+    closest
+}
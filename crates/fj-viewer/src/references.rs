@@ -0,0 +1,53 @@
+//! Reference bodies, that are displayed alongside the shape
+
+use fj_interop::{
+    analysis::Interference,
+    mesh::{Color, Mesh},
+};
+use fj_math::{Aabb, Point};
+
+/// A body that is not part of the shape, but displayed alongside it
+///
+/// Reference bodies are usually imported from other applications, like the
+/// models of parts that the shape needs to fit. They are drawn in a neutral
+/// color, to set them apart from the shape.
+#[derive(Clone, Debug)]
+pub struct Reference {
+    /// The name of the reference body
+    pub name: String,
+
+    /// The triangle mesh of the reference body
+    pub mesh: Mesh<Point<3>>,
+
+    /// Indicates whether the reference body is visible
+    pub visible: bool,
+
+    /// How the reference body relates to each part of the shape
+    ///
+    /// Contains the names of the parts, and is empty until it has been checked
+    /// against the current shape.
+    pub interference: Vec<(String, Interference)>,
+}
+
+impl Reference {
+    /// The color that reference bodies are drawn in
+    pub const COLOR: Color = Color([160, 170, 190, 255]);
+
+    /// Construct a visible reference body from its mesh
+    pub fn new(name: impl Into<String>, mesh: Mesh<Point<3>>) -> Self {
+        Self {
+            name: name.into(),
+            mesh,
+            visible: true,
+            interference: Vec::new(),
+        }
+    }
+
+    /// Compute the bounding box of the reference body
+    ///
+    /// Returns `None`, if its mesh is empty.
+    pub fn aabb(&self) -> Option<Aabb<3>> {
+        self.mesh.vertices().next()?;
+        Some(Aabb::<3>::from_points(self.mesh.vertices()))
+    }
+}
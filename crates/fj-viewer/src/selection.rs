@@ -0,0 +1,115 @@
+//! Selection of faces, edges, and vertices of the model
+//!
+//! See [`Selection`].
+
+use fj_interop::mesh::{Color, Mesh, ObjectId, Triangle};
+use fj_math::Point;
+
+use crate::{camera::Camera, overlay::Overlay, picking::Pick};
+
+/// The part of the model that is under the cursor, and the selected one
+///
+/// Clicking a face, edge, or vertex selects it. Both are highlighted: Faces are
+/// tinted, edges and vertices are drawn on top of the model.
+#[derive(Debug, Default)]
+pub struct Selection {
+    /// What is under the cursor
+    pub hovered: Option<Pick>,
+
+    /// What has been selected
+    pub selected: Option<Pick>,
+}
+
+impl Selection {
+    const HOVERED_COLOR: [u8; 4] = [255, 210, 60, 255];
+    const SELECTED_COLOR: [u8; 4] = [40, 130, 255, 255];
+
+    /// Forget what has been hovered and selected
+    ///
+    /// Call this when the model changes. The objects that were picked don't
+    /// exist anymore.
+    pub fn clear(&mut self) {
+        self.hovered = None;
+        self.selected = None;
+    }
+
+    /// Update what is under the cursor
+    ///
+    /// Returns whether the faces that need to be highlighted have changed.
+    pub fn hover(&mut self, pick: Option<Pick>) -> bool {
+        let changed = face(pick.as_ref()) != face(self.hovered.as_ref());
+        self.hovered = pick;
+        changed
+    }
+
+    /// Select what has been picked, or deselect by passing `None`
+    ///
+    /// Returns whether the faces that need to be highlighted have changed.
+    pub fn select(&mut self, pick: Option<Pick>) -> bool {
+        let changed = face(pick.as_ref()) != face(self.selected.as_ref());
+        self.selected = pick;
+        changed
+    }
+
+    /// Tint the triangles of the hovered and selected faces
+    pub fn highlight_faces(&self, mesh: &Mesh<Point<3>>) -> Mesh<Point<3>> {
+        let hovered = face(self.hovered.as_ref());
+        let selected = face(self.selected.as_ref());
+
+        let mut highlighted = Mesh::new();
+        highlighted.extend(mesh.triangles().map(|triangle| {
+            let color = match triangle.origin.face {
+                Some(face) if Some(face) == selected => {
+                    tint(triangle.color, Self::SELECTED_COLOR)
+                }
+                Some(face) if Some(face) == hovered => {
+                    tint(triangle.color, Self::HOVERED_COLOR)
+                }
+                _ => triangle.color,
+            };
+
+            Triangle { color, ..triangle }
+        }));
+
+        highlighted
+    }
+
+    /// Draw the hovered and selected edges and vertices
+    ///
+    /// The selected ones are drawn last, so they stay visible, if they are
+    /// hovered too.
+    pub fn draw(&self, overlay: &mut Overlay, camera: &Camera) {
+        for (pick, color) in [
+            (&self.hovered, Self::HOVERED_COLOR),
+            (&self.selected, Self::SELECTED_COLOR),
+        ] {
+            match pick {
+                Some(Pick::Vertex { point, .. }) => {
+                    overlay.point(point, color, camera);
+                }
+                Some(Pick::Edge { points, .. }) => {
+                    overlay.line(points, color, camera);
+                }
+                Some(Pick::Point { .. }) | None => {}
+            }
+        }
+    }
+}
+
+/// Access the face that has been picked, if a face has been picked
+fn face(pick: Option<&Pick>) -> Option<ObjectId> {
+    match pick {
+        Some(Pick::Point { face, .. }) => *face,
+        _ => None,
+    }
+}
+
+/// Mix a color halfway with the color of a highlight
+fn tint(Color(color): Color, highlight: [u8; 4]) -> Color {
+    let mut tinted = color;
+    for (channel, highlight) in tinted.iter_mut().zip(highlight).take(3) {
+        *channel = ((u16::from(*channel) + u16::from(highlight)) / 2) as u8;
+    }
+
+    Color(tinted)
+}
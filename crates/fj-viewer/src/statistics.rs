@@ -0,0 +1,74 @@
+//! Statistics about the evaluations of a model
+
+use std::{collections::VecDeque, time::Duration};
+
+/// Statistics about a single evaluation of a model
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct EvaluationStatistics {
+    /// The time it took to evaluate the model, including its compilation
+    pub evaluation_time: Duration,
+
+    /// The time it took to process the shape that the model returned
+    pub processing_time: Duration,
+
+    /// The number of faces in the shape
+    pub num_faces: usize,
+
+    /// The number of half-edges in the shape
+    pub num_half_edges: usize,
+
+    /// The number of triangles in the triangle mesh of the shape
+    pub num_triangles: usize,
+}
+
+/// The statistics of the most recent evaluations of a model
+#[derive(Clone, Debug, Default)]
+pub struct EvaluationHistory {
+    evaluations: VecDeque<EvaluationStatistics>,
+}
+
+impl EvaluationHistory {
+    /// The maximum number of evaluations kept in the history
+    pub const CAPACITY: usize = 50;
+
+    /// Create an empty `EvaluationHistory`
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add the statistics of an evaluation
+    ///
+    /// If the history is full, the oldest evaluation is removed.
+    pub fn push(&mut self, statistics: EvaluationStatistics) {
+        self.evaluations.push_back(statistics);
+        while self.evaluations.len() > Self::CAPACITY {
+            self.evaluations.pop_front();
+        }
+    }
+
+    /// Iterate over the recorded evaluations, from oldest to newest
+    pub fn iter(&self) -> impl Iterator<Item = &EvaluationStatistics> + '_ {
+        self.evaluations.iter()
+    }
+
+    /// Access the most recent evaluation
+    pub fn latest(&self) -> Option<&EvaluationStatistics> {
+        self.evaluations.back()
+    }
+
+    /// Access the evaluation before the most recent one
+    pub fn previous(&self) -> Option<&EvaluationStatistics> {
+        let len = self.evaluations.len();
+        len.checked_sub(2).and_then(|i| self.evaluations.get(i))
+    }
+
+    /// Indicate whether no evaluation has been recorded yet
+    pub fn is_empty(&self) -> bool {
+        self.evaluations.is_empty()
+    }
+
+    /// Remove all recorded evaluations
+    pub fn clear(&mut self) {
+        self.evaluations.clear();
+    }
+}
@@ -1,13 +1,21 @@
-use std::path::PathBuf;
+use std::{borrow::Cow, time::Duration};
 
-use fj_interop::processed_shape::ProcessedShape;
-use fj_math::Aabb;
+use fj_interop::mesh::{Mesh, Triangle};
+use fj_interop::{
+    analysis::TriangleValues, debug::DebugInfo, processed_shape::ProcessedShape,
+};
+use fj_math::{Aabb, Point, Scalar, Vector};
 use tracing::warn;
 
 use crate::{
-    camera::FocusPoint, gui::Gui, Camera, DrawConfig, GuiState, InputEvent,
-    InputHandler, NormalizedScreenPosition, Renderer, RendererInitError,
-    Screen, ScreenSize,
+    analysis::color_shape,
+    camera::{FocusPoint, Navigation},
+    gizmo::GizmoView,
+    gui::Gui,
+    Camera, DrawConfig, Extension, Gizmo, GuiOutput, GuiState, InputEvent,
+    InputHandler, Measurement, NormalizedScreenPosition, Overlay,
+    PartVisibility, Picker, Reference, Renderer, RendererInitError, Screen,
+    ScreenSize, Screenshot, ScreenshotError, Selection,
 };
 
 /// The Fornjot model viewer
@@ -30,17 +38,46 @@ pub struct Viewer {
     /// The input handler
     pub input_handler: InputHandler,
 
+    /// The visibility of the parts of the shape
+    pub part_visibility: PartVisibility,
+
     /// The renderer
     pub renderer: Renderer,
 
     /// The shape
     pub shape: Option<ProcessedShape>,
+
+    /// The results of the analysis that is shown, if any
+    pub analysis: Option<TriangleValues>,
+
+    /// The reference bodies that are displayed alongside the shape
+    pub references: Vec<Reference>,
+
+    /// The gizmo for moving the selected part
+    pub gizmo: Gizmo,
+
+    /// The extensions that add to the GUI and the graphics
+    pub extensions: Vec<Box<dyn Extension>>,
+
+    /// The measurement of distances on the model
+    pub measurement: Measurement,
+
+    /// The faces, edges, and vertices that are hovered and selected
+    pub selection: Selection,
+
+    /// Finds what is under the cursor, for measuring and selecting it
+    picker: Picker,
 }
 
 impl Viewer {
     /// Construct a new instance of `Viewer`
-    pub async fn new(screen: &impl Screen) -> Result<Self, RendererInitError> {
-        let renderer = Renderer::new(screen).await?;
+    ///
+    /// See [`Renderer::new`] for what `sample_count` means.
+    pub async fn new(
+        screen: &impl Screen,
+        sample_count: u32,
+    ) -> Result<Self, RendererInitError> {
+        let renderer = Renderer::new(screen, sample_count).await?;
         let gui = renderer.init_gui();
 
         Ok(Self {
@@ -50,11 +87,31 @@ impl Viewer {
             focus_point: None,
             gui,
             input_handler: InputHandler::default(),
+            part_visibility: PartVisibility::default(),
             renderer,
             shape: None,
+            analysis: None,
+            references: Vec::new(),
+            gizmo: Gizmo::default(),
+            extensions: Vec::new(),
+            measurement: Measurement::default(),
+            selection: Selection::default(),
+            picker: Picker::new(Mesh::new()),
         })
     }
 
+    /// Register an extension
+    ///
+    /// Extensions are called in the order they were added.
+    pub fn add_extension(&mut self, extension: impl Extension + 'static) {
+        self.extensions.push(Box::new(extension));
+    }
+
+    /// Toggle the "draw scale bar" setting
+    pub fn toggle_draw_scale_bar(&mut self) {
+        self.draw_config.draw_scale_bar = !self.draw_config.draw_scale_bar
+    }
+
     /// Toggle the "draw model" setting
     pub fn toggle_draw_model(&mut self) {
         self.draw_config.draw_model = !self.draw_config.draw_model
@@ -74,19 +131,201 @@ impl Viewer {
         }
     }
 
+    /// Toggle the "debug on top" setting
+    pub fn toggle_debug_on_top(&mut self) {
+        self.draw_config.debug_on_top = !self.draw_config.debug_on_top
+    }
+
+    /// Toggle the "draw gizmo" setting
+    pub fn toggle_draw_gizmo(&mut self) {
+        self.draw_config.draw_gizmo = !self.draw_config.draw_gizmo
+    }
+
+    /// Switch between the perspective and orthographic projections
+    pub fn toggle_projection(&mut self) {
+        self.camera.toggle_projection();
+    }
+
+    /// Switch between orbiting around the model and flying through it
+    pub fn toggle_navigation(&mut self) {
+        self.camera.toggle_navigation();
+    }
+
+    /// Toggle measuring what is clicked on
+    pub fn toggle_measurement(&mut self) {
+        self.measurement.active = !self.measurement.active;
+    }
+
+    /// Handle a click on the model
+    ///
+    /// Measures what is under the cursor, if measuring is active, or selects it
+    /// otherwise. Clicking where there's nothing clears the selection.
+    pub fn click(&mut self) {
+        if self.gui.context().is_pointer_over_area() {
+            return;
+        }
+
+        let pick = self
+            .cursor
+            .and_then(|cursor| self.picker.pick(&self.camera, cursor));
+
+        if self.measurement.active {
+            if let Some(pick) = pick {
+                self.measurement.add(pick);
+            }
+            return;
+        }
+
+        if self.selection.select(pick) {
+            self.upload_geometry();
+        }
+    }
+
+    /// Find what is under the cursor, to highlight it
+    ///
+    /// Call this when the cursor moves. Does nothing while the camera or the
+    /// gizmo is moved.
+    pub fn hover(&mut self) {
+        if self.focus_point.is_some() || self.gizmo.dragged_handle().is_some() {
+            return;
+        }
+
+        let pick = match self.cursor {
+            Some(cursor) if !self.gui.context().is_pointer_over_area() => {
+                self.picker.pick(&self.camera, cursor)
+            }
+            _ => None,
+        };
+
+        if self.selection.hover(pick) {
+            self.upload_geometry();
+        }
+    }
+
+    /// Hide all parts except the selected one, or show all of them again
+    pub fn toggle_part_isolation(&mut self) {
+        if let Some(shape) = &self.shape {
+            self.part_visibility.toggle_isolation(&shape.parts);
+            self.update_geometry();
+        }
+    }
+
     /// Handle the shape being updated
+    ///
+    /// Clears the results of any analysis and the selection, as they no longer
+    /// match the shape.
     pub fn handle_shape_update(&mut self, shape: ProcessedShape) {
-        self.renderer
-            .update_geometry((&shape.mesh).into(), (&shape.debug_info).into());
-
         let aabb = shape.aabb;
         if self.shape.replace(shape).is_none() {
             self.camera.init_planes(&aabb)
         }
+        self.analysis = None;
+        self.selection.clear();
+
+        self.update_geometry();
+    }
+
+    /// Handle the results of an analysis being updated
+    ///
+    /// The model is colored according to the results, until they are cleared
+    /// by passing `None`.
+    pub fn handle_analysis_update(&mut self, analysis: Option<TriangleValues>) {
+        self.analysis = analysis;
+        self.update_geometry();
+    }
+
+    /// Add a reference body, to be displayed alongside the shape
+    pub fn add_reference(&mut self, reference: Reference) {
+        self.references.push(reference);
+        self.update_geometry();
+    }
+
+    fn update_geometry(&mut self) {
+        if let Some(shape) = &self.shape {
+            let shape = match &self.analysis {
+                Some(analysis) => Cow::Owned(color_shape(shape, analysis)),
+                None => Cow::Borrowed(shape),
+            };
+            let mut mesh = self.part_visibility.visible_mesh(&shape);
+
+            // The part that the gizmo is attached to is shown where it has
+            // been moved to.
+            let gizmo_transform = match (self.gizmo.part(), self.gizmo_center())
+            {
+                (Some(name), Some(center)) if !self.gizmo.is_identity() => {
+                    Some((name, self.gizmo.transform(&center)))
+                }
+                _ => None,
+            };
+            if let Some((name, transform)) = gizmo_transform {
+                let mut moved = Mesh::new();
+                for part in &shape.parts {
+                    if !self.part_visibility.is_visible(&part.name) {
+                        continue;
+                    }
+
+                    if part.name != name {
+                        moved.extend(part.mesh.triangles());
+                        continue;
+                    }
+
+                    moved.extend(part.mesh.triangles().map(|triangle| {
+                        Triangle {
+                            inner: transform
+                                .transform_triangle(&triangle.inner),
+                            normals: triangle.normals.map(|normals| {
+                                normals.map(|normal| {
+                                    transform.transform_vector(&normal)
+                                })
+                            }),
+                            color: triangle.color,
+                            attributes: triangle.attributes,
+                            origin: triangle.origin,
+                        }
+                    }));
+                }
+
+                mesh = Cow::Owned(moved);
+            }
+
+            for reference in &self.references {
+                if !reference.visible {
+                    continue;
+                }
+
+                let mesh = mesh.to_mut();
+                for triangle in reference.mesh.triangles() {
+                    mesh.push_triangle(triangle.inner, Reference::COLOR);
+                }
+            }
+
+            self.picker = Picker::new(mesh.into_owned());
+            self.upload_geometry();
+        }
+    }
+
+    /// Pass the geometry to the renderer, with the selection highlighted
+    fn upload_geometry(&mut self) {
+        if let Some(shape) = &self.shape {
+            let mesh = self.selection.highlight_faces(self.picker.mesh());
+            self.renderer
+                .update_geometry((&mesh).into(), (&shape.debug_info).into());
+        }
     }
 
     /// Handle an input event
     pub fn handle_input_event(&mut self, event: InputEvent) {
+        // Flying doesn't happen around any point of the model.
+        if let InputEvent::Fly(_) = event {
+            let camera_position = FocusPoint(self.camera.position());
+            self.input_handler.handle_event(
+                event,
+                camera_position,
+                &mut self.camera,
+            );
+            return;
+        }
+
         if let Some(focus_point) = self.focus_point {
             self.input_handler.handle_event(
                 event,
@@ -96,6 +335,156 @@ impl Viewer {
         }
     }
 
+    /// Move the camera in fly mode
+    ///
+    /// `direction` is given in camera space, with x pointing to the right, y
+    /// up, and negative z forward. `elapsed` is the time since the camera was
+    /// last moved, which together with the fly speed determines how far it
+    /// moves. Does nothing, unless the camera is in fly mode.
+    pub fn fly(&mut self, direction: Vector<3>, elapsed: Duration) {
+        if self.camera.navigation != Navigation::Fly {
+            return;
+        }
+        let Some(shape) = &self.shape else {
+            return;
+        };
+        if direction.magnitude() == Scalar::ZERO {
+            return;
+        }
+
+        // The speed is relative to the size of the model, so small parts and
+        // whole buildings take about the same time to fly through.
+        let distance = shape.aabb.size().magnitude()
+            * self.camera.fly_speed
+            * elapsed.as_secs_f64();
+
+        self.handle_input_event(InputEvent::Fly(
+            direction.normalize() * distance,
+        ));
+    }
+
+    /// Grab the handle of the gizmo that is under the cursor
+    ///
+    /// Returns whether a handle has been grabbed. If so, moving the cursor
+    /// drags the handle instead of moving the camera, until the gizmo is
+    /// released.
+    pub fn grab_gizmo(&mut self) -> bool {
+        // Clicks on the GUI are not meant for the gizmo, even if it's drawn
+        // below.
+        if self.gui.context().is_pointer_over_area() {
+            return false;
+        }
+
+        match (self.cursor, self.gizmo_center()) {
+            (Some(cursor), Some(center)) => {
+                self.gizmo.grab(&center, &self.camera, cursor)
+            }
+            _ => false,
+        }
+    }
+
+    /// Drag the grabbed handle of the gizmo to the cursor
+    ///
+    /// Does nothing, if no handle is grabbed.
+    pub fn drag_gizmo(&mut self) {
+        if self.gizmo.dragged_handle().is_none() {
+            return;
+        }
+
+        if let Some(cursor) = self.cursor {
+            self.gizmo.drag(&self.camera, cursor);
+            self.update_geometry();
+        }
+    }
+
+    /// Release the grabbed handle of the gizmo
+    ///
+    /// Returns the code that moves the part the same way, if a handle was
+    /// grabbed, and the part has been moved.
+    pub fn release_gizmo(&mut self) -> Option<String> {
+        if !self.gizmo.release() {
+            return None;
+        }
+
+        self.gizmo.to_code(&self.gizmo_center()?)
+    }
+
+    /// Attach the gizmo to the selected part
+    ///
+    /// A shape that consists of a single part doesn't allow selecting it, so
+    /// the gizmo is attached to that part instead.
+    fn attach_gizmo(&mut self) {
+        let part = match &self.shape {
+            Some(shape) if self.draw_config.draw_gizmo => {
+                match (self.part_visibility.selected(), shape.parts.as_slice())
+                {
+                    (Some(selected), _) => Some(selected),
+                    (None, [part]) => Some(part.name.as_str()),
+                    (None, _) => None,
+                }
+            }
+            _ => None,
+        };
+
+        let was_identity = self.gizmo.is_identity();
+        self.gizmo.attach(part);
+        if !was_identity && self.gizmo.is_identity() {
+            self.update_geometry();
+        }
+    }
+
+    /// Compute the center of the part that the gizmo is attached to
+    fn gizmo_center(&self) -> Option<Point<3>> {
+        let shape = self.shape.as_ref()?;
+        let name = self.gizmo.part()?;
+
+        let points = shape
+            .parts
+            .iter()
+            .filter(|part| part.name == name)
+            .flat_map(|part| part.mesh.vertices())
+            .collect::<Vec<_>>();
+        if points.is_empty() {
+            return None;
+        }
+
+        Some(Aabb::<3>::from_points(points).center())
+    }
+
+    /// Take a screenshot of the model, as it is currently shown
+    ///
+    /// The GUI is not included.
+    pub fn screenshot(&mut self) -> Result<Screenshot, ScreenshotError> {
+        self.renderer.screenshot(
+            &self.camera,
+            &self.draw_config,
+            &mut self.extensions,
+            self.shape.as_ref(),
+        )
+    }
+
+    /// Replace the graphics device, if it has been lost
+    ///
+    /// Call this after drawing. Does nothing, if the device is still fine.
+    pub async fn recover_from_device_loss(
+        &mut self,
+    ) -> Result<(), RendererInitError> {
+        if !self.renderer.is_device_lost() {
+            return Ok(());
+        }
+
+        warn!("Graphics device lost. Creating a new one.");
+        self.renderer.recover().await?;
+        self.renderer.reinit_gui(&mut self.gui);
+        self.update_geometry();
+
+        for extension in &mut self.extensions {
+            extension.handle_device_change();
+        }
+
+        Ok(())
+    }
+
     /// Handle the screen being resized
     pub fn handle_screen_resize(&mut self, screen_size: ScreenSize) {
         self.renderer.handle_resize(screen_size)
@@ -106,8 +495,12 @@ impl Viewer {
         // Don't recompute the focus point unnecessarily.
         if let Some(shape) = &self.shape {
             if self.focus_point.is_none() {
-                self.focus_point =
-                    Some(self.camera.focus_point(self.cursor, shape));
+                let mesh = self.part_visibility.visible_mesh(shape);
+                self.focus_point = Some(self.camera.focus_point(
+                    self.cursor,
+                    &mesh,
+                    &shape.aabb,
+                ));
             }
         }
     }
@@ -123,33 +516,155 @@ impl Viewer {
         pixels_per_point: f32,
         egui_input: egui::RawInput,
         gui_state: GuiState,
-    ) -> Option<PathBuf> {
+    ) -> GuiOutput {
+        self.attach_gizmo();
+
         let aabb = self
             .shape
             .as_ref()
             .map(|shape| shape.aabb)
             .unwrap_or_else(Aabb::default);
 
-        self.camera.update_planes(&aabb);
+        // Debug geometry can extend beyond the model. The near and far planes
+        // need to account for it, or it would get clipped, even where the
+        // model isn't.
+        let planes_aabb = match &self.shape {
+            Some(shape) if self.draw_config.draw_debug => {
+                debug_aabb(&shape.debug_info)
+                    .map_or(aabb, |debug_aabb| aabb.merged(&debug_aabb))
+            }
+            _ => aabb,
+        };
+        let planes_aabb = self
+            .references
+            .iter()
+            .filter(|reference| reference.visible)
+            .filter_map(Reference::aabb)
+            .fold(planes_aabb, |aabb, reference| aabb.merged(&reference));
+        self.camera.update_planes(&planes_aabb);
+
+        let tolerance = self.shape.as_ref().map(|shape| shape.tolerance);
+        let mass_properties =
+            self.shape.as_ref().map(|shape| &shape.mass_properties);
+
+        let operation_timings = self
+            .shape
+            .as_ref()
+            .map(|shape| shape.debug_info.operation_timings.as_slice())
+            .unwrap_or_default();
+
+        let parts = self
+            .shape
+            .as_ref()
+            .map(|shape| shape.parts.as_slice())
+            .unwrap_or_default();
+        let part_visibility = self.part_visibility.clone();
+        let reference_visibility = self
+            .references
+            .iter()
+            .map(|reference| reference.visible)
+            .collect::<Vec<_>>();
+
+        // With a perspective projection, the scale of the model depends on the
+        // depth. The center of the model is the most representative depth
+        // available.
+        let visible_width = self.shape.as_ref().and_then(|shape| {
+            self.camera.visible_width_at(&shape.aabb.center())
+        });
 
-        let new_model_path = self.gui.update(
+        let gizmo_center = self.gizmo_center();
+        let gizmo_handles = gizmo_center
+            .and_then(|center| self.gizmo.handles(&center, &self.camera))
+            .unwrap_or_default()
+            .into_iter()
+            .filter_map(|(handle, points)| {
+                let points = points
+                    .iter()
+                    .map(|point| self.camera.project(point))
+                    .collect::<Option<Vec<_>>>()?;
+                Some((handle, points))
+            })
+            .collect();
+        let gizmo_was_identity = self.gizmo.is_identity();
+        let gizmo = gizmo_center.map(|center| GizmoView {
+            gizmo: &mut self.gizmo,
+            center,
+            handles: gizmo_handles,
+        });
+
+        let mut overlay = Overlay::new();
+        self.selection.draw(&mut overlay, &self.camera);
+        self.measurement.draw(&mut overlay, &self.camera);
+
+        let gui_output = self.gui.update(
             pixels_per_point,
             egui_input,
             &mut self.draw_config,
+            &mut self.camera,
             &aabb,
+            visible_width,
+            tolerance,
+            mass_properties,
+            parts,
+            &mut self.part_visibility,
+            &mut self.references,
+            gizmo,
+            &mut self.measurement,
+            &self.selection,
+            &overlay,
+            operation_timings,
             self.renderer.is_line_drawing_available(),
             gui_state,
         );
 
+        let references_changed = self
+            .references
+            .iter()
+            .map(|reference| reference.visible)
+            .ne(reference_visibility);
+        let gizmo_reset = !gizmo_was_identity && self.gizmo.is_identity();
+        if self.part_visibility != part_visibility
+            || references_changed
+            || gizmo_reset
+        {
+            self.update_geometry();
+        }
+
+        for extension in &mut self.extensions {
+            extension.show_gui(self.gui.context(), self.shape.as_ref());
+        }
+
         if let Err(err) = self.renderer.draw(
             &self.camera,
             &self.draw_config,
             pixels_per_point,
             &mut self.gui,
+            &mut self.extensions,
+            self.shape.as_ref(),
         ) {
             warn!("Draw error: {}", err);
         }
 
-        new_model_path
+        gui_output
+    }
+}
+
+/// Compute the bounding box of the geometry in the debug info
+///
+/// Returns `None`, if there's no such geometry.
+fn debug_aabb(debug_info: &DebugInfo) -> Option<Aabb<3>> {
+    let points = debug_info
+        .triangle_edge_checks
+        .iter()
+        .flat_map(|check| {
+            let hits = check.hits.iter().flat_map(|hit| hit.points());
+            std::iter::once(check.origin).chain(hits)
+        })
+        .collect::<Vec<_>>();
+
+    if points.is_empty() {
+        return None;
     }
+
+    Some(Aabb::<3>::from_points(points))
 }
@@ -1,9 +1,26 @@
+use std::{
+    collections::HashSet,
+    path::{Path, PathBuf},
+    time::{Duration, Instant},
+};
+
+use fj_export::{BillOfMaterials, Part};
 use fj_host::{Host, Model, ModelEvent, Parameters};
-use fj_operations::shape_processor::{self, ShapeProcessor};
+use fj_interop::{analysis::TriangleValues, processed_shape::ProcessedShape};
+use fj_kernel::algorithms::{analysis, approx::Tolerance};
+use fj_math::{Scalar, Vector};
+use fj_operations::{
+    mesh_cache::MeshCache,
+    shape_processor::{self, EvaluatedShape, ShapeProcessor},
+    tessellation_cache::TessellationCache,
+};
 use fj_viewer::{
-    GuiState, InputEvent, NormalizedScreenPosition, Screen, ScreenSize,
-    StatusReport, Viewer,
+    Analysis, CurvatureKind, EvaluationHistory, EvaluationStatistics, GuiState,
+    InputEvent, NormalizedScreenPosition, RendererInitError, Screen,
+    ScreenSize, Screenshot, StatusReport, Viewer,
 };
+use futures::executor::block_on;
+use tracing::warn;
 use winit::{
     dpi::PhysicalPosition,
     event::{
@@ -13,18 +30,64 @@ use winit::{
     event_loop::ControlFlow,
 };
 
-use crate::window::Window;
+use crate::{
+    recent_models::RecentModels, sample_models::SampleModels, window::Window,
+};
 
 pub struct EventLoopHandler {
     pub invert_zoom: bool,
     pub shape_processor: ShapeProcessor,
+    pub mesh_cache: Option<MeshCache>,
+
+    /// The shape, if it was loaded from the mesh cache
+    ///
+    /// It is only evaluated, once it needs to be triangulated again, or once
+    /// something is picked, which requires the objects that the cached mesh
+    /// doesn't know about.
+    pub unevaluated_shape: Option<fj::Shape>,
+
+    pub evaluated_shape: Option<EvaluatedShape>,
+    pub tessellation_cache: TessellationCache,
     pub window: Window,
     pub viewer: Viewer,
     pub egui_winit_state: egui_winit::State,
     pub host: Option<Host>,
+
+    /// The root directory of the current model's crate, if any
+    pub model_path: Option<PathBuf>,
+
+    /// The parameters that the current model is evaluated with
+    pub parameters: Parameters,
+
+    /// The seed for random numbers that the current model is evaluated with
+    pub seed: u64,
+
+    /// The size of the thumbnails that are written after each evaluation, if
+    /// any
+    pub thumbnail_size: Option<u32>,
+
+    /// The path of the current model's thumbnail
+    pub thumbnail_path: Option<PathBuf>,
+
     pub status: StatusReport,
+    pub evaluation_history: EvaluationHistory,
+    pub recent_models: RecentModels,
+    pub sample_models: SampleModels,
+    pub analysis: Analysis,
     pub held_mouse_button: Option<MouseButton>,
 
+    /// Where the cursor was when a mouse button was pressed
+    ///
+    /// If it hasn't moved much until the button is released, that's a click.
+    pub clicked_at: Option<NormalizedScreenPosition>,
+
+    /// The keys that move the camera in fly mode, that are currently held
+    pub held_fly_keys: HashSet<VirtualKeyCode>,
+
+    /// The time the last frame was drawn, for moving the camera at a steady
+    /// speed in fly mode
+    pub last_frame: Instant,
+
     /// Only handle resize events once every frame. This filters out spurious
     /// resize events that can lead to wgpu warnings. See this issue for some
     /// context:
@@ -67,11 +130,98 @@ impl EventLoopHandler {
                             "Model evaluated. Processing model...",
                         );
 
-                        let shape =
-                            self.shape_processor.process(&evaluation.shape)?;
-                        self.viewer.handle_shape_update(shape);
+                        let start = Instant::now();
+                        let cached =
+                            self.mesh_cache.as_ref().and_then(|cache| {
+                                cache
+                                    .load(
+                                        &evaluation.shape,
+                                        &self.shape_processor,
+                                    )
+                                    .unwrap_or_else(|err| {
+                                        warn!(
+                                            "Failed to load cached mesh: {err}"
+                                        );
+                                        None
+                                    })
+                            });
+
+                        let (shape, num_faces, num_half_edges) = match cached {
+                            Some(cached) => {
+                                self.unevaluated_shape = Some(evaluation.shape);
+                                self.evaluated_shape = None;
+
+                                (
+                                    cached.shape,
+                                    cached.num_faces,
+                                    cached.num_half_edges,
+                                )
+                            }
+                            None => {
+                                let evaluated = self
+                                    .shape_processor
+                                    .evaluate(&evaluation.shape)?;
+                                let shape = evaluated.triangulate_with_cache(
+                                    evaluated.tolerance(),
+                                    &mut self.tessellation_cache,
+                                );
+
+                                if let Some(cache) = &self.mesh_cache {
+                                    if let Err(err) = cache.store(
+                                        &evaluation.shape,
+                                        &self.shape_processor,
+                                        &evaluated,
+                                        &shape,
+                                    ) {
+                                        warn!("Failed to cache mesh: {err}");
+                                    }
+                                }
+
+                                let num_faces = evaluated.num_faces();
+                                let num_half_edges = evaluated.num_half_edges();
+                                self.unevaluated_shape = None;
+                                self.evaluated_shape = Some(evaluated);
+
+                                (shape, num_faces, num_half_edges)
+                            }
+                        };
 
-                        self.status.update_status("Model processed.");
+                        self.evaluation_history.push(EvaluationStatistics {
+                            evaluation_time: evaluation.evaluation_time,
+                            processing_time: start.elapsed(),
+                            num_faces,
+                            num_half_edges,
+                            num_triangles: shape.mesh.triangles().count(),
+                        });
+
+                        let slowest = shape
+                            .debug_info
+                            .operation_timings
+                            .iter()
+                            .map(|timing| timing.slowest())
+                            .max_by_key(|timing| timing.self_duration());
+                        match slowest {
+                            Some(slowest) => {
+                                self.status.update_status(&format!(
+                                    "Model processed. Slowest operation: {} \
+                                    ({:.1?})",
+                                    slowest.name,
+                                    slowest.self_duration(),
+                                ));
+                            }
+                            None => {
+                                self.status.update_status("Model processed.");
+                            }
+                        }
+
+                        self.write_thumbnail(&shape);
+                        self.viewer.handle_shape_update(shape);
+                        update_analysis(
+                            &mut self.viewer,
+                            self.analysis,
+                            self.evaluated_shape.as_ref(),
+                        );
+                        update_interference(&mut self.viewer);
                     }
 
                     ModelEvent::Error(err) => {
@@ -94,13 +244,15 @@ impl EventLoopHandler {
                 .on_event(self.viewer.gui.context(), event);
         }
 
+        self.update_held_fly_keys(&event);
+
         // fj-window events
         match event {
             Event::WindowEvent {
                 event: WindowEvent::CloseRequested,
                 ..
             } => {
-                *control_flow = ControlFlow::Exit;
+                self.exit(control_flow);
             }
             Event::WindowEvent {
                 event:
@@ -115,7 +267,7 @@ impl EventLoopHandler {
                     },
                 ..
             } => match virtual_key_code {
-                VirtualKeyCode::Escape => *control_flow = ControlFlow::Exit,
+                VirtualKeyCode::Escape => self.exit(control_flow),
                 VirtualKeyCode::Key1 => {
                     self.viewer.toggle_draw_model();
                 }
@@ -125,6 +277,30 @@ impl EventLoopHandler {
                 VirtualKeyCode::Key3 => {
                     self.viewer.toggle_draw_debug();
                 }
+                VirtualKeyCode::Key4 => {
+                    self.viewer.toggle_debug_on_top();
+                }
+                VirtualKeyCode::Key5 => {
+                    self.viewer.toggle_draw_scale_bar();
+                }
+                VirtualKeyCode::Key6 => {
+                    self.viewer.toggle_draw_gizmo();
+                }
+                VirtualKeyCode::Key7 => {
+                    self.viewer.toggle_projection();
+                }
+                VirtualKeyCode::Key8 => {
+                    self.viewer.toggle_navigation();
+                }
+                VirtualKeyCode::I => {
+                    self.viewer.toggle_part_isolation();
+                }
+                VirtualKeyCode::M => {
+                    self.viewer.toggle_measurement();
+                }
+                VirtualKeyCode::F12 => {
+                    self.save_screenshot(Path::new(&Screenshot::file_name()));
+                }
                 _ => {}
             },
             Event::WindowEvent {
@@ -141,10 +317,39 @@ impl EventLoopHandler {
                 ..
             } => match state {
                 ElementState::Pressed => {
+                    // A held button moves the camera, unless it grabbed the
+                    // gizmo.
+                    if button == MouseButton::Left && self.viewer.grab_gizmo() {
+                        return Ok(());
+                    }
+
                     self.held_mouse_button = Some(button);
+                    self.clicked_at = self.viewer.cursor;
                     self.viewer.add_focus_point();
                 }
                 ElementState::Released => {
+                    if let Some(code) = self.viewer.release_gizmo() {
+                        let part = self.viewer.gizmo.part().unwrap_or_default();
+                        self.status.update_status(&format!(
+                            "Moved part {part}. Append to it in the model:\n{code}"
+                        ));
+                    }
+
+                    // Only a click measures or selects. Dragging moves the
+                    // camera.
+                    let clicked =
+                        match (self.clicked_at.take(), self.viewer.cursor) {
+                            (Some(a), Some(b)) => {
+                                (a.x - b.x).abs().max((a.y - b.y).abs())
+                                    < MAX_CLICK_DISTANCE
+                            }
+                            _ => false,
+                        };
+                    if clicked && button == MouseButton::Left {
+                        self.evaluate_cached_shape()?;
+                        self.viewer.click();
+                    }
+
                     self.held_mouse_button = None;
                     self.viewer.remove_focus_point();
                 }
@@ -163,6 +368,21 @@ impl EventLoopHandler {
                     self.viewer.handle_screen_resize(size);
                 }
 
+                // A frame that took long, for example because a model was
+                // loaded, shouldn't make the camera jump.
+                let now = Instant::now();
+                let elapsed = (now - self.last_frame).min(MAX_FLY_FRAME_TIME);
+                self.last_frame = now;
+
+                let direction = self
+                    .held_fly_keys
+                    .iter()
+                    .filter_map(fly_direction)
+                    .fold(Vector::from([0., 0., 0.]), |sum, direction| {
+                        sum + Vector::from(direction)
+                    });
+                self.viewer.fly(direction, elapsed);
+
                 let pixels_per_point =
                     self.window.window().scale_factor() as f32;
 
@@ -173,15 +393,71 @@ impl EventLoopHandler {
                 let gui_state = GuiState {
                     status: &self.status,
                     model_available: self.host.is_some(),
+                    parameters: &self.parameters,
+                    evaluation_history: &self.evaluation_history,
+                    recent_models: self.recent_models.paths(),
+                    sample_models: &self.sample_models.names(),
                 };
-                let new_model_path =
+                let gui_output =
                     self.viewer.draw(pixels_per_point, egui_input, gui_state);
 
-                if let Some(model_path) = new_model_path {
-                    let model =
-                        Model::new(model_path, Parameters::empty()).unwrap();
+                // Laptops switching GPUs or drivers being reset can make the
+                // device go away at any time.
+                block_on(self.viewer.recover_from_device_loss())?;
+
+                if let Some(parameters) = gui_output.parameters {
+                    self.change_parameters(Parameters(parameters))?;
+                }
+
+                if let Some(tolerance) = gui_output.tolerance {
+                    self.retessellate(tolerance)?;
+                }
+
+                if let Some(path) = gui_output.bill_of_materials_path {
+                    self.export_bill_of_materials(&path)?;
+                }
+
+                if let Some(path) = gui_output.screenshot_path {
+                    self.save_screenshot(&path);
+                }
+
+                if let Some(analysis) = gui_output.analysis {
+                    self.analysis = analysis;
+                    update_analysis(
+                        &mut self.viewer,
+                        self.analysis,
+                        self.evaluated_shape.as_ref(),
+                    );
+                }
+
+                let model = match (
+                    gui_output.new_model_path,
+                    gui_output.sample_model,
+                ) {
+                    (Some(model_path), _) => {
+                        Some(Model::new(model_path, Parameters::empty())?)
+                    }
+                    (None, Some(name)) => self
+                        .sample_models
+                        .load(&name, Parameters::empty())
+                        .transpose()?,
+                    (None, None) => None,
+                };
+
+                if let Some(model) = model {
+                    if let Err(err) = self.recent_models.add(model.path()) {
+                        warn!("Failed to update list of recent models: {err}");
+                    }
+
+                    self.model_path = Some(model.path().to_path_buf());
+                    self.parameters = model.parameters().clone();
+                    self.seed = model.seed();
+                    self.thumbnail_path = Some(model.thumbnail_path());
+
+                    // The previous host is shut down when it's dropped.
                     let new_host = Host::from_model(model)?;
                     self.host = Some(new_host);
+                    self.evaluation_history.clear();
                 }
             }
             _ => {}
@@ -197,6 +473,234 @@ impl EventLoopHandler {
         if let Some(input_event) = input_event {
             self.viewer.handle_input_event(input_event);
         }
+        if let Event::WindowEvent {
+            event: WindowEvent::CursorMoved { .. },
+            ..
+        } = event
+        {
+            self.viewer.drag_gizmo();
+            self.evaluate_cached_shape()?;
+            self.viewer.hover();
+        }
+
+        Ok(())
+    }
+
+    /// Keep track of the keys that move the camera in fly mode
+    fn update_held_fly_keys(&mut self, event: &Event<()>) {
+        match event {
+            Event::WindowEvent {
+                event: WindowEvent::KeyboardInput { input, .. },
+                ..
+            } => {
+                let Some(key) = input
+                    .virtual_keycode
+                    .filter(|key| fly_direction(key).is_some())
+                else {
+                    return;
+                };
+
+                match input.state {
+                    // Keys that are typed into the GUI aren't meant for the
+                    // camera.
+                    ElementState::Pressed
+                        if !self
+                            .viewer
+                            .gui
+                            .context()
+                            .wants_keyboard_input() =>
+                    {
+                        self.held_fly_keys.insert(key);
+                    }
+                    ElementState::Pressed => {}
+                    ElementState::Released => {
+                        self.held_fly_keys.remove(&key);
+                    }
+                }
+            }
+            Event::WindowEvent {
+                event: WindowEvent::Focused(false),
+                ..
+            } => {
+                // Releasing a key isn't reported to an unfocused window.
+                self.held_fly_keys.clear();
+            }
+            _ => {}
+        }
+    }
+
+    /// Shut down the model host and exit the event loop
+    ///
+    /// The event loop never returns, so the host would not be dropped
+    /// otherwise, leaving a model build running in the background.
+    fn exit(&mut self, control_flow: &mut ControlFlow) {
+        if let Some(host) = self.host.take() {
+            host.shutdown();
+        }
+
+        *control_flow = ControlFlow::Exit;
+    }
+
+    /// Write a thumbnail of the shape next to the model, if enabled
+    ///
+    /// Failing to do so doesn't affect the model, so it's only logged.
+    fn write_thumbnail(&self, shape: &ProcessedShape) {
+        let (Some(size), Some(path)) =
+            (self.thumbnail_size, &self.thumbnail_path)
+        else {
+            return;
+        };
+
+        if let Err(err) = fj_export::export_thumbnail(&shape.mesh, size, path) {
+            warn!("Failed to write thumbnail to `{}`: {err}", path.display());
+        }
+    }
+
+    /// Evaluate the current model again, with the given parameters
+    ///
+    /// Parameters that can't be evaluated are shown in the status report, and
+    /// the model keeps the parameters it has.
+    #[allow(clippy::result_large_err)]
+    fn change_parameters(
+        &mut self,
+        parameters: Parameters,
+    ) -> Result<(), Error> {
+        let Some(path) = &self.model_path else {
+            return Ok(());
+        };
+
+        if let Err(err) = parameters.evaluate() {
+            self.status
+                .update_status(&format!("Invalid parameters: {err}"));
+            return Ok(());
+        }
+
+        let model = Model::new(path, parameters)?.with_seed(self.seed);
+        self.parameters = model.parameters().clone();
+
+        // The previous host is shut down when it's dropped.
+        self.host = Some(Host::from_model(model)?);
+
+        Ok(())
+    }
+
+    /// Write a bill of materials of the current shape to the given path
+    #[allow(clippy::result_large_err)]
+    fn export_bill_of_materials(&mut self, path: &Path) -> Result<(), Error> {
+        let Some(shape) = &self.viewer.shape else {
+            return Ok(());
+        };
+
+        let parts = shape
+            .parts
+            .iter()
+            .map(|part| Part {
+                name: &part.name,
+                mesh: &part.mesh,
+                units: part.units,
+            })
+            .collect::<Vec<_>>();
+        let bom = BillOfMaterials::new(&parts, self.parameters.0.clone());
+
+        fj_export::export_bom(&bom, path)?;
+        self.status.update_status(&format!(
+            "Bill of materials saved to `{}`.",
+            path.display()
+        ));
+
+        Ok(())
+    }
+
+    /// Save a screenshot of the model, as it is currently shown
+    ///
+    /// Failing to save a screenshot is not a reason to stop the application,
+    /// so errors are shown in the status report.
+    fn save_screenshot(&mut self, path: &Path) {
+        let result = self
+            .viewer
+            .screenshot()
+            .and_then(|screenshot| screenshot.save(path));
+
+        match result {
+            Ok(()) => self.status.update_status(&format!(
+                "Screenshot saved to `{}`.",
+                path.display()
+            )),
+            Err(err) => self.status.update_status(&format!(
+                "Failed to save screenshot to `{}`: {err}",
+                path.display()
+            )),
+        }
+    }
+
+    /// Evaluate the current shape, if it was loaded from the mesh cache
+    ///
+    /// The triangles of a cached mesh don't know which faces, edges, and
+    /// vertices they were triangulated from, so nothing could be picked or
+    /// highlighted. The mesh is replaced with one that does.
+    #[allow(clippy::result_large_err)]
+    fn evaluate_cached_shape(&mut self) -> Result<(), Error> {
+        let Some(shape) = self.unevaluated_shape.take() else {
+            return Ok(());
+        };
+
+        let evaluated = self.shape_processor.evaluate(&shape)?;
+        let shape = evaluated.triangulate_with_cache(
+            evaluated.tolerance(),
+            &mut self.tessellation_cache,
+        );
+        self.evaluated_shape = Some(evaluated);
+
+        self.viewer.handle_shape_update(shape);
+        update_analysis(
+            &mut self.viewer,
+            self.analysis,
+            self.evaluated_shape.as_ref(),
+        );
+        update_interference(&mut self.viewer);
+
+        Ok(())
+    }
+
+    /// Triangulate the current shape again, without evaluating the model
+    #[allow(clippy::result_large_err)]
+    fn retessellate(&mut self, tolerance: Scalar) -> Result<(), Error> {
+        let tolerance = Tolerance::from_scalar(tolerance)
+            .map_err(shape_processor::Error::from)?;
+
+        // Use the new tolerance for future evaluations of the model too.
+        self.shape_processor.tolerance = Some(tolerance);
+
+        if let Some(shape) = self.unevaluated_shape.take() {
+            self.evaluated_shape = Some(self.shape_processor.evaluate(&shape)?);
+        }
+
+        if let Some(evaluated) = &self.evaluated_shape {
+            // Only the distance is set by the user. Keep refining curved
+            // geometry as before.
+            let tolerance = match evaluated.tolerance().max_angle() {
+                Some(max_angle) => tolerance
+                    .with_max_angle(max_angle)
+                    .map_err(shape_processor::Error::from)?,
+                None => tolerance,
+            };
+
+            let shape = evaluated.triangulate_with_cache(
+                tolerance,
+                &mut self.tessellation_cache,
+            );
+            self.status.update_status(&format!(
+                "Model triangulated with tolerance {:.2e}.",
+                tolerance.inner().into_f64()
+            ));
+            self.viewer.handle_shape_update(shape);
+            update_analysis(
+                &mut self.viewer,
+                self.analysis,
+                self.evaluated_shape.as_ref(),
+            );
+            update_interference(&mut self.viewer);
+        }
 
         Ok(())
     }
@@ -271,6 +775,12 @@ pub enum Error {
 
     #[error("Shape processing error")]
     ShapeProcessor(#[from] shape_processor::Error),
+
+    #[error("Error re-initializing graphics")]
+    GraphicsInit(#[from] RendererInitError),
+
+    #[error("Error exporting bill of materials")]
+    Export(#[from] fj_export::Error),
 }
 
 /// Affects the speed of zoom movement given a scroll wheel input in lines.
@@ -290,3 +800,173 @@ const ZOOM_FACTOR_PIXEL: f64 = 0.005;
 /// Smaller values will move the camera less with the same input.
 /// Larger values will move the camera more with the same input.
 const ROTATION_SENSITIVITY: f64 = 5.;
+
+/// How far the cursor can move between pressing and releasing a mouse button,
+/// for it to still be a click
+///
+/// Given in normalized screen coordinates.
+const MAX_CLICK_DISTANCE: f64 = 0.005;
+
+/// The longest time that the camera keeps moving for, between two frames
+const MAX_FLY_FRAME_TIME: Duration = Duration::from_millis(100);
+
+/// Map a key to the direction it moves the camera in, in fly mode
+///
+/// The direction is given in camera space.
+fn fly_direction(key: &VirtualKeyCode) -> Option<[f64; 3]> {
+    let direction = match key {
+        VirtualKeyCode::W => [0., 0., -1.],
+        VirtualKeyCode::S => [0., 0., 1.],
+        VirtualKeyCode::A => [-1., 0., 0.],
+        VirtualKeyCode::D => [1., 0., 0.],
+        VirtualKeyCode::Q => [0., -1., 0.],
+        VirtualKeyCode::E => [0., 1., 0.],
+        _ => return None,
+    };
+
+    Some(direction)
+}
+
+/// Analyze the current shape, and pass the results to the viewer
+///
+/// The evaluated shape, if available, provides the exact geometry for analyses
+/// that can make use of it.
+fn update_analysis(
+    viewer: &mut Viewer,
+    mode: Analysis,
+    evaluated: Option<&EvaluatedShape>,
+) {
+    let Some(shape) = &viewer.shape else {
+        return;
+    };
+
+    let values = match mode {
+        Analysis::None => None,
+        Analysis::Draft { pull_direction } => {
+            let values = analysis::draft_angles(&shape.mesh, pull_direction)
+                .into_iter()
+                .map(Some)
+                .collect();
+
+            // Anything above a few degrees is plenty for most molds.
+            let max_draft = Scalar::from(3_f64.to_radians());
+
+            Some(TriangleValues {
+                values,
+                range: [-max_draft, max_draft],
+            })
+        }
+        Analysis::WallThickness => {
+            let values = analysis::wall_thickness(&shape.mesh);
+
+            let min = values.iter().flatten().min().copied();
+            let max = values.iter().flatten().max().copied();
+
+            Some(TriangleValues {
+                values,
+                range: [
+                    min.unwrap_or(Scalar::ZERO),
+                    max.unwrap_or(Scalar::ZERO),
+                ],
+            })
+        }
+        Analysis::Printability {
+            build_direction,
+            max_overhang,
+            min_thickness,
+        } => {
+            let values = analysis::printability(
+                &shape.mesh,
+                build_direction,
+                max_overhang,
+                min_thickness,
+            )
+            .into_iter()
+            .map(|printability| {
+                // Red for overhangs, which are the bigger problem, yellow for
+                // thin walls, and green for the rest.
+                let value = if printability.overhang {
+                    0.
+                } else if printability.too_thin {
+                    0.5
+                } else {
+                    1.
+                };
+
+                Some(Scalar::from(value))
+            })
+            .collect();
+
+            Some(TriangleValues {
+                values,
+                range: [Scalar::ZERO, Scalar::ONE],
+            })
+        }
+        Analysis::Curvature { kind } => {
+            // The mesh records the tolerance it was triangulated with. The
+            // maximum angle is always the one of the evaluated shape.
+            let evaluated = evaluated.and_then(|evaluated| {
+                let tolerance = Tolerance::from_scalar(shape.tolerance).ok()?;
+                let tolerance = match evaluated.tolerance().max_angle() {
+                    Some(max_angle) => {
+                        tolerance.with_max_angle(max_angle).ok()?
+                    }
+                    None => tolerance,
+                };
+                Some((evaluated, tolerance))
+            });
+            let curvature = match evaluated {
+                Some((evaluated, tolerance)) => {
+                    evaluated.curvature(&shape.mesh, tolerance)
+                }
+                None => analysis::mesh_curvature(&shape.mesh),
+            };
+
+            let values = curvature
+                .into_iter()
+                .map(|curvature| match kind {
+                    CurvatureKind::Gaussian => curvature.gaussian,
+                    CurvatureKind::Mean => curvature.mean,
+                })
+                .collect::<Vec<_>>();
+
+            // Sharp edges are much more strongly curved than anything else.
+            // Limit the range to the bulk of the values, so the edges don't
+            // wash out the rest, and center it on zero, so flat triangles are
+            // always yellow.
+            let mut magnitudes =
+                values.iter().map(|value| value.abs()).collect::<Vec<_>>();
+            magnitudes.sort();
+            let limit = magnitudes
+                .get(magnitudes.len() * 9 / 10)
+                .copied()
+                .unwrap_or(Scalar::ZERO);
+
+            Some(TriangleValues {
+                values: values.into_iter().map(Some).collect(),
+                range: [-limit, limit],
+            })
+        }
+    };
+
+    viewer.handle_analysis_update(values);
+}
+
+/// Check the reference bodies against the parts of the current shape
+fn update_interference(viewer: &mut Viewer) {
+    let Some(shape) = &viewer.shape else {
+        return;
+    };
+
+    for reference in &mut viewer.references {
+        reference.interference = shape
+            .parts
+            .iter()
+            .map(|part| {
+                let interference =
+                    analysis::interference(&part.mesh, &reference.mesh);
+                (part.name.clone(), interference)
+            })
+            .collect();
+    }
+}
@@ -13,7 +13,9 @@
 
 #![warn(missing_docs)]
 
+pub mod recent_models;
 pub mod run;
+pub mod sample_models;
 pub mod window;
 
 mod event_loop_handler;
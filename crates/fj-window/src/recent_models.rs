@@ -0,0 +1,96 @@
+//! The list of recently opened models
+//!
+//! See [`RecentModels`].
+
+use std::{
+    fs, io,
+    path::{Path, PathBuf},
+};
+
+/// The models that were opened most recently, starting with the latest one
+///
+/// If the list was loaded from a file, every change is written back to it,
+/// which makes the list survive restarts of the application. The file contains
+/// one path per line.
+#[derive(Clone, Debug, Default)]
+pub struct RecentModels {
+    file: Option<PathBuf>,
+    paths: Vec<PathBuf>,
+}
+
+impl RecentModels {
+    /// The maximum number of models kept in the list
+    pub const CAPACITY: usize = 10;
+
+    /// Create an empty list that is not persisted
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Load the list from the given file
+    ///
+    /// If the file doesn't exist yet, the list is empty. It is created, once
+    /// the first model is added.
+    pub fn load(file: impl Into<PathBuf>) -> io::Result<Self> {
+        let file = file.into();
+
+        let paths = match fs::read_to_string(&file) {
+            Ok(contents) => contents
+                .lines()
+                .map(str::trim)
+                .filter(|line| !line.is_empty())
+                .map(PathBuf::from)
+                .take(Self::CAPACITY)
+                .collect(),
+            Err(err) if err.kind() == io::ErrorKind::NotFound => Vec::new(),
+            Err(err) => return Err(err),
+        };
+
+        Ok(Self {
+            file: Some(file),
+            paths,
+        })
+    }
+
+    /// Access the paths of the models, starting with the latest one
+    pub fn paths(&self) -> &[PathBuf] {
+        &self.paths
+    }
+
+    /// Access the path of the model that was opened most recently
+    pub fn latest(&self) -> Option<&Path> {
+        self.paths.first().map(PathBuf::as_path)
+    }
+
+    /// Record that a model was opened
+    ///
+    /// The model moves to the front of the list. If the list was loaded from a
+    /// file, the file is updated.
+    pub fn add(&mut self, path: impl Into<PathBuf>) -> io::Result<()> {
+        let path = path.into();
+
+        self.paths.retain(|recent| recent != &path);
+        self.paths.insert(0, path);
+        self.paths.truncate(Self::CAPACITY);
+
+        self.save()
+    }
+
+    fn save(&self) -> io::Result<()> {
+        let Some(file) = &self.file else {
+            return Ok(());
+        };
+
+        if let Some(dir) = file.parent() {
+            fs::create_dir_all(dir)?;
+        }
+
+        let mut contents = String::new();
+        for path in &self.paths {
+            contents.push_str(&path.to_string_lossy());
+            contents.push('\n');
+        }
+
+        fs::write(file, contents)
+    }
+}
@@ -4,45 +4,105 @@
 //! with programmed models.
 
 use std::{
+    collections::HashSet,
     error,
     fmt::{self, Write},
+    time::Instant,
 };
 
-use fj_host::{Host, Model};
-use fj_operations::shape_processor::ShapeProcessor;
-use fj_viewer::{RendererInitError, StatusReport, Viewer};
+use fj_host::{Host, Model, Parameters};
+use fj_operations::{
+    mesh_cache::MeshCache, shape_processor::ShapeProcessor,
+    tessellation_cache::TessellationCache,
+};
+use fj_viewer::{
+    Analysis, EvaluationHistory, Reference, RendererInitError, StatusReport,
+    Viewer,
+};
 use futures::executor::block_on;
 use tracing::trace;
 use winit::event_loop::EventLoop;
 
 use crate::{
     event_loop_handler::{self, EventLoopHandler},
+    recent_models::RecentModels,
+    sample_models::SampleModels,
     window::{self, Window},
 };
 
 /// Initializes a model viewer for a given model and enters its process loop.
+///
+/// Models that are opened through the GUI are added to `recent_models`. The
+/// GUI also offers to switch to any of the models in that list.
+///
+/// If no model is passed, the GUI offers to open one of `sample_models`.
+///
+/// The `references` are displayed alongside the model, and checked for
+/// interference with it, whenever it is updated.
+///
+/// If a `mesh_cache` is passed, processed shapes are stored in it, and shapes
+/// that are found in it are displayed without processing them again.
+///
+/// The model is drawn with `sample_count` samples per pixel. See
+/// [`fj_viewer::Renderer::new`].
+///
+/// If a `thumbnail_size` is passed, a thumbnail of that size is written next
+/// to the model, after each successful evaluation. See
+/// [`Model::thumbnail_path`].
+#[allow(clippy::too_many_arguments)]
 pub fn run(
     model: Option<Model>,
     shape_processor: ShapeProcessor,
+    mesh_cache: Option<MeshCache>,
     invert_zoom: bool,
+    sample_count: u32,
+    recent_models: RecentModels,
+    sample_models: SampleModels,
+    references: Vec<Reference>,
+    thumbnail_size: Option<u32>,
 ) -> Result<(), Error> {
     let event_loop = EventLoop::new();
     let window = Window::new(&event_loop)?;
-    let viewer = block_on(Viewer::new(&window))?;
+    let mut viewer = block_on(Viewer::new(&window, sample_count))?;
+    for reference in references {
+        viewer.add_reference(reference);
+    }
 
     let egui_winit_state = egui_winit::State::new(&event_loop);
 
+    let model_path = model.as_ref().map(|model| model.path().to_path_buf());
+    let parameters = model
+        .as_ref()
+        .map_or_else(Parameters::empty, |model| model.parameters().clone());
+    let seed = model.as_ref().map_or(0, Model::seed);
+    let thumbnail_path = model.as_ref().map(Model::thumbnail_path);
     let host = model.map(Host::from_model).transpose()?;
 
     let mut handler = EventLoopHandler {
         invert_zoom,
         shape_processor,
+        mesh_cache,
+        unevaluated_shape: None,
+        evaluated_shape: None,
+        tessellation_cache: TessellationCache::new(),
         window,
         viewer,
         egui_winit_state,
         host,
+        model_path,
+        parameters,
+        seed,
+        thumbnail_size,
+        thumbnail_path,
         status: StatusReport::new(),
+        evaluation_history: EvaluationHistory::new(),
+        recent_models,
+        sample_models,
+        analysis: Analysis::None,
         held_mouse_button: None,
+        clicked_at: None,
+        held_fly_keys: HashSet::new(),
+        last_frame: Instant::now(),
         new_size: None,
     };
 
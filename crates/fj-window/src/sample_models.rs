@@ -0,0 +1,63 @@
+//! Sample models that new users can open with a click
+//!
+//! See [`SampleModels`].
+
+use std::path::PathBuf;
+
+use fj_host::{EmbeddedModel, FjDependency, Model, Parameters};
+
+/// Models that are embedded into the application, to serve as examples
+///
+/// The GUI offers to open those, if no model was loaded on startup.
+#[derive(Clone, Debug)]
+pub struct SampleModels {
+    dir: PathBuf,
+    fj: FjDependency,
+    models: Vec<EmbeddedModel>,
+}
+
+impl SampleModels {
+    /// Create a new instance of `SampleModels`
+    ///
+    /// When a model is opened, it is unpacked into `dir` first, depending on
+    /// the `fj` crate as specified by `fj`.
+    pub fn new(
+        dir: impl Into<PathBuf>,
+        fj: FjDependency,
+        models: impl IntoIterator<Item = EmbeddedModel>,
+    ) -> Self {
+        Self {
+            dir: dir.into(),
+            fj,
+            models: models.into_iter().collect(),
+        }
+    }
+
+    /// Create an instance that doesn't contain any models
+    pub fn none() -> Self {
+        Self::new(PathBuf::new(), FjDependency::Path(PathBuf::new()), [])
+    }
+
+    /// Access the names of the models
+    pub fn names(&self) -> Vec<&'static str> {
+        self.models.iter().map(|model| model.name).collect()
+    }
+
+    /// Unpack and load the model with the given name
+    ///
+    /// Returns `None`, if there is no model with that name.
+    pub fn load(
+        &self,
+        name: &str,
+        parameters: Parameters,
+    ) -> Option<Result<Model, fj_host::Error>> {
+        let model = self.models.iter().find(|model| model.name == name)?;
+
+        let model = model
+            .unpack(&self.dir, &self.fj)
+            .map_err(fj_host::Error::from)
+            .and_then(|path| Model::new(path, parameters));
+
+        Some(model)
+    }
+}
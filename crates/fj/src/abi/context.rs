@@ -1,12 +1,14 @@
 use std::{marker::PhantomData, os::raw::c_void, panic::AssertUnwindSafe};
 
-use crate::abi::ffi_safe::StringSlice;
+use crate::abi::ffi_safe::{Allocator, StringSlice};
 
 #[repr(C)]
 pub struct Context<'a> {
     user_data: *const c_void,
     get_argument:
         unsafe extern "C" fn(*const c_void, StringSlice) -> StringSlice,
+    allocator: Allocator,
+    seed: u64,
     _lifetime: PhantomData<&'a ()>,
 }
 
@@ -31,18 +33,27 @@ impl<'a> From<&'a &dyn crate::models::Context> for Context<'a> {
             user_data: ctx as *const &dyn crate::models::Context
                 as *const c_void,
             get_argument,
+            allocator: Allocator::global(),
+            seed: ctx.seed(),
             _lifetime: PhantomData,
         }
     }
 }
 
+impl Context<'_> {
+    /// Access the allocator of the host
+    pub fn allocator(&self) -> Allocator {
+        self.allocator
+    }
+}
+
 impl crate::models::Context for Context<'_> {
     fn get_argument(&self, name: &str) -> Option<&str> {
         unsafe {
             let Context {
                 user_data,
                 get_argument,
-                _lifetime,
+                ..
             } = *self;
 
             let name = StringSlice::from_str(name);
@@ -53,4 +64,8 @@ impl crate::models::Context for Context<'_> {
             }
         }
     }
+
+    fn seed(&self) -> u64 {
+        self.seed
+    }
 }
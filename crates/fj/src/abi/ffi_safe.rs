@@ -1,19 +1,176 @@
 //! FFI-safe versions of common `std` types.
 
 use std::{
-    alloc::{GlobalAlloc, Layout, System},
+    alloc::{handle_alloc_error, Layout},
+    cell::Cell,
     fmt::{self, Debug, Display, Formatter},
+    mem::{self, ManuallyDrop},
     ops::Deref,
     ptr::NonNull,
 };
 
 use crate::models::Error;
 
+/// An allocator that is shared between host and model
+///
+/// Data that a model returns to the host outlives the model's library, which
+/// the host unloads after evaluating the model. Any memory that is passed to
+/// the host must therefore be allocated (and later freed) by code that lives in
+/// the host. The host passes its allocator to the model with each call, and
+/// all FFI-safe collections that are created during that call use it.
+///
+/// Outside of such a call, the global allocator of the current binary is used.
+#[derive(Clone, Copy, Debug)]
+#[repr(C)]
+pub struct Allocator {
+    alloc: unsafe extern "C" fn(size: usize, align: usize) -> *mut u8,
+    dealloc: unsafe extern "C" fn(ptr: *mut u8, size: usize, align: usize),
+}
+
+impl Allocator {
+    /// The global allocator of the binary that calls this function
+    pub fn global() -> Self {
+        unsafe extern "C" fn alloc(size: usize, align: usize) -> *mut u8 {
+            std::alloc::alloc(Layout::from_size_align_unchecked(size, align))
+        }
+
+        unsafe extern "C" fn dealloc(ptr: *mut u8, size: usize, align: usize) {
+            std::alloc::dealloc(
+                ptr,
+                Layout::from_size_align_unchecked(size, align),
+            );
+        }
+
+        Self { alloc, dealloc }
+    }
+
+    /// The allocator that FFI-safe collections currently use
+    ///
+    /// See [`with_allocator`].
+    pub fn current() -> Self {
+        CURRENT_ALLOCATOR
+            .with(Cell::get)
+            .unwrap_or_else(Self::global)
+    }
+
+    /// Indicate whether this is the global allocator of the current binary
+    ///
+    /// Memory from the global allocator can be shared with `std` collections,
+    /// which makes it possible to convert between them without copying.
+    fn is_global(&self) -> bool {
+        let global = Self::global();
+
+        self.alloc as usize == global.alloc as usize
+            && self.dealloc as usize == global.dealloc as usize
+    }
+
+    fn allocate<T>(&self, capacity: usize) -> NonNull<T> {
+        let layout = Layout::array::<T>(capacity).expect("Capacity overflow");
+        if layout.size() == 0 {
+            return NonNull::dangling();
+        }
+
+        // Safety: The size of the layout is not zero.
+        let ptr = unsafe { (self.alloc)(layout.size(), layout.align()) };
+        NonNull::new(ptr.cast()).unwrap_or_else(|| handle_alloc_error(layout))
+    }
+
+    /// Free memory that was allocated with [`Allocator::allocate`]
+    ///
+    /// # Safety
+    ///
+    /// `ptr` must have been allocated by this allocator, with `capacity`.
+    unsafe fn deallocate<T>(&self, ptr: NonNull<T>, capacity: usize) {
+        let layout = Layout::array::<T>(capacity).expect("Capacity overflow");
+        if layout.size() == 0 {
+            return;
+        }
+
+        (self.dealloc)(ptr.as_ptr().cast(), layout.size(), layout.align());
+    }
+}
+
+thread_local! {
+    static CURRENT_ALLOCATOR: Cell<std::option::Option<Allocator>> =
+        const { Cell::new(None) };
+}
+
+/// Run a function, with FFI-safe collections using the provided allocator
+pub fn with_allocator<R>(allocator: Allocator, f: impl FnOnce() -> R) -> R {
+    struct Restore(std::option::Option<Allocator>);
+
+    impl Drop for Restore {
+        fn drop(&mut self) {
+            CURRENT_ALLOCATOR.with(|current| current.set(self.0));
+        }
+    }
+
+    let _restore = Restore(
+        CURRENT_ALLOCATOR.with(|current| current.replace(Some(allocator))),
+    );
+
+    f()
+}
+
 /// A FFI-safe version of `Vec<T>`.
+///
+/// The buffer is allocated by the [`Allocator`] that is current when the
+/// `Vec` is created, and is freed by the same allocator. Conversions from and
+/// to `std`'s `Vec` reuse its buffer, if that allocator is the global one.
 #[repr(C)]
 pub(crate) struct Vec<T> {
     ptr: NonNull<T>,
     len: usize,
+    capacity: usize,
+    allocator: Allocator,
+}
+
+impl<T> Vec<T> {
+    /// Create an empty `Vec` that can hold `capacity` items without
+    /// reallocating
+    pub fn with_capacity(capacity: usize) -> Self {
+        let allocator = Allocator::current();
+
+        Vec {
+            ptr: allocator.allocate(capacity),
+            len: 0,
+            capacity,
+            allocator,
+        }
+    }
+
+    /// Add an item to the end of the `Vec`
+    pub fn push(&mut self, item: T) {
+        if self.len == self.capacity {
+            self.grow(self.len + 1);
+        }
+
+        // Safety: We just made sure that there's enough capacity for the item.
+        unsafe {
+            self.ptr.as_ptr().add(self.len).write(item);
+        }
+        self.len += 1;
+    }
+
+    fn grow(&mut self, min_capacity: usize) {
+        let capacity = usize::max(self.capacity * 2, min_capacity).max(4);
+        let ptr = self.allocator.allocate(capacity);
+
+        // Safety: Both buffers are valid for `len` items, and they don't
+        // overlap. The items are moved into the new buffer, so we must not
+        // drop them when freeing the old one.
+        unsafe {
+            std::ptr::copy_nonoverlapping(
+                self.ptr.as_ptr(),
+                ptr.as_ptr(),
+                self.len,
+            );
+            self.allocator.deallocate(self.ptr, self.capacity);
+        }
+
+        self.ptr = ptr;
+        self.capacity = capacity;
+    }
 }
 
 impl<T: Debug> Debug for Vec<T> {
@@ -29,40 +186,84 @@ impl<T: PartialEq> PartialEq for Vec<T> {
 }
 
 impl<T> From<std::vec::Vec<T>> for Vec<T> {
-    fn from(mut items: std::vec::Vec<T>) -> Self {
-        // Safety: To avoid accidental double-frees and other memory issues, we
-        // need to go through a specific dance.
+    fn from(items: std::vec::Vec<T>) -> Self {
+        let mut items = ManuallyDrop::new(items);
+        let allocator = Allocator::current();
+
+        // Zero-sized types never allocate, so there's nothing to gain from
+        // reusing the buffer.
+        if allocator.is_global() && mem::size_of::<T>() != 0 {
+            // Safety: `items` won't be used again, so we can take over its
+            // buffer. It was allocated by the global allocator, which is the
+            // same one we're going to free it with.
+            return Vec {
+                ptr: NonNull::new(items.as_mut_ptr())
+                    .expect("`Vec` pointers are never null"),
+                len: items.len(),
+                capacity: items.capacity(),
+                allocator,
+            };
+        }
+
+        let len = items.len();
+        let vec = Vec {
+            ptr: allocator.allocate(len),
+            len,
+            capacity: len,
+            allocator,
+        };
+
+        // Safety: Both buffers are valid for `len` items, and they don't
+        // overlap. We move the items out of `items`, so we must only free its
+        // buffer afterwards, without dropping them again.
         unsafe {
-            // first, get a pointer to the first element and its length
-            let first_item = items.as_mut_ptr();
-            let len = items.len();
-
-            // next, tell Vec to forget about these items so it won't try to
-            // run their destructors if we return early (e.g. via a panic).
-            // We've now taken over ownership of the items, but *not* the Vec's
-            // backing array.
+            std::ptr::copy_nonoverlapping(
+                items.as_ptr(),
+                vec.ptr.as_ptr(),
+                len,
+            );
             items.set_len(0);
+            ManuallyDrop::drop(&mut items);
+        }
 
-            // Use the system allocator to create some space for our
-            // FfiSafeVec's buffer.
-            let layout = Layout::array::<T>(len).unwrap();
-            let ptr: *mut T = System::default().alloc(layout).cast();
-            let ptr = NonNull::new(ptr).expect("Allocation failed");
+        vec
+    }
+}
 
-            // Now, we can copy the items across
-            std::ptr::copy_nonoverlapping(first_item, ptr.as_ptr(), len);
+impl<T> From<Vec<T>> for std::vec::Vec<T> {
+    fn from(v: Vec<T>) -> Self {
+        let v = ManuallyDrop::new(v);
+        let Vec {
+            ptr,
+            len,
+            capacity,
+            allocator,
+        } = *v;
+
+        if allocator.is_global() && mem::size_of::<T>() != 0 {
+            // Safety: The buffer was allocated by the global allocator, with
+            // the given capacity, and `v` won't free it.
+            return unsafe {
+                std::vec::Vec::from_raw_parts(ptr.as_ptr(), len, capacity)
+            };
+        }
 
-            // the items are gone, time to free the original vec
-            drop(items);
+        let mut items = std::vec::Vec::with_capacity(len);
 
-            Vec { ptr, len }
+        // Safety: Both buffers are valid for `len` items, and they don't
+        // overlap. The items are moved into `items`, so we only free the
+        // buffer of `v`, without dropping them.
+        unsafe {
+            std::ptr::copy_nonoverlapping(
+                ptr.as_ptr(),
+                items.as_mut_ptr(),
+                len,
+            );
+            items.set_len(len);
+            allocator.deallocate(ptr, capacity);
         }
-    }
-}
 
-impl<T: Clone> From<Vec<T>> for std::vec::Vec<T> {
-    fn from(v: Vec<T>) -> Self {
-        v.iter().map(Clone::clone).collect()
+        items
     }
 }
 
@@ -74,20 +275,28 @@ impl<T: Clone> Clone for Vec<T> {
 
 impl<T: Copy> From<Vec<T>> for Box<[T]> {
     fn from(v: Vec<T>) -> Self {
-        Box::from(&*v)
+        std::vec::Vec::from(v).into_boxed_slice()
     }
 }
 
 impl<T> Default for Vec<T> {
     fn default() -> Self {
-        std::vec::Vec::default().into()
+        Self::with_capacity(0)
     }
 }
 
 impl<T> FromIterator<T> for Vec<T> {
     fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
-        let vec: std::vec::Vec<T> = iter.into_iter().collect();
-        vec.into()
+        // Build the `Vec` in place, instead of collecting into a `std` one and
+        // converting that.
+        let iter = iter.into_iter();
+
+        let mut vec = Self::with_capacity(iter.size_hint().0);
+        for item in iter {
+            vec.push(item);
+        }
+
+        vec
     }
 }
 
@@ -97,39 +306,30 @@ impl<T> Deref for Vec<T> {
     fn deref(&self) -> &Self::Target {
         // Safety: We control "ptr" and "len", so we know they are always
         // initialized and within bounds.
-        unsafe {
-            let Vec { ptr, len } = *self;
-            std::slice::from_raw_parts(ptr.as_ptr(), len)
-        }
+        unsafe { std::slice::from_raw_parts(self.ptr.as_ptr(), self.len) }
     }
 }
 
 impl<T> Drop for Vec<T> {
     fn drop(&mut self) {
-        let Vec { ptr, len } = *self;
-        let ptr = ptr.as_ptr();
-
-        for i in 0..self.len {
-            // Safety: We control the "len" field, so the item we're accessing
-            // is always within bounds. We also don't touch values after their
-            // destructors are called.
-            unsafe {
-                let item = ptr.add(i);
-                std::ptr::drop_in_place(item);
-            }
-        }
-
-        // Safety: This vec is immutable, so we're using the same layout as the
-        // original allocation. It's also not possible to touch the allocation
-        // after Drop completes.
+        // Safety: We control the "len" field, so the items we're accessing are
+        // always within bounds. We also don't touch values after their
+        // destructors are called.
+        //
+        // The buffer was allocated by our allocator, with our capacity. It's
+        // not possible to touch the allocation after Drop completes.
         unsafe {
-            let layout = Layout::array::<T>(len).unwrap();
-            System::default().dealloc(ptr.cast(), layout);
+            std::ptr::drop_in_place(std::ptr::slice_from_raw_parts_mut(
+                self.ptr.as_ptr(),
+                self.len,
+            ));
+            self.allocator.deallocate(self.ptr, self.capacity);
         }
     }
 }
 
-// Safety: We're Send+Sync as long as the underlying type is.
+// Safety: We're Send+Sync as long as the underlying type is. The allocator is
+// just a pair of function pointers, which are safe to call from any thread.
 unsafe impl<T: Send> Send for Vec<T> {}
 unsafe impl<T: Sync> Sync for Vec<T> {}
 
@@ -212,6 +412,29 @@ impl Deref for String {
     }
 }
 
+#[cfg(feature = "serde")]
+impl serde::ser::Serialize for String {
+    fn serialize<S>(
+        &self,
+        serializer: S,
+    ) -> std::result::Result<S::Ok, S::Error>
+    where
+        S: serde::ser::Serializer,
+    {
+        self.deref().serialize(serializer)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::de::Deserialize<'de> for String {
+    fn deserialize<D>(deserializer: D) -> std::result::Result<Self, D::Error>
+    where
+        D: serde::de::Deserializer<'de>,
+    {
+        Ok(std::string::String::deserialize(deserializer)?.into())
+    }
+}
+
 /// A version of `Result` that is `#[repr(C)]`.
 #[must_use]
 #[repr(C)]
@@ -362,7 +585,7 @@ impl From<Error> for BoxedError {
     }
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Copy, PartialEq)]
 #[repr(C)]
 pub enum Option<T> {
     Some(T),
@@ -398,3 +621,86 @@ impl<T> From<Option<T>> for std::option::Option<T> {
         }
     }
 }
+
+#[cfg(feature = "serde")]
+impl<T> serde::ser::Serialize for Option<T>
+where
+    T: serde::ser::Serialize + Clone,
+{
+    fn serialize<S>(
+        &self,
+        serializer: S,
+    ) -> std::result::Result<S::Ok, S::Error>
+    where
+        S: serde::ser::Serializer,
+    {
+        std::option::Option::<T>::from(self.clone()).serialize(serializer)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de, T> serde::de::Deserialize<'de> for Option<T>
+where
+    T: serde::de::Deserialize<'de>,
+{
+    fn deserialize<D>(deserializer: D) -> std::result::Result<Self, D::Error>
+    where
+        D: serde::de::Deserializer<'de>,
+    {
+        Ok(std::option::Option::<T>::deserialize(deserializer)?.into())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        alloc::Layout,
+        sync::atomic::{AtomicUsize, Ordering},
+    };
+
+    use super::{with_allocator, Allocator, Vec};
+
+    #[test]
+    fn convert_without_copy() {
+        let items = vec![1, 2, 3];
+        let ptr = items.as_ptr();
+
+        let items = Vec::from(items);
+        assert_eq!(items.as_ptr(), ptr);
+
+        let items = std::vec::Vec::from(items);
+        assert_eq!(items.as_ptr(), ptr);
+        assert_eq!(items, [1, 2, 3]);
+    }
+
+    #[test]
+    fn use_provided_allocator() {
+        static ALLOCATED: AtomicUsize = AtomicUsize::new(0);
+
+        unsafe extern "C" fn alloc(size: usize, align: usize) -> *mut u8 {
+            ALLOCATED.fetch_add(size, Ordering::SeqCst);
+            std::alloc::alloc(Layout::from_size_align_unchecked(size, align))
+        }
+        unsafe extern "C" fn dealloc(ptr: *mut u8, size: usize, align: usize) {
+            ALLOCATED.fetch_sub(size, Ordering::SeqCst);
+            std::alloc::dealloc(
+                ptr,
+                Layout::from_size_align_unchecked(size, align),
+            );
+        }
+
+        let allocator = Allocator { alloc, dealloc };
+
+        let items = with_allocator(allocator, || {
+            let mut items = (0..10).map(|i| i.to_string()).collect::<Vec<_>>();
+            items.push(10.to_string());
+            items
+        });
+        assert!(ALLOCATED.load(Ordering::SeqCst) > 0);
+
+        let items = std::vec::Vec::from(items);
+        assert_eq!(ALLOCATED.load(Ordering::SeqCst), 0);
+        assert_eq!(items.len(), 11);
+        assert_eq!(items[10], "10");
+    }
+}
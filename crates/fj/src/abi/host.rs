@@ -1,12 +1,13 @@
 use std::{marker::PhantomData, os::raw::c_void, panic::AssertUnwindSafe};
 
-use crate::abi::Model;
+use crate::abi::{ffi_safe::Allocator, Model};
 
 /// A FFI-safe `&mut dyn Host`.
 #[repr(C)]
 pub struct Host<'a> {
     user_data: *mut c_void,
     register_boxed_model: unsafe extern "C" fn(*mut c_void, model: Model),
+    allocator: Allocator,
     _lifetime: PhantomData<&'a mut ()>,
 }
 
@@ -28,11 +29,19 @@ impl<'a, H: crate::models::Host + Sized> From<&'a mut H> for Host<'a> {
         Host {
             user_data: host as *mut H as *mut c_void,
             register_boxed_model: register_boxed_model::<H>,
+            allocator: Allocator::global(),
             _lifetime: PhantomData,
         }
     }
 }
 
+impl Host<'_> {
+    /// Access the allocator of the host
+    pub fn allocator(&self) -> Allocator {
+        self.allocator
+    }
+}
+
 impl<'a> crate::models::Host for Host<'a> {
     fn register_boxed_model(&mut self, model: Box<dyn crate::models::Model>) {
         let Host {
@@ -6,6 +6,7 @@ pub struct ModelMetadata {
     name: ffi_safe::String,
     description: ffi_safe::Option<ffi_safe::String>,
     arguments: ffi_safe::Vec<ArgumentMetadata>,
+    capabilities: Capabilities,
 }
 
 impl From<ModelMetadata> for crate::models::ModelMetadata {
@@ -14,12 +15,14 @@ impl From<ModelMetadata> for crate::models::ModelMetadata {
             name,
             description,
             arguments,
+            capabilities,
         } = m;
 
         crate::models::ModelMetadata {
             name: name.into(),
             description: description.map(Into::into).into(),
             arguments: arguments.iter().cloned().map(|a| a.into()).collect(),
+            capabilities: capabilities.into(),
         }
     }
 }
@@ -30,12 +33,59 @@ impl From<crate::models::ModelMetadata> for ModelMetadata {
             name,
             description,
             arguments,
+            capabilities,
         } = m;
 
         ModelMetadata {
             name: name.into(),
             description: description.into(),
             arguments: arguments.into_iter().map(Into::into).collect(),
+            capabilities: capabilities.into(),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+#[repr(C)]
+pub struct Capabilities {
+    needs_booleans: bool,
+    needs_fonts: bool,
+    min_kernel_version: ffi_safe::Option<ffi_safe::String>,
+    supports_cancellation: bool,
+}
+
+impl From<Capabilities> for crate::models::Capabilities {
+    fn from(c: Capabilities) -> Self {
+        let Capabilities {
+            needs_booleans,
+            needs_fonts,
+            min_kernel_version,
+            supports_cancellation,
+        } = c;
+
+        crate::models::Capabilities {
+            needs_booleans,
+            needs_fonts,
+            min_kernel_version: min_kernel_version.map(Into::into).into(),
+            supports_cancellation,
+        }
+    }
+}
+
+impl From<crate::models::Capabilities> for Capabilities {
+    fn from(c: crate::models::Capabilities) -> Self {
+        let crate::models::Capabilities {
+            needs_booleans,
+            needs_fonts,
+            min_kernel_version,
+            supports_cancellation,
+        } = c;
+
+        Capabilities {
+            needs_booleans,
+            needs_fonts,
+            min_kernel_version: min_kernel_version.into(),
+            supports_cancellation,
         }
     }
 }
@@ -93,10 +93,15 @@ macro_rules! register_model {
                 $crate::models::Error,
             > = $init;
 
-            match init(&mut *host) {
-                Ok(meta) => $crate::abi::InitResult::Ok(meta.into()),
-                Err(e) => $crate::abi::InitResult::Err(e.into()),
-            }
+            // Everything that is returned to the host must be allocated by
+            // the host's allocator.
+            let allocator = (*host).allocator();
+            $crate::abi::ffi_safe::with_allocator(allocator, || {
+                match init(&mut *host) {
+                    Ok(meta) => $crate::abi::InitResult::Ok(meta.into()),
+                    Err(e) => $crate::abi::InitResult::Err(e.into()),
+                }
+            })
         }
     };
 }
@@ -1,14 +1,17 @@
 use std::{os::raw::c_void, panic::AssertUnwindSafe};
 
 use crate::{
-    abi::{Context, ModelMetadata, ShapeResult},
+    abi::{
+        ffi_safe::{self, Allocator},
+        Context, ModelMetadata, ShapeResult,
+    },
     models::Error,
 };
 
 #[repr(C)]
 pub struct Model {
     ptr: *mut c_void,
-    metadata: unsafe extern "C" fn(*mut c_void) -> ModelMetadata,
+    metadata: unsafe extern "C" fn(*mut c_void, Allocator) -> ModelMetadata,
     shape: unsafe extern "C" fn(*mut c_void, Context<'_>) -> ShapeResult,
     free: unsafe extern "C" fn(*mut c_void),
 }
@@ -33,19 +36,22 @@ impl crate::models::Model for Model {
     fn metadata(&self) -> crate::models::ModelMetadata {
         let Model { ptr, metadata, .. } = *self;
 
-        unsafe { metadata(ptr).into() }
+        unsafe { metadata(ptr, Allocator::global()).into() }
     }
 }
 
 impl From<Box<dyn crate::models::Model>> for Model {
     fn from(m: Box<dyn crate::models::Model>) -> Self {
-        unsafe extern "C" fn metadata(user_data: *mut c_void) -> ModelMetadata {
+        unsafe extern "C" fn metadata(
+            user_data: *mut c_void,
+            allocator: Allocator,
+        ) -> ModelMetadata {
             let model = &*(user_data as *mut Box<dyn crate::models::Model>);
 
             match std::panic::catch_unwind(AssertUnwindSafe(|| {
-                model.metadata()
+                ffi_safe::with_allocator(allocator, || model.metadata().into())
             })) {
-                Ok(meta) => meta.into(),
+                Ok(meta) => meta,
                 Err(payload) => crate::abi::on_panic(payload),
             }
         }
@@ -57,10 +63,14 @@ impl From<Box<dyn crate::models::Model>> for Model {
             let model = &*(user_data as *mut Box<dyn crate::models::Model>);
 
             match std::panic::catch_unwind(AssertUnwindSafe(|| {
-                model.shape(&ctx)
+                ffi_safe::with_allocator(ctx.allocator(), || {
+                    match model.shape(&ctx) {
+                        Ok(shape) => ShapeResult::Ok(shape),
+                        Err(err) => ShapeResult::Err(err.into()),
+                    }
+                })
             })) {
-                Ok(Ok(shape)) => ShapeResult::Ok(shape),
-                Ok(Err(err)) => ShapeResult::Err(err.into()),
+                Ok(result) => result,
                 Err(payload) => crate::abi::on_panic(payload),
             }
         }
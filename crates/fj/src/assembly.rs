@@ -0,0 +1,197 @@
+use crate::{abi::ffi_safe, Part, Shape};
+
+/// Parts that are placed relative to each other by mates
+///
+/// Instead of computing the position of every part by hand, the parts are
+/// added to the assembly as they were modeled. Mates then describe how the
+/// parts relate to each other, like which planes touch and which axes line up,
+/// and the host application moves the parts until they satisfy all mates.
+///
+/// The first part stays where it is, and all other parts are placed relative
+/// to it. Parts that can still move after all mates are satisfied stay as
+/// close to where they were modeled as the mates allow. The host application
+/// reports such parts, as well as mates that contradict each other.
+///
+/// Mates refer to planes and axes in the coordinates of the parts, before
+/// they are moved.
+///
+/// # Examples
+///
+/// ``` rust
+/// # use fj::syntax::*;
+/// use fj::{Assembly, Mate, MateAxis, MatePlane};
+///
+/// # let base = fj::Sketch::from_points(vec![[0., 0.], [2., 0.], [2., 2.]])
+/// #     .sweep([0., 0., 1.]);
+/// # let lid = fj::Sketch::from_points(vec![[0., 0.], [2., 0.], [2., 2.]])
+/// #     .sweep([0., 0., 1.]);
+/// let mut assembly = Assembly::new();
+/// let base = assembly.add_instance(fj::Part::new("base", base));
+/// let lid = assembly.add_instance(fj::Part::new("lid", lid));
+///
+/// // The bottom of the lid rests on the top of the base...
+/// assembly.add_mate(Mate::Coincident(
+///     MatePlane {
+///         instance: base,
+///         origin: [0., 0., 1.],
+///         normal: [0., 0., 1.],
+///     },
+///     MatePlane {
+///         instance: lid,
+///         origin: [0., 0., 0.],
+///         normal: [0., 0., -1.],
+///     },
+/// ));
+///
+/// // ...and their centers line up.
+/// assembly.add_mate(Mate::Concentric(
+///     MateAxis {
+///         instance: base,
+///         origin: [1., 1., 0.],
+///         direction: [0., 0., 1.],
+///     },
+///     MateAxis {
+///         instance: lid,
+///         origin: [1., 1., 0.],
+///         direction: [0., 0., 1.],
+///     },
+/// ));
+///
+/// let model: fj::Shape = assembly.into();
+/// ```
+#[derive(Clone, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[repr(C)]
+pub struct Assembly {
+    instances: ffi_safe::Vec<Part>,
+    mates: ffi_safe::Vec<Mate>,
+}
+
+impl Assembly {
+    /// Create an empty `Assembly`
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add an instance of a part
+    pub fn add_instance(&mut self, part: Part) -> InstanceId {
+        let mut instances: Vec<_> = std::mem::take(&mut self.instances).into();
+        instances.push(part);
+        self.instances = instances.into();
+
+        InstanceId(self.instances.len() - 1)
+    }
+
+    /// Add a mate
+    ///
+    /// # Panics
+    ///
+    /// Panics, if the mate refers to an instance of another assembly, or if
+    /// one of its normals or directions has zero length.
+    pub fn add_mate(&mut self, mate: Mate) {
+        let (features, vectors) = match &mate {
+            Mate::Coincident(a, b) | Mate::Offset(a, b, _) => {
+                ([a.instance, b.instance], [a.normal, b.normal])
+            }
+            Mate::Concentric(a, b) => {
+                ([a.instance, b.instance], [a.direction, b.direction])
+            }
+        };
+
+        for InstanceId(index) in features {
+            assert!(
+                index < self.instances.len(),
+                "Mate refers to instance that isn't part of the assembly"
+            );
+        }
+        for vector in vectors {
+            assert!(
+                vector.iter().any(|&coord| coord != 0.),
+                "Normals and directions of mates must not have zero length"
+            );
+        }
+
+        let mut mates: Vec<_> = std::mem::take(&mut self.mates).into();
+        mates.push(mate);
+        self.mates = mates.into();
+    }
+
+    /// Access the instances, in the order they were added
+    pub fn instances(&self) -> &[Part] {
+        &self.instances
+    }
+
+    /// Access the mates, in the order they were added
+    pub fn mates(&self) -> &[Mate] {
+        &self.mates
+    }
+}
+
+impl From<Assembly> for Shape {
+    fn from(shape: Assembly) -> Self {
+        Self::Assembly(Box::new(shape))
+    }
+}
+
+/// Identifies an instance of an [`Assembly`]
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash, Ord, PartialOrd)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[repr(C)]
+pub struct InstanceId(usize);
+
+impl InstanceId {
+    /// Access the index of the instance within its assembly
+    pub fn index(&self) -> usize {
+        self.0
+    }
+}
+
+/// A constraint on how two instances of an [`Assembly`] are placed
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[repr(C)]
+pub enum Mate {
+    /// The planes lie on top of each other, facing in opposite directions
+    Coincident(MatePlane, MatePlane),
+
+    /// The axes lie on top of each other
+    ///
+    /// The axes may point in the same or in opposite directions.
+    Concentric(MateAxis, MateAxis),
+
+    /// The planes face each other, at the given distance
+    ///
+    /// The distance is measured from the first plane, along its normal. A
+    /// distance of zero is the same as [`Mate::Coincident`].
+    Offset(MatePlane, MatePlane, f64),
+}
+
+/// A plane on an instance of an [`Assembly`], as part of a [`Mate`]
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[repr(C)]
+pub struct MatePlane {
+    /// The instance that the plane is attached to
+    pub instance: InstanceId,
+
+    /// A point on the plane, in the coordinates of the instance's part
+    pub origin: [f64; 3],
+
+    /// The normal of the plane, pointing away from the part
+    pub normal: [f64; 3],
+}
+
+/// An axis on an instance of an [`Assembly`], as part of a [`Mate`]
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[repr(C)]
+pub struct MateAxis {
+    /// The instance that the axis is attached to
+    pub instance: InstanceId,
+
+    /// A point on the axis, in the coordinates of the instance's part
+    pub origin: [f64; 3],
+
+    /// The direction of the axis
+    pub direction: [f64; 3],
+}
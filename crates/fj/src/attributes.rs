@@ -0,0 +1,73 @@
+use crate::abi::ffi_safe;
+
+/// Metadata that is attached to the faces of a shape
+///
+/// Attributes don't affect the geometry of a shape. Operations carry them from
+/// the faces they start with to the faces they create, and from there to the
+/// triangles of the mesh. That way, faces can still be told apart in the
+/// viewer or an exported file, for example to assign materials to them.
+///
+/// # Examples
+///
+/// ``` rust
+/// let sketch = fj::Sketch::from_points(vec![[0., 0.], [1., 0.], [0., 1.]])
+///     .with_name("wedge")
+///     .with_material("PLA");
+///
+/// assert_eq!(sketch.attributes().material(), Some("PLA"));
+/// ```
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[repr(C)]
+pub struct Attributes {
+    name: ffi_safe::Option<ffi_safe::String>,
+    material: ffi_safe::Option<ffi_safe::String>,
+}
+
+impl Attributes {
+    /// Create an empty set of attributes
+    pub fn new() -> Self {
+        Self {
+            name: ffi_safe::Option::None,
+            material: ffi_safe::Option::None,
+        }
+    }
+
+    /// Set the name
+    pub fn with_name(mut self, name: impl Into<String>) -> Self {
+        self.name = Some(name.into()).into();
+        self
+    }
+
+    /// Set the material
+    ///
+    /// The material is an identifier of your choice. Exporters that support
+    /// materials, like 3MF and glTF, group faces by it.
+    pub fn with_material(mut self, material: impl Into<String>) -> Self {
+        self.material = Some(material.into()).into();
+        self
+    }
+
+    /// Access the name, if set
+    pub fn name(&self) -> Option<&str> {
+        as_str(&self.name)
+    }
+
+    /// Access the material, if set
+    pub fn material(&self) -> Option<&str> {
+        as_str(&self.material)
+    }
+}
+
+impl Default for Attributes {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn as_str(s: &ffi_safe::Option<ffi_safe::String>) -> Option<&str> {
+    match s {
+        ffi_safe::Option::Some(s) => Some(s),
+        ffi_safe::Option::None => None,
+    }
+}
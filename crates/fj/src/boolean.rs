@@ -0,0 +1,100 @@
+use crate::Shape;
+
+/// A boolean operation on two 3-dimensional shapes
+///
+/// # Examples
+///
+/// Convenient syntax for this operation is available through [`crate::syntax`].
+///
+/// ``` rust
+/// # let a = fj::Sketch::from_points(vec![[0., 0.], [2., 0.], [0., 2.]])
+/// #     .sweep([0., 0., 2.]);
+/// # let b = fj::Sketch::from_points(vec![[1., 1.], [3., 1.], [1., 3.]])
+/// #     .sweep([0., 0., 2.]);
+/// use fj::syntax::*;
+///
+/// // `a` and `b` can be anything that converts to `fj::Shape`
+/// let union = a.union(&b);
+/// let difference = a.subtract(&b);
+/// let intersection = a.intersect(&b);
+/// ```
+///
+/// # Limitations
+///
+/// The operation is computed on the triangle meshes that approximate the
+/// shapes, not on their exact boundary representation. Curved surfaces are
+/// approximated using a tolerance that is derived from the size of the shapes,
+/// and the result consists of flat triangles only.
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[repr(C)]
+pub struct Boolean {
+    a: Shape,
+    b: Shape,
+    kind: BooleanKind,
+}
+
+impl Boolean {
+    /// Create the union of two shapes
+    pub fn union(a: Shape, b: Shape) -> Self {
+        Self {
+            a,
+            b,
+            kind: BooleanKind::Union,
+        }
+    }
+
+    /// Create the difference of two shapes, `b` being subtracted from `a`
+    pub fn difference(a: Shape, b: Shape) -> Self {
+        Self {
+            a,
+            b,
+            kind: BooleanKind::Difference,
+        }
+    }
+
+    /// Create the intersection of two shapes
+    pub fn intersection(a: Shape, b: Shape) -> Self {
+        Self {
+            a,
+            b,
+            kind: BooleanKind::Intersection,
+        }
+    }
+
+    /// Access the first shape
+    pub fn a(&self) -> &Shape {
+        &self.a
+    }
+
+    /// Access the second shape
+    pub fn b(&self) -> &Shape {
+        &self.b
+    }
+
+    /// Access the kind of operation
+    pub fn kind(&self) -> BooleanKind {
+        self.kind
+    }
+}
+
+impl From<Boolean> for Shape {
+    fn from(shape: Boolean) -> Self {
+        Self::Boolean(Box::new(shape))
+    }
+}
+
+/// The kind of operation of a [`Boolean`]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[repr(C)]
+pub enum BooleanKind {
+    /// Everything that is in either of the shapes
+    Union,
+
+    /// Everything that is in the first shape, but not in the second
+    Difference,
+
+    /// Everything that is in both shapes
+    Intersection,
+}
@@ -0,0 +1,341 @@
+use std::{error, fmt};
+
+use crate::{abi::ffi_safe, Shape};
+
+/// A shape that is defined by its boundary representation
+///
+/// Most models describe their shape through the high-level operations that
+/// this crate provides, which the kernel then turns into a boundary
+/// representation. A `Brep` allows models to skip that step and define the
+/// boundary of a shape directly, for example if they compute it themselves or
+/// load it from a file.
+///
+/// Like all shapes, a `Brep` is plain data. It can be passed to the host
+/// application as-is, and it can be serialized, if the `serde` feature is
+/// enabled. Deserializing a `Brep` checks its faces, the same way that
+/// [`Brep::with_face`] does.
+///
+/// A `Brep` consists of vertices and planar faces. Faces refer to their
+/// vertices by index, which means neighboring faces share the exact position of
+/// their common vertices.
+///
+/// # Examples
+///
+/// ``` rust
+/// // A tetrahedron
+/// let brep = fj::Brep::from_vertices(vec![
+///     [0., 0., 0.],
+///     [1., 0., 0.],
+///     [0., 1., 0.],
+///     [0., 0., 1.],
+/// ])
+/// .with_face(fj::BrepFace::from_exterior(vec![0, 2, 1]))
+/// .with_face(fj::BrepFace::from_exterior(vec![0, 1, 3]))
+/// .with_face(fj::BrepFace::from_exterior(vec![0, 3, 2]))
+/// .with_face(fj::BrepFace::from_exterior(vec![1, 2, 3]));
+/// ```
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(try_from = "RawBrep"))]
+#[repr(C)]
+pub struct Brep {
+    vertices: ffi_safe::Vec<[f64; 3]>,
+    faces: ffi_safe::Vec<BrepFace>,
+}
+
+impl Brep {
+    /// Create a `Brep` from its vertices, without any faces
+    pub fn from_vertices(vertices: Vec<[f64; 3]>) -> Self {
+        Self {
+            vertices: vertices.into(),
+            faces: Vec::new().into(),
+        }
+    }
+
+    /// Add a face
+    ///
+    /// # Panics
+    ///
+    /// Panics, if a polygon of the face has less than 3 vertices, if the face
+    /// refers to a vertex that doesn't exist, or if the vertices of the face
+    /// don't lie in a common plane.
+    pub fn with_face(self, face: BrepFace) -> Self {
+        if let Err(err) = check_face(&self.vertices, &face) {
+            panic!("{err}");
+        }
+
+        let mut faces: Vec<_> = self.faces.into();
+        faces.push(face);
+
+        Self {
+            vertices: self.vertices,
+            faces: faces.into(),
+        }
+    }
+
+    /// Access the vertices
+    pub fn vertices(&self) -> &[[f64; 3]] {
+        &self.vertices
+    }
+
+    /// Access the faces
+    pub fn faces(&self) -> &[BrepFace] {
+        &self.faces
+    }
+}
+
+/// The serialized form of a [`Brep`], before its faces have been checked
+#[cfg(feature = "serde")]
+#[derive(serde::Deserialize)]
+struct RawBrep {
+    vertices: Vec<[f64; 3]>,
+    faces: Vec<BrepFace>,
+}
+
+#[cfg(feature = "serde")]
+impl TryFrom<RawBrep> for Brep {
+    type Error = BrepError;
+
+    fn try_from(raw: RawBrep) -> Result<Self, Self::Error> {
+        for face in &raw.faces {
+            check_face(&raw.vertices, face)?;
+        }
+
+        Ok(Self {
+            vertices: raw.vertices.into(),
+            faces: raw.faces.into(),
+        })
+    }
+}
+
+impl From<Brep> for Shape {
+    fn from(shape: Brep) -> Self {
+        Self::Brep(shape)
+    }
+}
+
+/// A planar face, as part of a [`Brep`]
+///
+/// The face is bounded by an exterior polygon, and can have any number of
+/// interior polygons, which are holes in the face. All polygons are defined by
+/// the indices of their vertices.
+///
+/// The exterior polygon must be wound counter-clockwise, when looking at the
+/// front side of the face. The interior polygons must be wound clockwise.
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[repr(C)]
+pub struct BrepFace {
+    exterior: ffi_safe::Vec<usize>,
+    interiors: ffi_safe::Vec<ffi_safe::Vec<usize>>,
+    color: [u8; 4],
+}
+
+impl BrepFace {
+    /// Create a face from the vertex indices of its exterior polygon
+    ///
+    /// # Panics
+    ///
+    /// Panics, if the polygon has less than 3 vertices.
+    pub fn from_exterior(exterior: Vec<usize>) -> Self {
+        assert!(exterior.len() >= 3, "Polygon needs at least 3 vertices");
+
+        Self {
+            exterior: exterior.into(),
+            interiors: Vec::new().into(),
+            color: [255, 0, 0, 255],
+        }
+    }
+
+    /// Add an interior polygon
+    ///
+    /// # Panics
+    ///
+    /// Panics, if the polygon has less than 3 vertices.
+    pub fn with_interior(self, interior: Vec<usize>) -> Self {
+        assert!(interior.len() >= 3, "Polygon needs at least 3 vertices");
+
+        let mut interiors: Vec<_> = self.interiors.into();
+        interiors.push(interior.into());
+
+        Self {
+            interiors: interiors.into(),
+            ..self
+        }
+    }
+
+    /// Set the rendering color of the face in RGBA
+    pub fn with_color(mut self, color: [u8; 4]) -> Self {
+        self.color = color;
+        self
+    }
+
+    /// Access the vertex indices of the exterior polygon
+    pub fn exterior(&self) -> &[usize] {
+        &self.exterior
+    }
+
+    /// Access the vertex indices of the interior polygons
+    pub fn interiors(&self) -> impl Iterator<Item = &[usize]> + '_ {
+        self.interiors.iter().map(|interior| &**interior)
+    }
+
+    /// Access the vertex indices of all polygons, starting with the exterior
+    pub fn polygons(&self) -> impl Iterator<Item = &[usize]> + '_ {
+        [self.exterior()].into_iter().chain(self.interiors())
+    }
+
+    /// Get the rendering color of the face in RGBA
+    pub fn color(&self) -> [u8; 4] {
+        self.color
+    }
+}
+
+/// A face of a [`Brep`] is invalid
+#[derive(Clone, Debug, PartialEq)]
+pub enum BrepError {
+    /// A polygon of the face has less than 3 vertices
+    TooFewVertices,
+
+    /// The face refers to a vertex that doesn't exist
+    MissingVertex {
+        /// The index of the vertex that doesn't exist
+        index: usize,
+
+        /// The number of vertices in the `Brep`
+        num_vertices: usize,
+    },
+
+    /// The vertices of the face don't lie in a common plane
+    NotPlanar,
+}
+
+impl fmt::Display for BrepError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::TooFewVertices => {
+                write!(f, "Polygon needs at least 3 vertices")
+            }
+            Self::MissingVertex {
+                index,
+                num_vertices,
+            } => write!(
+                f,
+                "Face refers to vertex {index}, but there are only \
+                {num_vertices} vertices"
+            ),
+            Self::NotPlanar => {
+                write!(f, "Vertices of face don't lie in a common plane")
+            }
+        }
+    }
+}
+
+impl error::Error for BrepError {}
+
+fn check_face(vertices: &[[f64; 3]], face: &BrepFace) -> Result<(), BrepError> {
+    for polygon in face.polygons() {
+        if polygon.len() < 3 {
+            return Err(BrepError::TooFewVertices);
+        }
+
+        for &index in polygon {
+            if index >= vertices.len() {
+                return Err(BrepError::MissingVertex {
+                    index,
+                    num_vertices: vertices.len(),
+                });
+            }
+        }
+    }
+
+    if !is_planar(vertices, face) {
+        return Err(BrepError::NotPlanar);
+    }
+
+    Ok(())
+}
+
+fn is_planar(vertices: &[[f64; 3]], face: &BrepFace) -> bool {
+    let points = || {
+        face.polygons()
+            .flat_map(|polygon| polygon.iter().map(|&index| vertices[index]))
+    };
+
+    let sub =
+        |a: [f64; 3], b: [f64; 3]| [a[0] - b[0], a[1] - b[1], a[2] - b[2]];
+    let dot =
+        |a: [f64; 3], b: [f64; 3]| a[0] * b[0] + a[1] * b[1] + a[2] * b[2];
+
+    // Compute the normal of the exterior, using Newell's method.
+    let exterior = face.exterior();
+    let mut normal = [0.; 3];
+    for (i, &a) in exterior.iter().enumerate() {
+        let a = vertices[a];
+        let b = vertices[exterior[(i + 1) % exterior.len()]];
+
+        normal[0] += (a[1] - b[1]) * (a[2] + b[2]);
+        normal[1] += (a[2] - b[2]) * (a[0] + b[0]);
+        normal[2] += (a[0] - b[0]) * (a[1] + b[1]);
+    }
+
+    let length = dot(normal, normal).sqrt();
+    if length == 0. {
+        return false;
+    }
+    let normal = normal.map(|n| n / length);
+
+    let origin = vertices[exterior[0]];
+    let size = points()
+        .map(|point| dot(sub(point, origin), sub(point, origin)).sqrt())
+        .fold(0., f64::max);
+
+    points().all(|point| dot(sub(point, origin), normal).abs() <= size * 1e-9)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{check_face, BrepError, BrepFace};
+
+    const VERTICES: [[f64; 3]; 4] =
+        [[0., 0., 0.], [1., 0., 0.], [0., 1., 0.], [0., 0., 1.]];
+
+    #[test]
+    fn check_face_reports_invalid_faces() {
+        let valid = BrepFace::from_exterior(vec![0, 1, 2]);
+        assert_eq!(check_face(&VERTICES, &valid), Ok(()));
+
+        let missing_vertex = BrepFace::from_exterior(vec![0, 1, 4]);
+        assert_eq!(
+            check_face(&VERTICES, &missing_vertex),
+            Err(BrepError::MissingVertex {
+                index: 4,
+                num_vertices: 4,
+            })
+        );
+
+        let not_planar =
+            BrepFace::from_exterior(vec![0, 1, 2]).with_interior(vec![0, 1, 3]);
+        assert_eq!(
+            check_face(&VERTICES, &not_planar),
+            Err(BrepError::NotPlanar)
+        );
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn deserialize_checks_faces() {
+        use super::Brep;
+
+        let brep = Brep::from_vertices(VERTICES.to_vec())
+            .with_face(BrepFace::from_exterior(vec![0, 2, 1]));
+        let json = serde_json::to_string(&brep).unwrap();
+        assert_eq!(serde_json::from_str::<Brep>(&json).unwrap(), brep);
+
+        let json = json.replace("[0,2,1]", "[0,2,4]");
+        assert!(serde_json::from_str::<Brep>(&json).is_err());
+
+        let json = json.replace("[0,2,4]", "[0,2]");
+        assert!(serde_json::from_str::<Brep>(&json).is_err());
+    }
+}
@@ -0,0 +1,509 @@
+use std::{error, fmt};
+
+use crate::{Angle, Sketch};
+
+/// A sketch, whose points are defined by constraints
+///
+/// Instead of computing the coordinates of every point by hand, points are
+/// added with a rough initial position, and then moved by the solver, until
+/// they satisfy all constraints. Points that aren't fully constrained stay as
+/// close to their initial positions as the constraints allow.
+///
+/// # Examples
+///
+/// ``` rust
+/// use fj::{Angle, ConstrainedSketch, Constraint};
+///
+/// // A right triangle with legs of 3 and 4.
+/// let mut sketch = ConstrainedSketch::new();
+/// let a = sketch.add_point([0., 0.]);
+/// let b = sketch.add_point([3.5, 0.5]);
+/// let c = sketch.add_point([0.5, 3.5]);
+///
+/// sketch.add_constraint(Constraint::Fixed(a, [0., 0.]));
+/// sketch.add_constraint(Constraint::Horizontal([a, b]));
+/// sketch.add_constraint(Constraint::Distance(a, b, 4.));
+/// sketch.add_constraint(Constraint::Distance(a, c, 3.));
+/// sketch.add_constraint(Constraint::Angle(
+///     [a, b],
+///     [a, c],
+///     Angle::from_deg(90.),
+/// ));
+///
+/// let solution = sketch.solve().unwrap();
+/// let [x, y] = solution.position(c);
+/// assert!(x.abs() < 1e-9 && (y - 3.).abs() < 1e-9);
+///
+/// let sketch: fj::Sketch = solution.sketch();
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct ConstrainedSketch {
+    points: Vec<[f64; 2]>,
+    outline: Vec<PointId>,
+    constraints: Vec<Constraint>,
+}
+
+impl ConstrainedSketch {
+    /// Create an empty `ConstrainedSketch`
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a point to the outline of the sketch
+    ///
+    /// The outline connects the points in the order they were added, as with
+    /// [`Sketch::from_points`].
+    pub fn add_point(&mut self, initial: [f64; 2]) -> PointId {
+        let point = self.add_construction_point(initial);
+        self.outline.push(point);
+        point
+    }
+
+    /// Add a point that can be constrained, but isn't part of the outline
+    pub fn add_construction_point(&mut self, initial: [f64; 2]) -> PointId {
+        self.points.push(initial);
+        PointId(self.points.len() - 1)
+    }
+
+    /// Add a constraint
+    ///
+    /// # Panics
+    ///
+    /// Panics, if the constraint refers to a point of another sketch.
+    pub fn add_constraint(&mut self, constraint: Constraint) {
+        for PointId(index) in constraint.points() {
+            assert!(
+                index < self.points.len(),
+                "Constraint refers to point that isn't part of the sketch"
+            );
+        }
+
+        self.constraints.push(constraint);
+    }
+
+    /// Move the points, until they satisfy all constraints
+    ///
+    /// Uses the Levenberg-Marquardt algorithm, which copes with sketches that
+    /// have fewer constraints than degrees of freedom.
+    pub fn solve(&self) -> Result<Solution, SolveError> {
+        const MAX_ITERATIONS: usize = 200;
+
+        let mut positions = self.points.concat();
+        let mut residuals = self.residuals(&positions);
+        let mut cost = sum_of_squares(&residuals);
+
+        // The damping starts out moderate, and is adapted to how well the
+        // linearized system predicts the actual one. It never drops to zero,
+        // as the system is singular, if the sketch is under-constrained.
+        let mut damping = 1e-3;
+
+        for _ in 0..MAX_ITERATIONS {
+            if residuals
+                .iter()
+                .all(|residual| residual.value.abs() <= TOLERANCE)
+            {
+                break;
+            }
+
+            let n = positions.len();
+            let mut matrix = vec![vec![0.; n]; n];
+            let mut gradient = vec![0.; n];
+            for residual in &residuals {
+                for &(i, d_i) in &residual.derivatives {
+                    gradient[i] += d_i * residual.value;
+                    for &(j, d_j) in &residual.derivatives {
+                        matrix[i][j] += d_i * d_j;
+                    }
+                }
+            }
+            for (i, row) in matrix.iter_mut().enumerate() {
+                row[i] += damping;
+            }
+
+            let Some(step) = solve_linear(
+                matrix,
+                gradient.into_iter().map(|g| -g).collect(),
+            ) else {
+                damping *= 10.;
+                continue;
+            };
+
+            let candidate = positions
+                .iter()
+                .zip(&step)
+                .map(|(position, step)| position + step)
+                .collect::<Vec<_>>();
+            let candidate_residuals = self.residuals(&candidate);
+            let candidate_cost = sum_of_squares(&candidate_residuals);
+
+            if candidate_cost < cost {
+                positions = candidate;
+                residuals = candidate_residuals;
+                cost = candidate_cost;
+                damping = (damping / 10.).max(1e-12);
+            } else {
+                damping *= 10.;
+                if damping > 1e12 {
+                    break;
+                }
+            }
+        }
+
+        let unsatisfied = self
+            .constraints
+            .iter()
+            .zip(self.residuals_by_constraint(&positions))
+            .filter(|(_, residuals)| {
+                residuals
+                    .iter()
+                    .any(|residual| residual.value.abs() > TOLERANCE)
+            })
+            .map(|(constraint, _)| *constraint)
+            .collect::<Vec<_>>();
+        if !unsatisfied.is_empty() {
+            return Err(SolveError { unsatisfied });
+        }
+
+        Ok(Solution {
+            points: positions
+                .chunks(2)
+                .map(|position| [position[0], position[1]])
+                .collect(),
+            outline: self.outline.clone(),
+        })
+    }
+
+    fn residuals(&self, positions: &[f64]) -> Vec<Residual> {
+        self.residuals_by_constraint(positions)
+            .into_iter()
+            .flatten()
+            .collect()
+    }
+
+    fn residuals_by_constraint(&self, positions: &[f64]) -> Vec<Vec<Residual>> {
+        self.constraints
+            .iter()
+            .map(|constraint| constraint.residuals(positions))
+            .collect()
+    }
+}
+
+/// Identifies a point of a [`ConstrainedSketch`]
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash, Ord, PartialOrd)]
+pub struct PointId(usize);
+
+/// A constraint on the points of a [`ConstrainedSketch`]
+///
+/// Segments are given by their start and end points. They don't need to be
+/// part of the outline.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Constraint {
+    /// The point is at the given position
+    Fixed(PointId, [f64; 2]),
+
+    /// The points are at the same position
+    Coincident(PointId, PointId),
+
+    /// The segment is parallel to the x-axis
+    Horizontal([PointId; 2]),
+
+    /// The segment is parallel to the y-axis
+    Vertical([PointId; 2]),
+
+    /// The points are at the given distance from each other
+    Distance(PointId, PointId, f64),
+
+    /// The second segment is at the given angle to the first one
+    ///
+    /// The angle is measured counter-clockwise, from the direction of the
+    /// first segment to the direction of the second one.
+    Angle([PointId; 2], [PointId; 2], Angle),
+}
+
+impl Constraint {
+    fn points(&self) -> Vec<PointId> {
+        match *self {
+            Self::Fixed(a, _) => vec![a],
+            Self::Coincident(a, b) | Self::Distance(a, b, _) => vec![a, b],
+            Self::Horizontal([a, b]) | Self::Vertical([a, b]) => vec![a, b],
+            Self::Angle([a, b], [c, d], _) => vec![a, b, c, d],
+        }
+    }
+
+    /// Compute the residuals of the constraint, and their derivatives
+    ///
+    /// The constraint is satisfied, if all residuals are zero.
+    fn residuals(&self, positions: &[f64]) -> Vec<Residual> {
+        let x = |PointId(index): PointId| 2 * index;
+        let y = |PointId(index): PointId| 2 * index + 1;
+        let position =
+            |point: PointId| [positions[x(point)], positions[y(point)]];
+
+        match *self {
+            Self::Fixed(a, [px, py]) => {
+                let [ax, ay] = position(a);
+                vec![
+                    Residual::new(ax - px, [(x(a), 1.)]),
+                    Residual::new(ay - py, [(y(a), 1.)]),
+                ]
+            }
+            Self::Coincident(a, b) => {
+                let [ax, ay] = position(a);
+                let [bx, by] = position(b);
+                vec![
+                    Residual::new(bx - ax, [(x(a), -1.), (x(b), 1.)]),
+                    Residual::new(by - ay, [(y(a), -1.), (y(b), 1.)]),
+                ]
+            }
+            Self::Horizontal([a, b]) => {
+                let [_, ay] = position(a);
+                let [_, by] = position(b);
+                vec![Residual::new(by - ay, [(y(a), -1.), (y(b), 1.)])]
+            }
+            Self::Vertical([a, b]) => {
+                let [ax, _] = position(a);
+                let [bx, _] = position(b);
+                vec![Residual::new(bx - ax, [(x(a), -1.), (x(b), 1.)])]
+            }
+            Self::Distance(a, b, distance) => {
+                let [ax, ay] = position(a);
+                let [bx, by] = position(b);
+                let [dx, dy] = [bx - ax, by - ay];
+
+                let length = dx.hypot(dy);
+
+                // The direction is undefined for coincident points. Any
+                // direction will do, to push them apart.
+                let [ux, uy] = if length > 0. {
+                    [dx / length, dy / length]
+                } else {
+                    [1., 0.]
+                };
+
+                vec![Residual::new(
+                    length - distance,
+                    [(x(a), -ux), (y(a), -uy), (x(b), ux), (y(b), uy)],
+                )]
+            }
+            Self::Angle([a, b], [c, d], angle) => {
+                let [ax, ay] = position(a);
+                let [bx, by] = position(b);
+                let [cx, cy] = position(c);
+                let [dx, dy] = position(d);
+
+                let [ux, uy] = [bx - ax, by - ay];
+                let [vx, vy] = [dx - cx, dy - cy];
+
+                let actual = (ux * vy - uy * vx).atan2(ux * vx + uy * vy);
+
+                // Wrap the difference into `-π..π`, so the residual is small
+                // for angles that are close to each other across the wrap.
+                let difference = actual - angle.rad();
+                let difference = difference
+                    - std::f64::consts::TAU
+                        * (difference / std::f64::consts::TAU).round();
+
+                let u2 = (ux * ux + uy * uy).max(f64::EPSILON);
+                let v2 = (vx * vx + vy * vy).max(f64::EPSILON);
+
+                // The derivatives of the angle of each segment's direction.
+                let [du_x, du_y] = [uy / u2, -ux / u2];
+                let [dv_x, dv_y] = [-vy / v2, vx / v2];
+
+                vec![Residual::new(
+                    difference,
+                    [
+                        (x(a), -du_x),
+                        (y(a), -du_y),
+                        (x(b), du_x),
+                        (y(b), du_y),
+                        (x(c), -dv_x),
+                        (y(c), -dv_y),
+                        (x(d), dv_x),
+                        (y(d), dv_y),
+                    ],
+                )]
+            }
+        }
+    }
+}
+
+/// The points of a [`ConstrainedSketch`], after all constraints were satisfied
+///
+/// Returned by [`ConstrainedSketch::solve`].
+#[derive(Clone, Debug)]
+pub struct Solution {
+    points: Vec<[f64; 2]>,
+    outline: Vec<PointId>,
+}
+
+impl Solution {
+    /// Access the position of a point
+    pub fn position(&self, PointId(index): PointId) -> [f64; 2] {
+        self.points[index]
+    }
+
+    /// Create a [`Sketch`] from the outline
+    pub fn sketch(&self) -> Sketch {
+        Sketch::from_points(
+            self.outline
+                .iter()
+                .map(|&point| self.position(point))
+                .collect(),
+        )
+    }
+}
+
+/// The constraints of a [`ConstrainedSketch`] can't be satisfied
+///
+/// Either the constraints contradict each other, or the initial positions of
+/// the points are too far off for the solver to find a solution.
+#[derive(Clone, Debug, PartialEq)]
+pub struct SolveError {
+    /// The constraints that aren't satisfied by the best solution found
+    pub unsatisfied: Vec<Constraint>,
+}
+
+impl fmt::Display for SolveError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Failed to satisfy sketch constraints:")?;
+        for constraint in &self.unsatisfied {
+            write!(f, "\n- {constraint:?}")?;
+        }
+
+        Ok(())
+    }
+}
+
+impl error::Error for SolveError {}
+
+/// The maximum residual of a satisfied constraint
+const TOLERANCE: f64 = 1e-10;
+
+/// A residual of a constraint, and its derivatives by position coordinate
+struct Residual {
+    value: f64,
+    derivatives: Vec<(usize, f64)>,
+}
+
+impl Residual {
+    fn new(
+        value: f64,
+        derivatives: impl IntoIterator<Item = (usize, f64)>,
+    ) -> Self {
+        Self {
+            value,
+            derivatives: derivatives.into_iter().collect(),
+        }
+    }
+}
+
+fn sum_of_squares(residuals: &[Residual]) -> f64 {
+    residuals
+        .iter()
+        .map(|residual| residual.value.powi(2))
+        .sum()
+}
+
+/// Solve a linear system using Gaussian elimination
+///
+/// Returns `None`, if the matrix is singular.
+fn solve_linear(
+    mut matrix: Vec<Vec<f64>>,
+    mut b: Vec<f64>,
+) -> Option<Vec<f64>> {
+    let n = b.len();
+
+    for column in 0..n {
+        let pivot = (column..n).max_by(|&i, &j| {
+            matrix[i][column].abs().total_cmp(&matrix[j][column].abs())
+        })?;
+        if matrix[pivot][column] == 0. {
+            return None;
+        }
+        matrix.swap(column, pivot);
+        b.swap(column, pivot);
+
+        for row in column + 1..n {
+            let factor = matrix[row][column] / matrix[column][column];
+            if factor == 0. {
+                continue;
+            }
+
+            let (pivot_rows, rows) = matrix.split_at_mut(row);
+            for (value, pivot_value) in rows[0][column..]
+                .iter_mut()
+                .zip(&pivot_rows[column][column..])
+            {
+                *value -= factor * pivot_value;
+            }
+            b[row] -= factor * b[column];
+        }
+    }
+
+    let mut x = vec![0.; n];
+    for row in (0..n).rev() {
+        let sum = (row + 1..n).map(|i| matrix[row][i] * x[i]).sum::<f64>();
+        x[row] = (b[row] - sum) / matrix[row][row];
+    }
+
+    Some(x)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::Angle;
+
+    use super::{ConstrainedSketch, Constraint};
+
+    #[test]
+    fn rectangle() {
+        let mut sketch = ConstrainedSketch::new();
+        let a = sketch.add_point([0.1, -0.2]);
+        let b = sketch.add_point([2.2, 0.3]);
+        let c = sketch.add_point([1.8, 0.9]);
+        let d = sketch.add_point([-0.3, 1.2]);
+
+        for constraint in [
+            Constraint::Fixed(a, [0., 0.]),
+            Constraint::Horizontal([a, b]),
+            Constraint::Vertical([b, c]),
+            Constraint::Horizontal([c, d]),
+            Constraint::Vertical([d, a]),
+            Constraint::Distance(a, b, 2.),
+            Constraint::Distance(b, c, 1.),
+        ] {
+            sketch.add_constraint(constraint);
+        }
+
+        let solution = sketch.solve().unwrap();
+
+        let expected = [[0., 0.], [2., 0.], [2., 1.], [0., 1.]];
+        for (point, expected) in [a, b, c, d].into_iter().zip(expected) {
+            let [x, y] = solution.position(point);
+            assert!((x - expected[0]).abs() < 1e-9);
+            assert!((y - expected[1]).abs() < 1e-9);
+        }
+    }
+
+    #[test]
+    fn contradicting_constraints() {
+        let mut sketch = ConstrainedSketch::new();
+        let a = sketch.add_point([0., 0.]);
+        let b = sketch.add_point([1., 0.]);
+        let c = sketch.add_construction_point([1., 1.]);
+
+        for constraint in [
+            Constraint::Fixed(a, [0., 0.]),
+            Constraint::Coincident(b, c),
+            Constraint::Distance(a, b, 1.),
+            Constraint::Distance(a, c, 2.),
+            Constraint::Angle([a, b], [a, c], Angle::from_deg(45.)),
+        ] {
+            sketch.add_constraint(constraint);
+        }
+
+        let err = sketch.solve().unwrap_err();
+        assert!(!err.unsatisfied.is_empty());
+    }
+}
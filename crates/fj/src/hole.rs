@@ -0,0 +1,290 @@
+use crate::{abi::ffi_safe, Angle, Shape, Sweep};
+
+/// A sweep with holes in it
+///
+/// The holes are drilled into the face at the end of the sweep, parallel to the
+/// sweep's path. They are positioned using the 2D coordinates of the swept
+/// shape.
+///
+/// # Examples
+///
+/// Convenient syntax for this operation is available through [`crate::syntax`].
+///
+/// ``` rust
+/// # let plate = fj::Sketch::from_rectangle(fj::Rectangle::from_size(4., 4.))
+/// #     .sweep([0., 0., 1.]);
+/// use fj::syntax::*;
+///
+/// // `plate` can be anything that converts to `fj::Holes`, like `fj::Sweep`
+/// let plate = plate
+///     .hole(fj::Hole::through([-1., -1.], 0.5).with_counterbore(1., 0.25))
+///     .hole(
+///         fj::Hole::blind([1., 1.], 0.5, 0.75)
+///             .with_countersink(1., fj::Angle::from_deg(90.)),
+///     );
+/// ```
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[repr(C)]
+pub struct Holes {
+    sweep: Sweep,
+    holes: ffi_safe::Vec<Hole>,
+}
+
+impl Holes {
+    /// Create a `Holes` from the sweep to drill into, without any holes
+    pub fn from_sweep(sweep: Sweep) -> Self {
+        Self {
+            sweep,
+            holes: Vec::new().into(),
+        }
+    }
+
+    /// Add a hole
+    pub fn with_hole(self, hole: Hole) -> Self {
+        let mut holes: Vec<_> = self.holes.into();
+        holes.push(hole);
+
+        Self {
+            sweep: self.sweep,
+            holes: holes.into(),
+        }
+    }
+
+    /// Access the sweep that the holes are drilled into
+    pub fn sweep(&self) -> &Sweep {
+        &self.sweep
+    }
+
+    /// Access the holes
+    pub fn holes(&self) -> Vec<Hole> {
+        self.holes.to_vec()
+    }
+}
+
+impl From<Sweep> for Holes {
+    fn from(sweep: Sweep) -> Self {
+        Self::from_sweep(sweep)
+    }
+}
+
+impl From<Holes> for Shape {
+    fn from(shape: Holes) -> Self {
+        Self::Holes(shape)
+    }
+}
+
+/// A hole, as part of [`Holes`]
+///
+/// The hole itself is either round or slot-shaped. It can be drilled through
+/// the whole sweep, or to a given depth, and can have a counterbore or a
+/// countersink at its entrance.
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[repr(C)]
+pub struct Hole {
+    position: [f64; 2],
+    profile: HoleProfile,
+    depth: HoleDepth,
+    head: HoleHead,
+}
+
+impl Hole {
+    /// Create a round hole that goes all the way through
+    pub fn through(position: [f64; 2], diameter: f64) -> Self {
+        Self::new(
+            position,
+            HoleProfile::Round { diameter },
+            HoleDepth::Through,
+        )
+    }
+
+    /// Create a round hole with the given depth
+    pub fn blind(position: [f64; 2], diameter: f64, depth: f64) -> Self {
+        Self::new(
+            position,
+            HoleProfile::Round { diameter },
+            HoleDepth::Blind(depth),
+        )
+    }
+
+    /// Create a slot-shaped hole that goes all the way through
+    ///
+    /// The slot is aligned with the x-axis. `length` is the distance between
+    /// the centers of its rounded ends, `width` is its extent along the y-axis.
+    pub fn slot(position: [f64; 2], length: f64, width: f64) -> Self {
+        Self::new(
+            position,
+            HoleProfile::Slot { length, width },
+            HoleDepth::Through,
+        )
+    }
+
+    fn new(position: [f64; 2], profile: HoleProfile, depth: HoleDepth) -> Self {
+        let size = match profile {
+            HoleProfile::Round { diameter } => diameter,
+            HoleProfile::Slot { length, width } => {
+                assert!(length > 0., "Slot length must be positive");
+                width
+            }
+        };
+        assert!(size > 0., "Hole size must be positive");
+        if let HoleDepth::Blind(depth) = depth {
+            assert!(depth > 0., "Hole depth must be positive");
+        }
+
+        Self {
+            position,
+            profile,
+            depth,
+            head: HoleHead::None,
+        }
+    }
+
+    /// Set the depth of the hole
+    ///
+    /// By default, slots go all the way through.
+    ///
+    /// # Panics
+    ///
+    /// Panics, if `depth` is not positive.
+    pub fn with_depth(mut self, depth: f64) -> Self {
+        assert!(depth > 0., "Hole depth must be positive");
+        self.depth = HoleDepth::Blind(depth);
+        self
+    }
+
+    /// Add a counterbore to the hole
+    ///
+    /// For a slot, the counterbore is a wider slot with the same length.
+    ///
+    /// # Panics
+    ///
+    /// Panics, if the counterbore isn't wider than the hole, or if its depth is
+    /// not positive.
+    pub fn with_counterbore(mut self, diameter: f64, depth: f64) -> Self {
+        assert!(
+            diameter > self.width(),
+            "Counterbore must be wider than the hole"
+        );
+        assert!(depth > 0., "Counterbore depth must be positive");
+
+        self.head = HoleHead::Counterbore { diameter, depth };
+        self
+    }
+
+    /// Add a countersink to the hole
+    ///
+    /// `angle` is the full angle of the countersink's cone, for example 90
+    /// degrees for most metric countersunk screws.
+    ///
+    /// # Panics
+    ///
+    /// Panics, if the hole is a slot, if the countersink isn't wider than the
+    /// hole, or if the angle is not between 0 and 180 degrees.
+    pub fn with_countersink(mut self, diameter: f64, angle: Angle) -> Self {
+        assert!(
+            matches!(self.profile, HoleProfile::Round { .. }),
+            "Only round holes can have a countersink"
+        );
+        assert!(
+            diameter > self.width(),
+            "Countersink must be wider than the hole"
+        );
+        assert!(
+            angle.deg() > 0. && angle.deg() < 180.,
+            "Countersink angle must be between 0 and 180 degrees"
+        );
+
+        self.head = HoleHead::Countersink { diameter, angle };
+        self
+    }
+
+    /// Access the position of the hole
+    pub fn position(&self) -> [f64; 2] {
+        self.position
+    }
+
+    /// Access the profile of the hole
+    pub fn profile(&self) -> HoleProfile {
+        self.profile
+    }
+
+    /// Access the depth of the hole
+    pub fn depth(&self) -> HoleDepth {
+        self.depth
+    }
+
+    /// Access the head of the hole
+    pub fn head(&self) -> HoleHead {
+        self.head
+    }
+
+    /// Access the width of the hole, which is its diameter, if it is round
+    pub fn width(&self) -> f64 {
+        match self.profile {
+            HoleProfile::Round { diameter } => diameter,
+            HoleProfile::Slot { width, .. } => width,
+        }
+    }
+}
+
+/// The cross-section of a [`Hole`]
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[repr(C)]
+pub enum HoleProfile {
+    /// A round hole
+    Round {
+        /// The diameter of the hole
+        diameter: f64,
+    },
+
+    /// A slot-shaped hole, aligned with the x-axis
+    Slot {
+        /// The distance between the centers of the slot's rounded ends
+        length: f64,
+
+        /// The width of the slot
+        width: f64,
+    },
+}
+
+/// The depth of a [`Hole`]
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[repr(C)]
+pub enum HoleDepth {
+    /// The hole goes all the way through
+    Through,
+
+    /// The hole ends at the given depth
+    Blind(f64),
+}
+
+/// The entrance of a [`Hole`]
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[repr(C)]
+pub enum HoleHead {
+    /// The hole has no special entrance
+    None,
+
+    /// A wider, flat-bottomed hole at the entrance
+    Counterbore {
+        /// The diameter of the counterbore
+        diameter: f64,
+
+        /// The depth of the counterbore
+        depth: f64,
+    },
+
+    /// A cone-shaped widening of the hole at its entrance
+    Countersink {
+        /// The diameter of the countersink at the surface
+        diameter: f64,
+
+        /// The full angle of the countersink's cone
+        angle: Angle,
+    },
+}
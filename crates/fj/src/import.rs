@@ -0,0 +1,116 @@
+use std::path::Path;
+
+use crate::{abi::ffi_safe, Shape, Shape2d};
+
+/// A shape that is imported from a file
+///
+/// Allows models to make use of geometry that has been created elsewhere, like
+/// the models of purchased parts that manufacturers provide, and combine it
+/// with the shapes defined in code.
+///
+/// STEP files are supported. They may contain planar and cylindrical faces,
+/// bounded by lines and circles. Their lengths are converted into millimeters.
+///
+/// Shapes that Fornjot has saved as `.fjshape` files can be imported too. That
+/// way, expensive shapes don't need to be computed again.
+///
+/// The file is read by the host application, when it processes the shape, not
+/// when the model is evaluated. Relative paths are resolved against the
+/// working directory of the host application, so it's best to refer to files
+/// relative to the model's manifest directory.
+///
+/// # Examples
+///
+/// ``` rust
+/// let motor = fj::Import::from_path(concat!(
+///     env!("CARGO_MANIFEST_DIR"),
+///     "/motor.step"
+/// ));
+/// ```
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[repr(C)]
+pub struct Import {
+    path: ffi_safe::String,
+}
+
+impl Import {
+    /// Create an `Import` from the path of the file to import
+    pub fn from_path(path: impl AsRef<Path>) -> Self {
+        Self {
+            path: path.as_ref().to_string_lossy().into_owned().into(),
+        }
+    }
+
+    /// Access the path of the file to import
+    pub fn path(&self) -> &str {
+        &self.path
+    }
+}
+
+impl From<Import> for Shape {
+    fn from(shape: Import) -> Self {
+        Self::Import(shape)
+    }
+}
+
+/// A 2-dimensional shape that is imported from a file
+///
+/// Allows models to make use of 2D drawings that have been created elsewhere,
+/// for example to extrude them, without having to re-create every point in
+/// code.
+///
+/// Currently, only DXF files are supported. The lines, arcs, circles, and
+/// polylines of the drawing need to form closed contours. Each contour becomes
+/// a face in the xy-plane, with the contours that lie within it as its holes.
+/// Lengths are converted into millimeters.
+///
+/// Like with [`Import`], the file is read by the host application, and relative
+/// paths are resolved against its working directory.
+///
+/// # Examples
+///
+/// ``` rust
+/// let outline = fj::Import2d::from_path(concat!(
+///     env!("CARGO_MANIFEST_DIR"),
+///     "/outline.dxf"
+/// ));
+/// let plate = fj::Sweep::from_path(outline.into(), [0., 0., 3.]);
+/// ```
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[repr(C)]
+pub struct Import2d {
+    path: ffi_safe::String,
+}
+
+impl Import2d {
+    /// Create an `Import2d` from the path of the file to import
+    pub fn from_path(path: impl AsRef<Path>) -> Self {
+        Self {
+            path: path.as_ref().to_string_lossy().into_owned().into(),
+        }
+    }
+
+    /// Access the path of the file to import
+    pub fn path(&self) -> &str {
+        &self.path
+    }
+
+    /// Get the rendering color of the imported shape in RGBA
+    pub fn color(&self) -> [u8; 4] {
+        [255, 0, 0, 255]
+    }
+}
+
+impl From<Import2d> for Shape {
+    fn from(shape: Import2d) -> Self {
+        Self::Shape2d(shape.into())
+    }
+}
+
+impl From<Import2d> for Shape2d {
+    fn from(shape: Import2d) -> Self {
+        Self::Import(shape)
+    }
+}
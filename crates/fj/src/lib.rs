@@ -23,15 +23,45 @@ pub mod syntax;
 #[doc(hidden)]
 pub mod abi;
 mod angle;
+mod assembly;
+mod attributes;
+mod boolean;
+mod brep;
+mod constraints;
 mod group;
+mod hole;
+mod import;
 pub mod models;
+mod part;
+mod pattern;
+mod rng;
 mod shape_2d;
 mod sweep;
 mod transform;
+mod units;
 pub mod version;
 
 pub use self::{
-    angle::*, group::Group, shape_2d::*, sweep::Sweep, transform::Transform,
+    angle::*,
+    assembly::{Assembly, InstanceId, Mate, MateAxis, MatePlane},
+    attributes::Attributes,
+    boolean::{Boolean, BooleanKind},
+    brep::{Brep, BrepError, BrepFace},
+    constraints::{
+        ConstrainedSketch, Constraint, PointId, Solution, SolveError,
+    },
+    group::Group,
+    hole::{Hole, HoleDepth, HoleHead, HoleProfile, Holes},
+    import::{Import, Import2d},
+    part::Part,
+    pattern::{Pattern, PatternKind},
+    rng::Rng,
+    shape_2d::*,
+    sweep::{
+        FrameOrientation, HelicalSweep, Helix, PolylinePath, Sweep, SweepPath,
+    },
+    transform::{Mirror, Transform},
+    units::Units,
 };
 pub use fj_proc::*;
 
@@ -49,6 +79,33 @@ pub enum Shape {
     /// A sweep of 2-dimensional shape along the z-axis
     Sweep(Sweep),
 
+    /// A sweep of a 2-dimensional shape along a helix
+    HelicalSweep(HelicalSweep),
+
+    /// A sweep with holes drilled into it
+    Holes(Holes),
+
+    /// A named part of a model
+    Part(Box<Part>),
+
+    /// Parts that are placed relative to each other by mates
+    Assembly(Box<Assembly>),
+
     /// A transformed 3-dimensional shape
     Transform(Box<Transform>),
+
+    /// A 3-dimensional shape, mirrored across a plane
+    Mirror(Box<Mirror>),
+
+    /// A 3-dimensional shape, replicated in a regular pattern
+    Pattern(Box<Pattern>),
+
+    /// A boolean operation on two 3-dimensional shapes
+    Boolean(Box<Boolean>),
+
+    /// A shape that is defined by its boundary representation
+    Brep(Brep),
+
+    /// A shape that is imported from a file
+    Import(Import),
 }
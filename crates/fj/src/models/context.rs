@@ -3,6 +3,13 @@
 pub trait Context {
     /// Get an argument that was passed to this model.
     fn get_argument(&self, name: &str) -> Option<&str>;
+
+    /// Get the seed for random numbers that the model generates
+    ///
+    /// The seed is chosen by the user, and stays the same between evaluations
+    /// otherwise. Models that use it to seed their random numbers produce the
+    /// same shape on every evaluation. See [`Rng`][crate::Rng].
+    fn seed(&self) -> u64;
 }
 
 #[cfg(test)]
@@ -132,6 +132,9 @@ pub struct ModelMetadata {
 
     /// Arguments that the model uses when calculating its geometry.
     pub arguments: Vec<ArgumentMetadata>,
+
+    /// Capabilities that the model requires from, or offers to, the host.
+    pub capabilities: Capabilities,
 }
 
 impl ModelMetadata {
@@ -148,6 +151,7 @@ impl ModelMetadata {
             name,
             description: None,
             arguments: Vec::new(),
+            capabilities: Capabilities::default(),
         }
     }
 
@@ -172,6 +176,77 @@ impl ModelMetadata {
         self.arguments.push(arg.into());
         self
     }
+
+    /// Set the [`ModelMetadata::capabilities`].
+    pub fn with_capabilities(self, capabilities: Capabilities) -> Self {
+        ModelMetadata {
+            capabilities,
+            ..self
+        }
+    }
+}
+
+/// Capabilities that a model requires from, or offers to, the host.
+///
+/// The host checks these before evaluating the model, which allows it to
+/// report a missing capability right away, instead of failing somewhere in the
+/// middle of the evaluation.
+#[derive(Debug, Clone, Default, Eq, PartialEq)]
+pub struct Capabilities {
+    /// The model uses boolean operations between solids.
+    pub needs_booleans: bool,
+
+    /// The model renders text, which requires the host to provide fonts.
+    pub needs_fonts: bool,
+
+    /// The minimum version of the Fornjot kernel that the model requires.
+    ///
+    /// This is interpreted as a semver-compliant version number (e.g.
+    /// `0.27.0`).
+    pub min_kernel_version: Option<String>,
+
+    /// The model checks for cancellation while it is being evaluated, and can
+    /// be stopped before it finishes.
+    pub supports_cancellation: bool,
+}
+
+impl Capabilities {
+    /// Set the [`Capabilities::needs_booleans`] flag.
+    pub fn with_booleans(self) -> Self {
+        Capabilities {
+            needs_booleans: true,
+            ..self
+        }
+    }
+
+    /// Set the [`Capabilities::needs_fonts`] flag.
+    pub fn with_fonts(self) -> Self {
+        Capabilities {
+            needs_fonts: true,
+            ..self
+        }
+    }
+
+    /// Set the [`Capabilities::min_kernel_version`].
+    pub fn with_min_kernel_version(self, version: impl Into<String>) -> Self {
+        let version = version.into();
+        if version.is_empty() {
+            return self;
+        }
+
+        Capabilities {
+            min_kernel_version: Some(version),
+            ..self
+        }
+    }
+
+    /// Set the [`Capabilities::supports_cancellation`] flag.
+    pub fn with_cancellation(self) -> Self {
+        Capabilities {
+            supports_cancellation: true,
+            ..self
+        }
+    }
 }
 
 /// Metadata describing a model's argument.
@@ -8,7 +8,7 @@ mod model;
 pub use self::{
     context::Context,
     host::{Host, HostExt},
-    metadata::{ArgumentMetadata, Metadata, ModelMetadata},
+    metadata::{ArgumentMetadata, Capabilities, Metadata, ModelMetadata},
     model::Model,
 };
 
@@ -0,0 +1,157 @@
+use crate::{abi::ffi_safe, Angle, Shape, Units};
+
+/// A named part of a model
+///
+/// A model can consist of multiple parts, which are combined using
+/// [`crate::Group`]. Naming them makes it possible to tell them apart, for
+/// example when exporting each part to a separate file.
+///
+/// # Examples
+///
+/// Convenient syntax for this operation is available through [`crate::syntax`].
+///
+/// ``` rust
+/// # let a = fj::Sketch::from_points(vec![[0., 0.], [1., 0.], [0., 1.]]);
+/// # let b = fj::Sketch::from_points(vec![[2., 0.], [3., 0.], [2., 1.]]);
+/// use fj::syntax::*;
+///
+/// // `a` and `b` can be anything that converts to `fj::Shape`
+/// let model = a.part("base").group(&b.part("lid"));
+/// ```
+///
+/// Parts can override how finely they are approximated, when they are
+/// triangulated, for example to show the small fillets of one part smoothly,
+/// without slowing down the triangulation of the others.
+///
+/// ``` rust
+/// # let a = fj::Sketch::from_points(vec![[0., 0.], [1., 0.], [0., 1.]]);
+/// let part = fj::Part::new("knob", a)
+///     .with_tolerance(0.01)
+///     .with_max_angle(fj::Angle::from_deg(5.));
+/// ```
+///
+/// Parts can also change the appearance of their faces, and assign them a
+/// material, for example to print them from a different filament.
+///
+/// ``` rust
+/// # let a = fj::Sketch::from_points(vec![[0., 0.], [1., 0.], [0., 1.]]);
+/// let part = fj::Part::new("seal", a)
+///     .with_color([0, 0, 0, 255])
+///     .with_material("TPU");
+/// ```
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[repr(C)]
+pub struct Part {
+    name: ffi_safe::String,
+    shape: Shape,
+    tolerance: ffi_safe::Option<f64>,
+    max_angle: ffi_safe::Option<Angle>,
+    color: ffi_safe::Option<[u8; 4]>,
+    material: ffi_safe::Option<ffi_safe::String>,
+    units: Units,
+}
+
+impl Part {
+    /// Create a `Part` from a name and a shape
+    pub fn new(name: impl Into<String>, shape: impl Into<Shape>) -> Self {
+        Self {
+            name: name.into().into(),
+            shape: shape.into(),
+            tolerance: ffi_safe::Option::None,
+            max_angle: ffi_safe::Option::None,
+            color: ffi_safe::Option::None,
+            material: ffi_safe::Option::None,
+            units: Units::Millimeters,
+        }
+    }
+
+    /// Approximate the part with the given tolerance
+    ///
+    /// This overrides the tolerance that the host application chooses. It is
+    /// the maximum distance between the triangle mesh and the exact geometry.
+    pub fn with_tolerance(mut self, tolerance: f64) -> Self {
+        self.tolerance = Some(tolerance).into();
+        self
+    }
+
+    /// Limit the angle between adjacent segments of approximated curves
+    ///
+    /// This overrides the angle that the host application chooses. Curves and
+    /// surfaces are refined where they turn by more than this angle, even if
+    /// the tolerance would allow a coarser approximation.
+    pub fn with_max_angle(mut self, max_angle: Angle) -> Self {
+        self.max_angle = Some(max_angle).into();
+        self
+    }
+
+    /// Render all faces of the part in the given color (RGBA)
+    ///
+    /// This overrides the colors of the shapes that make up the part.
+    pub fn with_color(mut self, color: [u8; 4]) -> Self {
+        self.color = Some(color).into();
+        self
+    }
+
+    /// Assign a material to the faces of the part
+    ///
+    /// Faces that have been assigned a material already, for example by
+    /// [`crate::Sketch::with_material`], keep theirs.
+    pub fn with_material(mut self, material: impl Into<String>) -> Self {
+        self.material = Some(material.into()).into();
+        self
+    }
+
+    /// Specify the units that the lengths of the part are given in
+    ///
+    /// Defaults to millimeters. Exporters record the units in the exported
+    /// file, or convert the part into the units that were asked for.
+    pub fn with_units(mut self, units: Units) -> Self {
+        self.units = units;
+        self
+    }
+
+    /// Access the name of the part
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Access the shape of the part
+    pub fn shape(&self) -> &Shape {
+        &self.shape
+    }
+
+    /// Access the tolerance that the part is approximated with, if overridden
+    pub fn tolerance(&self) -> Option<f64> {
+        self.tolerance.into()
+    }
+
+    /// Access the maximum angle between segments, if overridden
+    pub fn max_angle(&self) -> Option<Angle> {
+        self.max_angle.into()
+    }
+
+    /// Access the color of the part's faces, if overridden
+    pub fn color(&self) -> Option<[u8; 4]> {
+        self.color.into()
+    }
+
+    /// Access the material of the part's faces, if assigned
+    pub fn material(&self) -> Option<&str> {
+        match &self.material {
+            ffi_safe::Option::Some(material) => Some(material),
+            ffi_safe::Option::None => None,
+        }
+    }
+
+    /// Access the units that the lengths of the part are given in
+    pub fn units(&self) -> Units {
+        self.units
+    }
+}
+
+impl From<Part> for Shape {
+    fn from(shape: Part) -> Self {
+        Self::Part(Box::new(shape))
+    }
+}
@@ -0,0 +1,121 @@
+use crate::Shape;
+
+/// A 3-dimensional shape, replicated in a regular pattern
+///
+/// The instances of the shape are placed in a row, or evenly distributed
+/// around an axis. A grid can be created by patterning a linear pattern.
+///
+/// # Examples
+///
+/// Convenient syntax for this operation is available through [`crate::syntax`].
+///
+/// ``` rust
+/// # let shape = fj::Sketch::from_points(vec![[0., 0.], [1., 0.], [0., 1.]])
+/// #     .sweep([0., 0., 1.]);
+/// use fj::syntax::*;
+///
+/// // `shape` can be anything that converts to `fj::Shape`
+/// let row = shape.pattern_linear([2., 0., 0.], 4);
+/// let grid = shape.pattern_linear([2., 0., 0.], 4).pattern_linear([0., 2., 0.], 3);
+/// let circle = shape.pattern_circular([5., 0., 0.], [0., 0., 1.], 6);
+/// ```
+///
+/// # Limitations
+///
+/// Like the shapes in a [`Group`], the instances are not allowed to touch or
+/// overlap. Whether they do is not currently checked.
+///
+/// [`Group`]: crate::Group
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[repr(C)]
+pub struct Pattern {
+    shape: Shape,
+    kind: PatternKind,
+    count: u32,
+}
+
+impl Pattern {
+    /// Replicate a shape in a row
+    ///
+    /// Each instance is offset from the previous one by `offset`. `count`
+    /// includes the original shape.
+    ///
+    /// # Panics
+    ///
+    /// Panics, if `count` is zero.
+    pub fn linear(shape: Shape, offset: [f64; 3], count: u32) -> Self {
+        Self::new(shape, PatternKind::Linear { offset }, count)
+    }
+
+    /// Replicate a shape around an axis
+    ///
+    /// The axis is defined by a point on it, `center`, and its direction. The
+    /// instances are distributed evenly over a full turn, `count` including
+    /// the original shape.
+    ///
+    /// # Panics
+    ///
+    /// Panics, if `axis` has zero length, or `count` is zero.
+    pub fn circular(
+        shape: Shape,
+        center: [f64; 3],
+        axis: [f64; 3],
+        count: u32,
+    ) -> Self {
+        assert!(
+            axis.iter().any(|&component| component != 0.),
+            "Axis of circular pattern must not have zero length"
+        );
+
+        Self::new(shape, PatternKind::Circular { center, axis }, count)
+    }
+
+    fn new(shape: Shape, kind: PatternKind, count: u32) -> Self {
+        assert!(count > 0, "Pattern must have at least one instance");
+
+        Self { shape, kind, count }
+    }
+
+    /// Access the shape being replicated
+    pub fn shape(&self) -> &Shape {
+        &self.shape
+    }
+
+    /// Access the kind of pattern
+    pub fn kind(&self) -> &PatternKind {
+        &self.kind
+    }
+
+    /// Access the number of instances, including the original shape
+    pub fn count(&self) -> u32 {
+        self.count
+    }
+}
+
+impl From<Pattern> for Shape {
+    fn from(shape: Pattern) -> Self {
+        Self::Pattern(Box::new(shape))
+    }
+}
+
+/// The arrangement of the instances in a [`Pattern`]
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[repr(C)]
+pub enum PatternKind {
+    /// Instances in a row
+    Linear {
+        /// The offset between two consecutive instances
+        offset: [f64; 3],
+    },
+
+    /// Instances evenly distributed around an axis
+    Circular {
+        /// A point on the axis
+        center: [f64; 3],
+
+        /// The direction of the axis
+        axis: [f64; 3],
+    },
+}
@@ -0,0 +1,116 @@
+use std::ops::Range;
+
+/// A random number generator, seeded by the host
+///
+/// Models that are generated randomly should use this generator, instead of
+/// one that is seeded by the operating system. The host passes the same seed to
+/// every evaluation, unless the user chooses a different one. That way, a model
+/// produces the same shape every time, on every machine.
+///
+/// Create a generator using [`Rng::from_seed`] and the seed from
+/// [`Context::seed`], or add an argument of this type to a function-based
+/// model. See [`model`].
+///
+/// The numbers are generated using SplitMix64. They are good enough to
+/// generate shapes, but not suitable for cryptography.
+///
+/// # Examples
+///
+/// ``` rust
+/// #[fj::model]
+/// pub fn scattered(#[param(default = 5)] num_pillars: u32, mut rng: fj::Rng) -> fj::Shape {
+///     use fj::syntax::*;
+///
+///     let mut pillars = Vec::new();
+///     for _ in 0..num_pillars {
+///         let x = rng.range(-10. ..10.);
+///         let y = rng.range(-10. ..10.);
+///         let radius = rng.range(0.5..1.);
+///
+///         let pillar = fj::Sketch::from_circle(fj::Circle::from_radius(radius))
+///             .sweep([0., 0., 5.])
+///             .translate([x, y, 0.]);
+///         pillars.push(fj::Shape::from(pillar));
+///     }
+///
+///     pillars
+///         .into_iter()
+///         .reduce(|a, b| a.group(&b).into())
+///         .expect("Model has at least one pillar")
+/// }
+/// ```
+///
+/// [`Context::seed`]: crate::models::Context::seed
+/// [`model`]: crate::model
+#[derive(Clone, Debug)]
+pub struct Rng {
+    state: u64,
+}
+
+impl Rng {
+    /// Create a generator from a seed
+    ///
+    /// Generators with the same seed produce the same numbers.
+    pub fn from_seed(seed: u64) -> Self {
+        Self { state: seed }
+    }
+
+    /// Generate a random integer
+    pub fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9e37_79b9_7f4a_7c15);
+
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^ (z >> 31)
+    }
+
+    /// Generate a random number from 0 (inclusive) to 1 (exclusive)
+    pub fn next_f64(&mut self) -> f64 {
+        // A `f64` has 53 bits of precision. Using the top bits of the integer
+        // results in evenly spaced numbers.
+        (self.next_u64() >> 11) as f64 / (1u64 << 53) as f64
+    }
+
+    /// Generate a random number within a range
+    pub fn range(&mut self, range: Range<f64>) -> f64 {
+        range.start + (range.end - range.start) * self.next_f64()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Rng;
+
+    #[test]
+    fn same_seed_produces_same_numbers() {
+        let mut a = Rng::from_seed(3);
+        let mut b = Rng::from_seed(3);
+        let mut c = Rng::from_seed(4);
+
+        let a = (0..8).map(|_| a.next_u64()).collect::<Vec<_>>();
+        let b = (0..8).map(|_| b.next_u64()).collect::<Vec<_>>();
+        let c = (0..8).map(|_| c.next_u64()).collect::<Vec<_>>();
+
+        assert_eq!(a, b);
+        assert_ne!(a, c);
+    }
+
+    #[test]
+    fn numbers_are_stable() {
+        // Models rely on the same seed producing the same shape, across
+        // versions of Fornjot. Changing the algorithm breaks that.
+        let mut rng = Rng::from_seed(1234567);
+        assert_eq!(rng.next_u64(), 0x599e_d017_fb08_fc85);
+    }
+
+    #[test]
+    fn range() {
+        let mut rng = Rng::from_seed(0);
+
+        for _ in 0..1000 {
+            let value = rng.range(-2. ..3.);
+            assert!((-2. ..3.).contains(&value));
+        }
+    }
+}
@@ -1,4 +1,4 @@
-use crate::{abi::ffi_safe, Shape};
+use crate::{abi::ffi_safe, Angle, Attributes, Import2d, Shape};
 
 /// A 2-dimensional shape
 #[derive(Clone, Debug, PartialEq)]
@@ -10,6 +10,9 @@ pub enum Shape2d {
 
     /// A sketch
     Sketch(Sketch),
+
+    /// A 2D shape that is imported from a file
+    Import(Import2d),
 }
 
 impl Shape2d {
@@ -18,6 +21,7 @@ impl Shape2d {
         match &self {
             Shape2d::Sketch(s) => s.color(),
             Shape2d::Difference(d) => d.color(),
+            Shape2d::Import(i) => i.color(),
         }
     }
 }
@@ -98,6 +102,7 @@ impl From<Difference2d> for Shape2d {
 pub struct Sketch {
     chain: Chain,
     color: [u8; 4],
+    attributes: Attributes,
 }
 
 impl Sketch {
@@ -106,6 +111,7 @@ impl Sketch {
         Self {
             chain: Chain::PolyChain(PolyChain::from_points(points)),
             color: [255, 0, 0, 255],
+            attributes: Attributes::new(),
         }
     }
 
@@ -114,6 +120,43 @@ impl Sketch {
         Self {
             chain: Chain::Circle(circle),
             color: [255, 0, 0, 255],
+            attributes: Attributes::new(),
+        }
+    }
+
+    /// Create a sketch from an ellipse
+    pub fn from_ellipse(ellipse: Ellipse) -> Self {
+        Self {
+            chain: Chain::Ellipse(ellipse),
+            color: [255, 0, 0, 255],
+            attributes: Attributes::new(),
+        }
+    }
+
+    /// Create a sketch from a rectangle
+    pub fn from_rectangle(rectangle: Rectangle) -> Self {
+        Self {
+            chain: Chain::Rectangle(rectangle),
+            color: [255, 0, 0, 255],
+            attributes: Attributes::new(),
+        }
+    }
+
+    /// Create a sketch from a regular polygon
+    pub fn from_regular_polygon(polygon: RegularPolygon) -> Self {
+        Self {
+            chain: Chain::RegularPolygon(polygon),
+            color: [255, 0, 0, 255],
+            attributes: Attributes::new(),
+        }
+    }
+
+    /// Create a sketch from a slot
+    pub fn from_slot(slot: Slot) -> Self {
+        Self {
+            chain: Chain::Slot(slot),
+            color: [255, 0, 0, 255],
+            attributes: Attributes::new(),
         }
     }
 
@@ -123,6 +166,24 @@ impl Sketch {
         self
     }
 
+    /// Set the name of the sketch's faces
+    ///
+    /// Faces that are created from the sketch, like the sides of a sweep, get
+    /// the same name.
+    pub fn with_name(mut self, name: impl Into<String>) -> Self {
+        self.attributes = self.attributes.with_name(name);
+        self
+    }
+
+    /// Set the material of the sketch's faces
+    ///
+    /// Like the name, the material carries over to faces that are created
+    /// from the sketch.
+    pub fn with_material(mut self, material: impl Into<String>) -> Self {
+        self.attributes = self.attributes.with_material(material);
+        self
+    }
+
     /// Access the chain of the sketch
     pub fn chain(&self) -> &Chain {
         &self.chain
@@ -132,6 +193,11 @@ impl Sketch {
     pub fn color(&self) -> [u8; 4] {
         self.color
     }
+
+    /// Access the attributes of the sketch's faces
+    pub fn attributes(&self) -> &Attributes {
+        &self.attributes
+    }
 }
 
 impl From<Sketch> for Shape {
@@ -154,8 +220,20 @@ pub enum Chain {
     /// The chain is a circle
     Circle(Circle),
 
+    /// The chain is an ellipse
+    Ellipse(Ellipse),
+
     /// The chain is a polygonal chain
     PolyChain(PolyChain),
+
+    /// The chain is a rectangle, possibly with rounded corners
+    Rectangle(Rectangle),
+
+    /// The chain is a regular polygon
+    RegularPolygon(RegularPolygon),
+
+    /// The chain is a slot
+    Slot(Slot),
 }
 
 /// A circle that is part of a [`Sketch`]
@@ -165,18 +243,216 @@ pub enum Chain {
 pub struct Circle {
     /// The radius of the circle
     radius: f64,
+
+    /// How the circle should be segmented when it is approximated
+    segmentation: Segmentation,
 }
 
 impl Circle {
     /// Construct a new circle with a specific radius
     pub fn from_radius(radius: f64) -> Self {
-        Self { radius }
+        Self {
+            radius,
+            segmentation: Segmentation::Tolerance,
+        }
+    }
+
+    /// Limit the angle that each segment of the circle's approximation spans
+    ///
+    /// The approximation still honors the tolerance, if that results in
+    /// smaller segments.
+    pub fn with_max_segment_angle(mut self, angle: Angle) -> Self {
+        self.segmentation = Segmentation::MaxAngle(angle);
+        self
+    }
+
+    /// Mark the circle as exact
+    ///
+    /// Exporters that support true arcs should preserve the circle as such.
+    /// Everything else approximates it according to the tolerance.
+    pub fn exact(mut self) -> Self {
+        self.segmentation = Segmentation::Exact;
+        self
     }
 
     /// Access the circle's radius
     pub fn radius(&self) -> f64 {
         self.radius
     }
+
+    /// Access the circle's segmentation
+    pub fn segmentation(&self) -> Segmentation {
+        self.segmentation
+    }
+}
+
+/// An ellipse that is part of a [`Sketch`]
+///
+/// The ellipse is centered on the origin, with its semi-axes aligned with the
+/// x- and y-axis.
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[repr(C)]
+pub struct Ellipse {
+    /// The radius of the ellipse along the x-axis
+    radius_x: f64,
+
+    /// The radius of the ellipse along the y-axis
+    radius_y: f64,
+}
+
+impl Ellipse {
+    /// Construct a new ellipse with the radii along the x- and y-axis
+    pub fn from_radii(radius_x: f64, radius_y: f64) -> Self {
+        Self { radius_x, radius_y }
+    }
+
+    /// Access the ellipse's radius along the x-axis
+    pub fn radius_x(&self) -> f64 {
+        self.radius_x
+    }
+
+    /// Access the ellipse's radius along the y-axis
+    pub fn radius_y(&self) -> f64 {
+        self.radius_y
+    }
+}
+
+/// A rectangle that is part of a [`Sketch`]
+///
+/// The rectangle is centered on the origin, with its sides aligned with the x-
+/// and y-axis.
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[repr(C)]
+pub struct Rectangle {
+    /// The width of the rectangle, along the x-axis
+    width: f64,
+
+    /// The height of the rectangle, along the y-axis
+    height: f64,
+
+    /// The radius of the rectangle's corners
+    corner_radius: f64,
+}
+
+impl Rectangle {
+    /// Construct a new rectangle with the given width and height
+    pub fn from_size(width: f64, height: f64) -> Self {
+        Self {
+            width,
+            height,
+            corner_radius: 0.,
+        }
+    }
+
+    /// Round the corners of the rectangle
+    ///
+    /// The radius must be smaller than half of the width and of the height.
+    pub fn with_corner_radius(mut self, radius: f64) -> Self {
+        self.corner_radius = radius;
+        self
+    }
+
+    /// Access the rectangle's width
+    pub fn width(&self) -> f64 {
+        self.width
+    }
+
+    /// Access the rectangle's height
+    pub fn height(&self) -> f64 {
+        self.height
+    }
+
+    /// Access the radius of the rectangle's corners
+    ///
+    /// A radius of zero means that the corners are sharp.
+    pub fn corner_radius(&self) -> f64 {
+        self.corner_radius
+    }
+}
+
+/// A regular polygon that is part of a [`Sketch`]
+///
+/// The polygon is centered on the origin. Its first corner is on the x-axis.
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[repr(C)]
+pub struct RegularPolygon {
+    /// The radius of the circle that the polygon's corners are on
+    radius: f64,
+
+    /// The number of sides of the polygon
+    num_sides: u32,
+}
+
+impl RegularPolygon {
+    /// Construct a new regular polygon
+    ///
+    /// The polygon must have at least 3 sides.
+    pub fn from_radius_and_sides(radius: f64, num_sides: u32) -> Self {
+        Self { radius, num_sides }
+    }
+
+    /// Access the radius of the circle that the polygon's corners are on
+    pub fn radius(&self) -> f64 {
+        self.radius
+    }
+
+    /// Access the polygon's number of sides
+    pub fn num_sides(&self) -> u32 {
+        self.num_sides
+    }
+}
+
+/// A slot that is part of a [`Sketch`]
+///
+/// A slot is made up of two semicircles, connected by straight sides. It is
+/// centered on the origin, with its straight sides parallel to the x-axis.
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[repr(C)]
+pub struct Slot {
+    /// The distance between the centers of the semicircles
+    length: f64,
+
+    /// The radius of the semicircles
+    radius: f64,
+}
+
+impl Slot {
+    /// Construct a new slot
+    ///
+    /// The length is the distance between the centers of the semicircles, and
+    /// must be larger than zero.
+    pub fn from_length_and_radius(length: f64, radius: f64) -> Self {
+        Self { length, radius }
+    }
+
+    /// Access the distance between the centers of the semicircles
+    pub fn length(&self) -> f64 {
+        self.length
+    }
+
+    /// Access the radius of the semicircles
+    pub fn radius(&self) -> f64 {
+        self.radius
+    }
+}
+
+/// How a [`Circle`] should be segmented when it is approximated
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[repr(C)]
+pub enum Segmentation {
+    /// Segment the circle according to the tolerance
+    Tolerance,
+
+    /// Segment the circle, so no segment spans more than the given angle
+    MaxAngle(Angle),
+
+    /// The circle is exact and should be preserved, where possible
+    Exact,
 }
 
 /// A polygonal chain that is part of a [`Sketch`]
@@ -197,9 +473,14 @@ impl PolyChain {
         Self { segments: points }
     }
 
-    /// Return the points that define the polygonal chain
+    /// Access the segments that define the polygonal chain
+    pub fn segments(&self) -> &[SketchSegment] {
+        &self.segments
+    }
+
+    /// Return the segments that define the polygonal chain
     pub fn to_segments(&self) -> Vec<SketchSegment> {
-        self.segments.clone().into()
+        self.segments.to_vec()
     }
 }
 
@@ -1,6 +1,8 @@
-use crate::{Shape, Shape2d};
+use crate::{abi::ffi_safe, Angle, Shape, Shape2d};
 
-/// A sweep of a 2-dimensional shape along straight path
+/// A sweep of a 2-dimensional shape along a path
+///
+/// The path is either straight, or a polygonal chain. See [`SweepPath`].
 ///
 /// # Examples
 ///
@@ -12,6 +14,15 @@ use crate::{Shape, Shape2d};
 ///
 /// // `shape` can be anything that converts to `fj::Shape2d`
 /// let group = shape.sweep([0., 0., 1.]);
+///
+/// // Side faces can be tapered, for example for molded parts
+/// let tapered = shape.sweep([0., 0., 1.]).with_draft(fj::Angle::from_deg(3.));
+///
+/// // A bent bar
+/// let bent = shape.sweep_along_path(
+///     vec![[0., 0., 0.], [0., 0., 5.], [5., 0., 10.]],
+///     fj::FrameOrientation::FixedUp { up: [0., 1., 0.] },
+/// );
 /// ```
 #[derive(Clone, Debug, PartialEq)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
@@ -20,14 +31,83 @@ pub struct Sweep {
     /// The 2-dimensional shape being swept
     shape: Shape2d,
 
-    /// The length and direction of the sweep
-    path: [f64; 3],
+    /// The path that the shape is swept along
+    path: SweepPath,
+
+    /// The draft angle of the side faces
+    draft: Angle,
 }
 
 impl Sweep {
     /// Create a `Sweep` along a straight path
     pub fn from_path(shape: Shape2d, path: [f64; 3]) -> Self {
-        Self { shape, path }
+        Self {
+            shape,
+            path: SweepPath::Straight { offset: path },
+            draft: Angle::from_rad(0.),
+        }
+    }
+
+    /// Create a `Sweep` along the polygonal chain through the given points
+    ///
+    /// See [`PolylinePath`].
+    ///
+    /// # Panics
+    ///
+    /// Panics, if less than two points are provided, or if two consecutive
+    /// points are coincident.
+    pub fn from_points(
+        shape: Shape2d,
+        points: Vec<[f64; 3]>,
+        orientation: FrameOrientation,
+    ) -> Self {
+        assert!(
+            points.len() >= 2,
+            "Sweep path must consist of at least two points"
+        );
+        for segment in points.windows(2) {
+            assert_ne!(
+                segment[0], segment[1],
+                "Sweep path must not contain coincident consecutive points"
+            );
+        }
+
+        Self {
+            shape,
+            path: SweepPath::Polyline(PolylinePath {
+                points: points.into(),
+                orientation,
+            }),
+            draft: Angle::from_rad(0.),
+        }
+    }
+
+    /// Taper the side faces of the sweep by the given draft angle
+    ///
+    /// The angle is measured between the side faces and the path. A positive
+    /// angle shrinks the shape towards the end of the sweep, a negative one
+    /// grows it. Holes in the shape are tapered the other way, so the walls
+    /// around them get the same draft.
+    ///
+    /// Since [`Angle`] wraps negative angles around, angles between 270 and 360
+    /// degrees are treated as negative draft angles.
+    ///
+    /// # Panics
+    ///
+    /// Panics, if the angle is not between -90 and 90 degrees, or if the path
+    /// of the sweep is not straight.
+    pub fn with_draft(mut self, angle: Angle) -> Self {
+        assert!(
+            angle.deg() < 90. || angle.deg() > 270.,
+            "Draft angle must be between -90 and 90 degrees"
+        );
+        assert!(
+            matches!(self.path, SweepPath::Straight { .. }),
+            "Only sweeps along a straight path can have a draft angle"
+        );
+
+        self.draft = angle;
+        self
     }
 
     /// Access the shape being swept
@@ -36,8 +116,13 @@ impl Sweep {
     }
 
     /// Access the path of the sweep
-    pub fn path(&self) -> [f64; 3] {
-        self.path
+    pub fn path(&self) -> &SweepPath {
+        &self.path
+    }
+
+    /// Access the draft angle of the sweep
+    pub fn draft(&self) -> Angle {
+        self.draft
     }
 }
 
@@ -46,3 +131,153 @@ impl From<Sweep> for Shape {
         Self::Sweep(shape)
     }
 }
+
+/// The path of a [`Sweep`]
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[repr(C)]
+pub enum SweepPath {
+    /// A straight path
+    ///
+    /// The shape is swept from where it is.
+    Straight {
+        /// The length and direction of the sweep
+        offset: [f64; 3],
+    },
+
+    /// A polygonal chain
+    Polyline(PolylinePath),
+}
+
+/// A path of a [`Sweep`] that is a polygonal chain
+///
+/// The shape is placed at the start of the path, perpendicular to its first
+/// segment, and rotated around it according to the [`FrameOrientation`]. The
+/// result is a single solid, which is bent wherever the path changes direction.
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[repr(C)]
+pub struct PolylinePath {
+    points: ffi_safe::Vec<[f64; 3]>,
+    orientation: FrameOrientation,
+}
+
+impl PolylinePath {
+    /// Access the points of the path
+    pub fn points(&self) -> &[[f64; 3]] {
+        &self.points
+    }
+
+    /// Access the orientation of the shape along the path
+    pub fn orientation(&self) -> FrameOrientation {
+        self.orientation
+    }
+}
+
+/// How a shape is rotated around a path, as it is swept along it
+///
+/// See [`PolylinePath`].
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[repr(C)]
+pub enum FrameOrientation {
+    /// Follow the curvature of the path
+    ///
+    /// The x-axis of the shape points towards the inside of the bends of the
+    /// path. Where the path is straight, the shape keeps its orientation.
+    Frenet,
+
+    /// Keep the shape upright
+    ///
+    /// The y-axis of the shape points as close to `up` as possible. Where the
+    /// path is parallel to `up`, the shape keeps its orientation.
+    FixedUp {
+        /// The direction that the y-axis of the shape points towards
+        up: [f64; 3],
+    },
+}
+
+/// A helix around the z-axis
+///
+/// Starts on the positive x-axis, and rises along the z-axis. A positive pitch
+/// results in a right-handed helix, a negative one in a left-handed helix.
+///
+/// See [`HelicalSweep`].
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[repr(C)]
+pub struct Helix {
+    /// The distance of the helix from the z-axis
+    pub radius: f64,
+
+    /// The distance the helix rises along the z-axis with each full turn
+    pub pitch: f64,
+
+    /// The number of turns of the helix
+    ///
+    /// Doesn't need to be a whole number.
+    pub turns: f64,
+}
+
+/// A sweep of a 2-dimensional shape along a helix
+///
+/// Useful for modeling springs and threads. The shape is placed at the start of
+/// the helix, perpendicular to it. Its x-axis points towards the z-axis, its
+/// y-axis points upwards along the z-axis (for a helix with a positive pitch).
+///
+/// # Examples
+///
+/// ``` rust
+/// # let shape = fj::Sketch::from_circle(fj::Circle::from_radius(0.1));
+/// use fj::syntax::*;
+///
+/// let spring = shape.sweep_along_helix(fj::Helix {
+///     radius: 1.,
+///     pitch: 0.5,
+///     turns: 5.,
+/// });
+/// ```
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[repr(C)]
+pub struct HelicalSweep {
+    /// The 2-dimensional shape being swept
+    shape: Shape2d,
+
+    /// The helix that the shape is swept along
+    helix: Helix,
+}
+
+impl HelicalSweep {
+    /// Create a `HelicalSweep` along the provided helix
+    ///
+    /// # Panics
+    ///
+    /// Panics, if the radius or the number of turns of the helix is not
+    /// positive.
+    pub fn from_helix(shape: Shape2d, helix: Helix) -> Self {
+        assert!(helix.radius > 0., "Helix radius must be positive");
+        assert!(
+            helix.turns > 0.,
+            "Helix must have a positive number of turns"
+        );
+
+        Self { shape, helix }
+    }
+
+    /// Access the shape being swept
+    pub fn shape(&self) -> &Shape2d {
+        &self.shape
+    }
+
+    /// Access the helix that the shape is swept along
+    pub fn helix(&self) -> Helix {
+        self.helix
+    }
+}
+
+impl From<HelicalSweep> for Shape {
+    fn from(shape: HelicalSweep) -> Self {
+        Self::HelicalSweep(shape)
+    }
+}
@@ -3,6 +3,52 @@
 //! This model defines extension traits, which provide convenient syntax for
 //! the various operations defined in this trait.
 
+/// Convenient syntax to create an [`fj::Boolean`]
+///
+/// [`fj::Boolean`]: crate::Boolean
+pub trait Boolean {
+    /// Create the union of `self` and `other`
+    fn union<Other>(&self, other: &Other) -> crate::Boolean
+    where
+        Other: Clone + Into<crate::Shape>;
+
+    /// Subtract `other` from `self`
+    fn subtract<Other>(&self, other: &Other) -> crate::Boolean
+    where
+        Other: Clone + Into<crate::Shape>;
+
+    /// Create the intersection of `self` and `other`
+    fn intersect<Other>(&self, other: &Other) -> crate::Boolean
+    where
+        Other: Clone + Into<crate::Shape>;
+}
+
+impl<T> Boolean for T
+where
+    T: Clone + Into<crate::Shape>,
+{
+    fn union<Other>(&self, other: &Other) -> crate::Boolean
+    where
+        Other: Clone + Into<crate::Shape>,
+    {
+        crate::Boolean::union(self.clone().into(), other.clone().into())
+    }
+
+    fn subtract<Other>(&self, other: &Other) -> crate::Boolean
+    where
+        Other: Clone + Into<crate::Shape>,
+    {
+        crate::Boolean::difference(self.clone().into(), other.clone().into())
+    }
+
+    fn intersect<Other>(&self, other: &Other) -> crate::Boolean
+    where
+        Other: Clone + Into<crate::Shape>,
+    {
+        crate::Boolean::intersection(self.clone().into(), other.clone().into())
+    }
+}
+
 /// Convenient syntax to create an [`fj::Difference2d`]
 ///
 /// [`fj::Difference2d`]: crate::Difference2d
@@ -53,6 +99,82 @@ where
     }
 }
 
+/// Convenient syntax to create an [`fj::Holes`]
+///
+/// [`fj::Holes`]: crate::Holes
+pub trait Hole {
+    /// Drill a hole into `self`
+    fn hole(&self, hole: crate::Hole) -> crate::Holes;
+}
+
+impl<T> Hole for T
+where
+    T: Clone + Into<crate::Holes>,
+{
+    fn hole(&self, hole: crate::Hole) -> crate::Holes {
+        let holes = self.clone().into();
+        holes.with_hole(hole)
+    }
+}
+
+/// Convenient syntax to create an [`fj::Part`]
+///
+/// [`fj::Part`]: crate::Part
+pub trait Part {
+    /// Create a part with the given name from `self`
+    fn part(&self, name: &str) -> crate::Part;
+}
+
+impl<T> Part for T
+where
+    T: Clone + Into<crate::Shape>,
+{
+    fn part(&self, name: &str) -> crate::Part {
+        crate::Part::new(name, self.clone())
+    }
+}
+
+/// Convenient syntax to create an [`fj::Pattern`]
+///
+/// [`fj::Pattern`]: crate::Pattern
+pub trait Pattern {
+    /// Replicate `self` in a row
+    ///
+    /// Create `count` instances, each offset from the previous one by `offset`.
+    fn pattern_linear(&self, offset: [f64; 3], count: u32) -> crate::Pattern;
+
+    /// Replicate `self` around an axis
+    ///
+    /// Create `count` instances, evenly distributed around the axis through
+    /// `center`, with direction `axis`.
+    fn pattern_circular(
+        &self,
+        center: [f64; 3],
+        axis: [f64; 3],
+        count: u32,
+    ) -> crate::Pattern;
+}
+
+impl<T> Pattern for T
+where
+    T: Clone + Into<crate::Shape>,
+{
+    fn pattern_linear(&self, offset: [f64; 3], count: u32) -> crate::Pattern {
+        let shape = self.clone().into();
+        crate::Pattern::linear(shape, offset, count)
+    }
+
+    fn pattern_circular(
+        &self,
+        center: [f64; 3],
+        axis: [f64; 3],
+        count: u32,
+    ) -> crate::Pattern {
+        let shape = self.clone().into();
+        crate::Pattern::circular(shape, center, axis, count)
+    }
+}
+
 /// Convenient syntax to create an [`fj::Sketch`]
 ///
 /// [`fj::Sketch`]: crate::Sketch
@@ -79,6 +201,16 @@ where
 pub trait Sweep {
     /// Sweep `self` along a straight path
     fn sweep(&self, path: [f64; 3]) -> crate::Sweep;
+
+    /// Sweep `self` along the polygonal chain through the given points
+    fn sweep_along_path(
+        &self,
+        points: Vec<[f64; 3]>,
+        orientation: crate::FrameOrientation,
+    ) -> crate::Sweep;
+
+    /// Sweep `self` along a helix
+    fn sweep_along_helix(&self, helix: crate::Helix) -> crate::HelicalSweep;
 }
 
 impl<T> Sweep for T
@@ -89,6 +221,20 @@ where
         let shape = self.clone().into();
         crate::Sweep::from_path(shape, path)
     }
+
+    fn sweep_along_path(
+        &self,
+        points: Vec<[f64; 3]>,
+        orientation: crate::FrameOrientation,
+    ) -> crate::Sweep {
+        let shape = self.clone().into();
+        crate::Sweep::from_points(shape, points, orientation)
+    }
+
+    fn sweep_along_helix(&self, helix: crate::Helix) -> crate::HelicalSweep {
+        let shape = self.clone().into();
+        crate::HelicalSweep::from_helix(shape, helix)
+    }
 }
 
 /// Convenient syntax to create an [`fj::Transform`]
@@ -105,6 +251,12 @@ pub trait Transform {
     ///
     /// Create a translation that translates `shape` by `offset`.
     fn translate(&self, offset: [f64; 3]) -> crate::Transform;
+
+    /// Create a mirror image
+    ///
+    /// Create a mirror image of `shape` across the plane defined by `origin`
+    /// and `normal`.
+    fn mirror(&self, origin: [f64; 3], normal: [f64; 3]) -> crate::Mirror;
 }
 
 impl<T> Transform for T
@@ -130,4 +282,9 @@ where
             offset,
         }
     }
+
+    fn mirror(&self, origin: [f64; 3], normal: [f64; 3]) -> crate::Mirror {
+        let shape = self.clone().into();
+        crate::Mirror::across_plane(shape, origin, normal)
+    }
 }
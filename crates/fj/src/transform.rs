@@ -44,3 +44,92 @@ impl From<Transform> for Shape {
         Self::Transform(Box::new(shape))
     }
 }
+
+/// A 3-dimensional shape, mirrored across a plane
+///
+/// The plane is defined by a point on it, and its normal. The mirror image can
+/// be joined with the original shape, which is useful for modeling symmetric
+/// shapes one half at a time.
+///
+/// # Examples
+///
+/// Convenient syntax for this operation is available through [`crate::syntax`].
+///
+/// ``` rust
+/// # let shape = fj::Sketch::from_points(vec![[0., 0.], [1., 0.], [0., 1.]])
+/// #     .sweep([0., 0., 1.]);
+/// use fj::syntax::*;
+///
+/// // `shape` can be anything that converts to `fj::Shape`
+/// let mirrored = shape.mirror([0., 0., 0.], [1., 0., 0.]);
+/// let symmetric = shape.mirror([0., 0., 0.], [1., 0., 0.]).with_original();
+/// ```
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[repr(C)]
+pub struct Mirror {
+    shape: Shape,
+    origin: [f64; 3],
+    normal: [f64; 3],
+    with_original: bool,
+}
+
+impl Mirror {
+    /// Mirror a shape across the plane with the given origin and normal
+    ///
+    /// # Panics
+    ///
+    /// Panics, if `normal` has zero length.
+    pub fn across_plane(
+        shape: Shape,
+        origin: [f64; 3],
+        normal: [f64; 3],
+    ) -> Self {
+        assert!(
+            normal.iter().any(|&component| component != 0.),
+            "Normal of mirror plane must not have zero length"
+        );
+
+        Self {
+            shape,
+            origin,
+            normal,
+            with_original: false,
+        }
+    }
+
+    /// Join the mirror image with the original shape
+    ///
+    /// The shape must be located on one side of the plane. Where it has faces
+    /// in the plane, it is joined with its mirror image into a single solid.
+    pub fn with_original(mut self) -> Self {
+        self.with_original = true;
+        self
+    }
+
+    /// Access the shape being mirrored
+    pub fn shape(&self) -> &Shape {
+        &self.shape
+    }
+
+    /// Access the point on the mirror plane
+    pub fn origin(&self) -> [f64; 3] {
+        self.origin
+    }
+
+    /// Access the normal of the mirror plane
+    pub fn normal(&self) -> [f64; 3] {
+        self.normal
+    }
+
+    /// Indicate whether the mirror image is joined with the original shape
+    pub fn is_with_original(&self) -> bool {
+        self.with_original
+    }
+}
+
+impl From<Mirror> for Shape {
+    fn from(shape: Mirror) -> Self {
+        Self::Mirror(Box::new(shape))
+    }
+}
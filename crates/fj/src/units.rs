@@ -0,0 +1,41 @@
+/// The units that the lengths of a shape are given in
+///
+/// Fornjot doesn't convert lengths while it computes a shape. The units are
+/// carried along with the shape, so exporters can record them in the file or
+/// convert the shape into the units that were asked for. Shapes that don't
+/// specify units are assumed to be in millimeters.
+///
+/// # Examples
+///
+/// ``` rust
+/// # let plate = fj::Sketch::from_points(vec![[0., 0.], [4., 0.], [0., 2.]]);
+/// let part = fj::Part::new("bracket", plate).with_units(fj::Units::Inches);
+///
+/// assert_eq!(part.units(), fj::Units::Inches);
+/// assert_eq!(fj::Units::Inches.millimeters(), 25.4);
+/// ```
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[repr(C)]
+pub enum Units {
+    /// Millimeters
+    #[default]
+    Millimeters,
+
+    /// Inches
+    Inches,
+
+    /// Meters
+    Meters,
+}
+
+impl Units {
+    /// The length of one unit, in millimeters
+    pub fn millimeters(self) -> f64 {
+        match self {
+            Self::Millimeters => 1.,
+            Self::Inches => 25.4,
+            Self::Meters => 1000.,
+        }
+    }
+}
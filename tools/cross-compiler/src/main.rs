@@ -13,6 +13,7 @@ fn main() -> anyhow::Result<()> {
     let crates = [
         "fj",
         "fj-export",
+        "fj-import",
         "fj-interop",
         "fj-kernel",
         "fj-math",